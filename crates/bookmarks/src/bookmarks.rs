@@ -0,0 +1,556 @@
+use std::{path::Path, sync::Arc};
+
+use editor::{open_bookmark, BookmarkStore, Editor, EditorMode};
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model, Render,
+    Subscription, View, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use text::Point;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(bookmarks, [Toggle, LabelBookmark]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(BookmarkList::register).detach();
+    cx.observe_new_views(BookmarkLabelPrompt::register).detach();
+}
+
+/// A picker listing the bookmarks of the workspace.
+pub struct BookmarkList {
+    picker: View<Picker<BookmarkListDelegate>>,
+}
+
+impl BookmarkList {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &Toggle, cx| {
+            let Some(store) = BookmarkStore::for_workspace(workspace.database_id(), cx) else {
+                return;
+            };
+            let entries = bookmark_entries(workspace, &store, cx);
+            let workspace_handle = cx.view().downgrade();
+            workspace.toggle_modal(cx, move |cx| {
+                BookmarkList::new(workspace_handle, entries, cx)
+            });
+        });
+    }
+
+    fn new(
+        workspace: WeakView<Workspace>,
+        entries: Vec<(BookmarkEntry, String)>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate = BookmarkListDelegate::new(cx.view().downgrade(), workspace, entries);
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for BookmarkList {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for BookmarkList {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarkList {}
+impl ModalView for BookmarkList {}
+
+struct BookmarkEntry {
+    abs_path: Arc<Path>,
+    row: u32,
+    /// The text of the bookmarked line, if its buffer is open.
+    line: Option<SharedString>,
+}
+
+fn bookmark_entries(
+    workspace: &Workspace,
+    store: &Model<BookmarkStore>,
+    cx: &AppContext,
+) -> Vec<(BookmarkEntry, String)> {
+    let project = workspace.project().read(cx);
+    store
+        .read(cx)
+        .sorted_bookmarks(cx)
+        .into_iter()
+        .map(|bookmark| {
+            let row = bookmark.row(cx);
+            let path = project
+                .visible_worktrees(cx)
+                .find_map(|worktree| {
+                    let relative_path = bookmark
+                        .abs_path
+                        .strip_prefix(worktree.read(cx).abs_path())
+                        .ok()?;
+                    Some(relative_path.to_string_lossy().to_string())
+                })
+                .unwrap_or_else(|| bookmark.abs_path.to_string_lossy().to_string());
+            let line = bookmark.buffer_anchor(cx).map(|(buffer, _)| {
+                let buffer = buffer.read(cx);
+                let line_end = Point::new(row, buffer.line_len(row));
+                SharedString::from(
+                    buffer
+                        .text_for_range(Point::new(row, 0)..line_end)
+                        .collect::<String>()
+                        .trim()
+                        .to_string(),
+                )
+            });
+
+            let mut text = format!("{path}:{}", row + 1);
+            if let Some(label) = bookmark.label {
+                text = format!("{label} {text}");
+            }
+            (
+                BookmarkEntry {
+                    abs_path: bookmark.abs_path,
+                    row,
+                    line,
+                },
+                text,
+            )
+        })
+        .collect()
+}
+
+pub struct BookmarkListDelegate {
+    bookmark_list: WeakView<BookmarkList>,
+    workspace: WeakView<Workspace>,
+    entries: Vec<BookmarkEntry>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl BookmarkListDelegate {
+    fn new(
+        bookmark_list: WeakView<BookmarkList>,
+        workspace: WeakView<Workspace>,
+        entries: Vec<(BookmarkEntry, String)>,
+    ) -> Self {
+        let (entries, candidates) = entries
+            .into_iter()
+            .enumerate()
+            .map(|(candidate_id, (entry, text))| {
+                (entry, StringMatchCandidate::new(candidate_id, text))
+            })
+            .unzip();
+
+        Self {
+            bookmark_list,
+            workspace,
+            entries,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for BookmarkListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search bookmarks...".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        if self.entries.is_empty() {
+            "No bookmarks".into()
+        } else {
+            "No matches".into()
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let entry = &self.entries[mat.candidate_id];
+            let (abs_path, row) = (entry.abs_path.clone(), entry.row);
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    open_bookmark(workspace, abs_path, row, cx).detach_and_log_err(cx);
+                })
+                .log_err();
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.bookmark_list
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let entry = &self.entries[mat.candidate_id];
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(HighlightedLabel::new(
+                            mat.string.clone(),
+                            mat.positions.clone(),
+                        ))
+                        .children(entry.line.clone().map(|line| {
+                            Label::new(line)
+                                .color(Color::Muted)
+                                .single_line()
+                                .size(LabelSize::Small)
+                        })),
+                ),
+        )
+    }
+}
+
+/// A prompt for the label of the bookmark on the line of the cursor.
+pub struct BookmarkLabelPrompt {
+    label_editor: View<Editor>,
+    active_editor: View<Editor>,
+    _subscription: Subscription,
+}
+
+impl ModalView for BookmarkLabelPrompt {}
+
+impl FocusableView for BookmarkLabelPrompt {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.label_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarkLabelPrompt {}
+
+impl BookmarkLabelPrompt {
+    fn register(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+        if editor.mode() != EditorMode::Full {
+            return;
+        }
+
+        let handle = cx.view().downgrade();
+        editor.register_action(move |_: &LabelBookmark, cx| {
+            let Some(editor) = handle.upgrade() else {
+                return;
+            };
+            let Some(workspace) = editor.read(cx).workspace() else {
+                return;
+            };
+            workspace.update(cx, |workspace, cx| {
+                workspace.toggle_modal(cx, move |cx| BookmarkLabelPrompt::new(editor, cx));
+            })
+        });
+    }
+
+    fn new(active_editor: View<Editor>, cx: &mut ViewContext<Self>) -> Self {
+        let label = active_editor.read(cx).bookmark_label_at_cursor(cx);
+        let label_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Bookmark label", cx);
+            if let Some(label) = label {
+                editor.set_text(label.to_string(), cx);
+                editor.select_all(&Default::default(), cx);
+            }
+            editor
+        });
+        let subscription = cx.subscribe(&label_editor, |_, _, event, cx| {
+            if let editor::EditorEvent::Blurred = event {
+                cx.emit(DismissEvent);
+            }
+        });
+
+        Self {
+            label_editor,
+            active_editor,
+            _subscription: subscription,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let label = self.label_editor.read(cx).text(cx);
+        let label = label.trim();
+        let label = (!label.is_empty()).then(|| SharedString::from(label.to_string()));
+        self.active_editor.update(cx, |editor, cx| {
+            editor.set_bookmark_label(label, cx);
+            editor.focus(cx);
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for BookmarkLabelPrompt {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div()
+            .elevation_2(cx)
+            .key_context("BookmarkLabelPrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .w_96()
+            .child(
+                v_flex()
+                    .px_1()
+                    .pt_0p5()
+                    .gap_px()
+                    .child(
+                        v_flex()
+                            .py_0p5()
+                            .px_1()
+                            .child(div().px_1().py_0p5().child(self.label_editor.clone())),
+                    )
+                    .child(
+                        div()
+                            .h_px()
+                            .w_full()
+                            .bg(cx.theme().colors().element_background),
+                    )
+                    .child(
+                        h_flex().justify_between().px_2().py_1().child(
+                            Label::new("Label the bookmark on this line").color(Color::Muted),
+                        ),
+                    ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::actions::{GoToBookmark, GoToPrevBookmark, ToggleBookmark};
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_bookmarks(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "a.txt": "one\ntwo\nthree\nfour\nfive\n",
+                "b.txt": "six\nseven\n",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let editor_a = open_editor(&workspace, "a.txt", cx).await;
+
+        set_cursor_row(&editor_a, 3, cx);
+        cx.dispatch_action(ToggleBookmark);
+        set_cursor_row(&editor_a, 1, cx);
+        cx.dispatch_action(ToggleBookmark);
+
+        // Bookmarks follow edits made above them.
+        editor_a.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |s| {
+                s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+            });
+            editor.handle_input("zero\n", cx);
+        });
+        assert_eq!(bookmarked_rows(&workspace, "a.txt", cx), vec![2, 4]);
+
+        cx.dispatch_action(GoToBookmark);
+        assert_eq!(cursor_row(&editor_a, cx), 2);
+        cx.dispatch_action(GoToBookmark);
+        assert_eq!(cursor_row(&editor_a, cx), 4);
+        cx.dispatch_action(GoToBookmark);
+        assert_eq!(cursor_row(&editor_a, cx), 2);
+        cx.dispatch_action(GoToPrevBookmark);
+        assert_eq!(cursor_row(&editor_a, cx), 4);
+
+        cx.dispatch_action(LabelBookmark);
+        cx.simulate_input("fourth");
+        cx.dispatch_action(menu::Confirm);
+
+        let editor_b = open_editor(&workspace, "b.txt", cx).await;
+        set_cursor_row(&editor_b, 1, cx);
+        cx.dispatch_action(ToggleBookmark);
+        assert_eq!(bookmarked_rows(&workspace, "b.txt", cx), vec![1]);
+
+        cx.dispatch_action(Toggle);
+        cx.run_until_parked();
+        let picker = workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<BookmarkList>(cx)
+                .unwrap()
+                .read(cx)
+                .picker
+                .clone()
+        });
+        assert_eq!(
+            match_strings_for(&picker, cx),
+            vec!["a.txt:3", "fourth a.txt:5", "b.txt:2"]
+        );
+
+        cx.simulate_input("fourth");
+        cx.run_until_parked();
+        assert_eq!(match_strings_for(&picker, cx), vec!["fourth a.txt:5"]);
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+
+        let active_editor = workspace.update(cx, |workspace, cx| {
+            workspace.active_item_as::<Editor>(cx).unwrap()
+        });
+        assert_eq!(active_editor, editor_a);
+        assert_eq!(cursor_row(&editor_a, cx), 4);
+
+        // Toggling a bookmarked line removes its bookmark.
+        cx.dispatch_action(ToggleBookmark);
+        assert_eq!(bookmarked_rows(&workspace, "a.txt", cx), vec![2]);
+    }
+
+    async fn open_editor(
+        workspace: &View<Workspace>,
+        path: &str,
+        cx: &mut VisualTestContext,
+    ) -> View<Editor> {
+        let worktree_id = workspace.update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees().next().unwrap().read(cx).id()
+            })
+        });
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, path), None, true, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap()
+    }
+
+    fn set_cursor_row(editor: &View<Editor>, row: u32, cx: &mut VisualTestContext) {
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |s| {
+                s.select_ranges([Point::new(row, 0)..Point::new(row, 0)])
+            });
+        });
+    }
+
+    fn cursor_row(editor: &View<Editor>, cx: &mut VisualTestContext) -> u32 {
+        editor.update(cx, |editor, cx| {
+            editor.selections.newest::<Point>(cx).head().row
+        })
+    }
+
+    fn bookmarked_rows(
+        workspace: &View<Workspace>,
+        file_name: &str,
+        cx: &mut VisualTestContext,
+    ) -> Vec<u32> {
+        workspace.update(cx, |workspace, cx| {
+            let store = BookmarkStore::for_workspace(workspace.database_id(), cx).unwrap();
+            let rows = store
+                .read(cx)
+                .sorted_bookmarks(cx)
+                .into_iter()
+                .filter(|bookmark| bookmark.abs_path.ends_with(file_name))
+                .map(|bookmark| bookmark.row(cx))
+                .collect();
+            rows
+        })
+    }
+
+    fn match_strings_for(
+        picker: &View<Picker<BookmarkListDelegate>>,
+        cx: &mut VisualTestContext,
+    ) -> Vec<String> {
+        picker.update(cx, |picker, _| {
+            picker
+                .delegate
+                .matches
+                .iter()
+                .map(|mat| mat.string.clone())
+                .collect()
+        })
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            language::init(cx);
+            crate::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            state
+        })
+    }
+}
@@ -55,6 +55,9 @@ pub enum Event {
     RoomLeft {
         channel_id: Option<ChannelId>,
     },
+    PresenterChanged {
+        presenter_id: Option<proto::PeerId>,
+    },
 }
 
 pub struct Room {
@@ -73,6 +76,7 @@ pub struct Room {
     client: Arc<Client>,
     user_store: Model<UserStore>,
     follows_by_leader_id_project_id: HashMap<(PeerId, u64), Vec<PeerId>>,
+    presenter_id: Option<PeerId>,
     client_subscriptions: Vec<client::Subscription>,
     _subscriptions: Vec<gpui::Subscription>,
     room_update_completed_tx: watch::Sender<Option<()>>,
@@ -217,6 +221,7 @@ impl Room {
             client,
             user_store,
             follows_by_leader_id_project_id: Default::default(),
+            presenter_id: None,
             maintain_connection: Some(maintain_connection),
             room_update_completed_tx,
             room_update_completed_rx,
@@ -643,6 +648,33 @@ impl Room {
         })
     }
 
+    /// The participant everyone in the room is asked to follow, if any.
+    pub fn presenter_id(&self) -> Option<PeerId> {
+        self.presenter_id
+    }
+
+    pub fn is_presenting(&self) -> bool {
+        self.presenter_id.is_some() && self.presenter_id == self.client.peer_id()
+    }
+
+    pub fn set_presenting(
+        &mut self,
+        presenting: bool,
+        cx: &ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let client = self.client.clone();
+        let room_id = self.id;
+        cx.spawn(|_, _| async move {
+            client
+                .request(proto::SetRoomPresenter {
+                    room_id,
+                    presenting,
+                })
+                .await
+                .map(|_| ())
+        })
+    }
+
     pub fn pending_participants(&self) -> &[Arc<User>] {
         &self.pending_participants
     }
@@ -919,6 +951,13 @@ impl Room {
                     }
                 }
 
+                if this.presenter_id != room.presenter_id {
+                    this.presenter_id = room.presenter_id;
+                    cx.emit(Event::PresenterChanged {
+                        presenter_id: this.presenter_id,
+                    });
+                }
+
                 this.follows_by_leader_id_project_id.clear();
                 for follower in room.followers {
                     let project_id = follower.project_id;
@@ -2,13 +2,16 @@ use crate::{Channel, ChannelStore};
 use anyhow::Result;
 use client::{ChannelId, Client, Collaborator, UserStore, ZED_ALWAYS_ACTIVE};
 use collections::HashMap;
+use db::channel_notes::ChannelNotesDb;
+use futures::{future::Shared, Future};
 use gpui::{AppContext, AsyncAppContext, Context, EventEmitter, Model, ModelContext, Task};
-use language::proto::serialize_version;
+use language::{proto::serialize_version, Capability};
+use prost::Message as _;
 use rpc::{
     proto::{self, PeerId},
     TypedEnvelope,
 };
-use std::{sync::Arc, time::Duration};
+use std::{mem, sync::Arc, time::Duration};
 use text::BufferId;
 use util::ResultExt;
 
@@ -30,28 +33,91 @@ pub struct ChannelBuffer {
     client: Arc<Client>,
     subscription: Option<client::Subscription>,
     acknowledge_task: Option<Task<Result<()>>>,
+    notes_db: ChannelNotesDb,
+    /// The version of the buffer that the server is known to have seen.
+    synced_version: clock::Global,
+    /// Whether edits that the server hasn't seen are saved locally.
+    has_offline_edits: bool,
+    /// Writes to the local database, each waiting on the one before it.
+    offline_edits_task: Option<Task<()>>,
 }
 
 pub enum ChannelBufferEvent {
     CollaboratorsChanged,
     Disconnected,
+    Reconnected,
+    /// The buffer couldn't be rejoined after reconnecting, and was replaced by
+    /// a new copy that includes any edits made while disconnected.
+    Reopened(Model<ChannelBuffer>),
     BufferEdited,
     ChannelChanged,
 }
 
 impl EventEmitter<ChannelBufferEvent> for ChannelBuffer {}
 
+/// Edits to a channel buffer that were saved locally while disconnected.
+struct OfflineEdits {
+    epoch: u64,
+    replica_id: u16,
+    base_text: String,
+    text: String,
+    operations: Vec<proto::Operation>,
+}
+
+impl OfflineEdits {
+    fn load(
+        notes_db: &ChannelNotesDb,
+        user_id: u64,
+        channel_id: ChannelId,
+    ) -> Result<Option<Self>> {
+        let Some((epoch, replica_id, base_text, text)) =
+            notes_db.offline_buffer(user_id, channel_id.0)?
+        else {
+            return Ok(None);
+        };
+        let operations = notes_db
+            .offline_operations(user_id, channel_id.0)?
+            .into_iter()
+            .map(|operation| proto::Operation::decode(operation.as_slice()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Some(Self {
+            epoch,
+            replica_id: replica_id as u16,
+            base_text,
+            text,
+            operations,
+        }))
+    }
+}
+
 impl ChannelBuffer {
     pub(crate) async fn new(
         channel: Arc<Channel>,
         client: Arc<Client>,
         user_store: Model<UserStore>,
         channel_store: Model<ChannelStore>,
+        notes_db: Shared<Task<ChannelNotesDb>>,
         mut cx: AsyncAppContext,
     ) -> Result<Model<Self>> {
+        let notes_db = notes_db.await;
+        let offline_edits = if let Some(user_id) = client.user_id() {
+            let notes_db = notes_db.clone();
+            let channel_id = channel.id;
+            cx.background_executor()
+                .spawn(async move { OfflineEdits::load(&notes_db, user_id, channel_id) })
+                .await
+                .log_err()
+                .flatten()
+        } else {
+            None
+        };
+
         let response = client
             .request(proto::JoinChannelBuffer {
                 channel_id: channel.id.0,
+                replica_id: offline_edits
+                    .as_ref()
+                    .map(|offline_edits| offline_edits.replica_id as u32),
             })
             .await?;
         let buffer_id = BufferId::new(response.buffer_id)?;
@@ -70,10 +136,11 @@ impl ChannelBuffer {
 
         let subscription = client.subscribe_to_entity(channel.id.0)?;
 
-        anyhow::Ok(cx.new_model(|cx| {
+        let this = cx.new_model(|cx| {
             cx.subscribe(&buffer, Self::on_buffer_update).detach();
             cx.on_release(Self::release).detach();
             let mut this = Self {
+                synced_version: buffer.read(cx).version(),
                 buffer,
                 buffer_epoch: response.epoch,
                 client,
@@ -84,13 +151,105 @@ impl ChannelBuffer {
                 subscription: Some(subscription.set_model(&cx.handle(), &mut cx.to_async())),
                 user_store,
                 channel_store,
+                notes_db,
+                has_offline_edits: offline_edits.is_some(),
+                offline_edits_task: None,
             };
             this.replace_collaborators(response.collaborators, cx);
             this
-        })?)
+        })?;
+
+        if let Some(offline_edits) = offline_edits {
+            Self::merge_offline_edits(&this, offline_edits, &mut cx)
+                .await
+                .log_err();
+            this.update(&mut cx, |this, cx| this.clear_offline_edits(cx))?;
+        }
+
+        anyhow::Ok(this)
+    }
+
+    /// Applies edits that were saved while disconnected. If the server hasn't
+    /// seen any of their operations, those are replayed. Otherwise the edits
+    /// are rebased onto the buffer's current text, and any that overlap edits
+    /// made in the meantime are discarded.
+    async fn merge_offline_edits(
+        this: &Model<Self>,
+        offline_edits: OfflineEdits,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        let (buffer, can_replay) = this.read_with(cx, |this, cx| {
+            let buffer = this.buffer.read(cx);
+            let version = buffer.version();
+            let can_replay = offline_edits.epoch == this.buffer_epoch
+                && offline_edits.replica_id == buffer.replica_id()
+                && offline_edits.operations.iter().all(|operation| {
+                    language::proto::lamport_timestamp_for_operation(operation)
+                        .map_or(false, |timestamp| !version.observed(timestamp))
+                });
+            (this.buffer.clone(), can_replay)
+        })?;
+
+        if buffer.read_with(cx, |buffer, _| buffer.capability())? == Capability::ReadOnly {
+            log::warn!(
+                "discarding offline edits to read-only channel buffer {}",
+                this.read_with(cx, |this, _| this.channel_id)?
+            );
+            return Ok(());
+        }
+
+        if can_replay {
+            let operations = offline_edits
+                .operations
+                .iter()
+                .cloned()
+                .map(language::proto::deserialize_operation)
+                .collect::<Result<Vec<_>>>()?;
+            buffer.update(cx, |buffer, cx| buffer.apply_ops(operations, cx))??;
+            this.update(cx, |this, _| {
+                for chunk in language::proto::split_operations(offline_edits.operations) {
+                    this.client
+                        .send(proto::UpdateChannelBuffer {
+                            channel_id: this.channel_id.0,
+                            operations: chunk,
+                        })
+                        .log_err();
+                }
+            })?;
+        } else {
+            let scratch_buffer =
+                cx.new_model(|cx| language::Buffer::local(offline_edits.base_text, cx))?;
+            let offline_diff = scratch_buffer
+                .update(cx, |scratch_buffer, cx| {
+                    scratch_buffer.diff(offline_edits.text, cx)
+                })?
+                .await;
+            let current_text = buffer.read_with(cx, |buffer, _| buffer.text())?;
+            let current_diff = scratch_buffer
+                .update(cx, |scratch_buffer, cx| {
+                    scratch_buffer.diff(current_text, cx)
+                })?
+                .await;
+            let merged_text = scratch_buffer.update(cx, |scratch_buffer, cx| {
+                scratch_buffer.apply_diff(current_diff, cx);
+                scratch_buffer.apply_diff(offline_diff, cx);
+                scratch_buffer.text()
+            })?;
+
+            // The merge is applied as a local edit, so it is sent to the server.
+            let diff = buffer
+                .update(cx, |buffer, cx| buffer.diff(merged_text, cx))?
+                .await;
+            buffer.update(cx, |buffer, cx| buffer.apply_diff(diff, cx))?;
+        }
+
+        Ok(())
     }
 
     fn release(&mut self, _: &mut AppContext) {
+        if let Some(task) = self.offline_edits_task.take() {
+            task.detach();
+        }
         if self.connected {
             if let Some(task) = self.acknowledge_task.take() {
                 task.detach();
@@ -151,7 +310,11 @@ impl ChannelBuffer {
         this.update(&mut cx, |this, cx| {
             cx.notify();
             this.buffer
-                .update(cx, |buffer, cx| buffer.apply_ops(ops, cx))
+                .update(cx, |buffer, cx| buffer.apply_ops(ops, cx))?;
+            if !this.has_offline_edits {
+                this.synced_version = this.buffer.read(cx).version();
+            }
+            anyhow::Ok(())
         })??;
 
         Ok(())
@@ -188,13 +351,23 @@ impl ChannelBuffer {
                         _ => {}
                     }
                 }
+                let is_edit = matches!(operation, language::Operation::Buffer(_));
                 let operation = language::proto::serialize_operation(operation);
-                self.client
-                    .send(proto::UpdateChannelBuffer {
-                        channel_id: self.channel_id.0,
-                        operations: vec![operation],
-                    })
-                    .log_err();
+                let sent = self.connected
+                    && self
+                        .client
+                        .send(proto::UpdateChannelBuffer {
+                            channel_id: self.channel_id.0,
+                            operations: vec![operation.clone()],
+                        })
+                        .is_ok();
+                if sent {
+                    if !self.has_offline_edits {
+                        self.synced_version = self.buffer.read(cx).version();
+                    }
+                } else if is_edit {
+                    self.save_offline_edit(operation, cx);
+                }
             }
             language::Event::Edited => {
                 cx.emit(ChannelBufferEvent::BufferEdited);
@@ -203,6 +376,72 @@ impl ChannelBuffer {
         }
     }
 
+    /// Saves an edit that couldn't be sent, along with the buffer's text, so
+    /// that it can be merged once the buffer is rejoined or reopened.
+    fn save_offline_edit(&mut self, operation: proto::Operation, cx: &mut ModelContext<Self>) {
+        let Some(user_id) = self.client.user_id() else {
+            return;
+        };
+        let buffer = self.buffer.read(cx);
+        let base_text = if self.has_offline_edits {
+            String::new()
+        } else {
+            buffer.rope_for_version(&self.synced_version).to_string()
+        };
+        let text = buffer.text();
+        let epoch = self.buffer_epoch;
+        let replica_id = buffer.replica_id() as u32;
+        let channel_id = self.channel_id.0;
+        self.has_offline_edits = true;
+        self.write_offline_edits(cx, move |notes_db| async move {
+            notes_db
+                .save_offline_buffer(user_id, channel_id, epoch, replica_id, base_text, text)
+                .await?;
+            notes_db
+                .save_offline_operation(user_id, channel_id, operation.encode_to_vec())
+                .await
+        });
+    }
+
+    /// Marks the buffer as in sync with the server, deleting any edits that
+    /// were saved while it was disconnected.
+    fn clear_offline_edits(&mut self, cx: &mut ModelContext<Self>) {
+        self.synced_version = self.buffer.read(cx).version();
+        if !mem::take(&mut self.has_offline_edits) {
+            return;
+        }
+        let Some(user_id) = self.client.user_id() else {
+            return;
+        };
+        let channel_id = self.channel_id.0;
+        self.write_offline_edits(cx, move |notes_db| async move {
+            notes_db.delete_offline_buffer(user_id, channel_id).await
+        });
+    }
+
+    fn write_offline_edits<F, Fut>(&mut self, cx: &mut ModelContext<Self>, write: F)
+    where
+        F: 'static + Send + FnOnce(ChannelNotesDb) -> Fut,
+        Fut: 'static + Send + Future<Output = Result<()>>,
+    {
+        let previous_task = self.offline_edits_task.take();
+        let notes_db = self.notes_db.clone();
+        self.offline_edits_task = Some(cx.background_executor().spawn(async move {
+            if let Some(previous_task) = previous_task {
+                previous_task.await;
+            }
+            write(notes_db).await.log_err();
+        }));
+    }
+
+    /// Returns a task that completes once edits saved while disconnected have
+    /// been written to the local database.
+    pub(crate) fn flush_offline_edits(&mut self) -> Task<()> {
+        self.offline_edits_task
+            .take()
+            .unwrap_or_else(|| Task::ready(()))
+    }
+
     pub fn acknowledge_buffer_version(&mut self, cx: &mut ModelContext<'_, ChannelBuffer>) {
         let buffer = self.buffer.read(cx);
         let version = buffer.version();
@@ -225,6 +464,24 @@ impl ChannelBuffer {
         }));
     }
 
+    /// Replaces the buffer's text with that of an earlier version. The change
+    /// is made as an ordinary edit, so it is shared with collaborators and
+    /// becomes part of the current version rather than rewriting history.
+    pub fn restore_version(&mut self, text: String, cx: &mut ModelContext<Self>) -> Task<()> {
+        let buffer = self.buffer.clone();
+        let diff = buffer.update(cx, |buffer, cx| buffer.diff(text, cx));
+        cx.spawn(|_, mut cx| async move {
+            let diff = diff.await;
+            buffer
+                .update(&mut cx, |buffer, cx| {
+                    if !buffer.read_only() {
+                        buffer.apply_diff(diff, cx);
+                    }
+                })
+                .ok();
+        })
+    }
+
     pub fn epoch(&self) -> u64 {
         self.buffer_epoch
     }
@@ -254,6 +511,24 @@ impl ChannelBuffer {
         }
     }
 
+    pub(crate) fn resubscribe(&mut self, cx: &mut ModelContext<Self>) -> Result<()> {
+        if self.subscription.is_none() {
+            let subscription = self.client.subscribe_to_entity(self.channel_id.0)?;
+            self.subscription = Some(subscription.set_model(&cx.handle(), &mut cx.to_async()));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn reconnect(&mut self, cx: &mut ModelContext<Self>) {
+        self.clear_offline_edits(cx);
+        if !self.connected {
+            log::info!("channel buffer {} reconnected", self.channel_id);
+            self.connected = true;
+            cx.emit(ChannelBufferEvent::Reconnected);
+            cx.notify();
+        }
+    }
+
     pub(crate) fn channel_changed(&mut self, cx: &mut ModelContext<Self>) {
         cx.emit(ChannelBufferEvent::ChannelChanged);
         cx.notify()
@@ -52,6 +52,14 @@ pub struct ChannelMessage {
     pub mentions: Vec<(Range<usize>, UserId)>,
     pub reply_to_message_id: Option<u64>,
     pub edited_at: Option<OffsetDateTime>,
+    pub reactions: Vec<ChannelMessageReaction>,
+}
+
+/// The users who reacted to a message with a given emoji.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelMessageReaction {
+    pub emoji: String,
+    pub user_ids: Vec<UserId>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -99,6 +107,7 @@ pub fn init(client: &Arc<Client>) {
     client.add_model_message_handler(ChannelChat::handle_message_sent);
     client.add_model_message_handler(ChannelChat::handle_message_removed);
     client.add_model_message_handler(ChannelChat::handle_message_updated);
+    client.add_model_message_handler(ChannelChat::handle_message_reactions_updated);
 }
 
 impl ChannelChat {
@@ -196,6 +205,7 @@ impl ChannelChat {
                     nonce,
                     reply_to_message_id: message.reply_to_message_id,
                     edited_at: None,
+                    reactions: Vec::new(),
                 },
                 &(),
             ),
@@ -273,6 +283,65 @@ impl ChannelChat {
         }))
     }
 
+    /// Adds the current user's reaction to a message, or removes it if they had
+    /// already reacted with the same emoji.
+    pub fn toggle_reaction(
+        &mut self,
+        message_id: u64,
+        emoji: &str,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let request = self.rpc.request(proto::ToggleChannelMessageReaction {
+            channel_id: self.channel_id.0,
+            message_id,
+            emoji: emoji.to_string(),
+        });
+        cx.spawn(move |_, _| async move {
+            request.await?;
+            Ok(())
+        })
+    }
+
+    /// Loads the reply chain that the given message belongs to, in the order
+    /// the messages were sent.
+    pub fn load_thread(
+        &mut self,
+        message_id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<ChannelMessage>>> {
+        let user_store = self.user_store.clone();
+        let request = self.rpc.request(proto::GetChannelMessageThread {
+            channel_id: self.channel_id.0,
+            message_id,
+        });
+        cx.spawn(move |_, mut cx| async move {
+            let response = request.await?;
+            ChannelMessage::from_proto_vec(response.messages, &user_store, &mut cx).await
+        })
+    }
+
+    /// Searches the channel's messages, returning the most recent matches first,
+    /// and whether there are no older matches.
+    pub fn search_messages(
+        &mut self,
+        query: String,
+        before_message_id: Option<u64>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<(Vec<ChannelMessage>, bool)>> {
+        let user_store = self.user_store.clone();
+        let request = self.rpc.request(proto::SearchChannelMessages {
+            channel_id: self.channel_id.0,
+            query,
+            before_message_id,
+        });
+        cx.spawn(move |_, mut cx| async move {
+            let response = request.await?;
+            let messages =
+                ChannelMessage::from_proto_vec(response.messages, &user_store, &mut cx).await?;
+            Ok((messages, response.done))
+        })
+    }
+
     pub fn load_more_messages(&mut self, cx: &mut ModelContext<Self>) -> Option<Task<Option<()>>> {
         if self.loaded_all_messages {
             return None;
@@ -588,6 +657,23 @@ impl ChannelChat {
         Ok(())
     }
 
+    async fn handle_message_reactions_updated(
+        this: Model<Self>,
+        message: TypedEnvelope<proto::ChannelMessageReactionsUpdated>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let message = message.payload;
+        this.update(&mut cx, |this, cx| {
+            this.message_reactions_updated(
+                ChannelMessageId::Saved(message.message_id),
+                reactions_from_proto(message.reactions),
+                cx,
+            )
+        })?;
+        Ok(())
+    }
+
     fn insert_messages(&mut self, messages: SumTree<ChannelMessage>, cx: &mut ModelContext<Self>) {
         if let Some((first_message, last_message)) = messages.first().zip(messages.last()) {
             let nonces = messages
@@ -710,6 +796,39 @@ impl ChannelChat {
 
         cx.notify();
     }
+
+    fn message_reactions_updated(
+        &mut self,
+        id: ChannelMessageId,
+        reactions: Vec<ChannelMessageReaction>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let mut cursor = self.messages.cursor::<ChannelMessageId>();
+        let mut messages = cursor.slice(&id, Bias::Left, &());
+        let ix = messages.summary().count;
+
+        let mut updated = false;
+        if let Some(mut message_to_update) = cursor.item().cloned() {
+            if message_to_update.id == id {
+                message_to_update.reactions = reactions;
+                messages.push(message_to_update, &());
+                cursor.next(&());
+                updated = true;
+            }
+        }
+
+        messages.append(cursor.suffix(&()), &());
+        drop(cursor);
+        self.messages = messages;
+
+        if updated {
+            cx.emit(ChannelChatEvent::UpdateMessage {
+                message_ix: ix,
+                message_id: id,
+            });
+            cx.notify();
+        }
+    }
 }
 
 async fn messages_from_proto(
@@ -762,9 +881,17 @@ impl ChannelMessage {
                 .into(),
             reply_to_message_id: message.reply_to_message_id,
             edited_at,
+            reactions: reactions_from_proto(message.reactions),
         })
     }
 
+    /// Whether the given user reacted to the message with the given emoji.
+    pub fn has_reaction(&self, emoji: &str, user_id: UserId) -> bool {
+        self.reactions
+            .iter()
+            .any(|reaction| reaction.emoji == emoji && reaction.user_ids.contains(&user_id))
+    }
+
     pub fn is_pending(&self) -> bool {
         matches!(self.id, ChannelMessageId::Pending(_))
     }
@@ -794,6 +921,18 @@ impl ChannelMessage {
     }
 }
 
+fn reactions_from_proto(
+    reactions: Vec<proto::ChannelMessageReaction>,
+) -> Vec<ChannelMessageReaction> {
+    reactions
+        .into_iter()
+        .map(|reaction| ChannelMessageReaction {
+            emoji: reaction.emoji,
+            user_ids: reaction.user_ids,
+        })
+        .collect()
+}
+
 pub fn mentions_to_proto(mentions: &[(Range<usize>, UserId)]) -> Vec<proto::ChatMention> {
     mentions
         .iter()
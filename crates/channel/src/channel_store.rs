@@ -1,10 +1,15 @@
 mod channel_index;
 
-use crate::{channel_buffer::ChannelBuffer, channel_chat::ChannelChat, ChannelMessage};
+use crate::{
+    channel_buffer::{ChannelBuffer, ChannelBufferEvent},
+    channel_chat::ChannelChat,
+    ChannelMessage,
+};
 use anyhow::{anyhow, Result};
 use channel_index::ChannelIndex;
 use client::{ChannelId, Client, ClientSettings, ProjectId, Subscription, User, UserId, UserStore};
 use collections::{hash_map, HashMap, HashSet};
+use db::channel_notes::ChannelNotesDb;
 use futures::{channel::mpsc, future::Shared, Future, FutureExt, StreamExt};
 use gpui::{
     AppContext, AsyncAppContext, Context, EventEmitter, Global, Model, ModelContext, SharedString,
@@ -17,6 +22,7 @@ use rpc::{
 };
 use settings::Settings;
 use std::{mem, sync::Arc, time::Duration};
+use time::OffsetDateTime;
 use util::{maybe, ResultExt};
 
 pub const RECONNECT_TIMEOUT: Duration = Duration::from_secs(30);
@@ -33,6 +39,16 @@ struct NotesVersion {
     version: clock::Global,
 }
 
+/// A saved version of a channel's notes. A new version starts each time the
+/// notes are saved after the last collaborator leaves.
+#[derive(Debug, Clone)]
+pub struct ChannelNotesVersion {
+    pub epoch: u64,
+    /// When this version was saved, or `None` if it is the current version.
+    pub saved_at: Option<OffsetDateTime>,
+    pub authors: Vec<Arc<User>>,
+}
+
 #[derive(Debug, Clone)]
 pub struct HostedProject {
     project_id: ProjectId,
@@ -61,11 +77,16 @@ pub struct ChannelStore {
     update_channels_tx: mpsc::UnboundedSender<proto::UpdateChannels>,
     opened_buffers: HashMap<ChannelId, OpenedModelHandle<ChannelBuffer>>,
     opened_chats: HashMap<ChannelId, OpenedModelHandle<ChannelChat>>,
+    /// Channel buffers to reopen once their channels are known, so that edits
+    /// made while disconnected are merged, along with any buffers they replace.
+    channel_buffers_to_reopen: HashMap<ChannelId, Option<WeakModel<ChannelBuffer>>>,
     client: Arc<Client>,
     user_store: Model<UserStore>,
+    notes_db: Shared<Task<ChannelNotesDb>>,
     _rpc_subscriptions: [Subscription; 2],
     _watch_connection_status: Task<Option<()>>,
     disconnect_channel_buffers_task: Option<Task<()>>,
+    reopen_offline_channel_buffers_task: Option<Task<()>>,
     _update_channels: Task<()>,
 }
 
@@ -218,12 +239,18 @@ impl ChannelStore {
             outgoing_invites: Default::default(),
             opened_buffers: Default::default(),
             opened_chats: Default::default(),
+            channel_buffers_to_reopen: Default::default(),
             update_channels_tx,
             client,
             user_store,
+            notes_db: cx
+                .background_executor()
+                .spawn(ChannelNotesDb::open())
+                .shared(),
             _rpc_subscriptions: rpc_subscriptions,
             _watch_connection_status: watch_connection_status,
             disconnect_channel_buffers_task: None,
+            reopen_offline_channel_buffers_task: None,
             _update_channels: cx.spawn(|this, mut cx| async move {
                 maybe!(async move {
                     while let Some(update_channels) = update_channels_rx.next().await {
@@ -331,10 +358,13 @@ impl ChannelStore {
         let client = self.client.clone();
         let user_store = self.user_store.clone();
         let channel_store = cx.handle();
+        let notes_db = self.notes_db.clone();
         self.open_channel_resource(
             channel_id,
             |this| &mut this.opened_buffers,
-            |channel, cx| ChannelBuffer::new(channel, client, user_store, channel_store, cx),
+            |channel, cx| {
+                ChannelBuffer::new(channel, client, user_store, channel_store, notes_db, cx)
+            },
             cx,
         )
     }
@@ -849,6 +879,75 @@ impl ChannelStore {
         })
     }
 
+    /// Lists the saved versions of a channel's notes, newest first.
+    pub fn channel_notes_history(
+        &self,
+        channel_id: ChannelId,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<ChannelNotesVersion>>> {
+        let client = self.client.clone();
+        let user_store = self.user_store.downgrade();
+        cx.spawn(move |_, mut cx| async move {
+            let response = client
+                .request(proto::GetChannelBufferHistory {
+                    channel_id: channel_id.0,
+                })
+                .await?;
+
+            let user_ids = response
+                .versions
+                .iter()
+                .flat_map(|version| version.author_ids.iter().copied())
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+            let user_store = user_store
+                .upgrade()
+                .ok_or_else(|| anyhow!("user store dropped"))?;
+            let users = user_store
+                .update(&mut cx, |user_store, cx| user_store.get_users(user_ids, cx))?
+                .await?
+                .into_iter()
+                .map(|user| (user.id, user))
+                .collect::<HashMap<_, _>>();
+
+            Ok(response
+                .versions
+                .into_iter()
+                .map(|version| ChannelNotesVersion {
+                    epoch: version.epoch,
+                    saved_at: version.saved_at.and_then(|saved_at| {
+                        OffsetDateTime::from_unix_timestamp(saved_at as i64).ok()
+                    }),
+                    authors: version
+                        .author_ids
+                        .iter()
+                        .filter_map(|user_id| users.get(user_id).cloned())
+                        .collect(),
+                })
+                .collect())
+        })
+    }
+
+    /// Fetches the text of the channel's notes as of the given version.
+    pub fn channel_notes_version_text(
+        &self,
+        channel_id: ChannelId,
+        epoch: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<String>> {
+        let client = self.client.clone();
+        cx.background_executor().spawn(async move {
+            let response = client
+                .request(proto::GetChannelBufferVersion {
+                    channel_id: channel_id.0,
+                    epoch,
+                })
+                .await?;
+            Ok(response.text)
+        })
+    }
+
     pub fn remove_channel(&self, channel_id: ChannelId) -> impl Future<Output = Result<()>> {
         let client = self.client.clone();
         async move {
@@ -935,16 +1034,45 @@ impl ChannelStore {
             }
         }
 
+        self.channel_buffers_to_reopen.clear();
+        self.reopen_offline_channel_buffers_task = self.client.user_id().map(|user_id| {
+            let notes_db = self.notes_db.clone();
+            cx.spawn(|this, mut cx| async move {
+                let notes_db = notes_db.await;
+                let channel_ids = cx
+                    .background_executor()
+                    .spawn(async move { notes_db.offline_channel_ids(user_id) })
+                    .await
+                    .log_err()
+                    .unwrap_or_default();
+                this.update(&mut cx, |this, cx| {
+                    for channel_id in channel_ids.into_iter().map(ChannelId) {
+                        if !this.has_open_channel_buffer(channel_id, cx)
+                            && !this.channel_buffers_to_reopen.contains_key(&channel_id)
+                        {
+                            this.reopen_channel_buffer(channel_id, None, cx);
+                        }
+                    }
+                })
+                .ok();
+            })
+        });
+
         let mut buffer_versions = Vec::new();
         for buffer in self.opened_buffers.values() {
             if let OpenedModelHandle::Open(buffer) = buffer {
                 if let Some(buffer) = buffer.upgrade() {
-                    let channel_buffer = buffer.read(cx);
-                    let buffer = channel_buffer.buffer().read(cx);
-                    buffer_versions.push(proto::ChannelBufferVersion {
-                        channel_id: channel_buffer.channel_id.0,
-                        epoch: channel_buffer.epoch(),
-                        version: language::proto::serialize_version(&buffer.version()),
+                    buffer.update(cx, |channel_buffer, cx| {
+                        channel_buffer.resubscribe(cx).log_err();
+                        self.channel_buffers_to_reopen
+                            .remove(&channel_buffer.channel_id);
+                        let buffer = channel_buffer.buffer().read(cx);
+                        buffer_versions.push(proto::ChannelBufferVersion {
+                            channel_id: channel_buffer.channel_id.0,
+                            epoch: channel_buffer.epoch(),
+                            version: language::proto::serialize_version(&buffer.version()),
+                            replica_id: Some(buffer.replica_id() as u32),
+                        });
                     });
                 }
             }
@@ -962,6 +1090,7 @@ impl ChannelStore {
             let mut response = response.await?;
 
             this.update(&mut cx, |this, cx| {
+                let mut rejected_buffers = Vec::new();
                 this.opened_buffers.retain(|_, buffer| match buffer {
                     OpenedModelHandle::Open(channel_buffer) => {
                         let Some(channel_buffer) = channel_buffer.upgrade() else {
@@ -1016,22 +1145,73 @@ impl ChannelStore {
                                             }
                                         })
                                         .detach();
+                                    channel_buffer.reconnect(cx);
                                     return true;
                                 }
                             }
 
+                            // The buffer can't be rejoined, so reopen it, merging in
+                            // any edits that were made while disconnected.
                             channel_buffer.disconnect(cx);
+                            channel_buffer.buffer().update(cx, |buffer, cx| {
+                                buffer.set_capability(Capability::ReadOnly, cx)
+                            });
+                            rejected_buffers
+                                .push((cx.handle(), channel_buffer.flush_offline_edits()));
                             false
                         })
                     }
                     OpenedModelHandle::Loading(_) => true,
                 });
+
+                for (channel_buffer, flush_offline_edits) in rejected_buffers {
+                    cx.spawn(|this, mut cx| async move {
+                        flush_offline_edits.await;
+                        let channel_id =
+                            channel_buffer.read_with(&cx, |buffer, _| buffer.channel_id)?;
+                        this.update(&mut cx, |this, cx| {
+                            this.reopen_channel_buffer(
+                                channel_id,
+                                Some(channel_buffer.downgrade()),
+                                cx,
+                            )
+                        })
+                    })
+                    .detach_and_log_err(cx);
+                }
             })
             .ok();
             anyhow::Ok(())
         })
     }
 
+    /// Reopens a channel's notes, merging in edits that were made while
+    /// disconnected, or waits to do so until the channel is known.
+    fn reopen_channel_buffer(
+        &mut self,
+        channel_id: ChannelId,
+        replaced_buffer: Option<WeakModel<ChannelBuffer>>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if self.channel_for_id(channel_id).is_none() {
+            self.channel_buffers_to_reopen
+                .insert(channel_id, replaced_buffer);
+            return;
+        }
+
+        let channel_buffer = self.open_channel_buffer(channel_id, cx);
+        cx.spawn(|_, mut cx| async move {
+            let channel_buffer = channel_buffer.await?;
+            if let Some(replaced_buffer) = replaced_buffer.and_then(|buffer| buffer.upgrade()) {
+                replaced_buffer.update(&mut cx, |_, cx| {
+                    cx.emit(ChannelBufferEvent::Reopened(channel_buffer))
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn handle_disconnect(&mut self, wait_for_reconnect: bool, cx: &mut ModelContext<Self>) {
         cx.notify();
 
@@ -1043,12 +1223,24 @@ impl ChannelStore {
 
                 if let Some(this) = this.upgrade() {
                     this.update(&mut cx, |this, cx| {
-                        for (_, buffer) in this.opened_buffers.drain() {
-                            if let OpenedModelHandle::Open(buffer) = buffer {
-                                if let Some(buffer) = buffer.upgrade() {
-                                    buffer.update(cx, |buffer, cx| buffer.disconnect(cx));
+                        // Keep the buffers while waiting to reconnect, so that they
+                        // can still be edited, and are rejoined once reconnected.
+                        let mut buffers = Vec::new();
+                        if wait_for_reconnect {
+                            for buffer in this.opened_buffers.values() {
+                                if let OpenedModelHandle::Open(buffer) = buffer {
+                                    buffers.extend(buffer.upgrade());
                                 }
                             }
+                        } else {
+                            for (_, buffer) in this.opened_buffers.drain() {
+                                if let OpenedModelHandle::Open(buffer) = buffer {
+                                    buffers.extend(buffer.upgrade());
+                                }
+                            }
+                        }
+                        for buffer in buffers {
+                            buffer.update(cx, |buffer, cx| buffer.disconnect(cx));
                         }
                     })
                     .ok();
@@ -1185,6 +1377,18 @@ impl ChannelStore {
             }
         }
 
+        let channel_ids_to_reopen = self
+            .channel_buffers_to_reopen
+            .keys()
+            .copied()
+            .filter(|channel_id| self.channel_for_id(*channel_id).is_some())
+            .collect::<Vec<_>>();
+        for channel_id in channel_ids_to_reopen {
+            if let Some(replaced_buffer) = self.channel_buffers_to_reopen.remove(&channel_id) {
+                self.reopen_channel_buffer(channel_id, replaced_buffer, cx);
+            }
+        }
+
         cx.notify();
         if payload.channel_participants.is_empty() {
             return None;
@@ -187,6 +187,7 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
                     nonce: Some(1.into()),
                     reply_to_message_id: None,
                     edited_at: None,
+                    reactions: vec![],
                 },
                 proto::ChannelMessage {
                     id: 11,
@@ -197,6 +198,7 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
                     nonce: Some(2.into()),
                     reply_to_message_id: None,
                     edited_at: None,
+                    reactions: vec![],
                 },
             ],
             done: false,
@@ -246,6 +248,7 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
             nonce: Some(3.into()),
             reply_to_message_id: None,
             edited_at: None,
+            reactions: vec![],
         }),
     });
 
@@ -301,6 +304,7 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
                     mentions: vec![],
                     reply_to_message_id: None,
                     edited_at: None,
+                    reactions: vec![],
                 },
                 proto::ChannelMessage {
                     id: 9,
@@ -311,6 +315,7 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
                     mentions: vec![],
                     reply_to_message_id: None,
                     edited_at: None,
+                    reactions: vec![],
                 },
             ],
         },
@@ -21,7 +21,7 @@ use gpui::{
     Task, WeakModel,
 };
 use lazy_static::lazy_static;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use postage::watch;
 use rand::prelude::*;
 use release_channel::{AppVersion, ReleaseChannel};
@@ -162,6 +162,7 @@ pub struct Client {
     http: Arc<HttpClientWithUrl>,
     telemetry: Arc<Telemetry>,
     state: RwLock<ClientState>,
+    rate_limits: Arc<RateLimits>,
 
     #[allow(clippy::type_complexity)]
     #[cfg(any(test, feature = "test-support"))]
@@ -275,6 +276,35 @@ struct ClientState {
     >,
 }
 
+/// The requests that the server has rate limited, and when they can next be sent.
+/// Until then, those requests fail without reaching the server, so that we back off
+/// instead of repeatedly hitting the limit.
+#[derive(Default)]
+struct RateLimits(Mutex<HashMap<&'static str, Instant>>);
+
+impl RateLimits {
+    fn check(&self, message_name: &'static str) -> Result<()> {
+        let mut retry_at_by_message = self.0.lock();
+        if let Some(retry_at) = retry_at_by_message.get(message_name) {
+            let now = Instant::now();
+            if *retry_at > now {
+                return Err(RpcError::rate_limit_exceeded(*retry_at - now).into());
+            }
+            retry_at_by_message.remove(message_name);
+        }
+        Ok(())
+    }
+
+    fn record(&self, message_name: &'static str, error: &anyhow::Error) {
+        if let Some(retry_after) = error.retry_after() {
+            log::warn!("rpc request {message_name} was rate limited for {retry_after:?}");
+            self.0
+                .lock()
+                .insert(message_name, Instant::now() + retry_after);
+        }
+    }
+}
+
 enum WeakSubscriber {
     Entity { handle: AnyWeakModel },
     Pending(Vec<Box<dyn AnyTypedEnvelope>>),
@@ -449,6 +479,7 @@ impl Client {
             telemetry: Telemetry::new(clock, http.clone(), cx),
             http,
             state: Default::default(),
+            rate_limits: Default::default(),
 
             #[cfg(any(test, feature = "test-support"))]
             authenticate: Default::default(),
@@ -1310,8 +1341,11 @@ impl Client {
             client_id,
             T::NAME
         );
+        let rate_limits = self.rate_limits.clone();
         let response = self
-            .connection_id()
+            .rate_limits
+            .check(T::NAME)
+            .and_then(|()| self.connection_id())
             .map(|conn_id| self.peer.request_stream(conn_id, request));
         async move {
             let response = response?.await;
@@ -1320,7 +1354,11 @@ impl Client {
                 client_id,
                 T::NAME
             );
-            response
+            Ok(response?.inspect(move |response| {
+                if let Err(error) = response {
+                    rate_limits.record(T::NAME, error);
+                }
+            }))
         }
     }
 
@@ -1334,8 +1372,11 @@ impl Client {
             client_id,
             T::NAME
         );
+        let rate_limits = self.rate_limits.clone();
         let response = self
-            .connection_id()
+            .rate_limits
+            .check(T::NAME)
+            .and_then(|()| self.connection_id())
             .map(|conn_id| self.peer.request_envelope(conn_id, request));
         async move {
             let response = response?.await;
@@ -1344,6 +1385,9 @@ impl Client {
                 client_id,
                 T::NAME
             );
+            if let Err(error) = &response {
+                rate_limits.record(T::NAME, error);
+            }
             response
         }
     }
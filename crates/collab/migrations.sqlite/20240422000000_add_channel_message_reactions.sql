@@ -0,0 +1,7 @@
+CREATE TABLE "channel_message_reactions" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "emoji" VARCHAR NOT NULL
+);
+CREATE UNIQUE INDEX "index_channel_message_reactions_on_message_id_user_id_emoji" ON "channel_message_reactions" ("message_id", "user_id", "emoji");
@@ -0,0 +1,57 @@
+-- SQLite can't drop columns with foreign keys, so the tables are rebuilt.
+CREATE TABLE "review_comments_new" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "host_user_id" INTEGER REFERENCES users (id) ON DELETE CASCADE,
+    "remote_project_id" INTEGER REFERENCES remote_projects (id) ON DELETE CASCADE,
+    "worktree_abs_path" VARCHAR NOT NULL,
+    "path" VARCHAR NOT NULL,
+    "start_row" INTEGER NOT NULL,
+    "start_column" INTEGER NOT NULL,
+    "end_row" INTEGER NOT NULL,
+    "end_column" INTEGER NOT NULL,
+    "anchor_text" TEXT NOT NULL DEFAULT '',
+    "author_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "body" TEXT NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "reply_to_comment_id" INTEGER REFERENCES review_comments_new (id) ON DELETE CASCADE,
+    "resolved" BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+INSERT INTO "review_comments_new" (
+    "id", "host_user_id", "remote_project_id", "worktree_abs_path", "path",
+    "start_row", "start_column", "end_row", "end_column", "author_id", "body",
+    "created_at", "reply_to_comment_id", "resolved"
+)
+SELECT
+    "review_comments"."id", "projects"."host_user_id", "projects"."remote_project_id",
+    "worktrees"."abs_path", "review_comments"."path", "review_comments"."start_row",
+    "review_comments"."start_column", "review_comments"."end_row",
+    "review_comments"."end_column", "review_comments"."author_id", "review_comments"."body",
+    "review_comments"."created_at", "review_comments"."reply_to_comment_id",
+    "review_comments"."resolved"
+FROM "review_comments"
+JOIN "projects" ON "projects"."id" = "review_comments"."project_id"
+JOIN "worktrees" ON "worktrees"."project_id" = "review_comments"."project_id"
+    AND "worktrees"."id" = "review_comments"."worktree_id";
+
+CREATE TABLE "review_comment_mentions_new" (
+    "comment_id" INTEGER NOT NULL REFERENCES review_comments_new (id) ON DELETE CASCADE,
+    "start_offset" INTEGER NOT NULL,
+    "end_offset" INTEGER NOT NULL,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    PRIMARY KEY(comment_id, start_offset)
+);
+
+INSERT INTO "review_comment_mentions_new"
+SELECT "review_comment_mentions".*
+FROM "review_comment_mentions"
+JOIN "review_comments_new" ON "review_comments_new"."id" = "review_comment_mentions"."comment_id";
+
+DROP TABLE "review_comment_mentions";
+DROP TABLE "review_comments";
+ALTER TABLE "review_comments_new" RENAME TO "review_comments";
+ALTER TABLE "review_comment_mentions_new" RENAME TO "review_comment_mentions";
+
+CREATE INDEX "index_review_comments_on_host_user_id_and_worktree" ON "review_comments" ("host_user_id", "worktree_abs_path");
+CREATE INDEX "index_review_comments_on_remote_project_id_and_worktree" ON "review_comments" ("remote_project_id", "worktree_abs_path");
+CREATE INDEX "index_review_comments_on_reply_to_comment_id" ON "review_comments" ("reply_to_comment_id");
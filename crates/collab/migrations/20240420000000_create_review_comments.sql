@@ -0,0 +1,26 @@
+CREATE TABLE "review_comments" (
+    "id" SERIAL PRIMARY KEY,
+    "project_id" INTEGER NOT NULL REFERENCES projects (id) ON DELETE CASCADE,
+    "worktree_id" INTEGER NOT NULL,
+    "path" VARCHAR NOT NULL,
+    "start_row" INTEGER NOT NULL,
+    "start_column" INTEGER NOT NULL,
+    "end_row" INTEGER NOT NULL,
+    "end_column" INTEGER NOT NULL,
+    "author_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "body" TEXT NOT NULL,
+    "created_at" TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    "reply_to_comment_id" INTEGER REFERENCES review_comments (id) ON DELETE CASCADE,
+    "resolved" BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX "index_review_comments_on_project_id" ON "review_comments" ("project_id");
+CREATE INDEX "index_review_comments_on_reply_to_comment_id" ON "review_comments" ("reply_to_comment_id");
+
+CREATE TABLE "review_comment_mentions" (
+    "comment_id" INTEGER NOT NULL REFERENCES review_comments (id) ON DELETE CASCADE,
+    "start_offset" INTEGER NOT NULL,
+    "end_offset" INTEGER NOT NULL,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    PRIMARY KEY(comment_id, start_offset)
+);
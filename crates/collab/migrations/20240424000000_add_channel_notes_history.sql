@@ -0,0 +1,2 @@
+ALTER TABLE "buffer_operations" ADD COLUMN "user_id" INTEGER REFERENCES users (id) ON DELETE SET NULL;
+ALTER TABLE "buffer_snapshots" ADD COLUMN "created_at" TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now();
@@ -0,0 +1,2 @@
+ALTER TABLE "rooms" ADD COLUMN "presenter_connection_id" INTEGER;
+ALTER TABLE "rooms" ADD COLUMN "presenter_connection_server_id" INTEGER REFERENCES servers (id) ON DELETE SET NULL;
@@ -0,0 +1,29 @@
+-- Review comments outlive the projects they were made in, so that unsharing a
+-- project and sharing it again keeps its threads. Comments belong to the
+-- worktree's path on the host, which is either a user or a remote project.
+ALTER TABLE "review_comments"
+    ADD COLUMN "host_user_id" INTEGER REFERENCES users (id) ON DELETE CASCADE,
+    ADD COLUMN "remote_project_id" INTEGER REFERENCES remote_projects (id) ON DELETE CASCADE,
+    ADD COLUMN "worktree_abs_path" VARCHAR,
+    ADD COLUMN "anchor_text" TEXT NOT NULL DEFAULT '';
+
+UPDATE "review_comments"
+SET
+    "host_user_id" = "projects"."host_user_id",
+    "remote_project_id" = "projects"."remote_project_id",
+    "worktree_abs_path" = "worktrees"."abs_path"
+FROM "projects", "worktrees"
+WHERE
+    "projects"."id" = "review_comments"."project_id"
+    AND "worktrees"."project_id" = "review_comments"."project_id"
+    AND "worktrees"."id" = "review_comments"."worktree_id";
+
+DELETE FROM "review_comments" WHERE "worktree_abs_path" IS NULL;
+
+ALTER TABLE "review_comments"
+    ALTER COLUMN "worktree_abs_path" SET NOT NULL,
+    DROP COLUMN "project_id",
+    DROP COLUMN "worktree_id";
+
+CREATE INDEX "index_review_comments_on_host_user_id_and_worktree" ON "review_comments" ("host_user_id", "worktree_abs_path");
+CREATE INDEX "index_review_comments_on_remote_project_id_and_worktree" ON "review_comments" ("remote_project_id", "worktree_abs_path");
@@ -1,6 +1,8 @@
+pub mod admin;
 pub mod events;
 pub mod extensions;
 pub mod ips_file;
+pub mod signin;
 pub mod slack;
 
 use crate::{
@@ -33,6 +35,7 @@ pub fn routes(rpc_server: Option<Arc<rpc::Server>>, state: Arc<AppState>) -> Rou
         .route("/rpc_server_snapshot", get(get_rpc_server_snapshot))
         .route("/contributors", get(get_contributors).post(add_contributor))
         .route("/contributor", get(check_is_contributor))
+        .merge(admin::router())
         .layer(
             ServiceBuilder::new()
                 .layer(Extension(state))
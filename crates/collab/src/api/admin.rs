@@ -0,0 +1,230 @@
+use crate::{
+    db::{ActiveRoom, ChannelId, ChannelRole, PrincipalId, User, UserId},
+    rpc, AppState, Error, RateBucketState, Result,
+};
+use ::rpc::ConnectionId;
+use anyhow::anyhow;
+use axum::{
+    body::Body,
+    extract::{self, Path, Query},
+    http::StatusCode,
+    routing::{delete, get, post, put},
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Routes for administering the server. These are mounted alongside the other
+/// API routes, so they require the API token.
+pub fn router() -> Router<(), Body> {
+    Router::new()
+        .route("/admin/users", get(list_users))
+        .route("/admin/users/:id/ban", post(ban_user))
+        .route("/admin/users/:id/unban", post(unban_user))
+        .route("/admin/users/:id/connections", delete(disconnect_user))
+        .route("/admin/users/:id/rate_limits", get(get_user_rate_limits))
+        .route("/admin/channels", get(list_channels))
+        .route("/admin/channels/:id/members", get(list_channel_members))
+        .route(
+            "/admin/channels/:id/members/:user_id",
+            put(set_channel_member_role).delete(remove_channel_member),
+        )
+        .route("/admin/rooms", get(list_active_rooms))
+        .route("/admin/connections", get(list_connections))
+        .route(
+            "/admin/connections/:owner_id/:id",
+            delete(disconnect_connection),
+        )
+}
+
+#[derive(Debug, Deserialize)]
+struct ListUsersParams {
+    #[serde(default)]
+    page: u32,
+    limit: Option<u32>,
+}
+
+async fn list_users(
+    Query(params): Query<ListUsersParams>,
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<Vec<User>>> {
+    let limit = params.limit.unwrap_or(100).min(1000);
+    Ok(Json(app.db.get_all_users(params.page, limit).await?))
+}
+
+async fn ban_user(
+    Path(user_id): Path<UserId>,
+    Extension(app): Extension<Arc<AppState>>,
+    Extension(rpc_server): Extension<Option<Arc<rpc::Server>>>,
+) -> Result<Json<User>> {
+    let user = app.db.set_user_banned(user_id, true).await?;
+    if let Some(rpc_server) = rpc_server {
+        rpc_server.disconnect_user(user_id);
+    }
+    Ok(Json(user))
+}
+
+async fn unban_user(
+    Path(user_id): Path<UserId>,
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<User>> {
+    Ok(Json(app.db.set_user_banned(user_id, false).await?))
+}
+
+#[derive(Debug, Serialize)]
+struct DisconnectResponse {
+    connection_ids: Vec<ConnectionId>,
+}
+
+async fn disconnect_user(
+    Path(user_id): Path<UserId>,
+    Extension(rpc_server): Extension<Option<Arc<rpc::Server>>>,
+) -> Result<Json<DisconnectResponse>> {
+    let rpc_server = require_rpc_server(rpc_server)?;
+    Ok(Json(DisconnectResponse {
+        connection_ids: rpc_server.disconnect_user(user_id),
+    }))
+}
+
+async fn get_user_rate_limits(
+    Path(user_id): Path<UserId>,
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<Vec<RateBucketState>>> {
+    Ok(Json(app.rate_limiter.user_buckets(user_id).await?))
+}
+
+#[derive(Debug, Serialize)]
+struct ChannelResponse {
+    id: ChannelId,
+    name: String,
+    parent_path: Vec<ChannelId>,
+}
+
+async fn list_channels(
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<Vec<ChannelResponse>>> {
+    let channels = app.db.get_all_channels_for_admin().await?;
+    Ok(Json(
+        channels
+            .into_iter()
+            .map(|channel| ChannelResponse {
+                id: channel.id,
+                name: channel.name,
+                parent_path: channel.parent_path,
+            })
+            .collect(),
+    ))
+}
+
+#[derive(Debug, Serialize)]
+struct ChannelMemberResponse {
+    user_id: UserId,
+    role: ChannelRole,
+    accepted: bool,
+}
+
+async fn list_channel_members(
+    Path(channel_id): Path<ChannelId>,
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<Vec<ChannelMemberResponse>>> {
+    let members = app.db.get_channel_members_for_admin(channel_id).await?;
+    Ok(Json(
+        members
+            .into_iter()
+            .map(|member| ChannelMemberResponse {
+                user_id: member.user_id,
+                role: member.role,
+                accepted: member.accepted,
+            })
+            .collect(),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct SetChannelMemberRoleParams {
+    role: ChannelRole,
+}
+
+async fn set_channel_member_role(
+    Path((channel_id, user_id)): Path<(ChannelId, UserId)>,
+    Extension(app): Extension<Arc<AppState>>,
+    Extension(rpc_server): Extension<Option<Arc<rpc::Server>>>,
+    extract::Json(params): extract::Json<SetChannelMemberRoleParams>,
+) -> Result<()> {
+    let membership_update = app
+        .db
+        .set_channel_member_role_for_admin(channel_id, user_id, params.role)
+        .await?;
+    if let Some(rpc_server) = rpc_server {
+        rpc_server.channel_membership_updated(user_id, membership_update);
+    }
+    Ok(())
+}
+
+async fn remove_channel_member(
+    Path((channel_id, user_id)): Path<(ChannelId, UserId)>,
+    Extension(app): Extension<Arc<AppState>>,
+    Extension(rpc_server): Extension<Option<Arc<rpc::Server>>>,
+) -> Result<()> {
+    let membership_update = app
+        .db
+        .remove_channel_member_for_admin(channel_id, user_id)
+        .await?;
+    if let Some(rpc_server) = rpc_server {
+        rpc_server.channel_membership_updated(user_id, membership_update);
+    }
+    Ok(())
+}
+
+async fn list_active_rooms(
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<Vec<ActiveRoom>>> {
+    Ok(Json(app.db.get_active_rooms().await?))
+}
+
+#[derive(Debug, Serialize)]
+struct ConnectionResponse {
+    connection_id: ConnectionId,
+    principal_id: PrincipalId,
+    admin: bool,
+    zed_version: String,
+}
+
+async fn list_connections(
+    Extension(rpc_server): Extension<Option<Arc<rpc::Server>>>,
+) -> Result<Json<Vec<ConnectionResponse>>> {
+    let rpc_server = require_rpc_server(rpc_server)?;
+    let connection_pool = rpc_server.connection_pool.lock();
+    Ok(Json(
+        connection_pool
+            .connections_by_id()
+            .map(|(connection_id, connection)| ConnectionResponse {
+                connection_id,
+                principal_id: connection.principal_id,
+                admin: connection.admin,
+                zed_version: connection.zed_version.to_string(),
+            })
+            .collect(),
+    ))
+}
+
+async fn disconnect_connection(
+    Path((owner_id, id)): Path<(u32, u32)>,
+    Extension(rpc_server): Extension<Option<Arc<rpc::Server>>>,
+) -> Result<Json<DisconnectResponse>> {
+    let rpc_server = require_rpc_server(rpc_server)?;
+    let connection_id = ConnectionId { owner_id, id };
+    if !rpc_server.disconnect(connection_id) {
+        return Err(Error::Http(
+            StatusCode::NOT_FOUND,
+            format!("no such connection {connection_id}"),
+        ));
+    }
+    Ok(Json(DisconnectResponse {
+        connection_ids: vec![connection_id],
+    }))
+}
+
+fn require_rpc_server(rpc_server: Option<Arc<rpc::Server>>) -> Result<Arc<rpc::Server>> {
+    rpc_server.ok_or_else(|| Error::Internal(anyhow!("rpc server is not available")))
+}
@@ -4,8 +4,8 @@ use anyhow::{anyhow, Context as _};
 use aws_sdk_s3::presigning::PresigningConfig;
 use axum::{
     extract::{Path, Query},
-    http::StatusCode,
-    response::Redirect,
+    http::{header, StatusCode},
+    response::{IntoResponse, Redirect, Response},
     routing::get,
     Extension, Json, Router,
 };
@@ -13,8 +13,8 @@ use collections::HashMap;
 use rpc::{ExtensionApiManifest, GetExtensionsResponse};
 use semantic_version::SemanticVersion;
 use serde::Deserialize;
-use std::{sync::Arc, time::Duration};
-use time::PrimitiveDateTime;
+use std::{fs, path::Path as FsPath, sync::Arc, time::Duration};
+use time::{OffsetDateTime, PrimitiveDateTime};
 use util::{maybe, ResultExt};
 
 pub fn router() -> Router {
@@ -122,7 +122,7 @@ async fn download_latest_extension(
     Extension(app): Extension<Arc<AppState>>,
     Path(params): Path<DownloadLatestExtensionPathParams>,
     Query(query): Query<DownloadLatestExtensionQueryParams>,
-) -> Result<Redirect> {
+) -> Result<Response> {
     let constraints = maybe!({
         let min_schema_version = query.min_schema_version?;
         let max_schema_version = query.max_schema_version?;
@@ -159,17 +159,17 @@ struct DownloadExtensionParams {
 async fn download_extension(
     Extension(app): Extension<Arc<AppState>>,
     Path(params): Path<DownloadExtensionParams>,
-) -> Result<Redirect> {
-    let Some((blob_store_client, bucket)) = app
+) -> Result<Response> {
+    let blob_store = app
         .blob_store_client
         .clone()
-        .zip(app.config.blob_store_bucket.clone())
-    else {
+        .zip(app.config.blob_store_bucket.clone());
+    if blob_store.is_none() && app.config.blob_store_path.is_none() {
         Err(Error::Http(
             StatusCode::NOT_IMPLEMENTED,
             "not supported".into(),
         ))?
-    };
+    }
 
     let DownloadExtensionParams {
         extension_id,
@@ -188,17 +188,26 @@ async fn download_extension(
         ))?;
     }
 
+    let key = format!("extensions/{extension_id}/{version}/archive.tar.gz");
+
+    let Some((blob_store_client, bucket)) = blob_store else {
+        // Self-hosted servers serve archives straight from the local blob store.
+        let blob_store_path = app.config.blob_store_path.as_ref().unwrap();
+        let archive = tokio::fs::read(blob_store_path.join(&key))
+            .await
+            .with_context(|| format!("failed to read extension archive {key}"))?;
+        return Ok(([(header::CONTENT_TYPE, "application/gzip")], archive).into_response());
+    };
+
     let url = blob_store_client
         .get_object()
         .bucket(bucket)
-        .key(format!(
-            "extensions/{extension_id}/{version}/archive.tar.gz"
-        ))
+        .key(key)
         .presigned(PresigningConfig::expires_in(EXTENSION_DOWNLOAD_URL_LIFETIME).unwrap())
         .await
         .map_err(|e| anyhow!("failed to create presigned extension download url {e}"))?;
 
-    Ok(Redirect::temporary(url.uri()))
+    Ok(Redirect::temporary(url.uri()).into_response())
 }
 
 const EXTENSION_FETCH_INTERVAL: Duration = Duration::from_secs(5 * 60);
@@ -206,7 +215,11 @@ const EXTENSION_DOWNLOAD_URL_LIFETIME: Duration = Duration::from_secs(3 * 60);
 
 pub fn fetch_extensions_from_blob_store_periodically(app_state: Arc<AppState>) {
     let Some(blob_store_client) = app_state.blob_store_client.clone() else {
-        log::info!("no blob store client");
+        if let Some(blob_store_path) = app_state.config.blob_store_path.clone() {
+            fetch_extensions_from_directory_periodically(blob_store_path, app_state);
+        } else {
+            log::info!("no blob store client");
+        }
         return;
     };
     let Some(blob_store_bucket) = app_state.config.blob_store_bucket.clone() else {
@@ -232,6 +245,94 @@ pub fn fetch_extensions_from_blob_store_periodically(app_state: Arc<AppState>) {
     });
 }
 
+fn fetch_extensions_from_directory_periodically(
+    blob_store_path: std::path::PathBuf,
+    app_state: Arc<AppState>,
+) {
+    let executor = app_state.executor.clone();
+    executor.spawn_detached({
+        let executor = executor.clone();
+        async move {
+            loop {
+                fetch_extensions_from_directory(&blob_store_path, &app_state)
+                    .await
+                    .log_err();
+                executor.sleep(EXTENSION_FETCH_INTERVAL).await;
+            }
+        }
+    });
+}
+
+/// Loads extensions from a local blob store, which uses the same
+/// `extensions/<id>/<version>/` layout as the bucket.
+async fn fetch_extensions_from_directory(
+    blob_store_path: &FsPath,
+    app_state: &Arc<AppState>,
+) -> anyhow::Result<()> {
+    log::info!("fetching extensions from {:?}", blob_store_path);
+
+    let extensions_path = blob_store_path.join("extensions");
+    if !extensions_path.exists() {
+        return Ok(());
+    }
+
+    let known_versions = app_state.db.get_known_extension_versions().await?;
+
+    let mut new_versions = HashMap::<String, Vec<NewExtensionVersion>>::default();
+    let empty = Vec::new();
+    for extension_entry in fs::read_dir(&extensions_path)? {
+        let extension_entry = extension_entry?;
+        let extension_id = extension_entry.file_name().to_string_lossy().into_owned();
+        let known_versions = known_versions.get(&extension_id).unwrap_or(&empty);
+
+        for version_entry in fs::read_dir(extension_entry.path())? {
+            let version_entry = version_entry?;
+            let version = version_entry.file_name().to_string_lossy().into_owned();
+            if known_versions.binary_search(&version).is_ok() {
+                continue;
+            }
+
+            let manifest_path = version_entry.path().join("manifest.json");
+            let Ok(metadata) = fs::metadata(&manifest_path) else {
+                continue;
+            };
+            let extension = maybe!({
+                let manifest_bytes = fs::read(&manifest_path)?;
+                let published_at = OffsetDateTime::from(metadata.modified()?);
+                parse_extension_manifest(
+                    &extension_id,
+                    &version,
+                    &manifest_bytes,
+                    PrimitiveDateTime::new(published_at.date(), published_at.time()),
+                )
+            });
+            if let Some(extension) = extension.log_err() {
+                new_versions
+                    .entry(extension_id.clone())
+                    .or_default()
+                    .push(extension);
+            }
+        }
+    }
+
+    let new_versions: HashMap<&str, Vec<NewExtensionVersion>> = new_versions
+        .iter_mut()
+        .map(|(extension_id, versions)| (extension_id.as_str(), std::mem::take(versions)))
+        .collect();
+    app_state
+        .db
+        .insert_extension_versions(&new_versions)
+        .await?;
+
+    log::info!(
+        "fetched {} new extensions from {:?}",
+        new_versions.values().map(|v| v.len()).sum::<usize>(),
+        blob_store_path
+    );
+
+    Ok(())
+}
+
 async fn fetch_extensions_from_blob_store(
     blob_store_client: &aws_sdk_s3::Client,
     blob_store_bucket: &String,
@@ -335,18 +436,27 @@ async fn fetch_extension_manifest(
             format!("failed to download manifest for extension {extension_id} version {version}")
         })?
         .to_vec();
-    let manifest =
-        serde_json::from_slice::<ExtensionApiManifest>(&manifest_bytes).with_context(|| {
-            format!(
-                "invalid manifest for extension {extension_id} version {version}: {}",
-                String::from_utf8_lossy(&manifest_bytes)
-            )
-        })?;
     let published_at = object.last_modified.ok_or_else(|| {
         anyhow!("missing last modified timestamp for extension {extension_id} version {version}")
     })?;
     let published_at = time::OffsetDateTime::from_unix_timestamp_nanos(published_at.as_nanos())?;
     let published_at = PrimitiveDateTime::new(published_at.date(), published_at.time());
+    parse_extension_manifest(extension_id, version, &manifest_bytes, published_at)
+}
+
+fn parse_extension_manifest(
+    extension_id: &str,
+    version: &str,
+    manifest_bytes: &[u8],
+    published_at: PrimitiveDateTime,
+) -> Result<NewExtensionVersion, anyhow::Error> {
+    let manifest =
+        serde_json::from_slice::<ExtensionApiManifest>(manifest_bytes).with_context(|| {
+            format!(
+                "invalid manifest for extension {extension_id} version {version}: {}",
+                String::from_utf8_lossy(manifest_bytes)
+            )
+        })?;
     let version = semver::Version::parse(&manifest.version).with_context(|| {
         format!("invalid version for extension {extension_id} version {version}")
     })?;
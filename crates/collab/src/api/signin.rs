@@ -0,0 +1,156 @@
+use crate::{auth, AppState, Error, Result, UserTier, NATIVE_APP_SIGNIN};
+use axum::{
+    extract::{Form, Query},
+    http::StatusCode,
+    response::{Html, IntoResponse, Redirect, Response},
+    routing::get,
+    Extension, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Routes that let the Zed client sign in to a self-hosted server with a password,
+/// in place of the GitHub sign-in flow hosted on zed.dev.
+pub fn router() -> Router {
+    Router::new()
+        .route("/native_app_signin", get(get_signin).post(post_signin))
+        .route("/native_app_signin_succeeded", get(get_signin_succeeded))
+}
+
+#[derive(Debug, Deserialize)]
+struct SigninParams {
+    native_app_port: u16,
+    native_app_public_key: String,
+}
+
+#[derive(Deserialize)]
+struct SigninForm {
+    native_app_port: u16,
+    native_app_public_key: String,
+    login: String,
+    password: String,
+}
+
+async fn get_signin(Query(params): Query<SigninParams>) -> Result<Html<String>> {
+    validate_public_key(&params.native_app_public_key)?;
+    Ok(signin_page(
+        params.native_app_port,
+        &params.native_app_public_key,
+        None,
+    ))
+}
+
+async fn post_signin(
+    Extension(app): Extension<Arc<AppState>>,
+    Form(form): Form<SigninForm>,
+) -> Result<Response> {
+    validate_public_key(&form.native_app_public_key)?;
+
+    let user = app
+        .db
+        .get_user_by_github_login(&form.login)
+        .await?
+        .filter(|user| !user.banned && user.password_hash.is_some());
+    let Some(user) = user else {
+        // Hash anyway, so that the response time doesn't reveal which logins exist.
+        auth::verify_password_against_dummy_hash(&form.password);
+        return Ok(invalid_login(&form));
+    };
+
+    // Attempts are limited per account, so that passwords can't be guessed by brute force.
+    let tier = if user.admin {
+        UserTier::Admin
+    } else {
+        UserTier::Default
+    };
+    if app
+        .rate_limiter
+        .check(user.id, tier, NATIVE_APP_SIGNIN)
+        .await
+        .is_err()
+    {
+        return Ok((
+            StatusCode::TOO_MANY_REQUESTS,
+            signin_page(
+                form.native_app_port,
+                &form.native_app_public_key,
+                Some("Too many sign-in attempts. Try again later."),
+            ),
+        )
+            .into_response());
+    }
+
+    let password_hash = user.password_hash.as_deref().unwrap_or_default();
+    if !auth::verify_password(&form.password, password_hash) {
+        return Ok(invalid_login(&form));
+    }
+
+    let access_token = auth::create_access_token(app.db.as_ref(), user.id, None).await?;
+    let encrypted_access_token =
+        auth::encrypt_access_token(&access_token, form.native_app_public_key)?;
+
+    Ok(Redirect::to(&format!(
+        "http://127.0.0.1:{}?user_id={}&access_token={}",
+        form.native_app_port, user.id, encrypted_access_token
+    ))
+    .into_response())
+}
+
+fn invalid_login(form: &SigninForm) -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        signin_page(
+            form.native_app_port,
+            &form.native_app_public_key,
+            Some("Invalid login or password."),
+        ),
+    )
+        .into_response()
+}
+
+async fn get_signin_succeeded() -> Html<&'static str> {
+    Html(concat!(
+        "<!doctype html><html><head><title>Signed in</title></head><body>",
+        "<p>You are signed in. You can close this window and return to Zed.</p>",
+        "</body></html>"
+    ))
+}
+
+/// The public key is echoed back into the sign-in form, so reject anything
+/// that isn't a key the client could have generated.
+fn validate_public_key(public_key: &str) -> Result<()> {
+    rpc::auth::PublicKey::try_from(public_key.to_string()).map_err(|_| {
+        Error::Http(
+            StatusCode::BAD_REQUEST,
+            "invalid native app public key".to_string(),
+        )
+    })?;
+    Ok(())
+}
+
+fn signin_page(
+    native_app_port: u16,
+    native_app_public_key: &str,
+    error: Option<&str>,
+) -> Html<String> {
+    let error = error
+        .map(|error| format!("<p class=\"error\">{error}</p>"))
+        .unwrap_or_default();
+    Html(format!(
+        r#"<!doctype html>
+<html>
+<head><title>Sign in to Zed</title></head>
+<body>
+<h1>Sign in to Zed</h1>
+{error}
+<form method="post" action="/native_app_signin">
+<input type="hidden" name="native_app_port" value="{native_app_port}">
+<input type="hidden" name="native_app_public_key" value="{native_app_public_key}">
+<p><label>Login <input name="login" autocomplete="username" required autofocus></label></p>
+<p><label>Password <input name="password" type="password" autocomplete="current-password" required></label></p>
+<p><button type="submit">Sign in</button></p>
+</form>
+</body>
+</html>"#
+    ))
+}
@@ -10,9 +10,10 @@ use axum::{
     response::IntoResponse,
 };
 use prometheus::{exponential_buckets, register_histogram, Histogram};
+use rand::thread_rng;
 pub use rpc::auth::random_token;
 use scrypt::{
-    password_hash::{PasswordHash, PasswordVerifier},
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Scrypt,
 };
 use serde::{Deserialize, Serialize};
@@ -94,6 +95,12 @@ pub async fn validate_header<B>(mut req: Request<B>, next: Next<B>) -> impl Into
                 .get_user_by_id(user_id)
                 .await?
                 .ok_or_else(|| anyhow!("user {} not found", user_id))?;
+            if user.banned {
+                Err(Error::Http(
+                    StatusCode::FORBIDDEN,
+                    "user is banned".to_string(),
+                ))?;
+            }
 
             if let Some(impersonator_id) = validate_result.impersonator_id {
                 let admin = state
@@ -231,6 +238,45 @@ pub async fn verify_access_token(
     })
 }
 
+/// Hashes a password for users who sign in to a self-hosted server without GitHub.
+pub fn hash_password(password: &str) -> anyhow::Result<String> {
+    // Avoid slow hashing in tests. Debug builds can be deployed, so they hash at full strength.
+    let params = if cfg!(test) {
+        scrypt::Params::new(1, 1, 1).unwrap()
+    } else {
+        scrypt::Params::new(15, 8, 1).unwrap()
+    };
+
+    Ok(Scrypt
+        .hash_password(
+            password.as_bytes(),
+            None,
+            params,
+            &SaltString::generate(thread_rng()),
+        )
+        .map_err(anyhow::Error::new)?
+        .to_string())
+}
+
+/// Checks the given password against a hash produced by [`hash_password`].
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    let Ok(password_hash) = PasswordHash::new(password_hash) else {
+        return false;
+    };
+    Scrypt
+        .verify_password(password.as_bytes(), &password_hash)
+        .is_ok()
+}
+
+/// Checks the given password against a throwaway hash, so that signing in as a user who
+/// doesn't exist takes as long as signing in with the wrong password.
+pub fn verify_password_against_dummy_hash(password: &str) {
+    static DUMMY_PASSWORD_HASH: OnceLock<String> = OnceLock::new();
+    let password_hash = DUMMY_PASSWORD_HASH
+        .get_or_init(|| hash_password(&random_token()).expect("failed to hash the dummy password"));
+    verify_password(password, password_hash);
+}
+
 pub fn generate_dev_server_token(id: usize, access_token: String) -> String {
     format!("{}.{}", id, access_token)
 }
@@ -268,8 +314,6 @@ pub fn split_dev_server_token(dev_server_token: &str) -> anyhow::Result<(DevServ
 
 #[cfg(test)]
 mod test {
-    use rand::thread_rng;
-    use scrypt::password_hash::{PasswordHasher, SaltString};
     use sea_orm::EntityTrait;
 
     use super::*;
@@ -514,6 +514,12 @@ pub struct CreatedChannelMessage {
     pub notifications: NotificationBatch,
 }
 
+pub struct CreatedReviewComment {
+    pub comment: proto::ReviewComment,
+    pub connection_ids: Vec<ConnectionId>,
+    pub notifications: NotificationBatch,
+}
+
 pub struct UpdatedChannelMessage {
     pub message_id: MessageId,
     pub participant_connection_ids: Vec<ConnectionId>,
@@ -576,6 +582,29 @@ pub struct MembershipUpdated {
     pub removed_channels: Vec<ChannelId>,
 }
 
+/// A room with connected participants, as reported by the admin API.
+#[derive(Debug, Serialize)]
+pub struct ActiveRoom {
+    pub id: RoomId,
+    pub channel_id: Option<ChannelId>,
+    pub participants: Vec<ActiveRoomParticipant>,
+    pub projects: Vec<ActiveRoomProject>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActiveRoomParticipant {
+    pub user_id: UserId,
+    pub connection_id: Option<ConnectionId>,
+    pub role: Option<ChannelRole>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActiveRoomProject {
+    pub id: ProjectId,
+    pub host_user_id: Option<UserId>,
+    pub host_connection_id: Option<ConnectionId>,
+}
+
 /// The result of setting a member's role.
 #[derive(Debug)]
 #[allow(clippy::large_enum_variant)]
@@ -665,7 +694,7 @@ pub struct ChannelsForUser {
 #[derive(Debug)]
 pub struct RejoinedChannelBuffer {
     pub buffer: proto::RejoinedChannelBuffer,
-    pub old_connection_id: ConnectionId,
+    pub old_connection_id: Option<ConnectionId>,
 }
 
 #[derive(Clone)]
@@ -73,6 +73,7 @@ id_type!(ChannelBufferCollaboratorId);
 id_type!(ChannelChatParticipantId);
 id_type!(ChannelId);
 id_type!(ChannelMemberId);
+id_type!(ChannelMessageReactionId);
 id_type!(ContactId);
 id_type!(DevServerId);
 id_type!(ExtensionId);
@@ -86,6 +87,7 @@ id_type!(ProjectCollaboratorId);
 id_type!(ProjectId);
 id_type!(RemoteProjectId);
 id_type!(ReplicaId);
+id_type!(ReviewCommentId);
 id_type!(RoomId);
 id_type!(RoomParticipantId);
 id_type!(ServerId);
@@ -94,7 +96,17 @@ id_type!(UserId);
 
 /// ChannelRole gives you permissions for both channels and calls.
 #[derive(
-    Eq, PartialEq, Copy, Clone, Debug, EnumIter, DeriveActiveEnum, Default, Hash, Serialize,
+    Eq,
+    PartialEq,
+    Copy,
+    Clone,
+    Debug,
+    EnumIter,
+    DeriveActiveEnum,
+    Default,
+    Hash,
+    Serialize,
+    Deserialize,
 )]
 #[sea_orm(rs_type = "String", db_type = "String(None)")]
 pub enum ChannelRole {
@@ -14,6 +14,7 @@ pub mod notifications;
 pub mod projects;
 pub mod rate_buckets;
 pub mod remote_projects;
+pub mod review_comments;
 pub mod rooms;
 pub mod servers;
 pub mod users;
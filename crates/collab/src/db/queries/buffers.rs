@@ -1,4 +1,5 @@
 use super::*;
+use collections::BTreeSet;
 use prost::Message;
 use text::{EditOperation, UndoOperation};
 
@@ -10,12 +11,13 @@ pub struct LeftChannelBuffer {
 
 impl Database {
     /// Open a channel buffer. Returns the current contents, and adds you to the list of people
-    /// to notify on changes.
+    /// to notify on changes. The requested replica id is used if no one else is using it.
     pub async fn join_channel_buffer(
         &self,
         channel_id: ChannelId,
         user_id: UserId,
         connection: ConnectionId,
+        requested_replica_id: Option<ReplicaId>,
     ) -> Result<proto::JoinChannelBufferResponse> {
         self.transaction(|tx| async move {
             let channel = self.get_channel_internal(channel_id, &tx).await?;
@@ -46,6 +48,7 @@ impl Database {
                     operation_serialization_version: ActiveValue::Set(
                         storage::SERIALIZATION_VERSION,
                     ),
+                    created_at: ActiveValue::NotSet,
                 }
                 .insert(&*tx)
                 .await?;
@@ -61,7 +64,9 @@ impl Database {
                 .iter()
                 .map(|c| c.replica_id)
                 .collect::<HashSet<_>>();
-            let mut replica_id = ReplicaId(0);
+            let mut replica_id = requested_replica_id
+                .filter(|replica_id| replica_id.0 >= 0 && !replica_ids.contains(replica_id))
+                .unwrap_or(ReplicaId(0));
             while replica_ids.contains(&replica_id) {
                 replica_id.0 += 1;
             }
@@ -159,25 +164,6 @@ impl Database {
                     continue;
                 }
 
-                // Find the collaborator record for this user's previous lost
-                // connection. Update it with the new connection id.
-                let Some(self_collaborator) =
-                    collaborators.iter_mut().find(|c| c.user_id == user_id)
-                else {
-                    log::info!("can't rejoin buffer, no previous collaborator found");
-                    continue;
-                };
-                let old_connection_id = self_collaborator.connection();
-                *self_collaborator = channel_buffer_collaborator::ActiveModel {
-                    id: ActiveValue::Unchanged(self_collaborator.id),
-                    connection_id: ActiveValue::Set(connection_id.id as i32),
-                    connection_server_id: ActiveValue::Set(ServerId(connection_id.owner_id as i32)),
-                    connection_lost: ActiveValue::Set(false),
-                    ..Default::default()
-                }
-                .update(&*tx)
-                .await?;
-
                 let client_version = version_from_wire(&client_buffer.version);
                 let serialization_version = self
                     .get_buffer_operation_serialization_version(buffer.id, buffer.epoch, &tx)
@@ -196,6 +182,7 @@ impl Database {
                 // that the client has not seen.
                 let mut server_version = clock::Global::new();
                 let mut operations = Vec::new();
+                let mut unseen_replica_ids = HashSet::default();
                 while let Some(row) = rows.next().await {
                     let row = row?;
                     let timestamp = clock::Lamport {
@@ -204,11 +191,63 @@ impl Database {
                     };
                     server_version.observe(timestamp);
                     if !client_version.observed(timestamp) {
+                        unseen_replica_ids.insert(row.replica_id);
                         operations.push(proto::Operation {
                             variant: Some(operation_from_storage(row, serialization_version)?),
                         })
                     }
                 }
+                drop(rows);
+
+                // Find the collaborator record for this user's previous lost
+                // connection. Update it with the new connection id.
+                let old_connection_id = if let Some(self_collaborator) =
+                    collaborators.iter_mut().find(|c| c.user_id == user_id)
+                {
+                    let old_connection_id = self_collaborator.connection();
+                    *self_collaborator = channel_buffer_collaborator::ActiveModel {
+                        id: ActiveValue::Unchanged(self_collaborator.id),
+                        connection_id: ActiveValue::Set(connection_id.id as i32),
+                        connection_server_id: ActiveValue::Set(ServerId(
+                            connection_id.owner_id as i32,
+                        )),
+                        connection_lost: ActiveValue::Set(false),
+                        ..Default::default()
+                    }
+                    .update(&*tx)
+                    .await?;
+                    Some(old_connection_id)
+                } else {
+                    // If that record has already been cleaned up, the client can
+                    // keep its replica id, as long as no one else has taken it or
+                    // edited the buffer with it in the meantime.
+                    let Some(replica_id) = client_buffer.replica_id.map(|id| ReplicaId(id as i32))
+                    else {
+                        log::info!("can't rejoin buffer, no previous collaborator found");
+                        continue;
+                    };
+                    if collaborators.iter().any(|c| c.replica_id == replica_id)
+                        || unseen_replica_ids.contains(&replica_id.0)
+                    {
+                        log::info!("can't rejoin buffer, replica id has been reused");
+                        continue;
+                    }
+
+                    let collaborator = channel_buffer_collaborator::ActiveModel {
+                        channel_id: ActiveValue::Set(channel.id),
+                        connection_id: ActiveValue::Set(connection_id.id as i32),
+                        connection_server_id: ActiveValue::Set(ServerId(
+                            connection_id.owner_id as i32,
+                        )),
+                        user_id: ActiveValue::Set(user_id),
+                        replica_id: ActiveValue::Set(replica_id),
+                        ..Default::default()
+                    }
+                    .insert(&*tx)
+                    .await?;
+                    collaborators.push(collaborator);
+                    None
+                };
 
                 results.push(RejoinedChannelBuffer {
                     old_connection_id,
@@ -477,6 +516,10 @@ impl Database {
             let operations = operations
                 .iter()
                 .filter_map(|op| operation_to_storage(op, &buffer, serialization_version))
+                .map(|mut operation| {
+                    operation.user_id = ActiveValue::Set(Some(user));
+                    operation
+                })
                 .collect::<Vec<_>>();
 
             let mut channel_members;
@@ -681,6 +724,7 @@ impl Database {
                 lamport_timestamp: row.lamport_timestamp,
                 replica_id: row.replica_id,
                 value: Default::default(),
+                user_id: row.user_id,
             });
             operations.push(proto::Operation {
                 variant: Some(operation_from_storage(row, version)?),
@@ -701,21 +745,16 @@ impl Database {
             return Ok(());
         }
 
-        let mut text_buffer = text::Buffer::new(0, text::BufferId::new(1).unwrap(), base_text);
-        text_buffer
-            .apply_ops(operations.into_iter().filter_map(operation_from_wire))
-            .unwrap();
-
-        let base_text = text_buffer.text();
+        let base_text = apply_operations(base_text, operations);
         let epoch = buffer.epoch + 1;
 
-        buffer_snapshot::Model {
-            buffer_id: buffer.id,
-            epoch,
-            text: base_text,
-            operation_serialization_version: storage::SERIALIZATION_VERSION,
+        buffer_snapshot::ActiveModel {
+            buffer_id: ActiveValue::Set(buffer.id),
+            epoch: ActiveValue::Set(epoch),
+            text: ActiveValue::Set(base_text),
+            operation_serialization_version: ActiveValue::Set(storage::SERIALIZATION_VERSION),
+            created_at: ActiveValue::NotSet,
         }
-        .into_active_model()
         .insert(tx)
         .await?;
 
@@ -733,6 +772,111 @@ impl Database {
         Ok(())
     }
 
+    /// Returns the versions of a channel buffer, newest first. Each version is
+    /// the buffer's text at the end of an epoch, and is attributed to the users
+    /// who edited the buffer during that epoch.
+    pub async fn get_channel_buffer_history(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> Result<Vec<proto::ChannelBufferHistoryEntry>> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+            let buffer = self.get_channel_buffer(channel_id, &tx).await?;
+
+            // A version is saved when the snapshot for the following epoch is taken.
+            let mut saved_at = HashMap::default();
+            let mut snapshots = buffer_snapshot::Entity::find()
+                .filter(buffer_snapshot::Column::BufferId.eq(buffer.id))
+                .stream(&*tx)
+                .await?;
+            while let Some(snapshot) = snapshots.next().await {
+                let snapshot = snapshot?;
+                saved_at.insert(snapshot.epoch - 1, snapshot.created_at);
+            }
+            drop(snapshots);
+
+            #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
+            enum QueryEpochsAndUserIds {
+                Epoch,
+                UserId,
+            }
+
+            let mut author_ids = HashMap::<i32, BTreeSet<UserId>>::default();
+            let mut rows = buffer_operation::Entity::find()
+                .filter(
+                    buffer_operation::Column::BufferId
+                        .eq(buffer.id)
+                        .and(buffer_operation::Column::UserId.is_not_null()),
+                )
+                .select_only()
+                .column(buffer_operation::Column::Epoch)
+                .column(buffer_operation::Column::UserId)
+                .distinct()
+                .into_values::<_, QueryEpochsAndUserIds>()
+                .stream(&*tx)
+                .await?;
+            while let Some(row) = rows.next().await {
+                let (epoch, user_id): (i32, UserId) = row?;
+                author_ids.entry(epoch).or_default().insert(user_id);
+            }
+            drop(rows);
+
+            Ok((0..=buffer.epoch)
+                .rev()
+                .map(|epoch| proto::ChannelBufferHistoryEntry {
+                    epoch: epoch as u64,
+                    saved_at: saved_at
+                        .get(&epoch)
+                        .map(|saved_at| saved_at.assume_utc().unix_timestamp() as u64),
+                    author_ids: author_ids
+                        .remove(&epoch)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|user_id| user_id.to_proto())
+                        .collect(),
+                })
+                .collect())
+        })
+        .await
+    }
+
+    /// Returns the text of a channel buffer at the end of the given epoch.
+    pub async fn get_channel_buffer_version(
+        &self,
+        channel_id: ChannelId,
+        epoch: i32,
+        user_id: UserId,
+    ) -> Result<String> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+            let buffer = self.get_channel_buffer(channel_id, &tx).await?;
+
+            if epoch == buffer.epoch {
+                let (base_text, operations, _) = self.get_buffer_state(&buffer, &tx).await?;
+                return Ok(apply_operations(base_text, operations));
+            } else if epoch < 0 || epoch > buffer.epoch {
+                Err(anyhow!("no such version"))?;
+            }
+
+            let snapshot = buffer_snapshot::Entity::find()
+                .filter(
+                    buffer_snapshot::Column::BufferId
+                        .eq(buffer.id)
+                        .and(buffer_snapshot::Column::Epoch.eq(epoch + 1)),
+                )
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such snapshot"))?;
+            Ok(snapshot.text)
+        })
+        .await
+    }
+
     pub async fn observe_buffer_version(
         &self,
         buffer_id: BufferId,
@@ -784,6 +928,7 @@ impl Database {
                         replica_id: op.replica_id as u32,
                         timestamp: op.lamport_timestamp as u32,
                     }],
+                    replica_id: None,
                 })
             })
             .collect())
@@ -842,9 +987,18 @@ fn operation_to_storage(
         replica_id: ActiveValue::Set(replica_id as i32),
         lamport_timestamp: ActiveValue::Set(lamport_timestamp as i32),
         value: ActiveValue::Set(value.encode_to_vec()),
+        user_id: ActiveValue::NotSet,
     })
 }
 
+fn apply_operations(base_text: String, operations: Vec<proto::Operation>) -> String {
+    let mut text_buffer = text::Buffer::new(0, text::BufferId::new(1).unwrap(), base_text);
+    text_buffer
+        .apply_ops(operations.into_iter().filter_map(operation_from_wire))
+        .unwrap();
+    text_buffer.text()
+}
+
 fn operation_from_storage(
     row: buffer_operation::Model,
     _format_version: i32,
@@ -622,6 +622,7 @@ impl Database {
                 } else {
                     vec![]
                 },
+                replica_id: None,
             });
         }
         drop(rows);
@@ -758,6 +759,106 @@ impl Database {
             .await?)
     }
 
+    /// Returns every channel on the server. There are no access checks here, so this should
+    /// only be used by the admin API.
+    pub async fn get_all_channels_for_admin(&self) -> Result<Vec<Channel>> {
+        self.transaction(|tx| async move {
+            Ok(channel::Entity::find()
+                .order_by_asc(channel::Column::Id)
+                .all(&*tx)
+                .await?
+                .into_iter()
+                .map(Channel::from_model)
+                .collect())
+        })
+        .await
+    }
+
+    /// Returns the members and invitees of the given channel's root, without access checks.
+    pub async fn get_channel_members_for_admin(
+        &self,
+        channel_id: ChannelId,
+    ) -> Result<Vec<channel_member::Model>> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.get_channel_participant_details_internal(&channel, &tx)
+                .await
+        })
+        .await
+    }
+
+    /// Gives a user the role in the given channel's root, adding them as a member
+    /// (or accepting their pending invitation) if needed. There are no access checks.
+    pub async fn set_channel_member_role_for_admin(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        role: ChannelRole,
+    ) -> Result<MembershipUpdated> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            let root_id = channel.root_id();
+
+            let membership = channel_member::Entity::find()
+                .filter(
+                    channel_member::Column::ChannelId
+                        .eq(root_id)
+                        .and(channel_member::Column::UserId.eq(user_id)),
+                )
+                .one(&*tx)
+                .await?;
+
+            if let Some(membership) = membership {
+                let mut update = membership.into_active_model();
+                update.role = ActiveValue::Set(role);
+                update.accepted = ActiveValue::Set(true);
+                channel_member::Entity::update(update).exec(&*tx).await?;
+            } else {
+                channel_member::ActiveModel {
+                    id: ActiveValue::NotSet,
+                    channel_id: ActiveValue::Set(root_id),
+                    user_id: ActiveValue::Set(user_id),
+                    accepted: ActiveValue::Set(true),
+                    role: ActiveValue::Set(role),
+                }
+                .insert(&*tx)
+                .await?;
+            }
+
+            let root = self.get_channel_internal(root_id, &tx).await?;
+            self.calculate_membership_updated(&root, user_id, &tx).await
+        })
+        .await
+    }
+
+    /// Removes a user from the given channel's root, without access checks.
+    pub async fn remove_channel_member_for_admin(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> Result<MembershipUpdated> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            let root = self.get_channel_internal(channel.root_id(), &tx).await?;
+
+            let result = channel_member::Entity::delete_many()
+                .filter(
+                    channel_member::Column::ChannelId
+                        .eq(root.id)
+                        .and(channel_member::Column::UserId.eq(user_id)),
+                )
+                .exec(&*tx)
+                .await?;
+
+            if result.rows_affected == 0 {
+                Err(anyhow!("no such member"))?;
+            }
+
+            self.calculate_membership_updated(&root, user_id, &tx).await
+        })
+        .await
+    }
+
     /// Returns the participants in the given channel.
     pub async fn get_channel_participants(
         &self,
@@ -1,6 +1,6 @@
 use super::*;
 use rpc::Notification;
-use sea_orm::{SelectColumns, TryInsertResult};
+use sea_orm::{DatabaseBackend, SelectColumns, TryInsertResult};
 use time::OffsetDateTime;
 use util::ResultExt;
 
@@ -166,6 +166,7 @@ impl Database {
                     edited_at: row
                         .edited_at
                         .map(|t| t.assume_utc().unix_timestamp() as u64),
+                    reactions: vec![],
                 }
             })
             .collect::<Vec<_>>();
@@ -199,10 +200,215 @@ impl Database {
                 }
             }
         }
+        drop(mentions);
+
+        let message_ixs_by_id = messages
+            .iter()
+            .enumerate()
+            .map(|(ix, message)| (message.id, ix))
+            .collect::<HashMap<_, _>>();
+        let mut reactions = channel_message_reaction::Entity::find()
+            .filter(
+                channel_message_reaction::Column::MessageId
+                    .is_in(message_ixs_by_id.keys().copied()),
+            )
+            .order_by_asc(channel_message_reaction::Column::Id)
+            .stream(tx)
+            .await?;
+        while let Some(reaction) = reactions.next().await {
+            let reaction = reaction?;
+            if let Some(ix) = message_ixs_by_id.get(&reaction.message_id.to_proto()) {
+                push_reaction(
+                    &mut messages[*ix].reactions,
+                    reaction.emoji,
+                    reaction.user_id.to_proto(),
+                );
+            }
+        }
 
         Ok(messages)
     }
 
+    /// Returns the reply chain that the given message belongs to, starting
+    /// with the message that the chain replies to, in the order they were sent.
+    pub async fn get_channel_message_thread(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        message_id: MessageId,
+    ) -> Result<Vec<proto::ChannelMessage>> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            let mut root = channel_message::Entity::find_by_id(message_id)
+                .filter(channel_message::Column::ChannelId.eq(channel_id))
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such message"))?;
+            let mut visited_ids = HashSet::from_iter([root.id]);
+            while let Some(parent_id) = root.reply_to_message_id {
+                if !visited_ids.insert(parent_id) {
+                    break;
+                }
+                let Some(parent) = channel_message::Entity::find_by_id(parent_id)
+                    .filter(channel_message::Column::ChannelId.eq(channel_id))
+                    .one(&*tx)
+                    .await?
+                else {
+                    break;
+                };
+                root = parent;
+            }
+
+            let mut thread_ids = HashSet::from_iter([root.id]);
+            let mut rows = vec![root];
+            let mut parent_ids = vec![rows[0].id];
+            while !parent_ids.is_empty() {
+                let replies = channel_message::Entity::find()
+                    .filter(channel_message::Column::ChannelId.eq(channel_id))
+                    .filter(channel_message::Column::ReplyToMessageId.is_in(parent_ids))
+                    .all(&*tx)
+                    .await?;
+                parent_ids = Vec::new();
+                for reply in replies {
+                    if thread_ids.insert(reply.id) {
+                        parent_ids.push(reply.id);
+                        rows.push(reply);
+                    }
+                }
+            }
+
+            rows.sort_unstable_by_key(|row| std::cmp::Reverse(row.id));
+            self.load_channel_messages(rows, &tx).await
+        })
+        .await
+    }
+
+    /// Searches the bodies of a channel's messages, returning the most recent
+    /// matches first.
+    ///
+    /// Use `before_message_id` to paginate through the results.
+    pub async fn search_channel_messages(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        query: &str,
+        count: usize,
+        before_message_id: Option<MessageId>,
+    ) -> Result<Vec<proto::ChannelMessage>> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            let matches_query = match self.pool.get_database_backend() {
+                DatabaseBackend::Postgres => Expr::cust_with_values(
+                    "to_tsvector('english', body) @@ plainto_tsquery('english', $1)",
+                    [query],
+                ),
+                _ => {
+                    let mut pattern = String::from("%");
+                    for c in query.chars() {
+                        if matches!(c, '%' | '_' | '\\') {
+                            pattern.push('\\');
+                        }
+                        pattern.push(c);
+                    }
+                    pattern.push('%');
+                    Expr::cust_with_values("body LIKE $1 ESCAPE '\\'", [pattern])
+                }
+            };
+
+            let mut condition = Condition::all()
+                .add(channel_message::Column::ChannelId.eq(channel_id))
+                .add(matches_query);
+            if let Some(before_message_id) = before_message_id {
+                condition = condition.add(channel_message::Column::Id.lt(before_message_id));
+            }
+
+            let rows = channel_message::Entity::find()
+                .filter(condition)
+                .order_by_desc(channel_message::Column::Id)
+                .limit(count as u64)
+                .all(&*tx)
+                .await?;
+
+            let mut messages = self.load_channel_messages(rows, &tx).await?;
+            messages.reverse();
+            Ok(messages)
+        })
+        .await
+    }
+
+    /// Adds the given reaction to a channel message, or removes it if the user
+    /// had already reacted with it.
+    ///
+    /// Returns the connections of the chat's participants along with the
+    /// message's reactions.
+    pub async fn toggle_channel_message_reaction(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        user_id: UserId,
+        emoji: &str,
+    ) -> Result<(Vec<ConnectionId>, Vec<proto::ChannelMessageReaction>)> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            channel_message::Entity::find_by_id(message_id)
+                .filter(channel_message::Column::ChannelId.eq(channel_id))
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such message"))?;
+
+            let existing_reaction = channel_message_reaction::Entity::find()
+                .filter(channel_message_reaction::Column::MessageId.eq(message_id))
+                .filter(channel_message_reaction::Column::UserId.eq(user_id))
+                .filter(channel_message_reaction::Column::Emoji.eq(emoji))
+                .one(&*tx)
+                .await?;
+            if let Some(existing_reaction) = existing_reaction {
+                channel_message_reaction::Entity::delete_by_id(existing_reaction.id)
+                    .exec(&*tx)
+                    .await?;
+            } else {
+                channel_message_reaction::ActiveModel {
+                    id: ActiveValue::NotSet,
+                    message_id: ActiveValue::Set(message_id),
+                    user_id: ActiveValue::Set(user_id),
+                    emoji: ActiveValue::Set(emoji.to_string()),
+                }
+                .insert(&*tx)
+                .await?;
+            }
+
+            let mut reactions = Vec::new();
+            for reaction in channel_message_reaction::Entity::find()
+                .filter(channel_message_reaction::Column::MessageId.eq(message_id))
+                .order_by_asc(channel_message_reaction::Column::Id)
+                .all(&*tx)
+                .await?
+            {
+                push_reaction(&mut reactions, reaction.emoji, reaction.user_id.to_proto());
+            }
+
+            let connection_ids = channel_chat_participant::Entity::find()
+                .filter(channel_chat_participant::Column::ChannelId.eq(channel_id))
+                .all(&*tx)
+                .await?
+                .into_iter()
+                .map(|participant| participant.connection())
+                .collect();
+
+            Ok((connection_ids, reactions))
+        })
+        .await
+    }
+
     fn format_mentions_to_entities(
         &self,
         message_id: MessageId,
@@ -727,3 +933,19 @@ impl Database {
         .await
     }
 }
+
+/// Adds a user's reaction to a message's reactions, grouping it with the other
+/// reactions that use the same emoji.
+fn push_reaction(reactions: &mut Vec<proto::ChannelMessageReaction>, emoji: String, user_id: u64) {
+    if let Some(reaction) = reactions
+        .iter_mut()
+        .find(|reaction| reaction.emoji == emoji)
+    {
+        reaction.user_ids.push(user_id);
+    } else {
+        reactions.push(proto::ChannelMessageReaction {
+            emoji,
+            user_ids: vec![user_id],
+        });
+    }
+}
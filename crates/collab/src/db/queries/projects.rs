@@ -1159,7 +1159,7 @@ impl Database {
         .await
     }
 
-    async fn project_guest_connection_ids(
+    pub(crate) async fn project_guest_connection_ids(
         &self,
         project_id: ProjectId,
         tx: &DatabaseTransaction,
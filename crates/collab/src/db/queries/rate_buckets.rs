@@ -55,4 +55,18 @@ impl Database {
         })
         .await
     }
+
+    /// Retrieves all saved rate limits for the given user.
+    pub async fn get_rate_buckets_for_user(
+        &self,
+        user_id: UserId,
+    ) -> Result<Vec<rate_buckets::Model>> {
+        self.transaction(|tx| async move {
+            Ok(rate_buckets::Entity::find()
+                .filter(rate_buckets::Column::UserId.eq(user_id))
+                .all(&*tx)
+                .await?)
+        })
+        .await
+    }
 }
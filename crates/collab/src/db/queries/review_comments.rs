@@ -0,0 +1,438 @@
+use super::*;
+use rpc::Notification;
+use time::OffsetDateTime;
+
+impl Database {
+    /// Creates a review comment in the given project, either starting a new
+    /// thread or replying to an existing one.
+    ///
+    /// Replies always belong to the thread's first comment, and share its
+    /// location.
+    pub async fn create_review_comment(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+        user_id: UserId,
+        params: &proto::CreateReviewComment,
+        body: &str,
+        timestamp: OffsetDateTime,
+    ) -> Result<TransactionGuard<CreatedReviewComment>> {
+        self.project_transaction(project_id, |tx| async move {
+            self.access_project(
+                project_id,
+                connection_id,
+                PrincipalId::UserId(user_id),
+                Capability::ReadOnly,
+                &tx,
+            )
+            .await?;
+            let scope = self.review_comment_scope(project_id, &tx).await?;
+
+            let thread = if let Some(reply_to_comment_id) = params.reply_to_comment_id {
+                let comment = review_comment::Entity::find_by_id(ReviewCommentId::from_proto(
+                    reply_to_comment_id,
+                ))
+                .one(&*tx)
+                .await?
+                .filter(|comment| scope.contains(comment))
+                .ok_or_else(|| anyhow!("no such review comment"))?;
+                Some(match comment.reply_to_comment_id {
+                    Some(thread_id) => review_comment::Entity::find_by_id(thread_id)
+                        .one(&*tx)
+                        .await?
+                        .ok_or_else(|| anyhow!("no such review comment"))?,
+                    None => comment,
+                })
+            } else {
+                None
+            };
+            let worktree_abs_path = match &thread {
+                Some(thread) => thread.worktree_abs_path.clone(),
+                None => scope
+                    .worktree_abs_path(params.worktree_id as i64)
+                    .ok_or_else(|| anyhow!("no such worktree"))?
+                    .to_string(),
+            };
+
+            let timestamp = timestamp.to_offset(time::UtcOffset::UTC);
+            let timestamp = time::PrimitiveDateTime::new(timestamp.date(), timestamp.time());
+            let comment = review_comment::ActiveModel {
+                id: ActiveValue::NotSet,
+                host_user_id: ActiveValue::Set(scope.host_user_id),
+                remote_project_id: ActiveValue::Set(scope.remote_project_id),
+                worktree_abs_path: ActiveValue::Set(worktree_abs_path),
+                path: ActiveValue::Set(
+                    thread
+                        .as_ref()
+                        .map_or_else(|| params.path.clone(), |thread| thread.path.clone()),
+                ),
+                start_row: ActiveValue::Set(
+                    thread
+                        .as_ref()
+                        .map_or(params.start_row as i32, |thread| thread.start_row),
+                ),
+                start_column: ActiveValue::Set(
+                    thread
+                        .as_ref()
+                        .map_or(params.start_column as i32, |thread| thread.start_column),
+                ),
+                end_row: ActiveValue::Set(
+                    thread
+                        .as_ref()
+                        .map_or(params.end_row as i32, |thread| thread.end_row),
+                ),
+                end_column: ActiveValue::Set(
+                    thread
+                        .as_ref()
+                        .map_or(params.end_column as i32, |thread| thread.end_column),
+                ),
+                anchor_text: ActiveValue::Set(thread.as_ref().map_or_else(
+                    || params.anchor_text.clone(),
+                    |thread| thread.anchor_text.clone(),
+                )),
+                author_id: ActiveValue::Set(user_id),
+                body: ActiveValue::Set(body.to_string()),
+                created_at: ActiveValue::Set(timestamp),
+                reply_to_comment_id: ActiveValue::Set(thread.as_ref().map(|thread| thread.id)),
+                resolved: ActiveValue::Set(false),
+            }
+            .insert(&*tx)
+            .await?;
+
+            // Only the people who can see the comment can be mentioned in it.
+            let participant_ids = self
+                .review_comment_participant_ids(&scope.project, &tx)
+                .await?;
+            let mut mentions = params
+                .mentions
+                .iter()
+                .filter_map(|mention| {
+                    let range = mention.range.as_ref()?;
+                    let mentioned_user_id = UserId::from_proto(mention.user_id);
+                    if !body.is_char_boundary(range.start as usize)
+                        || !body.is_char_boundary(range.end as usize)
+                        || !participant_ids.contains(&mentioned_user_id)
+                    {
+                        return None;
+                    }
+                    Some((range.start as i32, range.end as i32, mentioned_user_id))
+                })
+                .collect::<Vec<_>>();
+            // Mentions are keyed by where they start, so keep one for each offset.
+            mentions.sort_by_key(|(start_offset, _, _)| *start_offset);
+            mentions.dedup_by_key(|(start_offset, _, _)| *start_offset);
+            if !mentions.is_empty() {
+                review_comment_mention::Entity::insert_many(mentions.iter().map(
+                    |(start_offset, end_offset, mentioned_user_id)| {
+                        review_comment_mention::ActiveModel {
+                            comment_id: ActiveValue::Set(comment.id),
+                            start_offset: ActiveValue::Set(*start_offset),
+                            end_offset: ActiveValue::Set(*end_offset),
+                            user_id: ActiveValue::Set(*mentioned_user_id),
+                        }
+                    },
+                ))
+                .exec(&*tx)
+                .await?;
+            }
+
+            let mut notifications = Vec::new();
+            let mentioned_user_ids = mentions
+                .iter()
+                .map(|(_, _, mentioned_user_id)| *mentioned_user_id)
+                .filter(|mentioned_user_id| *mentioned_user_id != user_id)
+                .collect::<HashSet<_>>();
+            for mentioned_user_id in mentioned_user_ids {
+                notifications.extend(
+                    self.create_notification(
+                        mentioned_user_id,
+                        Notification::ReviewCommentMention {
+                            comment_id: comment.id.to_proto(),
+                            sender_id: user_id.to_proto(),
+                            project_id: project_id.to_proto(),
+                            path: comment.path.clone(),
+                        },
+                        false,
+                        &tx,
+                    )
+                    .await?,
+                );
+            }
+
+            let connection_ids = self
+                .review_comment_connection_ids(&scope.project, &tx)
+                .await?;
+            let mut comments = self
+                .load_review_comments(vec![comment], &scope, &tx)
+                .await?;
+            Ok(CreatedReviewComment {
+                comment: comments
+                    .pop()
+                    .ok_or_else(|| anyhow!("no such review comment"))?,
+                connection_ids,
+                notifications,
+            })
+        })
+        .await
+    }
+
+    /// Marks the thread containing the given comment as resolved or open
+    /// again, returning the thread's first comment.
+    pub async fn resolve_review_thread(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+        user_id: UserId,
+        comment_id: ReviewCommentId,
+        resolved: bool,
+    ) -> Result<TransactionGuard<(proto::ReviewComment, Vec<ConnectionId>)>> {
+        self.project_transaction(project_id, |tx| async move {
+            self.access_project(
+                project_id,
+                connection_id,
+                PrincipalId::UserId(user_id),
+                Capability::ReadOnly,
+                &tx,
+            )
+            .await?;
+            let scope = self.review_comment_scope(project_id, &tx).await?;
+
+            let comment = review_comment::Entity::find_by_id(comment_id)
+                .one(&*tx)
+                .await?
+                .filter(|comment| scope.contains(comment))
+                .ok_or_else(|| anyhow!("no such review comment"))?;
+            let thread_id = comment.reply_to_comment_id.unwrap_or(comment.id);
+            let thread = review_comment::Entity::update(review_comment::ActiveModel {
+                id: ActiveValue::Unchanged(thread_id),
+                resolved: ActiveValue::Set(resolved),
+                ..Default::default()
+            })
+            .exec(&*tx)
+            .await?;
+
+            let connection_ids = self
+                .review_comment_connection_ids(&scope.project, &tx)
+                .await?;
+            let mut comments = self.load_review_comments(vec![thread], &scope, &tx).await?;
+            Ok((
+                comments
+                    .pop()
+                    .ok_or_else(|| anyhow!("no such review comment"))?,
+                connection_ids,
+            ))
+        })
+        .await
+    }
+
+    /// Returns all of the review comments in the given project, oldest first.
+    pub async fn get_review_comments(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+        user_id: UserId,
+    ) -> Result<Vec<proto::ReviewComment>> {
+        self.project_transaction(project_id, |tx| async move {
+            self.access_project(
+                project_id,
+                connection_id,
+                PrincipalId::UserId(user_id),
+                Capability::ReadOnly,
+                &tx,
+            )
+            .await?;
+            let scope = self.review_comment_scope(project_id, &tx).await?;
+
+            let rows = review_comment::Entity::find()
+                .filter(scope.condition())
+                .order_by_asc(review_comment::Column::Id)
+                .all(&*tx)
+                .await?;
+            self.load_review_comments(rows, &scope, &tx).await
+        })
+        .await
+        .map(|guard| guard.into_inner())
+    }
+
+    /// Returns the host and worktrees of the given project, which determine
+    /// the review comments that it shows.
+    async fn review_comment_scope(
+        &self,
+        project_id: ProjectId,
+        tx: &DatabaseTransaction,
+    ) -> Result<ReviewCommentScope> {
+        let project = project::Entity::find_by_id(project_id)
+            .one(tx)
+            .await?
+            .ok_or_else(|| anyhow!("no such project"))?;
+        if project.host_user_id.is_none() && project.remote_project_id.is_none() {
+            return Err(anyhow!("review comments aren't available in this project"))?;
+        }
+        let worktree_ids_by_abs_path = worktree::Entity::find()
+            .filter(worktree::Column::ProjectId.eq(project_id))
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|worktree| (worktree.abs_path, worktree.id))
+            .collect();
+        Ok(ReviewCommentScope {
+            host_user_id: project.host_user_id,
+            remote_project_id: project.remote_project_id,
+            worktree_ids_by_abs_path,
+            project,
+        })
+    }
+
+    /// Returns the comments as they appear in the scope's project, leaving out
+    /// the ones on worktrees that it doesn't contain.
+    async fn load_review_comments(
+        &self,
+        rows: Vec<review_comment::Model>,
+        scope: &ReviewCommentScope,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<proto::ReviewComment>> {
+        let mut comments = rows
+            .into_iter()
+            .filter_map(|row| {
+                let worktree_id = *scope.worktree_ids_by_abs_path.get(&row.worktree_abs_path)?;
+                Some(proto::ReviewComment {
+                    id: row.id.to_proto(),
+                    author_id: row.author_id.to_proto(),
+                    worktree_id: worktree_id as u64,
+                    path: row.path,
+                    start_row: row.start_row as u32,
+                    start_column: row.start_column as u32,
+                    end_row: row.end_row as u32,
+                    end_column: row.end_column as u32,
+                    anchor_text: row.anchor_text,
+                    body: row.body,
+                    mentions: Vec::new(),
+                    timestamp: row.created_at.assume_utc().unix_timestamp() as u64,
+                    reply_to_comment_id: row.reply_to_comment_id.map(|id| id.to_proto()),
+                    resolved: row.resolved,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut mentions = review_comment_mention::Entity::find()
+            .filter(
+                review_comment_mention::Column::CommentId
+                    .is_in(comments.iter().map(|comment| comment.id)),
+            )
+            .order_by_asc(review_comment_mention::Column::CommentId)
+            .order_by_asc(review_comment_mention::Column::StartOffset)
+            .stream(tx)
+            .await?;
+
+        let mut comment_ix = 0;
+        while let Some(mention) = mentions.next().await {
+            let mention = mention?;
+            let comment_id = mention.comment_id.to_proto();
+            while let Some(comment) = comments.get_mut(comment_ix) {
+                if comment.id < comment_id {
+                    comment_ix += 1;
+                } else {
+                    if comment.id == comment_id {
+                        comment.mentions.push(proto::ChatMention {
+                            range: Some(proto::Range {
+                                start: mention.start_offset as u64,
+                                end: mention.end_offset as u64,
+                            }),
+                            user_id: mention.user_id.to_proto(),
+                        });
+                    }
+                    break;
+                }
+            }
+        }
+
+        Ok(comments)
+    }
+
+    /// Returns the connections of everyone in the project, including the host.
+    async fn review_comment_connection_ids(
+        &self,
+        project: &project::Model,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<ConnectionId>> {
+        let mut connection_ids = self.project_guest_connection_ids(project.id, tx).await?;
+        connection_ids.extend(project.host_connection().ok());
+        Ok(connection_ids)
+    }
+
+    /// Returns the users in the project or in the call it's shared in.
+    async fn review_comment_participant_ids(
+        &self,
+        project: &project::Model,
+        tx: &DatabaseTransaction,
+    ) -> Result<HashSet<UserId>> {
+        let mut user_ids = project_collaborator::Entity::find()
+            .filter(project_collaborator::Column::ProjectId.eq(project.id))
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|collaborator| collaborator.user_id)
+            .collect::<HashSet<_>>();
+        user_ids.extend(project.host_user_id);
+        if let Some(room_id) = project.room_id {
+            user_ids.extend(
+                room_participant::Entity::find()
+                    .filter(
+                        room_participant::Column::RoomId
+                            .eq(room_id)
+                            .and(room_participant::Column::AnsweringConnectionId.is_not_null()),
+                    )
+                    .all(tx)
+                    .await?
+                    .into_iter()
+                    .map(|participant| participant.user_id),
+            );
+        }
+        Ok(user_ids)
+    }
+}
+
+/// Review comments belong to a worktree's path on its host rather than to a
+/// project, so that they outlive it. A project shows the comments on its
+/// worktrees.
+struct ReviewCommentScope {
+    project: project::Model,
+    host_user_id: Option<UserId>,
+    remote_project_id: Option<RemoteProjectId>,
+    worktree_ids_by_abs_path: HashMap<String, i64>,
+}
+
+impl ReviewCommentScope {
+    fn condition(&self) -> Condition {
+        Condition::all()
+            .add(match self.remote_project_id {
+                Some(remote_project_id) => {
+                    review_comment::Column::RemoteProjectId.eq(remote_project_id)
+                }
+                None => review_comment::Column::RemoteProjectId.is_null(),
+            })
+            .add(match self.host_user_id {
+                Some(host_user_id) => review_comment::Column::HostUserId.eq(host_user_id),
+                None => review_comment::Column::HostUserId.is_null(),
+            })
+            .add(
+                review_comment::Column::WorktreeAbsPath
+                    .is_in(self.worktree_ids_by_abs_path.keys().cloned()),
+            )
+    }
+
+    fn contains(&self, comment: &review_comment::Model) -> bool {
+        comment.host_user_id == self.host_user_id
+            && comment.remote_project_id == self.remote_project_id
+            && self
+                .worktree_ids_by_abs_path
+                .contains_key(&comment.worktree_abs_path)
+    }
+
+    fn worktree_abs_path(&self, worktree_id: i64) -> Option<&str> {
+        self.worktree_ids_by_abs_path
+            .iter()
+            .find(|(_, id)| **id == worktree_id)
+            .map(|(abs_path, _)| abs_path.as_str())
+    }
+}
@@ -1082,6 +1082,88 @@ impl Database {
         .await
     }
 
+    /// Starts or stops presenting in the given room. While a participant is
+    /// presenting, everyone else in the room is asked to follow them, so only
+    /// one participant can present at a time: the others can start presenting
+    /// once the presenter stops.
+    pub async fn set_room_presenter(
+        &self,
+        room_id: RoomId,
+        connection: ConnectionId,
+        presenting: bool,
+    ) -> Result<TransactionGuard<proto::Room>> {
+        self.room_transaction(room_id, |tx| async move {
+            let participant = self
+                .answering_room_participant(room_id, connection, &tx)
+                .await?
+                .ok_or_else(|| anyhow!("not a room participant"))?;
+            if presenting && participant.role == Some(ChannelRole::Guest) {
+                Err(anyhow!("guests cannot present"))?;
+            }
+
+            let room = room::Entity::find_by_id(room_id)
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("could not find room"))?;
+
+            let presenter = if presenting {
+                // A presenter who left the room no longer counts.
+                if let Some(presenter) = room
+                    .presenter_connection()
+                    .filter(|presenter| *presenter != connection)
+                {
+                    if self
+                        .answering_room_participant(room_id, presenter, &tx)
+                        .await?
+                        .is_some()
+                    {
+                        Err(anyhow!("someone else is presenting in this room"))?;
+                    }
+                }
+                Some(connection)
+            } else if room.presenter_connection() == Some(connection) {
+                None
+            } else {
+                Err(anyhow!("not presenting in this room"))?
+            };
+
+            room::Entity::update(room::ActiveModel {
+                id: ActiveValue::unchanged(room_id),
+                presenter_connection_id: ActiveValue::set(presenter.map(|c| c.id as i32)),
+                presenter_connection_server_id: ActiveValue::set(
+                    presenter.map(|c| ServerId(c.owner_id as i32)),
+                ),
+                ..Default::default()
+            })
+            .exec(&*tx)
+            .await?;
+
+            self.get_room(room_id, &tx).await
+        })
+        .await
+    }
+
+    /// The participant of the room who answered the call on the given connection.
+    async fn answering_room_participant(
+        &self,
+        room_id: RoomId,
+        connection: ConnectionId,
+        tx: &DatabaseTransaction,
+    ) -> Result<Option<room_participant::Model>> {
+        Ok(room_participant::Entity::find()
+            .filter(
+                Condition::all()
+                    .add(room_participant::Column::RoomId.eq(room_id))
+                    .add(room_participant::Column::AnsweringConnectionId.eq(connection.id as i32))
+                    .add(
+                        room_participant::Column::AnsweringConnectionServerId
+                            .eq(connection.owner_id as i32),
+                    ),
+            )
+            .one(tx)
+            .await?)
+    }
+
     async fn check_user_has_signed_cla(
         &self,
         user_id: UserId,
@@ -1187,6 +1269,69 @@ impl Database {
         })
     }
 
+    /// Returns every room that has connected participants, along with the projects shared in it.
+    /// There are no access checks here, so this should only be used by the admin API.
+    pub async fn get_active_rooms(&self) -> Result<Vec<ActiveRoom>> {
+        self.transaction(|tx| async move {
+            let participants = room_participant::Entity::find()
+                .filter(room_participant::Column::AnsweringConnectionId.is_not_null())
+                .order_by_asc(room_participant::Column::Id)
+                .all(&*tx)
+                .await?;
+            let room_ids = participants
+                .iter()
+                .map(|participant| participant.room_id)
+                .collect::<HashSet<_>>();
+
+            let mut rooms = room::Entity::find()
+                .filter(room::Column::Id.is_in(room_ids))
+                .all(&*tx)
+                .await?
+                .into_iter()
+                .map(|room| {
+                    (
+                        room.id,
+                        ActiveRoom {
+                            id: room.id,
+                            channel_id: room.channel_id,
+                            participants: Vec::new(),
+                            projects: Vec::new(),
+                        },
+                    )
+                })
+                .collect::<BTreeMap<_, _>>();
+
+            for participant in participants {
+                if let Some(room) = rooms.get_mut(&participant.room_id) {
+                    room.participants.push(ActiveRoomParticipant {
+                        user_id: participant.user_id,
+                        connection_id: participant.answering_connection(),
+                        role: participant.role,
+                    });
+                }
+            }
+
+            let projects = project::Entity::find()
+                .filter(project::Column::RoomId.is_in(rooms.keys().copied()))
+                .order_by_asc(project::Column::Id)
+                .all(&*tx)
+                .await?;
+            for project in projects {
+                let host_connection_id = project.host_connection().ok();
+                if let Some(room) = project.room_id.and_then(|room_id| rooms.get_mut(&room_id)) {
+                    room.projects.push(ActiveRoomProject {
+                        id: project.id,
+                        host_user_id: project.host_user_id,
+                        host_connection_id,
+                    });
+                }
+            }
+
+            Ok(rooms.into_values().collect())
+        })
+        .await
+    }
+
     pub async fn get_room(&self, room_id: RoomId, tx: &DatabaseTransaction) -> Result<proto::Room> {
         let (_, room) = self.get_channel_room(room_id, tx).await?;
         Ok(room)
@@ -1355,6 +1500,11 @@ impl Database {
             None
         };
 
+        let presenter_id = db_room
+            .presenter_connection()
+            .filter(|connection| participants.contains_key(connection))
+            .map(Into::into);
+
         Ok((
             channel,
             proto::Room {
@@ -1363,6 +1513,7 @@ impl Database {
                 participants: participants.into_values().collect(),
                 pending_participants,
                 followers,
+                presenter_id,
             },
         ))
     }
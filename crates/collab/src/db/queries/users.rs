@@ -150,6 +150,48 @@ impl Database {
         }
     }
 
+    /// Creates a user who signs in with a password instead of a GitHub account,
+    /// or updates the password and admin flag of an existing user with that login.
+    pub async fn create_or_update_password_user(
+        &self,
+        login: &str,
+        admin: bool,
+        password_hash: &str,
+    ) -> Result<User> {
+        self.transaction(|tx| async move {
+            Ok(user::Entity::insert(user::ActiveModel {
+                github_login: ActiveValue::set(login.into()),
+                admin: ActiveValue::set(admin),
+                invite_count: ActiveValue::set(0),
+                metrics_id: ActiveValue::set(Uuid::new_v4()),
+                password_hash: ActiveValue::set(Some(password_hash.into())),
+                ..Default::default()
+            })
+            .on_conflict(
+                OnConflict::column(user::Column::GithubLogin)
+                    .update_columns([user::Column::Admin, user::Column::PasswordHash])
+                    .to_owned(),
+            )
+            .exec_with_returning(&*tx)
+            .await?)
+        })
+        .await
+    }
+
+    /// Bans or unbans a user. Banned users are refused when they try to connect.
+    pub async fn set_user_banned(&self, id: UserId, banned: bool) -> Result<User> {
+        self.transaction(|tx| async move {
+            let user = user::Entity::find_by_id(id)
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such user {}", id))?;
+            let mut user = user.into_active_model();
+            user.banned = ActiveValue::set(banned);
+            Ok(user.update(&*tx).await?)
+        })
+        .await
+    }
+
     /// get_all_users returns the next page of users. To get more call again with
     /// the same limit and the page incremented by 1.
     pub async fn get_all_users(&self, page: u32, limit: u32) -> Result<Vec<User>> {
@@ -8,6 +8,7 @@ pub mod channel_chat_participant;
 pub mod channel_member;
 pub mod channel_message;
 pub mod channel_message_mention;
+pub mod channel_message_reaction;
 pub mod contact;
 pub mod contributor;
 pub mod dev_server;
@@ -26,6 +27,8 @@ pub mod project;
 pub mod project_collaborator;
 pub mod rate_buckets;
 pub mod remote_project;
+pub mod review_comment;
+pub mod review_comment_mention;
 pub mod room;
 pub mod room_participant;
 pub mod server;
@@ -1,4 +1,4 @@
-use crate::db::BufferId;
+use crate::db::{BufferId, UserId};
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
@@ -13,6 +13,7 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub replica_id: i32,
     pub value: Vec<u8>,
+    pub user_id: Option<UserId>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
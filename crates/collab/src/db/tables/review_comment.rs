@@ -0,0 +1,47 @@
+use crate::db::{RemoteProjectId, ReviewCommentId, UserId};
+use sea_orm::entity::prelude::*;
+use time::PrimitiveDateTime;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "review_comments")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: ReviewCommentId,
+    /// The user whose shared projects include the comment's worktree, unless
+    /// it's in a remote project.
+    pub host_user_id: Option<UserId>,
+    pub remote_project_id: Option<RemoteProjectId>,
+    /// The path of the worktree on the host, which unlike its id stays the
+    /// same when the project is shared again.
+    pub worktree_abs_path: String,
+    pub path: String,
+    pub start_row: i32,
+    pub start_column: i32,
+    pub end_row: i32,
+    pub end_column: i32,
+    /// The text the thread was started on, for finding it again after edits.
+    pub anchor_text: String,
+    pub author_id: UserId,
+    pub body: String,
+    pub created_at: PrimitiveDateTime,
+    pub reply_to_comment_id: Option<ReviewCommentId>,
+    pub resolved: bool,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::AuthorId",
+        to = "super::user::Column::Id"
+    )]
+    Author,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Author.def()
+    }
+}
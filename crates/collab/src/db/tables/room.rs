@@ -1,4 +1,5 @@
-use crate::db::{ChannelId, RoomId};
+use crate::db::{ChannelId, RoomId, ServerId};
+use rpc::ConnectionId;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Default, Debug, PartialEq, Eq, DeriveEntityModel)]
@@ -8,6 +9,17 @@ pub struct Model {
     pub id: RoomId,
     pub live_kit_room: String,
     pub channel_id: Option<ChannelId>,
+    pub presenter_connection_id: Option<i32>,
+    pub presenter_connection_server_id: Option<ServerId>,
+}
+
+impl Model {
+    pub fn presenter_connection(&self) -> Option<ConnectionId> {
+        Some(ConnectionId {
+            owner_id: self.presenter_connection_server_id?.0 as u32,
+            id: self.presenter_connection_id? as u32,
+        })
+    }
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
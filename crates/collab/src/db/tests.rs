@@ -1,3 +1,4 @@
+mod admin_tests;
 mod buffer_tests;
 mod channel_tests;
 mod contributor_tests;
@@ -6,6 +7,7 @@ mod embedding_tests;
 mod extension_tests;
 mod feature_flag_tests;
 mod message_tests;
+mod review_comment_tests;
 
 use super::*;
 use gpui::BackgroundExecutor;
@@ -37,17 +39,28 @@ impl TestDb {
             let mut db = Database::new(options, Executor::Deterministic(background))
                 .await
                 .unwrap();
-            let sql = include_str!(concat!(
-                env!("CARGO_MANIFEST_DIR"),
-                "/migrations.sqlite/20221109000000_test_schema.sql"
-            ));
-            db.pool
-                .execute(sea_orm::Statement::from_string(
-                    db.pool.get_database_backend(),
-                    sql,
-                ))
-                .await
-                .unwrap();
+            // Each connection to `sqlite::memory:` opens a separate database, so the
+            // migrations are applied through the pool rather than with `Database::migrate`.
+            let migrations_path = concat!(env!("CARGO_MANIFEST_DIR"), "/migrations.sqlite");
+            let mut migration_paths = std::fs::read_dir(migrations_path)
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .filter(|path| {
+                    path.extension()
+                        .map_or(false, |extension| extension == "sql")
+                })
+                .collect::<Vec<_>>();
+            migration_paths.sort();
+            for migration_path in migration_paths {
+                let sql = std::fs::read_to_string(&migration_path).unwrap();
+                db.pool
+                    .execute(sea_orm::Statement::from_string(
+                        db.pool.get_database_backend(),
+                        sql,
+                    ))
+                    .await
+                    .unwrap();
+            }
             db.initialize_notification_kinds().await.unwrap();
             db
         });
@@ -0,0 +1,171 @@
+use crate::{
+    db::{
+        tests::{new_test_connection, new_test_user},
+        ChannelRole, Database, RoomId,
+    },
+    test_both_dbs,
+};
+use std::sync::Arc;
+
+test_both_dbs!(
+    test_set_user_banned,
+    test_set_user_banned_postgres,
+    test_set_user_banned_sqlite
+);
+
+async fn test_set_user_banned(db: &Arc<Database>) {
+    let user_id = new_test_user(db, "user1@example.com").await;
+    assert!(!db.get_user_by_id(user_id).await.unwrap().unwrap().banned);
+
+    let user = db.set_user_banned(user_id, true).await.unwrap();
+    assert!(user.banned);
+    assert!(db.get_user_by_id(user_id).await.unwrap().unwrap().banned);
+
+    let user = db.set_user_banned(user_id, false).await.unwrap();
+    assert!(!user.banned);
+    assert!(!db.get_user_by_id(user_id).await.unwrap().unwrap().banned);
+}
+
+test_both_dbs!(
+    test_admin_channel_membership,
+    test_admin_channel_membership_postgres,
+    test_admin_channel_membership_sqlite
+);
+
+async fn test_admin_channel_membership(db: &Arc<Database>) {
+    let a_id = new_test_user(db, "user1@example.com").await;
+    let b_id = new_test_user(db, "user2@example.com").await;
+
+    let zed_id = db.create_root_channel("zed", a_id).await.unwrap();
+    let crdb_id = db.create_sub_channel("crdb", zed_id, a_id).await.unwrap();
+    let rust_id = db.create_root_channel("rust", b_id).await.unwrap();
+
+    let channels = db.get_all_channels_for_admin().await.unwrap();
+    assert_eq!(
+        channels
+            .iter()
+            .map(|channel| channel.id)
+            .collect::<Vec<_>>(),
+        &[zed_id, crdb_id, rust_id]
+    );
+
+    // Setting a role on a sub-channel adds the user to its root.
+    let update = db
+        .set_channel_member_role_for_admin(crdb_id, b_id, ChannelRole::Member)
+        .await
+        .unwrap();
+    assert_eq!(update.channel_id, zed_id);
+    assert!(update
+        .new_channels
+        .channels
+        .iter()
+        .any(|channel| channel.id == crdb_id));
+
+    let members = db.get_channel_members_for_admin(crdb_id).await.unwrap();
+    let mut members = members
+        .iter()
+        .map(|member| (member.user_id, member.role, member.accepted))
+        .collect::<Vec<_>>();
+    members.sort_by_key(|(user_id, _, _)| *user_id);
+    assert_eq!(
+        members,
+        &[
+            (a_id, ChannelRole::Admin, true),
+            (b_id, ChannelRole::Member, true)
+        ]
+    );
+
+    // Setting a role again updates the existing membership.
+    db.set_channel_member_role_for_admin(zed_id, b_id, ChannelRole::Admin)
+        .await
+        .unwrap();
+    let members = db.get_channel_members_for_admin(zed_id).await.unwrap();
+    assert_eq!(members.len(), 2);
+    assert!(members
+        .iter()
+        .any(|member| member.user_id == b_id && member.role == ChannelRole::Admin));
+
+    let update = db
+        .remove_channel_member_for_admin(zed_id, b_id)
+        .await
+        .unwrap();
+    assert_eq!(update.removed_channels.len(), 2);
+    assert!(update.removed_channels.contains(&zed_id));
+    assert!(update.removed_channels.contains(&crdb_id));
+    let members = db.get_channel_members_for_admin(zed_id).await.unwrap();
+    assert_eq!(
+        members
+            .iter()
+            .map(|member| member.user_id)
+            .collect::<Vec<_>>(),
+        &[a_id]
+    );
+
+    db.remove_channel_member_for_admin(zed_id, b_id)
+        .await
+        .unwrap_err();
+}
+
+test_both_dbs!(
+    test_get_active_rooms,
+    test_get_active_rooms_postgres,
+    test_get_active_rooms_sqlite
+);
+
+async fn test_get_active_rooms(db: &Arc<Database>) {
+    let server = db.create_server("test").await.unwrap();
+    let a_id = new_test_user(db, "user1@example.com").await;
+    let b_id = new_test_user(db, "user2@example.com").await;
+    let a_connection = new_test_connection(server);
+    let b_connection = new_test_connection(server);
+
+    assert!(db.get_active_rooms().await.unwrap().is_empty());
+
+    let zed_id = db.create_root_channel("zed", a_id).await.unwrap();
+    let room_id = RoomId::from_proto(
+        db.join_channel(zed_id, a_id, a_connection)
+            .await
+            .unwrap()
+            .0
+            .room
+            .id,
+    );
+    db.share_project(room_id, a_connection, &[], None)
+        .await
+        .unwrap();
+
+    let other_room_id =
+        RoomId::from_proto(db.create_room(b_id, b_connection, "").await.unwrap().id);
+
+    let rooms = db.get_active_rooms().await.unwrap();
+    assert_eq!(rooms.len(), 2);
+
+    let channel_room = rooms.iter().find(|room| room.id == room_id).unwrap();
+    assert_eq!(channel_room.channel_id, Some(zed_id));
+    assert_eq!(channel_room.participants.len(), 1);
+    assert_eq!(channel_room.participants[0].user_id, a_id);
+    assert_eq!(
+        channel_room.participants[0].connection_id,
+        Some(a_connection)
+    );
+    assert_eq!(channel_room.participants[0].role, Some(ChannelRole::Admin));
+    assert_eq!(channel_room.projects.len(), 1);
+    assert_eq!(channel_room.projects[0].host_user_id, Some(a_id));
+    assert_eq!(
+        channel_room.projects[0].host_connection_id,
+        Some(a_connection)
+    );
+
+    let other_room = rooms.iter().find(|room| room.id == other_room_id).unwrap();
+    assert_eq!(other_room.channel_id, None);
+    assert_eq!(other_room.participants.len(), 1);
+    assert_eq!(other_room.participants[0].user_id, b_id);
+    assert!(other_room.projects.is_empty());
+
+    db.leave_room(b_connection).await.unwrap();
+    let rooms = db.get_active_rooms().await.unwrap();
+    assert_eq!(
+        rooms.iter().map(|room| room.id).collect::<Vec<_>>(),
+        &[room_id]
+    );
+}
@@ -63,7 +63,7 @@ async fn test_channel_buffers(db: &Arc<Database>) {
 
     let connection_id_a = ConnectionId { owner_id, id: 1 };
     let _ = db
-        .join_channel_buffer(zed_id, a_id, connection_id_a)
+        .join_channel_buffer(zed_id, a_id, connection_id_a, None)
         .await
         .unwrap();
 
@@ -87,7 +87,7 @@ async fn test_channel_buffers(db: &Arc<Database>) {
 
     let connection_id_b = ConnectionId { owner_id, id: 2 };
     let buffer_response_b = db
-        .join_channel_buffer(zed_id, b_id, connection_id_b)
+        .join_channel_buffer(zed_id, b_id, connection_id_b, None)
         .await
         .unwrap();
 
@@ -111,7 +111,7 @@ async fn test_channel_buffers(db: &Arc<Database>) {
 
     // Ensure that C fails to open the buffer
     assert!(db
-        .join_channel_buffer(zed_id, c_id, ConnectionId { owner_id, id: 3 })
+        .join_channel_buffer(zed_id, c_id, ConnectionId { owner_id, id: 3 }, None)
         .await
         .is_err());
 
@@ -145,7 +145,7 @@ async fn test_channel_buffers(db: &Arc<Database>) {
 
     let cargo_id = db.create_root_channel("cargo", a_id).await.unwrap();
     let _ = db
-        .join_channel_buffer(cargo_id, a_id, connection_id_a)
+        .join_channel_buffer(cargo_id, a_id, connection_id_a, None)
         .await
         .unwrap();
 
@@ -159,13 +159,122 @@ async fn test_channel_buffers(db: &Arc<Database>) {
     // When everyone has left the channel, the operations are collapsed into
     // a new base text.
     let buffer_response_b = db
-        .join_channel_buffer(zed_id, b_id, connection_id_b)
+        .join_channel_buffer(zed_id, b_id, connection_id_b, None)
         .await
         .unwrap();
     assert_eq!(buffer_response_b.base_text, "hello, cruel world");
     assert_eq!(buffer_response_b.operations, &[]);
 }
 
+test_both_dbs!(
+    test_channel_buffer_history,
+    test_channel_buffer_history_postgres,
+    test_channel_buffer_history_sqlite
+);
+
+async fn test_channel_buffer_history(db: &Arc<Database>) {
+    let a_id = new_test_user(db, "user_a@example.com").await;
+    let b_id = new_test_user(db, "user_b@example.com").await;
+    let c_id = new_test_user(db, "user_c@example.com").await;
+    let owner_id = db.create_server("production").await.unwrap().0 as u32;
+    let connection_id_a = ConnectionId { owner_id, id: 1 };
+    let connection_id_b = ConnectionId { owner_id, id: 2 };
+
+    let channel_id = db.create_root_channel("zed", a_id).await.unwrap();
+    db.invite_channel_member(channel_id, b_id, a_id, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel_id, b_id, true)
+        .await
+        .unwrap();
+
+    // User A writes the first version, which is saved when they leave.
+    let response = db
+        .join_channel_buffer(channel_id, a_id, connection_id_a, None)
+        .await
+        .unwrap();
+    let mut buffer = Buffer::new(
+        response.replica_id as u16,
+        text::BufferId::new(1).unwrap(),
+        response.base_text,
+    );
+    let operation = buffer.edit([(0..0, "hello")]);
+    db.update_channel_buffer(
+        channel_id,
+        a_id,
+        &[proto::serialize_operation(&language::Operation::Buffer(
+            operation,
+        ))],
+    )
+    .await
+    .unwrap();
+    db.leave_channel_buffer(channel_id, connection_id_a)
+        .await
+        .unwrap();
+
+    // User B edits the current version.
+    let response = db
+        .join_channel_buffer(channel_id, b_id, connection_id_b, None)
+        .await
+        .unwrap();
+    let mut buffer = Buffer::new(
+        response.replica_id as u16,
+        text::BufferId::new(1).unwrap(),
+        response.base_text,
+    );
+    let operation = buffer.edit([(5..5, " world")]);
+    db.update_channel_buffer(
+        channel_id,
+        b_id,
+        &[proto::serialize_operation(&language::Operation::Buffer(
+            operation,
+        ))],
+    )
+    .await
+    .unwrap();
+
+    let history = db
+        .get_channel_buffer_history(channel_id, b_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        history
+            .iter()
+            .map(|version| (version.epoch, version.author_ids.clone()))
+            .collect::<Vec<_>>(),
+        &[(1, vec![b_id.to_proto()]), (0, vec![a_id.to_proto()])]
+    );
+    assert!(history[0].saved_at.is_none());
+    assert!(history[1].saved_at.is_some());
+
+    assert_eq!(
+        db.get_channel_buffer_version(channel_id, 0, b_id)
+            .await
+            .unwrap(),
+        "hello"
+    );
+    assert_eq!(
+        db.get_channel_buffer_version(channel_id, 1, b_id)
+            .await
+            .unwrap(),
+        "hello world"
+    );
+    assert!(db
+        .get_channel_buffer_version(channel_id, 2, b_id)
+        .await
+        .is_err());
+
+    // Users outside of the channel can't see its history.
+    assert!(db
+        .get_channel_buffer_history(channel_id, c_id)
+        .await
+        .is_err());
+    assert!(db
+        .get_channel_buffer_version(channel_id, 0, c_id)
+        .await
+        .is_err());
+}
+
 test_both_dbs!(
     test_channel_buffers_last_operations,
     test_channel_buffers_last_operations_postgres,
@@ -218,7 +327,7 @@ async fn test_channel_buffers_last_operations(db: &Database) {
             .await
             .unwrap();
 
-        db.join_channel_buffer(channel, user_id, connection_id)
+        db.join_channel_buffer(channel, user_id, connection_id, None)
             .await
             .unwrap();
 
@@ -263,7 +372,7 @@ async fn test_channel_buffers_last_operations(db: &Database) {
     db.leave_channel_buffer(buffers[1].channel_id, connection_id)
         .await
         .unwrap();
-    db.join_channel_buffer(buffers[1].channel_id, user_id, connection_id)
+    db.join_channel_buffer(buffers[1].channel_id, user_id, connection_id, None)
         .await
         .unwrap();
     text_buffers[1] = Buffer::new(1, text::BufferId::new(1).unwrap(), "def".to_string());
@@ -295,6 +404,7 @@ async fn test_channel_buffers_last_operations(db: &Database) {
                 channel_id: buffers[0].channel_id.to_proto(),
                 epoch: 0,
                 version: serialize_version(&text_buffers[0].version()),
+                replica_id: None,
             },
             rpc::proto::ChannelBufferVersion {
                 channel_id: buffers[1].channel_id.to_proto(),
@@ -303,11 +413,13 @@ async fn test_channel_buffers_last_operations(db: &Database) {
                     .into_iter()
                     .filter(|vector| vector.replica_id == text_buffers[1].replica_id() as u32)
                     .collect::<Vec<_>>(),
+                replica_id: None,
             },
             rpc::proto::ChannelBufferVersion {
                 channel_id: buffers[2].channel_id.to_proto(),
                 epoch: 0,
                 version: serialize_version(&text_buffers[2].version()),
+                replica_id: None,
             },
         ]
     );
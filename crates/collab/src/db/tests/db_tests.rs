@@ -438,6 +438,50 @@ async fn test_add_contacts(db: &Arc<Database>) {
     );
 }
 
+test_both_dbs!(
+    test_create_or_update_password_user,
+    test_create_or_update_password_user_postgres,
+    test_create_or_update_password_user_sqlite
+);
+
+async fn test_create_or_update_password_user(db: &Arc<Database>) {
+    let first_hash = crate::auth::hash_password("first-password").unwrap();
+    let user = db
+        .create_or_update_password_user("admin", true, &first_hash)
+        .await
+        .unwrap();
+    assert!(user.admin);
+    assert_eq!(user.github_user_id, None);
+    assert!(crate::auth::verify_password(
+        "first-password",
+        user.password_hash.as_deref().unwrap()
+    ));
+
+    let second_hash = crate::auth::hash_password("second-password").unwrap();
+    let updated_user = db
+        .create_or_update_password_user("admin", false, &second_hash)
+        .await
+        .unwrap();
+    assert_eq!(updated_user.id, user.id);
+    assert!(!updated_user.admin);
+
+    let password_hash = db
+        .get_user_by_github_login("admin")
+        .await
+        .unwrap()
+        .unwrap()
+        .password_hash
+        .unwrap();
+    assert!(crate::auth::verify_password(
+        "second-password",
+        &password_hash
+    ));
+    assert!(!crate::auth::verify_password(
+        "first-password",
+        &password_hash
+    ));
+}
+
 test_both_dbs!(
     test_metrics_id,
     test_metrics_id_postgres,
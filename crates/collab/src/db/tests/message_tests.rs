@@ -419,3 +419,123 @@ async fn test_channel_message_mentions(db: &Arc<Database>) {
         ]
     );
 }
+
+test_both_dbs!(
+    test_channel_message_reactions_threads_and_search,
+    test_channel_message_reactions_threads_and_search_postgres,
+    test_channel_message_reactions_threads_and_search_sqlite
+);
+
+async fn test_channel_message_reactions_threads_and_search(db: &Arc<Database>) {
+    let user_a = new_test_user(db, "user_a@example.com").await;
+    let user_b = new_test_user(db, "user_b@example.com").await;
+    let channel = db.create_root_channel("channel", user_a).await.unwrap();
+    db.invite_channel_member(channel, user_b, user_a, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel, user_b, true)
+        .await
+        .unwrap();
+
+    let owner_id = db.create_server("test").await.unwrap().0 as u32;
+    db.join_channel_chat(channel, rpc::ConnectionId { owner_id, id: 0 }, user_a)
+        .await
+        .unwrap();
+    db.join_channel_chat(channel, rpc::ConnectionId { owner_id, id: 1 }, user_b)
+        .await
+        .unwrap();
+
+    let send = |user_id, body: &'static str, nonce, reply_to_message_id| {
+        let db = db.clone();
+        async move {
+            db.create_channel_message(
+                channel,
+                user_id,
+                body,
+                &[],
+                OffsetDateTime::now_utc(),
+                nonce,
+                reply_to_message_id,
+            )
+            .await
+            .unwrap()
+            .message_id
+        }
+    };
+    let root = send(user_a, "the deploy failed", 1, None).await;
+    let unrelated = send(user_b, "lunch?", 2, None).await;
+    let reply = send(user_b, "looking", 3, Some(root)).await;
+    let nested_reply = send(user_a, "deploy fixed", 4, Some(reply)).await;
+
+    // Reactions are grouped by emoji, and toggling a reaction again removes it.
+    db.toggle_channel_message_reaction(channel, root, user_a, "👍")
+        .await
+        .unwrap();
+    db.toggle_channel_message_reaction(channel, root, user_b, "🎉")
+        .await
+        .unwrap();
+    db.toggle_channel_message_reaction(channel, root, user_b, "👍")
+        .await
+        .unwrap();
+    let (connection_ids, reactions) = db
+        .toggle_channel_message_reaction(channel, root, user_a, "🎉")
+        .await
+        .unwrap();
+    assert_eq!(connection_ids.len(), 2);
+    assert_eq!(
+        reactions
+            .iter()
+            .map(|reaction| (reaction.emoji.as_str(), reaction.user_ids.clone()))
+            .collect::<Vec<_>>(),
+        &[
+            ("👍", vec![user_a.to_proto(), user_b.to_proto()]),
+            ("🎉", vec![user_b.to_proto(), user_a.to_proto()]),
+        ]
+    );
+    let (_, reactions) = db
+        .toggle_channel_message_reaction(channel, root, user_b, "👍")
+        .await
+        .unwrap();
+    assert_eq!(reactions[0].user_ids, &[user_a.to_proto()]);
+
+    let messages = db
+        .get_channel_messages(channel, user_b, 10, None)
+        .await
+        .unwrap();
+    assert_eq!(messages[0].reactions, reactions);
+    assert!(messages[1].reactions.is_empty());
+
+    // A thread contains the whole reply chain, whichever message it's opened from.
+    for message_id in [root, nested_reply] {
+        let thread = db
+            .get_channel_message_thread(channel, user_b, message_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|message| MessageId::from_proto(message.id))
+            .collect::<Vec<_>>();
+        assert_eq!(thread, &[root, reply, nested_reply]);
+    }
+
+    let results = db
+        .search_channel_messages(channel, user_b, "deploy", 10, None)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|message| MessageId::from_proto(message.id))
+        .collect::<Vec<_>>();
+    assert_eq!(results, &[nested_reply, root]);
+    let results = db
+        .search_channel_messages(channel, user_b, "deploy", 10, Some(nested_reply))
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|message| MessageId::from_proto(message.id))
+        .collect::<Vec<_>>();
+    assert_eq!(results, &[root]);
+    assert!(db
+        .search_channel_messages(channel, user_b, "lunch", 10, Some(unrelated))
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,219 @@
+use super::{new_test_connection, new_test_user};
+use crate::{
+    db::{Database, ReviewCommentId, RoomId},
+    test_both_dbs,
+};
+use rpc::{proto, Notification};
+use std::sync::Arc;
+use time::OffsetDateTime;
+
+test_both_dbs!(
+    test_review_comment_threads,
+    test_review_comment_threads_postgres,
+    test_review_comment_threads_sqlite
+);
+
+async fn test_review_comment_threads(db: &Arc<Database>) {
+    let host = new_test_user(db, "host@example.com").await;
+    let guest = new_test_user(db, "guest@example.com").await;
+    let outsider = new_test_user(db, "outsider@example.com").await;
+    let server = db.create_server("test").await.unwrap();
+    let host_connection = new_test_connection(server);
+    let guest_connection = new_test_connection(server);
+
+    let room_id = RoomId::from_proto(db.create_room(host, host_connection, "").await.unwrap().id);
+    db.call(room_id, host, host_connection, guest, None)
+        .await
+        .unwrap();
+    db.join_room(room_id, guest, guest_connection)
+        .await
+        .unwrap();
+    let worktrees = [proto::WorktreeMetadata {
+        id: 1,
+        root_name: "project".into(),
+        visible: true,
+        abs_path: "/project".into(),
+    }];
+    let project_id = db
+        .share_project(room_id, host_connection, &worktrees, None)
+        .await
+        .unwrap()
+        .0;
+
+    // Only people in the project or its call can be mentioned, once per offset.
+    let body = "@guest @outsider does this leak?";
+    let thread = db
+        .create_review_comment(
+            project_id,
+            host_connection,
+            host,
+            &proto::CreateReviewComment {
+                project_id: project_id.to_proto(),
+                worktree_id: 1,
+                path: "src/main.rs".into(),
+                start_row: 3,
+                start_column: 4,
+                end_row: 5,
+                end_column: 0,
+                anchor_text: "fn main() {\n".into(),
+                body: body.into(),
+                mentions: vec![
+                    proto::ChatMention {
+                        range: Some(proto::Range { start: 0, end: 6 }),
+                        user_id: guest.to_proto(),
+                    },
+                    proto::ChatMention {
+                        range: Some(proto::Range { start: 0, end: 6 }),
+                        user_id: guest.to_proto(),
+                    },
+                    proto::ChatMention {
+                        range: Some(proto::Range { start: 7, end: 16 }),
+                        user_id: outsider.to_proto(),
+                    },
+                ],
+                reply_to_comment_id: None,
+            },
+            body,
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(thread.comment.reply_to_comment_id, None);
+    assert_eq!(thread.comment.mentions.len(), 1);
+    assert!(thread.connection_ids.contains(&host_connection));
+    assert_eq!(
+        thread
+            .notifications
+            .iter()
+            .map(|(user_id, notification)| (*user_id, Notification::from_proto(notification)))
+            .collect::<Vec<_>>(),
+        &[(
+            guest,
+            Some(Notification::ReviewCommentMention {
+                comment_id: thread.comment.id,
+                sender_id: host.to_proto(),
+                project_id: project_id.to_proto(),
+                path: "src/main.rs".into(),
+            })
+        )]
+    );
+
+    // Replies share the location of the thread, and replying to a reply adds
+    // to the same thread.
+    let reply = |reply_to_comment_id: u64, body: &'static str| proto::CreateReviewComment {
+        project_id: project_id.to_proto(),
+        worktree_id: 1,
+        path: "src/lib.rs".into(),
+        body: body.into(),
+        reply_to_comment_id: Some(reply_to_comment_id),
+        ..Default::default()
+    };
+    let first_reply = db
+        .create_review_comment(
+            project_id,
+            guest_connection,
+            guest,
+            &reply(thread.comment.id, "yes"),
+            "yes",
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .unwrap()
+        .into_inner()
+        .comment;
+    let second_reply = db
+        .create_review_comment(
+            project_id,
+            guest_connection,
+            guest,
+            &reply(first_reply.id, "fixed it"),
+            "fixed it",
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .unwrap()
+        .into_inner()
+        .comment;
+    for reply in [&first_reply, &second_reply] {
+        assert_eq!(reply.reply_to_comment_id, Some(thread.comment.id));
+        assert_eq!(reply.path, "src/main.rs");
+        assert_eq!((reply.start_row, reply.end_row), (3, 5));
+        assert_eq!(reply.anchor_text, "fn main() {\n");
+    }
+
+    // Resolving any comment resolves its thread.
+    let (resolved_thread, _) = db
+        .resolve_review_thread(
+            project_id,
+            guest_connection,
+            guest,
+            ReviewCommentId::from_proto(second_reply.id),
+            true,
+        )
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(resolved_thread.id, thread.comment.id);
+    assert!(resolved_thread.resolved);
+
+    let comments = db
+        .get_review_comments(project_id, guest_connection, guest)
+        .await
+        .unwrap();
+    assert_eq!(
+        comments
+            .iter()
+            .map(|comment| (comment.body.as_str(), comment.resolved))
+            .collect::<Vec<_>>(),
+        &[(body, true), ("yes", false), ("fixed it", false)]
+    );
+    assert_eq!(comments[0].mentions, thread.comment.mentions);
+
+    // Threads outlive the project, and reappear when its worktree is shared again.
+    db.unshare_project(project_id, host_connection, Some(host))
+        .await
+        .unwrap();
+    let worktrees = [proto::WorktreeMetadata {
+        id: 2,
+        ..worktrees[0].clone()
+    }];
+    let project_id = db
+        .share_project(room_id, host_connection, &worktrees, None)
+        .await
+        .unwrap()
+        .0;
+    let comments = db
+        .get_review_comments(project_id, guest_connection, guest)
+        .await
+        .unwrap();
+    assert_eq!(
+        comments
+            .iter()
+            .map(|comment| (comment.body.as_str(), comment.worktree_id))
+            .collect::<Vec<_>>(),
+        &[(body, 2), ("yes", 2), ("fixed it", 2)]
+    );
+
+    // Other worktrees don't show them.
+    let other_project_id = db
+        .share_project(
+            room_id,
+            host_connection,
+            &[proto::WorktreeMetadata {
+                id: 1,
+                root_name: "other".into(),
+                visible: true,
+                abs_path: "/other".into(),
+            }],
+            None,
+        )
+        .await
+        .unwrap()
+        .0;
+    assert!(db
+        .get_review_comments(other_project_id, host_connection, host)
+        .await
+        .unwrap()
+        .is_empty());
+}
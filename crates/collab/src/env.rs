@@ -1,19 +1,56 @@
 use anyhow::anyhow;
-use std::fs;
+use std::{fs, path::Path};
+
+/// Defaults for a self-hosted server, applied to any variable that isn't set in
+/// the environment or in the config file.
+const SELF_HOSTED_DEFAULTS: &[(&str, &str)] = &[
+    ("HTTP_PORT", "8080"),
+    ("DATABASE_MAX_CONNECTIONS", "5"),
+    ("INVITE_LINK_PREFIX", ""),
+    ("ZED_ENVIRONMENT", "self-hosted"),
+    ("RUST_LOG", "info"),
+];
 
 pub fn load_dotenv() -> anyhow::Result<()> {
+    load_env_file(Path::new("./crates/collab/.env.toml"))
+}
+
+/// Loads the configuration of a self-hosted server from the given TOML file.
+/// This always enables `SELF_HOSTED`, which turns off every external service.
+pub fn load_self_hosted_config(path: &Path) -> anyhow::Result<()> {
+    load_env_file(path)?;
+
+    std::env::set_var("SELF_HOSTED", "true");
+    for (key, value) in SELF_HOSTED_DEFAULTS {
+        if std::env::var_os(key).is_none() {
+            std::env::set_var(key, value);
+        }
+    }
+
+    Ok(())
+}
+
+fn load_env_file(path: &Path) -> anyhow::Result<()> {
     let env: toml::map::Map<String, toml::Value> = toml::de::from_str(
-        &fs::read_to_string("./crates/collab/.env.toml")
-            .map_err(|_| anyhow!("no .env.toml file found"))?,
+        &fs::read_to_string(path).map_err(|_| anyhow!("no config file found at {:?}", path))?,
     )?;
 
+    // Every value is checked before any is set, so that a bad file doesn't apply partially.
+    let mut vars = Vec::with_capacity(env.len());
     for (key, value) in env {
         let value = match value {
             toml::Value::String(value) => value,
             toml::Value::Integer(value) => value.to_string(),
             toml::Value::Float(value) => value.to_string(),
-            _ => panic!("unsupported TOML value in .env.toml for key {}", key),
+            toml::Value::Boolean(value) => value.to_string(),
+            value => Err(anyhow!(
+                "unsupported {} value for {key} in {path:?}: use a string, number or boolean",
+                value.type_str()
+            ))?,
         };
+        vars.push((key, value));
+    }
+    for (key, value) in vars {
         std::env::set_var(key, value);
     }
 
@@ -114,6 +114,7 @@ pub struct Config {
     pub database_url: String,
     pub migrations_path: Option<PathBuf>,
     pub seed_path: Option<PathBuf>,
+    pub rate_limits_path: Option<PathBuf>,
     pub database_max_connections: u32,
     pub api_token: String,
     pub clickhouse_url: Option<String>,
@@ -138,12 +139,22 @@ pub struct Config {
     pub zed_client_checksum_seed: Option<String>,
     pub slack_panics_webhook: Option<String>,
     pub auto_join_channel_id: Option<ChannelId>,
+    pub self_hosted: Option<bool>,
+    pub blob_store_path: Option<PathBuf>,
+    pub admin_login: Option<String>,
+    pub admin_password: Option<String>,
 }
 
 impl Config {
     pub fn is_development(&self) -> bool {
         self.zed_environment == "development".into()
     }
+
+    /// A self-hosted server runs without any external services: users sign in with
+    /// a password, blobs are stored on the local filesystem and telemetry is disabled.
+    pub fn is_self_hosted(&self) -> bool {
+        self.self_hosted.unwrap_or(false)
+    }
 }
 
 pub struct AppState {
@@ -178,17 +189,28 @@ impl AppState {
             None
         };
 
+        let (blob_store_client, clickhouse_client) = if config.is_self_hosted() {
+            (None, None)
+        } else {
+            (
+                build_blob_store_client(&config).await.log_err(),
+                config
+                    .clickhouse_url
+                    .as_ref()
+                    .and_then(|_| build_clickhouse_client(&config).log_err()),
+            )
+        };
+
+        let rate_limits = RateLimitConfig::load(config.rate_limits_path.as_deref())?;
+
         let db = Arc::new(db);
         let this = Self {
             db: db.clone(),
             live_kit_client,
-            blob_store_client: build_blob_store_client(&config).await.log_err(),
-            rate_limiter: Arc::new(RateLimiter::new(db)),
+            blob_store_client,
+            rate_limiter: Arc::new(RateLimiter::new(db, rate_limits)),
             executor,
-            clickhouse_client: config
-                .clickhouse_url
-                .as_ref()
-                .and_then(|_| build_clickhouse_client(&config).log_err()),
+            clickhouse_client,
             config,
         };
         Ok(Arc::new(this))
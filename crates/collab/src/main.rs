@@ -30,15 +30,19 @@ const REVISION: Option<&'static str> = option_env!("GITHUB_SHA");
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    if let Err(error) = env::load_dotenv() {
-        eprintln!(
-            "error loading .env.toml (this is expected in production): {}",
-            error
-        );
+    let mut args = args().skip(1);
+    let command = args.next();
+
+    if command.as_deref() != Some("self-hosted") {
+        if let Err(error) = env::load_dotenv() {
+            eprintln!(
+                "error loading .env.toml (this is expected in production): {}",
+                error
+            );
+        }
     }
 
-    let mut args = args().skip(1);
-    match args.next().as_deref() {
+    match command.as_deref() {
         Some("version") => {
             println!("collab v{} ({})", VERSION, REVISION.unwrap_or("unknown"));
         }
@@ -67,124 +71,179 @@ async fn main() -> Result<()> {
             }
 
             let config = envy::from_env::<Config>().expect("error loading config");
-            init_tracing(&config);
+            serve(config, is_api, is_collab).await?;
+        }
+        Some("self-hosted") => {
+            let Some(config_path) = args.next() else {
+                Err(anyhow!(
+                    "usage: collab self-hosted <config.toml> [serve | add-user <login> [--admin]]"
+                ))?
+            };
+            env::load_self_hosted_config(Path::new(&config_path))?;
+            let config = envy::from_env::<Config>().expect("error loading config");
 
-            run_migrations(&config).await?;
+            if !cfg!(feature = "sqlite") && config.database_url.starts_with("sqlite:") {
+                Err(anyhow!(
+                    "collab was built without SQLite support, rebuild it with `--features sqlite`"
+                ))?;
+            }
 
-            let state = AppState::new(config, Executor::Production).await?;
+            match args.next().as_deref() {
+                None | Some("serve") => serve(config, true, true).await?,
+                Some("add-user") => {
+                    let login = args.next().ok_or_else(|| anyhow!("missing login"))?;
+                    let admin = args.next().as_deref() == Some("--admin");
+                    add_password_user(&config, &login, admin).await?;
+                }
+                Some(command) => Err(anyhow!("unknown self-hosted command {command}"))?,
+            }
+        }
+        _ => {
+            Err(anyhow!(
+                "usage: collab <version | migrate | seed | serve [api|collab] | self-hosted <config.toml>>"
+            ))?;
+        }
+    }
+    Ok(())
+}
 
-            let listener = TcpListener::bind(&format!("0.0.0.0:{}", state.config.http_port))
-                .expect("failed to bind TCP listener");
+async fn serve(config: Config, is_api: bool, is_collab: bool) -> Result<()> {
+    init_tracing(&config);
 
-            let rpc_server = if is_collab {
-                let epoch = state
-                    .db
-                    .create_server(&state.config.zed_environment)
-                    .await?;
-                let rpc_server = collab::rpc::Server::new(epoch, state.clone());
-                rpc_server.start().await?;
+    run_migrations(&config).await?;
 
-                Some(rpc_server)
-            } else {
-                None
-            };
+    let state = AppState::new(config, Executor::Production).await?;
 
-            if is_collab {
-                state.db.purge_old_embeddings().await.trace_err();
-                RateLimiter::save_periodically(state.rate_limiter.clone(), state.executor.clone());
-            }
+    let listener = TcpListener::bind(&format!("0.0.0.0:{}", state.config.http_port))
+        .expect("failed to bind TCP listener");
 
-            if is_api {
-                fetch_extensions_from_blob_store_periodically(state.clone());
-            }
+    let rpc_server = if is_collab {
+        let epoch = state
+            .db
+            .create_server(&state.config.zed_environment)
+            .await?;
+        let rpc_server = collab::rpc::Server::new(epoch, state.clone());
+        rpc_server.start().await?;
 
-            let mut app = collab::api::routes(rpc_server.clone(), state.clone());
-            if let Some(rpc_server) = rpc_server.clone() {
-                app = app.merge(collab::rpc::routes(rpc_server))
-            }
-            app = app
-                .merge(
-                    Router::new()
-                        .route("/", get(handle_root))
-                        .route("/healthz", get(handle_liveness_probe))
-                        .merge(collab::api::extensions::router())
-                        .merge(collab::api::events::router())
-                        .layer(Extension(state.clone())),
-                )
-                .layer(
-                    TraceLayer::new_for_http()
-                        .make_span_with(|request: &Request<_>| {
-                            let matched_path = request
-                                .extensions()
-                                .get::<MatchedPath>()
-                                .map(MatchedPath::as_str);
-
-                            tracing::info_span!(
-                                "http_request",
-                                method = ?request.method(),
-                                matched_path,
-                            )
-                        })
-                        .on_response(
-                            |response: &Response<_>, latency: Duration, _: &tracing::Span| {
-                                let duration_ms = latency.as_micros() as f64 / 1000.;
-                                tracing::info!(
-                                    duration_ms,
-                                    status = response.status().as_u16(),
-                                    "finished processing request"
-                                );
-                            },
-                        ),
-                );
-
-            #[cfg(unix)]
-            let signal = async move {
-                let mut sigterm = tokio::signal::unix::signal(SignalKind::terminate())
-                    .expect("failed to listen for interrupt signal");
-                let mut sigint = tokio::signal::unix::signal(SignalKind::interrupt())
-                    .expect("failed to listen for interrupt signal");
-                let sigterm = sigterm.recv();
-                let sigint = sigint.recv();
-                futures::pin_mut!(sigterm, sigint);
-                futures::future::select(sigterm, sigint).await;
-            };
+        Some(rpc_server)
+    } else {
+        None
+    };
 
-            #[cfg(windows)]
-            let signal = async move {
-                // todo(windows):
-                // `ctrl_close` does not work well, because tokio's signal handler always returns soon,
-                // but system termiates the application soon after returning CTRL+CLOSE handler.
-                // So we should implement blocking handler to treat CTRL+CLOSE signal.
-                let mut ctrl_break = tokio::signal::windows::ctrl_break()
-                    .expect("failed to listen for interrupt signal");
-                let mut ctrl_c = tokio::signal::windows::ctrl_c()
-                    .expect("failed to listen for interrupt signal");
-                let ctrl_break = ctrl_break.recv();
-                let ctrl_c = ctrl_c.recv();
-                futures::pin_mut!(ctrl_break, ctrl_c);
-                futures::future::select(ctrl_break, ctrl_c).await;
-            };
+    if is_collab {
+        state.db.purge_old_embeddings().await.trace_err();
+        RateLimiter::save_periodically(state.rate_limiter.clone(), state.executor.clone());
+    }
 
-            axum::Server::from_tcp(listener)
-                .map_err(|e| anyhow!(e))?
-                .serve(app.into_make_service_with_connect_info::<SocketAddr>())
-                .with_graceful_shutdown(async move {
-                    signal.await;
-                    tracing::info!("Received interrupt signal");
+    if is_api {
+        fetch_extensions_from_blob_store_periodically(state.clone());
+    }
 
-                    if let Some(rpc_server) = rpc_server {
-                        rpc_server.teardown();
-                    }
+    let mut app = collab::api::routes(rpc_server.clone(), state.clone());
+    if let Some(rpc_server) = rpc_server.clone() {
+        app = app.merge(collab::rpc::routes(rpc_server))
+    }
+    let mut public_routes = Router::new()
+        .route("/", get(handle_root))
+        .route("/healthz", get(handle_liveness_probe))
+        .merge(collab::api::extensions::router());
+    if state.config.is_self_hosted() {
+        // Self-hosted servers sign users in themselves and don't collect telemetry.
+        public_routes = public_routes.merge(collab::api::signin::router());
+    } else {
+        public_routes = public_routes.merge(collab::api::events::router());
+    }
+    app = app
+        .merge(public_routes.layer(Extension(state.clone())))
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(|request: &Request<_>| {
+                    let matched_path = request
+                        .extensions()
+                        .get::<MatchedPath>()
+                        .map(MatchedPath::as_str);
+
+                    tracing::info_span!(
+                        "http_request",
+                        method = ?request.method(),
+                        matched_path,
+                    )
                 })
-                .await
-                .map_err(|e| anyhow!(e))?;
-        }
-        _ => {
-            Err(anyhow!(
-                "usage: collab <version | migrate | seed | serve [api|collab]>"
-            ))?;
-        }
+                .on_response(
+                    |response: &Response<_>, latency: Duration, _: &tracing::Span| {
+                        let duration_ms = latency.as_micros() as f64 / 1000.;
+                        tracing::info!(
+                            duration_ms,
+                            status = response.status().as_u16(),
+                            "finished processing request"
+                        );
+                    },
+                ),
+        );
+
+    #[cfg(unix)]
+    let signal = async move {
+        let mut sigterm = tokio::signal::unix::signal(SignalKind::terminate())
+            .expect("failed to listen for interrupt signal");
+        let mut sigint = tokio::signal::unix::signal(SignalKind::interrupt())
+            .expect("failed to listen for interrupt signal");
+        let sigterm = sigterm.recv();
+        let sigint = sigint.recv();
+        futures::pin_mut!(sigterm, sigint);
+        futures::future::select(sigterm, sigint).await;
+    };
+
+    #[cfg(windows)]
+    let signal = async move {
+        // todo(windows):
+        // `ctrl_close` does not work well, because tokio's signal handler always returns soon,
+        // but system termiates the application soon after returning CTRL+CLOSE handler.
+        // So we should implement blocking handler to treat CTRL+CLOSE signal.
+        let mut ctrl_break =
+            tokio::signal::windows::ctrl_break().expect("failed to listen for interrupt signal");
+        let mut ctrl_c =
+            tokio::signal::windows::ctrl_c().expect("failed to listen for interrupt signal");
+        let ctrl_break = ctrl_break.recv();
+        let ctrl_c = ctrl_c.recv();
+        futures::pin_mut!(ctrl_break, ctrl_c);
+        futures::future::select(ctrl_break, ctrl_c).await;
+    };
+
+    axum::Server::from_tcp(listener)
+        .map_err(|e| anyhow!(e))?
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(async move {
+            signal.await;
+            tracing::info!("Received interrupt signal");
+
+            if let Some(rpc_server) = rpc_server {
+                rpc_server.teardown();
+            }
+        })
+        .await
+        .map_err(|e| anyhow!(e))?;
+    Ok(())
+}
+
+/// Creates a user who signs in to a self-hosted server with a password read from stdin.
+async fn add_password_user(config: &Config, login: &str, admin: bool) -> Result<()> {
+    run_migrations(config).await?;
+
+    eprint!("password for {login}: ");
+    let mut password = String::new();
+    std::io::stdin()
+        .read_line(&mut password)
+        .map_err(|e| anyhow!(e))?;
+    let password = password.trim_end_matches(['\r', '\n']);
+    if password.is_empty() {
+        Err(anyhow!("password must not be empty"))?;
     }
+
+    let db_options = db::ConnectOptions::new(config.database_url.clone());
+    let db = Database::new(db_options, Executor::Production).await?;
+    let password_hash = collab::auth::hash_password(password)?;
+    db.create_or_update_password_user(login, admin, &password_hash)
+        .await?;
     Ok(())
 }
 
@@ -217,6 +276,10 @@ async fn run_migrations(config: &Config) -> Result<()> {
         collab::seed::seed(&config, &db, false).await?;
     }
 
+    if config.is_self_hosted() {
+        collab::seed::seed_self_hosted_admin(&config, &db).await?;
+    }
+
     return Ok(());
 }
 
@@ -1,29 +1,217 @@
 use crate::{db::UserId, executor::Executor, Database, Error, Result};
-use anyhow::anyhow;
+use anyhow::Context as _;
 use chrono::{DateTime, Duration, Utc};
+use collections::HashMap;
 use dashmap::{DashMap, DashSet};
+use rpc::RpcError;
 use sea_orm::prelude::DateTimeUtc;
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::{iter, path::Path, sync::Arc};
 use util::ResultExt;
 
-pub trait RateLimit: 'static {
-    fn capacity() -> usize;
-    fn refill_duration() -> Duration;
-    fn db_name() -> &'static str;
+/// The set of rate limits that applies to a user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserTier {
+    Default,
+    Admin,
+}
+
+/// Allows `capacity` requests, refilled evenly over `refill_seconds`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub struct RateLimitPolicy {
+    pub capacity: usize,
+    pub refill_seconds: i64,
+}
+
+impl RateLimitPolicy {
+    fn refill_duration(&self) -> Duration {
+        Duration::seconds(self.refill_seconds)
+    }
+}
+
+/// A policy for one kind of message, with overrides for some user tiers.
+#[derive(Clone, Debug, Deserialize)]
+pub struct TieredRateLimitPolicy {
+    #[serde(flatten)]
+    pub policy: RateLimitPolicy,
+    #[serde(default)]
+    pub tiers: HashMap<UserTier, RateLimitPolicy>,
+}
+
+impl TieredRateLimitPolicy {
+    fn for_tier(&self, tier: UserTier) -> RateLimitPolicy {
+        self.tiers.get(&tier).copied().unwrap_or(self.policy)
+    }
+}
+
+/// Messages that keep collaborators' copies of projects and buffers in sync. Rejecting one
+/// would leave collaborators out of sync, so these can't be rate limited.
+const REPLICATION_MESSAGES: &[&str] = &[
+    "BufferReloaded",
+    "BufferSaved",
+    "RejoinChannelBuffers",
+    "SynchronizeBuffers",
+    "UpdateBuffer",
+    "UpdateBufferFile",
+    "UpdateChannelBuffer",
+    "UpdateDiagnosticSummary",
+    "UpdateDiffBase",
+    "UpdateProject",
+    "UpdateWorktree",
+    "UpdateWorktreeSettings",
+];
+
+/// The name under which sign-in attempts on the password form are limited, per account.
+/// It isn't an RPC message, but it's configured like one.
+pub const NATIVE_APP_SIGNIN: &str = "NativeAppSignin";
+
+/// A limit that applies without any configuration.
+struct BuiltinRateLimit {
+    message_name: &'static str,
+    /// The name of the limit's buckets in the database, kept so that limits carry over
+    /// from before they were configurable.
+    bucket_name: &'static str,
+    /// Overrides the capacity, even over the configuration file.
+    capacity_env_var: &'static str,
+    capacity_per_hour: usize,
+}
+
+// The capacities were picked arbitrarily.
+const BUILTIN_RATE_LIMITS: &[BuiltinRateLimit] = &[
+    BuiltinRateLimit {
+        message_name: "CompleteWithLanguageModel",
+        bucket_name: "complete-with-language-model",
+        capacity_env_var: "COMPLETE_WITH_LANGUAGE_MODEL_RATE_LIMIT_PER_HOUR",
+        capacity_per_hour: 120,
+    },
+    BuiltinRateLimit {
+        message_name: "CountTokensWithLanguageModel",
+        bucket_name: "count-tokens-with-language-model",
+        capacity_env_var: "COUNT_TOKENS_WITH_LANGUAGE_MODEL_RATE_LIMIT_PER_HOUR",
+        capacity_per_hour: 600,
+    },
+    BuiltinRateLimit {
+        message_name: "ComputeEmbeddings",
+        bucket_name: "compute-embeddings",
+        capacity_env_var: "EMBED_TEXTS_RATE_LIMIT_PER_HOUR",
+        capacity_per_hour: 120,
+    },
+    BuiltinRateLimit {
+        message_name: "GetCachedEmbeddings",
+        bucket_name: "get-cached-embeddings",
+        capacity_env_var: "EMBED_TEXTS_RATE_LIMIT_PER_HOUR",
+        capacity_per_hour: 120,
+    },
+    BuiltinRateLimit {
+        message_name: NATIVE_APP_SIGNIN,
+        bucket_name: "native-app-signin",
+        capacity_env_var: "NATIVE_APP_SIGNIN_RATE_LIMIT_PER_HOUR",
+        capacity_per_hour: 10,
+    },
+];
+
+/// The rate limits for RPC requests, keyed by message name (e.g. `"GetUsers"`).
+/// Requests that aren't listed aren't limited.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct RateLimitConfig {
+    #[serde(default)]
+    pub messages: HashMap<String, TieredRateLimitPolicy>,
+}
+
+impl RateLimitConfig {
+    /// Returns the built-in limits, overridden by the JSON file at `path` if one is given,
+    /// and then by the built-in limits' environment variables.
+    pub fn load(path: Option<&Path>) -> anyhow::Result<Self> {
+        let mut config = Self::builtin();
+        if let Some(path) = path {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            let overrides: Self = serde_json::from_str(&content)
+                .with_context(|| format!("failed to parse {}", path.display()))?;
+            config.messages.extend(overrides.messages);
+        }
+        config.apply_env_overrides(|name| std::env::var(name).ok());
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn builtin() -> Self {
+        Self {
+            messages: BUILTIN_RATE_LIMITS
+                .iter()
+                .map(|limit| {
+                    let policy = TieredRateLimitPolicy {
+                        policy: RateLimitPolicy {
+                            capacity: limit.capacity_per_hour,
+                            refill_seconds: 60 * 60,
+                        },
+                        tiers: HashMap::default(),
+                    };
+                    (limit.message_name.to_string(), policy)
+                })
+                .collect(),
+        }
+    }
+
+    fn apply_env_overrides(&mut self, env_var: impl Fn(&str) -> Option<String>) {
+        for limit in BUILTIN_RATE_LIMITS {
+            let Some(capacity) = env_var(limit.capacity_env_var).and_then(|v| v.parse().ok())
+            else {
+                continue;
+            };
+            if let Some(policy) = self.messages.get_mut(limit.message_name) {
+                policy.policy.capacity = capacity;
+            }
+        }
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        for (name, tiered_policy) in &self.messages {
+            anyhow::ensure!(
+                !REPLICATION_MESSAGES.contains(&name.as_str()),
+                "{name} keeps collaborators in sync and can't be rate limited"
+            );
+            for policy in iter::once(&tiered_policy.policy).chain(tiered_policy.tiers.values()) {
+                anyhow::ensure!(
+                    policy.capacity > 0 && policy.refill_seconds > 0,
+                    "rate limit for {name} must have a positive capacity and refill_seconds"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the limit on the given message for users in the given tier, if there is one.
+    pub fn policy(&self, message_name: &str, tier: UserTier) -> Option<RateLimitPolicy> {
+        self.messages
+            .get(message_name)
+            .map(|policy| policy.for_tier(tier))
+    }
+}
+
+/// Returns the name of the message's buckets in the database.
+fn bucket_name(message_name: &str) -> &str {
+    BUILTIN_RATE_LIMITS
+        .iter()
+        .find(|limit| limit.message_name == message_name)
+        .map_or(message_name, |limit| limit.bucket_name)
 }
 
 /// Used to enforce per-user rate limits
 pub struct RateLimiter {
     buckets: DashMap<(UserId, String), RateBucket>,
     dirty_buckets: DashSet<(UserId, String)>,
+    config: RateLimitConfig,
     db: Arc<Database>,
 }
 
 impl RateLimiter {
-    pub fn new(db: Arc<Database>) -> Self {
+    pub fn new(db: Arc<Database>, config: RateLimitConfig) -> Self {
         RateLimiter {
             buckets: DashMap::new(),
             dirty_buckets: DashSet::new(),
+            config,
             db,
         }
     }
@@ -40,20 +228,36 @@ impl RateLimiter {
         });
     }
 
-    /// Returns an error if the user has exceeded the specified `RateLimit`.
+    /// Returns a [`rpc::ErrorCode::RateLimitExceeded`] error if the user has exceeded the
+    /// configured limit on the given message. Messages without a limit are always allowed.
     /// Attempts to read the from the database if no cached RateBucket currently exists.
-    pub async fn check<T: RateLimit>(&self, user_id: UserId) -> Result<()> {
-        self.check_internal::<T>(user_id, Utc::now()).await
+    pub async fn check(&self, user_id: UserId, tier: UserTier, message_name: &str) -> Result<()> {
+        let Some(policy) = self.config.policy(message_name, tier) else {
+            return Ok(());
+        };
+        self.check_internal(user_id, bucket_name(message_name), policy, Utc::now())
+            .await
     }
 
-    async fn check_internal<T: RateLimit>(&self, user_id: UserId, now: DateTimeUtc) -> Result<()> {
-        let bucket_key = (user_id, T::db_name().to_string());
+    async fn check_internal(
+        &self,
+        user_id: UserId,
+        name: &str,
+        policy: RateLimitPolicy,
+        now: DateTimeUtc,
+    ) -> Result<()> {
+        let bucket_key = (user_id, name.to_string());
 
         // Attempt to fetch the bucket from the database if it hasn't been cached.
         // For now, we keep buckets in memory for the lifetime of the process rather than expiring them,
         // but this enforces limits across restarts so long as the database is reachable.
         if !self.buckets.contains_key(&bucket_key) {
-            if let Some(bucket) = self.load_bucket::<T>(user_id).await.log_err().flatten() {
+            if let Some(bucket) = self
+                .load_bucket(user_id, name, policy)
+                .await
+                .log_err()
+                .flatten()
+            {
                 self.buckets.insert(bucket_key.clone(), bucket);
                 self.dirty_buckets.insert(bucket_key.clone());
             }
@@ -62,29 +266,81 @@ impl RateLimiter {
         let mut bucket = self
             .buckets
             .entry(bucket_key.clone())
-            .or_insert_with(|| RateBucket::new(T::capacity(), T::refill_duration(), now));
+            .or_insert_with(|| RateBucket::new(policy, now));
 
-        if bucket.value_mut().allow(now) {
-            self.dirty_buckets.insert(bucket_key);
-            Ok(())
-        } else {
-            Err(anyhow!("rate limit exceeded"))?
+        // The user's tier may have changed since the bucket was created.
+        bucket.value_mut().set_policy(policy);
+
+        match bucket.value_mut().allow(now) {
+            Ok(()) => {
+                self.dirty_buckets.insert(bucket_key);
+                Ok(())
+            }
+            Err(retry_after) => Err(anyhow::Error::from(RpcError::rate_limit_exceeded(
+                retry_after.to_std().unwrap_or_default(),
+            )))?,
         }
     }
 
-    async fn load_bucket<K: RateLimit>(
+    /// Returns the state of each of the user's buckets. Buckets that haven't been used since
+    /// the server started are reported as saved, without their capacity.
+    pub async fn user_buckets(&self, user_id: UserId) -> Result<Vec<RateBucketState>> {
+        let now = Utc::now();
+        let mut states = self
+            .db
+            .get_rate_buckets_for_user(user_id)
+            .await?
+            .into_iter()
+            .filter(|saved_bucket| {
+                !self
+                    .buckets
+                    .contains_key(&(user_id, saved_bucket.rate_limit_name.clone()))
+            })
+            .map(|saved_bucket| RateBucketState {
+                name: saved_bucket.rate_limit_name,
+                capacity: None,
+                token_count: saved_bucket.token_count as usize,
+                last_refill: DateTime::from_naive_utc_and_offset(saved_bucket.last_refill, Utc),
+            })
+            .collect::<Vec<_>>();
+
+        for entry in self.buckets.iter() {
+            let (bucket_user_id, name) = entry.key();
+            if *bucket_user_id != user_id {
+                continue;
+            }
+
+            let mut bucket = entry.value().clone();
+            bucket.refill(now);
+            states.push(RateBucketState {
+                name: name.clone(),
+                capacity: Some(bucket.capacity),
+                token_count: bucket.token_count,
+                last_refill: bucket.last_refill,
+            });
+        }
+
+        states.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(states)
+    }
+
+    async fn load_bucket(
         &self,
         user_id: UserId,
+        name: &str,
+        policy: RateLimitPolicy,
     ) -> Result<Option<RateBucket>, Error> {
         Ok(self
             .db
-            .get_rate_bucket(user_id, K::db_name())
+            .get_rate_bucket(user_id, name)
             .await?
-            .map(|saved_bucket| RateBucket {
-                capacity: K::capacity(),
-                refill_time_per_token: K::refill_duration(),
-                token_count: saved_bucket.token_count as usize,
-                last_refill: DateTime::from_naive_utc_and_offset(saved_bucket.last_refill, Utc),
+            .map(|saved_bucket| {
+                let mut bucket = RateBucket::new(
+                    policy,
+                    DateTime::from_naive_utc_and_offset(saved_bucket.last_refill, Utc),
+                );
+                bucket.token_count = (saved_bucket.token_count as usize).min(bucket.capacity);
+                bucket
             }))
     }
 
@@ -115,6 +371,15 @@ impl RateLimiter {
     }
 }
 
+/// A snapshot of one of a user's rate limit buckets.
+#[derive(Debug, Serialize)]
+pub struct RateBucketState {
+    pub name: String,
+    pub capacity: Option<usize>,
+    pub token_count: usize,
+    pub last_refill: DateTimeUtc,
+}
+
 #[derive(Clone)]
 struct RateBucket {
     capacity: usize,
@@ -124,22 +389,29 @@ struct RateBucket {
 }
 
 impl RateBucket {
-    fn new(capacity: usize, refill_duration: Duration, now: DateTimeUtc) -> Self {
+    fn new(policy: RateLimitPolicy, now: DateTimeUtc) -> Self {
         RateBucket {
-            capacity,
-            token_count: capacity,
-            refill_time_per_token: refill_duration / capacity as i32,
+            capacity: policy.capacity,
+            token_count: policy.capacity,
+            refill_time_per_token: policy.refill_duration() / policy.capacity as i32,
             last_refill: now,
         }
     }
 
-    fn allow(&mut self, now: DateTimeUtc) -> bool {
+    fn set_policy(&mut self, policy: RateLimitPolicy) {
+        self.capacity = policy.capacity;
+        self.refill_time_per_token = policy.refill_duration() / policy.capacity as i32;
+        self.token_count = self.token_count.min(self.capacity);
+    }
+
+    /// Takes a token from the bucket, or returns how long it will be until one is available.
+    fn allow(&mut self, now: DateTimeUtc) -> Result<(), Duration> {
         self.refill(now);
         if self.token_count > 0 {
             self.token_count -= 1;
-            true
+            Ok(())
         } else {
-            false
+            Err(self.refill_time_per_token - (now - self.last_refill))
         }
     }
 
@@ -160,6 +432,17 @@ mod tests {
     use super::*;
     use crate::db::{NewUserParams, TestDb};
     use gpui::TestAppContext;
+    use rpc::{ErrorCode, ErrorExt};
+
+    const RATE_LIMIT_A: RateLimitPolicy = RateLimitPolicy {
+        capacity: 2,
+        refill_seconds: 2,
+    };
+
+    const RATE_LIMIT_B: RateLimitPolicy = RateLimitPolicy {
+        capacity: 10,
+        refill_seconds: 3,
+    };
 
     #[gpui::test]
     async fn test_rate_limiter(cx: &mut TestAppContext) {
@@ -192,40 +475,47 @@ mod tests {
 
         let mut now = Utc::now();
 
-        let rate_limiter = RateLimiter::new(db.clone());
+        let rate_limiter = RateLimiter::new(db.clone(), RateLimitConfig::default());
 
         // User 1 can access resource A two times before being rate-limited.
         rate_limiter
-            .check_internal::<RateLimitA>(user_1, now)
+            .check_internal(user_1, "rate-limit-a", RATE_LIMIT_A, now)
             .await
             .unwrap();
         rate_limiter
-            .check_internal::<RateLimitA>(user_1, now)
+            .check_internal(user_1, "rate-limit-a", RATE_LIMIT_A, now)
             .await
             .unwrap();
-        rate_limiter
-            .check_internal::<RateLimitA>(user_1, now)
+        let error = rate_limiter
+            .check_internal(user_1, "rate-limit-a", RATE_LIMIT_A, now)
             .await
             .unwrap_err();
 
+        // The error tells the client when a token will next be available.
+        let crate::Error::Internal(error) = error else {
+            panic!("unexpected error {error:?}");
+        };
+        assert_eq!(error.error_code(), ErrorCode::RateLimitExceeded);
+        assert_eq!(error.retry_after(), Some(std::time::Duration::from_secs(1)));
+
         // User 2 can access resource A and user 1 can access resource B.
         rate_limiter
-            .check_internal::<RateLimitB>(user_2, now)
+            .check_internal(user_2, "rate-limit-a", RATE_LIMIT_A, now)
             .await
             .unwrap();
         rate_limiter
-            .check_internal::<RateLimitB>(user_1, now)
+            .check_internal(user_1, "rate-limit-b", RATE_LIMIT_B, now)
             .await
             .unwrap();
 
         // After one second, user 1 can make another request before being rate-limited again.
         now += Duration::seconds(1);
         rate_limiter
-            .check_internal::<RateLimitA>(user_1, now)
+            .check_internal(user_1, "rate-limit-a", RATE_LIMIT_A, now)
             .await
             .unwrap();
         rate_limiter
-            .check_internal::<RateLimitA>(user_1, now)
+            .check_internal(user_1, "rate-limit-a", RATE_LIMIT_A, now)
             .await
             .unwrap_err();
 
@@ -233,42 +523,130 @@ mod tests {
 
         // Rate limits are reloaded from the database, so user A is still rate-limited
         // for resource A.
-        let rate_limiter = RateLimiter::new(db.clone());
+        let rate_limiter = RateLimiter::new(db.clone(), RateLimitConfig::default());
         rate_limiter
-            .check_internal::<RateLimitA>(user_1, now)
+            .check_internal(user_1, "rate-limit-a", RATE_LIMIT_A, now)
             .await
             .unwrap_err();
-    }
-
-    struct RateLimitA;
 
-    impl RateLimit for RateLimitA {
-        fn capacity() -> usize {
-            2
-        }
-
-        fn refill_duration() -> Duration {
-            Duration::seconds(2)
-        }
-
-        fn db_name() -> &'static str {
-            "rate-limit-a"
-        }
+        // If the user's limit is raised, the new refill rate applies right away.
+        now += Duration::milliseconds(300);
+        rate_limiter
+            .check_internal(user_1, "rate-limit-a", RATE_LIMIT_B, now)
+            .await
+            .unwrap();
     }
 
-    struct RateLimitB;
-
-    impl RateLimit for RateLimitB {
-        fn capacity() -> usize {
-            10
-        }
-
-        fn refill_duration() -> Duration {
-            Duration::seconds(3)
-        }
-
-        fn db_name() -> &'static str {
-            "rate-limit-b"
-        }
+    #[gpui::test]
+    async fn test_rate_limit_config(cx: &mut TestAppContext) {
+        let config: RateLimitConfig = serde_json::from_value(serde_json::json!({
+            "messages": {
+                "GetUsers": { "capacity": 1, "refill_seconds": 10 },
+                "FuzzySearchUsers": {
+                    "capacity": 100,
+                    "refill_seconds": 60,
+                    "tiers": {
+                        "admin": { "capacity": 1000, "refill_seconds": 60 }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        config.validate().unwrap();
+
+        assert_eq!(
+            config.policy("GetUsers", UserTier::Default),
+            Some(RateLimitPolicy {
+                capacity: 1,
+                refill_seconds: 10
+            })
+        );
+        assert_eq!(
+            config.policy("GetUsers", UserTier::Admin),
+            Some(RateLimitPolicy {
+                capacity: 1,
+                refill_seconds: 10
+            })
+        );
+        assert_eq!(
+            config.policy("FuzzySearchUsers", UserTier::Admin),
+            Some(RateLimitPolicy {
+                capacity: 1000,
+                refill_seconds: 60
+            })
+        );
+        // Requests that aren't listed aren't limited.
+        assert_eq!(config.policy("GetChannelMembers", UserTier::Default), None);
+
+        let invalid_config: RateLimitConfig = serde_json::from_value(serde_json::json!({
+            "messages": {
+                "GetUsers": { "capacity": 0, "refill_seconds": 10 }
+            }
+        }))
+        .unwrap();
+        invalid_config.validate().unwrap_err();
+
+        // Replication messages can't be limited.
+        let invalid_config: RateLimitConfig = serde_json::from_value(serde_json::json!({
+            "messages": {
+                "UpdateBuffer": { "capacity": 100, "refill_seconds": 10 }
+            }
+        }))
+        .unwrap();
+        invalid_config.validate().unwrap_err();
+
+        // The built-in limits keep their environment variables and bucket names.
+        let mut builtin = RateLimitConfig::builtin();
+        builtin.apply_env_overrides(|name| {
+            (name == "EMBED_TEXTS_RATE_LIMIT_PER_HOUR").then(|| "7".to_string())
+        });
+        assert_eq!(
+            builtin.policy("ComputeEmbeddings", UserTier::Default),
+            Some(RateLimitPolicy {
+                capacity: 7,
+                refill_seconds: 60 * 60
+            })
+        );
+        assert_eq!(
+            builtin.policy("CompleteWithLanguageModel", UserTier::Default),
+            Some(RateLimitPolicy {
+                capacity: 120,
+                refill_seconds: 60 * 60
+            })
+        );
+        assert_eq!(
+            bucket_name("CompleteWithLanguageModel"),
+            "complete-with-language-model"
+        );
+        assert_eq!(bucket_name("GetUsers"), "GetUsers");
+
+        // Limits apply to each message separately.
+        let test_db = TestDb::sqlite(cx.executor().clone());
+        let db = test_db.db().clone();
+        let user = db
+            .create_user(
+                "user-1@zed.dev",
+                false,
+                NewUserParams {
+                    github_login: "user-1".into(),
+                    github_user_id: 1,
+                },
+            )
+            .await
+            .unwrap()
+            .user_id;
+        let rate_limiter = RateLimiter::new(db, config);
+        rate_limiter
+            .check(user, UserTier::Default, "GetUsers")
+            .await
+            .unwrap();
+        rate_limiter
+            .check(user, UserTier::Default, "GetUsers")
+            .await
+            .unwrap_err();
+        rate_limiter
+            .check(user, UserTier::Default, "FuzzySearchUsers")
+            .await
+            .unwrap();
     }
 }
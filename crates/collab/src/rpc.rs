@@ -4,13 +4,14 @@ use crate::{
     auth,
     db::{
         self, dev_server, BufferId, Capability, Channel, ChannelId, ChannelRole, ChannelsForUser,
-        CreatedChannelMessage, Database, DevServerId, InviteMemberResult, MembershipUpdated,
-        MessageId, NotificationId, PrincipalId, Project, ProjectId, RejoinedProject,
-        RemoteProjectId, RemoveChannelMemberResult, ReplicaId, RespondToChannelInvite, RoomId,
-        ServerId, UpdatedChannelMessage, User, UserId,
+        CreatedChannelMessage, CreatedReviewComment, Database, DevServerId, InviteMemberResult,
+        MembershipUpdated, MessageId, NotificationId, PrincipalId, Project, ProjectId,
+        RejoinedProject, RemoteProjectId, RemoveChannelMemberResult, ReplicaId,
+        RespondToChannelInvite, ReviewCommentId, RoomId, ServerId, UpdatedChannelMessage, User,
+        UserId,
     },
     executor::Executor,
-    AppState, Error, RateLimit, RateLimiter, Result,
+    AppState, Error, RateLimiter, Result, UserTier,
 };
 use anyhow::{anyhow, Context as _};
 use async_tungstenite::tungstenite::{
@@ -48,6 +49,7 @@ use rpc::{
         LiveKitConnectionInfo, RequestMessage, ShareProject, UpdateChannelBufferCollaborators,
     },
     Connection, ConnectionId, ErrorCode, ErrorCodeExt, ErrorExt, Peer, Receipt, TypedEnvelope,
+    MAX_MESSAGE_LEN, MAX_REVIEW_ANCHOR_TEXT_LEN,
 };
 use semantic_version::SemanticVersion;
 use serde::{Serialize, Serializer};
@@ -82,7 +84,7 @@ pub const RECONNECT_TIMEOUT: Duration = Duration::from_secs(30);
 pub const CLEANUP_TIMEOUT: Duration = Duration::from_secs(15);
 
 const MESSAGE_COUNT_PER_PAGE: usize = 100;
-const MAX_MESSAGE_LEN: usize = 1024;
+const MAX_REACTION_LEN: usize = 32;
 const NOTIFICATION_COUNT_PER_PAGE: usize = 50;
 
 type MessageHandler =
@@ -196,6 +198,22 @@ impl Session {
         }
     }
 
+    /// Returns an error if the user has exceeded their limit on the given request.
+    /// Only requests with a configured limit are rate limited, and the configuration
+    /// can't limit the ones that keep collaborators in sync. Dev servers aren't rate limited.
+    async fn check_rate_limit(&self, message_name: &str) -> Result<()> {
+        let user = match &self.principal {
+            Principal::User(user) | Principal::Impersonated { user, .. } => user,
+            Principal::DevServer(_) => return Ok(()),
+        };
+        let tier = if user.admin {
+            UserTier::Admin
+        } else {
+            UserTier::Default
+        };
+        self.rate_limiter.check(user.id, tier, message_name).await
+    }
+
     fn principal_id(&self) -> PrincipalId {
         match &self.principal {
             Principal::User(user) => PrincipalId::UserId(user.id),
@@ -224,6 +242,22 @@ impl Debug for Session {
     }
 }
 
+/// Requests whose handlers check the rate limit themselves, once they've checked that the
+/// user has access, so that requests that would be refused don't use up the limit.
+const RATE_LIMITED_BY_HANDLER: &[&str] = &[
+    proto::CompleteWithLanguageModel::NAME,
+    proto::CountTokensWithLanguageModel::NAME,
+    proto::ComputeEmbeddings::NAME,
+    proto::GetCachedEmbeddings::NAME,
+];
+
+async fn check_rate_limit_before_handling(session: &Session, message_name: &str) -> Result<()> {
+    if RATE_LIMITED_BY_HANDLER.contains(&message_name) {
+        return Ok(());
+    }
+    session.check_rate_limit(message_name).await
+}
+
 struct UserSession(Session);
 
 impl UserSession {
@@ -403,6 +437,7 @@ impl Server {
             .add_request_handler(user_handler(rejoin_room))
             .add_request_handler(user_handler(leave_room))
             .add_request_handler(user_handler(set_room_participant_role))
+            .add_request_handler(user_handler(set_room_presenter))
             .add_request_handler(user_handler(call))
             .add_request_handler(user_handler(cancel_call))
             .add_message_handler(user_message_handler(decline_call))
@@ -518,6 +553,12 @@ impl Server {
             .add_request_handler(user_handler(
                 forward_mutating_project_request::<proto::MultiLspQuery>,
             ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::SynchronizeSharedTerminals>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::SharedTerminalInput>,
+            ))
             .add_message_handler(create_buffer_for_peer)
             .add_request_handler(update_buffer)
             .add_message_handler(broadcast_project_message_from_host::<proto::RefreshInlayHints>)
@@ -525,6 +566,8 @@ impl Server {
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferReloaded>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferSaved>)
             .add_message_handler(broadcast_project_message_from_host::<proto::UpdateDiffBase>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::UpdateSharedTerminal>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::CloseSharedTerminal>)
             .add_request_handler(get_users)
             .add_request_handler(user_handler(fuzzy_search_users))
             .add_request_handler(user_handler(request_contact))
@@ -541,6 +584,8 @@ impl Server {
             .add_request_handler(user_handler(leave_channel_buffer))
             .add_message_handler(user_message_handler(update_channel_buffer))
             .add_request_handler(user_handler(rejoin_channel_buffers))
+            .add_request_handler(user_handler(get_channel_buffer_history))
+            .add_request_handler(user_handler(get_channel_buffer_version))
             .add_request_handler(user_handler(get_channel_members))
             .add_request_handler(user_handler(respond_to_channel_invite))
             .add_request_handler(user_handler(join_channel))
@@ -551,6 +596,12 @@ impl Server {
             .add_request_handler(user_handler(update_channel_message))
             .add_request_handler(user_handler(get_channel_messages))
             .add_request_handler(user_handler(get_channel_messages_by_id))
+            .add_request_handler(user_handler(get_channel_message_thread))
+            .add_request_handler(user_handler(search_channel_messages))
+            .add_request_handler(user_handler(toggle_channel_message_reaction))
+            .add_request_handler(user_handler(create_review_comment))
+            .add_request_handler(user_handler(resolve_review_thread))
+            .add_request_handler(user_handler(get_review_comments))
             .add_request_handler(user_handler(get_notifications))
             .add_request_handler(user_handler(mark_notification_as_read))
             .add_request_handler(user_handler(move_channel))
@@ -846,7 +897,12 @@ impl Server {
                     responded: responded.clone(),
                     receipt,
                 };
-                match (handler)(envelope.payload, response, session).await {
+                let rate_limit = check_rate_limit_before_handling(&session, M::NAME).await;
+                let result = match rate_limit {
+                    Ok(()) => (handler)(envelope.payload, response, session).await,
+                    Err(error) => Err(error),
+                };
+                match result {
                     Ok(()) => {
                         if responded.load(std::sync::atomic::Ordering::SeqCst) {
                             Ok(())
@@ -883,7 +939,12 @@ impl Server {
                     peer: peer.clone(),
                     receipt,
                 };
-                match (handler)(envelope.payload, response, session).await {
+                let rate_limit = check_rate_limit_before_handling(&session, M::NAME).await;
+                let result = match rate_limit {
+                    Ok(()) => (handler)(envelope.payload, response, session).await,
+                    Err(error) => Err(error),
+                };
+                match result {
                     Ok(()) => {
                         peer.end_stream(receipt)?;
                         Ok(())
@@ -1185,6 +1246,44 @@ impl Server {
         Ok(())
     }
 
+    /// Closes the given connection, cleaning up after it as if the client had gone away.
+    /// Returns false if there is no such connection.
+    pub fn disconnect(&self, connection_id: ConnectionId) -> bool {
+        let exists = self
+            .connection_pool
+            .lock()
+            .connection(connection_id)
+            .is_some();
+        if exists {
+            self.peer.disconnect(connection_id);
+        }
+        exists
+    }
+
+    /// Closes all of the user's connections, returning their ids.
+    pub fn disconnect_user(&self, user_id: UserId) -> Vec<ConnectionId> {
+        let connection_ids = self
+            .connection_pool
+            .lock()
+            .user_connection_ids(user_id)
+            .collect::<Vec<_>>();
+        for connection_id in &connection_ids {
+            self.peer.disconnect(*connection_id);
+        }
+        connection_ids
+    }
+
+    /// Notifies the user's clients of a membership change made outside of a client request,
+    /// such as through the admin API.
+    pub fn channel_membership_updated(&self, user_id: UserId, update: MembershipUpdated) {
+        notify_membership_updated(
+            &mut self.connection_pool.lock(),
+            update,
+            user_id,
+            &self.peer,
+        );
+    }
+
     pub async fn snapshot<'a>(self: &'a Arc<Self>) -> ServerSnapshot<'a> {
         ServerSnapshot {
             connection_pool: ConnectionPoolGuard {
@@ -1796,6 +1895,26 @@ async fn set_room_participant_role(
     Ok(())
 }
 
+/// Starts or stops presenting to the rest of the room.
+async fn set_room_presenter(
+    request: proto::SetRoomPresenter,
+    response: Response<proto::SetRoomPresenter>,
+    session: UserSession,
+) -> Result<()> {
+    let room = session
+        .db()
+        .await
+        .set_room_presenter(
+            RoomId::from_proto(request.room_id),
+            session.connection_id,
+            request.presenting,
+        )
+        .await?;
+    room_updated(&room, &session.peer);
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
 /// Call someone else into the current room
 async fn call(
     request: proto::Call,
@@ -3663,7 +3782,12 @@ async fn join_channel_buffer(
     let channel_id = ChannelId::from_proto(request.channel_id);
 
     let open_response = db
-        .join_channel_buffer(channel_id, session.user_id(), session.connection_id)
+        .join_channel_buffer(
+            channel_id,
+            session.user_id(),
+            session.connection_id,
+            request.replica_id.map(|id| ReplicaId(id as i32)),
+        )
         .await?;
 
     let collaborators = open_response.collaborators.clone();
@@ -3722,6 +3846,7 @@ async fn update_channel_buffer(
                         channel_id: channel_id.to_proto(),
                         epoch: epoch as u64,
                         version: version.clone(),
+                        replica_id: None,
                     }],
                     ..Default::default()
                 },
@@ -3767,6 +3892,40 @@ async fn rejoin_channel_buffers(
     Ok(())
 }
 
+/// List the saved versions of the channel notes
+async fn get_channel_buffer_history(
+    request: proto::GetChannelBufferHistory,
+    response: Response<proto::GetChannelBufferHistory>,
+    session: UserSession,
+) -> Result<()> {
+    let versions = session
+        .db()
+        .await
+        .get_channel_buffer_history(ChannelId::from_proto(request.channel_id), session.user_id())
+        .await?;
+    response.send(proto::GetChannelBufferHistoryResponse { versions })?;
+    Ok(())
+}
+
+/// Retrieve the text of a saved version of the channel notes
+async fn get_channel_buffer_version(
+    request: proto::GetChannelBufferVersion,
+    response: Response<proto::GetChannelBufferVersion>,
+    session: UserSession,
+) -> Result<()> {
+    let text = session
+        .db()
+        .await
+        .get_channel_buffer_version(
+            ChannelId::from_proto(request.channel_id),
+            request.epoch as i32,
+            session.user_id(),
+        )
+        .await?;
+    response.send(proto::GetChannelBufferVersionResponse { text })?;
+    Ok(())
+}
+
 /// Stop editing the channel notes
 async fn leave_channel_buffer(
     request: proto::LeaveChannelBuffer,
@@ -3806,6 +3965,116 @@ fn channel_buffer_updated<T: EnvelopedMessage>(
     });
 }
 
+/// Create a review comment in a shared project
+async fn create_review_comment(
+    request: proto::CreateReviewComment,
+    response: Response<proto::CreateReviewComment>,
+    session: UserSession,
+) -> Result<()> {
+    let body = request.body.trim().to_string();
+    if body.len() > MAX_MESSAGE_LEN {
+        return Err(anyhow!("comment is too long"))?;
+    }
+    if body.is_empty() {
+        return Err(anyhow!("comment can't be blank"))?;
+    }
+    if request.anchor_text.len() > MAX_REVIEW_ANCHOR_TEXT_LEN {
+        return Err(anyhow!("comment anchor is too long"))?;
+    }
+
+    let project_id = ProjectId::from_proto(request.project_id);
+    let created = session
+        .db()
+        .await
+        .create_review_comment(
+            project_id,
+            session.connection_id,
+            session.user_id(),
+            &request,
+            &body,
+            OffsetDateTime::now_utc(),
+        )
+        .await?;
+    let CreatedReviewComment {
+        comment,
+        connection_ids,
+        notifications,
+    } = &*created;
+
+    broadcast(
+        Some(session.connection_id),
+        connection_ids.iter().copied(),
+        |connection_id| {
+            session.peer.send(
+                connection_id,
+                proto::UpdateReviewComments {
+                    project_id: project_id.to_proto(),
+                    comments: vec![comment.clone()],
+                },
+            )
+        },
+    );
+    response.send(proto::CreateReviewCommentResponse {
+        comment: Some(comment.clone()),
+    })?;
+
+    let pool = &*session.connection_pool().await;
+    send_notifications(pool, &session.peer, notifications.clone());
+    Ok(())
+}
+
+/// Resolve or reopen a review comment thread
+async fn resolve_review_thread(
+    request: proto::ResolveReviewThread,
+    response: Response<proto::ResolveReviewThread>,
+    session: UserSession,
+) -> Result<()> {
+    let project_id = ProjectId::from_proto(request.project_id);
+    let result = session
+        .db()
+        .await
+        .resolve_review_thread(
+            project_id,
+            session.connection_id,
+            session.user_id(),
+            ReviewCommentId::from_proto(request.comment_id),
+            request.resolved,
+        )
+        .await?;
+    let (thread, connection_ids) = &*result;
+
+    broadcast(None, connection_ids.iter().copied(), |connection_id| {
+        session.peer.send(
+            connection_id,
+            proto::UpdateReviewComments {
+                project_id: project_id.to_proto(),
+                comments: vec![thread.clone()],
+            },
+        )
+    });
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
+/// Get the review comments of a shared project
+async fn get_review_comments(
+    request: proto::GetReviewComments,
+    response: Response<proto::GetReviewComments>,
+    session: UserSession,
+) -> Result<()> {
+    let comments = session
+        .db()
+        .await
+        .get_review_comments(
+            ProjectId::from_proto(request.project_id),
+            session.connection_id,
+            session.user_id(),
+        )
+        .await?;
+    response.send(proto::GetReviewCommentsResponse { comments })?;
+    Ok(())
+}
+
 fn send_notifications(
     connection_pool: &ConnectionPool,
     peer: &Peer,
@@ -3883,6 +4152,7 @@ async fn send_channel_message(
         nonce: Some(nonce),
         reply_to_message_id: request.reply_to_message_id,
         edited_at: None,
+        reactions: Vec::new(),
     };
     broadcast(
         Some(session.connection_id),
@@ -3961,6 +4231,39 @@ async fn remove_channel_message(
     Ok(())
 }
 
+/// Add or remove the current user's reaction to a channel message
+async fn toggle_channel_message_reaction(
+    request: proto::ToggleChannelMessageReaction,
+    response: Response<proto::ToggleChannelMessageReaction>,
+    session: UserSession,
+) -> Result<()> {
+    let emoji = request.emoji.trim();
+    if emoji.is_empty() || emoji.len() > MAX_REACTION_LEN {
+        return Err(anyhow!("invalid reaction"))?;
+    }
+
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let message_id = MessageId::from_proto(request.message_id);
+    let (connection_ids, reactions) = session
+        .db()
+        .await
+        .toggle_channel_message_reaction(channel_id, message_id, session.user_id(), emoji)
+        .await?;
+
+    broadcast(None, connection_ids, |connection| {
+        session.peer.send(
+            connection,
+            proto::ChannelMessageReactionsUpdated {
+                channel_id: channel_id.to_proto(),
+                message_id: message_id.to_proto(),
+                reactions: reactions.clone(),
+            },
+        )
+    });
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
 async fn update_channel_message(
     request: proto::UpdateChannelMessage,
     response: Response<proto::UpdateChannelMessage>,
@@ -4004,6 +4307,7 @@ async fn update_channel_message(
         nonce: Some(nonce),
         reply_to_message_id: reply_to_message_id.map(|id| id.to_proto()),
         edited_at: Some(updated_at.unix_timestamp() as u64),
+        reactions: Vec::new(),
     };
 
     response.send(proto::Ack {})?;
@@ -4087,25 +4391,6 @@ async fn acknowledge_buffer_version(
     Ok(())
 }
 
-struct CompleteWithLanguageModelRateLimit;
-
-impl RateLimit for CompleteWithLanguageModelRateLimit {
-    fn capacity() -> usize {
-        std::env::var("COMPLETE_WITH_LANGUAGE_MODEL_RATE_LIMIT_PER_HOUR")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(120) // Picked arbitrarily
-    }
-
-    fn refill_duration() -> chrono::Duration {
-        chrono::Duration::hours(1)
-    }
-
-    fn db_name() -> &'static str {
-        "complete-with-language-model"
-    }
-}
-
 async fn complete_with_language_model(
     request: proto::CompleteWithLanguageModel,
     response: StreamingResponse<proto::CompleteWithLanguageModel>,
@@ -4119,8 +4404,7 @@ async fn complete_with_language_model(
     };
     authorize_access_to_language_models(&session).await?;
     session
-        .rate_limiter
-        .check::<CompleteWithLanguageModelRateLimit>(session.user_id())
+        .check_rate_limit(proto::CompleteWithLanguageModel::NAME)
         .await?;
 
     if request.model.starts_with("gpt") {
@@ -4377,25 +4661,6 @@ async fn complete_with_anthropic(
     Ok(())
 }
 
-struct CountTokensWithLanguageModelRateLimit;
-
-impl RateLimit for CountTokensWithLanguageModelRateLimit {
-    fn capacity() -> usize {
-        std::env::var("COUNT_TOKENS_WITH_LANGUAGE_MODEL_RATE_LIMIT_PER_HOUR")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(600) // Picked arbitrarily
-    }
-
-    fn refill_duration() -> chrono::Duration {
-        chrono::Duration::hours(1)
-    }
-
-    fn db_name() -> &'static str {
-        "count-tokens-with-language-model"
-    }
-}
-
 async fn count_tokens_with_language_model(
     request: proto::CountTokensWithLanguageModel,
     response: Response<proto::CountTokensWithLanguageModel>,
@@ -4412,8 +4677,7 @@ async fn count_tokens_with_language_model(
     }
 
     session
-        .rate_limiter
-        .check::<CountTokensWithLanguageModelRateLimit>(session.user_id())
+        .check_rate_limit(proto::CountTokensWithLanguageModel::NAME)
         .await?;
 
     let api_key = google_ai_api_key
@@ -4431,25 +4695,6 @@ async fn count_tokens_with_language_model(
     Ok(())
 }
 
-struct ComputeEmbeddingsRateLimit;
-
-impl RateLimit for ComputeEmbeddingsRateLimit {
-    fn capacity() -> usize {
-        std::env::var("EMBED_TEXTS_RATE_LIMIT_PER_HOUR")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(120) // Picked arbitrarily
-    }
-
-    fn refill_duration() -> chrono::Duration {
-        chrono::Duration::hours(1)
-    }
-
-    fn db_name() -> &'static str {
-        "compute-embeddings"
-    }
-}
-
 async fn compute_embeddings(
     request: proto::ComputeEmbeddings,
     response: Response<proto::ComputeEmbeddings>,
@@ -4460,8 +4705,7 @@ async fn compute_embeddings(
     authorize_access_to_language_models(&session).await?;
 
     session
-        .rate_limiter
-        .check::<ComputeEmbeddingsRateLimit>(session.user_id())
+        .check_rate_limit(proto::ComputeEmbeddings::NAME)
         .await?;
 
     let embeddings = match request.model.as_str() {
@@ -4510,25 +4754,6 @@ async fn compute_embeddings(
     Ok(())
 }
 
-struct GetCachedEmbeddingsRateLimit;
-
-impl RateLimit for GetCachedEmbeddingsRateLimit {
-    fn capacity() -> usize {
-        std::env::var("EMBED_TEXTS_RATE_LIMIT_PER_HOUR")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(120) // Picked arbitrarily
-    }
-
-    fn refill_duration() -> chrono::Duration {
-        chrono::Duration::hours(1)
-    }
-
-    fn db_name() -> &'static str {
-        "get-cached-embeddings"
-    }
-}
-
 async fn get_cached_embeddings(
     request: proto::GetCachedEmbeddings,
     response: Response<proto::GetCachedEmbeddings>,
@@ -4537,8 +4762,7 @@ async fn get_cached_embeddings(
     authorize_access_to_language_models(&session).await?;
 
     session
-        .rate_limiter
-        .check::<GetCachedEmbeddingsRateLimit>(session.user_id())
+        .check_rate_limit(proto::GetCachedEmbeddings::NAME)
         .await?;
 
     let db = session.db().await;
@@ -4642,6 +4866,60 @@ async fn get_channel_messages_by_id(
     Ok(())
 }
 
+/// Retrieve the chain of replies that the given chat message belongs to
+async fn get_channel_message_thread(
+    request: proto::GetChannelMessageThread,
+    response: Response<proto::GetChannelMessageThread>,
+    session: UserSession,
+) -> Result<()> {
+    let messages = session
+        .db()
+        .await
+        .get_channel_message_thread(
+            ChannelId::from_proto(request.channel_id),
+            session.user_id(),
+            MessageId::from_proto(request.message_id),
+        )
+        .await?;
+    response.send(proto::GetChannelMessagesResponse {
+        done: true,
+        messages,
+    })?;
+    Ok(())
+}
+
+/// Search the chat messages of a channel
+async fn search_channel_messages(
+    request: proto::SearchChannelMessages,
+    response: Response<proto::SearchChannelMessages>,
+    session: UserSession,
+) -> Result<()> {
+    let query = request.query.trim();
+    if query.is_empty() {
+        return Err(anyhow!("search query can't be blank"))?;
+    }
+    if query.len() > MAX_MESSAGE_LEN {
+        return Err(anyhow!("search query is too long"))?;
+    }
+
+    let messages = session
+        .db()
+        .await
+        .search_channel_messages(
+            ChannelId::from_proto(request.channel_id),
+            session.user_id(),
+            query,
+            MESSAGE_COUNT_PER_PAGE,
+            request.before_message_id.map(MessageId::from_proto),
+        )
+        .await?;
+    response.send(proto::GetChannelMessagesResponse {
+        done: messages.len() < MESSAGE_COUNT_PER_PAGE,
+        messages,
+    })?;
+    Ok(())
+}
+
 /// Retrieve the current users notifications
 async fn get_notifications(
     request: proto::GetNotifications,
@@ -1,4 +1,7 @@
-use crate::db::{self, ChannelRole, NewUserParams};
+use crate::{
+    auth,
+    db::{self, ChannelRole, NewUserParams},
+};
 
 use anyhow::Context;
 use db::Database;
@@ -118,6 +121,22 @@ pub async fn seed(config: &Config, db: &Database, force: bool) -> anyhow::Result
     Ok(())
 }
 
+/// Creates the admin user of a self-hosted server from `ADMIN_LOGIN` and
+/// `ADMIN_PASSWORD`, resetting the password if the user already exists.
+pub async fn seed_self_hosted_admin(config: &Config, db: &Database) -> anyhow::Result<()> {
+    let (Some(login), Some(password)) = (&config.admin_login, &config.admin_password) else {
+        log::info!("no ADMIN_LOGIN and ADMIN_PASSWORD configured, skipping admin seed");
+        return Ok(());
+    };
+
+    let password_hash = auth::hash_password(password).context("failed to hash admin password")?;
+    db.create_or_update_password_user(login, true, &password_hash)
+        .await
+        .context("failed to create admin user")?;
+
+    Ok(())
+}
+
 fn load_admins(path: impl AsRef<Path>) -> anyhow::Result<SeedConfig> {
     let file_content = fs::read_to_string(path)?;
     Ok(serde_json::from_str(&file_content)?)
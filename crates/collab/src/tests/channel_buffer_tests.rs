@@ -512,6 +512,348 @@ async fn test_rejoin_channel_buffer(
     });
 }
 
+#[gpui::test]
+async fn test_channel_buffer_offline_edits(
+    deterministic: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(deterministic.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    let channel_buffer_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    let channel_buffer_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+
+    channel_buffer_a.update(cx_a, |buffer, cx| {
+        buffer.buffer().update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "a")], None, cx);
+        })
+    });
+    deterministic.run_until_parked();
+    let replica_id_a = channel_buffer_a.read_with(cx_a, |buffer, cx| buffer.replica_id(cx));
+
+    // Client A stays disconnected for longer than the reconnect timeout.
+    server.forbid_connections();
+    server.disconnect_client(client_a.peer_id().unwrap());
+    deterministic.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    deterministic.run_until_parked();
+
+    // Client A can still edit the notes while disconnected.
+    channel_buffer_a.update(cx_a, |buffer, cx| {
+        assert!(!buffer.is_connected());
+        buffer.buffer().update(cx, |buffer, cx| {
+            assert!(!buffer.read_only());
+            buffer.edit([(1..1, "b")], None, cx);
+        })
+    });
+    channel_buffer_b.update(cx_b, |buffer, cx| {
+        buffer.buffer().update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "0")], None, cx);
+        })
+    });
+    deterministic.run_until_parked();
+
+    // Client A reconnects, rejoining the buffer with the same replica id.
+    // Both clients see each other's edits.
+    server.allow_connections();
+    deterministic.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    deterministic.run_until_parked();
+
+    channel_buffer_a.read_with(cx_a, |buffer, cx| {
+        assert!(buffer.is_connected());
+        assert_eq!(buffer.replica_id(cx), replica_id_a);
+        assert_eq!(buffer.buffer().read(cx).text(), "0ab");
+    });
+    channel_buffer_b.read_with(cx_b, |buffer, cx| {
+        assert_eq!(buffer.buffer().read(cx).text(), "0ab");
+    });
+    channel_buffer_a.read_with(cx_a, |buffer_a, _| {
+        channel_buffer_b.read_with(cx_b, |buffer_b, _| {
+            assert_eq!(buffer_a.collaborators(), buffer_b.collaborators());
+        });
+    });
+}
+
+#[gpui::test]
+async fn test_channel_buffer_offline_edits_after_buffer_is_closed(
+    deterministic: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(deterministic.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    let channel_buffer_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    let channel_buffer_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+
+    channel_buffer_b.update(cx_b, |buffer, cx| {
+        buffer.buffer().update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "b")], None, cx);
+        })
+    });
+    deterministic.run_until_parked();
+
+    server.forbid_connections();
+    server.disconnect_client(client_a.peer_id().unwrap());
+    deterministic.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    deterministic.run_until_parked();
+
+    // Client A edits the notes while disconnected, then closes them.
+    channel_buffer_a.update(cx_a, |buffer, cx| {
+        buffer.buffer().update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "a")], None, cx);
+        })
+    });
+    drop(channel_buffer_a);
+    deterministic.run_until_parked();
+
+    // Once client A reconnects, its edits are merged.
+    server.allow_connections();
+    deterministic.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    deterministic.run_until_parked();
+
+    channel_buffer_b.read_with(cx_b, |buffer, cx| {
+        assert_eq!(buffer.buffer().read(cx).text(), "ab");
+    });
+
+    // The edits aren't merged a second time when the notes are reopened.
+    let channel_buffer_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    deterministic.run_until_parked();
+    channel_buffer_a.read_with(cx_a, |buffer, cx| {
+        assert_eq!(buffer.buffer().read(cx).text(), "ab");
+    });
+    channel_buffer_b.read_with(cx_b, |buffer, cx| {
+        assert_eq!(buffer.buffer().read(cx).text(), "ab");
+    });
+}
+
+#[gpui::test]
+async fn test_channel_buffer_offline_edits_after_epoch_change(
+    deterministic: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(deterministic.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    let channel_buffer_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    channel_buffer_a.update(cx_a, |buffer, cx| {
+        buffer.buffer().update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "one\ntwo\nthree\n")], None, cx);
+        })
+    });
+    deterministic.run_until_parked();
+    let epoch = channel_buffer_a.read_with(cx_a, |buffer, _| buffer.epoch());
+
+    // Client A is the only collaborator, so the buffer is snapshotted
+    // when it stays disconnected past the reconnect timeout.
+    server.forbid_connections();
+    server.disconnect_client(client_a.peer_id().unwrap());
+    deterministic.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    deterministic.run_until_parked();
+
+    channel_buffer_a.update(cx_a, |buffer, cx| {
+        buffer.buffer().update(cx, |buffer, cx| {
+            buffer.edit([(0..3, "ONE")], None, cx);
+        })
+    });
+
+    // Meanwhile, client B edits a different part of the notes.
+    let channel_buffer_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    channel_buffer_b.update(cx_b, |buffer, cx| {
+        assert_eq!(buffer.epoch(), epoch + 1);
+        buffer.buffer().update(cx, |buffer, cx| {
+            buffer.edit([(8..13, "THREE")], None, cx);
+        })
+    });
+    deterministic.run_until_parked();
+
+    // Client A can't rejoin its copy of the buffer, so it is replaced by
+    // a new copy into which the offline edits are merged.
+    server.allow_connections();
+    deterministic.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    deterministic.run_until_parked();
+
+    channel_buffer_a.read_with(cx_a, |buffer, cx| {
+        assert!(!buffer.is_connected());
+        assert!(buffer.buffer().read(cx).read_only());
+    });
+    channel_buffer_b.read_with(cx_b, |buffer, cx| {
+        assert_eq!(buffer.buffer().read(cx).text(), "ONE\ntwo\nTHREE\n");
+    });
+
+    let channel_buffer_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    deterministic.run_until_parked();
+    channel_buffer_a.read_with(cx_a, |buffer, cx| {
+        assert!(buffer.is_connected());
+        assert_eq!(buffer.buffer().read(cx).text(), "ONE\ntwo\nTHREE\n");
+    });
+}
+
+#[gpui::test]
+async fn test_channel_buffer_history_and_restore(
+    deterministic: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(deterministic.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    // Client A writes the first version, then closes the notes so that
+    // the version is saved.
+    let channel_buffer_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    channel_buffer_a.update(cx_a, |buffer, cx| {
+        buffer.buffer().update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "one\ntwo\n")], None, cx);
+        })
+    });
+    deterministic.run_until_parked();
+    cx_a.update(|_| drop(channel_buffer_a));
+    deterministic.run_until_parked();
+
+    // Client B rewrites the notes.
+    let channel_buffer_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    channel_buffer_b.update(cx_b, |buffer, cx| {
+        assert_eq!(buffer.epoch(), 1);
+        buffer.buffer().update(cx, |buffer, cx| {
+            buffer.edit([(0..3, "uno")], None, cx);
+        })
+    });
+    deterministic.run_until_parked();
+
+    let history = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| {
+            store.channel_notes_history(channel_id, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        history
+            .iter()
+            .map(|version| (
+                version.epoch,
+                version.saved_at.is_some(),
+                version
+                    .authors
+                    .iter()
+                    .map(|author| author.github_login.as_str())
+                    .collect::<Vec<_>>()
+            ))
+            .collect::<Vec<_>>(),
+        &[(1, false, vec!["user_b"]), (0, true, vec!["user_a"])]
+    );
+
+    let text = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| {
+            store.channel_notes_version_text(channel_id, 0, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(text, "one\ntwo\n");
+
+    // Restoring the old version edits the current notes, which client A sees.
+    let channel_buffer_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    deterministic.run_until_parked();
+    channel_buffer_b
+        .update(cx_b, |buffer, cx| buffer.restore_version(text, cx))
+        .await;
+    deterministic.run_until_parked();
+
+    channel_buffer_a.read_with(cx_a, |buffer, cx| {
+        assert_eq!(buffer.epoch(), 1);
+        assert_eq!(buffer.buffer().read(cx).text(), "one\ntwo\n");
+    });
+    channel_buffer_b.read_with(cx_b, |buffer, cx| {
+        assert_eq!(buffer.buffer().read(cx).text(), "one\ntwo\n");
+    });
+}
+
 #[gpui::test]
 async fn test_channel_buffers_and_server_restarts(
     deterministic: BackgroundExecutor,
@@ -7,8 +7,8 @@ use collab_ui::{
 };
 use editor::{Editor, ExcerptRange, MultiBuffer};
 use gpui::{
-    point, BackgroundExecutor, BorrowAppContext, Context, Entity, SharedString, TestAppContext,
-    View, VisualContext, VisualTestContext,
+    point, BackgroundExecutor, BorrowAppContext, Context, Entity, SharedString, Task,
+    TestAppContext, View, VisualContext, VisualTestContext, WindowContext,
 };
 use language::Capability;
 use live_kit_client::MacOSDisplay;
@@ -2148,3 +2148,109 @@ async fn test_following_while_deactivated(cx_a: &mut TestAppContext, cx_b: &mut
         assert_eq!(editor.tab_description(0, cx).unwrap(), "2.js");
     });
 }
+
+#[gpui::test]
+async fn test_following_presenter(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let (_server, client_a, client_b, channel) = TestServer::start2(cx_a, cx_b).await;
+    let peer_id_a = client_a.peer_id().unwrap();
+    let peer_id_b = client_b.peer_id().unwrap();
+
+    let (workspace_a, cx_a) = client_a.build_test_workspace(cx_a).await;
+    join_channel(channel, &client_a, cx_a).await.unwrap();
+    share_workspace(&workspace_a, cx_a).await.unwrap();
+
+    // a opens 1.txt
+    cx_a.simulate_keystrokes("cmd-p 1 enter");
+    cx_a.run_until_parked();
+
+    // b joins the channel and stops following a
+    join_channel(channel, &client_b, cx_b).await.unwrap();
+    cx_b.run_until_parked();
+    let (workspace_b, cx_b) = client_b.active_workspace(cx_b);
+    cx_b.simulate_keystrokes("down");
+    cx_b.run_until_parked();
+    workspace_b.update(cx_b, |workspace, _| {
+        assert_eq!(workspace.leader_for_pane(workspace.active_pane()), None);
+    });
+
+    // a starts presenting, so b follows a again
+    cx_a.update(|cx| collab_ui::toggle_presenting(&Default::default(), cx));
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+    workspace_b.update(cx_b, |workspace, cx| {
+        assert_eq!(
+            workspace.leader_for_pane(workspace.active_pane()),
+            Some(peer_id_a)
+        );
+        let editor = workspace.active_item(cx).unwrap();
+        assert_eq!(editor.tab_description(0, cx).unwrap(), "1.txt");
+    });
+
+    // b can't take over the presentation while a is presenting
+    let present_b = cx_b.update(|cx| set_presenting(true, cx));
+    assert!(present_b.await.is_err());
+    cx_a.run_until_parked();
+    assert_eq!(presenter_id(cx_a), Some(peer_id_a));
+
+    cx_a.simulate_keystrokes("cmd-p 2 enter");
+    cx_a.run_until_parked();
+    workspace_b.update(cx_b, |workspace, cx| {
+        let editor = workspace.active_item(cx).unwrap();
+        assert_eq!(editor.tab_description(0, cx).unwrap(), "2.js");
+    });
+
+    // b opts out of following the presenter
+    cx_b.simulate_keystrokes("down");
+    cx_b.run_until_parked();
+    cx_a.simulate_keystrokes("cmd-p 1 enter");
+    cx_a.run_until_parked();
+    workspace_b.update(cx_b, |workspace, cx| {
+        assert_eq!(workspace.leader_for_pane(workspace.active_pane()), None);
+        let editor = workspace.active_item(cx).unwrap();
+        assert_eq!(editor.tab_description(0, cx).unwrap(), "2.js");
+    });
+
+    // a stops presenting and presents again, and b follows the new presentation
+    cx_a.update(|cx| collab_ui::toggle_presenting(&Default::default(), cx));
+    cx_a.run_until_parked();
+    cx_a.update(|cx| collab_ui::toggle_presenting(&Default::default(), cx));
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+    workspace_b.update(cx_b, |workspace, cx| {
+        assert_eq!(
+            workspace.leader_for_pane(workspace.active_pane()),
+            Some(peer_id_a)
+        );
+        let editor = workspace.active_item(cx).unwrap();
+        assert_eq!(editor.tab_description(0, cx).unwrap(), "1.txt");
+    });
+
+    // when a stops presenting, b stops following
+    cx_a.update(|cx| collab_ui::toggle_presenting(&Default::default(), cx));
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+    workspace_b.update(cx_b, |workspace, _| {
+        assert_eq!(workspace.leader_for_pane(workspace.active_pane()), None);
+    });
+
+    // and then b can present
+    let present_b = cx_b.update(|cx| set_presenting(true, cx));
+    present_b.await.unwrap();
+    cx_a.run_until_parked();
+    assert_eq!(presenter_id(cx_a), Some(peer_id_b));
+}
+
+fn set_presenting(presenting: bool, cx: &mut WindowContext) -> Task<anyhow::Result<()>> {
+    let room = ActiveCall::global(cx).read(cx).room().unwrap().clone();
+    room.update(cx, |room, cx| room.set_presenting(presenting, cx))
+}
+
+fn presenter_id(cx: &mut VisualTestContext) -> Option<PeerId> {
+    cx.update(|cx| {
+        ActiveCall::global(cx)
+            .read(cx)
+            .room()?
+            .read(cx)
+            .presenter_id()
+    })
+}
@@ -4,10 +4,11 @@ use crate::{
         channel_id, following_tests::join_channel, room_participants, rust_lang, RoomParticipants,
         TestClient, TestServer,
     },
+    RateLimitConfig,
 };
 use anyhow::{anyhow, Result};
 use call::{room, ActiveCall, ParticipantLocation, Room};
-use client::{User, RECEIVE_TIMEOUT};
+use client::{proto, ErrorCode, ErrorExt, User, RECEIVE_TIMEOUT};
 use collections::{HashMap, HashSet};
 use fs::{FakeFs, Fs as _, RemoveOptions};
 use futures::{channel::mpsc, StreamExt as _};
@@ -18,8 +19,8 @@ use gpui::{
 };
 use language::{
     language_settings::{AllLanguageSettings, Formatter},
-    tree_sitter_rust, Diagnostic, DiagnosticEntry, FakeLspAdapter, Language, LanguageConfig,
-    LanguageMatcher, LineEnding, OffsetRangeExt, Point, Rope,
+    tree_sitter_rust, Diagnostic, DiagnosticEntry, Encoding, FakeLspAdapter, Language,
+    LanguageConfig, LanguageMatcher, LineEnding, OffsetRangeExt, Point, Rope,
 };
 use live_kit_client::MacOSDisplay;
 use lsp::LanguageServerId;
@@ -3493,7 +3494,12 @@ async fn test_buffer_reloading(
     let new_contents = Rope::from("d\ne\nf");
     client_a
         .fs()
-        .save("/dir/a.txt".as_ref(), &new_contents, LineEnding::Windows)
+        .save(
+            "/dir/a.txt".as_ref(),
+            &new_contents,
+            LineEnding::Windows,
+            Default::default(),
+        )
         .await
         .unwrap();
 
@@ -3507,6 +3513,86 @@ async fn test_buffer_reloading(
     });
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_buffer_encoding(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a.fs().insert_tree("/dir", json!({})).await;
+    // "café" in Latin-1.
+    client_a
+        .fs()
+        .insert_file("/dir/a.txt", b"caf\xe9\n".to_vec())
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/dir", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_remote_project(project_id, cx_b).await;
+
+    // The guest's buffer starts out with the encoding the host detected.
+    let buffer_a = project_a
+        .update(cx_a, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    let buffer_b = project_b
+        .update(cx_b, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    buffer_b.read_with(cx_b, |buffer, _| {
+        assert_eq!(buffer.text(), "café\n");
+        assert_eq!(buffer.encoding().to_string(), "windows-1252");
+    });
+
+    // Reopening the file with another encoding on the host changes it for the guest.
+    buffer_a
+        .update(cx_a, |buffer, cx| {
+            buffer.reload_with_encoding(Encoding::from_name("ISO-8859-15").unwrap(), cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    buffer_b.read_with(cx_b, |buffer, _| {
+        assert_eq!(buffer.text(), "café\n");
+        assert_eq!(buffer.encoding().to_string(), "ISO-8859-15");
+        assert!(!buffer.is_dirty());
+    });
+
+    // So does saving the file with another encoding.
+    buffer_a.update(cx_a, |buffer, cx| {
+        buffer.set_encoding(Encoding::from_name("UTF-8 with BOM").unwrap(), cx)
+    });
+    project_a
+        .update(cx_a, |project, cx| {
+            project.save_buffer(buffer_a.clone(), cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(
+        client_a
+            .fs()
+            .load_bytes("/dir/a.txt".as_ref())
+            .await
+            .unwrap(),
+        b"\xef\xbb\xbfcaf\xc3\xa9\n"
+    );
+    buffer_b.read_with(cx_b, |buffer, _| {
+        assert_eq!(buffer.encoding().to_string(), "UTF-8 with BOM");
+        assert!(!buffer.is_dirty());
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_editing_while_guest_opens_buffer(
     executor: BackgroundExecutor,
@@ -4260,6 +4346,7 @@ async fn test_reloading_buffer_manually(
             "/a/a.rs".as_ref(),
             &Rope::from("let seven = 7;"),
             LineEnding::Unix,
+            Default::default(),
         )
         .await
         .unwrap();
@@ -6405,3 +6492,198 @@ async fn test_preview_tabs(cx: &mut TestAppContext) {
         assert!(!pane.can_navigate_forward());
     });
 }
+
+#[gpui::test]
+async fn test_disconnecting_user_from_server(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let user_a = client_a.current_user_id(cx_a);
+    let user_b = client_b.current_user_id(cx_b);
+
+    let connection_ids = server.disconnect_user(user_a);
+    assert_eq!(connection_ids.len(), 1);
+    executor.run_until_parked();
+
+    let pool = server.connection_pool.lock();
+    assert!(!pool.is_user_online(user_a));
+    assert!(pool.is_user_online(user_b));
+    drop(pool);
+
+    assert!(!server.disconnect(connection_ids[0]));
+}
+
+#[gpui::test]
+async fn test_rate_limited_requests(executor: BackgroundExecutor, cx_a: &mut TestAppContext) {
+    let rate_limits: RateLimitConfig = serde_json::from_value(json!({
+        "messages": {
+            "FuzzySearchUsers": { "capacity": 1, "refill_seconds": 3600 }
+        }
+    }))
+    .unwrap();
+    let mut server = TestServer::start_with_rate_limits(executor.clone(), rate_limits).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+
+    client_a
+        .request(proto::FuzzySearchUsers {
+            query: String::new(),
+        })
+        .await
+        .unwrap();
+
+    // Once the limit is reached, the server responds with a structured error.
+    let error = client_a
+        .request(proto::FuzzySearchUsers {
+            query: String::new(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_code(), ErrorCode::RateLimitExceeded);
+    let retry_after = error.retry_after().unwrap();
+    assert!(retry_after > Duration::from_secs(60 * 59));
+    assert!(retry_after <= Duration::from_secs(60 * 60));
+
+    // The client backs off until the limit is refilled, without sending the request.
+    let error = client_a
+        .request(proto::FuzzySearchUsers {
+            query: String::new(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_code(), ErrorCode::RateLimitExceeded);
+    assert!(error.retry_after().unwrap() <= retry_after);
+
+    // Other requests aren't limited.
+    client_a
+        .request(proto::GetUsers {
+            user_ids: vec![client_a.current_user_id(cx_a).to_proto()],
+        })
+        .await
+        .unwrap();
+}
+
+#[gpui::test]
+async fn test_shared_terminals(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    cx_a.update(terminal::init);
+    cx_b.update(terminal::init);
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "a.txt": "a-contents" }))
+        .await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_remote_project(project_id, cx_b).await;
+
+    let (input_tx, mut input_rx) = mpsc::unbounded();
+    let terminal_a = project_a.update(cx_a, |project, cx| {
+        project.create_fake_terminal(input_tx, cx)
+    });
+    let terminal_id = terminal_a.entity_id().as_u64();
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.append_fake_output(&["hello"], cx)
+    });
+    executor.run_until_parked();
+
+    // Terminals aren't shared until the host shares them.
+    project_a.read_with(cx_a, |project, _| {
+        assert!(!project.is_terminal_shared(&terminal_a))
+    });
+    project_b.read_with(cx_b, |project, _| {
+        assert!(project.shared_terminal(terminal_id).is_none())
+    });
+
+    project_a
+        .update(cx_a, |project, cx| project.share_terminal(&terminal_a, cx))
+        .unwrap();
+    executor.run_until_parked();
+    let terminal_b = project_b.read_with(cx_b, |project, _| {
+        project.shared_terminal(terminal_id).unwrap()
+    });
+    let text_b = terminal_b.read_with(cx_b, |terminal, _| terminal.text_contents());
+    assert!(text_b.contains("hello"));
+    assert_eq!(
+        text_b,
+        terminal_a.read_with(cx_a, |terminal, _| terminal.text_contents())
+    );
+
+    // Later output is streamed to guests.
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.append_fake_output(&["world"], cx)
+    });
+    executor.advance_clock(Duration::from_millis(100));
+    executor.run_until_parked();
+    let text_b = terminal_b.read_with(cx_b, |terminal, _| terminal.text_contents());
+    assert!(text_b.contains("world"));
+    assert_eq!(
+        text_b,
+        terminal_a.read_with(cx_a, |terminal, _| terminal.text_contents())
+    );
+
+    // Guests can only watch the terminal until the host allows them to write to it.
+    assert!(!terminal_b.read_with(cx_b, |terminal, _| terminal.can_write()));
+    client_b
+        .request(proto::SharedTerminalInput {
+            project_id,
+            terminal_id,
+            input: b"ls\n".to_vec(),
+        })
+        .await
+        .unwrap_err();
+    executor.run_until_parked();
+    assert!(input_rx.try_next().is_err());
+
+    let user_id_b = client_b.user_id().unwrap();
+    project_a.update(cx_a, |project, cx| {
+        project.set_shared_terminal_writer(&terminal_a, user_id_b, true, cx)
+    });
+    executor.run_until_parked();
+    assert!(terminal_b.read_with(cx_b, |terminal, _| terminal.can_write()));
+    terminal_b.update(cx_b, |terminal, _| terminal.input("ls\n".into()));
+    executor.run_until_parked();
+    assert_eq!(input_rx.try_next().unwrap().unwrap(), b"ls\n");
+
+    // Writing is forbidden again once the host revokes it.
+    project_a.update(cx_a, |project, cx| {
+        project.set_shared_terminal_writer(&terminal_a, user_id_b, false, cx)
+    });
+    executor.run_until_parked();
+    assert!(!terminal_b.read_with(cx_b, |terminal, _| terminal.can_write()));
+    client_b
+        .request(proto::SharedTerminalInput {
+            project_id,
+            terminal_id,
+            input: b"ls\n".to_vec(),
+        })
+        .await
+        .unwrap_err();
+    executor.run_until_parked();
+    assert!(input_rx.try_next().is_err());
+
+    // When the host stops sharing the terminal, guests no longer see it.
+    project_a.update(cx_a, |project, cx| {
+        project.unshare_terminal(&terminal_a, cx)
+    });
+    executor.run_until_parked();
+    project_b.read_with(cx_b, |project, _| {
+        assert!(project.shared_terminal(terminal_id).is_none())
+    });
+}
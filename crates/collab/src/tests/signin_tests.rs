@@ -0,0 +1,110 @@
+use crate::{
+    api::signin,
+    auth::{self, VerifyAccessTokenResult},
+    tests::TestServer,
+    RateLimitConfig,
+};
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Extension, Router,
+};
+use gpui::BackgroundExecutor;
+use serde_json::json;
+use tower::ServiceExt as _;
+
+#[gpui::test]
+async fn test_native_app_signin(executor: BackgroundExecutor) {
+    let rate_limits: RateLimitConfig = serde_json::from_value(json!({
+        "messages": {
+            "NativeAppSignin": { "capacity": 2, "refill_seconds": 3600 }
+        }
+    }))
+    .unwrap();
+    let server = TestServer::start_with_rate_limits(executor, rate_limits).await;
+    let db = server.app_state.db.clone();
+    let user = db
+        .create_or_update_password_user(
+            "user_a",
+            false,
+            &auth::hash_password("correct-password").unwrap(),
+        )
+        .await
+        .unwrap();
+    let router = signin::router().layer(Extension(server.app_state.clone()));
+    let (public_key, private_key) = rpc::auth::keypair().unwrap();
+    let public_key = String::try_from(public_key).unwrap();
+
+    // The form is only served for keys the client could have generated.
+    assert_eq!(get_signin(&router, &public_key).await, StatusCode::OK);
+    assert_eq!(
+        get_signin(&router, "not-a-key").await,
+        StatusCode::BAD_REQUEST
+    );
+
+    // Unknown logins and wrong passwords are rejected alike.
+    let response = post_signin(&router, &public_key, "user_b", "correct-password").await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = post_signin(&router, &public_key, "user_a", "wrong-password").await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // The right password redirects to the client with an access token only it can decrypt.
+    let response = post_signin(&router, &public_key, "user_a", "correct-password").await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let location = response.headers()[header::LOCATION].to_str().unwrap();
+    let encrypted_access_token = location
+        .strip_prefix(&format!(
+            "http://127.0.0.1:1234?user_id={}&access_token=",
+            user.id
+        ))
+        .unwrap();
+    let access_token = private_key.decrypt_string(encrypted_access_token).unwrap();
+    assert!(matches!(
+        auth::verify_access_token(&access_token, user.id, &db)
+            .await
+            .unwrap(),
+        VerifyAccessTokenResult { is_valid: true, .. }
+    ));
+
+    // Once the account has used up its attempts, even the right password is refused.
+    let response = post_signin(&router, &public_key, "user_a", "correct-password").await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // Attempts on other logins don't count against the account.
+    db.create_or_update_password_user(
+        "user_c",
+        false,
+        &auth::hash_password("other-password").unwrap(),
+    )
+    .await
+    .unwrap();
+    let response = post_signin(&router, &public_key, "user_c", "other-password").await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+}
+
+async fn get_signin(router: &Router, public_key: &str) -> StatusCode {
+    let request = Request::builder()
+        .uri(format!(
+            "/native_app_signin?native_app_port=1234&native_app_public_key={public_key}"
+        ))
+        .body(Body::empty())
+        .unwrap();
+    router.clone().oneshot(request).await.unwrap().status()
+}
+
+async fn post_signin(
+    router: &Router,
+    public_key: &str,
+    login: &str,
+    password: &str,
+) -> axum::response::Response {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/native_app_signin")
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(Body::from(format!(
+            "native_app_port=1234&native_app_public_key={public_key}&login={login}&password={password}"
+        )))
+        .unwrap();
+    router.clone().oneshot(request).await.unwrap()
+}
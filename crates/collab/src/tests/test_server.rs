@@ -3,7 +3,7 @@ use crate::{
     db::{tests::TestDb, NewUserParams, UserId},
     executor::Executor,
     rpc::{Principal, Server, ZedVersion, CLEANUP_TIMEOUT, RECONNECT_TIMEOUT},
-    AppState, Config, RateLimiter,
+    AppState, Config, RateLimitConfig, RateLimiter,
 };
 use anyhow::anyhow;
 use call::ActiveCall;
@@ -77,6 +77,13 @@ pub struct ContactsSummary {
 
 impl TestServer {
     pub async fn start(deterministic: BackgroundExecutor) -> Self {
+        Self::start_with_rate_limits(deterministic, RateLimitConfig::default()).await
+    }
+
+    pub async fn start_with_rate_limits(
+        deterministic: BackgroundExecutor,
+        rate_limits: RateLimitConfig,
+    ) -> Self {
         static NEXT_LIVE_KIT_SERVER_ID: AtomicUsize = AtomicUsize::new(0);
 
         let use_postgres = env::var("USE_POSTGRES").ok();
@@ -95,7 +102,8 @@ impl TestServer {
         )
         .unwrap();
         let executor = Executor::Deterministic(deterministic.clone());
-        let app_state = Self::build_app_state(&test_db, &live_kit_server, executor.clone()).await;
+        let app_state =
+            Self::build_app_state(&test_db, &live_kit_server, executor.clone(), rate_limits).await;
         let epoch = app_state
             .db
             .create_server(&app_state.config.zed_environment)
@@ -618,12 +626,13 @@ impl TestServer {
         test_db: &TestDb,
         live_kit_test_server: &live_kit_client::TestServer,
         executor: Executor,
+        rate_limits: RateLimitConfig,
     ) -> Arc<AppState> {
         Arc::new(AppState {
             db: test_db.db().clone(),
             live_kit_client: Some(Arc::new(live_kit_test_server.create_api_client())),
             blob_store_client: None,
-            rate_limiter: Arc::new(RateLimiter::new(test_db.db().clone())),
+            rate_limiter: Arc::new(RateLimiter::new(test_db.db().clone(), rate_limits)),
             executor,
             clickhouse_client: None,
             config: Config {
@@ -655,6 +664,11 @@ impl TestServer {
                 auto_join_channel_id: None,
                 migrations_path: None,
                 seed_path: None,
+                rate_limits_path: None,
+                self_hosted: None,
+                blob_store_path: None,
+                admin_login: None,
+                admin_password: None,
             },
         })
     }
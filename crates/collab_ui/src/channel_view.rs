@@ -1,3 +1,5 @@
+mod channel_notes_history;
+
 use anyhow::Result;
 use call::report_call_event_for_channel;
 use channel::{Channel, ChannelBuffer, ChannelBufferEvent, ChannelStore};
@@ -30,7 +32,9 @@ use workspace::{
     ItemNavHistory, Pane, SaveIntent, Toast, ViewId, Workspace, WorkspaceId,
 };
 
-actions!(collab, [CopyLink]);
+pub use channel_notes_history::ChannelNotesHistory;
+
+actions!(collab, [CopyLink, OpenNotesHistory]);
 
 pub fn init(cx: &mut AppContext) {
     register_followable_item::<ChannelView>(cx)
@@ -171,10 +175,18 @@ impl ChannelView {
             editor.set_custom_context_menu(move |_, position, cx| {
                 let this = this.clone();
                 Some(ui::ContextMenu::build(cx, move |menu, _| {
+                    let this_for_history = this.clone();
                     menu.entry("Copy link to section", None, move |cx| {
                         this.update(cx, |this, cx| this.copy_link_for_position(position, cx))
                             .ok();
                     })
+                    .entry("Open version history", None, move |cx| {
+                        this_for_history
+                            .update(cx, |this, cx| {
+                                this.open_notes_history(&OpenNotesHistory, cx)
+                            })
+                            .ok();
+                    })
                 }))
             });
             editor
@@ -283,6 +295,37 @@ impl ChannelView {
             .ok();
     }
 
+    fn open_notes_history(&mut self, _: &OpenNotesHistory, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let this = cx.view().clone();
+        let Some(pane) = workspace.read(cx).pane_for(&this) else {
+            return;
+        };
+
+        let existing_history = pane
+            .read(cx)
+            .items_of_type::<ChannelNotesHistory>()
+            .find(|history| history.read(cx).channel_buffer() == &self.channel_buffer);
+        pane.update(cx, |pane, cx| {
+            if let Some(existing_history) = existing_history {
+                if let Some(ix) = pane.index_for_item(&existing_history) {
+                    pane.activate_item(ix, true, true, cx);
+                }
+            } else {
+                let history = cx.new_view(|cx| {
+                    ChannelNotesHistory::new(
+                        self.channel_store.clone(),
+                        self.channel_buffer.clone(),
+                        cx,
+                    )
+                });
+                pane.add_item(Box::new(history), true, true, None, cx);
+            }
+        });
+    }
+
     pub fn channel(&self, cx: &AppContext) -> Option<Arc<Channel>> {
         self.channel_buffer.read(cx).channel(cx)
     }
@@ -294,11 +337,9 @@ impl ChannelView {
         cx: &mut ViewContext<Self>,
     ) {
         match event {
-            ChannelBufferEvent::Disconnected => self.editor.update(cx, |editor, cx| {
-                editor.set_read_only(true);
-                cx.notify();
-            }),
-            ChannelBufferEvent::ChannelChanged => {
+            ChannelBufferEvent::Disconnected
+            | ChannelBufferEvent::Reconnected
+            | ChannelBufferEvent::ChannelChanged => {
                 self.editor.update(cx, |_, cx| {
                     cx.emit(editor::EditorEvent::TitleChanged);
                     cx.notify()
@@ -319,10 +360,58 @@ impl ChannelView {
                     });
                 }
             }
+            ChannelBufferEvent::Reopened(channel_buffer) => {
+                self.replace_channel_buffer(channel_buffer.clone(), cx)
+            }
             ChannelBufferEvent::CollaboratorsChanged => {}
         }
     }
 
+    /// Replaces this view with one for a reopened copy of its channel buffer.
+    fn replace_channel_buffer(
+        &mut self,
+        channel_buffer: Model<ChannelBuffer>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let this = cx.view().clone();
+        let Some(pane) = workspace.read(cx).pane_for(&this) else {
+            return;
+        };
+        let language = self
+            .channel_buffer
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .language()
+            .cloned();
+        channel_buffer.update(cx, |channel_buffer, cx| {
+            channel_buffer.buffer().update(cx, |buffer, cx| {
+                buffer.set_language_registry(workspace.read(cx).app_state().languages.clone());
+                buffer.set_language(language, cx);
+            })
+        });
+
+        let view = cx.new_view(|cx| {
+            Self::new(
+                self.project.clone(),
+                self.workspace.clone(),
+                self.channel_store.clone(),
+                channel_buffer,
+                cx,
+            )
+        });
+        pane.update(cx, |pane, cx| {
+            if let Some(ix) = pane.index_for_item(&this) {
+                pane.add_item(Box::new(view), false, false, Some(ix), cx);
+                pane.close_item_by_id(this.entity_id(), SaveIntent::Skip, cx)
+                    .detach_and_log_err(cx);
+            }
+        });
+    }
+
     fn acknowledge_buffer_version(&mut self, cx: &mut ViewContext<ChannelView>) {
         self.channel_store.update(cx, |store, cx| {
             let channel_buffer = self.channel_buffer.read(cx);
@@ -346,6 +435,7 @@ impl Render for ChannelView {
         div()
             .size_full()
             .on_action(cx.listener(Self::copy_link))
+            .on_action(cx.listener(Self::open_notes_history))
             .child(self.editor.clone())
     }
 }
@@ -382,7 +472,8 @@ impl Item for ChannelView {
             ) {
                 (false, true) => format!("#{}", channel.name),
                 (true, true) => format!("#{} (read-only)", channel.name),
-                (_, false) => format!("#{} (disconnected)", channel.name),
+                (false, false) => format!("#{} (offline)", channel.name),
+                (true, false) => format!("#{} (disconnected)", channel.name),
             }
         } else {
             "channel notes (disconnected)".to_string()
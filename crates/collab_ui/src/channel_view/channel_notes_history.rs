@@ -0,0 +1,403 @@
+use anyhow::Result;
+use channel::{ChannelBuffer, ChannelNotesVersion, ChannelStore};
+use collections::HashMap;
+use editor::{actions::ExpandAllHunkDiffs, Editor, EditorEvent};
+use gpui::{
+    AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Model, Render, Subscription,
+    Task, View, ViewContext, VisualContext as _, WindowContext,
+};
+use language::Buffer;
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, Avatar, Button, Label, Tooltip};
+use util::TryFutureExt;
+use workspace::{
+    item::{Item, ItemEvent, TabContentParams},
+    WorkspaceId,
+};
+
+/// Lists the saved versions of a channel's notes, and shows any one of them,
+/// optionally as a diff against another version.
+pub struct ChannelNotesHistory {
+    channel_store: Model<ChannelStore>,
+    channel_buffer: Model<ChannelBuffer>,
+    versions: Vec<ChannelNotesVersion>,
+    selected_epoch: Option<u64>,
+    /// The version that the selected version is being compared against.
+    compared_epoch: Option<u64>,
+    version_texts: HashMap<u64, String>,
+    editor: Option<View<Editor>>,
+    local_timezone: UtcOffset,
+    focus_handle: FocusHandle,
+    is_loading_history: bool,
+    load_task: Option<Task<Option<()>>>,
+    _editor_subscription: Option<Subscription>,
+}
+
+impl ChannelNotesHistory {
+    pub fn new(
+        channel_store: Model<ChannelStore>,
+        channel_buffer: Model<ChannelBuffer>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let mut this = Self {
+            channel_store,
+            channel_buffer,
+            versions: Vec::new(),
+            selected_epoch: None,
+            compared_epoch: None,
+            version_texts: HashMap::default(),
+            editor: None,
+            local_timezone: cx.local_timezone(),
+            focus_handle: cx.focus_handle(),
+            is_loading_history: false,
+            load_task: None,
+            _editor_subscription: None,
+        };
+        this.refresh(cx);
+        this
+    }
+
+    fn refresh(&mut self, cx: &mut ViewContext<Self>) {
+        let channel_id = self.channel_buffer.read(cx).channel_id;
+        let history = self
+            .channel_store
+            .update(cx, |store, cx| store.channel_notes_history(channel_id, cx));
+        self.is_loading_history = true;
+        cx.spawn(|this, mut cx| async move {
+            let versions = history.await;
+            this.update(&mut cx, |this, cx| {
+                this.is_loading_history = false;
+                let versions = versions?;
+                let current_epoch = versions.first().map(|version| version.epoch);
+                this.versions = versions;
+                if this.selected_epoch.is_none() {
+                    this.select_version(current_epoch, cx);
+                }
+                cx.notify();
+                anyhow::Ok(())
+            })?
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub fn channel_buffer(&self) -> &Model<ChannelBuffer> {
+        &self.channel_buffer
+    }
+
+    fn current_epoch(&self, cx: &AppContext) -> u64 {
+        self.channel_buffer.read(cx).epoch()
+    }
+
+    fn select_version(&mut self, epoch: Option<u64>, cx: &mut ViewContext<Self>) {
+        self.selected_epoch = epoch;
+        if self.compared_epoch == epoch {
+            self.compared_epoch = None;
+        }
+        self.show_selected_version(cx);
+    }
+
+    fn toggle_compared_version(&mut self, epoch: u64, cx: &mut ViewContext<Self>) {
+        if self.compared_epoch == Some(epoch) {
+            self.compared_epoch = None;
+        } else {
+            self.compared_epoch = Some(epoch);
+        }
+        self.show_selected_version(cx);
+    }
+
+    /// Returns the text of the given version. The current version is read from
+    /// the open buffer, since it may include edits not yet saved on the server.
+    fn version_text(&mut self, epoch: u64, cx: &mut ViewContext<Self>) -> Task<Result<String>> {
+        if epoch == self.current_epoch(cx) {
+            let text = self.channel_buffer.read(cx).buffer().read(cx).text();
+            return Task::ready(Ok(text));
+        }
+        if let Some(text) = self.version_texts.get(&epoch) {
+            return Task::ready(Ok(text.clone()));
+        }
+
+        let channel_id = self.channel_buffer.read(cx).channel_id;
+        let text = self.channel_store.update(cx, |store, cx| {
+            store.channel_notes_version_text(channel_id, epoch, cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            let text = text.await?;
+            this.update(&mut cx, |this, _| {
+                this.version_texts.insert(epoch, text.clone());
+            })?;
+            Ok(text)
+        })
+    }
+
+    fn show_selected_version(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(selected_epoch) = self.selected_epoch else {
+            self.editor = None;
+            self._editor_subscription = None;
+            cx.notify();
+            return;
+        };
+        let text = self.version_text(selected_epoch, cx);
+        let base_text = self
+            .compared_epoch
+            .map(|compared_epoch| self.version_text(compared_epoch, cx));
+
+        self.load_task = Some(cx.spawn(|this, mut cx| {
+            async move {
+                let text = text.await?;
+                let base_text = match base_text {
+                    Some(base_text) => Some(base_text.await?),
+                    None => None,
+                };
+                this.update(&mut cx, |this, cx| {
+                    let language = this
+                        .channel_buffer
+                        .read(cx)
+                        .buffer()
+                        .read(cx)
+                        .language()
+                        .cloned();
+                    let buffer = cx.new_model(|cx| {
+                        let mut buffer = Buffer::local(text, cx);
+                        buffer.set_language(language, cx);
+                        buffer.set_diff_base(base_text, cx);
+                        buffer
+                    });
+                    let editor = cx.new_view(|cx| {
+                        let mut editor = Editor::for_buffer(buffer, None, cx);
+                        editor.set_read_only(true);
+                        editor
+                    });
+                    this._editor_subscription =
+                        Some(cx.subscribe(&editor, |_, editor, event: &EditorEvent, cx| {
+                            if let EditorEvent::DiffBaseChanged = event {
+                                editor.update(cx, |editor, cx| {
+                                    editor.expand_all_hunk_diffs(&ExpandAllHunkDiffs, cx)
+                                });
+                            }
+                        }));
+                    this.editor = Some(editor);
+                    cx.notify();
+                })
+            }
+            .log_err()
+        }));
+    }
+
+    /// Restores the selected version by editing the current notes to match it.
+    fn restore_selected_version(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(selected_epoch) = self.selected_epoch else {
+            return;
+        };
+        let text = self.version_text(selected_epoch, cx);
+        let channel_buffer = self.channel_buffer.clone();
+        cx.spawn(|this, mut cx| async move {
+            let text = text.await?;
+            channel_buffer
+                .update(&mut cx, |channel_buffer, cx| {
+                    channel_buffer.restore_version(text, cx)
+                })?
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.compared_epoch = None;
+                this.selected_epoch = None;
+                this.refresh(cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn can_restore(&self, cx: &AppContext) -> bool {
+        self.selected_epoch
+            .map_or(false, |epoch| epoch != self.current_epoch(cx))
+            && !self.channel_buffer.read(cx).buffer().read(cx).read_only()
+    }
+
+    fn render_version(
+        &self,
+        version: &ChannelNotesVersion,
+        now: OffsetDateTime,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let epoch = version.epoch;
+        let is_selected = self.selected_epoch == Some(epoch);
+        let is_compared = self.compared_epoch == Some(epoch);
+        let title = if version.saved_at.is_none() {
+            "Current version".to_string()
+        } else {
+            format!("Version {}", epoch + 1)
+        };
+        let saved_at = version.saved_at.map(|saved_at| {
+            time_format::format_localized_timestamp(
+                saved_at,
+                now,
+                self.local_timezone,
+                time_format::TimestampFormat::Relative,
+            )
+        });
+        let authors = version
+            .authors
+            .iter()
+            .map(|author| author.github_login.clone())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        h_flex()
+            .id(("notes-version", epoch as usize))
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .justify_between()
+            .cursor_pointer()
+            .when(is_selected, |el| {
+                el.bg(cx.theme().colors().element_selected)
+            })
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .on_click(cx.listener(move |this, _, cx| this.select_version(Some(epoch), cx)))
+            .child(
+                v_flex()
+                    .gap_0p5()
+                    .overflow_hidden()
+                    .child(Label::new(title))
+                    .children(saved_at.map(|saved_at| {
+                        Label::new(saved_at)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                    }))
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .children(version.authors.iter().take(3).map(|author| {
+                                Avatar::new(author.avatar_uri.clone()).size(rems(1.))
+                            }))
+                            .child(
+                                Label::new(authors)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    ),
+            )
+            .when(!is_selected, |el| {
+                el.child(
+                    IconButton::new(("compare-notes-version", epoch as usize), IconName::Replace)
+                        .icon_size(IconSize::Small)
+                        .selected(is_compared)
+                        .tooltip(|cx| Tooltip::text("Compare with Selected Version", cx))
+                        .on_click(
+                            cx.listener(move |this, _, cx| this.toggle_compared_version(epoch, cx)),
+                        ),
+                )
+            })
+    }
+}
+
+impl EventEmitter<ItemEvent> for ChannelNotesHistory {}
+
+impl Render for ChannelNotesHistory {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let now = OffsetDateTime::now_utc();
+        let message = if self.is_loading_history && self.versions.is_empty() {
+            Some("Loading version history…")
+        } else {
+            None
+        };
+
+        h_flex()
+            .size_full()
+            .track_focus(&self.focus_handle)
+            .child(
+                v_flex()
+                    .id("notes-versions")
+                    .h_full()
+                    .w(rems(18.))
+                    .flex_none()
+                    .overflow_y_scroll()
+                    .border_r_1()
+                    .border_color(cx.theme().colors().border)
+                    .children(message.map(|message| {
+                        div().p_2().child(
+                            Label::new(message)
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        )
+                    }))
+                    .children(
+                        self.versions
+                            .iter()
+                            .map(|version| self.render_version(version, now, cx))
+                            .collect::<Vec<_>>(),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .size_full()
+                    .child(
+                        h_flex()
+                            .justify_between()
+                            .px_2()
+                            .h(rems(ui::Tab::CONTAINER_HEIGHT_IN_REMS))
+                            .border_b_1()
+                            .border_color(cx.theme().colors().border)
+                            .child(
+                                Label::new(if self.compared_epoch.is_some() {
+                                    "Showing changes from the compared version"
+                                } else {
+                                    "Read-only"
+                                })
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                            )
+                            .child(
+                                Button::new("restore-notes-version", "Restore This Version")
+                                    .disabled(!self.can_restore(cx))
+                                    .on_click(
+                                        cx.listener(|this, _, cx| {
+                                            this.restore_selected_version(cx)
+                                        }),
+                                    ),
+                            ),
+                    )
+                    .children(self.editor.clone()),
+            )
+    }
+}
+
+impl FocusableView for ChannelNotesHistory {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for ChannelNotesHistory {
+    type Event = ItemEvent;
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        let label = match self.channel_buffer.read(cx).channel(cx) {
+            Some(channel) => format!("#{} history", channel.name),
+            None => "channel notes history".to_string(),
+        };
+        Label::new(label)
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn clone_on_split(&self, _: WorkspaceId, _: &mut ViewContext<Self>) -> Option<View<Self>> {
+        None
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
@@ -1,25 +1,31 @@
 use crate::{collab_panel, ChatPanelSettings};
 use anyhow::Result;
 use call::{room, ActiveCall};
-use channel::{ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageId, ChannelStore};
+use channel::{
+    ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageId, ChannelMessageReaction,
+    ChannelStore, MessageParams,
+};
 use client::{ChannelId, Client};
+use code_snippet::SnippetReference;
 use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
-use editor::{actions, Editor};
+use editor::{actions, scroll::Autoscroll, Editor};
 use gpui::{
     actions, div, list, prelude::*, px, Action, AppContext, AsyncWindowContext, ClipboardItem,
     CursorStyle, DismissEvent, ElementId, EventEmitter, FocusHandle, FocusableView, FontWeight,
     HighlightStyle, ListOffset, ListScrollEvent, ListState, Model, Render, Stateful, Subscription,
     Task, View, ViewContext, VisualContext, WeakView,
 };
-use language::LanguageRegistry;
+use language::{Bias, LanguageRegistry, Point};
 use menu::Confirm;
 use message_editor::MessageEditor;
-use project::Fs;
+use message_search::MessageSearch;
+use message_thread::MessageThread;
+use project::{Fs, Item as _, ProjectPath, WorktreeId};
 use rich_text::{Highlight, RichText};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
-use std::{sync::Arc, time::Duration};
+use std::{path::Path, sync::Arc, time::Duration};
 use time::{OffsetDateTime, UtcOffset};
 use ui::{
     popover_menu, prelude::*, Avatar, Button, ContextMenu, IconButton, IconName, KeyBinding, Label,
@@ -28,12 +34,17 @@ use ui::{
 use util::{ResultExt, TryFutureExt};
 use workspace::{
     dock::{DockPosition, Panel, PanelEvent},
-    Workspace,
+    notifications::NotificationId,
+    Toast, Workspace,
 };
 
+mod code_snippet;
 mod message_editor;
+mod message_search;
+mod message_thread;
 
 const MESSAGE_LOADING_THRESHOLD: usize = 50;
+const QUICK_REACTIONS: [&str; 6] = ["👍", "❤️", "😄", "🎉", "👀", "🚀"];
 const CHAT_PANEL_KEY: &str = "ChatPanel";
 
 pub fn init(cx: &mut AppContext) {
@@ -41,11 +52,13 @@ pub fn init(cx: &mut AppContext) {
         workspace.register_action(|workspace, _: &ToggleFocus, cx| {
             workspace.toggle_panel_focus::<ChatPanel>(cx);
         });
+        workspace.register_action(ChatPanel::share_selection_to_channel);
     })
     .detach();
 }
 
 pub struct ChatPanel {
+    workspace: WeakView<Workspace>,
     client: Arc<Client>,
     channel_store: Model<ChannelStore>,
     languages: Arc<LanguageRegistry>,
@@ -64,6 +77,8 @@ pub struct ChatPanel {
     open_context_menu: Option<(u64, Subscription)>,
     highlighted_message: Option<(u64, Task<()>)>,
     last_acknowledged_message_id: Option<u64>,
+    open_thread: Option<MessageThread>,
+    message_search: Option<MessageSearch>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -71,7 +86,7 @@ struct SerializedChatPanel {
     width: Option<Pixels>,
 }
 
-actions!(chat_panel, [ToggleFocus]);
+actions!(chat_panel, [ToggleFocus, ShareSelectionToChannel]);
 
 impl ChatPanel {
     pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
@@ -79,6 +94,7 @@ impl ChatPanel {
         let client = workspace.app_state().client.clone();
         let channel_store = ChannelStore::global(cx);
         let languages = workspace.app_state().languages.clone();
+        let workspace_handle = workspace.weak_handle();
 
         let input_editor = cx.new_view(|cx| {
             MessageEditor::new(
@@ -110,6 +126,7 @@ impl ChatPanel {
             }));
 
             let mut this = Self {
+                workspace: workspace_handle,
                 fs,
                 client,
                 channel_store,
@@ -128,6 +145,8 @@ impl ChatPanel {
                 open_context_menu: None,
                 highlighted_message: None,
                 last_acknowledged_message_id: None,
+                open_thread: None,
+                message_search: None,
             };
 
             if let Some(channel_id) = ActiveCall::global(cx)
@@ -234,6 +253,8 @@ impl ChatPanel {
             let channel_id = chat.read(cx).channel_id;
             {
                 self.markdown_data.clear();
+                self.open_thread = None;
+                self.message_search = None;
 
                 let chat = chat.read(cx);
                 let channel_name = chat.channel(cx).map(|channel| channel.name.clone());
@@ -283,6 +304,7 @@ impl ChatPanel {
                         store.update_latest_message_id(*channel_id, *message_id, cx)
                     })
                 }
+                self.refresh_thread(cx);
             }
         }
         cx.notify();
@@ -445,6 +467,15 @@ impl ChatPanel {
         let replied_to_you =
             reply_to_message.as_ref().map(|m| m.sender.id) == self.client.user_id();
 
+        let is_in_thread = message.reply_to_message_id.is_some()
+            || message_id.map_or(false, |message_id| {
+                active_chat
+                    .read(cx)
+                    .messages()
+                    .iter()
+                    .any(|message| message.reply_to_message_id == Some(message_id))
+            });
+
         let is_highlighted_message = self
             .highlighted_message
             .as_ref()
@@ -527,14 +558,22 @@ impl ChatPanel {
                     )
                     .when(mentioning_you || replied_to_you, |this| this.my_0p5())
                     .map(|el| {
+                        let view = cx.view().downgrade();
                         let text = self.markdown_data.entry(message.id).or_insert_with(|| {
-                            Self::render_markdown_with_mentions(
+                            let mut text = Self::render_markdown_with_mentions(
                                 &self.languages,
                                 self.client.id(),
                                 &message,
                                 self.local_timezone,
                                 cx,
-                            )
+                            );
+                            text.set_link_click_handler(move |url, cx| {
+                                if let Some(reference) = SnippetReference::from_url(url) {
+                                    view.update(cx, |this, cx| this.open_snippet(reference, cx))
+                                        .ok();
+                                }
+                            });
+                            text
                         });
                         el.child(
                             v_flex()
@@ -546,6 +585,11 @@ impl ChatPanel {
                         .when(self.has_open_menu(message_id), |el| {
                             el.bg(cx.theme().colors().element_selected)
                         })
+                    })
+                    .when_some(message_id, |el, message_id| {
+                        el.when(!message.reactions.is_empty(), |el| {
+                            el.child(self.render_reactions(message_id, &message.reactions, cx))
+                        })
                     }),
             )
             .when(
@@ -571,9 +615,110 @@ impl ChatPanel {
                 },
             )
             .child(
-                self.render_popover_buttons(&cx, message_id, can_delete_message, can_edit_message)
-                    .neg_mt_2p5(),
+                self.render_popover_buttons(
+                    &cx,
+                    message_id,
+                    can_delete_message,
+                    can_edit_message,
+                    is_in_thread,
+                )
+                .neg_mt_2p5(),
+            )
+    }
+
+    fn render_reactions(
+        &self,
+        message_id: u64,
+        reactions: &[ChannelMessageReaction],
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let current_user_id = self.client.user_id();
+        h_flex()
+            .flex_wrap()
+            .gap_1()
+            .pt_0p5()
+            .children(reactions.iter().map(|reaction| {
+                let emoji = reaction.emoji.clone();
+                let reacted =
+                    current_user_id.map_or(false, |user_id| reaction.user_ids.contains(&user_id));
+                Button::new(
+                    SharedString::from(format!("reaction-{message_id}-{emoji}")),
+                    format!("{} {}", reaction.emoji, reaction.user_ids.len()),
+                )
+                .style(ButtonStyle::Filled)
+                .label_size(LabelSize::XSmall)
+                .selected(reacted)
+                .on_click(
+                    cx.listener(move |this, _, cx| this.toggle_reaction(message_id, &emoji, cx)),
+                )
+            }))
+    }
+
+    /// Renders a compact, clickable copy of a message for the thread and search views.
+    fn render_message_summary(
+        &self,
+        message: &ChannelMessage,
+        cx: &mut ViewContext<Self>,
+    ) -> Stateful<Div> {
+        let message_id = match message.id {
+            ChannelMessageId::Saved(id) => Some(id),
+            ChannelMessageId::Pending(_) => None,
+        };
+        let element_id: ElementId = match message.id {
+            ChannelMessageId::Saved(id) => ("message-summary", id).into(),
+            ChannelMessageId::Pending(id) => ("pending-message-summary", id).into(),
+        };
+
+        v_flex()
+            .id(element_id)
+            .w_full()
+            .px_1p5()
+            .py_1()
+            .rounded_md()
+            .cursor(CursorStyle::PointingHand)
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .text_ui_sm(cx)
+                    .child(Avatar::new(message.sender.avatar_uri.clone()).size(rems(1.)))
+                    .child(div().font_weight(FontWeight::BOLD).child(
+                        Label::new(message.sender.github_login.clone()).size(LabelSize::Small),
+                    ))
+                    .child(
+                        Label::new(time_format::format_localized_timestamp(
+                            message.timestamp,
+                            OffsetDateTime::now_utc(),
+                            self.local_timezone,
+                            time_format::TimestampFormat::EnhancedAbsolute,
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
             )
+            .child(div().text_ui_sm(cx).child(message.body.clone()))
+            .when_some(message_id, |el, message_id| {
+                el.on_click(cx.listener(move |this, _, cx| this.jump_to_message(message_id, cx)))
+            })
+    }
+
+    /// Leaves the thread or search view and scrolls the chat history to the given message.
+    fn jump_to_message(&mut self, message_id: u64, cx: &mut ViewContext<Self>) {
+        let Some(channel_id) = self.channel_id(cx) else {
+            return;
+        };
+        self.open_thread = None;
+        self.message_search = None;
+        self.select_channel(channel_id, Some(message_id), cx)
+            .detach_and_log_err(cx);
+        cx.notify();
+    }
+
+    fn toggle_reaction(&mut self, message_id: u64, emoji: &str, cx: &mut ViewContext<Self>) {
+        if let Some((chat, _)) = self.active_chat.as_ref() {
+            chat.update(cx, |chat, cx| chat.toggle_reaction(message_id, emoji, cx))
+                .detach_and_log_err(cx);
+        }
     }
 
     fn has_open_menu(&self, message_id: Option<u64>) -> bool {
@@ -597,6 +742,7 @@ impl ChatPanel {
         message_id: Option<u64>,
         can_delete_message: bool,
         can_edit_message: bool,
+        is_in_thread: bool,
     ) -> Div {
         h_flex()
             .absolute()
@@ -630,6 +776,25 @@ impl ChatPanel {
                     ),
                 )
             })
+            .when_some(message_id, |el, message_id| {
+                let this = cx.view().clone();
+
+                el.child(
+                    self.render_popover_button(
+                        cx,
+                        div()
+                            .child(
+                                popover_menu(("react-menu", message_id))
+                                    .trigger(IconButton::new(("react", message_id), IconName::Plus))
+                                    .menu(move |cx| {
+                                        Some(Self::render_reaction_menu(&this, message_id, cx))
+                                    }),
+                            )
+                            .id("react")
+                            .tooltip(|cx| Tooltip::text("Add reaction", cx)),
+                    ),
+                )
+            })
             .when_some(message_id, |el, message_id| {
                 el.when(can_edit_message, |el| {
                     el.child(
@@ -694,6 +859,7 @@ impl ChatPanel {
                                             &this,
                                             message_id,
                                             can_delete_message,
+                                            is_in_thread,
                                             cx,
                                         ))
                                     }),
@@ -705,10 +871,36 @@ impl ChatPanel {
             })
     }
 
+    fn render_reaction_menu(
+        this: &View<Self>,
+        message_id: u64,
+        cx: &mut WindowContext,
+    ) -> View<ContextMenu> {
+        let menu = ContextMenu::build(cx, move |menu, cx| {
+            QUICK_REACTIONS.into_iter().fold(menu, |menu, emoji| {
+                menu.entry(
+                    emoji,
+                    None,
+                    cx.handler_for(&this, move |this, cx| {
+                        this.toggle_reaction(message_id, emoji, cx)
+                    }),
+                )
+            })
+        });
+        this.update(cx, |this, cx| {
+            let subscription = cx.subscribe(&menu, |this: &mut Self, _, _: &DismissEvent, _| {
+                this.open_context_menu = None;
+            });
+            this.open_context_menu = Some((message_id, subscription));
+        });
+        menu
+    }
+
     fn render_message_menu(
         this: &View<Self>,
         message_id: u64,
         can_delete_message: bool,
+        is_in_thread: bool,
         cx: &mut WindowContext,
     ) -> View<ContextMenu> {
         let menu = {
@@ -725,6 +917,13 @@ impl ChatPanel {
                         }
                     }),
                 )
+                .when(is_in_thread, |menu| {
+                    menu.entry(
+                        "View thread",
+                        None,
+                        cx.handler_for(&this, move |this, cx| this.open_thread(message_id, cx)),
+                    )
+                })
                 .when(can_delete_message, |menu| {
                     menu.entry(
                         "Delete message",
@@ -743,6 +942,142 @@ impl ChatPanel {
         menu
     }
 
+    /// Posts the active editor's selected lines to the open channel chat as a
+    /// snippet that links back to its location in the shared project.
+    fn share_selection_to_channel(
+        workspace: &mut Workspace,
+        _: &ShareSelectionToChannel,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        struct ShareSelectionToast;
+
+        let chat = workspace
+            .panel::<ChatPanel>(cx)
+            .and_then(|panel| panel.read(cx).active_chat());
+        let project_id = workspace.project().read(cx).remote_id();
+        let editor = workspace.active_item_as::<Editor>(cx);
+        let error = match (&chat, project_id, &editor) {
+            (None, _, _) => Some("Open a channel's chat to share code with it."),
+            (_, None, _) => Some("Share your project to share code from it."),
+            (_, _, None) => Some("Select some code in an editor to share it."),
+            _ => None,
+        };
+        if let Some(error) = error {
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<ShareSelectionToast>(), error),
+                cx,
+            );
+            return;
+        }
+        let (Some(chat), Some(project_id), Some(editor)) = (chat, project_id, editor) else {
+            return;
+        };
+
+        let snippet = editor.update(cx, |editor, cx| {
+            let buffer = editor.buffer().read(cx).as_singleton()?;
+            let selection = editor.selections.newest::<Point>(cx);
+            let buffer = buffer.read(cx);
+            let project_path = buffer.project_path(cx)?;
+
+            let start_row = selection.start.row;
+            let mut end_row = selection.end.row;
+            if end_row > start_row && selection.end.column == 0 {
+                end_row -= 1;
+            }
+            let code = buffer
+                .text_for_range(
+                    Point::new(start_row, 0)..Point::new(end_row, buffer.line_len(end_row)),
+                )
+                .collect::<String>();
+            let language_name = buffer.language().map(|language| language.name());
+            Some((project_path, start_row..end_row + 1, code, language_name))
+        });
+        let Some((project_path, rows, code, language_name)) = snippet else {
+            return;
+        };
+
+        let sha = workspace
+            .project()
+            .read(cx)
+            .get_repo(&project_path, cx)
+            .and_then(|repo| repo.lock().head_sha());
+        let reference = SnippetReference {
+            project_id,
+            worktree_id: project_path.worktree_id.to_proto(),
+            path: project_path.path.to_string_lossy().into_owned(),
+            rows,
+            sha,
+        };
+        let text = code_snippet::snippet_message(&reference, language_name.as_deref(), &code);
+
+        if let Some(task) = chat
+            .update(cx, |chat, cx| {
+                chat.send_message(
+                    MessageParams {
+                        text,
+                        mentions: Vec::new(),
+                        reply_to_message_id: None,
+                    },
+                    cx,
+                )
+            })
+            .log_err()
+        {
+            task.detach();
+        }
+        workspace.open_panel::<ChatPanel>(cx);
+    }
+
+    /// Opens the location referenced by a shared snippet, if it is in the
+    /// project that this workspace has joined.
+    fn open_snippet(&mut self, reference: SnippetReference, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let open_path = workspace.update(cx, |workspace, cx| {
+            if workspace.project().read(cx).remote_id() != Some(reference.project_id) {
+                struct OpenSnippetToast;
+
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<OpenSnippetToast>(),
+                        "Join the shared project to open this snippet.",
+                    ),
+                    cx,
+                );
+                return None;
+            }
+            let project_path = ProjectPath {
+                worktree_id: WorktreeId::from_proto(reference.worktree_id),
+                path: Arc::from(Path::new(&reference.path)),
+            };
+            Some(workspace.open_path(project_path, None, true, cx))
+        });
+        let Some(open_path) = open_path else {
+            return;
+        };
+
+        cx.spawn(|_, mut cx| async move {
+            let item = open_path.await?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                editor.update(&mut cx, |editor, cx| {
+                    let snapshot = editor.buffer().read(cx).snapshot(cx);
+                    let start =
+                        snapshot.clip_point(Point::new(reference.rows.start, 0), Bias::Left);
+                    let end = snapshot.clip_point(
+                        Point::new(reference.rows.end.saturating_sub(1), u32::MAX),
+                        Bias::Left,
+                    );
+                    editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                        s.select_ranges([start..end]);
+                    });
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn render_markdown_with_mentions(
         language_registry: &Arc<LanguageRegistry>,
         current_user_id: u64,
@@ -798,7 +1133,7 @@ impl ChatPanel {
 
     fn send(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
         if let Some((chat, _)) = self.active_chat.as_ref() {
-            let message = self
+            let mut message = self
                 .message_editor
                 .update(cx, |editor, cx| editor.take_message(cx));
 
@@ -814,11 +1149,28 @@ impl ChatPanel {
                     task.detach();
                 }
             } else {
+                // Messages sent while a thread is open continue the thread.
+                if message.reply_to_message_id.is_none() {
+                    message.reply_to_message_id = self
+                        .open_thread
+                        .as_ref()
+                        .and_then(|thread| thread.last_message_id());
+                }
+                let is_thread_reply = message.reply_to_message_id.is_some();
+
                 if let Some(task) = chat
                     .update(cx, |chat, cx| chat.send_message(message, cx))
                     .log_err()
                 {
-                    task.detach();
+                    if is_thread_reply && self.open_thread.is_some() {
+                        cx.spawn(|this, mut cx| async move {
+                            task.await?;
+                            this.update(&mut cx, |this, cx| this.refresh_thread(cx))
+                        })
+                        .detach_and_log_err(cx);
+                    } else {
+                        task.detach();
+                    }
                 }
             }
         }
@@ -949,24 +1301,39 @@ impl Render for ChatPanel {
             .on_action(cx.listener(Self::send))
             .child(
                 h_flex().child(
-                    TabBar::new("chat_header").child(
-                        h_flex()
-                            .w_full()
-                            .h(rems(ui::Tab::CONTAINER_HEIGHT_IN_REMS))
-                            .px_2()
-                            .child(Label::new(
-                                self.active_chat
-                                    .as_ref()
-                                    .and_then(|c| {
-                                        Some(format!("#{}", c.0.read(cx).channel(cx)?.name))
-                                    })
-                                    .unwrap_or("Chat".to_string()),
-                            )),
-                    ),
+                    TabBar::new("chat_header")
+                        .child(
+                            h_flex()
+                                .w_full()
+                                .h(rems(ui::Tab::CONTAINER_HEIGHT_IN_REMS))
+                                .px_2()
+                                .child(Label::new(
+                                    self.active_chat
+                                        .as_ref()
+                                        .and_then(|c| {
+                                            Some(format!("#{}", c.0.read(cx).channel(cx)?.name))
+                                        })
+                                        .unwrap_or("Chat".to_string()),
+                                )),
+                        )
+                        .when(self.active_chat.is_some(), |el| {
+                            el.end_child(
+                                IconButton::new("toggle-message-search", IconName::MagnifyingGlass)
+                                    .selected(self.message_search.is_some())
+                                    .tooltip(|cx| Tooltip::text("Search messages", cx))
+                                    .on_click(
+                                        cx.listener(|this, _, cx| this.toggle_message_search(cx)),
+                                    ),
+                            )
+                        }),
                 ),
             )
             .child(div().flex_grow().px_2().map(|this| {
-                if self.active_chat.is_some() {
+                if self.message_search.is_some() {
+                    this.child(self.render_message_search(cx))
+                } else if self.open_thread.is_some() {
+                    this.child(self.render_thread(cx))
+                } else if self.active_chat.is_some() {
                     this.child(list(self.message_list.clone()).size_full())
                 } else {
                     this.child(
@@ -1194,6 +1561,7 @@ mod tests {
             mentions: vec![(ranges[0].clone(), 101), (ranges[1].clone(), 102)],
             reply_to_message_id: None,
             edited_at: None,
+            reactions: Vec::new(),
         };
 
         let message = ChatPanel::render_markdown_with_mentions(
@@ -1248,6 +1616,7 @@ mod tests {
             mentions: Vec::new(),
             reply_to_message_id: None,
             edited_at: None,
+            reactions: Vec::new(),
         };
 
         let message = ChatPanel::render_markdown_with_mentions(
@@ -1295,6 +1664,7 @@ mod tests {
             mentions: Vec::new(),
             reply_to_message_id: None,
             edited_at: None,
+            reactions: Vec::new(),
         };
 
         let message = ChatPanel::render_markdown_with_mentions(
@@ -0,0 +1,186 @@
+use rpc::MAX_MESSAGE_LEN;
+use std::ops::Range;
+use url::Url;
+
+const SNIPPET_URL: &str = "zed://snippet";
+
+/// Identifies a range of lines in a shared project, as referenced by a code
+/// snippet posted to a channel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct SnippetReference {
+    pub project_id: u64,
+    pub worktree_id: u64,
+    pub path: String,
+    /// Zero-based rows, exclusive of the end row.
+    pub rows: Range<u32>,
+    pub sha: Option<String>,
+}
+
+impl SnippetReference {
+    pub fn to_url(&self) -> String {
+        let mut url = Url::parse(SNIPPET_URL).unwrap();
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("project", &self.project_id.to_string())
+                .append_pair("worktree", &self.worktree_id.to_string())
+                .append_pair("path", &self.path)
+                .append_pair("start", &(self.rows.start + 1).to_string())
+                .append_pair("end", &self.rows.end.to_string());
+            if let Some(sha) = &self.sha {
+                query.append_pair("sha", sha);
+            }
+        }
+        url.to_string()
+    }
+
+    pub fn from_url(url: &str) -> Option<Self> {
+        if !url.starts_with(SNIPPET_URL) {
+            return None;
+        }
+        let url = Url::parse(url).ok()?;
+
+        let mut project_id = None;
+        let mut worktree_id = None;
+        let mut path = None;
+        let mut start = None;
+        let mut end = None;
+        let mut sha = None;
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "project" => project_id = value.parse().ok(),
+                "worktree" => worktree_id = value.parse().ok(),
+                "path" => path = Some(value.into_owned()),
+                "start" => start = value.parse::<u32>().ok(),
+                "end" => end = value.parse::<u32>().ok(),
+                "sha" => sha = Some(value.into_owned()),
+                _ => {}
+            }
+        }
+
+        let start = start?.checked_sub(1)?;
+        let end = end?.max(start + 1);
+        Some(Self {
+            project_id: project_id?,
+            worktree_id: worktree_id?,
+            path: path?,
+            rows: start..end,
+            sha,
+        })
+    }
+
+    fn label(&self) -> String {
+        let mut label = if self.rows.len() > 1 {
+            format!("{}:{}-{}", self.path, self.rows.start + 1, self.rows.end)
+        } else {
+            format!("{}:{}", self.path, self.rows.start + 1)
+        };
+        if let Some(sha) = &self.sha {
+            label.push_str(" @ ");
+            label.extend(sha.chars().take(7));
+        }
+        label
+    }
+}
+
+/// Formats a message that links to the reference and shows its code in a
+/// fenced block, dropping trailing lines if the message would be too long.
+pub(super) fn snippet_message(
+    reference: &SnippetReference,
+    language_name: Option<&str>,
+    code: &str,
+) -> String {
+    let header = format!(
+        "`{}` [Open in shared project]({})\n",
+        reference.label().replace('`', "'"),
+        reference.to_url()
+    );
+
+    // Use a fence longer than any run of backticks in the code.
+    let mut longest_run = 0;
+    let mut run = 0;
+    for c in code.chars() {
+        if c == '`' {
+            run += 1;
+            longest_run = longest_run.max(run);
+        } else {
+            run = 0;
+        }
+    }
+    let fence = "`".repeat((longest_run + 1).max(3));
+
+    let mut lines = code.lines().collect::<Vec<_>>();
+    loop {
+        let mut message = header.clone();
+        message.push_str(&fence);
+        message.push_str(language_name.unwrap_or_default());
+        message.push('\n');
+        for line in &lines {
+            message.push_str(line);
+            message.push('\n');
+        }
+        message.push_str(&fence);
+
+        if message.len() <= MAX_MESSAGE_LEN || lines.is_empty() {
+            return message;
+        }
+        lines.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet_reference_round_trip() {
+        let reference = SnippetReference {
+            project_id: 7,
+            worktree_id: 2,
+            path: "src/a b/[main].rs".into(),
+            rows: 9..12,
+            sha: Some("0123456789abcdef".into()),
+        };
+        let message = snippet_message(&reference, Some("Rust"), "fn main() {\n    ```\n}");
+        assert_eq!(
+            message.lines().next().unwrap(),
+            format!(
+                "`src/a b/[main].rs:10-12 @ 0123456` [Open in shared project]({})",
+                reference.to_url()
+            )
+        );
+        assert!(message.contains("\n````Rust\nfn main() {\n    ```\n}\n````"));
+        assert_eq!(
+            SnippetReference::from_url(&reference.to_url()),
+            Some(reference)
+        );
+
+        assert_eq!(SnippetReference::from_url("https://zed.dev"), None);
+        assert_eq!(
+            SnippetReference::from_url(
+                "zed://snippet?project=1&worktree=1&path=a.rs&start=0&end=1"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_long_snippets_are_truncated() {
+        let reference = SnippetReference {
+            project_id: 1,
+            worktree_id: 1,
+            path: "a.txt".into(),
+            rows: 0..200,
+            sha: None,
+        };
+        let code = (0..200)
+            .map(|ix| format!("line {ix}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let message = snippet_message(&reference, None, &code);
+        assert!(message.len() <= MAX_MESSAGE_LEN);
+        assert!(message.contains("\nline 0\n"));
+        assert!(message.ends_with("\n```"));
+        assert!(message.starts_with("`a.txt:1-200` [Open in shared project]("));
+    }
+}
@@ -0,0 +1,153 @@
+use super::ChatPanel;
+use channel::ChannelMessage;
+use editor::Editor;
+use gpui::{div, prelude::*, FocusableView, Subscription, Task, View, ViewContext};
+use std::time::Duration;
+use ui::{prelude::*, v_flex, Button, Label};
+use util::ResultExt;
+
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Server-side full-text search over the active channel's messages.
+pub(super) struct MessageSearch {
+    query_editor: View<Editor>,
+    results: Vec<ChannelMessage>,
+    done: bool,
+    pending_search: Task<()>,
+    _subscription: Subscription,
+}
+
+impl ChatPanel {
+    pub(super) fn toggle_message_search(&mut self, cx: &mut ViewContext<Self>) {
+        if self.message_search.take().is_some() {
+            self.message_editor.focus_handle(cx).focus(cx);
+            cx.notify();
+            return;
+        }
+
+        let query_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Search messages…", cx);
+            editor
+        });
+        let subscription = cx.subscribe(&query_editor, |this: &mut Self, _, event, cx| {
+            if let editor::EditorEvent::BufferEdited = event {
+                this.search_messages(false, cx);
+            }
+        });
+        query_editor.focus_handle(cx).focus(cx);
+
+        self.open_thread = None;
+        self.message_search = Some(MessageSearch {
+            query_editor,
+            results: Vec::new(),
+            done: true,
+            pending_search: Task::ready(()),
+            _subscription: subscription,
+        });
+        cx.notify();
+    }
+
+    fn search_messages(&mut self, load_more: bool, cx: &mut ViewContext<Self>) {
+        let Some(chat) = self.active_chat() else {
+            return;
+        };
+        let Some(search) = self.message_search.as_mut() else {
+            return;
+        };
+
+        let query = search.query_editor.read(cx).text(cx).trim().to_string();
+        if query.is_empty() {
+            search.results.clear();
+            search.done = true;
+            search.pending_search = Task::ready(());
+            cx.notify();
+            return;
+        }
+
+        let before_message_id = if load_more {
+            search.results.last().and_then(|message| message.id.into())
+        } else {
+            None
+        };
+
+        search.pending_search = cx.spawn(|this, mut cx| async move {
+            if !load_more {
+                cx.background_executor().timer(SEARCH_DEBOUNCE).await;
+            }
+            let Some(task) = chat
+                .update(&mut cx, |chat, cx| {
+                    chat.search_messages(query, before_message_id, cx)
+                })
+                .log_err()
+            else {
+                return;
+            };
+            let Some((messages, done)) = task.await.log_err() else {
+                return;
+            };
+            this.update(&mut cx, |this, cx| {
+                if let Some(search) = this.message_search.as_mut() {
+                    if !load_more {
+                        search.results.clear();
+                    }
+                    search.results.extend(messages);
+                    search.done = done;
+                    cx.notify();
+                }
+            })
+            .ok();
+        });
+    }
+
+    pub(super) fn render_message_search(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let Some(search) = self.message_search.as_ref() else {
+            return div();
+        };
+        let has_query = !search.query_editor.read(cx).text(cx).trim().is_empty();
+
+        div().size_full().child(
+            v_flex()
+                .size_full()
+                .child(
+                    div()
+                        .py_1()
+                        .border_b_1()
+                        .border_color(cx.theme().colors().border)
+                        .child(search.query_editor.clone()),
+                )
+                .child(
+                    v_flex()
+                        .id("message-search-results")
+                        .size_full()
+                        .overflow_y_scroll()
+                        .when(has_query && search.results.is_empty(), |el| {
+                            el.child(
+                                div().p_2().child(
+                                    Label::new("No matching messages.")
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                ),
+                            )
+                        })
+                        .children(
+                            search
+                                .results
+                                .iter()
+                                .map(|message| self.render_message_summary(message, cx)),
+                        )
+                        .when(!search.done, |el| {
+                            el.child(
+                                div().py_1().child(
+                                    Button::new("load-more-search-results", "Load more")
+                                        .full_width()
+                                        .on_click(cx.listener(|this, _, cx| {
+                                            this.search_messages(true, cx)
+                                        })),
+                                ),
+                            )
+                        }),
+                ),
+        )
+    }
+}
@@ -0,0 +1,133 @@
+use super::ChatPanel;
+use channel::{ChannelMessage, ChannelMessageId};
+use gpui::{div, prelude::*, Task, ViewContext};
+use ui::{h_flex, prelude::*, v_flex, IconButton, IconName, Label, Tooltip};
+use util::ResultExt;
+
+/// A reply chain shown in place of the chat's history.
+pub(super) struct MessageThread {
+    message_id: u64,
+    messages: Vec<ChannelMessage>,
+    _load_messages: Task<()>,
+}
+
+impl MessageThread {
+    pub(super) fn last_message_id(&self) -> Option<u64> {
+        self.messages
+            .iter()
+            .rev()
+            .find_map(|message| message.id.into())
+    }
+}
+
+impl ChatPanel {
+    /// Shows the reply chain that the given message belongs to.
+    pub(super) fn open_thread(&mut self, message_id: u64, cx: &mut ViewContext<Self>) {
+        let Some(chat) = self.active_chat() else {
+            return;
+        };
+        let load_messages = chat.update(cx, |chat, cx| chat.load_thread(message_id, cx));
+        let messages = self
+            .open_thread
+            .take()
+            .filter(|thread| thread.message_id == message_id)
+            .map(|thread| thread.messages)
+            .unwrap_or_default();
+
+        self.message_search = None;
+        self.open_thread = Some(MessageThread {
+            message_id,
+            messages,
+            _load_messages: cx.spawn(|this, mut cx| async move {
+                let Some(messages) = load_messages.await.log_err() else {
+                    return;
+                };
+                this.update(&mut cx, |this, cx| {
+                    if let Some(thread) = this.open_thread.as_mut() {
+                        if thread.message_id == message_id {
+                            thread.messages = messages;
+                            cx.notify();
+                        }
+                    }
+                })
+                .ok();
+            }),
+        });
+        cx.notify();
+    }
+
+    pub(super) fn refresh_thread(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(message_id) = self.open_thread.as_ref().map(|thread| thread.message_id) {
+            self.open_thread(message_id, cx);
+        }
+    }
+
+    fn close_thread(&mut self, cx: &mut ViewContext<Self>) {
+        self.open_thread = None;
+        cx.notify();
+    }
+
+    pub(super) fn render_thread(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let chat = self.active_chat();
+        let messages = self
+            .open_thread
+            .as_ref()
+            .map(|thread| {
+                thread
+                    .messages
+                    .iter()
+                    .map(|message| {
+                        // Prefer the loaded copy of a message, which is kept up to date.
+                        let loaded_message = match (&chat, message.id) {
+                            (Some(chat), ChannelMessageId::Saved(id)) => {
+                                chat.read(cx).find_loaded_message(id).cloned()
+                            }
+                            _ => None,
+                        };
+                        loaded_message.unwrap_or_else(|| message.clone())
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        v_flex()
+            .size_full()
+            .child(
+                h_flex()
+                    .py_1()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new("Thread").size(LabelSize::Small))
+                    .child(
+                        IconButton::new("close-thread", IconName::Close)
+                            .shape(ui::IconButtonShape::Square)
+                            .tooltip(|cx| Tooltip::text("Close thread", cx))
+                            .on_click(cx.listener(|this, _, cx| this.close_thread(cx))),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .id("thread-messages")
+                    .size_full()
+                    .overflow_y_scroll()
+                    .children(messages.iter().map(|message| {
+                        let message_id = match message.id {
+                            ChannelMessageId::Saved(id) => Some(id),
+                            ChannelMessageId::Pending(_) => None,
+                        };
+                        div()
+                            .child(self.render_message_summary(message, cx))
+                            .when_some(message_id, |el, message_id| {
+                                el.when(!message.reactions.is_empty(), |el| {
+                                    el.child(self.render_reactions(
+                                        message_id,
+                                        &message.reactions,
+                                        cx,
+                                    ))
+                                })
+                            })
+                    })),
+            )
+    }
+}
@@ -178,6 +178,10 @@ impl Render for CollabTitlebarItem {
                         let is_screen_sharing = room.is_screen_sharing();
                         let can_use_microphone = room.can_use_microphone();
                         let can_share_projects = room.can_share_projects();
+                        let is_channel_room = room.channel_id().is_some();
+                        let is_presenting = room.is_presenting();
+                        let someone_else_is_presenting =
+                            room.presenter_id().is_some() && !is_presenting;
 
                         this.when(
                             (is_local || is_remote_project) && can_share_projects,
@@ -213,6 +217,38 @@ impl Render for CollabTitlebarItem {
                                 )
                             },
                         )
+                        .when(is_channel_room && can_share_projects, |this| {
+                            this.child(
+                                Button::new(
+                                    "toggle_presenting",
+                                    if is_presenting {
+                                        "Stop Presenting"
+                                    } else {
+                                        "Present"
+                                    },
+                                )
+                                .tooltip(move |cx| {
+                                    Tooltip::text(
+                                        if is_presenting {
+                                            "Stop asking participants to follow you"
+                                        } else if someone_else_is_presenting {
+                                            "Someone else is presenting"
+                                        } else {
+                                            "Ask everyone in the channel to follow you"
+                                        },
+                                        cx,
+                                    )
+                                })
+                                .style(ButtonStyle::Subtle)
+                                .selected_style(ButtonStyle::Tinted(TintColor::Accent))
+                                .selected(is_presenting)
+                                .disabled(someone_else_is_presenting)
+                                .label_size(LabelSize::Small)
+                                .on_click(move |_, cx| {
+                                    crate::toggle_presenting(&Default::default(), cx)
+                                }),
+                            )
+                        })
                         .child(
                             div()
                                 .child(
@@ -6,6 +6,7 @@ mod face_pile;
 pub mod notification_panel;
 pub mod notifications;
 mod panel_settings;
+pub mod review_panel;
 
 use std::{rc::Rc, sync::Arc};
 
@@ -18,20 +19,27 @@ use gpui::{
 };
 use panel_settings::MessageEditorSettings;
 pub use panel_settings::{
-    ChatPanelSettings, CollaborationPanelSettings, NotificationPanelSettings,
+    ChatPanelSettings, CollaborationPanelSettings, NotificationPanelSettings, ReviewPanelSettings,
 };
 use settings::Settings;
 use workspace::{notifications::DetachAndPromptErr, AppState};
 
 actions!(
     collab,
-    [ToggleScreenSharing, ToggleMute, ToggleDeafen, LeaveCall]
+    [
+        ToggleScreenSharing,
+        ToggleMute,
+        ToggleDeafen,
+        TogglePresenting,
+        LeaveCall
+    ]
 );
 
 pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
     CollaborationPanelSettings::register(cx);
     ChatPanelSettings::register(cx);
     NotificationPanelSettings::register(cx);
+    ReviewPanelSettings::register(cx);
     MessageEditorSettings::register(cx);
 
     vcs_menu::init(cx);
@@ -40,6 +48,7 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
     channel_view::init(cx);
     chat_panel::init(cx);
     notification_panel::init(cx);
+    review_panel::init(cx);
     notifications::init(&app_state, cx);
 }
 
@@ -70,6 +79,28 @@ pub fn toggle_screen_sharing(_: &ToggleScreenSharing, cx: &mut WindowContext) {
     }
 }
 
+pub fn toggle_presenting(_: &TogglePresenting, cx: &mut WindowContext) {
+    let call = ActiveCall::global(cx).read(cx);
+    if let Some(room) = call.room().cloned() {
+        let client = call.client();
+        let toggle_presenting = room.update(cx, |room, cx| {
+            let presenting = !room.is_presenting();
+            report_call_event_for_room(
+                if presenting {
+                    "start presenting"
+                } else {
+                    "stop presenting"
+                },
+                room.id(),
+                room.channel_id(),
+                &client,
+            );
+            room.set_presenting(presenting, cx)
+        });
+        toggle_presenting.detach_and_prompt_err("Presenting Failed", cx, |_, _| None);
+    }
+}
+
 pub fn toggle_mute(_: &ToggleMute, cx: &mut AppContext) {
     let call = ActiveCall::global(cx).read(cx);
     if let Some(room) = call.room().cloned() {
@@ -405,6 +405,23 @@ impl NotificationPanel {
                     can_navigate: true,
                 })
             }
+            Notification::ReviewCommentMention {
+                sender_id,
+                ref path,
+                ..
+            } => {
+                let sender = user_store.get_cached_user(sender_id)?;
+                Some(NotificationPresenter {
+                    icon: "icons/conversations.svg",
+                    text: format!(
+                        "{} mentioned you in a review comment on {path}",
+                        sender.github_login
+                    ),
+                    needs_response: false,
+                    actor: Some(sender),
+                    can_navigate: false,
+                })
+            }
         }
     }
 
@@ -415,7 +432,8 @@ impl NotificationPanel {
         cx: &mut ViewContext<Self>,
     ) {
         let should_mark_as_read = match notification {
-            Notification::ContactRequestAccepted { .. } => true,
+            Notification::ContactRequestAccepted { .. }
+            | Notification::ReviewCommentMention { .. } => true,
             Notification::ContactRequest { .. }
             | Notification::ChannelInvitation { .. }
             | Notification::ChannelMessageMention { .. } => false,
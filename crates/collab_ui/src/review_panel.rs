@@ -0,0 +1,414 @@
+use crate::ReviewPanelSettings;
+use anyhow::Result;
+use client::UserStore;
+use db::kvp::KEY_VALUE_STORE;
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{
+    actions, div, AppContext, AsyncWindowContext, CursorStyle, EventEmitter, FocusHandle,
+    FocusableView, InteractiveElement, IntoElement, Model, ParentElement, Render,
+    StatefulInteractiveElement, Styled, Subscription, Task, View, ViewContext, VisualContext,
+    WeakView, WindowContext,
+};
+use language::Bias;
+use project::{Fs, Project, ReviewThread};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use std::sync::Arc;
+use time::{OffsetDateTime, UtcOffset};
+use ui::{h_flex, prelude::*, v_flex, Avatar, Icon, IconButton, IconName, Label, Tooltip};
+use util::ResultExt;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+const REVIEW_PANEL_KEY: &str = "ReviewPanel";
+
+/// Lists the review threads of the project shared in the current call.
+pub struct ReviewPanel {
+    fs: Arc<dyn Fs>,
+    project: Model<Project>,
+    user_store: Model<UserStore>,
+    workspace: WeakView<Workspace>,
+    width: Option<Pixels>,
+    show_resolved: bool,
+    local_timezone: UtcOffset,
+    focus_handle: FocusHandle,
+    pending_serialization: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedReviewPanel {
+    width: Option<Pixels>,
+    #[serde(default)]
+    show_resolved: bool,
+}
+
+actions!(review_panel, [ToggleFocus]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<ReviewPanel>(cx);
+        });
+    })
+    .detach();
+}
+
+impl ReviewPanel {
+    pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let fs = workspace.app_state().fs.clone();
+        let user_store = workspace.app_state().user_store.clone();
+        let project = workspace.project().clone();
+        let workspace_handle = workspace.weak_handle();
+
+        cx.new_view(|cx: &mut ViewContext<Self>| {
+            let subscriptions = vec![
+                cx.subscribe(&project, |this, _, event, cx| {
+                    if let project::Event::ReviewThreadsUpdated = event {
+                        this.fetch_authors(cx);
+                        cx.notify();
+                    }
+                }),
+                cx.observe(&user_store, |_, _, cx| cx.notify()),
+                cx.observe_global::<SettingsStore>(|_: &mut Self, cx| cx.notify()),
+            ];
+
+            let mut this = Self {
+                fs,
+                project,
+                user_store,
+                workspace: workspace_handle,
+                width: None,
+                show_resolved: false,
+                local_timezone: cx.local_timezone(),
+                focus_handle: cx.focus_handle(),
+                pending_serialization: Task::ready(None),
+                _subscriptions: subscriptions,
+            };
+            this.fetch_authors(cx);
+            this
+        })
+    }
+
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialized_panel = if let Some(panel) = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(REVIEW_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedReviewPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|w| w.round());
+                        panel.show_resolved = serialized_panel.show_resolved;
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        let show_resolved = self.show_resolved;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        REVIEW_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedReviewPanel {
+                            width,
+                            show_resolved,
+                        })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn fetch_authors(&mut self, cx: &mut ViewContext<Self>) {
+        let user_ids = self
+            .project
+            .read(cx)
+            .review_threads()
+            .iter()
+            .flat_map(|thread| thread.comments.iter().map(|comment| comment.author_id))
+            .collect();
+        self.user_store
+            .update(cx, |user_store, cx| user_store.get_users(user_ids, cx))
+            .detach_and_log_err(cx);
+    }
+
+    fn toggle_show_resolved(&mut self, cx: &mut ViewContext<Self>) {
+        self.show_resolved = !self.show_resolved;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn open_thread(&mut self, thread_id: u64, cx: &mut ViewContext<Self>) {
+        let Some(thread) = self.project.read(cx).review_thread(thread_id) else {
+            return;
+        };
+        let path = thread.path.clone();
+        let point = thread.range(cx).start;
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let open_path = workspace.update(cx, |workspace, cx| {
+            workspace.open_path(path, None, true, cx)
+        });
+        cx.spawn(|_, mut cx| async move {
+            let item = open_path.await?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                editor.update(&mut cx, |editor, cx| {
+                    let snapshot = editor.buffer().read(cx).snapshot(cx);
+                    let point = snapshot.clip_point(point, Bias::Left);
+                    editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                        s.select_ranges([point..point]);
+                    });
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_thread(
+        &self,
+        thread: &ReviewThread,
+        now: OffsetDateTime,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let thread_id = thread.id;
+        let user_store = self.user_store.read(cx);
+        let author = thread
+            .author_id()
+            .and_then(|author_id| user_store.get_cached_user(author_id));
+        let first_comment = thread.comments.first();
+        let reply_count = thread.comments.len().saturating_sub(1);
+        let location = format!(
+            "{}:{}",
+            thread.path.path.to_string_lossy(),
+            thread.range(cx).start.row + 1
+        );
+        let timestamp = first_comment.map(|comment| {
+            time_format::format_localized_timestamp(
+                comment.timestamp,
+                now,
+                self.local_timezone,
+                time_format::TimestampFormat::Relative,
+            )
+        });
+
+        div()
+            .id(("review-thread", thread_id as usize))
+            .flex()
+            .flex_row()
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .cursor(CursorStyle::PointingHand)
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .on_click(cx.listener(move |this, _, cx| this.open_thread(thread_id, cx)))
+            .children(
+                author
+                    .as_ref()
+                    .map(|author| Avatar::new(author.avatar_uri.clone())),
+            )
+            .child(
+                v_flex()
+                    .gap_0p5()
+                    .w_full()
+                    .overflow_hidden()
+                    .child(
+                        h_flex()
+                            .justify_between()
+                            .child(Label::new(location).size(LabelSize::Small))
+                            .when(thread.resolved, |el| {
+                                el.child(
+                                    Icon::new(IconName::Check)
+                                        .size(IconSize::Small)
+                                        .color(Color::Muted),
+                                )
+                            }),
+                    )
+                    .children(
+                        first_comment.map(|comment| Label::new(comment.body.replace('\n', " "))),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .children(author.map(|author| {
+                                Label::new(author.github_login.clone())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                            }))
+                            .children(timestamp.map(|timestamp| {
+                                Label::new(timestamp)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                            }))
+                            .when(reply_count > 0, |el| {
+                                el.child(
+                                    Label::new(if reply_count == 1 {
+                                        "1 reply".to_string()
+                                    } else {
+                                        format!("{reply_count} replies")
+                                    })
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                                )
+                            }),
+                    ),
+            )
+    }
+}
+
+impl Render for ReviewPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let now = OffsetDateTime::now_utc();
+        let project = self.project.read(cx);
+        let is_shared = project.remote_id().is_some();
+        let threads = project
+            .review_threads()
+            .iter()
+            .filter(|thread| self.show_resolved || !thread.resolved)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .size_full()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .px_2()
+                    .py_1()
+                    // Match the height of the tab bar so they line up.
+                    .h(rems(ui::Tab::CONTAINER_HEIGHT_IN_REMS))
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new("Review Threads"))
+                    .child(
+                        IconButton::new("show-resolved", IconName::Check)
+                            .icon_size(IconSize::Small)
+                            .selected(self.show_resolved)
+                            .tooltip(|cx| Tooltip::text("Show Resolved Threads", cx))
+                            .on_click(cx.listener(|this, _, cx| this.toggle_show_resolved(cx))),
+                    ),
+            )
+            .map(|this| {
+                let message = if !is_shared {
+                    Some("Share a project in a call to review it with your collaborators.")
+                } else if threads.is_empty() {
+                    Some("There are no open review threads.")
+                } else {
+                    None
+                };
+                if let Some(message) = message {
+                    this.child(
+                        v_flex().p_4().child(
+                            div().flex().w_full().items_center().child(
+                                Label::new(message)
+                                    .color(Color::Muted)
+                                    .size(LabelSize::Small),
+                            ),
+                        ),
+                    )
+                } else {
+                    this.child(
+                        v_flex()
+                            .id("review-threads")
+                            .size_full()
+                            .overflow_y_scroll()
+                            .children(
+                                threads
+                                    .iter()
+                                    .map(|thread| self.render_thread(thread, now, cx)),
+                            ),
+                    )
+                }
+            })
+    }
+}
+
+impl FocusableView for ReviewPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for ReviewPanel {}
+
+impl Panel for ReviewPanel {
+    fn persistent_name() -> &'static str {
+        "ReviewPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        ReviewPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<ReviewPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| ReviewPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        Some(IconName::MessageBubbles).filter(|_| ReviewPanelSettings::get_global(cx).button)
+    }
+
+    fn icon_tooltip(&self, _cx: &WindowContext) -> Option<&'static str> {
+        Some("Review Panel")
+    }
+
+    fn icon_label(&self, cx: &WindowContext) -> Option<String> {
+        let open_count = self
+            .project
+            .read(cx)
+            .review_threads()
+            .iter()
+            .filter(|thread| !thread.resolved)
+            .count();
+        (open_count > 0).then(|| open_count.to_string())
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+}
@@ -616,6 +616,10 @@ impl Copilot {
     }
 
     pub fn register_buffer(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        // Large files would be sent to the server in full on every change.
+        if buffer.read(cx).is_large_file() {
+            return;
+        }
         let weak_buffer = buffer.downgrade();
         self.buffers.insert(weak_buffer.clone());
 
@@ -1247,7 +1251,7 @@ mod tests {
             self.abs_path.clone()
         }
 
-        fn load(&self, _: &AppContext) -> Task<Result<String>> {
+        fn load(&self, _: language::Encoding, _: &AppContext) -> Task<Result<String>> {
             unimplemented!()
         }
 
@@ -1256,6 +1260,7 @@ mod tests {
             _: BufferId,
             _: &clock::Global,
             _: language::LineEnding,
+            _: language::Encoding,
             _: Option<std::time::SystemTime>,
             _: &mut AppContext,
         ) {
@@ -0,0 +1,171 @@
+use sqlez::{domain::Domain, thread_safe_connection::ThreadSafeConnection};
+use sqlez_macros::sql;
+use std::ops::Deref;
+
+use crate::query;
+
+/// Edits to channel notes that were made while disconnected from the server,
+/// kept until they have been merged into the channel's notes.
+pub struct ChannelNotesDb(ThreadSafeConnection<ChannelNotesDb>);
+
+impl Deref for ChannelNotesDb {
+    type Target = ThreadSafeConnection<ChannelNotesDb>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Clone for ChannelNotesDb {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl Domain for ChannelNotesDb {
+    fn name() -> &'static str {
+        "ChannelNotesDb"
+    }
+
+    fn migrations() -> &'static [&'static str] {
+        &[sql!(
+            CREATE TABLE offline_channel_buffers(
+                user_id INTEGER NOT NULL,
+                channel_id INTEGER NOT NULL,
+                epoch INTEGER NOT NULL,
+                replica_id INTEGER NOT NULL,
+                base_text TEXT NOT NULL,
+                text TEXT NOT NULL,
+                PRIMARY KEY(user_id, channel_id)
+            ) STRICT;
+
+            CREATE TABLE offline_channel_buffer_operations(
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL,
+                channel_id INTEGER NOT NULL,
+                operation BLOB NOT NULL,
+                FOREIGN KEY(user_id, channel_id)
+                    REFERENCES offline_channel_buffers(user_id, channel_id)
+                    ON DELETE CASCADE
+            ) STRICT;
+        )]
+    }
+}
+
+impl ChannelNotesDb {
+    /// Opens the database. In tests, every call opens a separate in-memory
+    /// database, so that clients simulated in the same process don't share
+    /// their offline edits.
+    pub async fn open() -> Self {
+        #[cfg(any(test, feature = "test-support"))]
+        {
+            use std::sync::atomic::{AtomicUsize, Ordering};
+
+            static NEXT_DB_ID: AtomicUsize = AtomicUsize::new(0);
+            let db_id = NEXT_DB_ID.fetch_add(1, Ordering::SeqCst);
+            Self(crate::open_test_db(&format!("ChannelNotesDb-{db_id}")).await)
+        }
+
+        #[cfg(not(any(test, feature = "test-support")))]
+        {
+            Self(crate::open_db(&crate::DB_DIR, &crate::RELEASE_CHANNEL).await)
+        }
+    }
+
+    query! {
+        pub fn offline_channel_ids(user_id: u64) -> Result<Vec<u64>> {
+            SELECT channel_id FROM offline_channel_buffers WHERE user_id = (?)
+        }
+    }
+
+    query! {
+        pub fn offline_buffer(user_id: u64, channel_id: u64) -> Result<Option<(u64, u32, String, String)>> {
+            SELECT epoch, replica_id, base_text, text
+            FROM offline_channel_buffers
+            WHERE user_id = (?) AND channel_id = (?)
+        }
+    }
+
+    query! {
+        pub fn offline_operations(user_id: u64, channel_id: u64) -> Result<Vec<Vec<u8>>> {
+            SELECT operation
+            FROM offline_channel_buffer_operations
+            WHERE user_id = (?) AND channel_id = (?)
+            ORDER BY id
+        }
+    }
+
+    /// Records the text of a buffer that has been edited offline. The epoch,
+    /// replica id and base text are only recorded by the first call after the
+    /// buffer was last synchronized.
+    pub async fn save_offline_buffer(
+        &self,
+        user_id: u64,
+        channel_id: u64,
+        epoch: u64,
+        replica_id: u32,
+        base_text: String,
+        text: String,
+    ) -> anyhow::Result<()> {
+        self.write(move |connection| {
+            connection.exec_bound::<(u64, u64, u64, u32, String, String)>(sql!(
+                INSERT INTO offline_channel_buffers(
+                    user_id, channel_id, epoch, replica_id, base_text, text
+                )
+                VALUES ((?), (?), (?), (?), (?), (?))
+                ON CONFLICT(user_id, channel_id) DO UPDATE SET text = excluded.text
+            ))?((user_id, channel_id, epoch, replica_id, base_text, text))
+        })
+        .await
+    }
+
+    query! {
+        pub async fn save_offline_operation(user_id: u64, channel_id: u64, operation: Vec<u8>) -> Result<()> {
+            INSERT INTO offline_channel_buffer_operations(user_id, channel_id, operation)
+            VALUES ((?), (?), (?))
+        }
+    }
+
+    query! {
+        pub async fn delete_offline_buffer(user_id: u64, channel_id: u64) -> Result<()> {
+            DELETE FROM offline_channel_buffers WHERE user_id = (?) AND channel_id = (?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChannelNotesDb;
+
+    #[gpui::test]
+    async fn test_offline_channel_buffers() {
+        let db = ChannelNotesDb::open().await;
+
+        assert_eq!(db.offline_buffer(1, 2).unwrap(), None);
+        assert!(db.offline_channel_ids(1).unwrap().is_empty());
+
+        db.save_offline_buffer(1, 2, 3, 4, "base".into(), "base 1".into())
+            .await
+            .unwrap();
+        db.save_offline_operation(1, 2, vec![1]).await.unwrap();
+        db.save_offline_buffer(1, 2, 5, 6, "other".into(), "base 12".into())
+            .await
+            .unwrap();
+        db.save_offline_operation(1, 2, vec![2]).await.unwrap();
+        db.save_offline_buffer(7, 2, 0, 0, "".into(), "a".into())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            db.offline_buffer(1, 2).unwrap(),
+            Some((3, 4, "base".to_string(), "base 12".to_string()))
+        );
+        assert_eq!(db.offline_operations(1, 2).unwrap(), vec![vec![1], vec![2]]);
+        assert_eq!(db.offline_channel_ids(1).unwrap(), vec![2]);
+
+        db.delete_offline_buffer(1, 2).await.unwrap();
+        assert_eq!(db.offline_buffer(1, 2).unwrap(), None);
+        assert!(db.offline_operations(1, 2).unwrap().is_empty());
+        assert_eq!(db.offline_channel_ids(7).unwrap(), vec![2]);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod channel_notes;
 pub mod kvp;
 pub mod query;
 
@@ -5,7 +5,7 @@ mod toolbar_controls;
 #[cfg(test)]
 mod diagnostics_tests;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use collections::{BTreeSet, HashSet};
 use editor::{
     diagnostic_block_renderer,
@@ -30,6 +30,7 @@ use language::{
 use lsp::LanguageServerId;
 use project::{DiagnosticSummary, Project, ProjectPath};
 use project_diagnostics_settings::ProjectDiagnosticsSettings;
+use rpc::proto::{self, PeerId};
 use settings::Settings;
 use std::{
     any::{Any, TypeId},
@@ -42,14 +43,17 @@ pub use toolbar_controls::ToolbarControls;
 use ui::{h_flex, prelude::*, Icon, IconName, Label};
 use util::ResultExt;
 use workspace::{
-    item::{BreadcrumbText, Item, ItemEvent, ItemHandle, TabContentParams},
-    ItemNavHistory, Pane, ToolbarItemLocation, Workspace,
+    item::{
+        BreadcrumbText, FollowEvent, FollowableItem, Item, ItemEvent, ItemHandle, TabContentParams,
+    },
+    ItemNavHistory, Pane, ToolbarItemLocation, ViewId, Workspace,
 };
 
 actions!(diagnostics, [Deploy, ToggleWarnings]);
 
 pub fn init(cx: &mut AppContext) {
     ProjectDiagnosticsSettings::register(cx);
+    workspace::register_followable_item::<ProjectDiagnosticsEditor>(cx);
     cx.observe_new_views(ProjectDiagnosticsEditor::register)
         .detach();
 }
@@ -65,6 +69,7 @@ struct ProjectDiagnosticsEditor {
     paths_to_update: BTreeSet<(ProjectPath, LanguageServerId)>,
     include_warnings: bool,
     context: u32,
+    remote_id: Option<ViewId>,
     update_paths_tx: UnboundedSender<(ProjectPath, Option<LanguageServerId>)>,
     _update_excerpts_task: Task<Result<()>>,
     _subscription: Subscription,
@@ -193,6 +198,7 @@ impl ProjectDiagnosticsEditor {
             path_states: Default::default(),
             paths_to_update: Default::default(),
             include_warnings: ProjectDiagnosticsSettings::get_global(cx).include_warnings,
+            remote_id: None,
             update_paths_tx: update_excerpts_tx,
             _update_excerpts_task: cx.spawn(move |this, mut cx| async move {
                 while let Some((path, language_server_id)) = update_excerpts_rx.next().await {
@@ -240,8 +246,17 @@ impl ProjectDiagnosticsEditor {
     }
 
     fn toggle_warnings(&mut self, _: &ToggleWarnings, cx: &mut ViewContext<Self>) {
-        self.include_warnings = !self.include_warnings;
+        self.set_include_warnings(!self.include_warnings, cx);
+    }
+
+    fn set_include_warnings(&mut self, include_warnings: bool, cx: &mut ViewContext<Self>) {
+        if self.include_warnings == include_warnings {
+            return;
+        }
+        self.include_warnings = include_warnings;
         self.enqueue_update_all_excerpts(cx);
+        // Followers pick up the new filter on title changes.
+        cx.emit(EditorEvent::TitleChanged);
         cx.notify();
     }
 
@@ -792,6 +807,99 @@ impl Item for ProjectDiagnosticsEditor {
     }
 }
 
+impl FollowableItem for ProjectDiagnosticsEditor {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, _: &WindowContext) -> Option<proto::view::Variant> {
+        Some(proto::view::Variant::Diagnostics(
+            proto::view::Diagnostics {
+                include_warnings: self.include_warnings,
+            },
+        ))
+    }
+
+    fn from_state_proto(
+        pane: View<Pane>,
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<Result<View<Self>>>> {
+        let Some(proto::view::Variant::Diagnostics(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::Diagnostics(state)) = state.take() else {
+            unreachable!()
+        };
+
+        let existing = pane
+            .read(cx)
+            .items_of_type::<Self>()
+            .find(|view| view.read(cx).remote_id == Some(remote_id));
+        let view = existing.unwrap_or_else(|| {
+            let project = workspace.read(cx).project().clone();
+            let workspace = workspace.downgrade();
+            cx.new_view(|cx| {
+                let mut this = Self::new(project, workspace, cx);
+                this.remote_id = Some(remote_id);
+                this
+            })
+        });
+        view.update(cx, |this, cx| {
+            this.set_include_warnings(state.include_warnings, cx)
+        });
+        Some(Task::ready(Ok(view)))
+    }
+
+    fn to_follow_event(event: &EditorEvent) -> Option<FollowEvent> {
+        Editor::to_follow_event(event)
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        event: &EditorEvent,
+        update: &mut Option<proto::update_view::Variant>,
+        _: &WindowContext,
+    ) -> bool {
+        match event {
+            EditorEvent::TitleChanged => {
+                *update = Some(proto::update_view::Variant::Diagnostics(
+                    proto::view::Diagnostics {
+                        include_warnings: self.include_warnings,
+                    },
+                ));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        _: &Model<Project>,
+        message: proto::update_view::Variant,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let proto::update_view::Variant::Diagnostics(state) = message else {
+            return Task::ready(Err(anyhow!("unexpected update view variant")));
+        };
+        self.set_include_warnings(state.include_warnings, cx);
+        Task::ready(Ok(()))
+    }
+
+    fn is_project_item(&self, _: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, leader_peer_id: Option<PeerId>, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| {
+            editor.set_leader_peer_id(leader_peer_id, cx)
+        });
+    }
+}
+
 fn diagnostic_header_renderer(diagnostic: Diagnostic) -> RenderBlock {
     let (message, code_ranges) = highlight_diagnostic_message(&diagnostic);
     let message: SharedString = message;
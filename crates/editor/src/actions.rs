@@ -143,11 +143,15 @@ gpui::actions!(
     [
         AcceptPartialCopilotSuggestion,
         AcceptPartialInlineCompletion,
+        AddReviewComment,
         AddSelectionAbove,
         AddSelectionBelow,
         Backspace,
         Cancel,
+        CancelReviewComment,
+        ClearBookmarks,
         ConfirmRename,
+        ConfirmReviewComment,
         ContextMenuFirst,
         ContextMenuLast,
         ContextMenuNext,
@@ -184,14 +188,18 @@ gpui::actions!(
         Fold,
         FoldSelectedRanges,
         Format,
+        GoToBookmark,
         GoToDefinition,
         GoToDefinitionSplit,
         GoToDiagnostic,
         GoToHunk,
         GoToImplementation,
         GoToImplementationSplit,
+        GoToPrevBookmark,
         GoToPrevDiagnostic,
         GoToPrevHunk,
+        GoToPrevProjectBookmark,
+        GoToProjectBookmark,
         GoToTypeDefinition,
         GoToTypeDefinitionSplit,
         HalfPageDown,
@@ -221,6 +229,7 @@ gpui::actions!(
         NewlineBelow,
         NextInlineCompletion,
         NextScreen,
+        OpenBookmarks,
         OpenExcerpts,
         OpenExcerptsSplit,
         OpenPermalinkToLine,
@@ -266,6 +275,7 @@ gpui::actions!(
         SplitSelectionIntoLines,
         Tab,
         TabPrev,
+        ToggleBookmark,
         ToggleGitBlame,
         ToggleGitBlameInline,
         ToggleHunkDiff,
@@ -0,0 +1,668 @@
+use std::{
+    mem,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use collections::{hash_map, HashMap};
+use futures::future::join_all;
+use gpui::{
+    AppContext, Context as _, EntityId, Global, Model, ModelContext, SharedString, Subscription,
+    Task, ViewContext, WeakModel,
+};
+use itertools::Itertools;
+use language::{Buffer, Location};
+use multi_buffer::MultiBufferSnapshot;
+use text::{Bias, Point, ToPoint as _};
+use util::ResultExt;
+use workspace::{Workspace, WorkspaceId};
+
+use crate::{
+    persistence::DB, scroll::Autoscroll, ClearBookmarks, Direction, Editor, GoToBookmark,
+    GoToPrevBookmark, GoToPrevProjectBookmark, GoToProjectBookmark, OpenBookmarks, ToggleBookmark,
+};
+
+/// A line that the user marked to come back to.
+///
+/// Bookmarks are identified by the path of their file so that they can be persisted and listed
+/// before the file is opened, and by an anchor while their buffer is open so that they follow
+/// edits.
+#[derive(Clone, Debug)]
+pub struct Bookmark {
+    pub abs_path: Arc<Path>,
+    pub label: Option<SharedString>,
+    /// The row of the bookmark when its buffer was last seen.
+    row: u32,
+    /// The row of the bookmark in its file as last saved, which is the row that is persisted.
+    saved_row: u32,
+    anchor: Option<(WeakModel<Buffer>, text::Anchor)>,
+}
+
+impl Bookmark {
+    /// The current row of the bookmark in its file.
+    pub fn row(&self, cx: &AppContext) -> u32 {
+        match self.buffer_anchor(cx) {
+            Some((buffer, anchor)) => anchor.to_point(buffer.read(cx)).row,
+            None => self.row,
+        }
+    }
+
+    /// The buffer of the bookmark and its position in it, if the buffer is open.
+    pub fn buffer_anchor(&self, cx: &AppContext) -> Option<(Model<Buffer>, text::Anchor)> {
+        let (buffer, anchor) = self.anchor.as_ref()?;
+        Some((buffer.upgrade()?, *anchor))
+    }
+
+    fn anchor_in(&self, buffer: &Buffer) -> text::Anchor {
+        match &self.anchor {
+            Some((_, anchor)) => *anchor,
+            None => buffer.anchor_before(buffer.clip_point(Point::new(self.row, 0), Bias::Left)),
+        }
+    }
+
+    fn is_in_buffer(&self, buffer: &Model<Buffer>, abs_path: Option<&Path>) -> bool {
+        match &self.anchor {
+            Some((bookmark_buffer, _)) => bookmark_buffer.entity_id() == buffer.entity_id(),
+            None => abs_path == Some(&*self.abs_path),
+        }
+    }
+}
+
+/// The bookmarks of a workspace, persisted in the workspace database.
+///
+/// Only files on disk can be bookmarked, as bookmarks are stored by path.
+pub struct BookmarkStore {
+    workspace_id: WorkspaceId,
+    bookmarks: Vec<Bookmark>,
+    buffer_subscriptions: HashMap<EntityId, (WeakModel<Buffer>, [Subscription; 2])>,
+    load_bookmarks: Option<Task<()>>,
+    serialize_bookmarks: Option<Task<()>>,
+}
+
+#[derive(Default)]
+struct GlobalBookmarkStores(HashMap<WorkspaceId, Model<BookmarkStore>>);
+
+impl Global for GlobalBookmarkStores {}
+
+pub(crate) fn init(cx: &mut AppContext) {
+    cx.observe_new_views(
+        |workspace: &mut Workspace, cx: &mut ViewContext<Workspace>| {
+            let workspace_id = workspace.database_id();
+            let store = cx.new_model(|cx| BookmarkStore::load(workspace_id, cx));
+            cx.default_global::<GlobalBookmarkStores>()
+                .0
+                .insert(workspace_id, store);
+            cx.on_release(move |_, _, cx| {
+                cx.default_global::<GlobalBookmarkStores>()
+                    .0
+                    .remove(&workspace_id);
+            })
+            .detach();
+
+            workspace.register_action(|workspace, _: &GoToProjectBookmark, cx| {
+                go_to_project_bookmark(workspace, Direction::Next, cx)
+            });
+            workspace.register_action(|workspace, _: &GoToPrevProjectBookmark, cx| {
+                go_to_project_bookmark(workspace, Direction::Prev, cx)
+            });
+            workspace.register_action(open_bookmarks);
+            workspace.register_action(|workspace, _: &ClearBookmarks, cx| {
+                if let Some(store) = BookmarkStore::for_workspace(workspace.database_id(), cx) {
+                    store.update(cx, |store, cx| store.clear(cx));
+                }
+            });
+        },
+    )
+    .detach();
+}
+
+impl BookmarkStore {
+    pub(crate) fn load(workspace_id: WorkspaceId, cx: &mut ModelContext<Self>) -> Self {
+        let load_bookmarks = cx.spawn(|this, mut cx| async move {
+            let bookmarks = DB
+                .get_bookmarks(workspace_id)
+                .await
+                .log_err()
+                .unwrap_or_default();
+            this.update(&mut cx, |this, cx| this.bookmarks_loaded(bookmarks, cx))
+                .ok();
+        });
+
+        Self {
+            workspace_id,
+            bookmarks: Vec::new(),
+            buffer_subscriptions: HashMap::default(),
+            load_bookmarks: Some(load_bookmarks),
+            serialize_bookmarks: None,
+        }
+    }
+
+    /// Adds the persisted bookmarks, anchoring them in the buffers that are already open.
+    /// Bookmarks added while they were loading replace the persisted ones on the same lines.
+    fn bookmarks_loaded(
+        &mut self,
+        bookmarks: Vec<(PathBuf, u32, Option<String>)>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.load_bookmarks = None;
+        let added = mem::replace(
+            &mut self.bookmarks,
+            bookmarks
+                .into_iter()
+                .map(|(abs_path, row, label)| Bookmark {
+                    abs_path: abs_path.into(),
+                    label: label.map(SharedString::from),
+                    row,
+                    saved_row: row,
+                    anchor: None,
+                })
+                .collect(),
+        );
+        let buffers = self
+            .buffer_subscriptions
+            .values()
+            .filter_map(|(buffer, _)| buffer.upgrade())
+            .collect::<Vec<_>>();
+        for buffer in &buffers {
+            self.anchor_bookmarks(buffer, cx);
+        }
+
+        if !added.is_empty() {
+            self.bookmarks.retain(|bookmark| {
+                !added.iter().any(|added| {
+                    added.abs_path == bookmark.abs_path && added.row(cx) == bookmark.row(cx)
+                })
+            });
+            self.bookmarks.extend(added);
+            self.serialize(cx);
+        }
+        cx.notify();
+    }
+
+    pub fn for_workspace(workspace_id: WorkspaceId, cx: &AppContext) -> Option<Model<Self>> {
+        cx.try_global::<GlobalBookmarkStores>()?
+            .0
+            .get(&workspace_id)
+            .cloned()
+    }
+
+    pub fn bookmarks(&self) -> &[Bookmark] {
+        &self.bookmarks
+    }
+
+    /// All bookmarks, ordered by path and row.
+    pub fn sorted_bookmarks(&self, cx: &AppContext) -> Vec<Bookmark> {
+        self.bookmarks
+            .iter()
+            .cloned()
+            .sorted_by_cached_key(|bookmark| (bookmark.abs_path.clone(), bookmark.row(cx)))
+            .collect()
+    }
+
+    pub fn bookmarks_in_buffer<'a>(
+        &'a self,
+        buffer: &'a Model<Buffer>,
+        cx: &AppContext,
+    ) -> impl Iterator<Item = &'a Bookmark> + 'a {
+        let abs_path = buffer_abs_path(buffer, cx);
+        self.bookmarks
+            .iter()
+            .filter(move |bookmark| bookmark.is_in_buffer(buffer, abs_path.as_deref()))
+    }
+
+    pub fn bookmark_at(
+        &self,
+        buffer: &Model<Buffer>,
+        row: u32,
+        cx: &AppContext,
+    ) -> Option<&Bookmark> {
+        let ix = self.bookmark_ix_at(buffer, row, cx)?;
+        Some(&self.bookmarks[ix])
+    }
+
+    fn bookmark_ix_at(&self, buffer: &Model<Buffer>, row: u32, cx: &AppContext) -> Option<usize> {
+        let abs_path = buffer_abs_path(buffer, cx);
+        let snapshot = buffer.read(cx);
+        self.bookmarks.iter().position(|bookmark| {
+            bookmark.is_in_buffer(buffer, abs_path.as_deref())
+                && bookmark.anchor_in(snapshot).to_point(snapshot).row == row
+        })
+    }
+
+    /// Anchors the bookmarks in the file of the given buffer to it, so that they follow its edits.
+    pub fn register_buffer(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        if !self.anchor_bookmarks(buffer, cx) {
+            return;
+        }
+
+        if let hash_map::Entry::Vacant(entry) = self.buffer_subscriptions.entry(buffer.entity_id())
+        {
+            let buffer_id = buffer.entity_id();
+            let subscriptions = [
+                // Rows are persisted when files are saved, so that they match the files on disk.
+                cx.subscribe(buffer, |this, buffer, event, cx| {
+                    if let language::Event::Saved | language::Event::Reloaded = event {
+                        this.update_saved_rows(&buffer, cx);
+                        this.serialize(cx);
+                    }
+                }),
+                cx.observe_release(buffer, move |this, buffer, _| {
+                    for bookmark in &mut this.bookmarks {
+                        if let Some((bookmark_buffer, anchor)) = &bookmark.anchor {
+                            if bookmark_buffer.entity_id() == buffer_id {
+                                bookmark.row = anchor.to_point(buffer).row;
+                                bookmark.anchor = None;
+                            }
+                        }
+                    }
+                    this.buffer_subscriptions.remove(&buffer_id);
+                }),
+            ];
+            entry.insert((buffer.downgrade(), subscriptions));
+        }
+    }
+
+    /// Records the rows of the bookmarks in the given buffer as their rows in its file on disk.
+    fn update_saved_rows(&mut self, buffer: &Model<Buffer>, cx: &AppContext) {
+        let snapshot = buffer.read(cx);
+        for bookmark in &mut self.bookmarks {
+            if let Some((bookmark_buffer, anchor)) = &bookmark.anchor {
+                if bookmark_buffer.entity_id() == buffer.entity_id() {
+                    bookmark.saved_row = anchor.to_point(snapshot).row;
+                }
+            }
+        }
+    }
+
+    /// Anchors the bookmarks in the file of the given buffer to it, returning whether the buffer
+    /// has a file on disk that bookmarks can be in.
+    fn anchor_bookmarks(&mut self, buffer: &Model<Buffer>, cx: &AppContext) -> bool {
+        let Some(abs_path) = buffer_abs_path(buffer, cx) else {
+            return false;
+        };
+
+        let snapshot = buffer.read(cx);
+        for bookmark in &mut self.bookmarks {
+            if bookmark.anchor.is_none() && *bookmark.abs_path == *abs_path {
+                bookmark.anchor = Some((buffer.downgrade(), bookmark.anchor_in(snapshot)));
+            }
+        }
+        true
+    }
+
+    /// Removes the bookmark on the given row of the buffer, or adds one if there is none.
+    pub fn toggle(&mut self, buffer: &Model<Buffer>, row: u32, cx: &mut ModelContext<Self>) {
+        if let Some(ix) = self.bookmark_ix_at(buffer, row, cx) {
+            self.bookmarks.remove(ix);
+        } else if !self.insert(buffer, row, None, cx) {
+            return;
+        }
+        self.bookmarks_changed(cx);
+    }
+
+    /// Sets the label of the bookmark on the given row of the buffer, adding one if there is none.
+    pub fn set_label(
+        &mut self,
+        buffer: &Model<Buffer>,
+        row: u32,
+        label: Option<SharedString>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if let Some(ix) = self.bookmark_ix_at(buffer, row, cx) {
+            self.bookmarks[ix].label = label;
+        } else if !self.insert(buffer, row, label, cx) {
+            return;
+        }
+        self.bookmarks_changed(cx);
+    }
+
+    pub fn clear(&mut self, cx: &mut ModelContext<Self>) {
+        // Bookmarks that are still loading are cleared as well.
+        let was_loading = self.load_bookmarks.take().is_some();
+        if was_loading || !self.bookmarks.is_empty() {
+            self.bookmarks.clear();
+            self.bookmarks_changed(cx);
+        }
+    }
+
+    fn insert(
+        &mut self,
+        buffer: &Model<Buffer>,
+        row: u32,
+        label: Option<SharedString>,
+        cx: &mut ModelContext<Self>,
+    ) -> bool {
+        let Some(abs_path) = buffer_abs_path(buffer, cx) else {
+            return false;
+        };
+
+        self.register_buffer(buffer, cx);
+        let snapshot = buffer.read(cx);
+        let anchor = snapshot.anchor_before(snapshot.clip_point(Point::new(row, 0), Bias::Left));
+        self.bookmarks.push(Bookmark {
+            abs_path: abs_path.into(),
+            label,
+            row,
+            // The row on disk of a line with unsaved edits isn't known, so it's assumed unchanged.
+            saved_row: row,
+            anchor: Some((buffer.downgrade(), anchor)),
+        });
+        true
+    }
+
+    fn bookmarks_changed(&mut self, cx: &mut ModelContext<Self>) {
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn serialize(&mut self, cx: &mut ModelContext<Self>) {
+        // The persisted bookmarks would be replaced by the ones added while they are loading,
+        // so those are saved along with them once they are loaded.
+        if self.load_bookmarks.is_some() {
+            return;
+        }
+        let workspace_id = self.workspace_id;
+        let bookmarks = self
+            .bookmarks
+            .iter()
+            .map(|bookmark| {
+                (
+                    bookmark.abs_path.to_path_buf(),
+                    bookmark.saved_row,
+                    bookmark.label.as_ref().map(ToString::to_string),
+                )
+            })
+            .collect::<Vec<_>>();
+        self.serialize_bookmarks = Some(cx.background_executor().spawn(async move {
+            DB.save_bookmarks(workspace_id, bookmarks).await.log_err();
+        }));
+    }
+}
+
+fn buffer_abs_path(buffer: &Model<Buffer>, cx: &AppContext) -> Option<PathBuf> {
+    let file = buffer.read(cx).file()?.as_local()?;
+    Some(file.abs_path(cx))
+}
+
+impl Editor {
+    fn bookmark_store(&self, cx: &AppContext) -> Option<Model<BookmarkStore>> {
+        let (_, workspace_id) = self.workspace.as_ref()?;
+        BookmarkStore::for_workspace(*workspace_id, cx)
+    }
+
+    /// The buffers and rows of the lines containing the cursors.
+    fn bookmark_rows_for_selections(&self, cx: &AppContext) -> Vec<(Model<Buffer>, u32)> {
+        let multi_buffer = self.buffer.read(cx);
+        let mut rows: Vec<(Model<Buffer>, u32)> = Vec::new();
+        for selection in self.selections.all::<Point>(cx) {
+            if let Some((buffer, anchor)) =
+                multi_buffer.text_anchor_for_position(selection.head(), cx)
+            {
+                let row = anchor.to_point(buffer.read(cx)).row;
+                if !rows
+                    .iter()
+                    .any(|(other_buffer, other_row)| *other_buffer == buffer && *other_row == row)
+                {
+                    rows.push((buffer, row));
+                }
+            }
+        }
+        rows
+    }
+
+    pub fn toggle_bookmark(&mut self, _: &ToggleBookmark, cx: &mut ViewContext<Self>) {
+        let Some(store) = self.bookmark_store(cx) else {
+            return;
+        };
+        let rows = self.bookmark_rows_for_selections(cx);
+        store.update(cx, |store, cx| {
+            for (buffer, row) in rows {
+                store.toggle(&buffer, row, cx);
+            }
+        });
+    }
+
+    /// The label of the bookmark on the line of the newest cursor.
+    pub fn bookmark_label_at_cursor(&self, cx: &AppContext) -> Option<SharedString> {
+        let store = self.bookmark_store(cx)?;
+        let head = self.selections.newest::<Point>(cx).head();
+        let (buffer, anchor) = self.buffer.read(cx).text_anchor_for_position(head, cx)?;
+        let row = anchor.to_point(buffer.read(cx)).row;
+        store.read(cx).bookmark_at(&buffer, row, cx)?.label.clone()
+    }
+
+    /// Labels the bookmark on the line of the newest cursor, adding one if there is none.
+    pub fn set_bookmark_label(&mut self, label: Option<SharedString>, cx: &mut ViewContext<Self>) {
+        let Some(store) = self.bookmark_store(cx) else {
+            return;
+        };
+        let head = self.selections.newest::<Point>(cx).head();
+        let Some((buffer, anchor)) = self.buffer.read(cx).text_anchor_for_position(head, cx) else {
+            return;
+        };
+        let row = anchor.to_point(buffer.read(cx)).row;
+        store.update(cx, |store, cx| store.set_label(&buffer, row, label, cx));
+    }
+
+    pub fn go_to_bookmark(&mut self, _: &GoToBookmark, cx: &mut ViewContext<Self>) {
+        self.go_to_bookmark_in_direction(Direction::Next, cx);
+    }
+
+    pub fn go_to_prev_bookmark(&mut self, _: &GoToPrevBookmark, cx: &mut ViewContext<Self>) {
+        self.go_to_bookmark_in_direction(Direction::Prev, cx);
+    }
+
+    fn go_to_bookmark_in_direction(&mut self, direction: Direction, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let rows = self.bookmarked_rows(&snapshot, cx);
+        let head_row = self.selections.newest::<Point>(cx).head().row;
+        let row = match direction {
+            Direction::Next => rows.iter().find(|row| **row > head_row).or(rows.first()),
+            Direction::Prev => rows
+                .iter()
+                .rev()
+                .find(|row| **row < head_row)
+                .or(rows.last()),
+        };
+
+        if let Some(row) = row.copied() {
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                let point = Point::new(row, 0);
+                s.select_ranges([point..point]);
+            });
+        }
+    }
+
+    /// The rows of the multibuffer that are bookmarked, in ascending order.
+    pub(crate) fn bookmarked_rows(
+        &self,
+        snapshot: &MultiBufferSnapshot,
+        cx: &AppContext,
+    ) -> Vec<u32> {
+        let Some(store) = self.bookmark_store(cx) else {
+            return Vec::new();
+        };
+        let store = store.read(cx);
+        if store.bookmarks.is_empty() {
+            return Vec::new();
+        }
+
+        let multi_buffer = self.buffer.read(cx);
+        let mut rows = Vec::new();
+        for buffer in multi_buffer.all_buffers() {
+            let buffer_snapshot = buffer.read(cx);
+            let anchors = store
+                .bookmarks_in_buffer(&buffer, cx)
+                .map(|bookmark| bookmark.anchor_in(buffer_snapshot))
+                .collect::<Vec<_>>();
+            if anchors.is_empty() {
+                continue;
+            }
+
+            for (excerpt_id, range) in multi_buffer.excerpts_for_buffer(&buffer, cx) {
+                for anchor in &anchors {
+                    if range.context.start.cmp(anchor, buffer_snapshot).is_le()
+                        && anchor.cmp(&range.context.end, buffer_snapshot).is_le()
+                    {
+                        if let Some(anchor) = snapshot.anchor_in_excerpt(excerpt_id, *anchor) {
+                            rows.push(multi_buffer::ToPoint::to_point(&anchor, snapshot).row);
+                        }
+                    }
+                }
+            }
+        }
+        rows.sort_unstable();
+        rows.dedup();
+        rows
+    }
+
+    /// The file and row of the newest cursor, used to find the next bookmark in the project.
+    fn bookmark_position(&self, cx: &AppContext) -> Option<(Arc<Path>, u32)> {
+        let head = self.selections.newest::<Point>(cx).head();
+        let (buffer, anchor) = self.buffer.read(cx).text_anchor_for_position(head, cx)?;
+        let abs_path = buffer_abs_path(&buffer, cx)?;
+        Some((abs_path.into(), anchor.to_point(buffer.read(cx)).row))
+    }
+
+    pub(crate) fn register_bookmarked_buffers(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(store) = self.bookmark_store(cx) else {
+            return;
+        };
+        let buffers = self.buffer.read(cx).all_buffers();
+        store.update(cx, |store, cx| {
+            for buffer in &buffers {
+                store.register_buffer(buffer, cx);
+            }
+        });
+        self.bookmark_store_subscription = Some(cx.observe(&store, |_, _, cx| cx.notify()));
+    }
+}
+
+/// Opens the file of a bookmark and moves the cursor to its line.
+pub fn open_bookmark(
+    workspace: &mut Workspace,
+    abs_path: Arc<Path>,
+    row: u32,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<Result<()>> {
+    let open_path = workspace.open_abs_path(abs_path.to_path_buf(), true, cx);
+    cx.spawn(|_, mut cx| async move {
+        let item = open_path.await?;
+        if let Some(editor) = item.downcast::<Editor>() {
+            editor.update(&mut cx, |editor, cx| {
+                let snapshot = editor.buffer.read(cx).snapshot(cx);
+                let point = snapshot.clip_point(Point::new(row, 0), Bias::Left);
+                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                    s.select_ranges([point..point]);
+                });
+            })?;
+        }
+        Ok(())
+    })
+}
+
+fn go_to_project_bookmark(
+    workspace: &mut Workspace,
+    direction: Direction,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(store) = BookmarkStore::for_workspace(workspace.database_id(), cx) else {
+        return;
+    };
+    let positions = store
+        .read(cx)
+        .sorted_bookmarks(cx)
+        .into_iter()
+        .map(|bookmark| {
+            let row = bookmark.row(cx);
+            (bookmark.abs_path, row)
+        })
+        .collect::<Vec<_>>();
+    let current = workspace
+        .active_item_as::<Editor>(cx)
+        .and_then(|editor| editor.read(cx).bookmark_position(cx));
+
+    let target = match direction {
+        Direction::Next => positions
+            .iter()
+            .find(|position| current.as_ref().map_or(true, |current| *position > current))
+            .or(positions.first()),
+        Direction::Prev => positions
+            .iter()
+            .rev()
+            .find(|position| current.as_ref().map_or(true, |current| *position < current))
+            .or(positions.last()),
+    };
+
+    if let Some((abs_path, row)) = target.cloned() {
+        open_bookmark(workspace, abs_path, row, cx).detach_and_log_err(cx);
+    }
+}
+
+/// Opens the lines of all bookmarks in a multibuffer.
+fn open_bookmarks(workspace: &mut Workspace, _: &OpenBookmarks, cx: &mut ViewContext<Workspace>) {
+    let Some(store) = BookmarkStore::for_workspace(workspace.database_id(), cx) else {
+        return;
+    };
+    let open_buffers = store
+        .read(cx)
+        .sorted_bookmarks(cx)
+        .into_iter()
+        .map(|bookmark| bookmark.abs_path)
+        .dedup()
+        .map(|abs_path| {
+            workspace
+                .project()
+                .update(cx, |project, cx| project.open_local_buffer(abs_path, cx))
+        })
+        .collect::<Vec<_>>();
+    if open_buffers.is_empty() {
+        return;
+    }
+
+    cx.spawn(|workspace, mut cx| async move {
+        let buffers = join_all(open_buffers)
+            .await
+            .into_iter()
+            .filter_map(|buffer| buffer.log_err())
+            .collect::<Vec<_>>();
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let locations = store.update(cx, |store, cx| {
+                for buffer in &buffers {
+                    store.register_buffer(buffer, cx);
+                }
+                store
+                    .sorted_bookmarks(cx)
+                    .into_iter()
+                    .filter_map(|bookmark| {
+                        let (buffer, _) = bookmark.buffer_anchor(cx)?;
+                        let snapshot = buffer.read(cx);
+                        let row = bookmark.row(cx);
+                        let range = snapshot.anchor_before(Point::new(row, 0))
+                            ..snapshot.anchor_after(Point::new(row, snapshot.line_len(row)));
+                        Some(Location {
+                            buffer: buffer.clone(),
+                            range,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            });
+            if locations.is_empty() {
+                return;
+            }
+
+            let replica_id = workspace.project().read(cx).replica_id();
+            Editor::open_locations_in_multibuffer(
+                workspace,
+                locations,
+                replica_id,
+                "Bookmarks".to_string(),
+                false,
+                cx,
+            );
+        })
+    })
+    .detach_and_log_err(cx);
+}
@@ -23,6 +23,7 @@ mod inlay_map;
 mod tab_map;
 mod wrap_map;
 
+use crate::indent_guides::{indent_guides_in_range, IndentGuide, IndentLevelCache};
 use crate::EditorStyle;
 use crate::{hover_links::InlayHighlight, movement::TextLayoutDetails, InlayId};
 pub use block_map::{BlockMap, BlockPoint};
@@ -35,6 +36,7 @@ use language::{
 };
 use lsp::DiagnosticSeverity;
 use multi_buffer::{Anchor, AnchorRangeExt, MultiBuffer, MultiBufferSnapshot, ToOffset, ToPoint};
+use parking_lot::Mutex;
 use std::{any::TypeId, borrow::Cow, fmt::Debug, num::NonZeroU32, ops::Range, sync::Arc};
 use sum_tree::{Bias, TreeMap};
 use tab_map::TabMap;
@@ -87,6 +89,8 @@ pub struct DisplayMap {
     text_highlights: TextHighlights,
     /// Regions of inlays that should be highlighted.
     inlay_highlights: InlayHighlights,
+    /// The indentation of buffer rows, shared by the snapshots to draw indent guides.
+    indent_levels: Arc<Mutex<IndentLevelCache>>,
     pub clip_at_line_ends: bool,
 }
 
@@ -119,6 +123,7 @@ impl DisplayMap {
             block_map,
             text_highlights: Default::default(),
             inlay_highlights: Default::default(),
+            indent_levels: Default::default(),
             clip_at_line_ends: false,
         }
     }
@@ -144,6 +149,7 @@ impl DisplayMap {
             block_snapshot,
             text_highlights: self.text_highlights.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
+            indent_levels: self.indent_levels.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
         }
     }
@@ -369,6 +375,7 @@ pub struct DisplaySnapshot {
     block_snapshot: block_map::BlockSnapshot,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
+    indent_levels: Arc<Mutex<IndentLevelCache>>,
     clip_at_line_ends: bool,
 }
 
@@ -807,6 +814,22 @@ impl DisplaySnapshot {
         self.block_snapshot.line_len(row)
     }
 
+    /// Returns the indent guides to draw across the given buffer rows, with the one of the block
+    /// containing the cursor marked as active.
+    pub fn indent_guides_in_range(
+        &self,
+        buffer_rows: Range<u32>,
+        cursor: Option<Point>,
+    ) -> Vec<IndentGuide> {
+        indent_guides_in_range(
+            &self.buffer_snapshot,
+            &self.indent_levels,
+            self.tab_snapshot.tab_size.get(),
+            buffer_rows,
+            cursor,
+        )
+    }
+
     pub fn longest_row(&self) -> u32 {
         self.block_snapshot.longest_row()
     }
@@ -15,11 +15,14 @@
 pub mod actions;
 mod blame_entry_tooltip;
 mod blink_manager;
+mod bookmarks;
 pub mod display_map;
 mod editor_settings;
 mod element;
 mod hunk_diff;
+mod indent_guides;
 mod inlay_hint_cache;
+mod large_file_banner;
 
 mod debounced_delay;
 mod git;
@@ -31,6 +34,7 @@ pub mod items;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
+mod review_comments;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
@@ -45,6 +49,7 @@ pub(crate) use actions::*;
 use aho_corasick::AhoCorasick;
 use anyhow::{anyhow, Context as _, Result};
 use blink_manager::BlinkManager;
+pub use bookmarks::{open_bookmark, Bookmark, BookmarkStore};
 use client::{Collaborator, ParticipantIndex};
 use clock::ReplicaId;
 use collections::{hash_map, BTreeMap, Bound, HashMap, HashSet, VecDeque};
@@ -83,8 +88,9 @@ use language::{
     language_settings::{self, all_language_settings, InlayHintSettings},
     markdown, point_from_lsp, AutoindentMode, BracketPair, Buffer, Capability, CharKind, CodeLabel,
     CursorShape, Diagnostic, Documentation, IndentKind, IndentSize, Language, OffsetRangeExt,
-    Point, Selection, SelectionGoal, TransactionId,
+    OutlineItem, Point, Selection, SelectionGoal, TransactionId,
 };
+pub use large_file_banner::LargeFileBanner;
 
 use hover_links::{HoverLink, HoveredLinkState, InlayHighlight};
 use lsp::{DiagnosticSeverity, LanguageServerId};
@@ -250,6 +256,7 @@ pub fn init_settings(cx: &mut AppContext) {
 
 pub fn init(cx: &mut AppContext) {
     init_settings(cx);
+    bookmarks::init(cx);
 
     workspace::register_project_item::<Editor>(cx);
     workspace::register_followable_item::<Editor>(cx);
@@ -395,6 +402,43 @@ impl Default for ScrollbarMarkerState {
     }
 }
 
+/// The lines and markers of the minimap, laid out in the background for a window of display
+/// rows around the ones it shows.
+struct MinimapState {
+    rows: Range<u32>,
+    size: Size<Pixels>,
+    max_row: u32,
+    dirty: bool,
+    quads: Arc<[PaintQuad]>,
+    pending_refresh: Option<Task<Result<()>>>,
+    is_dragging: bool,
+}
+
+impl MinimapState {
+    fn should_refresh(&self, visible_rows: &Range<u32>, size: Size<Pixels>, max_row: u32) -> bool {
+        self.pending_refresh.is_none()
+            && (self.dirty
+                || self.size != size
+                || self.max_row != max_row
+                || visible_rows.start < self.rows.start
+                || visible_rows.end > self.rows.end)
+    }
+}
+
+impl Default for MinimapState {
+    fn default() -> Self {
+        Self {
+            rows: 0..0,
+            size: Size::default(),
+            max_row: 0,
+            dirty: false,
+            quads: Arc::from([]),
+            pending_refresh: None,
+            is_dragging: false,
+        }
+    }
+}
+
 /// Zed's primary text input `View`, allowing users to edit a [`MultiBuffer`]
 ///
 /// See the [module level documentation](self) for more information.
@@ -429,11 +473,13 @@ pub struct Editor {
     show_breadcrumbs: bool,
     show_gutter: bool,
     show_wrap_guides: Option<bool>,
+    show_indent_guides: Option<bool>,
     placeholder_text: Option<Arc<str>>,
     highlight_order: usize,
     highlighted_rows: HashMap<TypeId, Vec<(usize, Range<Anchor>, Hsla)>>,
     background_highlights: TreeMap<TypeId, BackgroundHighlight>,
     scrollbar_marker_state: ScrollbarMarkerState,
+    minimap_state: MinimapState,
     nav_history: Option<ItemNavHistory>,
     context_menu: RwLock<Option<ContextMenu>>,
     mouse_context_menu: Option<MouseContextMenu>,
@@ -479,6 +525,8 @@ pub struct Editor {
     git_blame_inline_enabled: bool,
     blame: Option<Model<GitBlame>>,
     blame_subscription: Option<Subscription>,
+    bookmark_store_subscription: Option<Subscription>,
+    review_threads: review_comments::ReviewThreadBlocks,
     custom_context_menu: Option<
         Box<
             dyn 'static
@@ -1434,11 +1482,19 @@ impl Editor {
                         cx.emit(EditorEvent::TitleChanged);
                     }));
                 }
-                project_subscriptions.push(cx.subscribe(project, |editor, _, event, cx| {
-                    if let project::Event::RefreshInlayHints = event {
-                        editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
-                    };
-                }));
+                project_subscriptions.push(cx.subscribe(
+                    project,
+                    |editor, _, event, cx| match event {
+                        project::Event::RefreshInlayHints => {
+                            editor
+                                .refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                        }
+                        project::Event::ReviewThreadsUpdated => {
+                            editor.refresh_review_threads(cx);
+                        }
+                        _ => {}
+                    },
+                ));
             }
         }
 
@@ -1478,11 +1534,13 @@ impl Editor {
             show_breadcrumbs: EditorSettings::get_global(cx).toolbar.breadcrumbs,
             show_gutter: mode == EditorMode::Full,
             show_wrap_guides: None,
+            show_indent_guides: None,
             placeholder_text: None,
             highlight_order: 0,
             highlighted_rows: HashMap::default(),
             background_highlights: Default::default(),
             scrollbar_marker_state: ScrollbarMarkerState::default(),
+            minimap_state: MinimapState::default(),
             nav_history: None,
             context_menu: RwLock::new(None),
             mouse_context_menu: None,
@@ -1532,6 +1590,8 @@ impl Editor {
             git_blame_inline_enabled: ProjectSettings::get_global(cx).git.inline_blame_enabled(),
             blame: None,
             blame_subscription: None,
+            bookmark_store_subscription: None,
+            review_threads: Default::default(),
             _subscriptions: vec![
                 cx.observe(&buffer, Self::on_buffer_changed),
                 cx.subscribe(&buffer, Self::on_buffer_event),
@@ -1566,6 +1626,8 @@ impl Editor {
                 this.git_blame_inline_enabled = true;
                 this.start_git_blame_inline(false, cx);
             }
+
+            this.refresh_review_threads(cx);
         }
 
         this.report_editor_event("open", None, cx);
@@ -3334,6 +3396,9 @@ impl Editor {
             .filter(|(_, excerpt_visible_range, _)| !excerpt_visible_range.is_empty())
             .filter_map(|(buffer_handle, excerpt_visible_range, excerpt_id)| {
                 let buffer = buffer_handle.read(cx);
+                if buffer.is_large_file() {
+                    return None;
+                }
                 let buffer_file = project::File::from_dyn(buffer.file())?;
                 let buffer_worktree = project.worktree_for_id(buffer_file.worktree_id(cx), cx)?;
                 let worktree_entry = buffer_worktree
@@ -9022,6 +9087,11 @@ impl Editor {
         cx.notify();
     }
 
+    pub fn set_show_indent_guides(&mut self, show_indent_guides: bool, cx: &mut ViewContext<Self>) {
+        self.show_indent_guides = Some(show_indent_guides);
+        cx.notify();
+    }
+
     pub fn reveal_in_finder(&mut self, _: &RevealInFinder, cx: &mut ViewContext<Self>) {
         if let Some(buffer) = self.buffer().read(cx).as_singleton() {
             if let Some(file) = buffer.read(cx).file().and_then(|f| f.as_local()) {
@@ -9374,6 +9444,7 @@ impl Editor {
         self.background_highlights
             .insert(TypeId::of::<T>(), (color_fetcher, Arc::from(ranges)));
         self.scrollbar_marker_state.dirty = true;
+        self.minimap_state.dirty = true;
         cx.notify();
     }
 
@@ -9384,6 +9455,7 @@ impl Editor {
         let text_highlights = self.background_highlights.remove(&TypeId::of::<T>())?;
         if !text_highlights.1.is_empty() {
             self.scrollbar_marker_state.dirty = true;
+            self.minimap_state.dirty = true;
             cx.notify();
         }
         Some(text_highlights)
@@ -9641,6 +9713,7 @@ impl Editor {
                 singleton_buffer_edited,
             } => {
                 self.scrollbar_marker_state.dirty = true;
+                self.minimap_state.dirty = true;
                 self.refresh_active_diagnostics(cx);
                 self.refresh_code_actions(cx);
                 if self.has_active_inline_completion(cx) {
@@ -9698,8 +9771,12 @@ impl Editor {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
-            multi_buffer::Event::Reparsed => cx.emit(EditorEvent::Reparsed),
+            multi_buffer::Event::Reparsed => {
+                self.minimap_state.dirty = true;
+                cx.emit(EditorEvent::Reparsed);
+            }
             multi_buffer::Event::LanguageChanged => {
+                self.minimap_state.dirty = true;
                 cx.emit(EditorEvent::Reparsed);
                 cx.notify();
             }
@@ -9710,10 +9787,12 @@ impl Editor {
             }
             multi_buffer::Event::DiffBaseChanged => {
                 self.scrollbar_marker_state.dirty = true;
+                self.minimap_state.dirty = true;
                 cx.emit(EditorEvent::DiffBaseChanged);
                 cx.notify();
             }
             multi_buffer::Event::DiffUpdated { buffer } => {
+                self.minimap_state.dirty = true;
                 self.sync_expanded_diff_hunks(buffer.clone(), cx);
                 cx.notify();
             }
@@ -9721,6 +9800,7 @@ impl Editor {
             multi_buffer::Event::DiagnosticsUpdated => {
                 self.refresh_active_diagnostics(cx);
                 self.scrollbar_marker_state.dirty = true;
+                self.minimap_state.dirty = true;
                 cx.notify();
             }
             _ => {}
@@ -10397,6 +10477,40 @@ impl EditorSnapshot {
         self.scroll_anchor.scroll_position(&self.display_snapshot)
     }
 
+    /// Returns the outline items to pin at the top of the editor when `top_row` is the first
+    /// visible buffer row, from the outermost one.
+    ///
+    /// Each pinned header covers a row, so an item is only pinned while it starts above the row
+    /// its header would cover and ends below it.
+    pub fn sticky_headers(
+        &self,
+        top_row: u32,
+        max_depth: usize,
+        theme: Option<&SyntaxTheme>,
+    ) -> Vec<OutlineItem<Anchor>> {
+        let buffer = &self.buffer_snapshot;
+        // Look past the start of the row, so that an item ending on the previous row isn't
+        // taken for an ancestor.
+        let offset = (Point::new(top_row, 0).to_offset(buffer) + 1).min(buffer.len());
+        let Some((_, items)) = buffer.symbols_containing(offset, theme) else {
+            return Vec::new();
+        };
+
+        let mut headers = Vec::new();
+        for item in items {
+            if headers.len() >= max_depth {
+                break;
+            }
+            let covered_row = top_row + headers.len() as u32;
+            let start = item.range.start.to_point(buffer);
+            let end = item.range.end.to_point(buffer);
+            if start.row < covered_row && end.row > covered_row {
+                headers.push(item);
+            }
+        }
+        headers
+    }
+
     pub fn gutter_dimensions(
         &self,
         font_id: FontId,
@@ -1,5 +1,6 @@
 use super::*;
 use crate::{
+    persistence::DB,
     scroll::scroll_amount::ScrollAmount,
     test::{
         assert_text_with_selections, build_editor, editor_hunks,
@@ -23,7 +24,7 @@ use project::FakeFs;
 use serde_json::{self, json};
 use std::sync::atomic;
 use std::sync::atomic::AtomicUsize;
-use std::{cell::RefCell, future::Future, rc::Rc, time::Instant};
+use std::{cell::RefCell, future::Future, path::PathBuf, rc::Rc, time::Instant};
 use unindent::Unindent;
 use util::{
     assert_set_eq,
@@ -11187,6 +11188,330 @@ async fn test_multiple_expanded_hunks_merge(
     );
 }
 
+#[gpui::test]
+async fn test_indent_guides(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        fn a() {
+            if x {
+                foo();
+
+                ˇbar();
+            }
+        }
+    "});
+    assert_eq!(
+        indent_guides(&mut cx),
+        vec![(2..5, 2, 4, true), (1..6, 1, 0, false)]
+    );
+
+    // On the first row of a block, its guide is the active one.
+    cx.set_state(indoc! {"
+        fn a() {
+            ˇif x {
+                foo();
+
+                bar();
+            }
+        }
+    "});
+    assert_eq!(
+        indent_guides(&mut cx),
+        vec![(2..5, 2, 4, true), (1..6, 1, 0, false)]
+    );
+    cx.set_state(indoc! {"
+        ˇfn a() {
+            if x {
+                foo();
+            }
+        }
+    "});
+    assert_eq!(
+        indent_guides(&mut cx),
+        vec![(2..3, 2, 4, false), (1..4, 1, 0, true)]
+    );
+
+    // Guides are recomputed after an edit.
+    cx.update_editor(|editor, cx| editor.handle_input("    ", cx));
+    assert_eq!(
+        indent_guides(&mut cx),
+        vec![(2..3, 2, 4, false), (0..4, 1, 0, true)]
+    );
+}
+
+#[gpui::test]
+async fn test_indent_guides_active_scope_from_syntax(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(rust_lang()), cx));
+    cx.set_state(indoc! {"
+        fn a() {
+            let x = [
+                1,
+            ˇ];
+            foo();
+        }
+    "});
+    cx.executor().run_until_parked();
+    assert_eq!(
+        indent_guides(&mut cx),
+        vec![(2..3, 2, 4, true), (1..5, 1, 0, false)]
+    );
+
+    // The closing delimiter of a block belongs to it, unlike with indentation alone.
+    cx.set_state(indoc! {"
+        fn a() {
+            let x = [
+                1,
+            ];
+            foo();
+        ˇ}
+    "});
+    cx.executor().run_until_parked();
+    assert_eq!(
+        indent_guides(&mut cx),
+        vec![(2..3, 2, 4, false), (1..5, 1, 0, true)]
+    );
+}
+
+fn indent_guides(cx: &mut EditorTestContext) -> Vec<(Range<u32>, u32, u32, bool)> {
+    cx.update_editor(|editor, cx| {
+        let snapshot = editor.snapshot(cx);
+        let cursor = editor.selections.newest::<Point>(cx).head();
+        snapshot
+            .indent_guides_in_range(0..snapshot.max_buffer_row() + 1, Some(cursor))
+            .into_iter()
+            .map(|guide| (guide.buffer_rows, guide.depth, guide.column, guide.active))
+            .collect()
+    })
+}
+
+#[gpui::test]
+async fn test_sticky_headers(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::language()),
+        )
+        .with_outline_query(indoc! {r#"
+            (mod_item "mod" @context name: (_) @name) @item
+            (function_item "fn" @context name: (_) @name) @item
+        "#})
+        .unwrap(),
+    );
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+    cx.set_state(indoc! {"
+        ˇmod m {
+            fn a() {
+                x();
+                y();
+                z();
+            }
+        }
+    "});
+    cx.executor().run_until_parked();
+
+    let sticky_headers = |top_row, max_depth, cx: &mut EditorTestContext| {
+        cx.update_editor(|editor, cx| {
+            editor
+                .snapshot(cx)
+                .sticky_headers(top_row, max_depth, None)
+                .into_iter()
+                .map(|item| item.text)
+                .collect::<Vec<_>>()
+        })
+    };
+    assert_eq!(sticky_headers(0, 5, &mut cx), Vec::<String>::new());
+    assert_eq!(sticky_headers(2, 5, &mut cx), vec!["mod m", "fn a"]);
+    assert_eq!(sticky_headers(2, 1, &mut cx), vec!["mod m"]);
+    // The function ends on the row its header would cover, so it is no longer pinned.
+    assert_eq!(sticky_headers(4, 5, &mut cx), vec!["mod m"]);
+}
+
+#[gpui::test]
+async fn test_bookmarks(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    // Bookmarks are loaded and saved by the database's thread.
+    cx.executor().allow_parking();
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/a",
+        json!({
+            "main.rs": "one\ntwo\nthree\nfour\nfive\n",
+        }),
+    )
+    .await;
+    let project = Project::test(fs, ["/a".as_ref()], cx).await;
+    let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+    let cx = &mut VisualTestContext::from_window(*workspace.deref(), cx);
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let editor = workspace
+        .update(cx, |workspace, cx| {
+            workspace.open_path((worktree_id, "main.rs"), None, true, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    let workspace_id = workspace
+        .update(cx, |workspace, _| workspace.database_id())
+        .unwrap();
+    cx.run_until_parked();
+    // The database runs queries in order, so the workspace's bookmarks are loaded after this one.
+    DB.get_bookmarks(workspace_id).await.unwrap();
+    cx.run_until_parked();
+
+    // Toggling adds a bookmark on the line of each cursor, or removes the one already there.
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([
+                Point::new(1, 2)..Point::new(1, 2),
+                Point::new(3, 0)..Point::new(3, 0),
+            ])
+        });
+        editor.toggle_bookmark(&ToggleBookmark, cx);
+        assert_eq!(bookmarked_rows(editor, cx), vec![1, 3]);
+
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(3, 1)..Point::new(3, 1)])
+        });
+        editor.toggle_bookmark(&ToggleBookmark, cx);
+        assert_eq!(bookmarked_rows(editor, cx), vec![1]);
+
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(4, 0)..Point::new(4, 0)])
+        });
+        editor.toggle_bookmark(&ToggleBookmark, cx);
+        assert_eq!(bookmarked_rows(editor, cx), vec![1, 4]);
+    });
+
+    // Going to the next or previous bookmark wraps around the ends of the buffer.
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(2, 0)..Point::new(2, 0)])
+        });
+        editor.go_to_bookmark(&GoToBookmark, cx);
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).head(),
+            Point::new(4, 0)
+        );
+        editor.go_to_bookmark(&GoToBookmark, cx);
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).head(),
+            Point::new(1, 0)
+        );
+        editor.go_to_prev_bookmark(&GoToPrevBookmark, cx);
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).head(),
+            Point::new(4, 0)
+        );
+        editor.go_to_prev_bookmark(&GoToPrevBookmark, cx);
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).head(),
+            Point::new(1, 0)
+        );
+    });
+
+    // Bookmarks stay on their lines as lines are inserted and removed above them.
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+        });
+        editor.handle_input("zero\n", cx);
+        assert_eq!(bookmarked_rows(editor, cx), vec![2, 5]);
+
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(0, 0)..Point::new(1, 0)])
+        });
+        editor.backspace(&Backspace, cx);
+        assert_eq!(editor.text(cx), "one\ntwo\nthree\nfour\nfive\n");
+        assert_eq!(bookmarked_rows(editor, cx), vec![1, 4]);
+    });
+
+    // Bookmarks are saved as they change, and loaded again for the workspace.
+    cx.run_until_parked();
+    let mut saved_bookmarks = DB.get_bookmarks(workspace_id).await.unwrap();
+    saved_bookmarks.sort();
+    assert_eq!(
+        saved_bookmarks,
+        vec![
+            (PathBuf::from("/a/main.rs"), 1, None),
+            (PathBuf::from("/a/main.rs"), 4, None)
+        ]
+    );
+
+    let store = cx.new_model(|cx| BookmarkStore::load(workspace_id, cx));
+    cx.run_until_parked();
+    DB.get_bookmarks(workspace_id).await.unwrap();
+    cx.run_until_parked();
+    let rows = cx.update(|cx| {
+        store
+            .read(cx)
+            .sorted_bookmarks(cx)
+            .iter()
+            .map(|bookmark| bookmark.row(cx))
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(rows, vec![1, 4]);
+
+    // Rows moved by unsaved edits are only saved along with the file.
+    let store = cx.update(|cx| BookmarkStore::for_workspace(workspace_id, cx).unwrap());
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+        });
+        editor.handle_input("zero\n", cx);
+        let buffer = editor.buffer.read(cx).as_singleton().unwrap();
+        store.update(cx, |store, cx| {
+            store.set_label(&buffer, 2, Some("two".into()), cx)
+        });
+    });
+    cx.run_until_parked();
+    let mut saved_bookmarks = DB.get_bookmarks(workspace_id).await.unwrap();
+    saved_bookmarks.sort();
+    assert_eq!(
+        saved_bookmarks,
+        vec![
+            (PathBuf::from("/a/main.rs"), 1, Some("two".to_string())),
+            (PathBuf::from("/a/main.rs"), 4, None)
+        ]
+    );
+
+    editor
+        .update(cx, |editor, cx| editor.save(false, project.clone(), cx))
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    let mut saved_bookmarks = DB.get_bookmarks(workspace_id).await.unwrap();
+    saved_bookmarks.sort();
+    assert_eq!(
+        saved_bookmarks,
+        vec![
+            (PathBuf::from("/a/main.rs"), 2, Some("two".to_string())),
+            (PathBuf::from("/a/main.rs"), 5, None)
+        ]
+    );
+}
+
+fn bookmarked_rows(editor: &Editor, cx: &AppContext) -> Vec<u32> {
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    editor.bookmarked_rows(&snapshot, cx)
+}
+
 fn empty_range(row: usize, column: usize) -> Range<DisplayPoint> {
     let point = DisplayPoint::new(row as u32, column as u32);
     point..point
@@ -1,17 +1,19 @@
 use crate::{
     blame_entry_tooltip::{blame_entry_relative_timestamp, BlameEntryTooltip},
     display_map::{
-        BlockContext, BlockStyle, DisplaySnapshot, FoldStatus, HighlightedChunk, ToDisplayPoint,
-        TransformBlock,
+        BlockContext, BlockStyle, DisplaySnapshot, FoldStatus, HighlightStyles, HighlightedChunk,
+        ToDisplayPoint, TransformBlock,
+    },
+    editor_settings::{
+        DoubleClickInMultibuffer, Minimap, MultiCursorModifier, ShowMinimap, ShowScrollbar,
     },
-    editor_settings::{DoubleClickInMultibuffer, MultiCursorModifier, ShowScrollbar},
     git::{blame::GitBlame, diff_hunk_to_display, DisplayDiffHunk},
     hover_popover::{
         self, hover_at, HOVER_POPOVER_GAP, MIN_POPOVER_CHARACTER_WIDTH, MIN_POPOVER_LINE_HEIGHT,
     },
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MouseContextMenu},
-    scroll::scroll_amount::ScrollAmount,
+    scroll::{scroll_amount::ScrollAmount, Autoscroll},
     CursorShape, DisplayPoint, DocumentHighlightRead, DocumentHighlightWrite, Editor, EditorMode,
     EditorSettings, EditorSnapshot, EditorStyle, ExpandExcerpts, GutterDimensions, HalfPageDown,
     HalfPageUp, HoveredCursor, HunkToExpand, LineDown, LineUp, OpenExcerpts, PageDown, PageUp,
@@ -28,8 +30,8 @@ use gpui::{
     GlobalElementId, Hitbox, Hsla, InteractiveElement, IntoElement, ModifiersChangedEvent,
     MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, ParentElement, Pixels,
     ScrollDelta, ScrollWheelEvent, ShapedLine, SharedString, Size, Stateful,
-    StatefulInteractiveElement, Style, Styled, TextRun, TextStyle, TextStyleRefinement, View,
-    ViewContext, WeakView, WindowContext,
+    StatefulInteractiveElement, Style, Styled, StyledText, TextRun, TextStyle, TextStyleRefinement,
+    View, ViewContext, WeakView, WindowContext,
 };
 use itertools::Itertools;
 use language::language_settings::ShowWhitespaceSetting;
@@ -51,7 +53,7 @@ use std::{
     sync::Arc,
 };
 use sum_tree::Bias;
-use theme::{ActiveTheme, PlayerColor};
+use theme::{ActiveTheme, PlayerColor, Theme};
 use ui::prelude::*;
 use ui::{h_flex, ButtonLike, ButtonStyle, ContextMenu, Tooltip};
 use util::ResultExt;
@@ -269,6 +271,20 @@ impl EditorElement {
         register_action(view, cx, Editor::go_to_prev_diagnostic);
         register_action(view, cx, Editor::go_to_hunk);
         register_action(view, cx, Editor::go_to_prev_hunk);
+        register_action(view, cx, Editor::toggle_bookmark);
+        register_action(view, cx, Editor::go_to_bookmark);
+        register_action(view, cx, Editor::go_to_prev_bookmark);
+        register_action(view, cx, Editor::add_review_comment);
+        register_action(view, cx, |editor, action, cx| {
+            if !editor.confirm_review_comment(action, cx) {
+                cx.propagate();
+            }
+        });
+        register_action(view, cx, |editor, action, cx| {
+            if !editor.cancel_review_comment(action, cx) {
+                cx.propagate();
+            }
+        });
         register_action(view, cx, |editor, a, cx| {
             editor.go_to_definition(a, cx).detach_and_log_err(cx);
         });
@@ -1119,6 +1135,67 @@ impl EditorElement {
         })
     }
 
+    /// The width the minimap takes from the text, including the scrollbar next to it.
+    fn minimap_width(
+        &self,
+        snapshot: &EditorSnapshot,
+        rows_per_page: f32,
+        cx: &WindowContext,
+    ) -> Pixels {
+        let minimap_settings = EditorSettings::get_global(cx).minimap;
+        let show_minimap = match minimap_settings.show {
+            ShowMinimap::Auto => snapshot.max_point().row() as f32 + 1. > rows_per_page,
+            ShowMinimap::Always => true,
+            ShowMinimap::Never => false,
+        };
+        if snapshot.mode != EditorMode::Full || !show_minimap {
+            return Pixels::ZERO;
+        }
+
+        px(minimap_settings.width.max(0.)) + self.style.scrollbar_width
+    }
+
+    fn layout_minimap(
+        &self,
+        snapshot: &EditorSnapshot,
+        bounds: Bounds<Pixels>,
+        minimap_width: Pixels,
+        scroll_position: gpui::Point<f32>,
+        rows_per_page: f32,
+        cx: &mut WindowContext,
+    ) -> Option<MinimapLayout> {
+        if minimap_width == Pixels::ZERO {
+            return None;
+        }
+
+        let right = self.scrollbar_left(&bounds);
+        let minimap_bounds = Bounds::from_corners(
+            point(
+                right - (minimap_width - self.style.scrollbar_width),
+                bounds.top(),
+            ),
+            point(right, bounds.bottom()),
+        );
+
+        let max_row = snapshot.max_point().row();
+        let minimap_rows = minimap_bounds.size.height / MinimapLayout::ROW_HEIGHT;
+        let row_count = max_row as f32 + 1.;
+        // When the buffer doesn't fit in the minimap, it scrolls along with the editor so that
+        // both reach the end of the buffer together.
+        let first_row = if row_count > minimap_rows && max_row > 0 {
+            (scroll_position.y / max_row as f32).clamp(0., 1.) * (row_count - minimap_rows)
+        } else {
+            0.
+        };
+
+        Some(MinimapLayout {
+            hitbox: cx.insert_hitbox(minimap_bounds, false),
+            first_row,
+            visible_row_range: scroll_position.y..scroll_position.y + rows_per_page,
+            max_row,
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_gutter_fold_indicators(
         &self,
@@ -1168,6 +1245,55 @@ impl EditorElement {
         indicators
     }
 
+    fn layout_indent_guides(
+        &self,
+        display_rows: Range<u32>,
+        snapshot: &EditorSnapshot,
+        cx: &WindowContext,
+    ) -> Vec<IndentGuideLayout> {
+        let editor = self.editor.read(cx);
+        if editor.show_indent_guides == Some(false) {
+            return Vec::new();
+        }
+
+        let buffer_snapshot = &snapshot.buffer_snapshot;
+        let cursor = editor
+            .selections
+            .newest_anchor()
+            .head()
+            .to_point(buffer_snapshot);
+        let buffer_start_row = DisplayPoint::new(display_rows.start, 0)
+            .to_point(snapshot)
+            .row;
+        let buffer_end_row = DisplayPoint::new(display_rows.end, 0)
+            .to_point(snapshot)
+            .row;
+
+        snapshot
+            .indent_guides_in_range(buffer_start_row..buffer_end_row + 1, Some(cursor))
+            .into_iter()
+            .filter_map(|guide| {
+                let settings = buffer_snapshot
+                    .settings_at(Point::new(guide.buffer_rows.start, 0), cx)
+                    .indent_guides;
+                if !settings.enabled {
+                    return None;
+                }
+
+                let last_row = guide.buffer_rows.end - 1;
+                let start = Point::new(guide.buffer_rows.start, 0).to_display_point(snapshot);
+                let end = Point::new(last_row, buffer_snapshot.line_len(last_row))
+                    .to_display_point(snapshot);
+                Some(IndentGuideLayout {
+                    display_rows: start.row()..end.row() + 1,
+                    column: guide.column,
+                    line_width: px(settings.line_width.clamp(1, 10) as f32),
+                    active: guide.active && settings.active_scope,
+                })
+            })
+            .collect()
+    }
+
     // Folds contained in a hunk are ignored apart from shrinking visual size
     // If a fold contains any hunks then that fold line is marked as modified
     fn layout_git_gutters(
@@ -1305,6 +1431,77 @@ impl EditorElement {
         Some(element)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_sticky_headers(
+        &self,
+        display_row: u32,
+        snapshot: &EditorSnapshot,
+        text_hitbox: &Hitbox,
+        content_origin: gpui::Point<Pixels>,
+        em_width: Pixels,
+        line_height: Pixels,
+        cx: &mut WindowContext,
+    ) -> Option<AnyElement> {
+        if snapshot.mode != EditorMode::Full || display_row == 0 {
+            return None;
+        }
+
+        let top_row = DisplayPoint::new(display_row, 0).to_point(snapshot).row;
+        let settings = snapshot
+            .buffer_snapshot
+            .settings_at(Point::new(top_row, 0), cx)
+            .sticky_scroll;
+        if !settings.enabled {
+            return None;
+        }
+
+        let headers =
+            snapshot.sticky_headers(top_row, settings.max_depth, Some(&self.style.syntax));
+        if headers.is_empty() {
+            return None;
+        }
+
+        let mut element = v_flex()
+            .occlude()
+            .w(text_hitbox.size.width)
+            .bg(self.style.background)
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .children(headers.into_iter().enumerate().map(|(ix, header)| {
+                let start = header.range.start;
+                let column = start.to_display_point(snapshot).column();
+                let editor = self.editor.clone();
+                h_flex()
+                    .id(("sticky-header", ix))
+                    .h(line_height)
+                    .w_full()
+                    .pl(content_origin.x - text_hitbox.origin.x + em_width * column as f32)
+                    .cursor_pointer()
+                    .hover(|style| style.bg(cx.theme().colors().editor_active_line_background))
+                    .child(
+                        StyledText::new(header.text)
+                            .with_highlights(&self.style.text, header.highlight_ranges),
+                    )
+                    .on_mouse_down(MouseButton::Left, move |_, cx| {
+                        cx.stop_propagation();
+                        editor.update(cx, |editor, cx| {
+                            // Keep the header's row where it was pinned.
+                            editor.change_selections(Some(Autoscroll::top_relative(ix)), cx, |s| {
+                                s.select_ranges([start..start])
+                            });
+                        });
+                    })
+            }))
+            .into_any();
+
+        let available_space = size(
+            AvailableSpace::Definite(text_hitbox.size.width),
+            AvailableSpace::MinContent,
+        );
+        element.prepaint_as_root(text_hitbox.origin, available_space, cx);
+        Some(element)
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_blame_entries(
         &self,
@@ -1415,6 +1612,106 @@ impl EditorElement {
         Some(button)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_bookmark_indicators(
+        &self,
+        snapshot: &EditorSnapshot,
+        rows: Range<u32>,
+        line_height: Pixels,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        cx: &mut WindowContext,
+    ) -> Vec<AnyElement> {
+        let bookmarked_rows = self
+            .editor
+            .read(cx)
+            .bookmarked_rows(&snapshot.buffer_snapshot, cx);
+        let x = gutter_dimensions
+            .git_blame_entries_width
+            .unwrap_or(Pixels::ZERO);
+
+        let mut last_display_row = None;
+        bookmarked_rows
+            .into_iter()
+            .filter_map(|row| {
+                let display_row = Point::new(row, 0)
+                    .to_display_point(&snapshot.display_snapshot)
+                    .row();
+                // Rows hidden in a fold share the display row of the fold.
+                if !rows.contains(&display_row) || last_display_row == Some(display_row) {
+                    return None;
+                }
+                last_display_row = Some(display_row);
+
+                let mut indicator = Icon::new(IconName::Bookmark)
+                    .size(IconSize::XSmall)
+                    .color(Color::Accent)
+                    .into_any_element();
+                let available_space = size(
+                    AvailableSpace::MinContent,
+                    AvailableSpace::Definite(line_height),
+                );
+                let indicator_size = indicator.layout_as_root(available_space, cx);
+                let y = display_row as f32 * line_height - scroll_pixel_position.y
+                    + (line_height - indicator_size.height) / 2.;
+                indicator.prepaint_as_root(gutter_hitbox.origin + point(x, y), available_space, cx);
+                Some(indicator)
+            })
+            .collect()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn layout_review_thread_indicators(
+        &self,
+        snapshot: &EditorSnapshot,
+        rows: Range<u32>,
+        line_height: Pixels,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        em_width: Pixels,
+        cx: &mut WindowContext,
+    ) -> Vec<AnyElement> {
+        let thread_rows = self
+            .editor
+            .read(cx)
+            .review_thread_rows(&snapshot.buffer_snapshot, cx);
+        // Review threads are shown next to bookmarks, which are at the start of the gutter.
+        let x = gutter_dimensions
+            .git_blame_entries_width
+            .unwrap_or(Pixels::ZERO)
+            + em_width * 1.5;
+
+        let mut last_display_row = None;
+        thread_rows
+            .into_iter()
+            .filter_map(|row| {
+                let display_row = Point::new(row, 0)
+                    .to_display_point(&snapshot.display_snapshot)
+                    .row();
+                if !rows.contains(&display_row) || last_display_row == Some(display_row) {
+                    return None;
+                }
+                last_display_row = Some(display_row);
+
+                let mut indicator = Icon::new(IconName::MessageBubbles)
+                    .size(IconSize::XSmall)
+                    .color(Color::Accent)
+                    .into_any_element();
+                let available_space = size(
+                    AvailableSpace::MinContent,
+                    AvailableSpace::Definite(line_height),
+                );
+                let indicator_size = indicator.layout_as_root(available_space, cx);
+                let y = display_row as f32 * line_height - scroll_pixel_position.y
+                    + (line_height - indicator_size.height) / 2.;
+                indicator.prepaint_as_root(gutter_hitbox.origin + point(x, y), available_space, cx);
+                Some(indicator)
+            })
+            .collect()
+    }
+
     fn get_participant_color(
         participant_index: Option<ParticipantIndex>,
         cx: &WindowContext,
@@ -2299,6 +2596,36 @@ impl EditorElement {
                         color,
                     ));
                 }
+
+                for guide in &layout.indent_guides {
+                    let x = layout.content_origin.x
+                        + guide.column as f32 * layout.position_map.em_width
+                        - scroll_left;
+                    if x < layout.text_hitbox.origin.x {
+                        continue;
+                    }
+
+                    let color = if guide.active {
+                        cx.theme().colors().editor_indent_guide_active
+                    } else {
+                        cx.theme().colors().editor_indent_guide
+                    };
+                    cx.paint_quad(fill(
+                        Bounds {
+                            origin: point(
+                                x,
+                                layout.hitbox.origin.y
+                                    + (guide.display_rows.start as f32 - scroll_top)
+                                        * layout.position_map.line_height,
+                            ),
+                            size: size(
+                                guide.line_width,
+                                layout.position_map.line_height * guide.display_rows.len() as f32,
+                            ),
+                        },
+                        color,
+                    ));
+                }
             }
         })
     }
@@ -2349,6 +2676,18 @@ impl EditorElement {
                 }
             });
 
+            cx.with_element_namespace("bookmark_indicators", |cx| {
+                for indicator in &mut layout.bookmark_indicators {
+                    indicator.paint(cx);
+                }
+            });
+
+            cx.with_element_namespace("review_thread_indicators", |cx| {
+                for indicator in &mut layout.review_thread_indicators {
+                    indicator.paint(cx);
+                }
+            });
+
             if let Some(indicator) = layout.code_actions_indicator.as_mut() {
                 indicator.paint(cx);
             }
@@ -2756,6 +3095,192 @@ impl EditorElement {
         }
     }
 
+    fn paint_minimap(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
+        let Some(minimap_layout) = layout.minimap_layout.as_ref() else {
+            return;
+        };
+
+        // Lay out the lines in the background. Below, we paint whatever lines have already been laid out.
+        self.refresh_minimap(layout, minimap_layout, cx);
+
+        let slider_bounds = minimap_layout.slider_bounds();
+        cx.paint_layer(minimap_layout.hitbox.bounds, |cx| {
+            cx.with_content_mask(
+                Some(ContentMask {
+                    bounds: minimap_layout.hitbox.bounds,
+                }),
+                |cx| {
+                    cx.paint_quad(fill(minimap_layout.hitbox.bounds, self.style.background));
+
+                    let (rows, quads) = {
+                        let minimap_state = &self.editor.read(cx).minimap_state;
+                        (minimap_state.rows.clone(), minimap_state.quads.clone())
+                    };
+                    let origin = point(
+                        minimap_layout.hitbox.left(),
+                        minimap_layout.y_for_row(rows.start as f32),
+                    );
+                    for quad in quads.iter() {
+                        let mut quad = quad.clone();
+                        quad.bounds.origin += origin;
+                        cx.paint_quad(quad);
+                    }
+
+                    let slider_color = if self.editor.read(cx).minimap_state.is_dragging
+                        || slider_bounds.contains(&cx.mouse_position())
+                    {
+                        cx.theme().colors().scrollbar_thumb_hover_background
+                    } else {
+                        cx.theme().colors().scrollbar_thumb_background
+                    };
+                    cx.paint_quad(fill(slider_bounds, slider_color));
+                },
+            );
+        });
+
+        cx.set_cursor_style(CursorStyle::Arrow, &minimap_layout.hitbox);
+
+        let rows_per_pixel = minimap_layout.rows_per_pixel();
+        let rows_per_page =
+            minimap_layout.visible_row_range.end - minimap_layout.visible_row_range.start;
+
+        cx.on_mouse_event({
+            let editor = self.editor.clone();
+            let mut mouse_position = cx.mouse_position();
+            move |event: &MouseMoveEvent, phase, cx| {
+                if phase == DispatchPhase::Capture {
+                    return;
+                }
+
+                editor.update(cx, |editor, cx| {
+                    if event.pressed_button == Some(MouseButton::Left)
+                        && editor.minimap_state.is_dragging
+                    {
+                        let mut position = editor.scroll_position(cx);
+                        position.y += (event.position.y - mouse_position.y).0 * rows_per_pixel;
+                        if position.y < 0.0 {
+                            position.y = 0.0;
+                        }
+                        editor.set_scroll_position(position, cx);
+                        cx.stop_propagation();
+                    } else if editor.minimap_state.is_dragging {
+                        editor.minimap_state.is_dragging = false;
+                        cx.notify();
+                    }
+                    mouse_position = event.position;
+                })
+            }
+        });
+
+        if self.editor.read(cx).minimap_state.is_dragging {
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                move |_: &MouseUpEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.minimap_state.is_dragging = false;
+                        cx.notify();
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        } else {
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                let minimap_layout = minimap_layout.clone();
+                move |event: &MouseDownEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture
+                        || event.button != MouseButton::Left
+                        || !minimap_layout.hitbox.is_hovered(cx)
+                    {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.minimap_state.is_dragging = true;
+
+                        // Clicking outside of the slider centers it on the clicked row.
+                        if !slider_bounds.contains(&event.position) {
+                            let row = minimap_layout.row_for_y(event.position.y);
+                            let mut position = editor.scroll_position(cx);
+                            position.y = (row - rows_per_page / 2.).max(0.);
+                            editor.set_scroll_position(position, cx);
+                        }
+
+                        cx.notify();
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        }
+    }
+
+    fn refresh_minimap(
+        &self,
+        layout: &EditorLayout,
+        minimap_layout: &MinimapLayout,
+        cx: &mut WindowContext,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            let visible_rows = minimap_layout.rows();
+            let minimap_size = minimap_layout.hitbox.size;
+            let max_row = minimap_layout.max_row;
+            if !editor
+                .minimap_state
+                .should_refresh(&visible_rows, minimap_size, max_row)
+            {
+                return;
+            }
+
+            // Lay out a page of rows on both sides of the visible ones, so that scrolling
+            // doesn't need a refresh on every frame.
+            let page = visible_rows.len() as u32;
+            let rows =
+                visible_rows.start.saturating_sub(page)..(visible_rows.end + page).min(max_row + 1);
+            let snapshot = layout.position_map.snapshot.clone();
+            let search_ranges = editor
+                .background_highlights
+                .get(&TypeId::of::<BufferSearchHighlights>())
+                .map(|(_, ranges)| ranges.clone());
+            let theme = cx.theme().clone();
+            let minimap_settings = EditorSettings::get_global(cx).minimap;
+
+            editor.minimap_state.dirty = false;
+            editor.minimap_state.pending_refresh = Some(cx.spawn(|editor, mut cx| async move {
+                let quads = cx
+                    .background_executor()
+                    .spawn({
+                        let rows = rows.clone();
+                        async move {
+                            Arc::from(layout_minimap_quads(
+                                &snapshot,
+                                rows,
+                                minimap_size.width,
+                                search_ranges.as_deref(),
+                                &theme,
+                                minimap_settings,
+                            ))
+                        }
+                    })
+                    .await;
+
+                editor.update(&mut cx, |editor, cx| {
+                    editor.minimap_state.rows = rows;
+                    editor.minimap_state.size = minimap_size;
+                    editor.minimap_state.max_row = max_row;
+                    editor.minimap_state.quads = quads;
+                    editor.minimap_state.pending_refresh = None;
+                    cx.notify();
+                })?;
+
+                Ok(())
+            }));
+        });
+    }
+
     fn collect_fast_scrollbar_markers(
         &self,
         layout: &EditorLayout,
@@ -3034,6 +3559,14 @@ impl EditorElement {
         }
     }
 
+    fn paint_sticky_headers(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
+        if let Some(mut sticky_headers) = layout.sticky_headers.take() {
+            cx.paint_layer(layout.text_hitbox.bounds, |cx| {
+                sticky_headers.paint(cx);
+            })
+        }
+    }
+
     fn paint_blocks(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
         for mut block in layout.blocks.drain(..) {
             block.element.paint(cx);
@@ -3640,7 +4173,9 @@ impl Element for EditorElement {
                     self.max_line_number_width(&snapshot, cx),
                     cx,
                 );
-                let text_width = bounds.size.width - gutter_dimensions.width;
+                let minimap_width =
+                    self.minimap_width(&snapshot, bounds.size.height / line_height, cx);
+                let text_width = bounds.size.width - gutter_dimensions.width - minimap_width;
                 let overscroll = size(em_width, px(0.));
 
                 snapshot = self.editor.update(cx, |editor, cx| {
@@ -3774,6 +4309,8 @@ impl Element for EditorElement {
                     cx,
                 );
 
+                let indent_guides = self.layout_indent_guides(start_row..end_row, &snapshot, cx);
+
                 let mut max_visible_line_width = Pixels::ZERO;
                 let line_layouts =
                     self.layout_lines(start_row..end_row, &line_numbers, &snapshot, cx);
@@ -3828,6 +4365,18 @@ impl Element for EditorElement {
                     }
                 }
 
+                let sticky_headers = cx.with_element_namespace("sticky_headers", |cx| {
+                    self.layout_sticky_headers(
+                        start_row,
+                        &snapshot,
+                        &text_hitbox,
+                        content_origin,
+                        em_width,
+                        line_height,
+                        cx,
+                    )
+                });
+
                 let blamed_display_rows = self.layout_blame_entries(
                     buffer_rows,
                     em_width,
@@ -3905,6 +4454,15 @@ impl Element for EditorElement {
                     cx,
                 );
 
+                let minimap_layout = self.layout_minimap(
+                    &snapshot,
+                    bounds,
+                    minimap_width,
+                    scroll_position,
+                    height_in_lines,
+                    cx,
+                );
+
                 let folds = cx.with_element_namespace("folds", |cx| {
                     self.layout_folds(
                         &snapshot,
@@ -3920,6 +4478,31 @@ impl Element for EditorElement {
 
                 let gutter_settings = EditorSettings::get_global(cx).gutter;
 
+                let bookmark_indicators = cx.with_element_namespace("bookmark_indicators", |cx| {
+                    self.layout_bookmark_indicators(
+                        &snapshot,
+                        start_row..end_row,
+                        line_height,
+                        scroll_pixel_position,
+                        &gutter_dimensions,
+                        &gutter_hitbox,
+                        cx,
+                    )
+                });
+                let review_thread_indicators =
+                    cx.with_element_namespace("review_thread_indicators", |cx| {
+                        self.layout_review_thread_indicators(
+                            &snapshot,
+                            start_row..end_row,
+                            line_height,
+                            scroll_pixel_position,
+                            &gutter_dimensions,
+                            &gutter_hitbox,
+                            em_width,
+                            cx,
+                        )
+                    });
+
                 let mut context_menu_visible = false;
                 let mut code_actions_indicator = None;
                 if let Some(newest_selection_head) = newest_selection_head {
@@ -4028,12 +4611,14 @@ impl Element for EditorElement {
                     }),
                     visible_display_row_range: start_row..end_row,
                     wrap_guides,
+                    indent_guides,
                     hitbox,
                     text_hitbox,
                     gutter_hitbox,
                     gutter_dimensions,
                     content_origin,
                     scrollbar_layout,
+                    minimap_layout,
                     max_row,
                     active_rows,
                     highlighted_rows,
@@ -4043,12 +4628,15 @@ impl Element for EditorElement {
                     display_hunks,
                     blamed_display_rows,
                     inline_blame,
+                    sticky_headers,
                     folds,
                     blocks,
                     cursors,
                     visible_cursors,
                     selections,
                     mouse_context_menu,
+                    bookmark_indicators,
+                    review_thread_indicators,
                     code_actions_indicator,
                     fold_indicators,
                     tab_invisible,
@@ -4125,6 +4713,11 @@ impl Element for EditorElement {
                     });
                 }
 
+                cx.with_element_namespace("sticky_headers", |cx| {
+                    self.paint_sticky_headers(layout, cx);
+                });
+
+                self.paint_minimap(layout, cx);
                 self.paint_scrollbar(layout, cx);
                 self.paint_mouse_context_menu(layout, cx);
             });
@@ -4150,8 +4743,10 @@ pub struct EditorLayout {
     gutter_dimensions: GutterDimensions,
     content_origin: gpui::Point<Pixels>,
     scrollbar_layout: Option<ScrollbarLayout>,
+    minimap_layout: Option<MinimapLayout>,
     mode: EditorMode,
     wrap_guides: SmallVec<[(Pixels, bool); 2]>,
+    indent_guides: Vec<IndentGuideLayout>,
     visible_display_row_range: Range<u32>,
     active_rows: BTreeMap<u32, bool>,
     highlighted_rows: BTreeMap<u32, Hsla>,
@@ -4159,6 +4754,7 @@ pub struct EditorLayout {
     display_hunks: Vec<(DisplayDiffHunk, Option<Hitbox>)>,
     blamed_display_rows: Option<Vec<AnyElement>>,
     inline_blame: Option<AnyElement>,
+    sticky_headers: Option<AnyElement>,
     folds: Vec<FoldLayout>,
     blocks: Vec<BlockLayout>,
     highlighted_ranges: Vec<(Range<DisplayPoint>, Hsla)>,
@@ -4167,6 +4763,8 @@ pub struct EditorLayout {
     visible_cursors: Vec<CursorLayout>,
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     max_row: u32,
+    bookmark_indicators: Vec<AnyElement>,
+    review_thread_indicators: Vec<AnyElement>,
     code_actions_indicator: Option<AnyElement>,
     fold_indicators: Vec<Option<AnyElement>>,
     mouse_context_menu: Option<AnyElement>,
@@ -4292,11 +4890,262 @@ impl ScrollbarLayout {
     }
 }
 
+#[derive(Clone)]
+struct MinimapLayout {
+    hitbox: Hitbox,
+    /// The display row shown at the top of the minimap.
+    first_row: f32,
+    /// The display rows visible in the editor, covered by the slider.
+    visible_row_range: Range<f32>,
+    max_row: u32,
+}
+
+impl MinimapLayout {
+    const ROW_HEIGHT: Pixels = px(2.0);
+    const COLUMN_WIDTH: Pixels = px(1.0);
+    const MARKER_WIDTH: Pixels = px(2.0);
+
+    /// The display rows shown in the minimap.
+    fn rows(&self) -> Range<u32> {
+        let row_count = self.hitbox.size.height / Self::ROW_HEIGHT;
+        let start = self.first_row.floor() as u32;
+        let end = ((self.first_row + row_count).ceil() as u32).min(self.max_row + 1);
+        start..end.max(start)
+    }
+
+    fn y_for_row(&self, row: f32) -> Pixels {
+        self.hitbox.top() + (row - self.first_row) * Self::ROW_HEIGHT
+    }
+
+    fn row_for_y(&self, y: Pixels) -> f32 {
+        self.first_row + (y - self.hitbox.top()) / Self::ROW_HEIGHT
+    }
+
+    fn slider_bounds(&self) -> Bounds<Pixels> {
+        let top = self.y_for_row(self.visible_row_range.start);
+        let bottom = self.y_for_row(self.visible_row_range.end);
+        Bounds::from_corners(
+            point(self.hitbox.left(), top.max(self.hitbox.top())),
+            point(self.hitbox.right(), bottom.min(self.hitbox.bottom())),
+        )
+    }
+
+    /// How many rows the editor scrolls when the slider is dragged by one pixel. The minimap
+    /// scrolls along with the editor, so the slider moves slower than the rows under it.
+    fn rows_per_pixel(&self) -> f32 {
+        let row_count = self.max_row as f32 + 1.;
+        let minimap_rows = self.hitbox.size.height / Self::ROW_HEIGHT;
+        let minimap_scroll_ratio = if row_count > minimap_rows && self.max_row > 0 {
+            (row_count - minimap_rows) / self.max_row as f32
+        } else {
+            0.
+        };
+        1. / (Self::ROW_HEIGHT.0 * (1. - minimap_scroll_ratio).max(0.1))
+    }
+}
+
+/// Lays out the quads of the minimap for the given display rows, relative to the first of them.
+fn layout_minimap_quads(
+    snapshot: &EditorSnapshot,
+    rows: Range<u32>,
+    width: Pixels,
+    search_ranges: Option<&[Range<Anchor>]>,
+    theme: &Theme,
+    minimap_settings: Minimap,
+) -> Vec<PaintQuad> {
+    let mut quads = Vec::new();
+    if rows.is_empty() {
+        return quads;
+    }
+
+    let row_bounds = |start: u32, end: u32, x_range: Range<Pixels>| {
+        Bounds::from_corners(
+            point(
+                x_range.start,
+                (start - rows.start) as f32 * MinimapLayout::ROW_HEIGHT,
+            ),
+            point(
+                x_range.end,
+                (end + 1 - rows.start) as f32 * MinimapLayout::ROW_HEIGHT,
+            ),
+        )
+    };
+    let clamp_rows = |start: u32, end: u32| {
+        let start = start.max(rows.start);
+        let end = end.min(rows.end - 1);
+        (start <= end).then_some((start, end))
+    };
+
+    let buffer_start = DisplayPoint::new(rows.start, 0).to_point(&snapshot.display_snapshot);
+    let buffer_end = snapshot
+        .display_snapshot
+        .clip_point(DisplayPoint::new(rows.end, 0), Bias::Left)
+        .to_point(&snapshot.display_snapshot);
+    let buffer_end = if rows.end > snapshot.max_point().row() {
+        snapshot.buffer_snapshot.max_point()
+    } else {
+        buffer_end
+    };
+
+    if minimap_settings.search_results {
+        if let Some(search_ranges) = search_ranges {
+            let buffer = &snapshot.buffer_snapshot;
+            let start_anchor = buffer.anchor_before(buffer_start);
+            let end_anchor = buffer.anchor_after(buffer_end);
+            let first_ix =
+                search_ranges.partition_point(|range| range.end.cmp(&start_anchor, buffer).is_lt());
+            let mut color = theme.status().info;
+            color.fade_out(0.6);
+            for range in &search_ranges[first_ix..] {
+                if range.start.cmp(&end_anchor, buffer).is_gt() {
+                    break;
+                }
+                let start = range
+                    .start
+                    .to_display_point(&snapshot.display_snapshot)
+                    .row();
+                let end = range.end.to_display_point(&snapshot.display_snapshot).row();
+                if let Some((start, end)) = clamp_rows(start, end) {
+                    quads.push(fill(row_bounds(start, end, Pixels::ZERO..width), color));
+                }
+            }
+        }
+    }
+
+    let text_left = MinimapLayout::MARKER_WIDTH;
+    let text_right = width - MinimapLayout::MARKER_WIDTH;
+    let default_color = theme.colors().editor_foreground;
+    let mut row = rows.start;
+    let mut column = 0;
+    let mut run: Option<(u32, u32, Hsla)> = None;
+    let push_run = |row: u32, run: Option<(u32, u32, Hsla)>, quads: &mut Vec<PaintQuad>| {
+        if let Some((start_column, end_column, color)) = run {
+            let left = text_left + start_column as f32 * MinimapLayout::COLUMN_WIDTH;
+            let right =
+                (text_left + end_column as f32 * MinimapLayout::COLUMN_WIDTH).min(text_right);
+            if left < right {
+                let top = (row - rows.start) as f32 * MinimapLayout::ROW_HEIGHT;
+                quads.push(fill(
+                    Bounds::from_corners(
+                        point(left, top),
+                        point(right, top + MinimapLayout::ROW_HEIGHT * 0.75),
+                    ),
+                    color,
+                ));
+            }
+        }
+    };
+    for chunk in snapshot.chunks(rows.clone(), true, HighlightStyles::default()) {
+        let mut color = chunk
+            .highlight_style
+            .and_then(|style| style.color)
+            .or_else(|| {
+                chunk
+                    .syntax_highlight_id
+                    .and_then(|id| id.style(theme.syntax()))
+                    .and_then(|style| style.color)
+            })
+            .unwrap_or(default_color);
+        color.fade_out(0.4);
+
+        for c in chunk.text.chars() {
+            if c == '\n' {
+                push_run(row, run.take(), &mut quads);
+                row += 1;
+                column = 0;
+                continue;
+            }
+
+            if c.is_whitespace() {
+                push_run(row, run.take(), &mut quads);
+            } else {
+                match &mut run {
+                    Some((_, end_column, run_color)) if *run_color == color => {
+                        *end_column = column + 1;
+                    }
+                    _ => {
+                        push_run(row, run.take(), &mut quads);
+                        run = Some((column, column + 1, color));
+                    }
+                }
+            }
+            column += 1;
+        }
+    }
+    push_run(row, run.take(), &mut quads);
+
+    if minimap_settings.git_diff {
+        for hunk in snapshot
+            .buffer_snapshot
+            .git_diff_hunks_in_range(buffer_start.row..buffer_end.row + 1)
+        {
+            let start = Point::new(hunk.associated_range.start, 0)
+                .to_display_point(&snapshot.display_snapshot)
+                .row();
+            let mut end = Point::new(hunk.associated_range.end, 0)
+                .to_display_point(&snapshot.display_snapshot)
+                .row();
+            if end != start {
+                end -= 1;
+            }
+            let color = match hunk.status() {
+                DiffHunkStatus::Added => theme.status().created,
+                DiffHunkStatus::Modified => theme.status().modified,
+                DiffHunkStatus::Removed => theme.status().deleted,
+            };
+            if let Some((start, end)) = clamp_rows(start, end) {
+                quads.push(fill(
+                    row_bounds(start, end, Pixels::ZERO..MinimapLayout::MARKER_WIDTH),
+                    color,
+                ));
+            }
+        }
+    }
+
+    if minimap_settings.diagnostics {
+        let diagnostics = snapshot
+            .buffer_snapshot
+            .diagnostics_in_range::<_, Point>(buffer_start..buffer_end, false)
+            // We want to sort by severity, in order to paint the most severe diagnostics last.
+            .sorted_by_key(|diagnostic| cmp::Reverse(diagnostic.diagnostic.severity));
+        for diagnostic in diagnostics {
+            let start = diagnostic
+                .range
+                .start
+                .to_display_point(&snapshot.display_snapshot)
+                .row();
+            let end = diagnostic
+                .range
+                .end
+                .to_display_point(&snapshot.display_snapshot)
+                .row();
+            let color = match diagnostic.diagnostic.severity {
+                DiagnosticSeverity::ERROR => theme.status().error,
+                DiagnosticSeverity::WARNING => theme.status().warning,
+                DiagnosticSeverity::INFORMATION => theme.status().info,
+                _ => theme.status().hint,
+            };
+            if let Some((start, end)) = clamp_rows(start, end) {
+                quads.push(fill(row_bounds(start, end, text_right..width), color));
+            }
+        }
+    }
+
+    quads
+}
+
 struct FoldLayout {
     display_range: Range<DisplayPoint>,
     hover_element: AnyElement,
 }
 
+struct IndentGuideLayout {
+    display_rows: Range<u32>,
+    column: u32,
+    line_width: Pixels,
+    active: bool,
+}
+
 struct PositionMap {
     size: Size<Pixels>,
     line_height: Pixels,
@@ -4679,12 +5528,14 @@ mod tests {
     use super::*;
     use crate::{
         display_map::{BlockDisposition, BlockProperties},
+        editor_settings::MinimapContent,
         editor_tests::{init_test, update_test_language_settings},
         Editor, MultiBuffer,
     };
     use gpui::{TestAppContext, VisualTestContext};
     use language::language_settings;
     use log::info;
+    use settings::SettingsStore;
     use std::num::NonZeroU32;
     use ui::Context;
     use util::test::sample_text;
@@ -4932,6 +5783,51 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_layout_with_minimap(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.minimap = Some(MinimapContent {
+                        show: Some(ShowMinimap::Always),
+                        width: Some(80.),
+                        git_diff: Some(true),
+                        search_results: Some(true),
+                        diagnostics: Some(true),
+                    });
+                });
+            });
+        });
+
+        let window = cx.add_window(|cx| {
+            let buffer = MultiBuffer::build_simple(&sample_text(200, 20, 'a'), cx);
+            Editor::new(EditorMode::Full, buffer, None, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style.clone())
+        });
+        let minimap_layout = state.minimap_layout.as_ref().unwrap();
+        assert_eq!(minimap_layout.hitbox.size.width, px(80.));
+        assert_eq!(minimap_layout.first_row, 0.);
+        assert_eq!(
+            state.text_hitbox.size.width,
+            px(500.) - state.gutter_hitbox.size.width - px(80.) - style.scrollbar_width
+        );
+
+        // The lines of the minimap are laid out in the background.
+        cx.run_until_parked();
+        editor.update(cx, |editor, _| {
+            let minimap_state = &editor.minimap_state;
+            assert_eq!(minimap_state.rows, 0..200);
+            assert!(!minimap_state.quads.is_empty());
+        });
+    }
+
     #[gpui::test]
     fn test_all_invisibles_drawing(cx: &mut TestAppContext) {
         const TAB_SIZE: u32 = 4;
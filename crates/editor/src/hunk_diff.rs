@@ -591,6 +591,7 @@ fn editor_with_deleted_text(
         let mut editor = Editor::for_multibuffer(multi_buffer, None, cx);
         editor.soft_wrap_mode_override = Some(language::language_settings::SoftWrap::None);
         editor.show_wrap_guides = Some(false);
+        editor.show_indent_guides = Some(false);
         editor.show_gutter = false;
         editor.scroll_manager.set_forbid_vertical_scroll(true);
         editor.set_read_only(true);
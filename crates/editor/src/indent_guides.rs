@@ -0,0 +1,199 @@
+use collections::HashMap;
+use multi_buffer::{MultiBufferSnapshot, ToOffset, ToPoint};
+use parking_lot::Mutex;
+use std::ops::Range;
+use text::{BufferId, Point};
+
+/// A vertical line drawn at an indentation level, across consecutive buffer rows indented at
+/// least that deep.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndentGuide {
+    /// The buffer rows the guide is drawn across.
+    pub buffer_rows: Range<u32>,
+    /// The indentation level of the guide, starting at 1 for the outermost one.
+    pub depth: u32,
+    /// The column the guide is drawn at.
+    pub column: u32,
+    /// Whether this is the guide of the block containing the cursor.
+    pub active: bool,
+}
+
+/// The indentation of buffer rows, measured in columns, kept across display snapshots so that
+/// guides don't need to be recomputed for every frame while scrolling.
+///
+/// The rows of a buffer are forgotten as soon as it is edited or its tab size changes.
+#[derive(Default)]
+pub(crate) struct IndentLevelCache {
+    buffers: HashMap<BufferId, BufferIndents>,
+}
+
+struct BufferIndents {
+    version: clock::Global,
+    tab_size: u32,
+    /// The indentation of each row measured so far, or `None` for blank rows.
+    widths: HashMap<u32, Option<u32>>,
+}
+
+impl IndentLevelCache {
+    fn indent_width(
+        &mut self,
+        snapshot: &MultiBufferSnapshot,
+        row: u32,
+        tab_size: u32,
+    ) -> Option<u32> {
+        let (buffer, range) = snapshot.buffer_line_for_row(row)?;
+        let indents = self
+            .buffers
+            .entry(buffer.remote_id())
+            .or_insert_with(|| BufferIndents {
+                version: buffer.version().clone(),
+                tab_size,
+                widths: HashMap::default(),
+            });
+        if indents.tab_size != tab_size || indents.version != *buffer.version() {
+            indents.version = buffer.version().clone();
+            indents.tab_size = tab_size;
+            indents.widths.clear();
+        }
+
+        *indents.widths.entry(range.start.row).or_insert_with(|| {
+            let mut width = 0;
+            for c in buffer.chars_at(Point::new(range.start.row, 0)) {
+                match c {
+                    ' ' => width += 1,
+                    '\t' => width += tab_size - width % tab_size,
+                    '\n' => return None,
+                    _ => return Some(width),
+                }
+            }
+            None
+        })
+    }
+}
+
+struct IndentLevels<'a> {
+    snapshot: &'a MultiBufferSnapshot,
+    cache: &'a mut IndentLevelCache,
+    tab_size: u32,
+}
+
+impl IndentLevels<'_> {
+    /// The indentation level of a row, or `None` if it is blank.
+    fn level(&mut self, row: u32) -> Option<u32> {
+        self.cache
+            .indent_width(self.snapshot, row, self.tab_size)
+            .map(|width| width / self.tab_size)
+    }
+
+    /// The indentation level of a row, where blank rows take the smaller level of the closest
+    /// non-blank rows around them, so guides run through the blank lines inside a block.
+    fn resolved_level(&mut self, row: u32) -> u32 {
+        if let Some(level) = self.level(row) {
+            return level;
+        }
+
+        let max_row = self.snapshot.max_buffer_row();
+        let previous = (0..row).rev().find_map(|row| self.level(row));
+        let next = (row + 1..=max_row).find_map(|row| self.level(row));
+        previous.unwrap_or(0).min(next.unwrap_or(0))
+    }
+
+    /// The depth and rows of the guide of the block containing the cursor, found from the
+    /// syntax tree when there is one and from the indentation otherwise.
+    fn active_scope(&mut self, cursor: Point) -> Option<(u32, Range<u32>)> {
+        let offset = cursor.to_offset(self.snapshot);
+        let mut range = offset..offset;
+        while let Some(ancestor) = self.snapshot.range_for_syntax_ancestor(range.clone()) {
+            let start = ancestor.start.to_point(self.snapshot);
+            let end = ancestor.end.to_point(self.snapshot);
+            if end.row > start.row {
+                let depth = self.resolved_level(start.row) + 1;
+                // Closing delimiters sit at the level of the first row, but blocks without
+                // them (as in Python) end with an indented row.
+                let end_row = if self.resolved_level(end.row) >= depth {
+                    end.row + 1
+                } else {
+                    end.row
+                };
+                // Only nodes that open an indented block have a guide, unlike the whole file.
+                if start.row + 1 < end_row && self.resolved_level(start.row + 1) >= depth {
+                    return Some((depth, start.row + 1..end_row));
+                }
+            }
+            range = ancestor;
+        }
+
+        let level = self.resolved_level(cursor.row);
+        let max_row = self.snapshot.max_buffer_row();
+        let next_level = (cursor.row + 1..=max_row).find_map(|row| self.level(row));
+        let (depth, first_row) = match next_level {
+            Some(next_level) if next_level > level && self.level(cursor.row).is_some() => {
+                (level + 1, cursor.row + 1)
+            }
+            _ if level > 0 => (level, cursor.row),
+            _ => return None,
+        };
+
+        let mut start_row = first_row;
+        while start_row > 0 && self.resolved_level(start_row - 1) >= depth {
+            start_row -= 1;
+        }
+        let mut end_row = first_row + 1;
+        while end_row <= max_row && self.resolved_level(end_row) >= depth {
+            end_row += 1;
+        }
+        Some((depth, start_row..end_row))
+    }
+}
+
+/// Computes the guides to draw across the given buffer rows, marking the one of the block
+/// containing the cursor as active.
+pub(crate) fn indent_guides_in_range(
+    snapshot: &MultiBufferSnapshot,
+    cache: &Mutex<IndentLevelCache>,
+    tab_size: u32,
+    buffer_rows: Range<u32>,
+    cursor: Option<Point>,
+) -> Vec<IndentGuide> {
+    let mut cache = cache.lock();
+    let mut levels = IndentLevels {
+        snapshot,
+        cache: &mut cache,
+        tab_size: tab_size.max(1),
+    };
+
+    let end_row = buffer_rows.end.min(snapshot.max_buffer_row() + 1);
+    let active_scope = cursor.and_then(|cursor| levels.active_scope(cursor));
+
+    let mut guides = Vec::new();
+    // The guides that are still open, from the outermost one.
+    let mut open: Vec<IndentGuide> = Vec::new();
+    for row in buffer_rows.start..end_row {
+        let level = levels.resolved_level(row) as usize;
+        if level < open.len() {
+            guides.extend(open.drain(level..).rev());
+        }
+        for guide in &mut open {
+            guide.buffer_rows.end = row + 1;
+        }
+        while open.len() < level {
+            let depth = open.len() as u32 + 1;
+            open.push(IndentGuide {
+                buffer_rows: row..row + 1,
+                depth,
+                column: (depth - 1) * levels.tab_size,
+                active: false,
+            });
+        }
+    }
+    guides.extend(open.into_iter().rev());
+
+    if let Some((depth, rows)) = active_scope {
+        for guide in &mut guides {
+            guide.active = guide.depth == depth
+                && guide.buffer_rows.start < rows.end
+                && rows.start < guide.buffer_rows.end;
+        }
+    }
+    guides
+}
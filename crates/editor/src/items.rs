@@ -1,7 +1,7 @@
 use crate::{
     editor_settings::SeedQuerySetting, persistence::DB, scroll::ScrollAnchor, Anchor, Autoscroll,
     Editor, EditorEvent, EditorSettings, ExcerptId, ExcerptRange, MultiBuffer, MultiBufferSnapshot,
-    NavigationData, SearchWithinRange, ToPoint as _,
+    NavigationData, SearchWithinRange, ToOffset as _, ToPoint as _,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::HashSet;
@@ -309,6 +309,7 @@ impl FollowableItem for Editor {
                 }
                 _ => false,
             },
+            _ => false,
         }
     }
 
@@ -318,7 +319,9 @@ impl FollowableItem for Editor {
         message: update_view::Variant,
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<()>> {
-        let update_view::Variant::Editor(message) = message;
+        let update_view::Variant::Editor(message) = message else {
+            return Task::ready(Err(anyhow!("unexpected update view variant")));
+        };
         let project = project.clone();
         cx.spawn(|this, mut cx| async move {
             update_editor_from_message(this, project, message, &mut cx).await
@@ -849,6 +852,7 @@ impl Item for Editor {
         let workspace_id = workspace.database_id();
         let item_id = cx.view().item_id().as_u64() as ItemId;
         self.workspace = Some((workspace.weak_handle(), workspace.database_id()));
+        self.register_bookmarked_buffers(cx);
 
         fn serialize(
             buffer: Model<Buffer>,
@@ -881,6 +885,7 @@ impl Item for Editor {
                             cx.view().item_id().as_u64() as ItemId,
                             cx,
                         );
+                        this.register_bookmarked_buffers(cx);
                     }
                 }
             })
@@ -986,6 +991,36 @@ impl ProjectItem for Editor {
 impl EventEmitter<SearchEvent> for Editor {}
 
 pub(crate) enum BufferSearchHighlights {}
+impl Editor {
+    /// Returns the text that replaces the given match of the query, or `None`
+    /// if the query has no replacement for it.
+    pub fn replacement_for(
+        &self,
+        range: &Range<Anchor>,
+        query: &SearchQuery,
+        cx: &AppContext,
+    ) -> Option<String> {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        if query.is_structural() {
+            let excerpt = snapshot.excerpt_containing(range.clone())?;
+            let range = range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot);
+            return query
+                .structural_replacement_for(excerpt.buffer(), excerpt.map_range_to_buffer(range));
+        }
+
+        let text = snapshot.text_for_range(range.clone()).collect::<Vec<_>>();
+        let text: Cow<_> = if text.len() == 1 {
+            text.first().cloned().unwrap().into()
+        } else {
+            let joined_chunks = text.join("");
+            joined_chunks.into()
+        };
+        query
+            .replacement_for(&text)
+            .map(|replacement| replacement.into_owned())
+    }
+}
+
 impl SearchableItem for Editor {
     type Match = Range<Anchor>;
 
@@ -1058,19 +1093,9 @@ impl SearchableItem for Editor {
         query: &SearchQuery,
         cx: &mut ViewContext<Self>,
     ) {
-        let text = self.buffer.read(cx);
-        let text = text.snapshot(cx);
-        let text = text.text_for_range(identifier.clone()).collect::<Vec<_>>();
-        let text: Cow<_> = if text.len() == 1 {
-            text.first().cloned().unwrap().into()
-        } else {
-            let joined_chunks = text.join("");
-            joined_chunks.into()
-        };
-
-        if let Some(replacement) = query.replacement_for(&text) {
+        if let Some(replacement) = self.replacement_for(identifier, query, cx) {
             self.transact(cx, |this, cx| {
-                this.edit([(identifier.clone(), Arc::from(&*replacement))], cx);
+                this.edit([(identifier.clone(), Arc::from(replacement))], cx);
             });
         }
     }
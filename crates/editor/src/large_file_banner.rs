@@ -0,0 +1,47 @@
+use crate::Editor;
+use gpui::{EventEmitter, ParentElement, Render, ViewContext};
+use ui::prelude::*;
+use workspace::{item::ItemHandle, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
+
+/// A banner shown above the editors of large files, which explains
+/// the features disabled for them to keep the editor responsive.
+#[derive(Default)]
+pub struct LargeFileBanner;
+
+impl LargeFileBanner {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Render for LargeFileBanner {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .gap_2()
+            .child(Icon::new(IconName::ExclamationTriangle).color(Color::Warning))
+            .child(Label::new(
+                "This file is opened in large file mode: syntax highlighting, \
+                language servers, inlay hints and git diff are disabled.",
+            ))
+    }
+}
+
+impl EventEmitter<ToolbarItemEvent> for LargeFileBanner {}
+
+impl ToolbarItemView for LargeFileBanner {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut ViewContext<Self>,
+    ) -> ToolbarItemLocation {
+        let is_large_file = active_pane_item
+            .and_then(|item| item.act_as::<Editor>(cx))
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+            .map_or(false, |buffer| buffer.read(cx).is_large_file());
+        if is_large_file {
+            ToolbarItemLocation::Secondary
+        } else {
+            ToolbarItemLocation::Hidden
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use anyhow::Result;
+
 use db::sqlez_macros::sql;
 use db::{define_connection, query};
 
@@ -15,6 +17,13 @@ define_connection!(
     //   scroll_vertical_offset: f32,
     //   scroll_horizontal_offset: f32,
     // )
+    //
+    // bookmarks(
+    //   workspace_id: usize,
+    //   path: PathBuf,
+    //   row: u32,
+    //   label: Option<String>,
+    // )
     pub static ref DB: EditorDb<WorkspaceDb> =
         &[sql! (
             CREATE TABLE editors(
@@ -31,6 +40,17 @@ define_connection!(
             ALTER TABLE editors ADD COLUMN scroll_top_row INTEGER NOT NULL DEFAULT 0;
             ALTER TABLE editors ADD COLUMN scroll_horizontal_offset REAL NOT NULL DEFAULT 0;
             ALTER TABLE editors ADD COLUMN scroll_vertical_offset REAL NOT NULL DEFAULT 0;
+        ),
+        sql! (
+            CREATE TABLE bookmarks(
+                workspace_id INTEGER NOT NULL,
+                path BLOB NOT NULL,
+                row INTEGER NOT NULL,
+                label TEXT,
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
         )];
 );
 
@@ -80,4 +100,32 @@ impl EditorDb {
             WHERE item_id = ?1 AND workspace_id = ?2
         }
     }
+
+    query! {
+        pub async fn get_bookmarks(workspace_id: WorkspaceId) -> Result<Vec<(PathBuf, u32, Option<String>)>> {
+            SELECT path, row, label FROM bookmarks
+            WHERE workspace_id = ?
+        }
+    }
+
+    pub async fn save_bookmarks(
+        &self,
+        workspace_id: WorkspaceId,
+        bookmarks: Vec<(PathBuf, u32, Option<String>)>,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("update_bookmarks", || {
+                conn.exec_bound(sql!(DELETE FROM bookmarks WHERE workspace_id = ?))?(workspace_id)?;
+
+                let mut insert = conn.exec_bound(sql!(
+                    INSERT INTO bookmarks(workspace_id, path, row, label) VALUES (?1, ?2, ?3, ?4)
+                ))?;
+                for (path, row, label) in bookmarks {
+                    insert((workspace_id, path, row, label))?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
 }
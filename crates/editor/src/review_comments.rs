@@ -0,0 +1,502 @@
+use std::{mem, ops::Range};
+
+use collections::{HashMap, HashSet};
+use gpui::{
+    AnyElement, AppContext, FontWeight, HighlightStyle, Model, StyledText, View, ViewContext,
+    WeakModel, WeakView,
+};
+use itertools::Itertools;
+use language::Buffer;
+use multi_buffer::MultiBufferSnapshot;
+use project::{Project, ReviewThread};
+use time::OffsetDateTime;
+use ui::{prelude::*, Tooltip};
+use util::ResultExt;
+
+use crate::{
+    display_map::{BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    scroll::Autoscroll,
+    AddReviewComment, Anchor, CancelReviewComment, ConfirmReviewComment, Editor, EditorMode,
+};
+
+/// The review threads shown in an editor, and the editors for commenting on them.
+#[derive(Default)]
+pub(crate) struct ReviewThreadBlocks {
+    blocks: HashSet<BlockId>,
+    reply_editors: HashMap<u64, View<Editor>>,
+    composer: Option<ReviewCommentComposer>,
+}
+
+/// An editor for starting a review thread on a range of a buffer.
+struct ReviewCommentComposer {
+    buffer: Model<Buffer>,
+    range: Range<text::Anchor>,
+    editor: View<Editor>,
+    block: BlockId,
+}
+
+struct RenderedComment {
+    author: SharedString,
+    timestamp: SharedString,
+    body: SharedString,
+    mentions: Vec<Range<usize>>,
+}
+
+impl Editor {
+    /// Starts a review thread on the newest selection, in a shared project.
+    pub fn add_review_comment(&mut self, _: &AddReviewComment, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.as_ref() else {
+            return;
+        };
+        if self.mode != EditorMode::Full || project.read(cx).remote_id().is_none() {
+            return;
+        }
+
+        let selection = self.selections.newest_anchor().clone();
+        let multi_buffer = self.buffer.read(cx);
+        let Some((buffer, start)) = multi_buffer.text_anchor_for_position(selection.start, cx)
+        else {
+            return;
+        };
+        let Some((end_buffer, end)) = multi_buffer.text_anchor_for_position(selection.end, cx)
+        else {
+            return;
+        };
+        if end_buffer != buffer {
+            return;
+        }
+
+        self.dismiss_review_comment_composer(cx);
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Add a review comment…", cx);
+            editor
+        });
+        let block = self.insert_blocks(
+            [BlockProperties {
+                position: selection.end,
+                height: 3,
+                style: BlockStyle::Sticky,
+                render: Box::new({
+                    let editor = editor.clone();
+                    let parent = cx.view().downgrade();
+                    move |cx: &mut BlockContext| render_composer(&editor, parent.clone(), cx)
+                }),
+                disposition: BlockDisposition::Below,
+            }],
+            Some(Autoscroll::fit()),
+            cx,
+        )[0];
+        cx.focus_view(&editor);
+        self.review_threads.composer = Some(ReviewCommentComposer {
+            buffer,
+            range: start..end,
+            editor,
+            block,
+        });
+    }
+
+    /// Sends the comment in the focused review comment editor, returning
+    /// whether one was focused.
+    pub(crate) fn confirm_review_comment(
+        &mut self,
+        _: &ConfirmReviewComment,
+        cx: &mut ViewContext<Self>,
+    ) -> bool {
+        let Some(project) = self.project.clone() else {
+            return false;
+        };
+
+        if self
+            .review_threads
+            .composer
+            .as_ref()
+            .map_or(false, |composer| {
+                composer.editor.focus_handle(cx).is_focused(cx)
+            })
+        {
+            self.submit_review_comment_composer(cx);
+            return true;
+        }
+
+        let Some((thread_id, reply_editor)) = self.focused_reply_editor(cx) else {
+            return false;
+        };
+        let body = reply_editor.read(cx).text(cx);
+        if !body.trim().is_empty() {
+            reply_editor.update(cx, |editor, cx| editor.clear(cx));
+            project
+                .update(cx, |project, cx| {
+                    project.reply_to_review_thread(thread_id, body, cx)
+                })
+                .detach_and_log_err(cx);
+        }
+        true
+    }
+
+    /// Discards the focused review comment editor's comment, returning whether
+    /// one was focused.
+    pub(crate) fn cancel_review_comment(
+        &mut self,
+        _: &CancelReviewComment,
+        cx: &mut ViewContext<Self>,
+    ) -> bool {
+        if self
+            .review_threads
+            .composer
+            .as_ref()
+            .map_or(false, |composer| {
+                composer.editor.focus_handle(cx).is_focused(cx)
+            })
+        {
+            self.dismiss_review_comment_composer(cx);
+        } else if let Some((_, reply_editor)) = self.focused_reply_editor(cx) {
+            reply_editor.update(cx, |editor, cx| editor.clear(cx));
+        } else {
+            return false;
+        }
+        cx.focus(&self.focus_handle);
+        true
+    }
+
+    fn submit_review_comment_composer(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some(composer) = self.review_threads.composer.as_ref() else {
+            return;
+        };
+        let body = composer.editor.read(cx).text(cx);
+        if body.trim().is_empty() {
+            return;
+        }
+        let buffer = composer.buffer.clone();
+        let range = composer.range.clone();
+        project
+            .update(cx, |project, cx| {
+                project.create_review_thread(&buffer, range, body, cx)
+            })
+            .detach_and_log_err(cx);
+        self.dismiss_review_comment_composer(cx);
+        cx.focus(&self.focus_handle);
+    }
+
+    fn focused_reply_editor(&self, cx: &ViewContext<Self>) -> Option<(u64, View<Editor>)> {
+        self.review_threads
+            .reply_editors
+            .iter()
+            .find(|(_, editor)| editor.focus_handle(cx).is_focused(cx))
+            .map(|(thread_id, editor)| (*thread_id, editor.clone()))
+    }
+
+    fn dismiss_review_comment_composer(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(composer) = self.review_threads.composer.take() {
+            self.remove_blocks(HashSet::from_iter([composer.block]), None, cx);
+        }
+    }
+
+    /// The range of a review thread in this editor, if its buffer is shown.
+    fn review_thread_range(
+        &self,
+        thread: &ReviewThread,
+        snapshot: &MultiBufferSnapshot,
+        cx: &AppContext,
+    ) -> Option<Range<Anchor>> {
+        let (buffer, range) = thread.buffer_range()?;
+        let buffer_snapshot = buffer.read(cx);
+        let (excerpt_id, _) = self
+            .buffer
+            .read(cx)
+            .excerpts_for_buffer(&buffer, cx)
+            .into_iter()
+            .find(|(_, excerpt_range)| {
+                excerpt_range
+                    .context
+                    .start
+                    .cmp(&range.start, buffer_snapshot)
+                    .is_le()
+                    && range
+                        .start
+                        .cmp(&excerpt_range.context.end, buffer_snapshot)
+                        .is_le()
+            })?;
+        Some(
+            snapshot.anchor_in_excerpt(excerpt_id, range.start)?
+                ..snapshot.anchor_in_excerpt(excerpt_id, range.end)?,
+        )
+    }
+
+    /// The rows of the open review threads in this editor, for the gutter.
+    pub(crate) fn review_thread_rows(
+        &self,
+        snapshot: &MultiBufferSnapshot,
+        cx: &AppContext,
+    ) -> Vec<u32> {
+        let Some(project) = self.project.as_ref() else {
+            return Vec::new();
+        };
+        project
+            .read(cx)
+            .review_threads()
+            .iter()
+            .filter(|thread| !thread.resolved)
+            .filter_map(|thread| {
+                let range = self.review_thread_range(thread, snapshot, cx)?;
+                Some(multi_buffer::ToPoint::to_point(&range.start, snapshot).row)
+            })
+            .sorted_unstable()
+            .dedup()
+            .collect()
+    }
+
+    /// Shows the open review threads of the project below the lines they're on.
+    pub(crate) fn refresh_review_threads(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        if self.mode != EditorMode::Full {
+            return;
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let threads = project
+            .read(cx)
+            .review_threads()
+            .iter()
+            .filter(|thread| !thread.resolved)
+            .filter_map(|thread| {
+                let range = self.review_thread_range(thread, &snapshot, cx)?;
+                Some((thread.clone(), range.end))
+            })
+            .collect::<Vec<_>>();
+
+        let user_store = project.read(cx).user_store();
+        let missing_user_ids = threads
+            .iter()
+            .flat_map(|(thread, _)| &thread.comments)
+            .map(|comment| comment.author_id)
+            .filter(|user_id| user_store.read(cx).get_cached_user(*user_id).is_none())
+            .unique()
+            .collect::<Vec<_>>();
+        if !missing_user_ids.is_empty() {
+            let fetch_users = user_store.update(cx, |user_store, cx| {
+                user_store.get_users(missing_user_ids, cx)
+            });
+            cx.spawn(|this, mut cx| async move {
+                fetch_users.await?;
+                this.update(&mut cx, |this, cx| this.refresh_review_threads(cx))
+            })
+            .detach_and_log_err(cx);
+        }
+
+        let shown_thread_ids = threads
+            .iter()
+            .map(|(thread, _)| thread.id)
+            .collect::<HashSet<_>>();
+        self.review_threads
+            .reply_editors
+            .retain(|thread_id, _| shown_thread_ids.contains(thread_id));
+
+        let now = OffsetDateTime::now_utc();
+        let timezone = cx.local_timezone();
+        let parent = cx.view().downgrade();
+        let blocks = threads
+            .into_iter()
+            .map(|(thread, position)| {
+                let reply_editor = self
+                    .review_threads
+                    .reply_editors
+                    .entry(thread.id)
+                    .or_insert_with(|| {
+                        cx.new_view(|cx| {
+                            let mut editor = Editor::single_line(cx);
+                            editor.set_placeholder_text("Reply…", cx);
+                            editor
+                        })
+                    })
+                    .clone();
+                let comments = thread
+                    .comments
+                    .iter()
+                    .map(|comment| RenderedComment {
+                        author: user_store
+                            .read(cx)
+                            .get_cached_user(comment.author_id)
+                            .map_or("unknown".into(), |user| user.github_login.clone().into()),
+                        timestamp: time_format::format_localized_timestamp(
+                            comment.timestamp,
+                            now,
+                            timezone,
+                            time_format::TimestampFormat::Relative,
+                        )
+                        .into(),
+                        body: comment.body.clone().into(),
+                        mentions: comment
+                            .mentions
+                            .iter()
+                            .map(|(range, _)| range.clone())
+                            .collect(),
+                    })
+                    .collect::<Vec<_>>();
+                let height = comments
+                    .iter()
+                    .map(|comment| comment.body.lines().count().max(1) + 1)
+                    .sum::<usize>()
+                    + 2;
+
+                let project = project.downgrade();
+                let parent = parent.clone();
+                let thread_id = thread.id;
+                BlockProperties {
+                    position,
+                    height: height.min(u8::MAX as usize) as u8,
+                    style: BlockStyle::Sticky,
+                    render: Box::new(move |cx: &mut BlockContext| {
+                        render_review_thread(
+                            thread_id,
+                            &comments,
+                            &reply_editor,
+                            project.clone(),
+                            parent.clone(),
+                            cx,
+                        )
+                    }),
+                    disposition: BlockDisposition::Below,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let old_blocks = mem::take(&mut self.review_threads.blocks);
+        self.remove_blocks(old_blocks, None, cx);
+        self.review_threads.blocks = self.insert_blocks(blocks, None, cx).into_iter().collect();
+    }
+}
+
+fn render_review_thread(
+    thread_id: u64,
+    comments: &[RenderedComment],
+    reply_editor: &View<Editor>,
+    project: WeakModel<Project>,
+    parent: WeakView<Editor>,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    let text_style = cx.text_style();
+    let mention_style = HighlightStyle {
+        font_weight: Some(FontWeight::BOLD),
+        color: Some(cx.theme().colors().text_accent),
+        ..Default::default()
+    };
+
+    div()
+        .id(cx.block_id)
+        .key_context("ReviewComment")
+        .w_full()
+        .pl(cx.gutter_dimensions.width + cx.gutter_dimensions.margin)
+        .pr_4()
+        .child(
+            v_flex()
+                .max_w(rems(48.))
+                .px_2()
+                .border_l_2()
+                .border_color(cx.theme().colors().text_accent)
+                .bg(cx.theme().colors().editor_subheader_background)
+                .children(comments.iter().map(|comment| {
+                    v_flex()
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(div().font_weight(FontWeight::BOLD).child(
+                                    Label::new(comment.author.clone()).size(LabelSize::Small),
+                                ))
+                                .child(
+                                    Label::new(comment.timestamp.clone())
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                ),
+                        )
+                        .child(
+                            StyledText::new(comment.body.clone()).with_highlights(
+                                &text_style,
+                                comment
+                                    .mentions
+                                    .iter()
+                                    .map(|range| (range.clone(), mention_style)),
+                            ),
+                        )
+                }))
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(div().flex_1().child(reply_editor.clone()))
+                        .child(
+                            IconButton::new(("resolve-review-thread", thread_id), IconName::Check)
+                                .icon_size(IconSize::Small)
+                                .tooltip(|cx| Tooltip::text("Resolve Thread", cx))
+                                .on_click(move |_, cx| {
+                                    if let Some(task) = project
+                                        .update(cx, |project, cx| {
+                                            project.set_review_thread_resolved(thread_id, true, cx)
+                                        })
+                                        .log_err()
+                                    {
+                                        task.detach_and_log_err(cx);
+                                    }
+                                    parent
+                                        .update(cx, |editor, cx| cx.focus(&editor.focus_handle))
+                                        .ok();
+                                }),
+                        ),
+                ),
+        )
+        .into_any_element()
+}
+
+fn render_composer(
+    editor: &View<Editor>,
+    parent: WeakView<Editor>,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    div()
+        .id(cx.block_id)
+        .key_context("ReviewComment")
+        .w_full()
+        .pl(cx.gutter_dimensions.width + cx.gutter_dimensions.margin)
+        .pr_4()
+        .child(
+            v_flex()
+                .max_w(rems(48.))
+                .px_2()
+                .border_l_2()
+                .border_color(cx.theme().colors().text_accent)
+                .bg(cx.theme().colors().editor_subheader_background)
+                .child(div().py_1().child(editor.clone()))
+                .child(
+                    h_flex()
+                        .justify_end()
+                        .gap_1()
+                        .child(Button::new("cancel-review-comment", "Cancel").on_click({
+                            let parent = parent.clone();
+                            move |_, cx| {
+                                parent
+                                    .update(cx, |editor, cx| {
+                                        editor.dismiss_review_comment_composer(cx);
+                                        cx.focus(&editor.focus_handle);
+                                    })
+                                    .ok();
+                            }
+                        }))
+                        .child(
+                            Button::new("confirm-review-comment", "Comment")
+                                .style(ButtonStyle::Filled)
+                                .on_click(move |_, cx| {
+                                    parent
+                                        .update(cx, |editor, cx| {
+                                            editor.submit_review_comment_composer(cx)
+                                        })
+                                        .ok();
+                                }),
+                        ),
+                ),
+        )
+        .into_any_element()
+}
@@ -149,6 +149,27 @@ impl EditorLspTestContext {
                 ("<" @open ">" @close)
                 ("\"" @open "\"" @close)
                 (closure_parameters "|" @open "|" @close)"#})),
+            text_objects: Some(Cow::from(indoc! {r#"
+                (function_item) @function.around
+                (function_item
+                    body: (_
+                        "{"
+                        .
+                        (_) @function.inside
+                        (_)? @function.inside
+                        .
+                        "}"))
+                (impl_item) @class.around
+                (impl_item
+                    body: (_
+                        "{"
+                        .
+                        (_) @class.inside
+                        (_)? @class.inside
+                        .
+                        "}"))
+                (line_comment) @comment.inside
+                (line_comment)+ @comment.around"#})),
             ..Default::default()
         })
         .expect("Could not parse queries");
@@ -55,6 +55,10 @@ pub trait GitRepository: Send {
     /// no need to consider the working directory file if the mtime matches.
     fn status(&self, path: &RepoPath, mtime: SystemTime) -> Option<GitFileStatus>;
 
+    /// Returns the paths changed on the current branch since it diverged from
+    /// its upstream or the default branch, including uncommitted changes.
+    fn branch_changed_paths(&self) -> Result<Vec<RepoPath>>;
+
     fn branches(&self) -> Result<Vec<Branch>>;
     fn change_branch(&self, _: &str) -> Result<()>;
     fn create_branch(&self, _: &str) -> Result<()>;
@@ -190,6 +194,54 @@ impl GitRepository for RealGitRepository {
         status
     }
 
+    fn branch_changed_paths(&self) -> Result<Vec<RepoPath>> {
+        let head = self.repository.head()?;
+        let head_commit = head.peel_to_commit()?;
+        let upstream = head
+            .shorthand()
+            .and_then(|name| self.repository.find_branch(name, BranchType::Local).ok())
+            .and_then(|branch| branch.upstream().ok())
+            .and_then(|upstream| upstream.get().peel_to_commit().ok());
+        let base_commit = upstream.or_else(|| {
+            [
+                "refs/remotes/origin/HEAD",
+                "refs/heads/main",
+                "refs/heads/master",
+            ]
+            .into_iter()
+            .find_map(|name| {
+                self.repository
+                    .find_reference(name)
+                    .ok()?
+                    .peel_to_commit()
+                    .ok()
+            })
+        });
+        let base_tree = match base_commit {
+            Some(base_commit) => {
+                let merge_base = self
+                    .repository
+                    .merge_base(head_commit.id(), base_commit.id())?;
+                self.repository.find_commit(merge_base)?.tree()?
+            }
+            None => head_commit.tree()?,
+        };
+
+        let diff = self
+            .repository
+            .diff_tree_to_workdir_with_index(Some(&base_tree), None)?;
+        Ok(diff
+            .deltas()
+            .filter_map(|delta| {
+                let path = delta
+                    .new_file()
+                    .path()
+                    .or_else(|| delta.old_file().path())?;
+                Some(RepoPath::new(path.to_path_buf()))
+            })
+            .collect())
+    }
+
     fn branches(&self) -> Result<Vec<Branch>> {
         let local_branches = self.repository.branches(Some(BranchType::Local))?;
         let valid_branches = local_branches
@@ -293,6 +345,7 @@ pub struct FakeGitRepositoryState {
     pub blames: HashMap<PathBuf, Blame>,
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
     pub branch_name: Option<String>,
+    pub head_sha: Option<String>,
 }
 
 impl FakeGitRepository {
@@ -319,7 +372,8 @@ impl GitRepository for FakeGitRepository {
     }
 
     fn head_sha(&self) -> Option<String> {
-        None
+        let state = self.state.lock();
+        state.head_sha.clone()
     }
 
     fn staged_statuses(&self, path_prefix: &Path) -> TreeMap<RepoPath, GitFileStatus> {
@@ -342,6 +396,11 @@ impl GitRepository for FakeGitRepository {
         state.worktree_statuses.get(path).cloned()
     }
 
+    fn branch_changed_paths(&self) -> Result<Vec<RepoPath>> {
+        let state = self.state.lock();
+        Ok(state.worktree_statuses.keys().cloned().collect())
+    }
+
     fn branches(&self) -> Result<Vec<Branch>> {
         Ok(vec![])
     }
@@ -0,0 +1,672 @@
+mod hex_item;
+
+pub use hex_item::HexItem;
+use hex_item::{find_bytes, format_hex, parse_byte_pattern, parse_offset};
+
+use anyhow::{anyhow, Result};
+use editor::Editor;
+use gpui::{
+    actions, div, uniform_list, AnyElement, AppContext, ClipboardItem, EntityId, EventEmitter,
+    FocusHandle, FocusableView, Hsla, InteractiveElement, IntoElement, KeyDownEvent, Model,
+    MouseButton, MouseDownEvent, ParentElement, Render, SharedString, Styled, Subscription, Task,
+    UniformListScrollHandle, View, ViewContext, VisualContext, WindowContext,
+};
+use project::{Project, ProjectPath};
+use settings::Settings;
+use std::{ops::Range, sync::Arc};
+use theme::ThemeSettings;
+use ui::prelude::*;
+use workspace::{
+    item::{Item, ItemEvent, ProjectItem, TabContentParams},
+    Workspace, WorkspaceId,
+};
+
+actions!(
+    hex_editor,
+    [
+        OpenHexEditor,
+        MoveLeft,
+        MoveRight,
+        MoveUp,
+        MoveDown,
+        SelectLeft,
+        SelectRight,
+        SelectUp,
+        SelectDown,
+        PageUp,
+        PageDown,
+        MoveToStart,
+        MoveToEnd,
+        SelectAll,
+        ToggleColumn,
+        GoToOffset,
+        FindBytes,
+        FindNext,
+        FindPrevious,
+        CopyAsHex,
+        CopyBytes,
+    ]
+);
+
+const BYTES_PER_ROW: usize = 16;
+const PAGE_ROWS: usize = 32;
+
+pub fn init(cx: &mut AppContext) {
+    workspace::register_project_item_fallback::<HexEditor>(cx);
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(HexEditor::open_active_item);
+    })
+    .detach();
+}
+
+/// The column that typed characters are written to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Column {
+    Hex,
+    Ascii,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum QueryKind {
+    GoToOffset,
+    FindBytes,
+}
+
+/// Shows a file as a hex dump, with the offset, hex and ASCII columns of every 16 bytes,
+/// and edits it by overwriting the bytes under the cursor.
+pub struct HexEditor {
+    item: Model<HexItem>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    cursor: usize,
+    /// The other end of the selection, which includes the bytes at both of its ends.
+    selection_tail: usize,
+    column: Column,
+    /// Whether the high half of the byte under the cursor was typed, so the low half is next.
+    pending_low_nibble: bool,
+    query_editor: View<Editor>,
+    query: Option<QueryKind>,
+    query_error: Option<SharedString>,
+    last_pattern: Option<Arc<[u8]>>,
+    pending_search: Option<Task<()>>,
+    _observe_item: Subscription,
+}
+
+impl HexEditor {
+    pub fn new(item: Model<HexItem>, cx: &mut ViewContext<Self>) -> Self {
+        let query_editor = cx.new_view(Editor::single_line);
+        let observe_item = cx.observe(&item, |this, item, cx| {
+            let last_offset = item.read(cx).len().saturating_sub(1);
+            this.cursor = this.cursor.min(last_offset);
+            this.selection_tail = this.selection_tail.min(last_offset);
+            cx.emit(ItemEvent::UpdateTab);
+            cx.notify();
+        });
+
+        Self {
+            item,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            cursor: 0,
+            selection_tail: 0,
+            column: Column::Hex,
+            pending_low_nibble: false,
+            query_editor,
+            query: None,
+            query_error: None,
+            last_pattern: None,
+            pending_search: None,
+            _observe_item: observe_item,
+        }
+    }
+
+    /// Reopens the file of the active item as a hex dump.
+    fn open_active_item(
+        workspace: &mut Workspace,
+        _: &OpenHexEditor,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(project_path) = workspace
+            .active_item(cx)
+            .and_then(|item| item.project_path(cx))
+        else {
+            return;
+        };
+        let project = workspace.project().clone();
+        if !project.read(cx).is_local() {
+            return;
+        }
+
+        let open_item = HexItem::open(&project, project_path, cx);
+        let pane = workspace.active_pane().clone();
+        cx.spawn(|workspace, mut cx| async move {
+            let item = open_item.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                workspace.open_project_item::<HexEditor>(pane, item, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn selection(&self) -> Range<usize> {
+        self.cursor.min(self.selection_tail)..self.cursor.max(self.selection_tail) + 1
+    }
+
+    fn selected_bytes<'a>(&self, cx: &'a AppContext) -> &'a [u8] {
+        let bytes = self.item.read(cx).bytes();
+        let selection = self.selection();
+        &bytes[selection.start.min(bytes.len())..selection.end.min(bytes.len())]
+    }
+
+    fn move_cursor(&mut self, offset: usize, select: bool, cx: &mut ViewContext<Self>) {
+        self.cursor = offset.min(self.item.read(cx).len().saturating_sub(1));
+        if !select {
+            self.selection_tail = self.cursor;
+        }
+        self.pending_low_nibble = false;
+        self.scroll_handle
+            .scroll_to_item(self.cursor / BYTES_PER_ROW);
+        cx.notify();
+    }
+
+    fn move_up(&mut self, rows: usize, select: bool, cx: &mut ViewContext<Self>) {
+        let offset = self.cursor.saturating_sub(rows * BYTES_PER_ROW);
+        self.move_cursor(offset, select, cx);
+    }
+
+    fn move_down(&mut self, rows: usize, select: bool, cx: &mut ViewContext<Self>) {
+        let offset = self.cursor.saturating_add(rows * BYTES_PER_ROW);
+        self.move_cursor(offset, select, cx);
+    }
+
+    fn select_all(&mut self, _: &SelectAll, cx: &mut ViewContext<Self>) {
+        self.selection_tail = 0;
+        self.move_cursor(usize::MAX, true, cx);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        self.move_cursor(self.cursor, false, cx);
+    }
+
+    fn toggle_column(&mut self, _: &ToggleColumn, cx: &mut ViewContext<Self>) {
+        self.column = match self.column {
+            Column::Hex => Column::Ascii,
+            Column::Ascii => Column::Hex,
+        };
+        self.pending_low_nibble = false;
+        cx.notify();
+    }
+
+    fn copy_as_hex(&mut self, _: &CopyAsHex, cx: &mut ViewContext<Self>) {
+        let hex = format_hex(self.selected_bytes(cx));
+        cx.write_to_clipboard(ClipboardItem::new(hex));
+    }
+
+    fn copy_bytes(&mut self, _: &CopyBytes, cx: &mut ViewContext<Self>) {
+        // The clipboard holds text, so bytes that aren't valid UTF-8 are copied as hex
+        // rather than losing the ones that can't be represented.
+        let bytes = self.selected_bytes(cx);
+        let text = match std::str::from_utf8(bytes) {
+            Ok(text) => text.to_string(),
+            Err(_) => format_hex(bytes),
+        };
+        cx.write_to_clipboard(ClipboardItem::new(text));
+    }
+
+    fn key_down(&mut self, event: &KeyDownEvent, cx: &mut ViewContext<Self>) {
+        let keystroke = &event.keystroke;
+        let modifiers = keystroke.modifiers;
+        if modifiers.control || modifiers.alt || modifiers.platform || modifiers.function {
+            return;
+        }
+        let input = keystroke.ime_key.as_deref().unwrap_or(&keystroke.key);
+        let mut chars = input.chars();
+        let (Some(input), None) = (chars.next(), chars.next()) else {
+            return;
+        };
+        let offset = self.cursor;
+        let Some(&byte) = self.item.read(cx).bytes().get(offset) else {
+            return;
+        };
+
+        match self.column {
+            Column::Hex => {
+                let Some(digit) = input.to_digit(16).map(|digit| digit as u8) else {
+                    return;
+                };
+                if self.pending_low_nibble {
+                    self.item.update(cx, |item, cx| {
+                        item.overwrite(offset, &[(byte & 0xf0) | digit], cx)
+                    });
+                    self.move_cursor(offset + 1, false, cx);
+                } else {
+                    self.item.update(cx, |item, cx| {
+                        item.overwrite(offset, &[(digit << 4) | (byte & 0x0f)], cx)
+                    });
+                    self.selection_tail = offset;
+                    self.pending_low_nibble = true;
+                }
+            }
+            Column::Ascii => {
+                if !input.is_ascii_graphic() && input != ' ' {
+                    return;
+                }
+                self.item
+                    .update(cx, |item, cx| item.overwrite(offset, &[input as u8], cx));
+                self.move_cursor(offset + 1, false, cx);
+            }
+        }
+        cx.stop_propagation();
+        cx.notify();
+    }
+
+    fn deploy_query(&mut self, kind: QueryKind, cx: &mut ViewContext<Self>) {
+        self.query = Some(kind);
+        self.query_error = None;
+        let placeholder = match kind {
+            QueryKind::GoToOffset => "Offset, such as 1024 or 0x400",
+            QueryKind::FindBytes => "Bytes, such as de ad be ef, or text in quotes",
+        };
+        self.query_editor.update(cx, |editor, cx| {
+            editor.set_placeholder_text(placeholder, cx);
+            editor.select_all(&editor::actions::SelectAll, cx);
+        });
+        cx.focus_view(&self.query_editor);
+        cx.notify();
+    }
+
+    fn dismiss_query(&mut self, cx: &mut ViewContext<Self>) {
+        self.query = None;
+        self.query_error = None;
+        cx.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    fn confirm_query(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let Some(kind) = self.query else {
+            return;
+        };
+        let query = self.query_editor.read(cx).text(cx);
+        let result = match kind {
+            QueryKind::GoToOffset => self
+                .go_to_offset(&query, cx)
+                .map(|()| self.dismiss_query(cx)),
+            // The query is dismissed once a match is found.
+            QueryKind::FindBytes => parse_byte_pattern(&query).map(|pattern| {
+                self.last_pattern = Some(pattern.into());
+                self.select_match(self.selection().start, false, true, cx)
+            }),
+        };
+        if let Err(error) = result {
+            self.query_error = Some(error.to_string().into());
+            cx.notify();
+        }
+    }
+
+    fn cancel_query(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        self.dismiss_query(cx);
+    }
+
+    fn go_to_offset(&mut self, query: &str, cx: &mut ViewContext<Self>) -> Result<()> {
+        let offset = parse_offset(query)
+            .ok_or_else(|| anyhow!("offsets are decimal, or hexadecimal when prefixed with 0x"))?;
+        if offset >= self.item.read(cx).len() {
+            return Err(anyhow!("the offset is past the end of the file"));
+        }
+        self.move_cursor(offset, false, cx);
+        Ok(())
+    }
+
+    /// Selects the first match of the last searched pattern starting at or after `offset`,
+    /// or the last one starting before it if `backwards`. The file is searched on a
+    /// background thread, replacing any search still running.
+    fn select_match(
+        &mut self,
+        offset: usize,
+        backwards: bool,
+        dismiss_query: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(pattern) = self.last_pattern.clone() else {
+            return;
+        };
+        let bytes = self.item.read(cx).snapshot();
+        let search = cx.background_executor().spawn(async move {
+            find_bytes(&bytes, &pattern, offset, backwards)
+                .map(|start| start..start + pattern.len())
+        });
+        self.pending_search = Some(cx.spawn(|this, mut cx| async move {
+            let range = search.await;
+            this.update(&mut cx, |this, cx| {
+                this.pending_search = None;
+                match range {
+                    Some(range) => {
+                        this.selection_tail = range.start;
+                        this.move_cursor(range.end - 1, true, cx);
+                        if dismiss_query {
+                            this.dismiss_query(cx);
+                        }
+                    }
+                    None if dismiss_query => {
+                        this.query_error = Some("no matches".into());
+                        cx.notify();
+                    }
+                    None => {}
+                }
+            })
+            .ok();
+        }));
+    }
+
+    fn find_next(&mut self, _: &FindNext, cx: &mut ViewContext<Self>) {
+        if self.last_pattern.is_none() {
+            self.deploy_query(QueryKind::FindBytes, cx);
+        } else {
+            self.select_match(self.selection().start + 1, false, false, cx);
+        }
+    }
+
+    fn find_previous(&mut self, _: &FindPrevious, cx: &mut ViewContext<Self>) {
+        if self.last_pattern.is_none() {
+            self.deploy_query(QueryKind::FindBytes, cx);
+        } else {
+            self.select_match(self.selection().start, true, false, cx);
+        }
+    }
+
+    fn render_rows(&mut self, rows: Range<usize>, cx: &mut ViewContext<Self>) -> Vec<AnyElement> {
+        let colors = cx.theme().colors();
+        let offset_color = colors.editor_line_number;
+        let selection_color = colors.element_selected;
+        let cursor_color = colors.text_accent;
+        let dimmed_color = colors.text_muted;
+        let selection = self.selection();
+
+        let bytes = self.item.read(cx).bytes();
+        rows.map(|row| {
+            let row_start = row * BYTES_PER_ROW;
+            let row_bytes = &bytes[row_start..(row_start + BYTES_PER_ROW).min(bytes.len())];
+            let cell = |offset: usize, column: Column, text: String, color: Option<Hsla>| {
+                let is_cursor = offset == self.cursor;
+                div()
+                    .px_0p5()
+                    .when(selection.contains(&offset), |cell| cell.bg(selection_color))
+                    .when(is_cursor && column == self.column, |cell| {
+                        cell.border_b_2().border_color(cursor_color)
+                    })
+                    .when(is_cursor && column != self.column, |cell| {
+                        cell.border_b_1().border_color(dimmed_color)
+                    })
+                    .when_some(color, |cell, color| cell.text_color(color))
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, event: &MouseDownEvent, cx| {
+                            this.column = column;
+                            this.move_cursor(offset, event.modifiers.shift, cx);
+                            cx.focus(&this.focus_handle);
+                        }),
+                    )
+                    .child(text)
+            };
+
+            h_flex()
+                .gap_4()
+                .child(
+                    div()
+                        .text_color(offset_color)
+                        .child(format!("{row_start:08x}")),
+                )
+                .child(h_flex().w(rems(BYTES_PER_ROW as f32 * 1.5)).children(
+                    row_bytes.iter().enumerate().map(|(ix, byte)| {
+                        let color = (*byte == 0).then_some(dimmed_color);
+                        cell(row_start + ix, Column::Hex, format!("{byte:02x}"), color)
+                    }),
+                ))
+                .child(
+                    h_flex().children(row_bytes.iter().enumerate().map(|(ix, byte)| {
+                        let (text, color) = if byte.is_ascii_graphic() || *byte == b' ' {
+                            ((*byte as char).to_string(), None)
+                        } else {
+                            (".".to_string(), Some(dimmed_color))
+                        };
+                        cell(row_start + ix, Column::Ascii, text, color)
+                    })),
+                )
+                .into_any_element()
+        })
+        .collect()
+    }
+
+    fn render_query_bar(&self, kind: QueryKind, cx: &ViewContext<Self>) -> impl IntoElement {
+        let label = match kind {
+            QueryKind::GoToOffset => "Go to offset",
+            QueryKind::FindBytes => "Find bytes",
+        };
+        h_flex()
+            .key_context("HexEditorQuery")
+            .on_action(cx.listener(Self::confirm_query))
+            .on_action(cx.listener(Self::cancel_query))
+            .gap_2()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(label).color(Color::Muted))
+            .child(div().flex_1().child(self.query_editor.clone()))
+            .when_some(self.query_error.clone(), |bar, error| {
+                bar.child(Label::new(error).color(Color::Error))
+            })
+    }
+
+    fn render_status(&self, cx: &ViewContext<Self>) -> impl IntoElement {
+        let len = self.item.read(cx).len();
+        let selection = self.selection();
+        let status = if len == 0 {
+            "Empty file".to_string()
+        } else if selection.len() > 1 {
+            format!(
+                "Selected {} bytes at {:#x}, of {len} bytes",
+                selection.len(),
+                selection.start
+            )
+        } else {
+            format!(
+                "Offset {:#x} ({}), of {len} bytes",
+                self.cursor, self.cursor
+            )
+        };
+        h_flex()
+            .px_2()
+            .py_1()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Label::new(status)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+    }
+}
+
+impl EventEmitter<ItemEvent> for HexEditor {}
+
+impl FocusableView for HexEditor {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for HexEditor {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        let path = self.item.read(cx).abs_path();
+        let title = path
+            .file_name()
+            .unwrap_or_else(|| path.as_os_str())
+            .to_string_lossy()
+            .to_string();
+        Label::new(title)
+            .single_line()
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .italic(params.preview)
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
+        Some(
+            self.item
+                .read(cx)
+                .abs_path()
+                .to_string_lossy()
+                .to_string()
+                .into(),
+        )
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(EntityId, &dyn project::Item),
+    ) {
+        f(self.item.entity_id(), self.item.read(cx))
+    }
+
+    fn is_singleton(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn clone_on_split(&self, _: WorkspaceId, cx: &mut ViewContext<Self>) -> Option<View<Self>> {
+        Some(cx.new_view(|cx| Self::new(self.item.clone(), cx)))
+    }
+
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.item.read(cx).is_dirty()
+    }
+
+    fn can_save(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _format: bool,
+        _project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        self.item.update(cx, |item, cx| item.save(cx))
+    }
+
+    fn save_as(
+        &mut self,
+        project: Model<Project>,
+        path: ProjectPath,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let project = project.read(cx);
+        let Some(abs_path) = project.absolute_path(&path, cx) else {
+            return Task::ready(Err(anyhow!("failed to find the absolute path")));
+        };
+        let entry_id = project.entry_for_path(&path, cx).map(|entry| entry.id);
+        self.item
+            .update(cx, |item, cx| item.save_as(path, abs_path, entry_id, cx))
+    }
+
+    fn reload(&mut self, _project: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        self.item.update(cx, |item, cx| item.reload(cx))
+    }
+}
+
+impl ProjectItem for HexEditor {
+    type Item = HexItem;
+
+    fn for_project_item(
+        _project: Model<Project>,
+        item: Model<Self::Item>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, cx)
+    }
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let row_count = self.item.read(cx).len().div_ceil(BYTES_PER_ROW);
+        let buffer_font = ThemeSettings::get_global(cx).buffer_font.family.clone();
+
+        v_flex()
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .when_some(self.query, |hex_editor, kind| {
+                hex_editor.child(self.render_query_bar(kind, cx))
+            })
+            .child(
+                div()
+                    .key_context("HexEditor")
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(|this, _: &MoveLeft, cx| {
+                        this.move_cursor(this.cursor.saturating_sub(1), false, cx)
+                    }))
+                    .on_action(cx.listener(|this, _: &MoveRight, cx| {
+                        this.move_cursor(this.cursor + 1, false, cx)
+                    }))
+                    .on_action(cx.listener(|this, _: &MoveUp, cx| this.move_up(1, false, cx)))
+                    .on_action(cx.listener(|this, _: &MoveDown, cx| this.move_down(1, false, cx)))
+                    .on_action(cx.listener(|this, _: &SelectLeft, cx| {
+                        this.move_cursor(this.cursor.saturating_sub(1), true, cx)
+                    }))
+                    .on_action(cx.listener(|this, _: &SelectRight, cx| {
+                        this.move_cursor(this.cursor + 1, true, cx)
+                    }))
+                    .on_action(cx.listener(|this, _: &SelectUp, cx| this.move_up(1, true, cx)))
+                    .on_action(cx.listener(|this, _: &SelectDown, cx| this.move_down(1, true, cx)))
+                    .on_action(
+                        cx.listener(|this, _: &PageUp, cx| this.move_up(PAGE_ROWS, false, cx)),
+                    )
+                    .on_action(
+                        cx.listener(|this, _: &PageDown, cx| this.move_down(PAGE_ROWS, false, cx)),
+                    )
+                    .on_action(
+                        cx.listener(|this, _: &MoveToStart, cx| this.move_cursor(0, false, cx)),
+                    )
+                    .on_action(cx.listener(|this, _: &MoveToEnd, cx| {
+                        this.move_cursor(usize::MAX, false, cx)
+                    }))
+                    .on_action(cx.listener(Self::select_all))
+                    .on_action(cx.listener(Self::cancel))
+                    .on_action(cx.listener(Self::toggle_column))
+                    .on_action(cx.listener(|this, _: &GoToOffset, cx| {
+                        this.deploy_query(QueryKind::GoToOffset, cx)
+                    }))
+                    .on_action(cx.listener(|this, _: &FindBytes, cx| {
+                        this.deploy_query(QueryKind::FindBytes, cx)
+                    }))
+                    .on_action(cx.listener(Self::find_next))
+                    .on_action(cx.listener(Self::find_previous))
+                    .on_action(cx.listener(Self::copy_as_hex))
+                    .on_action(cx.listener(Self::copy_bytes))
+                    .on_key_down(cx.listener(Self::key_down))
+                    .flex_1()
+                    .p_2()
+                    .font_family(buffer_font)
+                    .text_buffer(cx)
+                    .child(
+                        uniform_list(cx.view().clone(), "hex-rows", row_count, Self::render_rows)
+                            .size_full()
+                            .track_scroll(self.scroll_handle.clone()),
+                    ),
+            )
+            .child(self.render_status(cx))
+    }
+}
@@ -0,0 +1,308 @@
+use anyhow::{anyhow, Result};
+use fs::Fs;
+use gpui::{AppContext, Context, Model, ModelContext, Task};
+use project::{Project, ProjectEntryId, ProjectPath};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// The largest file the hex editor opens, as it keeps the whole file in memory.
+pub const MAX_FILE_SIZE: u64 = 256 * 1024 * 1024;
+
+/// The raw contents of a file, edited in place: bytes are overwritten, never inserted or removed.
+pub struct HexItem {
+    project_path: ProjectPath,
+    abs_path: PathBuf,
+    entry_id: Option<ProjectEntryId>,
+    fs: Arc<dyn Fs>,
+    bytes: Arc<Vec<u8>>,
+    is_dirty: bool,
+}
+
+impl project::Item for HexItem {
+    fn try_open(
+        project: &Model<Project>,
+        path: &ProjectPath,
+        cx: &mut AppContext,
+    ) -> Option<Task<Result<Model<Self>>>> {
+        // Only the host can read the bytes of a file.
+        if project.read(cx).is_local() {
+            Some(Self::open(project, path.clone(), cx))
+        } else {
+            None
+        }
+    }
+
+    fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+}
+
+impl HexItem {
+    pub fn open(
+        project: &Model<Project>,
+        project_path: ProjectPath,
+        cx: &mut AppContext,
+    ) -> Task<Result<Model<Self>>> {
+        let project = project.read(cx);
+        let abs_path = project.absolute_path(&project_path, cx);
+        let entry_id = project
+            .entry_for_path(&project_path, cx)
+            .map(|entry| entry.id);
+        let fs = project.fs().clone();
+
+        cx.spawn(|mut cx| async move {
+            let abs_path = abs_path.ok_or_else(|| anyhow!("failed to find the absolute path"))?;
+            let bytes = load_bytes(fs.as_ref(), &abs_path).await?;
+            cx.new_model(|_| Self {
+                project_path,
+                abs_path,
+                entry_id,
+                fs,
+                bytes: Arc::new(bytes),
+                is_dirty: false,
+            })
+        })
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// The bytes as they are now, for reading off the main thread. Overwriting bytes while
+    /// a snapshot is alive copies them first.
+    pub fn snapshot(&self) -> Arc<Vec<u8>> {
+        self.bytes.clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn abs_path(&self) -> &Path {
+        &self.abs_path
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.is_dirty
+    }
+
+    /// Overwrites the bytes starting at `offset`, dropping the ones that would go past the end of the file.
+    pub fn overwrite(&mut self, offset: usize, bytes: &[u8], cx: &mut ModelContext<Self>) {
+        let end = (offset + bytes.len()).min(self.bytes.len());
+        if offset >= end || self.bytes[offset..end] == bytes[..end - offset] {
+            return;
+        }
+        Arc::make_mut(&mut self.bytes)[offset..end].copy_from_slice(&bytes[..end - offset]);
+        self.is_dirty = true;
+        cx.notify();
+    }
+
+    pub fn save(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let abs_path = self.abs_path.clone();
+        self.write(abs_path, cx)
+    }
+
+    pub fn save_as(
+        &mut self,
+        project_path: ProjectPath,
+        abs_path: PathBuf,
+        entry_id: Option<ProjectEntryId>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let write = self.write(abs_path.clone(), cx);
+        cx.spawn(|this, mut cx| async move {
+            write.await?;
+            this.update(&mut cx, |this, cx| {
+                this.project_path = project_path;
+                this.abs_path = abs_path;
+                this.entry_id = entry_id;
+                cx.notify();
+            })
+        })
+    }
+
+    /// Reads the file again, discarding the bytes overwritten since the last save.
+    pub fn reload(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        cx.spawn(|this, mut cx| async move {
+            let bytes = load_bytes(fs.as_ref(), &abs_path).await?;
+            this.update(&mut cx, |this, cx| {
+                this.bytes = Arc::new(bytes);
+                this.is_dirty = false;
+                cx.notify();
+            })
+        })
+    }
+
+    fn write(&mut self, abs_path: PathBuf, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let bytes = self.bytes.clone();
+        cx.spawn(|this, mut cx| async move {
+            fs.atomic_write_bytes(abs_path, bytes.to_vec()).await?;
+            this.update(&mut cx, |this, cx| {
+                this.is_dirty = false;
+                cx.notify();
+            })
+        })
+    }
+}
+
+async fn load_bytes(fs: &dyn Fs, abs_path: &Path) -> Result<Vec<u8>> {
+    let metadata = fs
+        .metadata(abs_path)
+        .await?
+        .ok_or_else(|| anyhow!("{abs_path:?} does not exist"))?;
+    if metadata.len > MAX_FILE_SIZE {
+        return Err(anyhow!(
+            "{abs_path:?} is {} bytes, larger than the {MAX_FILE_SIZE} bytes the hex editor opens",
+            metadata.len
+        ));
+    }
+    fs.load_bytes(abs_path).await
+}
+
+/// Parses an offset into the file: hexadecimal when prefixed with `0x`, decimal otherwise.
+pub fn parse_offset(query: &str) -> Option<usize> {
+    let query = query.trim();
+    match query
+        .strip_prefix("0x")
+        .or_else(|| query.strip_prefix("0X"))
+    {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => query.parse().ok(),
+    }
+}
+
+/// Parses the bytes to search for: either hexadecimal digits, optionally separated by
+/// whitespace (`de ad be ef`), or text in double quotes (`"PNG"`), searched for as UTF-8.
+pub fn parse_byte_pattern(query: &str) -> Result<Vec<u8>> {
+    let query = query.trim();
+    if let Some(text) = query
+        .strip_prefix('"')
+        .and_then(|query| query.strip_suffix('"'))
+    {
+        if text.is_empty() {
+            return Err(anyhow!("the text to search for is empty"));
+        }
+        return Ok(text.as_bytes().to_vec());
+    }
+
+    let digits = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            c.to_digit(16)
+                .map(|digit| digit as u8)
+                .ok_or_else(|| anyhow!("{c:?} is not a hexadecimal digit"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if digits.is_empty() {
+        return Err(anyhow!("the bytes to search for are empty"));
+    }
+    if digits.len() % 2 != 0 {
+        return Err(anyhow!("each byte needs two hexadecimal digits"));
+    }
+    Ok(digits
+        .chunks_exact(2)
+        .map(|pair| (pair[0] << 4) | pair[1])
+        .collect())
+}
+
+/// Finds the first occurrence of the pattern starting at or after `offset`, or the last one
+/// starting before it if `backwards`, wrapping around the ends of the file.
+pub fn find_bytes(bytes: &[u8], pattern: &[u8], offset: usize, backwards: bool) -> Option<usize> {
+    if pattern.is_empty() || pattern.len() > bytes.len() {
+        return None;
+    }
+    let mut starts = bytes
+        .windows(pattern.len())
+        .enumerate()
+        .filter(|(_, window)| *window == pattern)
+        .map(|(start, _)| start);
+    if backwards {
+        let (mut before, mut last) = (None, None);
+        for start in starts {
+            if start < offset {
+                before = Some(start);
+            }
+            last = Some(start);
+        }
+        before.or(last)
+    } else {
+        let first = starts.next()?;
+        if first >= offset {
+            return Some(first);
+        }
+        starts.find(|start| *start >= offset).or(Some(first))
+    }
+}
+
+/// Formats the bytes as space-separated pairs of hexadecimal digits.
+pub fn format_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 3);
+    for (ix, byte) in bytes.iter().enumerate() {
+        if ix > 0 {
+            hex.push(' ');
+        }
+        hex.push_str(&format!("{byte:02x}"));
+    }
+    hex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("1024"), Some(1024));
+        assert_eq!(parse_offset(" 0x400 "), Some(1024));
+        assert_eq!(parse_offset("0XfF"), Some(255));
+        assert_eq!(parse_offset("ff"), None);
+        assert_eq!(parse_offset(""), None);
+    }
+
+    #[test]
+    fn test_parse_byte_pattern() {
+        assert_eq!(
+            parse_byte_pattern("de ad BE EF").unwrap(),
+            vec![0xde, 0xad, 0xbe, 0xef]
+        );
+        assert_eq!(parse_byte_pattern("0a0D").unwrap(), vec![0x0a, 0x0d]);
+        assert_eq!(parse_byte_pattern("\"PNG\"").unwrap(), b"PNG".to_vec());
+        assert!(parse_byte_pattern("abc").is_err());
+        assert!(parse_byte_pattern("zz").is_err());
+        assert!(parse_byte_pattern("").is_err());
+        assert!(parse_byte_pattern("\"\"").is_err());
+    }
+
+    #[test]
+    fn test_find_bytes() {
+        let bytes = b"ab_ab_ab";
+        assert_eq!(find_bytes(bytes, b"ab", 0, false), Some(0));
+        assert_eq!(find_bytes(bytes, b"ab", 1, false), Some(3));
+        assert_eq!(find_bytes(bytes, b"ab", 7, false), Some(0));
+        assert_eq!(find_bytes(bytes, b"ab", 6, true), Some(3));
+        assert_eq!(find_bytes(bytes, b"ab", 0, true), Some(6));
+        assert_eq!(find_bytes(bytes, b"_a", 6, false), Some(2));
+        assert_eq!(find_bytes(bytes, b"xyz", 0, false), None);
+        assert_eq!(find_bytes(b"a", b"ab", 0, false), None);
+    }
+
+    #[test]
+    fn test_format_hex() {
+        assert_eq!(format_hex(&[0xde, 0xad, 0x00, 0x0f]), "de ad 00 0f");
+        assert_eq!(format_hex(&[]), "");
+    }
+}
@@ -2,7 +2,7 @@ pub use crate::{
     diagnostic_set::DiagnosticSet,
     highlight_map::{HighlightId, HighlightMap},
     markdown::ParsedMarkdown,
-    proto, Grammar, Language, LanguageRegistry,
+    proto, Grammar, Language, LanguageRegistry, TextObject,
 };
 use crate::{
     diagnostic_set::{DiagnosticEntry, DiagnosticGroup},
@@ -22,6 +22,7 @@ use gpui::{AppContext, EventEmitter, HighlightStyle, ModelContext, Task, TaskLab
 use lazy_static::lazy_static;
 use lsp::LanguageServerId;
 use parking_lot::Mutex;
+use postage::{prelude::Stream, watch};
 use similar::{ChangeTag, TextDiff};
 use smallvec::SmallVec;
 use smol::future::yield_now;
@@ -74,6 +75,15 @@ pub enum Capability {
     ReadOnly,
 }
 
+/// Whether a [Buffer]'s syntax tree is being computed in the background.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseStatus {
+    /// The syntax tree is up to date with the last parse.
+    Idle,
+    /// The buffer is being parsed in the background.
+    Parsing,
+}
+
 /// An in-memory representation of a source code file, including its text,
 /// syntax trees, git status, and diagnostics.
 pub struct Buffer {
@@ -87,6 +97,12 @@ pub struct Buffer {
     /// The version vector when this buffer was last loaded from
     /// or saved to disk.
     saved_version: clock::Global,
+    /// The encoding of the file on disk, which the buffer's text is
+    /// decoded from when loaded and encoded into when saved.
+    encoding: Encoding,
+    /// Whether the buffer's file is too large to be parsed and diffed,
+    /// see [`Buffer::with_large_file_mode`].
+    large_file_mode: bool,
     transaction_depth: usize,
     was_dirty_before_starting_transaction: Option<bool>,
     reload_task: Option<Task<Result<()>>>,
@@ -96,6 +112,7 @@ pub struct Buffer {
     sync_parse_timeout: Duration,
     syntax_map: Mutex<SyntaxMap>,
     parsing_in_background: bool,
+    parse_status: (watch::Sender<ParseStatus>, watch::Receiver<ParseStatus>),
     parse_count: usize,
     diagnostics: SmallVec<[(LanguageServerId, DiagnosticSet); 2]>,
     remote_selections: TreeMap<ReplicaId, SelectionSet>,
@@ -372,8 +389,8 @@ pub trait LocalFile: File {
     /// Returns the absolute path of this file.
     fn abs_path(&self, cx: &AppContext) -> PathBuf;
 
-    /// Loads the file's contents from disk.
-    fn load(&self, cx: &AppContext) -> Task<Result<String>>;
+    /// Loads the file's contents from disk, decoding them with the given [`Encoding`].
+    fn load(&self, encoding: Encoding, cx: &AppContext) -> Task<Result<String>>;
 
     /// Called when the buffer is reloaded from disk.
     fn buffer_reloaded(
@@ -381,6 +398,7 @@ pub trait LocalFile: File {
         buffer_id: BufferId,
         version: &clock::Global,
         line_ending: LineEnding,
+        encoding: Encoding,
         mtime: Option<SystemTime>,
         cx: &mut AppContext,
     );
@@ -548,6 +566,8 @@ impl Buffer {
             rpc::proto::LineEnding::from_i32(message.line_ending)
                 .ok_or_else(|| anyhow!("missing line_ending"))?,
         ));
+        this.encoding = proto::deserialize_encoding(&message.encoding);
+        this.large_file_mode = message.large_file_mode;
         this.saved_version = proto::deserialize_version(&message.saved_version);
         this.saved_mtime = message.saved_mtime.map(|time| time.into());
         Ok(this)
@@ -563,6 +583,8 @@ impl Buffer {
             line_ending: proto::serialize_line_ending(self.line_ending()) as i32,
             saved_version: proto::serialize_version(&self.saved_version),
             saved_mtime: self.saved_mtime.map(|time| time.into()),
+            encoding: proto::serialize_encoding(self.encoding),
+            large_file_mode: self.large_file_mode,
         }
     }
 
@@ -619,6 +641,27 @@ impl Buffer {
         self
     }
 
+    /// Assign an [Encoding] to the buffer, returning the buffer.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Opens the buffer in large file mode, returning the buffer.
+    ///
+    /// Large files are still edited and saved as usual, but are never parsed, diffed
+    /// against their git index text, or opened in language servers, as all of these
+    /// take time and memory proportional to the size of the file.
+    pub fn with_large_file_mode(mut self, large_file_mode: bool) -> Self {
+        self.large_file_mode = large_file_mode;
+        self
+    }
+
+    /// Whether the buffer was opened in large file mode.
+    pub fn is_large_file(&self) -> bool {
+        self.large_file_mode
+    }
+
     /// Returns the [Capability] of this buffer.
     pub fn capability(&self) -> Capability {
         self.capability
@@ -641,6 +684,8 @@ impl Buffer {
         Self {
             saved_mtime,
             saved_version: buffer.version(),
+            encoding: Encoding::default(),
+            large_file_mode: false,
             reload_task: None,
             transaction_depth: 0,
             was_dirty_before_starting_transaction: None,
@@ -652,6 +697,7 @@ impl Buffer {
             capability,
             syntax_map: Mutex::new(SyntaxMap::new()),
             parsing_in_background: false,
+            parse_status: watch::channel_with(ParseStatus::Idle),
             parse_count: 0,
             sync_parse_timeout: Duration::from_millis(1),
             autoindent_requests: Default::default(),
@@ -721,6 +767,19 @@ impl Buffer {
         self.saved_mtime
     }
 
+    /// The encoding of the buffer's file on disk.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Assign the encoding that the buffer is saved with.
+    pub fn set_encoding(&mut self, encoding: Encoding, cx: &mut ModelContext<Self>) {
+        if self.encoding != encoding {
+            self.encoding = encoding;
+            cx.notify();
+        }
+    }
+
     /// Assign a language to the buffer.
     pub fn set_language(&mut self, language: Option<Arc<Language>>, cx: &mut ModelContext<Self>) {
         self.parse_count += 1;
@@ -762,13 +821,24 @@ impl Buffer {
     pub fn reload(
         &mut self,
         cx: &mut ModelContext<Self>,
+    ) -> oneshot::Receiver<Option<Transaction>> {
+        self.reload_with_encoding(self.encoding, cx)
+    }
+
+    /// Reloads the contents of the buffer from disk, decoding them with the given [`Encoding`].
+    /// Once the new contents replace the buffer's text, the buffer is saved with that encoding
+    /// too; if the file can't be read, or the buffer was edited meanwhile, the encoding is kept.
+    pub fn reload_with_encoding(
+        &mut self,
+        encoding: Encoding,
+        cx: &mut ModelContext<Self>,
     ) -> oneshot::Receiver<Option<Transaction>> {
         let (tx, rx) = futures::channel::oneshot::channel();
         let prev_version = self.text.version();
         self.reload_task = Some(cx.spawn(|this, mut cx| async move {
             let Some((new_mtime, new_text)) = this.update(&mut cx, |this, cx| {
                 let file = this.file.as_ref()?.as_local()?;
-                Some((file.mtime(), file.load(cx)))
+                Some((file.mtime(), file.load(encoding, cx)))
             })?
             else {
                 return Ok(());
@@ -784,7 +854,7 @@ impl Buffer {
                     this.apply_diff(diff, cx);
                     tx.send(this.finalize_last_transaction().cloned()).ok();
                     this.has_conflict = false;
-                    this.did_reload(this.version(), this.line_ending(), new_mtime, cx);
+                    this.did_reload(this.version(), this.line_ending(), encoding, new_mtime, cx);
                 } else {
                     if !diff.edits.is_empty()
                         || this
@@ -795,7 +865,13 @@ impl Buffer {
                         this.has_conflict = true;
                     }
 
-                    this.did_reload(prev_version, this.line_ending(), this.saved_mtime, cx);
+                    this.did_reload(
+                        prev_version,
+                        this.line_ending(),
+                        this.encoding,
+                        this.saved_mtime,
+                        cx,
+                    );
                 }
 
                 this.reload_task.take();
@@ -809,17 +885,20 @@ impl Buffer {
         &mut self,
         version: clock::Global,
         line_ending: LineEnding,
+        encoding: Encoding,
         mtime: Option<SystemTime>,
         cx: &mut ModelContext<Self>,
     ) {
         self.saved_version = version;
         self.text.set_line_ending(line_ending);
+        self.set_encoding(encoding, cx);
         self.saved_mtime = mtime;
         if let Some(file) = self.file.as_ref().and_then(|f| f.as_local()) {
             file.buffer_reloaded(
                 self.remote_id(),
                 &self.saved_version,
                 self.line_ending(),
+                self.encoding,
                 self.saved_mtime,
                 cx,
             );
@@ -897,6 +976,9 @@ impl Buffer {
 
     /// Recomputes the Git diff status.
     pub fn git_diff_recalc(&mut self, cx: &mut ModelContext<Self>) -> Option<Task<()>> {
+        if self.large_file_mode {
+            return None;
+        }
         let diff_base = self.diff_base.clone()?; // TODO: Make this an Arc
         let snapshot = self.snapshot();
 
@@ -964,6 +1046,19 @@ impl Buffer {
         self.parsing_in_background
     }
 
+    /// Returns a future that resolves once the buffer is no longer being
+    /// parsed in the background.
+    pub fn parsing_idle(&self) -> impl Future<Output = ()> {
+        let mut parse_status = self.parse_status.1.clone();
+        async move {
+            while *parse_status.borrow() != ParseStatus::Idle {
+                if parse_status.recv().await.is_none() {
+                    break;
+                }
+            }
+        }
+    }
+
     /// Indicates whether the buffer contains any regions that may be
     /// written in a language that hasn't been loaded yet.
     pub fn contains_unknown_injections(&self) -> bool {
@@ -999,7 +1094,7 @@ impl Buffer {
     /// for the same buffer, we only initiate a new parse if we are not already
     /// parsing in the background.
     pub fn reparse(&mut self, cx: &mut ModelContext<Self>) {
-        if self.parsing_in_background {
+        if self.parsing_in_background || self.large_file_mode {
             return;
         }
         let language = if let Some(language) = self.language.clone() {
@@ -1036,6 +1131,7 @@ impl Buffer {
             }
             Err(parse_task) => {
                 self.parsing_in_background = true;
+                *self.parse_status.0.borrow_mut() = ParseStatus::Parsing;
                 cx.spawn(move |this, mut cx| async move {
                     let new_syntax_map = parse_task.await;
                     this.update(&mut cx, move |this, cx| {
@@ -1053,6 +1149,7 @@ impl Buffer {
                             || this.version.changed_since(&parsed_version);
                         this.did_finish_parsing(new_syntax_map, cx);
                         this.parsing_in_background = false;
+                        *this.parse_status.0.borrow_mut() = ParseStatus::Idle;
                         if parse_again {
                             this.reparse(cx);
                         }
@@ -1344,6 +1441,18 @@ impl Buffer {
     pub fn diff(&self, mut new_text: String, cx: &AppContext) -> Task<Diff> {
         let old_text = self.as_rope().clone();
         let base_version = self.version();
+        if self.large_file_mode {
+            // Diffing takes quadratic time at worst, so large files are replaced as a whole.
+            return cx.background_executor().spawn(async move {
+                let line_ending = LineEnding::detect(&new_text);
+                LineEnding::normalize(&mut new_text);
+                Diff {
+                    base_version,
+                    line_ending,
+                    edits: vec![(0..old_text.len(), new_text.into())],
+                }
+            });
+        }
         cx.background_executor()
             .spawn_labeled(*BUFFER_DIFF_TASK, async move {
                 let old_text = old_text.to_string();
@@ -2959,6 +3068,72 @@ impl BufferSnapshot {
         })
     }
 
+    /// Returns the ranges of all [`TextObject`]s whose syntax nodes intersect the given range.
+    /// When a single match captures several nodes for the same text object (e.g. the statements
+    /// of a function body), their ranges are merged into one.
+    pub fn text_object_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> impl Iterator<Item = (Range<usize>, TextObject)> + '_ {
+        // Widen the range so that nodes starting or ending at the given offsets are included.
+        let range = range.start.to_offset(self).saturating_sub(1)
+            ..self.len().min(range.end.to_offset(self) + 1);
+
+        let mut matches = self.syntax.matches(range.clone(), self, |grammar| {
+            grammar
+                .text_object_config
+                .as_ref()
+                .map(|config| &config.query)
+        });
+
+        let configs = matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.text_object_config.as_ref())
+            .collect::<Vec<_>>();
+
+        let mut captures = Vec::<(Range<usize>, TextObject)>::new();
+
+        iter::from_fn(move || loop {
+            while let Some(capture) = captures.pop() {
+                if capture.0.overlaps(&range) {
+                    return Some(capture);
+                }
+            }
+
+            let mat = matches.peek()?;
+
+            let Some(config) = configs[mat.grammar_index] else {
+                matches.advance();
+                continue;
+            };
+
+            for capture in mat.captures {
+                let Some(text_object) = config
+                    .text_objects_by_capture_ix
+                    .iter()
+                    .find(|(ix, _)| *ix == capture.index)
+                    .map(|(_, text_object)| *text_object)
+                else {
+                    continue;
+                };
+
+                let byte_range = capture.node.byte_range();
+                if let Some((existing_range, _)) = captures
+                    .iter_mut()
+                    .find(|(_, existing)| *existing == text_object)
+                {
+                    existing_range.start = existing_range.start.min(byte_range.start);
+                    existing_range.end = existing_range.end.max(byte_range.end);
+                } else {
+                    captures.push((byte_range, text_object));
+                }
+            }
+
+            matches.advance();
+        })
+    }
+
     /// Returns selections for remote peers intersecting the given range.
     #[allow(clippy::type_complexity)]
     pub fn remote_selections_in_range(
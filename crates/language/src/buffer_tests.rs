@@ -1031,6 +1031,69 @@ fn test_range_for_syntax_ancestor(cx: &mut AppContext) {
     }
 }
 
+#[gpui::test]
+fn test_structural_pattern(cx: &mut AppContext) {
+    let text = r#"
+        fn main() {
+            foo(1, bar(2, 3));
+            foo(x + 1, y);
+            foo(z);
+            baz(w, w);
+            baz(v, u);
+        }
+    "#
+    .unindent();
+    let buffer =
+        cx.new_model(|cx| Buffer::local(text.clone(), cx).with_language(Arc::new(rust_lang()), cx));
+    let snapshot = buffer.read(cx).snapshot();
+    let matched_text = |pattern: &StructuralPattern| {
+        pattern
+            .matches(&snapshot, 0..snapshot.len())
+            .into_iter()
+            .map(|mat| &text[mat.range])
+            .collect::<Vec<_>>()
+    };
+
+    let pattern = StructuralPattern::new("foo($a, $b)").unwrap();
+    assert_eq!(
+        pattern
+            .metavariables()
+            .iter()
+            .map(|name| name.as_ref())
+            .collect::<Vec<_>>(),
+        &["a", "b"]
+    );
+    assert_eq!(
+        matched_text(&pattern),
+        &["foo(1, bar(2, 3))", "foo(x + 1, y)"]
+    );
+    let replacements = pattern
+        .matches(&snapshot, 0..snapshot.len())
+        .iter()
+        .map(|mat| pattern.expand_template("foo($b, $a)", mat, &snapshot))
+        .collect::<Vec<_>>();
+    assert_eq!(replacements, &["foo(bar(2, 3), 1)", "foo(y, x + 1)"]);
+
+    // Matches are limited to the given range.
+    let start = text.find("foo(x").unwrap();
+    let matches = pattern.matches(&snapshot, start..snapshot.len());
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].range.start, start);
+
+    // A metavariable used twice must match the same text.
+    let pattern = StructuralPattern::new("baz($a, $a)").unwrap();
+    assert_eq!(matched_text(&pattern), &["baz(w, w)"]);
+
+    assert_eq!(
+        StructuralPattern::new("bar($a, $b) + $c")
+            .unwrap()
+            .literal_words()
+            .collect::<Vec<_>>(),
+        &["bar"]
+    );
+    assert!(StructuralPattern::new(" $a ").is_err());
+}
+
 #[gpui::test]
 fn test_autoindent_with_soft_tabs(cx: &mut AppContext) {
     init_settings(cx, |_| {});
@@ -124,6 +124,7 @@ pub const QUERY_FILENAME_PREFIXES: &[(
     ("injections", |q| &mut q.injections),
     ("overrides", |q| &mut q.overrides),
     ("redactions", |q| &mut q.redactions),
+    ("textobjects", |q| &mut q.text_objects),
 ];
 
 /// Tree-sitter language queries for a given language.
@@ -137,6 +138,7 @@ pub struct LanguageQueries {
     pub injections: Option<Cow<'static, str>>,
     pub overrides: Option<Cow<'static, str>>,
     pub redactions: Option<Cow<'static, str>>,
+    pub text_objects: Option<Cow<'static, str>>,
 }
 
 #[derive(Clone, Default)]
@@ -395,6 +397,18 @@ impl LanguageRegistry {
         result
     }
 
+    /// Returns the path suffixes of the files that belong to the language with
+    /// the given name, without loading the language.
+    pub fn path_suffixes_for_language(&self, name: &str) -> Option<Vec<String>> {
+        let name = UniCase::new(name);
+        let state = self.state.read();
+        state
+            .available_languages
+            .iter()
+            .find(|language| UniCase::new(language.name.as_ref()) == name)
+            .map(|language| language.matcher.path_suffixes.clone())
+    }
+
     pub fn grammar_names(&self) -> Vec<Arc<str>> {
         let state = self.state.read();
         let mut result = state.grammars.keys().cloned().collect::<Vec<_>>();
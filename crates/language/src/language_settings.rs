@@ -110,6 +110,10 @@ pub struct LanguageSettings {
     pub extend_comment_on_newline: bool,
     /// Inlay hint related settings.
     pub inlay_hints: InlayHintSettings,
+    /// Indent guide related settings.
+    pub indent_guides: IndentGuideSettings,
+    /// Sticky scroll related settings.
+    pub sticky_scroll: StickyScrollSettings,
     /// Whether to automatically close brackets.
     pub use_autoclose: bool,
     // Controls how the editor handles the autoclosed characters.
@@ -294,6 +298,12 @@ pub struct LanguageSettingsContent {
     /// Inlay hint related settings.
     #[serde(default)]
     pub inlay_hints: Option<InlayHintSettings>,
+    /// Indent guide related settings.
+    #[serde(default)]
+    pub indent_guides: Option<IndentGuideSettings>,
+    /// Sticky scroll related settings.
+    #[serde(default)]
+    pub sticky_scroll: Option<StickyScrollSettings>,
     /// Whether to automatically type closing characters for you. For example,
     /// when you type (, Zed will automatically add a closing ) at the correct position.
     ///
@@ -447,6 +457,50 @@ fn scroll_debounce_ms() -> u64 {
     50
 }
 
+/// The settings for indent guides.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct IndentGuideSettings {
+    /// Whether to show indent guides in the editor.
+    ///
+    /// Default: true
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// The width of the indent guides in pixels, between 1 and 10.
+    ///
+    /// Default: 1
+    #[serde(default = "line_width")]
+    pub line_width: u32,
+    /// Whether to highlight the guide of the block containing the cursor.
+    ///
+    /// Default: true
+    #[serde(default = "default_true")]
+    pub active_scope: bool,
+}
+
+fn line_width() -> u32 {
+    1
+}
+
+/// The settings for sticky scroll, which pins the first lines of the scopes enclosing the top
+/// of the editor while scrolling through them.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct StickyScrollSettings {
+    /// Whether to pin the enclosing scopes at the top of the editor.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub enabled: bool,
+    /// The maximum number of nested scopes to pin.
+    ///
+    /// Default: 5
+    #[serde(default = "max_depth")]
+    pub max_depth: usize,
+}
+
+fn max_depth() -> usize {
+    5
+}
+
 impl InlayHintSettings {
     /// Returns the kinds of inlay hints that are enabled based on the settings.
     pub fn enabled_inlay_hint_kinds(&self) -> HashSet<Option<InlayHintKind>> {
@@ -723,6 +777,8 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.extend_comment_on_newline,
     );
     merge(&mut settings.inlay_hints, src.inlay_hints);
+    merge(&mut settings.indent_guides, src.indent_guides);
+    merge(&mut settings.sticky_scroll, src.sticky_scroll);
 }
 
 #[cfg(test)]
@@ -26,6 +26,17 @@ pub fn serialize_line_ending(message: text::LineEnding) -> proto::LineEnding {
     }
 }
 
+/// Deserializes a [`text::Encoding`] from the RPC representation, which is its name.
+/// Unknown or missing names fall back to UTF-8.
+pub fn deserialize_encoding(name: &str) -> text::Encoding {
+    text::Encoding::from_name(name).unwrap_or_default()
+}
+
+/// Serializes a [`text::Encoding`] to be sent over RPC.
+pub fn serialize_encoding(encoding: text::Encoding) -> String {
+    encoding.to_string()
+}
+
 /// Serializes a [`crate::Operation`] to be sent over RPC.
 pub fn serialize_operation(operation: &crate::Operation) -> proto::Operation {
     proto::Operation {
@@ -0,0 +1,342 @@
+use crate::{with_parser, BufferSnapshot, Grammar, GrammarId};
+use anyhow::{anyhow, Result};
+use collections::HashMap;
+use parking_lot::Mutex;
+use std::{ops::Range, sync::Arc};
+use tree_sitter::Node;
+
+const METAVARIABLE_PLACEHOLDER_PREFIX: &str = "zed_metavariable_";
+
+/// A code pattern that is matched against the syntax trees of a buffer.
+///
+/// The pattern is written in the language being searched, and can contain
+/// metavariables such as `$a`, which match any single syntax node. A
+/// metavariable that appears more than once must match identical text each
+/// time. For example, `foo($a, $b)` matches every call to `foo` with two
+/// arguments.
+#[derive(Debug)]
+pub struct StructuralPattern {
+    source: Arc<str>,
+    /// The pattern with each metavariable replaced by a placeholder identifier,
+    /// so that it can be parsed by the grammar of the searched language.
+    placeholder_source: String,
+    metavariables: Vec<Arc<str>>,
+    compiled: Mutex<HashMap<GrammarId, Option<Arc<PatternNode>>>>,
+}
+
+/// A range of a buffer matched by a [`StructuralPattern`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructuralMatch {
+    pub range: Range<usize>,
+    /// The range captured by each of the pattern's metavariables.
+    pub captures: Vec<Range<usize>>,
+}
+
+#[derive(Debug)]
+enum PatternNode {
+    Metavariable(usize),
+    Leaf {
+        kind_id: u16,
+        text: String,
+    },
+    Node {
+        kind_id: u16,
+        children: Vec<PatternNode>,
+    },
+}
+
+impl StructuralPattern {
+    pub fn new(source: &str) -> Result<Self> {
+        let mut metavariables = Vec::<Arc<str>>::new();
+        let mut placeholder_source = String::with_capacity(source.len());
+        for token in tokenize(source) {
+            match token {
+                Token::Text(text) => placeholder_source.push_str(text),
+                Token::Metavariable(name) => {
+                    let ix = match metavariables.iter().position(|m| m.as_ref() == name) {
+                        Some(ix) => ix,
+                        None => {
+                            metavariables.push(name.into());
+                            metavariables.len() - 1
+                        }
+                    };
+                    placeholder_source.push_str(METAVARIABLE_PLACEHOLDER_PREFIX);
+                    placeholder_source.push_str(&ix.to_string());
+                }
+            }
+        }
+
+        if placeholder_source.trim().is_empty() {
+            return Err(anyhow!("empty structural pattern"));
+        }
+        if metavariables.len() == 1 && placeholder_source.trim() == placeholder(0) {
+            return Err(anyhow!("structural pattern must contain code"));
+        }
+
+        Ok(Self {
+            source: source.into(),
+            placeholder_source,
+            metavariables,
+            compiled: Default::default(),
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The names of the pattern's metavariables, without the leading `$`.
+    pub fn metavariables(&self) -> &[Arc<str>] {
+        &self.metavariables
+    }
+
+    /// Returns the words of the pattern outside of its metavariables. Any
+    /// text matched by the pattern contains all of them.
+    pub fn literal_words(&self) -> impl Iterator<Item = &str> {
+        tokenize(&self.source)
+            .filter_map(|token| match token {
+                Token::Text(text) => Some(text),
+                Token::Metavariable(_) => None,
+            })
+            .flat_map(|text| text.split(|c: char| !c.is_alphanumeric() && c != '_'))
+            .filter(|word| !word.is_empty())
+    }
+
+    /// Finds the syntax nodes contained in `range` that match this pattern,
+    /// in every syntax layer of the buffer whose grammar can parse it.
+    pub fn matches(&self, buffer: &BufferSnapshot, range: Range<usize>) -> Vec<StructuralMatch> {
+        let mut matches = Vec::new();
+        for layer in buffer.syntax_layers() {
+            let Some(grammar) = layer.language.grammar() else {
+                continue;
+            };
+            let Some(pattern) = self.compiled(grammar) else {
+                continue;
+            };
+
+            let mut cursor = layer.node().walk();
+            'nodes: loop {
+                let node = cursor.node();
+                let node_range = node.byte_range();
+                if node_range.start < range.end && node_range.end > range.start {
+                    let mut matched = false;
+                    if node_range.start >= range.start && node_range.end <= range.end {
+                        let mut captures = vec![None; self.metavariables.len()];
+                        if match_node(&pattern, node, buffer, &mut captures) {
+                            matches.push(StructuralMatch {
+                                range: node_range,
+                                captures: captures
+                                    .into_iter()
+                                    .map(|capture| capture.unwrap_or_default())
+                                    .collect(),
+                            });
+                            matched = true;
+                        }
+                    }
+
+                    if !matched && cursor.goto_first_child() {
+                        continue;
+                    }
+                }
+
+                while !cursor.goto_next_sibling() {
+                    if !cursor.goto_parent() {
+                        break 'nodes;
+                    }
+                }
+            }
+        }
+
+        matches.sort_by_key(|mat| (mat.range.start, std::cmp::Reverse(mat.range.end)));
+        matches.dedup_by(|next, prev| next.range.start < prev.range.end);
+        matches
+    }
+
+    /// Expands a replacement template for a match, substituting the text
+    /// captured by each metavariable for its occurrences in the template.
+    pub fn expand_template(
+        &self,
+        template: &str,
+        mat: &StructuralMatch,
+        buffer: &BufferSnapshot,
+    ) -> String {
+        let mut expanded = String::new();
+        for token in tokenize(template) {
+            match token {
+                Token::Text(text) => expanded.push_str(text),
+                Token::Metavariable(name) => {
+                    match self.metavariables.iter().position(|m| m.as_ref() == name) {
+                        Some(ix) => {
+                            expanded.extend(buffer.text_for_range(mat.captures[ix].clone()))
+                        }
+                        None => {
+                            expanded.push('$');
+                            expanded.push_str(name);
+                        }
+                    }
+                }
+            }
+        }
+        expanded
+    }
+
+    fn compiled(&self, grammar: &Grammar) -> Option<Arc<PatternNode>> {
+        self.compiled
+            .lock()
+            .entry(grammar.id())
+            .or_insert_with(|| self.compile(grammar).map(Arc::new))
+            .clone()
+    }
+
+    fn compile(&self, grammar: &Grammar) -> Option<PatternNode> {
+        let tree = with_parser(|parser| {
+            parser.set_language(&grammar.ts_language).ok()?;
+            parser.parse(&self.placeholder_source, None)
+        })?;
+
+        // Tolerate errors around the pattern, such as a missing semicolon
+        // after an expression, as long as the pattern itself parses.
+        let source = &self.placeholder_source;
+        let pattern_range = source.len() - source.trim_start().len()..source.trim_end().len();
+        let mut node = tree.root_node();
+        while node.has_error() {
+            let mut cursor = node.walk();
+            node = node.children(&mut cursor).find(|child| {
+                !child.is_missing()
+                    && child.start_byte() <= pattern_range.start
+                    && child.end_byte() >= pattern_range.end
+            })?;
+        }
+        // Skip the nodes that merely wrap the pattern, such as the root of the
+        // file and the statement around an expression.
+        loop {
+            let mut cursor = node.walk();
+            let mut children = node.children(&mut cursor).filter(|child| !child.is_extra());
+            match (children.next(), children.next()) {
+                (Some(child), None) => node = child,
+                _ => break,
+            }
+        }
+
+        match build_pattern_node(node, &self.placeholder_source, self.metavariables.len()) {
+            PatternNode::Metavariable(_) => None,
+            pattern => Some(pattern),
+        }
+    }
+}
+
+fn placeholder(ix: usize) -> String {
+    format!("{METAVARIABLE_PLACEHOLDER_PREFIX}{ix}")
+}
+
+fn build_pattern_node(node: Node, source: &str, metavariable_count: usize) -> PatternNode {
+    let text = &source[node.byte_range()];
+    if let Some(ix) = (0..metavariable_count).find(|ix| text == placeholder(*ix)) {
+        return PatternNode::Metavariable(ix);
+    }
+
+    let mut cursor = node.walk();
+    let children = node
+        .children(&mut cursor)
+        .filter(|child| !child.is_extra())
+        .map(|child| build_pattern_node(child, source, metavariable_count))
+        .collect::<Vec<_>>();
+    if children.is_empty() {
+        PatternNode::Leaf {
+            kind_id: node.kind_id(),
+            text: text.to_string(),
+        }
+    } else {
+        PatternNode::Node {
+            kind_id: node.kind_id(),
+            children,
+        }
+    }
+}
+
+fn match_node(
+    pattern: &PatternNode,
+    node: Node,
+    buffer: &BufferSnapshot,
+    captures: &mut [Option<Range<usize>>],
+) -> bool {
+    match pattern {
+        PatternNode::Metavariable(ix) => {
+            let range = node.byte_range();
+            match captures[*ix].clone() {
+                Some(captured) => {
+                    captured.len() == range.len()
+                        && buffer
+                            .text_for_range(captured)
+                            .flat_map(str::bytes)
+                            .eq(buffer.text_for_range(range).flat_map(str::bytes))
+                }
+                None => {
+                    captures[*ix] = Some(range);
+                    true
+                }
+            }
+        }
+        PatternNode::Leaf { kind_id, text } => {
+            node.kind_id() == *kind_id
+                && node.byte_range().len() == text.len()
+                && buffer.contains_str_at(node.start_byte(), text)
+        }
+        PatternNode::Node { kind_id, children } => {
+            if node.kind_id() != *kind_id {
+                return false;
+            }
+
+            let mut cursor = node.walk();
+            let mut pattern_children = children.iter();
+            for child in node.children(&mut cursor).filter(|child| !child.is_extra()) {
+                let Some(pattern_child) = pattern_children.next() else {
+                    return false;
+                };
+                if !match_node(pattern_child, child, buffer, captures) {
+                    return false;
+                }
+            }
+            pattern_children.next().is_none()
+        }
+    }
+}
+
+enum Token<'a> {
+    Text(&'a str),
+    Metavariable(&'a str),
+}
+
+/// Splits a pattern or a replacement template into literal text and `$name`
+/// metavariables. A `$` that isn't followed by a name is literal text.
+fn tokenize<'a>(source: &'a str) -> impl Iterator<Item = Token<'a>> {
+    let mut rest = source;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        if let Some(name) = rest.strip_prefix('$') {
+            let name_len = name
+                .char_indices()
+                .find(|(ix, c)| {
+                    !(c.is_alphanumeric() || *c == '_') || (*ix == 0 && c.is_ascii_digit())
+                })
+                .map_or(name.len(), |(ix, _)| ix);
+            if name_len > 0 {
+                let token = Token::Metavariable(&name[..name_len]);
+                rest = &name[name_len..];
+                return Some(token);
+            }
+        }
+
+        let text_len = rest
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| *c == '$')
+            .map_or(rest.len(), |(ix, _)| ix);
+        let token = Token::Text(&rest[..text_len]);
+        rest = &rest[text_len..];
+        Some(token)
+    })
+}
@@ -8,7 +8,7 @@ use task::{TaskTemplates, TaskVariables, VariableName};
 use text::{Point, ToPoint};
 
 /// Language Contexts are used by Zed tasks to extract information about the source file where the tasks are supposed to be scheduled from.
-/// Multiple context providers may be used together: by default, Zed provides a base [`BasicContextProvider`] context that fills all non-custom [`VariableName`] variants.
+/// Multiple context providers may be used together: by default, Zed provides a base [`BasicContextProvider`] context that fills all buffer-related [`VariableName`] variants.
 ///
 /// The context will be used to fill data for the tasks, and filter out the ones that do not have the variables required.
 pub trait ContextProvider: Send + Sync {
@@ -33,7 +33,7 @@ pub trait ContextProvider: Send + Sync {
     }
 }
 
-/// A context provided that tries to provide values for all non-custom [`VariableName`] variants for a currently opened file.
+/// A context provided that tries to provide values for all [`VariableName`] variants, that are derived from a currently opened file and its language.
 /// Applied as a base for every custom [`ContextProvider`] unless explicitly oped out.
 pub struct BasicContextProvider;
 
@@ -63,7 +63,7 @@ impl ContextProvider for BasicContextProvider {
         let current_file = buffer
             .file()
             .and_then(|file| file.as_local())
-            .map(|file| file.abs_path(cx).to_string_lossy().to_string());
+            .map(|file| file.abs_path(cx));
         let Point { row, column } = location.range.start.to_point(&buffer_snapshot);
         let row = row + 1;
         let column = column + 1;
@@ -82,8 +82,46 @@ impl ContextProvider for BasicContextProvider {
         if !selected_text.trim().is_empty() {
             task_variables.insert(VariableName::SelectedText, selected_text);
         }
-        if let Some(path) = current_file {
-            task_variables.insert(VariableName::File, path);
+        if let Some(path) = &current_file {
+            task_variables.insert(VariableName::File, path.to_string_lossy().to_string());
+            if let Some(directory) = path.parent() {
+                task_variables.insert(
+                    VariableName::Dirname,
+                    directory.to_string_lossy().to_string(),
+                );
+            }
+        }
+        if let Some(file) = buffer.file() {
+            if !file.path().as_os_str().is_empty() {
+                task_variables.insert(
+                    VariableName::RelativeFile,
+                    file.path().to_string_lossy().to_string(),
+                );
+            }
+            let file_name = Path::new(file.file_name(cx));
+            task_variables.insert(
+                VariableName::Filename,
+                file_name.to_string_lossy().to_string(),
+            );
+            if let Some(stem) = file_name.file_stem() {
+                task_variables.insert(VariableName::Stem, stem.to_string_lossy().to_string());
+            }
+            let extension = file_name
+                .extension()
+                .map(|extension| extension.to_string_lossy());
+            task_variables.insert(
+                VariableName::DottedExtension,
+                extension
+                    .as_ref()
+                    .map(|extension| format!(".{extension}"))
+                    .unwrap_or_default(),
+            );
+            if let Some(extension) = extension {
+                task_variables.insert(VariableName::Extension, extension.to_string());
+            }
+        }
+        if let Some(language) = buffer.language() {
+            task_variables.insert(VariableName::Language, language.name().to_string());
         }
         if let Some(worktree_path) = worktree_abs_path {
             task_variables.insert(
@@ -0,0 +1,16 @@
+; functions
+(function_definition) @function.around
+
+(function_definition
+    body: (_
+        "{"
+        .
+        (_) @function.inside
+        (_)? @function.inside
+        .
+        "}"))
+
+; comments
+(comment) @comment.inside
+
+(comment)+ @comment.around
@@ -0,0 +1,72 @@
+; functions
+(function_definition) @function.around
+
+(function_definition
+    body: (_
+        "{"
+        .
+        (_) @function.inside
+        (_)? @function.inside
+        .
+        "}"))
+
+(lambda_expression) @function.around
+
+(lambda_expression
+    body: (_
+        "{"
+        .
+        (_) @function.inside
+        (_)? @function.inside
+        .
+        "}"))
+
+; classes
+(class_specifier) @class.around
+
+(class_specifier
+    body: (_
+        "{"
+        .
+        (_) @class.inside
+        (_)? @class.inside
+        .
+        "}"))
+
+(struct_specifier) @class.around
+
+(struct_specifier
+    body: (_
+        "{"
+        .
+        (_) @class.inside
+        (_)? @class.inside
+        .
+        "}"))
+
+(union_specifier) @class.around
+
+(union_specifier
+    body: (_
+        "{"
+        .
+        (_) @class.inside
+        (_)? @class.inside
+        .
+        "}"))
+
+(enum_specifier) @class.around
+
+(enum_specifier
+    body: (_
+        "{"
+        .
+        (_) @class.inside
+        (_)? @class.inside
+        .
+        "}"))
+
+; comments
+(comment) @comment.inside
+
+(comment)+ @comment.around
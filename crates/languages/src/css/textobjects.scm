@@ -0,0 +1,4 @@
+; comments
+(comment) @comment.inside
+
+(comment) @comment.around
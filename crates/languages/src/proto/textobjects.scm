@@ -0,0 +1,11 @@
+; classes
+(message) @class.around
+
+(enum) @class.around
+
+(service) @class.around
+
+; comments
+(comment) @comment.inside
+
+(comment)+ @comment.around
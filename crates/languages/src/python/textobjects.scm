@@ -0,0 +1,15 @@
+; functions
+(function_definition
+    body: (_) @function.inside) @function.around
+
+(lambda
+    body: (_) @function.inside) @function.around
+
+; classes
+(class_definition
+    body: (_) @class.inside) @class.around
+
+; comments
+(comment) @comment.inside
+
+(comment)+ @comment.around
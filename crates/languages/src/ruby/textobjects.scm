@@ -0,0 +1,77 @@
+; functions
+(method) @function.around
+
+(method
+    parameters: (_)
+    .
+    (_) @function.inside
+    (_)? @function.inside
+    .
+    "end")
+
+(method
+    name: (_)
+    !parameters
+    .
+    (_) @function.inside
+    (_)? @function.inside
+    .
+    "end")
+
+(singleton_method) @function.around
+
+(singleton_method
+    parameters: (_)
+    .
+    (_) @function.inside
+    (_)? @function.inside
+    .
+    "end")
+
+(singleton_method
+    name: (_)
+    !parameters
+    .
+    (_) @function.inside
+    (_)? @function.inside
+    .
+    "end")
+
+(lambda) @function.around
+
+; classes
+(class) @class.around
+
+(class
+    superclass: (_)
+    .
+    (_) @class.inside
+    (_)? @class.inside
+    .
+    "end")
+
+(class
+    name: (_)
+    !superclass
+    .
+    (_) @class.inside
+    (_)? @class.inside
+    .
+    "end")
+
+(module) @class.around
+
+(module
+    name: (_)
+    .
+    (_) @class.inside
+    (_)? @class.inside
+    .
+    "end")
+
+(singleton_class) @class.around
+
+; comments
+(comment) @comment.inside
+
+(comment)+ @comment.around
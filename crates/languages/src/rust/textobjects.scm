@@ -0,0 +1,100 @@
+; functions
+(function_signature_item) @function.around
+
+(function_item) @function.around
+
+(function_item
+    body: (_
+        "{"
+        .
+        (_) @function.inside
+        (_)? @function.inside
+        .
+        "}"))
+
+(closure_expression) @function.around
+
+(closure_expression
+    body: (_
+        "{"
+        .
+        (_) @function.inside
+        (_)? @function.inside
+        .
+        "}"))
+
+; classes
+(struct_item) @class.around
+
+(struct_item
+    body: (_
+        ["{" "("]
+        .
+        (_) @class.inside
+        (_)? @class.inside
+        .
+        ["}" ")"]))
+
+(enum_item) @class.around
+
+(enum_item
+    body: (_
+        "{"
+        .
+        (_) @class.inside
+        (_)? @class.inside
+        .
+        "}"))
+
+(union_item) @class.around
+
+(union_item
+    body: (_
+        "{"
+        .
+        (_) @class.inside
+        (_)? @class.inside
+        .
+        "}"))
+
+(trait_item) @class.around
+
+(trait_item
+    body: (_
+        "{"
+        .
+        (_) @class.inside
+        (_)? @class.inside
+        .
+        "}"))
+
+(impl_item) @class.around
+
+(impl_item
+    body: (_
+        "{"
+        .
+        (_) @class.inside
+        (_)? @class.inside
+        .
+        "}"))
+
+(mod_item) @class.around
+
+(mod_item
+    body: (_
+        "{"
+        .
+        (_) @class.inside
+        (_)? @class.inside
+        .
+        "}"))
+
+; comments
+(line_comment) @comment.inside
+
+(line_comment)+ @comment.around
+
+(block_comment) @comment.inside
+
+(block_comment) @comment.around
@@ -0,0 +1,127 @@
+; functions
+(function) @function.around
+
+(function
+    body: (_
+        "{"
+        .
+        (_) @function.inside
+        (_)? @function.inside
+        .
+        "}"))
+
+(function_declaration) @function.around
+
+(function_declaration
+    body: (_
+        "{"
+        .
+        (_) @function.inside
+        (_)? @function.inside
+        .
+        "}"))
+
+(generator_function) @function.around
+
+(generator_function
+    body: (_
+        "{"
+        .
+        (_) @function.inside
+        (_)? @function.inside
+        .
+        "}"))
+
+(generator_function_declaration) @function.around
+
+(generator_function_declaration
+    body: (_
+        "{"
+        .
+        (_) @function.inside
+        (_)? @function.inside
+        .
+        "}"))
+
+(method_definition) @function.around
+
+(method_definition
+    body: (_
+        "{"
+        .
+        (_) @function.inside
+        (_)? @function.inside
+        .
+        "}"))
+
+(arrow_function) @function.around
+
+(arrow_function
+    body: (_
+        "{"
+        .
+        (_) @function.inside
+        (_)? @function.inside
+        .
+        "}"))
+
+; classes
+(class) @class.around
+
+(class
+    body: (_
+        "{"
+        .
+        (_) @class.inside
+        (_)? @class.inside
+        .
+        "}"))
+
+(class_declaration) @class.around
+
+(class_declaration
+    body: (_
+        "{"
+        .
+        (_) @class.inside
+        (_)? @class.inside
+        .
+        "}"))
+
+(abstract_class_declaration) @class.around
+
+(abstract_class_declaration
+    body: (_
+        "{"
+        .
+        (_) @class.inside
+        (_)? @class.inside
+        .
+        "}"))
+
+(interface_declaration) @class.around
+
+(interface_declaration
+    body: (_
+        "{"
+        .
+        (_) @class.inside
+        (_)? @class.inside
+        .
+        "}"))
+
+(enum_declaration) @class.around
+
+(enum_declaration
+    body: (_
+        "{"
+        .
+        (_) @class.inside
+        (_)? @class.inside
+        .
+        "}"))
+
+; comments
+(comment) @comment.inside
+
+(comment)+ @comment.around
@@ -2,18 +2,20 @@ use std::sync::Arc;
 use std::time::Duration;
 use std::{ops::Range, path::PathBuf};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use editor::scroll::{Autoscroll, AutoscrollStrategy};
 use editor::{Editor, EditorEvent};
 use gpui::{
-    list, AnyElement, AppContext, ClickEvent, EventEmitter, FocusHandle, FocusableView,
-    InteractiveElement, IntoElement, ListState, ParentElement, Render, Styled, Subscription, Task,
-    View, ViewContext, WeakView,
+    list, AnyElement, AppContext, AsyncWindowContext, ClickEvent, EventEmitter, FocusHandle,
+    FocusableView, InteractiveElement, IntoElement, ListOffset, ListState, Model, ParentElement,
+    Render, Styled, Subscription, Task, View, ViewContext, WeakView,
 };
-use language::LanguageRegistry;
+use language::{BufferId, LanguageRegistry};
+use project::Project;
+use rpc::proto::{self, PeerId};
 use ui::prelude::*;
-use workspace::item::{Item, ItemHandle, TabContentParams};
-use workspace::{Pane, Workspace};
+use workspace::item::{FollowEvent, FollowableItem, Item, ItemHandle, TabContentParams};
+use workspace::{Pane, ViewId, Workspace};
 
 use crate::markdown_elements::ParsedMarkdownElement;
 use crate::OpenPreviewToTheSide;
@@ -37,6 +39,10 @@ pub struct MarkdownPreviewView {
     fallback_tab_description: SharedString,
     language_registry: Arc<LanguageRegistry>,
     parsing_markdown_task: Option<Task<Result<()>>>,
+    remote_id: Option<ViewId>,
+    /// The scroll position of the collaborator we follow, kept so that it can
+    /// be restored once the markdown has been parsed.
+    leader_scroll_top: Option<ListOffset>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -137,7 +143,7 @@ impl MarkdownPreviewView {
         workspace: WeakView<Workspace>,
         language_registry: Arc<LanguageRegistry>,
         fallback_description: Option<SharedString>,
-        cx: &mut ViewContext<Workspace>,
+        cx: &mut WindowContext,
     ) -> View<Self> {
         cx.new_view(|cx: &mut ViewContext<Self>| {
             let view = cx.view().downgrade();
@@ -230,6 +236,12 @@ impl MarkdownPreviewView {
                     }
                 });
 
+            let view = cx.view().downgrade();
+            list_state.set_scroll_handler(move |_, cx| {
+                view.update(cx, |_, cx| cx.emit(PreviewEvent::ScrollPositionChanged))
+                    .ok();
+            });
+
             let mut this = Self {
                 selected_block: 0,
                 active_editor: None,
@@ -242,6 +254,8 @@ impl MarkdownPreviewView {
                 fallback_tab_description: fallback_description
                     .unwrap_or_else(|| "Markdown Preview".into()),
                 parsing_markdown_task: None,
+                remote_id: None,
+                leader_scroll_top: None,
             };
 
             this.set_editor(active_editor, cx);
@@ -302,6 +316,7 @@ impl MarkdownPreviewView {
                     let selection_range = editor.selections.last::<usize>(cx).range();
                     this.selected_block = this.get_block_index_under_cursor(selection_range);
                     this.list_state.scroll_to_reveal_item(this.selected_block);
+                    cx.emit(PreviewEvent::ScrollPositionChanged);
                     cx.notify();
                 }
                 _ => {}
@@ -319,6 +334,7 @@ impl MarkdownPreviewView {
         });
 
         self.parse_markdown_from_active_editor(false, cx);
+        cx.emit(PreviewEvent::EditorChanged);
     }
 
     fn parse_markdown_from_active_editor(
@@ -363,7 +379,9 @@ impl MarkdownPreviewView {
             view.update(&mut cx, move |view, cx| {
                 let markdown_blocks_count = contents.children.len();
                 view.contents = Some(contents);
-                let scroll_top = view.list_state.logical_scroll_top();
+                let scroll_top = view
+                    .leader_scroll_top
+                    .unwrap_or_else(|| view.list_state.logical_scroll_top());
                 view.list_state.reset(markdown_blocks_count);
                 view.list_state.scroll_to(scroll_top);
                 cx.notify();
@@ -430,6 +448,61 @@ impl MarkdownPreviewView {
         block_index.unwrap_or_default()
     }
 
+    fn active_buffer_id(&self, cx: &AppContext) -> Option<BufferId> {
+        let editor = self.active_editor.as_ref()?.editor.read(cx);
+        let buffer = editor.buffer().read(cx).as_singleton()?;
+        Some(buffer.read(cx).remote_id())
+    }
+
+    fn follower_state(&self, cx: &AppContext) -> Option<proto::view::MarkdownPreview> {
+        let editor = self.active_editor.as_ref()?.editor.read(cx);
+        let buffer = editor.buffer().read(cx).as_singleton()?;
+        // Only files can be opened by followers, and private ones are never shared.
+        if buffer
+            .read(cx)
+            .file()
+            .map_or(true, |file| file.is_private())
+        {
+            return None;
+        }
+
+        let scroll_top = self.list_state.logical_scroll_top();
+        Some(proto::view::MarkdownPreview {
+            buffer_id: buffer.read(cx).remote_id().into(),
+            scroll_top_item: scroll_top.item_ix as u64,
+            scroll_top_offset: scroll_top.offset_in_item.0,
+        })
+    }
+
+    /// Shows the same file as the collaborator we follow, scrolled to the same position.
+    async fn apply_leader_state(
+        this: WeakView<Self>,
+        project: Model<Project>,
+        state: proto::view::MarkdownPreview,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<()> {
+        let buffer_id = BufferId::new(state.buffer_id)?;
+        if this.update(cx, |this, cx| this.active_buffer_id(cx))? != Some(buffer_id) {
+            let buffer = project
+                .update(cx, |project, cx| project.open_buffer_by_id(buffer_id, cx))?
+                .await?;
+            this.update(cx, |this, cx| {
+                let editor = cx.new_view(|cx| Editor::for_buffer(buffer, Some(project), cx));
+                this.set_editor(editor, cx);
+            })?;
+        }
+
+        this.update(cx, |this, cx| {
+            let scroll_top = ListOffset {
+                item_ix: state.scroll_top_item as usize,
+                offset_in_item: px(state.scroll_top_offset),
+            };
+            this.leader_scroll_top = Some(scroll_top);
+            this.list_state.scroll_to(scroll_top);
+            cx.notify();
+        })
+    }
+
     fn should_apply_padding_between(
         current_block: &ParsedMarkdownElement,
         next_block: Option<&ParsedMarkdownElement>,
@@ -445,7 +518,10 @@ impl FocusableView for MarkdownPreviewView {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub enum PreviewEvent {}
+pub enum PreviewEvent {
+    EditorChanged,
+    ScrollPositionChanged,
+}
 
 impl EventEmitter<PreviewEvent> for MarkdownPreviewView {}
 
@@ -482,6 +558,110 @@ impl Item for MarkdownPreviewView {
     fn to_item_events(_event: &Self::Event, _f: impl FnMut(workspace::item::ItemEvent)) {}
 }
 
+impl FollowableItem for MarkdownPreviewView {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant> {
+        Some(proto::view::Variant::MarkdownPreview(
+            self.follower_state(cx)?,
+        ))
+    }
+
+    fn from_state_proto(
+        pane: View<Pane>,
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<Result<View<Self>>>> {
+        let Some(proto::view::Variant::MarkdownPreview(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::MarkdownPreview(state)) = state.take() else {
+            unreachable!()
+        };
+
+        let project = workspace.read(cx).project().clone();
+        let existing = pane
+            .read(cx)
+            .items_of_type::<Self>()
+            .find(|view| view.read(cx).remote_id == Some(remote_id));
+        let open_buffer = BufferId::new(state.buffer_id).map(|buffer_id| {
+            project.update(cx, |project, cx| project.open_buffer_by_id(buffer_id, cx))
+        });
+        let workspace = workspace.downgrade();
+        Some(cx.spawn(|mut cx| async move {
+            let view = if let Some(view) = existing {
+                view
+            } else {
+                let buffer = open_buffer?.await?;
+                cx.update(|cx| {
+                    let language_registry = project.read(cx).languages().clone();
+                    let editor =
+                        cx.new_view(|cx| Editor::for_buffer(buffer, Some(project.clone()), cx));
+                    let view = Self::new(
+                        MarkdownPreviewMode::Default,
+                        editor,
+                        workspace,
+                        language_registry,
+                        None,
+                        cx,
+                    );
+                    view.update(cx, |view, _| view.remote_id = Some(remote_id));
+                    view
+                })?
+            };
+            Self::apply_leader_state(view.downgrade(), project, state, &mut cx).await?;
+            Ok(view)
+        }))
+    }
+
+    fn to_follow_event(_: &Self::Event) -> Option<FollowEvent> {
+        None
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        _: &Self::Event,
+        update: &mut Option<proto::update_view::Variant>,
+        cx: &WindowContext,
+    ) -> bool {
+        if let Some(state) = self.follower_state(cx) {
+            *update = Some(proto::update_view::Variant::MarkdownPreview(state));
+            true
+        } else {
+            false
+        }
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        project: &Model<Project>,
+        message: proto::update_view::Variant,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let proto::update_view::Variant::MarkdownPreview(state) = message else {
+            return Task::ready(Err(anyhow!("unexpected update view variant")));
+        };
+        let project = project.clone();
+        cx.spawn(|this, mut cx| async move {
+            Self::apply_leader_state(this, project, state, &mut cx).await
+        })
+    }
+
+    fn is_project_item(&self, _: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, leader_peer_id: Option<PeerId>, _: &mut ViewContext<Self>) {
+        if leader_peer_id.is_none() {
+            self.leader_scroll_top = None;
+        }
+    }
+}
+
 impl Render for MarkdownPreviewView {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         v_flex()
@@ -320,6 +320,9 @@ impl NotificationStore {
                     user_ids.push(sender_id);
                     message_ids.push(message_id);
                 }
+                Notification::ReviewCommentMention { sender_id, .. } => {
+                    user_ids.push(sender_id);
+                }
             }
         }
 
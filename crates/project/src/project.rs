@@ -4,6 +4,7 @@ pub mod lsp_command;
 pub mod lsp_ext_command;
 mod prettier_support;
 pub mod project_settings;
+mod review_comments;
 pub mod search;
 mod task_inventory;
 pub mod terminals;
@@ -44,14 +45,14 @@ use language::{
     language_settings::{language_settings, FormatOnSave, Formatter, InlayHintKind},
     markdown, point_to_lsp, prepare_completion_documentation,
     proto::{
-        deserialize_anchor, deserialize_line_ending, deserialize_version, serialize_anchor,
-        serialize_version, split_operations,
+        deserialize_anchor, deserialize_encoding, deserialize_line_ending, deserialize_version,
+        serialize_anchor, serialize_encoding, serialize_version, split_operations,
     },
     range_from_lsp, Bias, Buffer, BufferSnapshot, CachedLspAdapter, Capability, CodeLabel,
-    Diagnostic, DiagnosticEntry, DiagnosticSet, Diff, Documentation, Event as BufferEvent,
-    File as _, Language, LanguageRegistry, LanguageServerName, LocalFile, LspAdapterDelegate,
-    Operation, Patch, PendingLanguageServer, PointUtf16, TextBufferSnapshot, ToOffset,
-    ToPointUtf16, Transaction, Unclipped,
+    Diagnostic, DiagnosticEntry, DiagnosticSet, Diff, Documentation, EncodingError,
+    Event as BufferEvent, File as _, Language, LanguageRegistry, LanguageServerName, LocalFile,
+    LspAdapterDelegate, Operation, Patch, PendingLanguageServer, PointUtf16, TextBufferSnapshot,
+    ToOffset, ToPointUtf16, Transaction, Unclipped,
 };
 use log::error;
 use lsp::{
@@ -114,7 +115,10 @@ pub use fs::*;
 pub use language::Location;
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
-pub use task_inventory::{Inventory, TaskSourceKind};
+pub use review_comments::{ReviewComment, ReviewThread};
+pub use task_inventory::{
+    Inventory, TaskGraphProgress, TaskGraphStep, TaskGraphStepStatus, TaskSourceKind,
+};
 pub use worktree::{
     DiagnosticSummary, Entry, EntryKind, File, LocalWorktree, PathChange, ProjectEntryId,
     RepositoryEntry, UpdatedEntriesSet, UpdatedGitRepositoriesSet, Worktree, WorktreeId,
@@ -211,6 +215,7 @@ pub struct Project {
     hosted_project_id: Option<ProjectId>,
     remote_project_id: Option<client::RemoteProjectId>,
     search_history: SearchHistory,
+    review_threads: Vec<ReviewThread>,
 }
 
 pub enum LanguageServerToQuery {
@@ -336,6 +341,7 @@ pub enum Event {
     CollaboratorLeft(proto::PeerId),
     RefreshInlayHints,
     RevealInProjectPanel(ProjectEntryId),
+    ReviewThreadsUpdated,
 }
 
 pub enum LanguageServerState {
@@ -668,9 +674,14 @@ impl Project {
         client.add_model_request_handler(Self::handle_open_new_buffer);
         client.add_model_request_handler(Self::handle_save_buffer);
         client.add_model_message_handler(Self::handle_update_diff_base);
+        client.add_model_message_handler(Self::handle_update_review_comments);
         client.add_model_request_handler(Self::handle_lsp_command::<lsp_ext_command::ExpandMacro>);
         client.add_model_request_handler(Self::handle_blame_buffer);
         client.add_model_request_handler(Self::handle_multi_lsp_query);
+        client.add_model_message_handler(Self::handle_update_shared_terminal);
+        client.add_model_message_handler(Self::handle_close_shared_terminal);
+        client.add_model_request_handler(Self::handle_synchronize_shared_terminals);
+        client.add_model_request_handler(Self::handle_shared_terminal_input);
     }
 
     pub fn local(
@@ -734,6 +745,9 @@ impl Project {
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
+                    shared: HashMap::default(),
+                    remote: HashMap::default(),
                 },
                 copilot_lsp_subscription,
                 copilot_log_subscription: None,
@@ -746,6 +760,7 @@ impl Project {
                 hosted_project_id: None,
                 remote_project_id: None,
                 search_history: Self::new_search_history(),
+                review_threads: Vec::new(),
             }
         })
     }
@@ -890,6 +905,9 @@ impl Project {
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
+                    shared: HashMap::default(),
+                    remote: HashMap::default(),
                 },
                 copilot_lsp_subscription,
                 copilot_log_subscription: None,
@@ -905,6 +923,7 @@ impl Project {
                     .remote_project_id
                     .map(|remote_project_id| RemoteProjectId(remote_project_id)),
                 search_history: Self::new_search_history(),
+                review_threads: Vec::new(),
             };
             this.set_role(role, cx);
             for worktree in worktrees {
@@ -927,6 +946,8 @@ impl Project {
         this.update(&mut cx, |this, cx| {
             this.set_collaborators_from_proto(response.payload.collaborators, cx)?;
             this.client_subscriptions.push(subscription);
+            this.refresh_review_threads(cx);
+            this.synchronize_shared_terminals(cx);
             anyhow::Ok(())
         })??;
 
@@ -1745,6 +1766,7 @@ impl Project {
         };
 
         self.metadata_changed(cx);
+        self.refresh_review_threads(cx);
         cx.emit(Event::RemoteIdChanged(Some(project_id)));
         cx.notify();
         Ok(())
@@ -1795,6 +1817,7 @@ impl Project {
             .collect();
         self.enqueue_buffer_ordered_message(BufferOrderedMessage::Resync)
             .unwrap();
+        self.synchronize_shared_terminals(cx);
         cx.notify();
         Ok(())
     }
@@ -1822,6 +1845,8 @@ impl Project {
             self.client_state = ProjectClientState::Local;
             self.collaborators.clear();
             self.shared_buffers.clear();
+            self.review_threads.clear();
+            self.terminals.shared.clear();
             self.client_subscriptions.clear();
 
             for worktree_handle in self.worktrees.iter_mut() {
@@ -1891,6 +1916,7 @@ impl Project {
             *sharing_has_stopped = true;
 
             self.collaborators.clear();
+            self.terminals.remote.clear();
 
             for worktree in &self.worktrees {
                 if let Some(worktree) = worktree.upgrade() {
@@ -2073,9 +2099,14 @@ impl Project {
         };
 
         cx.background_executor().spawn(async move {
-            wait_for_loading_buffer(loading_watch)
-                .await
-                .map_err(|e| e.cloned())
+            wait_for_loading_buffer(loading_watch).await.map_err(|e| {
+                // Keep the error typed, so that binary files can be opened by other items.
+                if e.is::<EncodingError>() {
+                    EncodingError.into()
+                } else {
+                    e.cloned()
+                }
+            })
         })
     }
 
@@ -2352,6 +2383,7 @@ impl Project {
         self.detect_language_for_buffer(buffer, cx);
         self.register_buffer_with_language_servers(buffer, cx);
         self.register_buffer_with_copilot(buffer, cx);
+        self.anchor_review_threads(cx);
         cx.observe_release(buffer, |this, buffer, cx| {
             if let Some(file) = File::from_dyn(buffer.file()) {
                 if file.is_local() {
@@ -2386,6 +2418,9 @@ impl Project {
     ) {
         let buffer = buffer_handle.read(cx);
         let buffer_id = buffer.remote_id();
+        if buffer.is_large_file() {
+            return;
+        }
 
         if let Some(file) = File::from_dyn(buffer.file()) {
             if !file.is_local() {
@@ -3052,6 +3087,10 @@ impl Project {
     ) {
         // If the buffer has a language, set it and start the language server if we haven't already.
         let buffer = buffer_handle.read(cx);
+        // Large files are opened as plain text, as they are too large to be parsed.
+        if buffer.is_large_file() {
+            return;
+        }
         let Some(file) = buffer.file() else {
             return;
         };
@@ -3089,6 +3128,9 @@ impl Project {
                 buffer.set_language(Some(new_language.clone()), cx);
             }
         });
+        if buffer.read(cx).is_large_file() {
+            return;
+        }
 
         let buffer_file = buffer.read(cx).file().cloned();
         let settings = language_settings(Some(&new_language), buffer_file.as_ref(), cx).clone();
@@ -3719,6 +3761,9 @@ impl Project {
         for buffer in self.opened_buffers.values() {
             if let Some(buffer_handle) = buffer.upgrade() {
                 let buffer = buffer_handle.read(cx);
+                if buffer.is_large_file() {
+                    continue;
+                }
                 let file = match File::from_dyn(buffer.file()) {
                     Some(file) => file,
                     None => continue,
@@ -6662,6 +6707,37 @@ impl Project {
                 Some(tree.snapshot())
             })
             .collect::<Vec<_>>();
+
+        // Finding the files changed in git requires diffing each repository, so it's done
+        // in the background before searching only the changed files.
+        if query.filters().only_git_changes {
+            let (result_tx, result_rx) = smol::channel::bounded(1024);
+            let changed_paths = cx
+                .background_executor()
+                .spawn(async move { git_changed_paths(&snapshots) });
+            cx.spawn(|this, mut cx| async move {
+                let changed_paths = changed_paths.await;
+                let mut filters = query.filters().clone();
+                filters.only_git_changes = false;
+                filters.within_paths = Some(Arc::new(match filters.within_paths.take() {
+                    Some(within_paths) => {
+                        within_paths.intersection(&changed_paths).cloned().collect()
+                    }
+                    None => changed_paths,
+                }));
+                let results = this.update(&mut cx, |this, cx| {
+                    this.search_local(query.with_filters(filters), cx)
+                })?;
+                while let Ok(result) = results.recv().await {
+                    if result_tx.send(result).await.is_err() {
+                        break;
+                    }
+                }
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+            return result_rx;
+        }
         let include_root = snapshots.len() > 1;
 
         let background = cx.background_executor().clone();
@@ -6687,12 +6763,13 @@ impl Project {
             .iter()
             .filter_map(|(_, b)| {
                 let buffer = b.upgrade()?;
-                let (is_ignored, snapshot) = buffer.update(cx, |buffer, cx| {
-                    let is_ignored = buffer
-                        .project_path(cx)
-                        .and_then(|path| self.entry_for_path(&path, cx))
+                let (project_path, is_ignored, snapshot) = buffer.update(cx, |buffer, cx| {
+                    let project_path = buffer.project_path(cx);
+                    let is_ignored = project_path
+                        .as_ref()
+                        .and_then(|path| self.entry_for_path(path, cx))
                         .map_or(false, |entry| entry.is_ignored);
-                    (is_ignored, buffer.snapshot())
+                    (project_path, is_ignored, buffer.snapshot())
                 });
                 if is_ignored && !query.include_ignored() {
                     return None;
@@ -6702,14 +6779,23 @@ impl Project {
                     } else {
                         query.file_matches(Some(file.path()))
                     };
+                    let filters = query.filters();
+                    let matched_filters = filters.file_type_matches(file.path())
+                        && filters.metadata_matches(Some(snapshot.len() as u64), file.mtime())
+                        && project_path.map_or(true, |project_path| {
+                            filters.project_path_matches(&project_path)
+                        });
 
-                    if matched_path {
+                    if matched_path && matched_filters {
                         Some((file.path().clone(), (buffer, snapshot)))
                     } else {
                         None
                     }
                 } else {
-                    unnamed_files.push(buffer);
+                    // Untitled buffers have none of the properties that filters check.
+                    if query.filters().is_empty() {
+                        unnamed_files.push(buffer);
+                    }
                     None
                 }
             })
@@ -6771,6 +6857,12 @@ impl Project {
 
                     chunk_results.push(cx.spawn(|cx| async move {
                         let buffer = buffer.await?;
+                        if query.is_structural() {
+                            // Structural queries are matched against the syntax tree.
+                            buffer
+                                .read_with(&cx, |buffer, _| buffer.parsing_idle())?
+                                .await;
+                        }
                         let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
                         let ranges = cx
                             .background_executor()
@@ -8777,6 +8869,7 @@ impl Project {
             buffer_id: buffer_id.into(),
             version: serialize_version(buffer.saved_version()),
             mtime: buffer.saved_mtime().map(|time| time.into()),
+            encoding: serialize_encoding(buffer.encoding()),
         })
     }
 
@@ -8872,6 +8965,7 @@ impl Project {
                             line_ending: language::proto::serialize_line_ending(
                                 buffer.line_ending(),
                             ) as i32,
+                            encoding: serialize_encoding(buffer.encoding()),
                         })
                         .log_err();
 
@@ -9906,6 +10000,7 @@ impl Project {
         let version = deserialize_version(&envelope.payload.version);
         let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
         let mtime = envelope.payload.mtime.map(|time| time.into());
+        let encoding = deserialize_encoding(&envelope.payload.encoding);
 
         this.update(&mut cx, |this, cx| {
             let buffer = this
@@ -9915,6 +10010,7 @@ impl Project {
                 .or_else(|| this.incomplete_remote_buffers.get(&buffer_id).cloned());
             if let Some(buffer) = buffer {
                 buffer.update(cx, |buffer, cx| {
+                    buffer.set_encoding(encoding, cx);
                     buffer.did_save(version, mtime, cx);
                 });
             }
@@ -9934,6 +10030,7 @@ impl Project {
             proto::LineEnding::from_i32(payload.line_ending)
                 .ok_or_else(|| anyhow!("missing line ending"))?,
         );
+        let encoding = deserialize_encoding(&payload.encoding);
         let mtime = payload.mtime.map(|time| time.into());
         let buffer_id = BufferId::new(payload.buffer_id)?;
         this.update(&mut cx, |this, cx| {
@@ -9944,7 +10041,7 @@ impl Project {
                 .or_else(|| this.incomplete_remote_buffers.get(&buffer_id).cloned());
             if let Some(buffer) = buffer {
                 buffer.update(cx, |buffer, cx| {
-                    buffer.did_reload(version, line_ending, mtime, cx);
+                    buffer.did_reload(version, line_ending, encoding, mtime, cx);
                 });
             }
             Ok(())
@@ -10371,12 +10468,28 @@ async fn search_snapshots(
                 } else {
                     query.file_matches(Some(&entry.path))
                 };
+                let filters = query.filters();
+                let matched_path = matched_path
+                    && filters.file_type_matches(&entry.path)
+                    && filters.metadata_matches(None, entry.mtime)
+                    && filters.project_path_matches(&ProjectPath {
+                        worktree_id: snapshot.id(),
+                        path: entry.path.clone(),
+                    });
 
                 let matches = if matched_path {
                     abs_path.clear();
                     abs_path.push(&snapshot.abs_path());
                     abs_path.push(&entry.path);
-                    if let Some(file) = fs.open_sync(&abs_path).await.log_err() {
+                    let matched_size = if filters.max_file_size.is_some() {
+                        let metadata = fs.metadata(&abs_path).await.log_err().flatten();
+                        filters.metadata_matches(metadata.map(|metadata| metadata.len), None)
+                    } else {
+                        true
+                    };
+                    if !matched_size {
+                        false
+                    } else if let Some(file) = fs.open_sync(&abs_path).await.log_err() {
                         query.detect(file).unwrap_or(false)
                     } else {
                         false
@@ -10402,6 +10515,37 @@ async fn search_snapshots(
     }
 }
 
+/// Returns the files of the worktrees that are changed in git, either on the current branch
+/// or in the working copy.
+fn git_changed_paths(snapshots: &[LocalSnapshot]) -> HashSet<ProjectPath> {
+    let mut changed_paths = HashSet::default();
+    for snapshot in snapshots {
+        for (work_directory, _) in snapshot.repositories() {
+            let Some(repo) = snapshot.local_git_repo(work_directory) else {
+                continue;
+            };
+            let Some(repo_paths) = repo.lock().branch_changed_paths().log_err() else {
+                continue;
+            };
+            changed_paths.extend(repo_paths.into_iter().map(|repo_path| ProjectPath {
+                worktree_id: snapshot.id(),
+                path: work_directory.join(&repo_path.0).into(),
+            }));
+        }
+
+        changed_paths.extend(
+            snapshot
+                .entries(false)
+                .filter(|entry| entry.is_file() && entry.git_status.is_some())
+                .map(|entry| ProjectPath {
+                    worktree_id: snapshot.id(),
+                    path: entry.path.clone(),
+                }),
+        );
+    }
+    changed_paths
+}
+
 async fn search_ignored_entry(
     snapshot: &LocalSnapshot,
     ignored_entry: &Entry,
@@ -10441,6 +10585,20 @@ async fn search_ignored_entry(
                 {
                     continue;
                 }
+                let filters = query.filters();
+                let matched_filters = filters.file_type_matches(&ignored_abs_path)
+                    && filters.metadata_matches(Some(fs_metadata.len), Some(fs_metadata.mtime))
+                    && ignored_abs_path
+                        .strip_prefix(snapshot.abs_path())
+                        .map_or(false, |path| {
+                            filters.project_path_matches(&ProjectPath {
+                                worktree_id: snapshot.id(),
+                                path: path.into(),
+                            })
+                        });
+                if !matched_filters {
+                    continue;
+                }
                 let matches = if let Some(file) = fs
                     .open_sync(&ignored_abs_path)
                     .await
@@ -4,7 +4,7 @@ use futures::{future, StreamExt};
 use gpui::AppContext;
 use language::{
     language_settings::{AllLanguageSettings, LanguageSettingsContent},
-    tree_sitter_rust, tree_sitter_typescript, Diagnostic, FakeLspAdapter, LanguageConfig,
+    tree_sitter_rust, tree_sitter_typescript, Diagnostic, Encoding, FakeLspAdapter, LanguageConfig,
     LanguageMatcher, LineEnding, OffsetRangeExt, Point, ToPoint,
 };
 use lsp::Url;
@@ -2799,6 +2799,7 @@ async fn test_file_changes_multiple_times_on_disk(cx: &mut gpui::TestAppContext)
         "/dir/file1".as_ref(),
         &"the first contents".into(),
         Default::default(),
+        Default::default(),
     )
     .await
     .unwrap();
@@ -2810,6 +2811,7 @@ async fn test_file_changes_multiple_times_on_disk(cx: &mut gpui::TestAppContext)
         "/dir/file1".as_ref(),
         &"the second contents".into(),
         Default::default(),
+        Default::default(),
     )
     .await
     .unwrap();
@@ -2854,6 +2856,7 @@ async fn test_edit_buffer_while_it_reloads(cx: &mut gpui::TestAppContext) {
         "/dir/file1".as_ref(),
         &"the first contents".into(),
         Default::default(),
+        Default::default(),
     )
     .await
     .unwrap();
@@ -3392,6 +3395,7 @@ async fn test_buffer_file_changes_on_disk(cx: &mut gpui::TestAppContext) {
         "/dir/the-file".as_ref(),
         &new_contents.into(),
         LineEnding::Unix,
+        Default::default(),
     )
     .await
     .unwrap();
@@ -3427,6 +3431,7 @@ async fn test_buffer_file_changes_on_disk(cx: &mut gpui::TestAppContext) {
         "/dir/the-file".as_ref(),
         &"\n\n\nAAAA\naaa\nBB\nbbbbb\n".into(),
         LineEnding::Unix,
+        Default::default(),
     )
     .await
     .unwrap();
@@ -3478,6 +3483,7 @@ async fn test_buffer_line_endings(cx: &mut gpui::TestAppContext) {
         "/dir/file1".as_ref(),
         &"aaa\nb\nc\n".into(),
         LineEnding::Windows,
+        Default::default(),
     )
     .await
     .unwrap();
@@ -3501,6 +3507,200 @@ async fn test_buffer_line_endings(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_buffer_encodings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({})).await;
+    // "café" in Latin-1 and "日本" in Shift_JIS.
+    fs.insert_file("/dir/latin1.txt", b"caf\xe9\n".to_vec())
+        .await;
+    fs.insert_file("/dir/sjis.txt", b"\x93\xfa\x96\x7b\n".to_vec())
+        .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let latin1_buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/latin1.txt", cx))
+        .await
+        .unwrap();
+    let sjis_buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/sjis.txt", cx))
+        .await
+        .unwrap();
+
+    latin1_buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "café\n");
+        assert_eq!(buffer.encoding().to_string(), "windows-1252");
+    });
+    sjis_buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "日本\n");
+        assert_eq!(buffer.encoding().to_string(), "Shift_JIS");
+    });
+
+    // Saving a buffer writes it in its original encoding.
+    latin1_buffer.update(cx, |buffer, cx| {
+        buffer.edit([(0..0, "crème ")], None, cx);
+    });
+    project
+        .update(cx, |project, cx| {
+            project.save_buffer(latin1_buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load_bytes("/dir/latin1.txt".as_ref()).await.unwrap(),
+        b"cr\xe8me caf\xe9\n"
+    );
+
+    // Reopening a buffer with a different encoding decodes the file again.
+    let utf16 = Encoding::from_name("UTF-16LE with BOM").unwrap();
+    sjis_buffer
+        .update(cx, |buffer, cx| {
+            buffer.reload_with_encoding(Encoding::from_name("windows-1252").unwrap(), cx)
+        })
+        .await
+        .unwrap();
+    sjis_buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "\u{201c}ú\u{2013}{\n");
+        assert!(!buffer.is_dirty());
+        buffer.set_text("日本\n", cx);
+        buffer.set_encoding(utf16, cx);
+    });
+
+    // Saving a buffer with a different encoding writes it in the new encoding.
+    project
+        .update(cx, |project, cx| {
+            project.save_buffer(sjis_buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load_bytes("/dir/sjis.txt".as_ref()).await.unwrap(),
+        b"\xff\xfe\xe5\x65\x2c\x67\n\0"
+    );
+
+    // Text that cannot be represented in the encoding fails to save, without touching the file.
+    latin1_buffer.update(cx, |buffer, cx| buffer.set_text("日本\n", cx));
+    assert!(project
+        .update(cx, |project, cx| project
+            .save_buffer(latin1_buffer.clone(), cx))
+        .await
+        .is_err());
+    assert_eq!(
+        fs.load_bytes("/dir/latin1.txt".as_ref()).await.unwrap(),
+        b"cr\xe8me caf\xe9\n"
+    );
+
+    // Reopening a file that can't be read keeps the buffer's encoding.
+    fs.remove_file("/dir/sjis.txt".as_ref(), Default::default())
+        .await
+        .unwrap();
+    assert!(sjis_buffer
+        .update(cx, |buffer, cx| {
+            buffer.reload_with_encoding(Encoding::from_name("windows-1252").unwrap(), cx)
+        })
+        .await
+        .is_err());
+    sjis_buffer.update(cx, |buffer, _| assert_eq!(buffer.encoding(), utf16));
+}
+
+#[gpui::test]
+async fn test_large_file_mode(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        cx.update_global(|settings: &mut SettingsStore, cx| {
+            settings.update_user_settings::<WorktreeSettings>(cx, |settings| {
+                settings.large_file_threshold = Some(1024 * 1024);
+            });
+        })
+    });
+
+    // Each line is 17 bytes long, so the first 1MB chunk of the file ends between a "\r" and a "\n".
+    let large_text = "0123456789abcde\r\n".repeat(70_000);
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "large.rs": large_text.clone(),
+            "small.rs": "fn main() {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers =
+        language_registry.register_fake_lsp_adapter("Rust", FakeLspAdapter::default());
+
+    let large_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/large.rs", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    large_buffer.update(cx, |buffer, _| {
+        assert!(buffer.is_large_file());
+        assert!(buffer.language().is_none());
+        assert_eq!(buffer.line_ending(), LineEnding::Windows);
+        assert_eq!(buffer.text(), "0123456789abcde\n".repeat(70_000));
+    });
+    assert!(fake_servers.try_next().is_err());
+
+    // Files below the threshold are opened as usual.
+    let small_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/small.rs", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    small_buffer.update(cx, |buffer, _| {
+        assert!(!buffer.is_large_file());
+        assert_eq!(
+            buffer.language().map(|language| language.name()),
+            Some("Rust".into())
+        );
+    });
+    fake_servers.next().await.unwrap();
+
+    // Large files can still be edited and saved.
+    large_buffer.update(cx, |buffer, cx| {
+        buffer.edit([(0..0, "first line\n")], None, cx);
+    });
+    project
+        .update(cx, |project, cx| {
+            project.save_buffer(large_buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load("/dir/large.rs".as_ref()).await.unwrap(),
+        format!("first line\r\n{large_text}")
+    );
+}
+
+#[gpui::test]
+async fn test_open_binary_file(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({})).await;
+    fs.insert_file("/dir/image.png", b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec())
+        .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let error = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/image.png", cx)
+        })
+        .await
+        .unwrap_err();
+    assert!(error.is::<EncodingError>());
+}
+
 #[gpui::test]
 async fn test_grouped_diagnostics(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3933,6 +4133,169 @@ async fn test_search(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_structural_search(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": "fn one() { foo(1, 2); foo(3); }",
+            "two.rs": "fn two() { foo(bar(4), 5); }",
+            "three.txt": "foo(6, 7)",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+
+    assert_eq!(
+        search(
+            &project,
+            SearchQuery::structural("foo($a, $b)", false, Vec::new(), Vec::new()).unwrap(),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([
+            ("dir/one.rs".to_string(), vec![11..20]),
+            ("dir/two.rs".to_string(), vec![11..25]),
+            // Files without a grammar are candidates, but never match.
+            ("dir/three.txt".to_string(), vec![]),
+        ])
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/two.rs", cx)
+        })
+        .await
+        .unwrap();
+    let query = SearchQuery::structural("foo($a, $b)", false, Vec::new(), Vec::new())
+        .unwrap()
+        .with_replacement("foo($b, $a)".to_string());
+    buffer.update(cx, |buffer, _| {
+        let snapshot = buffer.snapshot();
+        assert_eq!(
+            query.structural_replacement_for(&snapshot, 11..25),
+            Some("foo(5, bar(4))".to_string())
+        );
+        assert_eq!(query.structural_replacement_for(&snapshot, 15..21), None);
+    });
+}
+
+#[gpui::test]
+async fn test_search_with_filters(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "one.rs": "const ONE: usize = 1;",
+            "two.ts": "const TWO = one();",
+            "three.rs": "const THREE: usize = ONE + 2;",
+            "Makefile": "one:",
+        }),
+    )
+    .await;
+    fs.set_status_for_repo_via_git_operation(
+        Path::new("/dir/.git"),
+        &[(
+            Path::new("three.rs"),
+            git::repository::GitFileStatus::Modified,
+        )],
+    );
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    cx.executor().run_until_parked();
+    let worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let query = || SearchQuery::text("one", false, false, false, Vec::new(), Vec::new()).unwrap();
+
+    assert_eq!(
+        search(
+            &project,
+            query().with_filters(search::SearchFilters {
+                file_types: vec!["rs".to_string(), "Makefile".to_string()],
+                ..Default::default()
+            }),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([
+            ("dir/one.rs".to_string(), vec![6..9]),
+            ("dir/three.rs".to_string(), vec![21..24]),
+            ("dir/Makefile".to_string(), vec![0..3]),
+        ]),
+        "Only files with the given extensions or names should be searched"
+    );
+
+    assert_eq!(
+        search(
+            &project,
+            query().with_filters(search::SearchFilters {
+                within_paths: Some(Arc::new(HashSet::from_iter([
+                    ProjectPath {
+                        worktree_id,
+                        path: Path::new("one.rs").into(),
+                    },
+                    ProjectPath {
+                        worktree_id,
+                        path: Path::new("two.ts").into(),
+                    },
+                ]))),
+                ..Default::default()
+            }),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([
+            ("dir/one.rs".to_string(), vec![6..9]),
+            ("dir/two.ts".to_string(), vec![12..15]),
+        ]),
+        "Only the files of the previous results should be searched"
+    );
+
+    assert_eq!(
+        search(
+            &project,
+            query().with_filters(search::SearchFilters {
+                only_git_changes: true,
+                ..Default::default()
+            }),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([("dir/three.rs".to_string(), vec![21..24])]),
+        "Only the files changed in git should be searched"
+    );
+
+    assert_eq!(
+        search(
+            &project,
+            query().with_filters(search::SearchFilters {
+                max_file_size: Some(20),
+                ..Default::default()
+            }),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([
+            ("dir/two.ts".to_string(), vec![12..15]),
+            ("dir/Makefile".to_string(), vec![0..3]),
+        ]),
+        "Files larger than the maximum size should be skipped"
+    );
+}
+
 #[gpui::test]
 async fn test_search_with_inclusions(cx: &mut gpui::TestAppContext) {
     init_test(cx);
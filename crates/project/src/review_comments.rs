@@ -0,0 +1,339 @@
+use std::{ops::Range, path::Path, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use client::Client;
+use gpui::{AppContext, AsyncAppContext, Model, ModelContext, Task, WeakModel};
+use language::Buffer;
+use rpc::{proto, TypedEnvelope, MAX_REVIEW_ANCHOR_TEXT_LEN};
+use text::{Bias, Point, ToPoint as _};
+use time::OffsetDateTime;
+
+use crate::{Event, File, Project, ProjectPath, WorktreeId};
+
+/// A comment in a [`ReviewThread`].
+#[derive(Clone, Debug)]
+pub struct ReviewComment {
+    pub id: u64,
+    pub author_id: u64,
+    pub body: String,
+    pub mentions: Vec<(Range<usize>, u64)>,
+    pub timestamp: OffsetDateTime,
+}
+
+/// A discussion about a range of a file in a shared project.
+///
+/// Threads are stored by the collaboration server with the range and text they
+/// were started on. They follow edits to their buffer while it's open, and are
+/// found by their text when it's opened again.
+#[derive(Clone, Debug)]
+pub struct ReviewThread {
+    /// The id of the first comment in the thread.
+    pub id: u64,
+    pub path: ProjectPath,
+    pub comments: Vec<ReviewComment>,
+    pub resolved: bool,
+    range: Range<Point>,
+    anchor_text: String,
+    anchor_range: Option<(WeakModel<Buffer>, Range<text::Anchor>)>,
+}
+
+impl ReviewThread {
+    /// The buffer of the thread and its range in it, if the buffer is open.
+    pub fn buffer_range(&self) -> Option<(Model<Buffer>, Range<text::Anchor>)> {
+        let (buffer, range) = self.anchor_range.as_ref()?;
+        Some((buffer.upgrade()?, range.clone()))
+    }
+
+    /// The current range of the thread in its file.
+    pub fn range(&self, cx: &AppContext) -> Range<Point> {
+        match self.buffer_range() {
+            Some((buffer, range)) => {
+                let buffer = buffer.read(cx);
+                range.start.to_point(buffer)..range.end.to_point(buffer)
+            }
+            None => self.range.clone(),
+        }
+    }
+
+    /// Finds the thread in its buffer: at the range it was started on, unless
+    /// the text there has changed, in which case at the nearest copy of the text.
+    fn locate(&self, buffer: &Buffer) -> Range<usize> {
+        let start = buffer.point_to_offset(buffer.clip_point(self.range.start, Bias::Left));
+        let end = buffer
+            .point_to_offset(buffer.clip_point(self.range.end, Bias::Right))
+            .max(start);
+        if self.anchor_text.is_empty()
+            || buffer.text_for_range(start..end).collect::<String>() == self.anchor_text
+        {
+            return start..end;
+        }
+        buffer
+            .text()
+            .match_indices(&self.anchor_text)
+            .map(|(offset, _)| offset)
+            .min_by_key(|offset| offset.abs_diff(start))
+            .map_or(start..end, |offset| offset..offset + self.anchor_text.len())
+    }
+
+    pub fn author_id(&self) -> Option<u64> {
+        Some(self.comments.first()?.author_id)
+    }
+
+    fn comment_from_proto(comment: &proto::ReviewComment) -> ReviewComment {
+        ReviewComment {
+            id: comment.id,
+            author_id: comment.author_id,
+            body: comment.body.clone(),
+            mentions: comment
+                .mentions
+                .iter()
+                .filter_map(|mention| {
+                    let range = mention.range.as_ref()?;
+                    Some((range.start as usize..range.end as usize, mention.user_id))
+                })
+                .collect(),
+            timestamp: OffsetDateTime::from_unix_timestamp(comment.timestamp as i64)
+                .unwrap_or(OffsetDateTime::UNIX_EPOCH),
+        }
+    }
+}
+
+impl Project {
+    /// The review threads of the project, in the order they were started.
+    ///
+    /// Review threads are only available while the project is shared.
+    pub fn review_threads(&self) -> &[ReviewThread] {
+        &self.review_threads
+    }
+
+    pub fn review_thread(&self, thread_id: u64) -> Option<&ReviewThread> {
+        self.review_threads
+            .binary_search_by_key(&thread_id, |thread| thread.id)
+            .ok()
+            .map(|ix| &self.review_threads[ix])
+    }
+
+    /// Starts a review thread on the given range of a buffer.
+    pub fn create_review_thread(
+        &mut self,
+        buffer: &Model<Buffer>,
+        range: Range<text::Anchor>,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let buffer = buffer.read(cx);
+        let Some(file) = File::from_dyn(buffer.file()) else {
+            return Task::ready(Err(anyhow!("can't comment on a buffer without a file")));
+        };
+        let anchor_text = buffer.text_for_range(range.clone()).collect::<String>();
+        let range = range.start.to_point(buffer)..range.end.to_point(buffer);
+        let request = proto::CreateReviewComment {
+            worktree_id: file.worktree_id(cx).to_proto(),
+            path: file.path().to_string_lossy().into(),
+            start_row: range.start.row,
+            start_column: range.start.column,
+            end_row: range.end.row,
+            end_column: range.end.column,
+            anchor_text: if anchor_text.len() <= MAX_REVIEW_ANCHOR_TEXT_LEN {
+                anchor_text
+            } else {
+                String::new()
+            },
+            ..Default::default()
+        };
+        self.send_review_comment(request, body, cx)
+    }
+
+    /// Adds a comment to the end of a review thread.
+    pub fn reply_to_review_thread(
+        &mut self,
+        thread_id: u64,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.send_review_comment(
+            proto::CreateReviewComment {
+                reply_to_comment_id: Some(thread_id),
+                ..Default::default()
+            },
+            body,
+            cx,
+        )
+    }
+
+    pub fn set_review_thread_resolved(
+        &mut self,
+        thread_id: u64,
+        resolved: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(project_id) = self.remote_id() else {
+            return Task::ready(Err(anyhow!("project is not shared")));
+        };
+        let request = self.client.request(proto::ResolveReviewThread {
+            project_id,
+            comment_id: thread_id,
+            resolved,
+        });
+        cx.spawn(|_, _| async move {
+            request.await?;
+            Ok(())
+        })
+    }
+
+    fn send_review_comment(
+        &mut self,
+        mut request: proto::CreateReviewComment,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(project_id) = self.remote_id() else {
+            return Task::ready(Err(anyhow!("project is not shared")));
+        };
+        let body = body.trim().to_string();
+        request.project_id = project_id;
+        request.mentions = self.review_comment_mentions(&body, cx);
+        request.body = body;
+        let request = self.client.request(request);
+        cx.spawn(|this, mut cx| async move {
+            let response = request.await?;
+            let comment = response
+                .comment
+                .ok_or_else(|| anyhow!("invalid review comment"))?;
+            this.update(&mut cx, |this, cx| {
+                this.insert_review_comments(vec![comment], cx)
+            })
+        })
+    }
+
+    /// Finds the `@login` mentions of the project's collaborators in a comment.
+    fn review_comment_mentions(&self, body: &str, cx: &AppContext) -> Vec<proto::ChatMention> {
+        let user_store = self.user_store.read(cx);
+        let collaborators = self
+            .collaborators
+            .values()
+            .filter_map(|collaborator| user_store.get_cached_user(collaborator.user_id))
+            .collect::<Vec<_>>();
+
+        let mut mentions = Vec::new();
+        for (start, _) in body.match_indices('@') {
+            let login_start = start + 1;
+            let login_end = body[login_start..]
+                .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+                .map_or(body.len(), |len| login_start + len);
+            let login = &body[login_start..login_end];
+            if let Some(user) = collaborators
+                .iter()
+                .find(|user| !login.is_empty() && user.github_login.eq_ignore_ascii_case(login))
+            {
+                mentions.push(proto::ChatMention {
+                    range: Some(proto::Range {
+                        start: start as u64,
+                        end: login_end as u64,
+                    }),
+                    user_id: user.id,
+                });
+            }
+        }
+        mentions
+    }
+
+    pub(crate) fn refresh_review_threads(&mut self, cx: &mut ModelContext<Self>) {
+        let Some(project_id) = self.remote_id() else {
+            return;
+        };
+        let request = self.client.request(proto::GetReviewComments { project_id });
+        cx.spawn(|this, mut cx| async move {
+            let response = request.await?;
+            this.update(&mut cx, |this, cx| {
+                this.review_threads.clear();
+                this.insert_review_comments(response.comments, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn insert_review_comments(
+        &mut self,
+        comments: Vec<proto::ReviewComment>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        for comment in comments {
+            let thread_id = comment.reply_to_comment_id.unwrap_or(comment.id);
+            let ix = match self
+                .review_threads
+                .binary_search_by_key(&thread_id, |thread| thread.id)
+            {
+                Ok(ix) => ix,
+                Err(ix) => {
+                    self.review_threads.insert(
+                        ix,
+                        ReviewThread {
+                            id: thread_id,
+                            path: ProjectPath {
+                                worktree_id: WorktreeId::from_proto(comment.worktree_id),
+                                path: Path::new(&comment.path).into(),
+                            },
+                            comments: Vec::new(),
+                            resolved: false,
+                            range: Point::new(comment.start_row, comment.start_column)
+                                ..Point::new(comment.end_row, comment.end_column),
+                            anchor_text: comment.anchor_text.clone(),
+                            anchor_range: None,
+                        },
+                    );
+                    ix
+                }
+            };
+
+            let thread = &mut self.review_threads[ix];
+            if comment.reply_to_comment_id.is_none() {
+                thread.resolved = comment.resolved;
+            }
+            if let Err(ix) = thread
+                .comments
+                .binary_search_by_key(&comment.id, |comment| comment.id)
+            {
+                thread
+                    .comments
+                    .insert(ix, ReviewThread::comment_from_proto(&comment));
+            }
+        }
+
+        self.anchor_review_threads(cx);
+        cx.emit(Event::ReviewThreadsUpdated);
+        cx.notify();
+    }
+
+    /// Anchors the review threads whose buffers were opened, so that they
+    /// follow edits to them.
+    pub(crate) fn anchor_review_threads(&mut self, cx: &mut ModelContext<Self>) {
+        for ix in 0..self.review_threads.len() {
+            let thread = &self.review_threads[ix];
+            if thread.buffer_range().is_some() {
+                continue;
+            }
+            let path = thread.path.clone();
+            if let Some(buffer) = self.get_open_buffer(&path, cx) {
+                let thread = &mut self.review_threads[ix];
+                let snapshot = buffer.read(cx);
+                let range = thread.locate(snapshot);
+                thread.anchor_range = Some((
+                    buffer.downgrade(),
+                    snapshot.anchor_after(range.start)..snapshot.anchor_before(range.end),
+                ));
+            }
+        }
+    }
+
+    pub(crate) async fn handle_update_review_comments(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateReviewComments>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            this.insert_review_comments(envelope.payload.comments, cx)
+        })
+    }
+}
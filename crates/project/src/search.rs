@@ -1,16 +1,19 @@
+use crate::{ProjectPath, WorktreeId};
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder};
 use anyhow::{Context, Result};
 use client::proto;
+use collections::HashSet;
 use itertools::Itertools;
-use language::{char_kind, BufferSnapshot};
+use language::{char_kind, BufferSnapshot, StructuralPattern};
 use regex::{Captures, Regex, RegexBuilder};
 use smol::future::yield_now;
 use std::{
     borrow::Cow,
     io::{BufRead, BufReader, Read},
     ops::Range,
-    path::Path,
+    path::{Path, PathBuf},
     sync::{Arc, OnceLock},
+    time::SystemTime,
 };
 use util::paths::PathMatcher;
 
@@ -21,6 +24,101 @@ pub struct SearchInputs {
     query: Arc<str>,
     files_to_include: Vec<PathMatcher>,
     files_to_exclude: Vec<PathMatcher>,
+    filters: SearchFilters,
+}
+
+/// Restrictions on the files searched by a [`SearchQuery`], in addition to
+/// its include and exclude globs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchFilters {
+    /// The extensions or file names of the files to search, such as `rs` or
+    /// `Makefile`. Empty to search files of every type.
+    pub file_types: Vec<String>,
+    /// Skip files larger than this many bytes.
+    pub max_file_size: Option<u64>,
+    /// Skip files that were last modified before this time.
+    pub modified_since: Option<SystemTime>,
+    /// Only search files changed in git, either on the current branch or
+    /// since the last commit. The project resolves this into `within_paths`.
+    pub only_git_changes: bool,
+    /// Only search these files, such as the results of a previous search.
+    pub within_paths: Option<Arc<HashSet<ProjectPath>>>,
+}
+
+impl SearchFilters {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether the file at `path` has one of the file types to search.
+    pub fn file_type_matches(&self, path: &Path) -> bool {
+        if self.file_types.is_empty() {
+            return true;
+        }
+        let file_name = path.file_name().and_then(|name| name.to_str());
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        self.file_types.iter().any(|file_type| {
+            Some(file_type.as_str()) == extension || Some(file_type.as_str()) == file_name
+        })
+    }
+
+    /// Whether a file with the given size and modification time passes the
+    /// size and modification filters. Unknown values pass.
+    pub fn metadata_matches(&self, len: Option<u64>, mtime: Option<SystemTime>) -> bool {
+        let size_matches = self
+            .max_file_size
+            .zip(len)
+            .map_or(true, |(max_file_size, len)| len <= max_file_size);
+        let mtime_matches = self
+            .modified_since
+            .zip(mtime)
+            .map_or(true, |(modified_since, mtime)| mtime >= modified_since);
+        size_matches && mtime_matches
+    }
+
+    pub fn project_path_matches(&self, project_path: &ProjectPath) -> bool {
+        self.within_paths
+            .as_ref()
+            .map_or(true, |within_paths| within_paths.contains(project_path))
+    }
+
+    fn to_proto(&self, message: &mut proto::SearchProject) {
+        message.file_types = self.file_types.clone();
+        message.max_file_size = self.max_file_size;
+        message.modified_since = self.modified_since.map(Into::into);
+        message.only_git_changes = self.only_git_changes;
+        message.within_results = self.within_paths.is_some();
+        message.result_paths = self
+            .within_paths
+            .iter()
+            .flat_map(|within_paths| within_paths.iter())
+            .map(|project_path| proto::ProjectPath {
+                worktree_id: project_path.worktree_id.to_proto(),
+                path: project_path.path.to_string_lossy().to_string(),
+            })
+            .collect();
+    }
+
+    fn from_proto(message: &proto::SearchProject) -> Self {
+        Self {
+            file_types: message.file_types.clone(),
+            max_file_size: message.max_file_size,
+            modified_since: message.modified_since.clone().map(Into::into),
+            only_git_changes: message.only_git_changes,
+            within_paths: message.within_results.then(|| {
+                Arc::new(
+                    message
+                        .result_paths
+                        .iter()
+                        .map(|project_path| ProjectPath {
+                            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+                            path: PathBuf::from(&project_path.path).into(),
+                        })
+                        .collect(),
+                )
+            }),
+        }
+    }
 }
 
 impl SearchInputs {
@@ -33,12 +131,16 @@ impl SearchInputs {
     pub fn files_to_exclude(&self) -> &[PathMatcher] {
         &self.files_to_exclude
     }
+    pub fn filters(&self) -> &SearchFilters {
+        &self.filters
+    }
 }
 #[derive(Clone, Debug)]
 pub enum SearchQuery {
     Text {
         search: Arc<AhoCorasick>,
         replacement: Option<String>,
+        preserve_case: bool,
         whole_word: bool,
         case_sensitive: bool,
         include_ignored: bool,
@@ -48,12 +150,20 @@ pub enum SearchQuery {
     Regex {
         regex: Regex,
         replacement: Option<String>,
+        preserve_case: bool,
         multiline: bool,
         whole_word: bool,
         case_sensitive: bool,
         include_ignored: bool,
         inner: SearchInputs,
     },
+
+    Structural {
+        pattern: Arc<StructuralPattern>,
+        replacement: Option<String>,
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
 }
 
 impl SearchQuery {
@@ -73,10 +183,12 @@ impl SearchQuery {
             query: query.into(),
             files_to_exclude,
             files_to_include,
+            filters: SearchFilters::default(),
         };
         Ok(Self::Text {
             search: Arc::new(search),
             replacement: None,
+            preserve_case: false,
             whole_word,
             case_sensitive,
             include_ignored,
@@ -111,10 +223,12 @@ impl SearchQuery {
             query: initial_query,
             files_to_exclude,
             files_to_include,
+            filters: SearchFilters::default(),
         };
         Ok(Self::Regex {
             regex,
             replacement: None,
+            preserve_case: false,
             multiline,
             whole_word,
             case_sensitive,
@@ -123,8 +237,40 @@ impl SearchQuery {
         })
     }
 
+    /// Creates a query that matches code against a pattern with `$name`
+    /// metavariables, using the syntax tree of each buffer's language.
+    pub fn structural(
+        query: impl ToString,
+        include_ignored: bool,
+        files_to_include: Vec<PathMatcher>,
+        files_to_exclude: Vec<PathMatcher>,
+    ) -> Result<Self> {
+        let query = query.to_string();
+        let pattern = StructuralPattern::new(&query)?;
+        let inner = SearchInputs {
+            query: query.into(),
+            files_to_exclude,
+            files_to_include,
+            filters: SearchFilters::default(),
+        };
+        Ok(Self::Structural {
+            pattern: Arc::new(pattern),
+            replacement: None,
+            include_ignored,
+            inner,
+        })
+    }
+
     pub fn from_proto(message: proto::SearchProject) -> Result<Self> {
-        if message.regex {
+        let filters = SearchFilters::from_proto(&message);
+        let query = if message.structural {
+            Self::structural(
+                message.query,
+                message.include_ignored,
+                deserialize_path_matches(&message.files_to_include)?,
+                deserialize_path_matches(&message.files_to_exclude)?,
+            )
+        } else if message.regex {
             Self::regex(
                 message.query,
                 message.whole_word,
@@ -142,7 +288,19 @@ impl SearchQuery {
                 deserialize_path_matches(&message.files_to_include)?,
                 deserialize_path_matches(&message.files_to_exclude)?,
             )
+        }?;
+        Ok(query.with_filters(filters))
+    }
+    /// Restricts the files searched by this query.
+    pub fn with_filters(mut self, filters: SearchFilters) -> Self {
+        match &mut self {
+            Self::Text { inner, .. }
+            | Self::Regex { inner, .. }
+            | Self::Structural { inner, .. } => {
+                inner.filters = filters;
+            }
         }
+        self
     }
     pub fn with_replacement(mut self, new_replacement: String) -> Self {
         match self {
@@ -153,17 +311,39 @@ impl SearchQuery {
             | Self::Regex {
                 ref mut replacement,
                 ..
+            }
+            | Self::Structural {
+                ref mut replacement,
+                ..
             } => {
                 *replacement = Some(new_replacement);
                 self
             }
         }
     }
+    /// Makes replacements follow the case of the text they replace, so that replacing `foo` with
+    /// `bar` turns `Foo` into `Bar` and `FOO` into `BAR`. Structural replacements are unaffected.
+    pub fn with_preserved_case(mut self, preserve: bool) -> Self {
+        match &mut self {
+            Self::Text { preserve_case, .. } | Self::Regex { preserve_case, .. } => {
+                *preserve_case = preserve;
+            }
+            Self::Structural { .. } => {}
+        }
+        self
+    }
+    pub fn preserve_case(&self) -> bool {
+        match self {
+            Self::Text { preserve_case, .. } | Self::Regex { preserve_case, .. } => *preserve_case,
+            Self::Structural { .. } => false,
+        }
+    }
     pub fn to_proto(&self, project_id: u64) -> proto::SearchProject {
-        proto::SearchProject {
+        let mut message = proto::SearchProject {
             project_id,
             query: self.as_str().to_string(),
             regex: self.is_regex(),
+            structural: self.is_structural(),
             whole_word: self.whole_word(),
             case_sensitive: self.case_sensitive(),
             include_ignored: self.include_ignored(),
@@ -177,7 +357,10 @@ impl SearchQuery {
                 .iter()
                 .map(|matcher| matcher.to_string())
                 .join(","),
-        }
+            ..Default::default()
+        };
+        self.filters().to_proto(&mut message);
+        message
     }
 
     pub fn detect<T: Read>(&self, stream: T) -> Result<bool> {
@@ -215,22 +398,42 @@ impl SearchQuery {
                     Ok(false)
                 }
             }
+            Self::Structural { pattern, .. } => {
+                let mut text = String::new();
+                BufReader::new(stream).read_to_string(&mut text)?;
+                Ok(pattern.literal_words().all(|word| text.contains(word)))
+            }
         }
     }
     /// Returns the replacement text for this `SearchQuery`.
     pub fn replacement(&self) -> Option<&str> {
         match self {
-            SearchQuery::Text { replacement, .. } | SearchQuery::Regex { replacement, .. } => {
-                replacement.as_deref()
-            }
+            SearchQuery::Text { replacement, .. }
+            | SearchQuery::Regex { replacement, .. }
+            | SearchQuery::Structural { replacement, .. } => replacement.as_deref(),
         }
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
+    /// Structural queries need the syntax tree around the match, see [`Self::structural_replacement_for`].
     pub fn replacement_for<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
         match self {
-            SearchQuery::Text { replacement, .. } => replacement.clone().map(Cow::from),
+            SearchQuery::Structural { .. } => None,
+            SearchQuery::Text {
+                replacement,
+                preserve_case,
+                ..
+            } => replacement.as_deref().map(|replacement| {
+                if *preserve_case {
+                    Cow::Owned(apply_case_of(text, replacement))
+                } else {
+                    Cow::Owned(replacement.to_string())
+                }
+            }),
             SearchQuery::Regex {
-                regex, replacement, ..
+                regex,
+                replacement,
+                preserve_case,
+                ..
             } => {
                 if let Some(replacement) = replacement {
                     let replacement = TEXT_REPLACEMENT_SPECIAL_CHARACTERS_REGEX
@@ -243,13 +446,40 @@ impl SearchQuery {
                                 x => unreachable!("Unexpected escape sequence: {}", x),
                             }
                         });
-                    Some(regex.replace(text, replacement))
+                    let replaced = regex.replace(text, replacement);
+                    if *preserve_case {
+                        Some(Cow::Owned(apply_case_of(text, &replaced)))
+                    } else {
+                        Some(replaced)
+                    }
                 } else {
                     None
                 }
             }
         }
     }
+    /// Replaces a hit of a structural query, substituting the nodes captured
+    /// by the pattern's metavariables into the replacement template.
+    pub fn structural_replacement_for(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<String> {
+        let SearchQuery::Structural {
+            pattern,
+            replacement: Some(replacement),
+            ..
+        } = self
+        else {
+            return None;
+        };
+        let mat = pattern
+            .matches(buffer, range.clone())
+            .into_iter()
+            .find(|mat| mat.range == range)?;
+        Some(pattern.expand_template(replacement, &mat, buffer))
+    }
+
     pub async fn search(
         &self,
         buffer: &BufferSnapshot,
@@ -334,6 +564,13 @@ impl SearchQuery {
                     }
                 }
             }
+
+            Self::Structural { pattern, .. } => {
+                let range = range_offset..range_offset + rope.len();
+                for mat in pattern.matches(buffer, range) {
+                    matches.push(mat.range.start - range_offset..mat.range.end - range_offset);
+                }
+            }
         }
 
         matches
@@ -351,6 +588,7 @@ impl SearchQuery {
         match self {
             Self::Text { whole_word, .. } => *whole_word,
             Self::Regex { whole_word, .. } => *whole_word,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -358,6 +596,7 @@ impl SearchQuery {
         match self {
             Self::Text { case_sensitive, .. } => *case_sensitive,
             Self::Regex { case_sensitive, .. } => *case_sensitive,
+            Self::Structural { .. } => true,
         }
     }
 
@@ -369,6 +608,9 @@ impl SearchQuery {
             Self::Regex {
                 include_ignored, ..
             } => *include_ignored,
+            Self::Structural {
+                include_ignored, ..
+            } => *include_ignored,
         }
     }
 
@@ -376,6 +618,10 @@ impl SearchQuery {
         matches!(self, Self::Regex { .. })
     }
 
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Self::Structural { .. })
+    }
+
     pub fn files_to_include(&self) -> &[PathMatcher] {
         self.as_inner().files_to_include()
     }
@@ -384,6 +630,10 @@ impl SearchQuery {
         self.as_inner().files_to_exclude()
     }
 
+    pub fn filters(&self) -> &SearchFilters {
+        self.as_inner().filters()
+    }
+
     pub fn file_matches(&self, file_path: Option<&Path>) -> bool {
         match file_path {
             Some(file_path) => {
@@ -412,7 +662,9 @@ impl SearchQuery {
     }
     pub fn as_inner(&self) -> &SearchInputs {
         match self {
-            Self::Regex { inner, .. } | Self::Text { inner, .. } => inner,
+            Self::Regex { inner, .. }
+            | Self::Text { inner, .. }
+            | Self::Structural { inner, .. } => inner,
         }
     }
 }
@@ -429,6 +681,31 @@ fn deserialize_path_matches(glob_set: &str) -> anyhow::Result<Vec<PathMatcher>>
         .collect()
 }
 
+/// Changes the case of `replacement` to follow the case of the `replaced` text: all uppercase,
+/// all lowercase or capitalized. Text of any other case keeps the replacement as it is, and so
+/// does a replacement that mixes cases itself, such as `userKey`.
+fn apply_case_of(replaced: &str, replacement: &str) -> String {
+    if replacement.chars().any(char::is_uppercase) && replacement.chars().any(char::is_lowercase) {
+        return replacement.to_string();
+    }
+    let letters = replaced
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .collect::<Vec<_>>();
+    match letters.as_slice() {
+        [_, _, ..] if letters.iter().all(|c| c.is_uppercase()) => replacement.to_uppercase(),
+        [_, ..] if letters.iter().all(|c| c.is_lowercase()) => replacement.to_lowercase(),
+        [first, rest @ ..] if first.is_uppercase() && rest.iter().all(|c| c.is_lowercase()) => {
+            let mut chars = replacement.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        }
+        _ => replacement.to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -476,4 +753,64 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn case_preserving_replacement() {
+        let query = SearchQuery::text("foo", false, false, false, Vec::new(), Vec::new())
+            .unwrap()
+            .with_replacement("bar".to_string())
+            .with_preserved_case(true);
+        for (text, expected_replacement) in [
+            ("foo", "bar"),
+            ("Foo", "Bar"),
+            ("FOO", "BAR"),
+            ("fOo", "bar"),
+        ] {
+            assert_eq!(
+                query.replacement_for(text).as_deref(),
+                Some(expected_replacement),
+                "Unexpected replacement for {text}"
+            );
+        }
+
+        let query = SearchQuery::regex("(\\w+)_id", false, false, false, Vec::new(), Vec::new())
+            .unwrap()
+            .with_replacement("${1}Key".to_string())
+            .with_preserved_case(true);
+        assert_eq!(query.replacement_for("user_id").as_deref(), Some("userKey"));
+        assert_eq!(query.replacement_for("User_id").as_deref(), Some("UserKey"));
+        assert_eq!(query.replacement_for("USER_ID").as_deref(), Some("USERKey"));
+
+        let query = SearchQuery::regex("(\\w+)_id", false, false, false, Vec::new(), Vec::new())
+            .unwrap()
+            .with_replacement("${1}_key".to_string())
+            .with_preserved_case(true);
+        assert_eq!(
+            query.replacement_for("user_id").as_deref(),
+            Some("user_key")
+        );
+        assert_eq!(
+            query.replacement_for("User_id").as_deref(),
+            Some("User_key")
+        );
+        assert_eq!(
+            query.replacement_for("USER_ID").as_deref(),
+            Some("USER_KEY")
+        );
+        assert_eq!(
+            query
+                .with_preserved_case(false)
+                .replacement_for("USER_ID")
+                .as_deref(),
+            Some("USER_key")
+        );
+
+        let query = SearchQuery::text("foo", false, false, false, Vec::new(), Vec::new())
+            .unwrap()
+            .with_replacement("barBaz".to_string())
+            .with_preserved_case(true);
+        for text in ["foo", "Foo", "FOO"] {
+            assert_eq!(query.replacement_for(text).as_deref(), Some("barBaz"));
+        }
+    }
 }
@@ -11,7 +11,9 @@ use collections::{hash_map, HashMap, VecDeque};
 use gpui::{AppContext, Context, Model, ModelContext, Subscription};
 use itertools::{Either, Itertools};
 use language::Language;
-use task::{ResolvedTask, TaskContext, TaskId, TaskSource, TaskTemplate, VariableName};
+use task::{
+    ResolvedTask, SpawnInTerminal, TaskContext, TaskId, TaskSource, TaskTemplate, VariableName,
+};
 use util::{post_inc, NumericPrefixWithSuffix};
 use worktree::WorktreeId;
 
@@ -19,6 +21,78 @@ use worktree::WorktreeId;
 pub struct Inventory {
     sources: Vec<SourceInInventory>,
     last_scheduled_tasks: VecDeque<(TaskSourceKind, ResolvedTask)>,
+    task_graphs: HashMap<TaskId, TaskGraphProgress>,
+}
+
+/// Progress of the latest run of a task with dependencies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskGraphProgress {
+    /// Every task of the graph, once, in the order of their spawn: dependencies first, the task itself last.
+    pub steps: Vec<TaskGraphStep>,
+}
+
+/// A single task of the [`TaskGraphProgress`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskGraphStep {
+    pub id: TaskId,
+    pub label: String,
+    pub status: TaskGraphStepStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskGraphStepStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    /// Not run, due to some of the dependencies failing.
+    Skipped,
+}
+
+impl TaskGraphProgress {
+    fn new(task: &SpawnInTerminal) -> Self {
+        fn add_steps(task: &SpawnInTerminal, steps: &mut Vec<TaskGraphStep>) {
+            for dependency in &task.depends_on {
+                add_steps(dependency, steps);
+            }
+            if steps.iter().all(|step| step.id != task.id) {
+                steps.push(TaskGraphStep {
+                    id: task.id.clone(),
+                    label: task.label.clone(),
+                    status: TaskGraphStepStatus::Pending,
+                });
+            }
+        }
+
+        let mut steps = Vec::new();
+        add_steps(task, &mut steps);
+        Self { steps }
+    }
+
+    /// Whether any of the tasks of the graph is not finished yet.
+    pub fn is_running(&self) -> bool {
+        self.steps.iter().any(|step| {
+            matches!(
+                step.status,
+                TaskGraphStepStatus::Pending | TaskGraphStepStatus::Running
+            )
+        })
+    }
+
+    /// The first task of the graph that failed, if any.
+    pub fn failed_step(&self) -> Option<&TaskGraphStep> {
+        self.steps
+            .iter()
+            .find(|step| step.status == TaskGraphStepStatus::Failed)
+    }
+
+    /// Number of tasks of the graph that finished successfully.
+    pub fn succeeded_steps(&self) -> usize {
+        self.steps
+            .iter()
+            .filter(|step| step.status == TaskGraphStepStatus::Succeeded)
+            .count()
+    }
 }
 
 struct SourceInInventory {
@@ -86,6 +160,7 @@ impl Inventory {
         cx.new_model(|_| Self {
             sources: Vec::new(),
             last_scheduled_tasks: VecDeque::new(),
+            task_graphs: HashMap::default(),
         })
     }
 
@@ -338,6 +413,54 @@ impl Inventory {
         }
     }
 
+    /// Starts tracking the progress of the task with dependencies given, replacing the progress of its previous run.
+    pub fn task_graph_started(&mut self, task: &SpawnInTerminal, cx: &mut ModelContext<Self>) {
+        self.task_graphs
+            .insert(task.id.clone(), TaskGraphProgress::new(task));
+        cx.notify();
+    }
+
+    /// Updates the status of a task in the graph of the task with id `graph_id`.
+    pub fn task_graph_step_updated(
+        &mut self,
+        graph_id: &TaskId,
+        step_id: &TaskId,
+        status: TaskGraphStepStatus,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(graph) = self.task_graphs.get_mut(graph_id) else {
+            return;
+        };
+        for step in graph.steps.iter_mut().filter(|step| &step.id == step_id) {
+            step.status = status;
+        }
+        cx.notify();
+    }
+
+    /// Marks all graph tasks that were not run as skipped, returning the final progress of the graph.
+    pub fn task_graph_finished(
+        &mut self,
+        graph_id: &TaskId,
+        cx: &mut ModelContext<Self>,
+    ) -> Option<TaskGraphProgress> {
+        let graph = self.task_graphs.get_mut(graph_id)?;
+        for step in &mut graph.steps {
+            if matches!(
+                step.status,
+                TaskGraphStepStatus::Pending | TaskGraphStepStatus::Running
+            ) {
+                step.status = TaskGraphStepStatus::Skipped;
+            }
+        }
+        cx.notify();
+        Some(graph.clone())
+    }
+
+    /// Progress of the latest run of the task with dependencies, if there was any.
+    pub fn task_graph_progress(&self, graph_id: &TaskId) -> Option<&TaskGraphProgress> {
+        self.task_graphs.get(graph_id)
+    }
+
     /// Deletes a resolved task from history, using its id.
     /// A similar may still resurface in `used_and_current_resolved_tasks` when its [`TaskTemplate`] is resolved again.
     pub fn delete_previously_used(&mut self, id: &TaskId) {
@@ -845,4 +968,59 @@ mod tests {
                 .collect::<Vec<_>>(),
         );
     }
+
+    #[gpui::test]
+    fn test_task_graph_progress(cx: &mut TestAppContext) {
+        let spawn_task = |label: &str| {
+            TaskTemplate {
+                label: label.to_string(),
+                command: "echo".to_string(),
+                ..TaskTemplate::default()
+            }
+            .resolve_task("test", &TaskContext::default())
+            .unwrap()
+            .resolved
+            .unwrap()
+        };
+        let build = spawn_task("build");
+        let mut lint = spawn_task("lint");
+        lint.depends_on = vec![build.clone()];
+        let mut run = spawn_task("run");
+        run.depends_on = vec![build.clone(), lint.clone()];
+
+        let inventory = cx.update(Inventory::new);
+        inventory.update(cx, |inventory, cx| {
+            inventory.task_graph_started(&run, cx);
+            let progress = inventory.task_graph_progress(&run.id).unwrap();
+            assert_eq!(
+                progress
+                    .steps
+                    .iter()
+                    .map(|step| step.label.as_str())
+                    .collect::<Vec<_>>(),
+                vec!["build", "lint", "run"],
+                "Each task should be listed once, after its dependencies"
+            );
+            assert!(progress.is_running());
+
+            inventory.task_graph_step_updated(
+                &run.id,
+                &build.id,
+                TaskGraphStepStatus::Succeeded,
+                cx,
+            );
+            inventory.task_graph_step_updated(&run.id, &lint.id, TaskGraphStepStatus::Failed, cx);
+            let progress = inventory.task_graph_finished(&run.id, cx).unwrap();
+            assert!(!progress.is_running());
+            assert_eq!(progress.succeeded_steps(), 1);
+            assert_eq!(
+                progress.failed_step().map(|step| step.label.as_str()),
+                Some("lint")
+            );
+            assert_eq!(
+                progress.steps.last().map(|step| step.status),
+                Some(TaskGraphStepStatus::Skipped)
+            );
+        });
+    }
 }
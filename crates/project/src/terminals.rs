@@ -1,21 +1,82 @@
 use crate::Project;
-use collections::HashMap;
-use gpui::{AnyWindowHandle, Context, Entity, Model, ModelContext, WeakModel};
+use anyhow::{anyhow, Context as _, Result};
+use client::Client;
+use collections::{HashMap, HashSet};
+use futures::{FutureExt, StreamExt};
+use gpui::{
+    AnyWindowHandle, AsyncAppContext, Context, Entity, Model, ModelContext, Subscription, Task,
+    WeakModel,
+};
+use language::{Diagnostic, DiagnosticEntry, PointUtf16, Unclipped};
+use lsp::{DiagnosticSeverity, LanguageServerId};
+use rpc::{proto, TypedEnvelope};
 use settings::Settings;
 use smol::channel::bounded;
-use std::path::{Path, PathBuf};
-use task::SpawnInTerminal;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use task::{Problem, ProblemMatcher, ProblemSeverity, SpawnInTerminal};
 use terminal::{
+    alacritty_terminal::{
+        index::{Column, Line, Point as AlacPoint},
+        term::{
+            cell::{Cell, Flags},
+            RenderableCursor, TermMode,
+        },
+        vte::ansi::{Color, CursorShape, NamedColor, Rgb},
+    },
     terminal_settings::{self, Shell, TerminalSettings, VenvSettingsContent},
-    TaskState, TaskStatus, Terminal, TerminalBuilder,
+    TaskState, TaskStatus, Terminal, TerminalBuilder, TerminalGridUpdate, TerminalLineUpdate,
 };
-use util::ResultExt;
+use util::{post_inc, ResultExt};
+
+/// How long the host waits after a shared terminal changes before sending the
+/// changes to guests, so that fast output is sent in batches.
+const SHARED_TERMINAL_UPDATE_INTERVAL: Duration = Duration::from_millis(30);
+
+/// How long to wait after a task's output changes before matching it with the task's problem matchers again.
+const TASK_PROBLEMS_MATCH_INTERVAL: Duration = Duration::from_millis(500);
 
 // #[cfg(target_os = "macos")]
 // use std::os::unix::ffi::OsStrExt;
 
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakModel<terminal::Terminal>>,
+    pub(crate) task_diagnostics: HashMap<TaskDiagnosticsKey, TaskDiagnostics>,
+    /// The local terminals shared with guests, by terminal id. Terminals are only shared
+    /// when the host asks to, and stop being shared when the project is unshared.
+    pub(crate) shared: HashMap<u64, SharedTerminal>,
+    /// The terminals shared by the host, by terminal id, when the project is remote.
+    pub(crate) remote: HashMap<u64, RemoteTerminal>,
+}
+
+pub(crate) struct SharedTerminal {
+    terminal: WeakModel<Terminal>,
+    /// The users who are allowed to write to the terminal.
+    writer_ids: HashSet<u64>,
+    _subscription: Subscription,
+    _send_updates: Task<()>,
+}
+
+pub(crate) struct RemoteTerminal {
+    terminal: Model<Terminal>,
+    _send_input: Task<()>,
+}
+
+/// Identifies a task across its runs, unlike its [`task::TaskId`], which changes with the context the task is resolved in.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct TaskDiagnosticsKey {
+    id_base: String,
+    label: String,
+}
+
+/// Diagnostics, produced by the problem matchers of a task's last run.
+/// Each task gets its own diagnostics server id, so that its diagnostics can be replaced independently.
+pub(crate) struct TaskDiagnostics {
+    server_id: LanguageServerId,
+    paths: HashSet<PathBuf>,
 }
 
 impl Project {
@@ -31,6 +92,24 @@ impl Project {
             "creating terminals as a guest is not supported yet"
         );
 
+        let task_problem_matchers = spawn_task.as_ref().and_then(|spawn_task| {
+            let key = TaskDiagnosticsKey {
+                id_base: spawn_task.id_base.clone(),
+                label: spawn_task.full_label.clone(),
+            };
+            // Diagnostics of the previous run are stale now, the new ones will come as this run goes.
+            self.clear_task_diagnostics(&key, cx);
+            let matchers = spawn_task
+                .problem_matcher
+                .as_ref()?
+                .matchers()
+                .with_context(|| {
+                    format!("resolving problem matchers for task {:?}", spawn_task.label)
+                })
+                .log_err()?;
+            Some((key, matchers))
+        });
+
         let is_terminal = spawn_task.is_none();
         let settings = TerminalSettings::get_global(cx);
         let python_settings = settings.detect_venv.clone();
@@ -82,24 +161,17 @@ impl Project {
         )
         .map(|builder| {
             let terminal_handle = cx.new_model(|cx| builder.subscribe(cx));
+            self.register_local_terminal(&terminal_handle, cx);
 
-            self.terminals
-                .local_handles
-                .push(terminal_handle.downgrade());
-
-            let id = terminal_handle.entity_id();
-            cx.observe_release(&terminal_handle, move |project, _terminal, cx| {
-                let handles = &mut project.terminals.local_handles;
-
-                if let Some(index) = handles
-                    .iter()
-                    .position(|terminal| terminal.entity_id() == id)
-                {
-                    handles.remove(index);
-                    cx.notify();
-                }
-            })
-            .detach();
+            if let Some((key, matchers)) = task_problem_matchers {
+                self.match_task_problems(
+                    key,
+                    matchers,
+                    working_directory.clone(),
+                    &terminal_handle,
+                    cx,
+                );
+            }
 
             // if the terminal is not a task, activate full Python virtual environment
             if is_terminal {
@@ -122,6 +194,191 @@ impl Project {
         terminal
     }
 
+    fn register_local_terminal(&mut self, terminal: &Model<Terminal>, cx: &mut ModelContext<Self>) {
+        self.terminals.local_handles.push(terminal.downgrade());
+
+        let id = terminal.entity_id();
+        cx.observe_release(terminal, move |project, _terminal, cx| {
+            project.stop_sharing_terminal(id.as_u64());
+            let handles = &mut project.terminals.local_handles;
+
+            if let Some(index) = handles
+                .iter()
+                .position(|terminal| terminal.entity_id() == id)
+            {
+                handles.remove(index);
+                cx.notify();
+            }
+        })
+        .detach();
+    }
+
+    /// Creates a terminal without a process, whose input is sent on `input_tx`.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn create_fake_terminal(
+        &mut self,
+        input_tx: futures::channel::mpsc::UnboundedSender<Vec<u8>>,
+        cx: &mut ModelContext<Self>,
+    ) -> Model<Terminal> {
+        let builder = TerminalBuilder::new_fake(input_tx);
+        let terminal = cx.new_model(|cx| builder.subscribe(cx));
+        self.register_local_terminal(&terminal, cx);
+        terminal
+    }
+
+    /// Matches the task's output with the problem matchers whenever it changes, and once the task finishes,
+    /// reporting the problems found as diagnostics.
+    fn match_task_problems(
+        &mut self,
+        key: TaskDiagnosticsKey,
+        matchers: Vec<ProblemMatcher>,
+        working_directory: Option<PathBuf>,
+        terminal: &Model<Terminal>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let (output_changed_tx, output_changed_rx) = bounded(1);
+        let subscription = cx.subscribe(terminal, move |_, _, event, _| {
+            if let terminal::Event::Wakeup = event {
+                output_changed_tx.try_send(()).ok();
+            }
+        });
+        let terminal = terminal.downgrade();
+        let matchers = Arc::new(matchers);
+        cx.spawn(|project, mut cx| async move {
+            let _subscription = subscription;
+            let task_finished = terminal
+                .update(&mut cx, |terminal, cx| terminal.wait_for_completed_task(cx))
+                .ok()?;
+            let mut task_finished = task_finished.fuse();
+            loop {
+                let finished = futures::select_biased! {
+                    _ = task_finished => true,
+                    output_changed = output_changed_rx.recv().fuse() => {
+                        output_changed.ok()?;
+                        cx.background_executor()
+                            .timer(TASK_PROBLEMS_MATCH_INTERVAL)
+                            .await;
+                        false
+                    }
+                };
+                let output = terminal
+                    .update(&mut cx, |terminal, _| terminal.text_contents())
+                    .ok()?;
+                let matchers = matchers.clone();
+                let problems = cx
+                    .background_executor()
+                    .spawn(async move {
+                        matchers
+                            .iter()
+                            .flat_map(|matcher| matcher.match_output(&output))
+                            .collect::<Vec<_>>()
+                    })
+                    .await;
+                project
+                    .update(&mut cx, |project, cx| {
+                        project.update_task_diagnostics(
+                            &key,
+                            problems,
+                            working_directory.as_deref(),
+                            cx,
+                        )
+                    })
+                    .ok()?;
+                if finished {
+                    return Some(());
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn clear_task_diagnostics(&mut self, key: &TaskDiagnosticsKey, cx: &mut ModelContext<Self>) {
+        let Some(task_diagnostics) = self.terminals.task_diagnostics.get_mut(key) else {
+            return;
+        };
+        let server_id = task_diagnostics.server_id;
+        for abs_path in std::mem::take(&mut task_diagnostics.paths) {
+            self.update_diagnostic_entries(server_id, abs_path, None, Vec::new(), cx)
+                .log_err();
+        }
+    }
+
+    fn update_task_diagnostics(
+        &mut self,
+        key: &TaskDiagnosticsKey,
+        problems: Vec<Problem>,
+        working_directory: Option<&Path>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.clear_task_diagnostics(key, cx);
+        if problems.is_empty() {
+            return;
+        }
+
+        let mut diagnostics_by_path = HashMap::<PathBuf, Vec<_>>::default();
+        for problem in problems {
+            let path = Path::new(&problem.path);
+            let abs_path = match working_directory {
+                Some(working_directory) if path.is_relative() => working_directory.join(path),
+                _ => path.to_path_buf(),
+            };
+            let start = PointUtf16::new(
+                problem.line - 1,
+                problem.column.unwrap_or(1).saturating_sub(1),
+            );
+            let end = match (problem.end_line, problem.end_column) {
+                (Some(end_line), Some(end_column)) => {
+                    PointUtf16::new(end_line.max(1) - 1, end_column.saturating_sub(1))
+                }
+                (Some(end_line), None) => PointUtf16::new(end_line.max(1) - 1, u32::MAX),
+                (None, Some(end_column)) => {
+                    PointUtf16::new(start.row, end_column.saturating_sub(1))
+                }
+                (None, None) => start,
+            };
+            diagnostics_by_path
+                .entry(abs_path)
+                .or_default()
+                .push(DiagnosticEntry {
+                    range: Unclipped(start)..Unclipped(end.max(start)),
+                    diagnostic: Diagnostic {
+                        source: Some(problem.source.unwrap_or_else(|| key.label.clone())),
+                        code: problem.code,
+                        severity: match problem.severity {
+                            ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                            ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                            ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+                            ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+                        },
+                        message: problem.message,
+                        group_id: post_inc(&mut self.next_diagnostic_group_id),
+                        is_primary: true,
+                        is_disk_based: true,
+                        is_unnecessary: false,
+                    },
+                });
+        }
+
+        let server_id = match self.terminals.task_diagnostics.get(key) {
+            Some(task_diagnostics) => task_diagnostics.server_id,
+            None => self.languages.next_language_server_id(),
+        };
+        let mut paths = HashSet::default();
+        for (abs_path, diagnostics) in diagnostics_by_path {
+            if self
+                .update_diagnostic_entries(server_id, abs_path.clone(), None, diagnostics, cx)
+                .with_context(|| format!("reporting problems of task {:?}", key.label))
+                .log_err()
+                .is_some()
+            {
+                paths.insert(abs_path);
+            }
+        }
+        self.terminals
+            .task_diagnostics
+            .insert(key.clone(), TaskDiagnostics { server_id, paths });
+    }
+
     pub fn find_activate_script_path(
         &mut self,
         settings: &VenvSettingsContent,
@@ -212,6 +469,493 @@ impl Project {
     pub fn local_terminal_handles(&self) -> &Vec<WeakModel<terminal::Terminal>> {
         &self.terminals.local_handles
     }
+
+    /// Returns the id under which the given terminal is shared with the project's collaborators.
+    pub fn shared_terminal_id(&self, terminal: &Model<Terminal>) -> Option<u64> {
+        let terminal_id = terminal.entity_id().as_u64();
+        if self.terminals.shared.contains_key(&terminal_id) {
+            return Some(terminal_id);
+        }
+        self.terminals
+            .remote
+            .iter()
+            .find(|(_, remote)| remote.terminal == *terminal)
+            .map(|(terminal_id, _)| *terminal_id)
+    }
+
+    /// Returns the terminal shared under the given id, by this project or by its host.
+    pub fn shared_terminal(&self, terminal_id: u64) -> Option<Model<Terminal>> {
+        if let Some(remote) = self.terminals.remote.get(&terminal_id) {
+            return Some(remote.terminal.clone());
+        }
+        self.terminals
+            .shared
+            .get(&terminal_id)
+            .and_then(|shared| shared.terminal.upgrade())
+    }
+
+    /// Returns the ids of the users allowed to write to the given shared terminal.
+    pub fn shared_terminal_writers(&self, terminal: &Model<Terminal>) -> Option<&HashSet<u64>> {
+        self.terminals
+            .shared
+            .get(&terminal.entity_id().as_u64())
+            .map(|shared| &shared.writer_ids)
+    }
+
+    /// Allows or forbids a collaborator to write to a terminal shared by this project.
+    /// Guests can only watch a shared terminal until they are allowed to write to it.
+    pub fn set_shared_terminal_writer(
+        &mut self,
+        terminal: &Model<Terminal>,
+        user_id: u64,
+        can_write: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let terminal_id = terminal.entity_id().as_u64();
+        let Some(shared) = self.terminals.shared.get_mut(&terminal_id) else {
+            return;
+        };
+        let changed = if can_write {
+            shared.writer_ids.insert(user_id)
+        } else {
+            shared.writer_ids.remove(&user_id)
+        };
+        if changed {
+            self.send_shared_terminal_update(terminal_id, true, cx);
+            cx.notify();
+        }
+    }
+
+    /// Returns whether the given terminal is shared with the project's collaborators.
+    pub fn is_terminal_shared(&self, terminal: &Model<Terminal>) -> bool {
+        self.terminals
+            .shared
+            .contains_key(&terminal.entity_id().as_u64())
+    }
+
+    /// Shares one of this project's terminals with its collaborators, who can watch it
+    /// but can't write to it until [`Self::set_shared_terminal_writer`] allows them to.
+    pub fn share_terminal(
+        &mut self,
+        terminal: &Model<Terminal>,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        anyhow::ensure!(
+            self.is_local() && self.is_shared(),
+            "only the host of a shared project can share terminals"
+        );
+        let terminal_id = terminal.entity_id().as_u64();
+        anyhow::ensure!(
+            self.terminals
+                .local_handles
+                .iter()
+                .any(|handle| handle.entity_id().as_u64() == terminal_id),
+            "terminal {terminal_id} does not belong to this project"
+        );
+        if !self.terminals.shared.contains_key(&terminal_id) {
+            self.start_sharing_terminal(terminal, cx);
+            cx.notify();
+        }
+        Ok(())
+    }
+
+    /// Stops sharing a terminal with the project's collaborators, closing it for them.
+    pub fn unshare_terminal(&mut self, terminal: &Model<Terminal>, cx: &mut ModelContext<Self>) {
+        self.stop_sharing_terminal(terminal.entity_id().as_u64());
+        cx.notify();
+    }
+
+    fn start_sharing_terminal(&mut self, terminal: &Model<Terminal>, cx: &mut ModelContext<Self>) {
+        let terminal_id = terminal.entity_id().as_u64();
+        let (updates_tx, updates_rx) = bounded(1);
+        let subscription = cx.subscribe(terminal, move |_, _, event, _| match event {
+            terminal::Event::Wakeup | terminal::Event::TitleChanged => {
+                updates_tx.try_send(()).ok();
+            }
+            _ => {}
+        });
+        let send_updates = cx.spawn(|this, mut cx| async move {
+            while updates_rx.recv().await.is_ok() {
+                cx.background_executor()
+                    .timer(SHARED_TERMINAL_UPDATE_INTERVAL)
+                    .await;
+                if this
+                    .update(&mut cx, |this, cx| {
+                        this.send_shared_terminal_update(terminal_id, false, cx)
+                    })
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        terminal.update(cx, |terminal, _| terminal.reset_grid_update());
+        self.terminals.shared.insert(
+            terminal_id,
+            SharedTerminal {
+                terminal: terminal.downgrade(),
+                writer_ids: HashSet::default(),
+                _subscription: subscription,
+                _send_updates: send_updates,
+            },
+        );
+        self.send_shared_terminal_update(terminal_id, true, cx);
+    }
+
+    fn stop_sharing_terminal(&mut self, terminal_id: u64) {
+        if self.terminals.shared.remove(&terminal_id).is_none() {
+            return;
+        }
+        if let Some(project_id) = self.remote_id() {
+            self.client
+                .send(proto::CloseSharedTerminal {
+                    project_id,
+                    terminal_id,
+                })
+                .log_err();
+        }
+    }
+
+    fn send_shared_terminal_update(
+        &mut self,
+        terminal_id: u64,
+        force: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(project_id) = self.remote_id() else {
+            return;
+        };
+        let Some(shared) = self.terminals.shared.get(&terminal_id) else {
+            return;
+        };
+        let Some(terminal) = shared.terminal.upgrade() else {
+            return;
+        };
+        let Some(update) = terminal.update(cx, |terminal, _| terminal.take_grid_update(force))
+        else {
+            return;
+        };
+        let writer_ids = shared.writer_ids.iter().copied().collect();
+        self.client
+            .send(serialize_grid_update(
+                project_id,
+                terminal_id,
+                writer_ids,
+                update,
+            ))
+            .log_err();
+    }
+
+    /// Asks the host to send the whole grid of each of its shared terminals.
+    pub(crate) fn synchronize_shared_terminals(&mut self, cx: &mut ModelContext<Self>) {
+        let Some(project_id) = self.remote_id() else {
+            return;
+        };
+        let request = self
+            .client
+            .request(proto::SynchronizeSharedTerminals { project_id });
+        cx.spawn(|_, _| async move {
+            request.await?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn create_remote_terminal(
+        &mut self,
+        project_id: u64,
+        terminal_id: u64,
+        title: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Model<Terminal> {
+        let (input_tx, mut input_rx) = futures::channel::mpsc::unbounded();
+        let builder = TerminalBuilder::new_remote(
+            title,
+            TerminalSettings::get_global(cx).max_scroll_history_lines,
+            input_tx,
+        );
+        let terminal = cx.new_model(|cx| builder.subscribe(cx));
+        let client = self.client.clone();
+        let send_input = cx.spawn(|_, _| async move {
+            while let Some(input) = input_rx.next().await {
+                client
+                    .request(proto::SharedTerminalInput {
+                        project_id,
+                        terminal_id,
+                        input,
+                    })
+                    .await
+                    .log_err();
+            }
+        });
+        self.terminals.remote.insert(
+            terminal_id,
+            RemoteTerminal {
+                terminal: terminal.clone(),
+                _send_input: send_input,
+            },
+        );
+        terminal
+    }
+
+    pub(crate) async fn handle_update_shared_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateSharedTerminal>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            let message = envelope.payload;
+            let can_write = this
+                .client
+                .user_id()
+                .map_or(false, |user_id| message.writer_ids.contains(&user_id));
+            let terminal = match this.terminals.remote.get(&message.terminal_id) {
+                Some(remote) => remote.terminal.clone(),
+                // Changes to a terminal can only be applied after its whole grid is known.
+                None if message.is_full => this.create_remote_terminal(
+                    message.project_id,
+                    message.terminal_id,
+                    message.title.clone(),
+                    cx,
+                ),
+                None => return,
+            };
+            terminal.update(cx, |terminal, cx| {
+                terminal.set_remote_can_write(can_write, cx);
+                terminal.apply_grid_update(deserialize_grid_update(message), cx);
+            });
+        })
+    }
+
+    pub(crate) async fn handle_close_shared_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::CloseSharedTerminal>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            if let Some(remote) = this.terminals.remote.remove(&envelope.payload.terminal_id) {
+                remote
+                    .terminal
+                    .update(cx, |_, cx| cx.emit(terminal::Event::CloseTerminal));
+            }
+        })
+    }
+
+    pub(crate) async fn handle_synchronize_shared_terminals(
+        this: Model<Self>,
+        _: TypedEnvelope<proto::SynchronizeSharedTerminals>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |this, cx| {
+            let terminal_ids = this.terminals.shared.keys().copied().collect::<Vec<_>>();
+            for terminal_id in terminal_ids {
+                if let Some(terminal) = this.shared_terminal(terminal_id) {
+                    terminal.update(cx, |terminal, _| terminal.reset_grid_update());
+                }
+                this.send_shared_terminal_update(terminal_id, true, cx);
+            }
+        })?;
+        Ok(proto::Ack {})
+    }
+
+    pub(crate) async fn handle_shared_terminal_input(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::SharedTerminalInput>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let sender_id = envelope.original_sender_id()?;
+        this.update(&mut cx, |this, cx| {
+            let user_id = this
+                .collaborators
+                .get(&sender_id)
+                .map(|collaborator| collaborator.user_id)
+                .ok_or_else(|| anyhow!("unknown collaborator {sender_id:?}"))?;
+            let shared = this
+                .terminals
+                .shared
+                .get(&envelope.payload.terminal_id)
+                .ok_or_else(|| anyhow!("unknown terminal id {}", envelope.payload.terminal_id))?;
+            anyhow::ensure!(
+                shared.writer_ids.contains(&user_id),
+                "user {user_id} is not allowed to write to terminal {}",
+                envelope.payload.terminal_id
+            );
+            let terminal = shared
+                .terminal
+                .upgrade()
+                .context("shared terminal was closed")?;
+            terminal.update(cx, |terminal, _| {
+                terminal.input_bytes(envelope.payload.input)
+            });
+            Ok(proto::Ack {})
+        })?
+    }
+}
+
+// The named colors, in the order of their discriminants.
+const NAMED_COLORS: [NamedColor; 29] = [
+    NamedColor::Black,
+    NamedColor::Red,
+    NamedColor::Green,
+    NamedColor::Yellow,
+    NamedColor::Blue,
+    NamedColor::Magenta,
+    NamedColor::Cyan,
+    NamedColor::White,
+    NamedColor::BrightBlack,
+    NamedColor::BrightRed,
+    NamedColor::BrightGreen,
+    NamedColor::BrightYellow,
+    NamedColor::BrightBlue,
+    NamedColor::BrightMagenta,
+    NamedColor::BrightCyan,
+    NamedColor::BrightWhite,
+    NamedColor::Foreground,
+    NamedColor::Background,
+    NamedColor::Cursor,
+    NamedColor::DimBlack,
+    NamedColor::DimRed,
+    NamedColor::DimGreen,
+    NamedColor::DimYellow,
+    NamedColor::DimBlue,
+    NamedColor::DimMagenta,
+    NamedColor::DimCyan,
+    NamedColor::DimWhite,
+    NamedColor::BrightForeground,
+    NamedColor::DimForeground,
+];
+
+const NAMED_COLOR_TAG: u32 = 0;
+const SPEC_COLOR_TAG: u32 = 1;
+const INDEXED_COLOR_TAG: u32 = 2;
+
+/// Colors are sent as a tag in the top byte, and the color's value in the rest.
+fn serialize_color(color: Color) -> u32 {
+    match color {
+        Color::Named(named) => (NAMED_COLOR_TAG << 24) | named as u32,
+        Color::Spec(Rgb { r, g, b }) => {
+            (SPEC_COLOR_TAG << 24) | ((r as u32) << 16) | ((g as u32) << 8) | b as u32
+        }
+        Color::Indexed(index) => (INDEXED_COLOR_TAG << 24) | index as u32,
+    }
+}
+
+fn deserialize_color(color: u32) -> Color {
+    let value = color & 0xff_ffff;
+    match color >> 24 {
+        SPEC_COLOR_TAG => Color::Spec(Rgb {
+            r: (value >> 16) as u8,
+            g: (value >> 8) as u8,
+            b: value as u8,
+        }),
+        INDEXED_COLOR_TAG => Color::Indexed(value as u8),
+        _ => Color::Named(
+            NAMED_COLORS
+                .into_iter()
+                .find(|named| *named as u32 == value)
+                .unwrap_or(NamedColor::Foreground),
+        ),
+    }
+}
+
+fn serialize_cursor_shape(shape: CursorShape) -> u32 {
+    match shape {
+        CursorShape::Block => 0,
+        CursorShape::Underline => 1,
+        CursorShape::Beam => 2,
+        CursorShape::HollowBlock => 3,
+        CursorShape::Hidden => 4,
+    }
+}
+
+fn deserialize_cursor_shape(shape: u32) -> CursorShape {
+    match shape {
+        0 => CursorShape::Block,
+        1 => CursorShape::Underline,
+        2 => CursorShape::Beam,
+        3 => CursorShape::HollowBlock,
+        _ => CursorShape::Hidden,
+    }
+}
+
+fn serialize_grid_update(
+    project_id: u64,
+    terminal_id: u64,
+    writer_ids: Vec<u64>,
+    update: TerminalGridUpdate,
+) -> proto::UpdateSharedTerminal {
+    proto::UpdateSharedTerminal {
+        project_id,
+        terminal_id,
+        title: update.title,
+        writer_ids,
+        is_full: update.is_full,
+        screen_lines: update.screen_lines as u32,
+        columns: update.columns as u32,
+        scrolled_lines: update.scrolled_lines as u32,
+        lines: update
+            .lines
+            .into_iter()
+            .map(|line| proto::TerminalLine {
+                line: line.line,
+                cells: line
+                    .cells
+                    .into_iter()
+                    .map(|cell| proto::TerminalCell {
+                        character: cell.c as u32,
+                        foreground: serialize_color(cell.fg),
+                        background: serialize_color(cell.bg),
+                        flags: cell.flags.bits() as u32,
+                    })
+                    .collect(),
+            })
+            .collect(),
+        cursor: Some(proto::TerminalCursor {
+            line: update.cursor.point.line.0,
+            column: update.cursor.point.column.0 as u32,
+            shape: serialize_cursor_shape(update.cursor.shape),
+        }),
+        mode: update.mode.bits(),
+    }
+}
+
+fn deserialize_grid_update(message: proto::UpdateSharedTerminal) -> TerminalGridUpdate {
+    let cursor = message.cursor.unwrap_or_default();
+    TerminalGridUpdate {
+        is_full: message.is_full,
+        screen_lines: message.screen_lines as usize,
+        columns: message.columns as usize,
+        scrolled_lines: message.scrolled_lines as usize,
+        lines: message
+            .lines
+            .into_iter()
+            .map(|line| TerminalLineUpdate {
+                line: line.line,
+                cells: line
+                    .cells
+                    .into_iter()
+                    .map(|cell| Cell {
+                        c: char::from_u32(cell.character).unwrap_or(' '),
+                        fg: deserialize_color(cell.foreground),
+                        bg: deserialize_color(cell.background),
+                        flags: Flags::from_bits_truncate(cell.flags as u16),
+                        ..Cell::default()
+                    })
+                    .collect(),
+            })
+            .collect(),
+        cursor: RenderableCursor {
+            shape: deserialize_cursor_shape(cursor.shape),
+            point: AlacPoint::new(Line(cursor.line), Column(cursor.column as usize)),
+        },
+        mode: TermMode::from_bits_truncate(message.mode),
+        title: message.title,
+    }
 }
 
 // TODO: Add a few tests for adding and removing terminal tabs
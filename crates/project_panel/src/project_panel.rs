@@ -1,5 +1,5 @@
 mod project_panel_settings;
-use client::{ErrorCode, ErrorExt};
+use client::{proto, ErrorCode, ErrorExt};
 use settings::{Settings, SettingsStore};
 
 use db::kvp::KEY_VALUE_STORE;
@@ -187,10 +187,21 @@ struct DraggedProjectEntryView {
 impl ProjectPanel {
     fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
         let project = workspace.project().clone();
+        let workspace_handle = cx.view().clone();
         let project_panel = cx.new_view(|cx: &mut ViewContext<Self>| {
             let focus_handle = cx.focus_handle();
             cx.on_focus(&focus_handle, Self::focus_in).detach();
 
+            cx.subscribe(&workspace_handle, |this, _, event, cx| {
+                if let workspace::Event::LeaderPanelUpdated(proto::UpdatePanel {
+                    variant: Some(proto::update_panel::Variant::ProjectPanel(state)),
+                }) = event
+                {
+                    this.apply_leader_state(state, cx);
+                }
+            })
+            .detach();
+
             cx.subscribe(&project, |this, project, event, cx| match event {
                 project::Event::ActiveEntryChanged(Some(entry_id)) => {
                     if ProjectPanelSettings::get_global(cx).auto_reveal_entries {
@@ -277,6 +288,12 @@ impl ProjectPanel {
             this
         });
 
+        cx.observe(&project_panel, |workspace, project_panel, cx| {
+            let state = project_panel.read(cx).follower_state();
+            workspace.update_followers_panel(proto::update_panel::Variant::ProjectPanel(state), cx);
+        })
+        .detach();
+
         cx.subscribe(&project_panel, {
             let project_panel = project_panel.downgrade();
             move |workspace, _, event, cx| match event {
@@ -1068,6 +1085,64 @@ impl ProjectPanel {
         }
     }
 
+    fn follower_state(&self) -> proto::update_panel::ProjectPanel {
+        let mut expanded_entries = self
+            .expanded_dir_ids
+            .iter()
+            .map(
+                |(worktree_id, entry_ids)| proto::update_panel::ExpandedEntries {
+                    worktree_id: worktree_id.to_proto(),
+                    entry_ids: entry_ids.iter().map(|id| id.to_proto()).collect(),
+                },
+            )
+            .collect::<Vec<_>>();
+        expanded_entries.sort_unstable_by_key(|entries| entries.worktree_id);
+
+        let selection = self
+            .selection
+            .filter(|selection| selection.entry_id != NEW_ENTRY_ID);
+        proto::update_panel::ProjectPanel {
+            expanded_entries,
+            selected_worktree_id: selection.map(|selection| selection.worktree_id.to_proto()),
+            selected_entry_id: selection.map(|selection| selection.entry_id.to_proto()),
+        }
+    }
+
+    /// Mirrors the expanded directories and selection of the collaborator we follow.
+    fn apply_leader_state(
+        &mut self,
+        state: &proto::update_panel::ProjectPanel,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.edit_state.is_some() {
+            return;
+        }
+
+        self.expanded_dir_ids = state
+            .expanded_entries
+            .iter()
+            .map(|entries| {
+                let mut entry_ids = entries
+                    .entry_ids
+                    .iter()
+                    .copied()
+                    .map(ProjectEntryId::from_proto)
+                    .collect::<Vec<_>>();
+                entry_ids.sort_unstable();
+                (WorktreeId::from_proto(entries.worktree_id), entry_ids)
+            })
+            .collect();
+        self.selection = state.selected_worktree_id.zip(state.selected_entry_id).map(
+            |(worktree_id, entry_id)| Selection {
+                worktree_id: WorktreeId::from_proto(worktree_id),
+                entry_id: ProjectEntryId::from_proto(entry_id),
+            },
+        );
+        self.update_visible_entries(None, cx);
+        self.autoscroll(cx);
+        cx.notify();
+    }
+
     fn autoscroll(&mut self, cx: &mut ViewContext<Self>) {
         if let Some((_, _, index)) = self.selection.and_then(|s| self.index_for_selection(s)) {
             self.scroll_handle.scroll_to_item(index);
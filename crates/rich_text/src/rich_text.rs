@@ -41,6 +41,7 @@ pub struct RichText {
     pub custom_ranges: Vec<Range<usize>>,
     custom_ranges_tooltip_fn:
         Option<Arc<dyn Fn(usize, Range<usize>, &mut WindowContext) -> Option<AnyView>>>,
+    link_click_fn: Option<Arc<dyn Fn(&str, &mut WindowContext)>>,
 }
 
 impl Default for RichText {
@@ -52,6 +53,7 @@ impl Default for RichText {
             link_urls: Arc::from([]),
             custom_ranges: Vec::new(),
             custom_ranges_tooltip_fn: None,
+            link_click_fn: None,
         }
     }
 }
@@ -93,6 +95,7 @@ impl RichText {
             highlights,
             custom_ranges: Vec::new(),
             custom_ranges_tooltip_fn: None,
+            link_click_fn: None,
         }
     }
 
@@ -103,6 +106,11 @@ impl RichText {
         self.custom_ranges_tooltip_fn = Some(Arc::new(f));
     }
 
+    /// Handles clicks on links that aren't web URLs, which are otherwise ignored.
+    pub fn set_link_click_handler(&mut self, f: impl Fn(&str, &mut WindowContext) + 'static) {
+        self.link_click_fn = Some(Arc::new(f));
+    }
+
     pub fn element(&self, id: ElementId, cx: &mut WindowContext) -> AnyElement {
         let theme = cx.theme();
         let code_background = theme.colors().surface_background;
@@ -156,10 +164,13 @@ impl RichText {
         )
         .on_click(self.link_ranges.clone(), {
             let link_urls = self.link_urls.clone();
+            let link_click_fn = self.link_click_fn.clone();
             move |ix, cx| {
                 let url = &link_urls[ix];
                 if url.starts_with("http") {
                     cx.open_url(url);
+                } else if let Some(f) = &link_click_fn {
+                    f(url, cx);
                 }
             }
         })
@@ -33,6 +33,11 @@
 ///
 use crate::proto;
 pub use proto::ErrorCode;
+use std::time::Duration;
+
+/// The tag on a [`ErrorCode::RateLimitExceeded`] error that holds the number of
+/// milliseconds until the request can be retried.
+pub const RETRY_AFTER_MS_TAG: &str = "retry_after_ms";
 
 /// ErrorCodeExt provides some helpers for structured error handling.
 ///
@@ -82,6 +87,15 @@ pub trait ErrorExt {
     fn to_proto(&self) -> proto::Error;
     /// Clones the error and turns into an [anyhow::Error].
     fn cloned(&self) -> anyhow::Error;
+
+    /// retry_after() returns how long to wait before retrying, if the request was rate limited.
+    fn retry_after(&self) -> Option<Duration> {
+        if self.error_code() != proto::ErrorCode::RateLimitExceeded {
+            return None;
+        }
+        let retry_after_ms = self.error_tag(RETRY_AFTER_MS_TAG)?.parse().ok()?;
+        Some(Duration::from_millis(retry_after_ms))
+    }
 }
 
 impl ErrorExt for anyhow::Error {
@@ -157,6 +171,17 @@ impl RpcError {
         }
         .into()
     }
+
+    /// rate_limit_exceeded() returns an error telling the client to wait for
+    /// `retry_after` before sending the request again.
+    pub fn rate_limit_exceeded(retry_after: Duration) -> RpcError {
+        let retry_after_ms = retry_after.as_millis().max(1);
+        proto::ErrorCode::RateLimitExceeded
+            .message(format!(
+                "rate limit exceeded, retry after {retry_after_ms}ms"
+            ))
+            .with_tag(RETRY_AFTER_MS_TAG, &retry_after_ms.to_string())
+    }
 }
 
 impl ErrorCodeExt for RpcError {
@@ -38,6 +38,13 @@ pub enum Notification {
         sender_id: u64,
         channel_id: u64,
     },
+    ReviewCommentMention {
+        #[serde(rename = "entity_id")]
+        comment_id: u64,
+        sender_id: u64,
+        project_id: u64,
+        path: String,
+    },
 }
 
 impl Notification {
@@ -96,6 +103,12 @@ mod tests {
                 channel_id: 30,
                 message_id: 1,
             },
+            Notification::ReviewCommentMention {
+                sender_id: 200,
+                project_id: 4,
+                comment_id: 2,
+                path: "src/main.rs".into(),
+            },
         ] {
             let message = notification.to_proto();
             let deserialized = Notification::from_proto(&message).unwrap();
@@ -150,6 +150,7 @@ messages!(
     (CancelCall, Foreground),
     (ChannelMessageSent, Foreground),
     (ChannelMessageUpdate, Foreground),
+    (ChannelMessageReactionsUpdated, Foreground),
     (CompleteWithLanguageModel, Background),
     (ComputeEmbeddings, Background),
     (ComputeEmbeddingsResponse, Background),
@@ -178,11 +179,16 @@ messages!(
     (FuzzySearchUsers, Foreground),
     (GetCachedEmbeddings, Background),
     (GetCachedEmbeddingsResponse, Background),
+    (GetChannelBufferHistory, Background),
+    (GetChannelBufferHistoryResponse, Background),
+    (GetChannelBufferVersion, Background),
+    (GetChannelBufferVersionResponse, Background),
     (GetChannelMembers, Foreground),
     (GetChannelMembersResponse, Foreground),
     (GetChannelMessages, Background),
     (GetChannelMessagesById, Background),
     (GetChannelMessagesResponse, Background),
+    (GetChannelMessageThread, Background),
     (GetCodeActions, Background),
     (GetCodeActionsResponse, Background),
     (GetCompletions, Background),
@@ -251,6 +257,7 @@ messages!(
     (RemoveChannelMember, Foreground),
     (RemoveChannelMessage, Foreground),
     (UpdateChannelMessage, Foreground),
+    (ToggleChannelMessageReaction, Foreground),
     (RemoveContact, Foreground),
     (RemoveProjectCollaborator, Foreground),
     (RenameChannel, Foreground),
@@ -269,6 +276,7 @@ messages!(
     (SetChannelVisibility, Foreground),
     (SearchProject, Background),
     (SearchProjectResponse, Background),
+    (SearchChannelMessages, Background),
     (SendChannelMessage, Background),
     (SendChannelMessageResponse, Background),
     (ShareProject, Foreground),
@@ -320,7 +328,18 @@ messages!(
     (RemoteProjectsUpdate, Foreground),
     (ValidateRemoteProjectRequest, Background),
     (DeleteDevServer, Foreground),
-    (OpenNewBuffer, Foreground)
+    (OpenNewBuffer, Foreground),
+    (CreateReviewComment, Foreground),
+    (CreateReviewCommentResponse, Foreground),
+    (ResolveReviewThread, Foreground),
+    (GetReviewComments, Foreground),
+    (GetReviewCommentsResponse, Foreground),
+    (UpdateReviewComments, Foreground),
+    (UpdateSharedTerminal, Foreground),
+    (CloseSharedTerminal, Foreground),
+    (SynchronizeSharedTerminals, Foreground),
+    (SharedTerminalInput, Foreground),
+    (SetRoomPresenter, Foreground)
 );
 
 request_messages!(
@@ -346,9 +365,12 @@ request_messages!(
     (FormatBuffers, FormatBuffersResponse),
     (FuzzySearchUsers, UsersResponse),
     (GetCachedEmbeddings, GetCachedEmbeddingsResponse),
+    (GetChannelBufferHistory, GetChannelBufferHistoryResponse),
+    (GetChannelBufferVersion, GetChannelBufferVersionResponse),
     (GetChannelMembers, GetChannelMembersResponse),
     (GetChannelMessages, GetChannelMessagesResponse),
     (GetChannelMessagesById, GetChannelMessagesResponse),
+    (GetChannelMessageThread, GetChannelMessagesResponse),
     (GetCodeActions, GetCodeActionsResponse),
     (GetCompletions, GetCompletionsResponse),
     (GetDefinition, GetDefinitionResponse),
@@ -389,6 +411,7 @@ request_messages!(
     (RemoveChannelMember, Ack),
     (RemoveChannelMessage, Ack),
     (UpdateChannelMessage, Ack),
+    (ToggleChannelMessageReaction, Ack),
     (RemoveContact, Ack),
     (RenameChannel, RenameChannelResponse),
     (RenameProjectEntry, ProjectEntryResponse),
@@ -402,6 +425,7 @@ request_messages!(
     (RespondToContactRequest, Ack),
     (SaveBuffer, BufferSaved),
     (SearchProject, SearchProjectResponse),
+    (SearchChannelMessages, GetChannelMessagesResponse),
     (SendChannelMessage, SendChannelMessageResponse),
     (SetChannelMemberRole, Ack),
     (SetChannelVisibility, Ack),
@@ -425,6 +449,12 @@ request_messages!(
     (ValidateRemoteProjectRequest, Ack),
     (MultiLspQuery, MultiLspQueryResponse),
     (DeleteDevServer, Ack),
+    (CreateReviewComment, CreateReviewCommentResponse),
+    (ResolveReviewThread, Ack),
+    (GetReviewComments, GetReviewCommentsResponse),
+    (SynchronizeSharedTerminals, Ack),
+    (SharedTerminalInput, Ack),
+    (SetRoomPresenter, Ack),
 );
 
 entity_messages!(
@@ -482,12 +512,21 @@ entity_messages!(
     UpdateWorktree,
     UpdateWorktreeSettings,
     LspExtExpandMacro,
+    CreateReviewComment,
+    ResolveReviewThread,
+    GetReviewComments,
+    UpdateReviewComments,
+    UpdateSharedTerminal,
+    CloseSharedTerminal,
+    SynchronizeSharedTerminals,
+    SharedTerminalInput,
 );
 
 entity_messages!(
     {channel_id, Channel},
     ChannelMessageSent,
     ChannelMessageUpdate,
+    ChannelMessageReactionsUpdated,
     RemoveChannelMessage,
     UpdateChannelMessage,
     UpdateChannelBuffer,
@@ -14,3 +14,10 @@ pub use peer::*;
 mod macros;
 
 pub const PROTOCOL_VERSION: u32 = 68;
+
+/// The longest chat message, review comment or message search query that the server accepts.
+pub const MAX_MESSAGE_LEN: usize = 1024;
+
+/// The longest text a review thread can be anchored to. Threads on longer ranges
+/// are found again by their position alone.
+pub const MAX_REVIEW_ANCHOR_TEXT_LEN: usize = 4096;
@@ -1,12 +1,13 @@
 use crate::{
+    replace_preview::{self, ReplacePreview},
     FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions,
-    SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex,
-    ToggleReplace, ToggleWholeWord,
+    SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleGitChanges, ToggleIncludeIgnored,
+    TogglePreserveCase, ToggleRegex, ToggleReplace, ToggleStructural, ToggleWholeWord,
 };
-use anyhow::Context as _;
+use anyhow::{anyhow, Context as _};
 use collections::{HashMap, HashSet};
 use editor::{
-    actions::SelectAll,
+    actions::{MoveDown, MoveUp, SelectAll},
     items::active_match_index,
     scroll::{Autoscroll, Axis},
     Anchor, Editor, EditorElement, EditorEvent, EditorStyle, MultiBuffer, MAX_TAB_TITLE_LEN,
@@ -18,8 +19,13 @@ use gpui::{
     SharedString, Styled, Subscription, Task, TextStyle, View, ViewContext, VisualContext,
     WeakModel, WeakView, WhiteSpace, WindowContext,
 };
-use menu::Confirm;
-use project::{search::SearchQuery, search_history::SearchHistoryCursor, Project, ProjectPath};
+use menu::{Cancel, Confirm};
+use project::{
+    search::{SearchFilters, SearchQuery},
+    search_history::SearchHistoryCursor,
+    Item as _, Project, ProjectPath,
+};
+use rpc::proto::{self, PeerId};
 use settings::Settings;
 use smol::stream::StreamExt;
 use std::{
@@ -27,6 +33,8 @@ use std::{
     mem,
     ops::{Not, Range},
     path::Path,
+    sync::Arc,
+    time::{Duration, SystemTime},
 };
 use theme::ThemeSettings;
 use ui::{
@@ -35,18 +43,27 @@ use ui::{
 };
 use util::paths::PathMatcher;
 use workspace::{
-    item::{BreadcrumbText, Item, ItemEvent, ItemHandle, TabContentParams},
+    item::{
+        BreadcrumbText, FollowEvent, FollowableItem, Item, ItemEvent, ItemHandle, TabContentParams,
+    },
     searchable::{Direction, SearchableItem, SearchableItemHandle},
     DeploySearch, ItemNavHistory, NewSearch, Pane, ToolbarItemEvent, ToolbarItemLocation,
-    ToolbarItemView, Workspace, WorkspaceId,
+    ToolbarItemView, ViewId, Workspace, WorkspaceId,
 };
 
 const MIN_INPUT_WIDTH_REMS: f32 = 15.;
 const MAX_INPUT_WIDTH_REMS: f32 = 30.;
+const MAX_QUERY_LINES: usize = 8;
 
 actions!(
     project_search,
-    [SearchInNew, ToggleFocus, NextField, ToggleFilters]
+    [
+        SearchInNew,
+        ToggleFocus,
+        NextField,
+        ToggleFilters,
+        SearchInResults
+    ]
 );
 
 #[derive(Default)]
@@ -56,6 +73,7 @@ impl Global for ActiveSettings {}
 
 pub fn init(cx: &mut AppContext) {
     cx.set_global(ActiveSettings::default());
+    workspace::register_followable_item::<ProjectSearchView>(cx);
     cx.observe_new_views(|workspace: &mut Workspace, _cx| {
         register_workspace_action(workspace, move |search_bar, _: &FocusSearch, cx| {
             search_bar.focus_search(cx);
@@ -72,6 +90,18 @@ pub fn init(cx: &mut AppContext) {
         register_workspace_action(workspace, move |search_bar, _: &ToggleRegex, cx| {
             search_bar.toggle_search_option(SearchOptions::REGEX, cx);
         });
+        register_workspace_action(workspace, move |search_bar, _: &ToggleStructural, cx| {
+            search_bar.toggle_search_option(SearchOptions::STRUCTURAL, cx);
+        });
+        register_workspace_action(workspace, move |search_bar, _: &ToggleGitChanges, cx| {
+            search_bar.toggle_search_option(SearchOptions::GIT_CHANGES, cx);
+        });
+        register_workspace_action(workspace, move |search_bar, _: &TogglePreserveCase, cx| {
+            search_bar.toggle_search_option(SearchOptions::PRESERVE_CASE, cx);
+        });
+        register_workspace_action(workspace, move |search_bar, _: &SearchInResults, cx| {
+            search_bar.search_in_results(cx);
+        });
         register_workspace_action(workspace, move |search_bar, action: &ToggleReplace, cx| {
             search_bar.toggle_replace(action, cx)
         });
@@ -132,6 +162,9 @@ enum InputPanel {
     Query,
     Exclude,
     Include,
+    FileTypes,
+    MaxFileSize,
+    ModifiedSince,
 }
 
 pub struct ProjectSearchView {
@@ -147,8 +180,20 @@ pub struct ProjectSearchView {
     query_editor_was_focused: bool,
     included_files_editor: View<Editor>,
     excluded_files_editor: View<Editor>,
+    file_types_editor: View<Editor>,
+    max_file_size_editor: View<Editor>,
+    modified_since_editor: View<Editor>,
+    /// When set, only these files are searched, narrowing down the results of a previous search.
+    within_paths: Option<Arc<HashSet<ProjectPath>>>,
+    /// The replacements to review before replacing all matches.
+    replace_preview: Option<ReplacePreview>,
     filters_enabled: bool,
     replace_enabled: bool,
+    remote_id: Option<ViewId>,
+    /// The id of the leader's search we last ran, when following.
+    leader_search_id: Option<u64>,
+    /// The match the leader has selected, applied once our results contain it.
+    leader_match_index: Option<usize>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -276,12 +321,82 @@ impl EventEmitter<ViewEvent> for ProjectSearchView {}
 
 impl Render for ProjectSearchView {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        if self.has_matches() {
+        if let Some(replace_preview) = self.replace_preview.as_ref() {
+            let included_count = replace_preview.included_count();
+            let replacement_count = replace_preview.replacement_count();
+            v_flex()
+                .key_context("ReplacePreview")
+                .flex_1()
+                .size_full()
+                .track_focus(&self.focus_handle)
+                .on_action(cx.listener(Self::toggle_replacement_excluded))
+                .on_action(cx.listener(|this, _: &ReplaceAll, cx| this.apply_replace_preview(cx)))
+                .on_action(cx.listener(|this, _: &Cancel, cx| this.dismiss_replace_preview(cx)))
+                .child(
+                    h_flex()
+                        .flex_none()
+                        .justify_between()
+                        .gap_2()
+                        .px_2()
+                        .py_1()
+                        .border_b_1()
+                        .border_color(cx.theme().colors().border)
+                        .child(
+                            Label::new(format!(
+                                "Replacing {included_count} of {replacement_count} matches"
+                            ))
+                            .size(LabelSize::Small),
+                        )
+                        .child(
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    Button::new("exclude-replacement", "Exclude Selected")
+                                        .on_click(cx.listener(|this, _, cx| {
+                                            this.toggle_replacement_excluded(
+                                                &replace_preview::ToggleExcluded,
+                                                cx,
+                                            )
+                                        }))
+                                        .tooltip(|cx| {
+                                            Tooltip::for_action(
+                                                "Exclude or include the selected matches",
+                                                &replace_preview::ToggleExcluded,
+                                                cx,
+                                            )
+                                        }),
+                                )
+                                .child(Button::new("cancel-replace-preview", "Cancel").on_click(
+                                    cx.listener(|this, _, cx| this.dismiss_replace_preview(cx)),
+                                ))
+                                .child(
+                                    Button::new("apply-replace-preview", "Replace")
+                                        .style(ButtonStyle::Filled)
+                                        .disabled(included_count == 0)
+                                        .on_click(
+                                            cx.listener(|this, _, cx| {
+                                                this.apply_replace_preview(cx)
+                                            }),
+                                        )
+                                        .tooltip(|cx| {
+                                            Tooltip::for_action(
+                                                "Apply previewed replacements",
+                                                &ReplaceAll,
+                                                cx,
+                                            )
+                                        }),
+                                ),
+                        ),
+                )
+                .child(replace_preview.editor().clone())
+                .into_any_element()
+        } else if self.has_matches() {
             div()
                 .flex_1()
                 .size_full()
                 .track_focus(&self.focus_handle)
                 .child(self.results_editor.clone())
+                .into_any_element()
         } else {
             let model = self.model.read(cx);
             let has_no_results = model.no_results.unwrap_or(false);
@@ -325,6 +440,7 @@ impl Render for ProjectSearchView {
                         .child(v_flex().child(major_text).children(minor_text))
                         .child(h_flex().flex_1()),
                 )
+                .into_any_element()
         }
     }
 }
@@ -523,6 +639,97 @@ impl Item for ProjectSearchView {
     }
 }
 
+impl FollowableItem for ProjectSearchView {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant> {
+        Some(proto::view::Variant::ProjectSearch(self.follower_state(cx)))
+    }
+
+    fn from_state_proto(
+        pane: View<Pane>,
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<anyhow::Result<View<Self>>>> {
+        let Some(proto::view::Variant::ProjectSearch(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::ProjectSearch(state)) = state.take() else {
+            unreachable!()
+        };
+
+        let existing = pane
+            .read(cx)
+            .items_of_type::<Self>()
+            .find(|view| view.read(cx).remote_id == Some(remote_id));
+        let view = existing.unwrap_or_else(|| {
+            let project = workspace.read(cx).project().clone();
+            let model = cx.new_model(|cx| ProjectSearch::new(project, cx));
+            cx.new_view(|cx| {
+                let mut view = Self::new(model, cx, None);
+                view.remote_id = Some(remote_id);
+                view
+            })
+        });
+        view.update(cx, |view, cx| view.apply_leader_state(state, cx));
+        Some(Task::ready(Ok(view)))
+    }
+
+    fn to_follow_event(_: &Self::Event) -> Option<FollowEvent> {
+        None
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        event: &Self::Event,
+        update: &mut Option<proto::update_view::Variant>,
+        cx: &WindowContext,
+    ) -> bool {
+        match event {
+            ViewEvent::UpdateTab
+            | ViewEvent::EditorEvent(EditorEvent::Edited | EditorEvent::SelectionsChanged { .. }) =>
+            {
+                *update = Some(proto::update_view::Variant::ProjectSearch(
+                    self.follower_state(cx),
+                ));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        _: &Model<Project>,
+        message: proto::update_view::Variant,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let proto::update_view::Variant::ProjectSearch(state) = message else {
+            return Task::ready(Err(anyhow!("unexpected update view variant")));
+        };
+        self.apply_leader_state(state, cx);
+        Task::ready(Ok(()))
+    }
+
+    fn is_project_item(&self, _: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, leader_peer_id: Option<PeerId>, cx: &mut ViewContext<Self>) {
+        if leader_peer_id.is_none() {
+            self.leader_search_id = None;
+            self.leader_match_index = None;
+        }
+        self.results_editor.update(cx, |editor, cx| {
+            editor.set_leader_peer_id(leader_peer_id, cx)
+        });
+    }
+}
+
 impl ProjectSearchView {
     fn toggle_filters(&mut self, cx: &mut ViewContext<Self>) {
         self.filters_enabled = !self.filters_enabled;
@@ -542,6 +749,12 @@ impl ProjectSearchView {
     }
     fn toggle_search_option(&mut self, option: SearchOptions, cx: &mut ViewContext<Self>) {
         self.search_options.toggle(option);
+        // A query is either a regex or a structural pattern, never both.
+        if option == SearchOptions::REGEX && self.search_options.contains(option) {
+            self.search_options.remove(SearchOptions::STRUCTURAL);
+        } else if option == SearchOptions::STRUCTURAL && self.search_options.contains(option) {
+            self.search_options.remove(SearchOptions::REGEX);
+        }
         cx.update_global(|state: &mut ActiveSettings, cx| {
             state.0.insert(
                 self.model.read(cx).project.downgrade(),
@@ -560,7 +773,9 @@ impl ProjectSearchView {
 
         let query = self.model.read(cx).active_query.clone();
         if let Some(query) = query {
-            let query = query.with_replacement(self.replacement(cx));
+            let query = query
+                .with_replacement(self.replacement(cx))
+                .with_preserved_case(self.search_options.contains(SearchOptions::PRESERVE_CASE));
 
             // TODO: Do we need the clone here?
             let mat = self.model.read(cx).match_ranges[active_index].clone();
@@ -573,7 +788,13 @@ impl ProjectSearchView {
     pub fn replacement(&self, cx: &AppContext) -> String {
         self.replacement_editor.read(cx).text(cx)
     }
+    /// Previews replacing all matches, or applies the previewed replacements
+    /// if they're already shown.
     fn replace_all(&mut self, _: &ReplaceAll, cx: &mut ViewContext<Self>) {
+        if self.replace_preview.is_some() {
+            self.apply_replace_preview(cx);
+            return;
+        }
         if self.active_match_index.is_none() {
             return;
         }
@@ -581,24 +802,51 @@ impl ProjectSearchView {
         let Some(query) = self.model.read(cx).active_query.as_ref() else {
             return;
         };
-        let query = query.clone().with_replacement(self.replacement(cx));
-
-        let match_ranges = self
-            .model
-            .update(cx, |model, _| mem::take(&mut model.match_ranges));
-        if match_ranges.is_empty() {
-            return;
+        let query = query
+            .clone()
+            .with_replacement(self.replacement(cx))
+            .with_preserved_case(self.search_options.contains(SearchOptions::PRESERVE_CASE));
+        let project = self.model.read(cx).project.clone();
+        let match_ranges = self.model.read(cx).match_ranges.clone();
+        self.replace_preview =
+            ReplacePreview::new(&self.results_editor, &match_ranges, &query, project, cx);
+        if let Some(replace_preview) = &self.replace_preview {
+            let preview_handle = replace_preview.editor().focus_handle(cx);
+            cx.focus(&preview_handle);
         }
+        cx.notify();
+    }
 
+    /// Applies the replacements of the preview that weren't excluded as a
+    /// single transaction, so that they're undone together.
+    fn apply_replace_preview(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(replace_preview) = self.replace_preview.take() else {
+            return;
+        };
+        let edits = replace_preview.edits().collect::<Vec<_>>();
         self.results_editor.update(cx, |editor, cx| {
-            for item in &match_ranges {
-                editor.replace(item, &query, cx);
-            }
+            editor.transact(cx, |editor, cx| editor.edit(edits, cx));
         });
+        self.focus_results_editor(cx);
+        cx.notify();
+    }
 
-        self.model.update(cx, |model, _cx| {
-            model.match_ranges = match_ranges;
-        });
+    fn dismiss_replace_preview(&mut self, cx: &mut ViewContext<Self>) {
+        if self.replace_preview.take().is_some() {
+            self.focus_results_editor(cx);
+            cx.notify();
+        }
+    }
+
+    fn toggle_replacement_excluded(
+        &mut self,
+        _: &replace_preview::ToggleExcluded,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(replace_preview) = self.replace_preview.as_mut() {
+            replace_preview.toggle_excluded(cx);
+            cx.notify();
+        }
     }
 
     fn new(
@@ -632,7 +880,7 @@ impl ProjectSearchView {
         subscriptions.push(cx.observe(&model, |this, _, cx| this.model_changed(cx)));
 
         let query_editor = cx.new_view(|cx| {
-            let mut editor = Editor::single_line(cx);
+            let mut editor = Editor::auto_height(MAX_QUERY_LINES, cx);
             editor.set_placeholder_text("Search all files..", cx);
             editor.set_text(query_text, cx);
             editor
@@ -644,7 +892,7 @@ impl ProjectSearchView {
             }),
         );
         let replacement_editor = cx.new_view(|cx| {
-            let mut editor = Editor::single_line(cx);
+            let mut editor = Editor::auto_height(MAX_QUERY_LINES, cx);
             editor.set_placeholder_text("Replace in project..", cx);
             if let Some(text) = replacement_text {
                 editor.set_text(text, cx);
@@ -694,10 +942,41 @@ impl ProjectSearchView {
             }),
         );
 
+        let file_types_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("File types: Rust, toml, Makefile", cx);
+
+            editor
+        });
+        let max_file_size_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Max size: 512kb", cx);
+
+            editor
+        });
+        let modified_since_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Modified within: 7d", cx);
+
+            editor
+        });
+        // Subscribe to the filter editors in order to reraise editor events for workspace item activation purposes
+        for editor in [
+            &file_types_editor,
+            &max_file_size_editor,
+            &modified_since_editor,
+        ] {
+            subscriptions.push(cx.subscribe(editor, |_, _, event: &EditorEvent, cx| {
+                cx.emit(ViewEvent::EditorEvent(event.clone()))
+            }));
+        }
+
         let focus_handle = cx.focus_handle();
         subscriptions.push(cx.on_focus_in(&focus_handle, |this, cx| {
             if this.focus_handle.is_focused(cx) {
-                if this.has_matches() {
+                if let Some(replace_preview) = this.replace_preview.as_ref() {
+                    replace_preview.editor().focus_handle(cx).focus(cx);
+                } else if this.has_matches() {
                     this.results_editor.focus_handle(cx).focus(cx);
                 } else {
                     this.query_editor.focus_handle(cx).focus(cx);
@@ -719,8 +998,16 @@ impl ProjectSearchView {
             query_editor_was_focused: false,
             included_files_editor,
             excluded_files_editor,
+            file_types_editor,
+            max_file_size_editor,
+            modified_since_editor,
+            within_paths: None,
+            replace_preview: None,
             filters_enabled,
             replace_enabled: false,
+            remote_id: None,
+            leader_search_id: None,
+            leader_match_index: None,
             _subscriptions: subscriptions,
         };
         this.model_changed(cx);
@@ -854,6 +1141,7 @@ impl ProjectSearchView {
 
     fn search(&mut self, cx: &mut ViewContext<Self>) {
         if let Some(query) = self.build_search_query(cx) {
+            self.replace_preview = None;
             self.model.update(cx, |model, cx| model.search(query, cx));
         }
     }
@@ -896,8 +1184,33 @@ impl ProjectSearchView {
                     vec![]
                 }
             };
+        let filters = self.build_search_filters(cx);
+
+        let query = if self.search_options.contains(SearchOptions::STRUCTURAL) {
+            match SearchQuery::structural(
+                text,
+                self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+                included_files,
+                excluded_files,
+            ) {
+                Ok(query) => {
+                    let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
+                    if should_unmark_error {
+                        cx.notify();
+                    }
+
+                    Some(query)
+                }
+                Err(_e) => {
+                    let should_mark_error = self.panels_with_errors.insert(InputPanel::Query);
+                    if should_mark_error {
+                        cx.notify();
+                    }
 
-        let query = if self.search_options.contains(SearchOptions::REGEX) {
+                    None
+                }
+            }
+        } else if self.search_options.contains(SearchOptions::REGEX) {
             match SearchQuery::regex(
                 text,
                 self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -956,7 +1269,128 @@ impl ProjectSearchView {
         if query.as_ref().is_some_and(|query| query.is_empty()) {
             return None;
         }
-        query
+        query.map(|query| query.with_filters(filters))
+    }
+
+    fn build_search_filters(&mut self, cx: &mut ViewContext<Self>) -> SearchFilters {
+        let languages = self.model.read(cx).project.read(cx).languages().clone();
+        let file_types = Self::parse_file_types(&self.file_types_editor.read(cx).text(cx))
+            .flat_map(|file_type| {
+                languages
+                    .path_suffixes_for_language(&file_type)
+                    .unwrap_or_else(|| vec![file_type])
+            })
+            .collect();
+        let max_file_size = Self::parse_file_size(&self.max_file_size_editor.read(cx).text(cx));
+        let modified_since =
+            Self::parse_modified_since(&self.modified_since_editor.read(cx).text(cx));
+
+        let mut has_errors_changed = false;
+        for (panel, is_error) in [
+            (InputPanel::MaxFileSize, max_file_size.is_err()),
+            (InputPanel::ModifiedSince, modified_since.is_err()),
+        ] {
+            has_errors_changed |= if is_error {
+                self.panels_with_errors.insert(panel)
+            } else {
+                self.panels_with_errors.remove(&panel)
+            };
+        }
+        if has_errors_changed {
+            cx.notify();
+        }
+
+        SearchFilters {
+            file_types,
+            max_file_size: max_file_size.unwrap_or_default(),
+            modified_since: modified_since.unwrap_or_default(),
+            only_git_changes: self.search_options.contains(SearchOptions::GIT_CHANGES),
+            within_paths: self.within_paths.clone(),
+        }
+    }
+
+    /// Parses a comma-separated list of language names, extensions and file names.
+    fn parse_file_types(text: &str) -> impl Iterator<Item = String> + '_ {
+        text.split(',')
+            .map(|file_type| {
+                file_type
+                    .trim()
+                    .trim_start_matches('*')
+                    .trim_start_matches('.')
+            })
+            .filter(|file_type| !file_type.is_empty())
+            .map(str::to_string)
+    }
+
+    /// Parses a file size such as `2048`, `512kb` or `1.5mb`.
+    fn parse_file_size(text: &str) -> anyhow::Result<Option<u64>> {
+        let text = text.trim().to_lowercase();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        let number_len = text
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(text.len());
+        let (number, unit) = text.split_at(number_len);
+        let number = number
+            .parse::<f64>()
+            .with_context(|| format!("parsing {text} as file size"))?;
+        let multiplier = match unit.trim() {
+            "" | "b" => 1,
+            "k" | "kb" => 1 << 10,
+            "m" | "mb" => 1 << 20,
+            "g" | "gb" => 1 << 30,
+            unit => return Err(anyhow!("unknown file size unit {unit}")),
+        };
+        Ok(Some((number * multiplier as f64) as u64))
+    }
+
+    /// Parses how recently files must have been modified, such as `30m`, `12h`, `7d` or `2w`.
+    fn parse_modified_since(text: &str) -> anyhow::Result<Option<SystemTime>> {
+        let text = text.trim().to_lowercase();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        let number_len = text
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len());
+        let (number, unit) = text.split_at(number_len);
+        let number = number
+            .parse::<u64>()
+            .with_context(|| format!("parsing {text} as duration"))?;
+        let seconds = match unit.trim() {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "" | "d" => 24 * 60 * 60,
+            "w" => 7 * 24 * 60 * 60,
+            unit => return Err(anyhow!("unknown duration unit {unit}")),
+        };
+        let duration = Duration::from_secs(number.saturating_mul(seconds));
+        Ok(Some(
+            SystemTime::now()
+                .checked_sub(duration)
+                .unwrap_or(SystemTime::UNIX_EPOCH),
+        ))
+    }
+
+    /// Narrows the next searches down to the files of the current results.
+    fn search_in_results(&mut self, cx: &mut ViewContext<Self>) {
+        let buffers = self.model.read(cx).excerpts.read(cx).all_buffers();
+        let within_paths = buffers
+            .into_iter()
+            .filter_map(|buffer| buffer.read(cx).project_path(cx))
+            .collect::<HashSet<_>>();
+        self.within_paths = Some(Arc::new(within_paths));
+        self.focus_query_editor(cx);
+        cx.notify();
+    }
+
+    fn clear_results_scope(&mut self, cx: &mut ViewContext<Self>) {
+        if self.within_paths.take().is_some() {
+            self.search(cx);
+            cx.notify();
+        }
     }
 
     fn parse_path_matches(text: &str) -> anyhow::Result<Vec<PathMatcher>> {
@@ -997,6 +1431,26 @@ impl ProjectSearchView {
         cx.focus(&editor_handle);
     }
 
+    /// Moves the cursor of a multi-line query to the line above or below it.
+    /// Returns false if the cursor is on the query's first or last line, where
+    /// moving up or down navigates the search history instead.
+    fn move_within_query(&mut self, direction: Direction, cx: &mut ViewContext<Self>) -> bool {
+        self.query_editor.update(cx, |query_editor, cx| {
+            let last_row = query_editor.buffer().read(cx).snapshot(cx).max_point().row;
+            let row = query_editor
+                .selections
+                .newest::<language::Point>(cx)
+                .head()
+                .row;
+            match direction {
+                Direction::Prev if row > 0 => query_editor.move_up(&MoveUp, cx),
+                Direction::Next if row < last_row => query_editor.move_down(&MoveDown, cx),
+                _ => return false,
+            }
+            true
+        })
+    }
+
     fn set_query(&mut self, query: &str, cx: &mut ViewContext<Self>) {
         self.query_editor
             .update(cx, |query_editor, cx| query_editor.set_text(query, cx));
@@ -1040,6 +1494,7 @@ impl ProjectSearchView {
             if is_new_search && self.query_editor.focus_handle(cx).is_focused(cx) {
                 self.focus_results_editor(cx);
             }
+            self.select_leader_match(cx);
         }
 
         cx.emit(ViewEvent::UpdateTab);
@@ -1059,6 +1514,72 @@ impl ProjectSearchView {
         }
     }
 
+    fn follower_state(&self, cx: &AppContext) -> proto::view::ProjectSearch {
+        proto::view::ProjectSearch {
+            query: self.query_editor.read(cx).text(cx),
+            search_options: self.search_options.bits() as u32,
+            filters_enabled: self.filters_enabled,
+            included_files: self.included_files_editor.read(cx).text(cx),
+            excluded_files: self.excluded_files_editor.read(cx).text(cx),
+            file_types: self.file_types_editor.read(cx).text(cx),
+            max_file_size: self.max_file_size_editor.read(cx).text(cx),
+            modified_since: self.modified_since_editor.read(cx).text(cx),
+            search_id: self.model.read(cx).search_id as u64,
+            active_match_index: self.active_match_index.map(|index| index as u64),
+        }
+    }
+
+    /// Mirrors the query and selected match of the collaborator we follow,
+    /// running their search again whenever they start a new one.
+    fn apply_leader_state(
+        &mut self,
+        state: proto::view::ProjectSearch,
+        cx: &mut ViewContext<Self>,
+    ) {
+        for (editor, text) in [
+            (&self.query_editor, state.query),
+            (&self.included_files_editor, state.included_files),
+            (&self.excluded_files_editor, state.excluded_files),
+            (&self.file_types_editor, state.file_types),
+            (&self.max_file_size_editor, state.max_file_size),
+            (&self.modified_since_editor, state.modified_since),
+        ] {
+            if editor.read(cx).text(cx) != text {
+                editor.update(cx, |editor, cx| editor.set_text(text, cx));
+            }
+        }
+        self.search_options = SearchOptions::from_bits_truncate(state.search_options as u8);
+        self.filters_enabled = state.filters_enabled;
+        self.leader_match_index = state.active_match_index.map(|index| index as usize);
+
+        // A search id of zero means the leader hasn't searched yet.
+        if state.search_id > 0 && self.leader_search_id != Some(state.search_id) {
+            self.leader_search_id = Some(state.search_id);
+            self.search(cx);
+        } else {
+            self.select_leader_match(cx);
+        }
+        cx.notify();
+    }
+
+    fn select_leader_match(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(index) = self.leader_match_index else {
+            return;
+        };
+        if self.active_match_index == Some(index) {
+            return;
+        }
+        // Results stream in, so the match may not have arrived yet.
+        let Some(range) = self.model.read(cx).match_ranges.get(index).cloned() else {
+            return;
+        };
+        self.results_editor.update(cx, |editor, cx| {
+            let range = editor.range_for_match(&range);
+            editor.unfold_ranges([range.clone()], false, true, cx);
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| s.select_ranges([range]));
+        });
+    }
+
     pub fn has_matches(&self) -> bool {
         self.active_match_index.is_some()
     }
@@ -1142,6 +1663,9 @@ impl ProjectSearchBar {
                 views.extend([
                     &project_view.included_files_editor,
                     &project_view.excluded_files_editor,
+                    &project_view.file_types_editor,
+                    &project_view.max_file_size_editor,
+                    &project_view.modified_since_editor,
                 ]);
             }
             let current_index = match views
@@ -1178,6 +1702,13 @@ impl ProjectSearchBar {
         }
     }
 
+    fn search_in_results(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(search_view) = self.active_project_search.as_ref() {
+            search_view.update(cx, |search_view, cx| search_view.search_in_results(cx));
+            cx.notify();
+        }
+    }
+
     fn toggle_replace(&mut self, _: &ToggleReplace, cx: &mut ViewContext<Self>) {
         if let Some(search) = &self.active_project_search {
             search.update(cx, |this, cx| {
@@ -1203,6 +1734,15 @@ impl ProjectSearchBar {
                 search_view
                     .excluded_files_editor
                     .update(cx, |_, cx| cx.notify());
+                search_view
+                    .file_types_editor
+                    .update(cx, |_, cx| cx.notify());
+                search_view
+                    .max_file_size_editor
+                    .update(cx, |_, cx| cx.notify());
+                search_view
+                    .modified_since_editor
+                    .update(cx, |_, cx| cx.notify());
                 cx.refresh();
                 cx.notify();
             });
@@ -1233,6 +1773,9 @@ impl ProjectSearchBar {
     fn next_history_query(&mut self, _: &NextHistoryQuery, cx: &mut ViewContext<Self>) {
         if let Some(search_view) = self.active_project_search.as_ref() {
             search_view.update(cx, |search_view, cx| {
+                if search_view.move_within_query(Direction::Next, cx) {
+                    return;
+                }
                 let new_query = search_view.model.update(cx, |model, cx| {
                     if let Some(new_query) = model.project.update(cx, |project, _| {
                         project
@@ -1254,6 +1797,9 @@ impl ProjectSearchBar {
     fn previous_history_query(&mut self, _: &PreviousHistoryQuery, cx: &mut ViewContext<Self>) {
         if let Some(search_view) = self.active_project_search.as_ref() {
             search_view.update(cx, |search_view, cx| {
+                if search_view.move_within_query(Direction::Prev, cx) {
+                    return;
+                }
                 if search_view.query_editor.read(cx).text(cx).is_empty() {
                     if let Some(new_query) = search_view
                         .model
@@ -1340,7 +1886,7 @@ impl Render for ProjectSearchBar {
 
         let query_column = h_flex()
             .flex_1()
-            .h_8()
+            .min_h_8()
             .mr_2()
             .px_2()
             .py_1()
@@ -1372,6 +1918,12 @@ impl Render for ProjectSearchBar {
                         cx.listener(|this, _, cx| {
                             this.toggle_search_option(SearchOptions::REGEX, cx);
                         }),
+                    ))
+                    .child(SearchOptions::STRUCTURAL.as_button(
+                        self.is_option_enabled(SearchOptions::STRUCTURAL, cx),
+                        cx.listener(|this, _, cx| {
+                            this.toggle_search_option(SearchOptions::STRUCTURAL, cx);
+                        }),
                     )),
             );
 
@@ -1448,6 +2000,14 @@ impl Render for ProjectSearchBar {
                     }))
                     .tooltip(|cx| Tooltip::for_action("Go to next match", &SelectNextMatch, cx)),
             )
+            .child(
+                IconButton::new("project-search-in-results", IconName::MagnifyingGlass)
+                    .disabled(!search.has_matches())
+                    .on_click(cx.listener(|this, _, cx| this.search_in_results(cx)))
+                    .tooltip(|cx| {
+                        Tooltip::for_action("Search within results", &SearchInResults, cx)
+                    }),
+            )
             .child(
                 h_flex()
                     .min_w(rems_from_px(40.))
@@ -1465,6 +2025,32 @@ impl Render for ProjectSearchBar {
                         .child(Label::new("Search limit reached").color(Color::Warning))
                         .ml_2(),
                 )
+            })
+            .when_some(search.within_paths.as_ref(), |this, within_paths| {
+                let file_count = within_paths.len();
+                this.child(
+                    h_flex()
+                        .ml_2()
+                        .gap_1()
+                        .child(
+                            Label::new(format!(
+                                "Within {file_count} {}",
+                                if file_count == 1 { "file" } else { "files" }
+                            ))
+                            .color(Color::Muted),
+                        )
+                        .child(
+                            IconButton::new("project-search-clear-results-scope", IconName::Close)
+                                .on_click(cx.listener(|this, _, cx| {
+                                    if let Some(search) = this.active_project_search.as_ref() {
+                                        search.update(cx, |search, cx| {
+                                            search.clear_results_scope(cx);
+                                        })
+                                    }
+                                }))
+                                .tooltip(|cx| Tooltip::text("Search all files", cx)),
+                        ),
+                )
             });
 
         let search_line = h_flex()
@@ -1478,13 +2064,24 @@ impl Render for ProjectSearchBar {
                 .flex_1()
                 .min_w(rems(MIN_INPUT_WIDTH_REMS))
                 .max_w(rems(MAX_INPUT_WIDTH_REMS))
-                .h_8()
+                .min_h_8()
                 .px_2()
                 .py_1()
                 .border_1()
                 .border_color(cx.theme().colors().border)
                 .rounded_lg()
-                .child(self.render_text_input(&search.replacement_editor, cx));
+                .child(self.render_text_input(&search.replacement_editor, cx))
+                .child(SearchOptions::PRESERVE_CASE.as_button(
+                    search.search_options.contains(SearchOptions::PRESERVE_CASE),
+                    cx.listener(|this, _, cx| {
+                        this.toggle_search_option(SearchOptions::PRESERVE_CASE, cx);
+                    }),
+                ));
+            let replace_all_tooltip = if search.replace_preview.is_some() {
+                "Apply previewed replacements"
+            } else {
+                "Preview replacing all matches"
+            };
             let replace_actions = h_flex().when(search.replace_enabled, |this| {
                 this.child(
                     IconButton::new("project-search-replace-next", IconName::ReplaceNext)
@@ -1506,7 +2103,9 @@ impl Render for ProjectSearchBar {
                                 })
                             }
                         }))
-                        .tooltip(|cx| Tooltip::for_action("Replace all matches", &ReplaceAll, cx)),
+                        .tooltip(move |cx| {
+                            Tooltip::for_action(replace_all_tooltip, &ReplaceAll, cx)
+                        }),
                 )
             });
             h_flex()
@@ -1557,6 +2156,62 @@ impl Render for ProjectSearchBar {
                 )
         });
 
+        let file_filter_line = search.filters_enabled.then(|| {
+            h_flex()
+                .w_full()
+                .gap_2()
+                .child(
+                    h_flex()
+                        .flex_1()
+                        .min_w(rems(MIN_INPUT_WIDTH_REMS))
+                        .max_w(rems(MAX_INPUT_WIDTH_REMS))
+                        .h_8()
+                        .px_2()
+                        .py_1()
+                        .border_1()
+                        .border_color(search.border_color_for(InputPanel::FileTypes, cx))
+                        .rounded_lg()
+                        .child(self.render_text_input(&search.file_types_editor, cx))
+                        .child(SearchOptions::GIT_CHANGES.as_button(
+                            search.search_options.contains(SearchOptions::GIT_CHANGES),
+                            cx.listener(|this, _, cx| {
+                                this.toggle_search_option(SearchOptions::GIT_CHANGES, cx);
+                            }),
+                        )),
+                )
+                .child(
+                    h_flex()
+                        .flex_1()
+                        .min_w(rems(MIN_INPUT_WIDTH_REMS))
+                        .max_w(rems(MAX_INPUT_WIDTH_REMS))
+                        .gap_2()
+                        .child(
+                            h_flex()
+                                .flex_1()
+                                .h_8()
+                                .px_2()
+                                .py_1()
+                                .border_1()
+                                .border_color(search.border_color_for(InputPanel::MaxFileSize, cx))
+                                .rounded_lg()
+                                .child(self.render_text_input(&search.max_file_size_editor, cx)),
+                        )
+                        .child(
+                            h_flex()
+                                .flex_1()
+                                .h_8()
+                                .px_2()
+                                .py_1()
+                                .border_1()
+                                .border_color(
+                                    search.border_color_for(InputPanel::ModifiedSince, cx),
+                                )
+                                .rounded_lg()
+                                .child(self.render_text_input(&search.modified_since_editor, cx)),
+                        ),
+                )
+        });
+
         v_flex()
             .key_context("ProjectSearchBar")
             .on_action(cx.listener(|this, _: &ToggleFocus, cx| this.move_focus_to_results(cx)))
@@ -1581,6 +2236,9 @@ impl Render for ProjectSearchBar {
             .on_action(cx.listener(|this, _: &ToggleCaseSensitive, cx| {
                 this.toggle_search_option(SearchOptions::CASE_SENSITIVE, cx);
             }))
+            .on_action(cx.listener(|this, _: &TogglePreserveCase, cx| {
+                this.toggle_search_option(SearchOptions::PRESERVE_CASE, cx);
+            }))
             .on_action(cx.listener(|this, action, cx| {
                 if let Some(search) = this.active_project_search.as_ref() {
                     search.update(cx, |this, cx| {
@@ -1599,7 +2257,13 @@ impl Render for ProjectSearchBar {
                 this.on_action(cx.listener(|this, _: &ToggleIncludeIgnored, cx| {
                     this.toggle_search_option(SearchOptions::INCLUDE_IGNORED, cx);
                 }))
+                .on_action(cx.listener(|this, _: &ToggleGitChanges, cx| {
+                    this.toggle_search_option(SearchOptions::GIT_CHANGES, cx);
+                }))
             })
+            .on_action(cx.listener(|this, _: &SearchInResults, cx| {
+                this.search_in_results(cx);
+            }))
             .on_action(cx.listener(Self::select_next_match))
             .on_action(cx.listener(Self::select_prev_match))
             .gap_2()
@@ -1607,6 +2271,7 @@ impl Render for ProjectSearchBar {
             .child(search_line)
             .children(replace_line)
             .children(filter_line)
+            .children(file_filter_line)
     }
 }
 
@@ -1828,6 +2493,87 @@ pub mod tests {
             .unwrap();
     }
 
+    #[gpui::test]
+    async fn test_project_search_replace_preview(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "one.rs": "let foo = Foo::new(FOO);",
+                "two.rs": "fn foo() {}\nfn other() {}",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let search = cx.new_model(|cx| ProjectSearch::new(project, cx));
+        let search_view = cx.add_window(|cx| ProjectSearchView::new(search.clone(), cx, None));
+
+        perform_search(search_view, "foo", cx);
+        search_view
+            .update(cx, |search_view, cx| {
+                search_view
+                    .replacement_editor
+                    .update(cx, |editor, cx| editor.set_text("bar", cx));
+                search_view
+                    .search_options
+                    .insert(SearchOptions::PRESERVE_CASE);
+                search_view.replace_all(&ReplaceAll, cx);
+
+                let replace_preview = search_view.replace_preview.as_ref().unwrap();
+                assert_eq!(replace_preview.replacement_count(), 4);
+                assert_eq!(replace_preview.included_count(), 4);
+                assert!(
+                    search_view
+                        .results_editor
+                        .read(cx)
+                        .text(cx)
+                        .contains("let foo = Foo::new(FOO);"),
+                    "Previewing replacements should not change the buffers"
+                );
+
+                // Exclude the match in two.rs.
+                let preview_editor = replace_preview.editor().clone();
+                preview_editor.update(cx, |editor, cx| {
+                    let row = editor
+                        .text(cx)
+                        .lines()
+                        .position(|line| line == "fn foo() {}")
+                        .unwrap() as u32;
+                    editor.change_selections(None, cx, |s| {
+                        s.select_ranges(
+                            [language::Point::new(row, 0)..language::Point::new(row, 0)],
+                        )
+                    });
+                });
+                search_view.toggle_replacement_excluded(&replace_preview::ToggleExcluded, cx);
+                let replace_preview = search_view.replace_preview.as_ref().unwrap();
+                assert_eq!(replace_preview.included_count(), 3);
+
+                search_view.replace_all(&ReplaceAll, cx);
+                assert!(search_view.replace_preview.is_none());
+                let results_text = search_view.results_editor.read(cx).text(cx);
+                assert!(
+                    results_text.contains("let bar = Bar::new(BAR);"),
+                    "Replacements should follow the case of the replaced text: {results_text:?}"
+                );
+                assert!(
+                    results_text.contains("fn foo() {}"),
+                    "Excluded matches should not be replaced: {results_text:?}"
+                );
+
+                search_view.results_editor.update(cx, |editor, cx| {
+                    editor.undo(&editor::actions::Undo, cx);
+                    assert!(
+                        editor.text(cx).contains("let foo = Foo::new(FOO);"),
+                        "All replacements should be undone together"
+                    );
+                });
+            })
+            .unwrap();
+    }
+
     #[gpui::test]
     async fn test_deploy_project_search_focus(cx: &mut TestAppContext) {
         init_test(cx);
@@ -3233,6 +3979,39 @@ pub mod tests {
             .expect("unable to update search view");
     }
 
+    #[test]
+    fn test_parse_search_filters() {
+        assert_eq!(
+            ProjectSearchView::parse_file_types(" rs, *.toml,.md ,, Makefile").collect::<Vec<_>>(),
+            ["rs", "toml", "md", "Makefile"]
+        );
+
+        assert_eq!(ProjectSearchView::parse_file_size("").unwrap(), None);
+        assert_eq!(
+            ProjectSearchView::parse_file_size("100").unwrap(),
+            Some(100)
+        );
+        assert_eq!(
+            ProjectSearchView::parse_file_size("512kb").unwrap(),
+            Some(512 * 1024)
+        );
+        assert_eq!(
+            ProjectSearchView::parse_file_size("1.5 MB").unwrap(),
+            Some(3 * 512 * 1024)
+        );
+        assert!(ProjectSearchView::parse_file_size("10 apples").is_err());
+
+        assert_eq!(ProjectSearchView::parse_modified_since(" ").unwrap(), None);
+        let modified_since = ProjectSearchView::parse_modified_since("2d")
+            .unwrap()
+            .unwrap();
+        let age = SystemTime::now().duration_since(modified_since).unwrap();
+        assert!(age >= Duration::from_secs(2 * 24 * 60 * 60));
+        assert!(age < Duration::from_secs(3 * 24 * 60 * 60));
+        assert!(ProjectSearchView::parse_modified_since("d2").is_err());
+        assert!(ProjectSearchView::parse_modified_since("3y").is_err());
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings = SettingsStore::test(cx);
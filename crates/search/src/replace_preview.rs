@@ -0,0 +1,294 @@
+use collections::HashSet;
+use editor::{
+    display_map::{BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    Anchor, Editor, MultiBuffer, ToPoint as _,
+};
+use gpui::{
+    actions, px, AnyElement, HighlightStyle, Model, StrikethroughStyle, StyledText, View,
+    ViewContext, VisualContext as _,
+};
+use language::{BufferSnapshot, Capability, OffsetRangeExt as _, Point, ToOffset as _};
+use project::{search::SearchQuery, Project};
+use settings::Settings;
+use std::{mem, ops::Range};
+use theme::ThemeSettings;
+use ui::prelude::*;
+
+actions!(replace_preview, [ToggleExcluded]);
+
+/// Highlights the matches that are replaced when the preview is applied.
+enum ReplacedMatches {}
+
+/// A preview of replacing the matches of a project search. Each match is struck
+/// out, and the lines it's on are shown again below it with the replacement
+/// applied. Matches can be excluded from the replacement before it's applied.
+pub(crate) struct ReplacePreview {
+    editor: View<Editor>,
+    replacements: Vec<PreviewedReplacement>,
+    line_groups: Vec<LineGroup>,
+    blocks: HashSet<BlockId>,
+}
+
+struct PreviewedReplacement {
+    /// The range of the match in the search results.
+    match_range: Range<Anchor>,
+    /// The range of the match in the preview.
+    preview_range: Range<Anchor>,
+    /// The range of the match in its buffer.
+    buffer_range: Range<usize>,
+    replacement: String,
+    excluded: bool,
+}
+
+/// Replacements whose lines overlap, which are previewed together.
+struct LineGroup {
+    snapshot: BufferSnapshot,
+    rows: Range<u32>,
+    replacements: Range<usize>,
+}
+
+impl ReplacePreview {
+    /// Creates a preview of replacing the given matches of the query in the
+    /// search results, or `None` if the query replaces none of them.
+    pub fn new<V: 'static>(
+        results_editor: &View<Editor>,
+        match_ranges: &[Range<Anchor>],
+        query: &SearchQuery,
+        project: Model<Project>,
+        cx: &mut ViewContext<V>,
+    ) -> Option<Self> {
+        let mut matches_by_buffer = Vec::new();
+        {
+            let results_editor = results_editor.read(cx);
+            let results = results_editor.buffer().read(cx);
+            for match_range in match_ranges {
+                let Some(buffer) = match_range
+                    .start
+                    .buffer_id
+                    .and_then(|id| results.buffer(id))
+                else {
+                    continue;
+                };
+                let Some(replacement) = results_editor.replacement_for(match_range, query, cx)
+                else {
+                    continue;
+                };
+                match matches_by_buffer.last_mut() {
+                    Some((last_buffer, matches)) if *last_buffer == buffer => {
+                        matches.push((match_range.clone(), replacement));
+                    }
+                    _ => matches_by_buffer.push((buffer, vec![(match_range.clone(), replacement)])),
+                }
+            }
+        }
+        if matches_by_buffer.is_empty() {
+            return None;
+        }
+
+        let mut replacements = Vec::new();
+        let mut line_groups = Vec::<LineGroup>::new();
+        let excerpts = cx.new_model(|cx| {
+            let mut excerpts = MultiBuffer::new(0, Capability::ReadOnly);
+            for (buffer, matches) in matches_by_buffer {
+                let snapshot = buffer.read(cx).snapshot();
+                let ranges = matches
+                    .iter()
+                    .map(|(range, _)| range.start.text_anchor..range.end.text_anchor)
+                    .collect::<Vec<_>>();
+                let preview_ranges =
+                    excerpts.push_excerpts_with_context_lines(buffer, ranges.clone(), 1, cx);
+                for (((match_range, replacement), range), preview_range) in
+                    matches.into_iter().zip(ranges).zip(preview_ranges)
+                {
+                    let buffer_range = range.to_offset(&snapshot);
+                    let rows = snapshot.offset_to_point(buffer_range.start).row
+                        ..snapshot.offset_to_point(buffer_range.end).row + 1;
+                    match line_groups.last_mut() {
+                        Some(group)
+                            if group.snapshot.remote_id() == snapshot.remote_id()
+                                && group.rows.end > rows.start =>
+                        {
+                            group.rows.end = group.rows.end.max(rows.end);
+                            group.replacements.end += 1;
+                        }
+                        _ => line_groups.push(LineGroup {
+                            snapshot: snapshot.clone(),
+                            rows,
+                            replacements: replacements.len()..replacements.len() + 1,
+                        }),
+                    }
+                    replacements.push(PreviewedReplacement {
+                        match_range,
+                        preview_range,
+                        buffer_range,
+                        replacement,
+                        excluded: false,
+                    });
+                }
+            }
+            excerpts
+        });
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_multibuffer(excerpts, Some(project), cx);
+            editor.set_searchable(false);
+            editor
+        });
+
+        let mut this = Self {
+            editor,
+            replacements,
+            line_groups,
+            blocks: HashSet::default(),
+        };
+        this.update_decorations(cx);
+        Some(this)
+    }
+
+    pub fn editor(&self) -> &View<Editor> {
+        &self.editor
+    }
+
+    pub fn replacement_count(&self) -> usize {
+        self.replacements.len()
+    }
+
+    pub fn included_count(&self) -> usize {
+        self.replacements
+            .iter()
+            .filter(|replacement| !replacement.excluded)
+            .count()
+    }
+
+    /// The edits that apply the replacements that weren't excluded, as ranges
+    /// in the search results.
+    pub fn edits(&self) -> impl Iterator<Item = (Range<Anchor>, String)> + '_ {
+        self.replacements
+            .iter()
+            .filter(|replacement| !replacement.excluded)
+            .map(|replacement| {
+                (
+                    replacement.match_range.clone(),
+                    replacement.replacement.clone(),
+                )
+            })
+    }
+
+    /// Excludes the replacements on the selected lines, or includes them again
+    /// if they were all excluded.
+    pub fn toggle_excluded<V: 'static>(&mut self, cx: &mut ViewContext<V>) {
+        let selected = {
+            let editor = self.editor.read(cx);
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let selected_rows = editor
+                .selections
+                .all::<Point>(cx)
+                .into_iter()
+                .map(|selection| selection.start.row..selection.end.row + 1)
+                .collect::<Vec<_>>();
+            self.replacements
+                .iter()
+                .map(|replacement| {
+                    let rows = replacement.preview_range.start.to_point(&snapshot).row
+                        ..replacement.preview_range.end.to_point(&snapshot).row + 1;
+                    selected_rows
+                        .iter()
+                        .any(|selection| selection.start < rows.end && rows.start < selection.end)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let exclude = self
+            .replacements
+            .iter()
+            .zip(&selected)
+            .any(|(replacement, selected)| *selected && !replacement.excluded);
+        for (replacement, selected) in self.replacements.iter_mut().zip(selected) {
+            if selected {
+                replacement.excluded = exclude;
+            }
+        }
+        self.update_decorations(cx);
+    }
+
+    fn update_decorations<V: 'static>(&mut self, cx: &mut ViewContext<V>) {
+        let status = cx.theme().status();
+        let replaced_style = HighlightStyle {
+            background_color: Some(status.deleted_background),
+            strikethrough: Some(StrikethroughStyle {
+                thickness: px(1.),
+                color: Some(status.deleted),
+            }),
+            ..Default::default()
+        };
+        let replaced_ranges = self
+            .replacements
+            .iter()
+            .filter(|replacement| !replacement.excluded)
+            .map(|replacement| replacement.preview_range.clone())
+            .collect::<Vec<_>>();
+        let blocks = self
+            .line_groups
+            .iter()
+            .filter_map(|group| {
+                let replacements = &self.replacements[group.replacements.clone()];
+                if replacements.iter().all(|replacement| replacement.excluded) {
+                    return None;
+                }
+                let replaced_lines = SharedString::from(replaced_lines(group, replacements));
+                Some(BlockProperties {
+                    position: replacements.last()?.preview_range.end,
+                    height: (replaced_lines.matches('\n').count() + 1).min(u8::MAX as usize) as u8,
+                    style: BlockStyle::Fixed,
+                    render: Box::new(move |cx: &mut BlockContext| {
+                        render_replaced_lines(replaced_lines.clone(), cx)
+                    }),
+                    disposition: BlockDisposition::Below,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let old_blocks = mem::take(&mut self.blocks);
+        self.blocks = self.editor.update(cx, |editor, cx| {
+            editor.remove_blocks(old_blocks, None, cx);
+            editor.highlight_text::<ReplacedMatches>(replaced_ranges, replaced_style, cx);
+            editor.insert_blocks(blocks, None, cx).into_iter().collect()
+        });
+    }
+}
+
+/// Returns the lines of a group with its included replacements applied.
+fn replaced_lines(group: &LineGroup, replacements: &[PreviewedReplacement]) -> String {
+    let snapshot = &group.snapshot;
+    let last_row = group.rows.end - 1;
+    let mut offset = Point::new(group.rows.start, 0).to_offset(snapshot);
+    let end = Point::new(last_row, snapshot.line_len(last_row)).to_offset(snapshot);
+
+    let mut text = String::new();
+    for replacement in replacements {
+        if !replacement.excluded {
+            text.extend(snapshot.text_for_range(offset..replacement.buffer_range.start));
+            text.push_str(&replacement.replacement);
+            offset = replacement.buffer_range.end;
+        }
+    }
+    text.extend(snapshot.text_for_range(offset..end));
+    text
+}
+
+fn render_replaced_lines(text: SharedString, cx: &mut BlockContext) -> AnyElement {
+    let theme_settings = ThemeSettings::get_global(cx);
+    let mut text_style = cx.text_style();
+    text_style.color = cx.theme().status().created;
+    text_style.font_family = theme_settings.buffer_font.family.clone();
+    text_style.font_style = theme_settings.buffer_font.style;
+    text_style.font_features = theme_settings.buffer_font.features.clone();
+    text_style.font_weight = theme_settings.buffer_font.weight;
+
+    div()
+        .id(cx.block_id)
+        .size_full()
+        .pl(cx.gutter_dimensions.width + cx.gutter_dimensions.margin)
+        .bg(cx.theme().status().created_background)
+        .child(StyledText::new(text).with_highlights(&text_style, []))
+        .into_any_element()
+}
@@ -8,6 +8,7 @@ use ui::{ButtonStyle, IconButton};
 
 pub mod buffer_search;
 pub mod project_search;
+pub(crate) mod replace_preview;
 pub(crate) mod search_bar;
 
 pub fn init(cx: &mut AppContext) {
@@ -24,6 +25,9 @@ actions!(
         ToggleCaseSensitive,
         ToggleIncludeIgnored,
         ToggleRegex,
+        ToggleStructural,
+        ToggleGitChanges,
+        TogglePreserveCase,
         ToggleReplace,
         SelectNextMatch,
         SelectPrevMatch,
@@ -43,6 +47,9 @@ bitflags! {
         const CASE_SENSITIVE = 0b010;
         const INCLUDE_IGNORED = 0b100;
         const REGEX = 0b1000;
+        const STRUCTURAL = 0b10000;
+        const GIT_CHANGES = 0b100000;
+        const PRESERVE_CASE = 0b1000000;
     }
 }
 
@@ -53,6 +60,9 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => "match case",
             SearchOptions::INCLUDE_IGNORED => "include Ignored",
             SearchOptions::REGEX => "regular expression",
+            SearchOptions::STRUCTURAL => "structural pattern",
+            SearchOptions::GIT_CHANGES => "only git changes",
+            SearchOptions::PRESERVE_CASE => "preserve case",
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -63,6 +73,9 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => ui::IconName::CaseSensitive,
             SearchOptions::INCLUDE_IGNORED => ui::IconName::FileGit,
             SearchOptions::REGEX => ui::IconName::Regex,
+            SearchOptions::STRUCTURAL => ui::IconName::Code,
+            SearchOptions::GIT_CHANGES => ui::IconName::PullRequest,
+            SearchOptions::PRESERVE_CASE => ui::IconName::CaseSensitive,
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -73,6 +86,9 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => Box::new(ToggleCaseSensitive),
             SearchOptions::INCLUDE_IGNORED => Box::new(ToggleIncludeIgnored),
             SearchOptions::REGEX => Box::new(ToggleRegex),
+            SearchOptions::STRUCTURAL => Box::new(ToggleStructural),
+            SearchOptions::GIT_CHANGES => Box::new(ToggleGitChanges),
+            SearchOptions::PRESERVE_CASE => Box::new(TogglePreserveCase),
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -87,6 +103,9 @@ impl SearchOptions {
         options.set(SearchOptions::CASE_SENSITIVE, query.case_sensitive());
         options.set(SearchOptions::INCLUDE_IGNORED, query.include_ignored());
         options.set(SearchOptions::REGEX, query.is_regex());
+        options.set(SearchOptions::STRUCTURAL, query.is_structural());
+        options.set(SearchOptions::GIT_CHANGES, query.filters().only_git_changes);
+        options.set(SearchOptions::PRESERVE_CASE, query.preserve_case());
         options
     }
 
@@ -1,6 +1,7 @@
 //! Baseline interface of Tasks in Zed: all tasks in Zed are intended to use those for implementing their own logic.
 #![deny(missing_docs)]
 
+mod problem_matcher;
 pub mod static_source;
 mod task_template;
 mod vscode_format;
@@ -12,7 +13,12 @@ use std::any::Any;
 use std::borrow::Cow;
 use std::path::PathBuf;
 
-pub use task_template::{RevealStrategy, TaskTemplate, TaskTemplates};
+pub use problem_matcher::{
+    Problem, ProblemMatcher, ProblemMatcherBackground, ProblemMatcherConfig,
+    ProblemMatcherDefinition, ProblemMatcherEntry, ProblemPattern, ProblemPatterns,
+    ProblemSeverity,
+};
+pub use task_template::{DependsOrder, RevealStrategy, TaskInput, TaskTemplate, TaskTemplates};
 pub use vscode_format::VsCodeTaskFile;
 
 /// Task identifier, unique within the application.
@@ -25,6 +31,9 @@ pub struct TaskId(pub String);
 pub struct SpawnInTerminal {
     /// Id of the task to use when determining task tab affinity.
     pub id: TaskId,
+    /// Id of the task's source, which tells apart the tasks with the same label from different sources.
+    /// Unlike `id`, it does not change with the context the task is resolved in.
+    pub id_base: String,
     /// Full unshortened form of `label` field.
     pub full_label: String,
     /// Human readable name of the terminal tab.
@@ -46,6 +55,13 @@ pub struct SpawnInTerminal {
     pub allow_concurrent_runs: bool,
     /// What to do with the terminal pane and tab, after the command was started.
     pub reveal: RevealStrategy,
+    /// Problem matchers to turn the task output into diagnostics with, as the task runs.
+    pub problem_matcher: Option<ProblemMatcherConfig>,
+    /// Tasks to run before this one, resolved from the template's `depends_on` labels.
+    /// Empty after [`TaskTemplate::resolve_task`], as the labels are resolved against the other tasks available.
+    pub depends_on: Vec<SpawnInTerminal>,
+    /// How to run the tasks from `depends_on`.
+    pub depends_order: DependsOrder,
 }
 
 /// A final form of the [`TaskTemplate`], that got resolved with a particualar [`TaskContext`] and now is ready to spawn the actual task.
@@ -103,6 +119,27 @@ pub enum VariableName {
     Column,
     /// Text from the latest selection.
     SelectedText,
+    /// A path of the currently opened file, relative to the worktree root.
+    RelativeFile,
+    /// A name of the currently opened file, with its extension.
+    Filename,
+    /// A name of the currently opened file, without its extension.
+    Stem,
+    /// An absolute path of the directory, that contains the currently opened file.
+    Dirname,
+    /// An extension of the currently opened file.
+    Extension,
+    /// An extension of the currently opened file with a leading dot, or nothing if it has none.
+    DottedExtension,
+    /// A name of the language of the currently opened file.
+    Language,
+    /// A name of the git branch, checked out in the repository of the currently opened file.
+    GitBranch,
+    /// A SHA of the `HEAD` commit in the repository of the currently opened file.
+    GitHeadSha,
+    /// A value of the [`TaskInput`] with the corresponding id, that the user is asked for before the task is spawned.
+    /// Will be printed with `ZED_INPUT_` prefix.
+    Input(Cow<'static, str>),
     /// Custom variable, provided by the plugin or other external source.
     /// Will be printed with `ZED_` prefix to avoid potential conflicts with other variables.
     Custom(Cow<'static, str>),
@@ -110,9 +147,9 @@ pub enum VariableName {
 
 impl VariableName {
     /// Generates a `$VARIABLE`-like string value to be used in templates.
-    /// Custom variables are wrapped in `${}` to avoid substitution issues with whitespaces.
+    /// Custom and input variables are wrapped in `${}` to avoid substitution issues with whitespaces.
     pub fn template_value(&self) -> String {
-        if matches!(self, Self::Custom(_)) {
+        if matches!(self, Self::Custom(_) | Self::Input(_)) {
             format!("${{{self}}}")
         } else {
             format!("${self}")
@@ -132,6 +169,16 @@ impl std::fmt::Display for VariableName {
             Self::Row => write!(f, "{ZED_VARIABLE_NAME_PREFIX}ROW"),
             Self::Column => write!(f, "{ZED_VARIABLE_NAME_PREFIX}COLUMN"),
             Self::SelectedText => write!(f, "{ZED_VARIABLE_NAME_PREFIX}SELECTED_TEXT"),
+            Self::RelativeFile => write!(f, "{ZED_VARIABLE_NAME_PREFIX}RELATIVE_FILE"),
+            Self::Filename => write!(f, "{ZED_VARIABLE_NAME_PREFIX}FILENAME"),
+            Self::Stem => write!(f, "{ZED_VARIABLE_NAME_PREFIX}STEM"),
+            Self::Dirname => write!(f, "{ZED_VARIABLE_NAME_PREFIX}DIRNAME"),
+            Self::Extension => write!(f, "{ZED_VARIABLE_NAME_PREFIX}EXTENSION"),
+            Self::DottedExtension => write!(f, "{ZED_VARIABLE_NAME_PREFIX}DOTTED_EXTENSION"),
+            Self::Language => write!(f, "{ZED_VARIABLE_NAME_PREFIX}LANGUAGE"),
+            Self::GitBranch => write!(f, "{ZED_VARIABLE_NAME_PREFIX}GIT_BRANCH"),
+            Self::GitHeadSha => write!(f, "{ZED_VARIABLE_NAME_PREFIX}GIT_HEAD_SHA"),
+            Self::Input(id) => write!(f, "{ZED_VARIABLE_NAME_PREFIX}INPUT_{id}"),
             Self::Custom(s) => write!(f, "{ZED_VARIABLE_NAME_PREFIX}CUSTOM_{s}"),
         }
     }
@@ -147,6 +194,11 @@ impl TaskVariables {
         self.0.insert(variable, value)
     }
 
+    /// Returns the value of the variable, if it is present in the container.
+    pub fn get(&self, variable: &VariableName) -> Option<&str> {
+        self.0.get(variable).map(String::as_str)
+    }
+
     /// Extends the container with another one, overwriting the existing variables on collision.
     pub fn extend(&mut self, other: Self) {
         self.0.extend(other.0);
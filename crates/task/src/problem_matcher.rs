@@ -0,0 +1,651 @@
+//! Problem matchers, turning the task output text into diagnostics-like [`Problem`]s.
+//!
+//! The format is modelled after VS Code's problem matchers: a matcher is either a name of a builtin preset (e.g. `$rustc`),
+//! or a set of regular expressions that are matched against consecutive lines of the task output.
+
+use anyhow::{anyhow, Context};
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Problem matchers to apply to the output of a task, as it runs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcherConfig {
+    /// A single problem matcher.
+    Single(ProblemMatcherEntry),
+    /// Multiple problem matchers, each applied to the whole task output.
+    Multiple(Vec<ProblemMatcherEntry>),
+}
+
+/// Either a builtin problem matcher, or a custom one.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcherEntry {
+    /// A name of the builtin matcher: `$rustc`, `$tsc`, `$tsc-watch`, `$gcc` or `$eslint`.
+    Preset(String),
+    /// A custom problem matcher definition.
+    Custom(ProblemMatcherDefinition),
+}
+
+/// A custom problem matcher, matching the task output with one or multiple regular expressions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemMatcherDefinition {
+    /// A name to show as a source of the diagnostics produced, defaults to the task label.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Severity of the problems whose patterns do not capture the severity.
+    #[serde(default)]
+    pub severity: ProblemSeverity,
+    /// Pattern(s) to match the output with.
+    /// Multiple patterns are matched against consecutive lines, the last one producing a problem with all groups captured.
+    pub pattern: ProblemPatterns,
+    /// For tasks that keep running: only the problems of the last finished run are reported.
+    #[serde(default)]
+    pub background: Option<ProblemMatcherBackground>,
+}
+
+/// Patterns delimiting the runs of a task that keeps running, e.g. in watch mode,
+/// so that problems from earlier runs are dropped.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemMatcherBackground {
+    /// A regular expression matching the line that starts a new run.
+    pub begins_pattern: String,
+    /// A regular expression matching the line that ends a run.
+    pub ends_pattern: String,
+}
+
+/// One or multiple problem patterns.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemPatterns {
+    /// A single line pattern.
+    Single(ProblemPattern),
+    /// A multi-line pattern, each element matching one line of the output.
+    Multiple(Vec<ProblemPattern>),
+}
+
+/// A regular expression to match a single line of the output with,
+/// along with the indices of the capture groups that contain the problem's data.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// The regular expression to match the line with.
+    pub regexp: String,
+    /// Group with the path to the file, either absolute or relative to the task's working directory.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// Group with the location of the problem, in `line`, `line,column` or `line,column,end_line,end_column` form.
+    #[serde(default)]
+    pub location: Option<usize>,
+    /// Group with the 1-based line of the problem.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// Group with the 1-based column of the problem.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// Group with the 1-based line the problem ends at.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    /// Group with the 1-based column the problem ends at.
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    /// Group with the severity of the problem.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// Group with the code of the problem.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// Group with the message of the problem.
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// For the last pattern of a multi-line matcher only: whether to keep matching it against the following lines,
+    /// producing a new problem for every matched line.
+    #[serde(default, rename = "loop")]
+    pub repeat: bool,
+}
+
+/// How severe the matched problem is.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    /// An error.
+    #[default]
+    Error,
+    /// A warning.
+    Warning,
+    /// An informational message.
+    Info,
+    /// A hint.
+    Hint,
+}
+
+impl ProblemSeverity {
+    fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_lowercase().as_str() {
+            "error" | "fatal" | "fatal error" => Some(Self::Error),
+            "warning" | "warn" => Some(Self::Warning),
+            "info" | "information" | "note" => Some(Self::Info),
+            "hint" | "help" => Some(Self::Hint),
+            _ => None,
+        }
+    }
+}
+
+/// A problem, found in the task output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// A name of the diagnostics source, if the matcher has one.
+    pub source: Option<String>,
+    /// Path to the file with the problem, as printed in the output.
+    pub path: String,
+    /// 1-based line of the problem start.
+    pub line: u32,
+    /// 1-based column of the problem start.
+    pub column: Option<u32>,
+    /// 1-based line of the problem end.
+    pub end_line: Option<u32>,
+    /// 1-based column of the problem end.
+    pub end_column: Option<u32>,
+    /// How severe the problem is.
+    pub severity: ProblemSeverity,
+    /// A code of the problem, e.g. `E0308`.
+    pub code: Option<String>,
+    /// Problem description.
+    pub message: String,
+}
+
+/// A compiled [`ProblemMatcherDefinition`], ready to match the output.
+#[derive(Debug)]
+pub struct ProblemMatcher {
+    source: Option<String>,
+    severity: ProblemSeverity,
+    patterns: Vec<(Regex, ProblemPattern)>,
+    /// The patterns that begin and end a run, if the matcher is for a task that keeps running.
+    background: Option<(Regex, Regex)>,
+}
+
+impl ProblemMatcherConfig {
+    /// Resolves presets and compiles all regular expressions of the matchers.
+    pub fn matchers(&self) -> anyhow::Result<Vec<ProblemMatcher>> {
+        let entries = match self {
+            Self::Single(entry) => std::slice::from_ref(entry),
+            Self::Multiple(entries) => entries.as_slice(),
+        };
+        entries
+            .iter()
+            .map(|entry| match entry {
+                ProblemMatcherEntry::Preset(name) => {
+                    let definition = ProblemMatcher::preset(name)
+                        .ok_or_else(|| anyhow!("Unknown problem matcher preset `{name}`"))?;
+                    ProblemMatcher::new(&definition)
+                }
+                ProblemMatcherEntry::Custom(definition) => ProblemMatcher::new(definition),
+            })
+            .collect()
+    }
+}
+
+impl ProblemMatcher {
+    /// Compiles the matcher definition given.
+    pub fn new(definition: &ProblemMatcherDefinition) -> anyhow::Result<Self> {
+        let patterns = match &definition.pattern {
+            ProblemPatterns::Single(pattern) => std::slice::from_ref(pattern),
+            ProblemPatterns::Multiple(patterns) => patterns.as_slice(),
+        };
+        anyhow::ensure!(!patterns.is_empty(), "Problem matcher has no patterns");
+        let patterns = patterns
+            .iter()
+            .map(|pattern| {
+                let regex = Regex::new(&pattern.regexp).with_context(|| {
+                    format!("compiling problem matcher regex {:?}", pattern.regexp)
+                })?;
+                Ok((regex, pattern.clone()))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let background = match &definition.background {
+            Some(background) => {
+                let compile = |pattern: &str| {
+                    Regex::new(pattern).with_context(|| {
+                        format!("compiling problem matcher background regex {pattern:?}")
+                    })
+                };
+                Some((
+                    compile(&background.begins_pattern)?,
+                    compile(&background.ends_pattern)?,
+                ))
+            }
+            None => None,
+        };
+        Ok(Self {
+            source: definition.source.clone(),
+            severity: definition.severity,
+            patterns,
+            background,
+        })
+    }
+
+    /// Returns a definition of the builtin matcher with the name given, if any.
+    pub fn preset(name: &str) -> Option<ProblemMatcherDefinition> {
+        let (source, pattern) = match name {
+            "$rustc" => (
+                "rustc",
+                ProblemPatterns::Multiple(vec![
+                    ProblemPattern {
+                        regexp: r"^(warning|error)(?:\[(\w+)\])?: (.*)$".to_owned(),
+                        severity: Some(1),
+                        code: Some(2),
+                        message: Some(3),
+                        ..ProblemPattern::default()
+                    },
+                    ProblemPattern {
+                        regexp: r"^\s*--> (.*):(\d+):(\d+)$".to_owned(),
+                        file: Some(1),
+                        line: Some(2),
+                        column: Some(3),
+                        ..ProblemPattern::default()
+                    },
+                ]),
+            ),
+            "$tsc-watch" => {
+                return Some(ProblemMatcherDefinition {
+                    background: Some(ProblemMatcherBackground {
+                        begins_pattern: r"(?:Starting compilation in watch mode|File change detected\. Starting incremental compilation)\.\.\.".to_owned(),
+                        ends_pattern: r"(?:Compilation complete\.|Found \d+ errors?\.) Watching for file changes\.".to_owned(),
+                    }),
+                    ..Self::preset("$tsc")?
+                });
+            }
+            "$tsc" => (
+                "ts",
+                ProblemPatterns::Single(ProblemPattern {
+                    regexp: r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+TS(\d+)\s*:\s*(.*)$".to_owned(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    severity: Some(4),
+                    code: Some(5),
+                    message: Some(6),
+                    ..ProblemPattern::default()
+                }),
+            ),
+            "$gcc" => (
+                "gcc",
+                ProblemPatterns::Single(ProblemPattern {
+                    regexp: r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error|note):\s+(.*)$"
+                        .to_owned(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    severity: Some(4),
+                    message: Some(5),
+                    ..ProblemPattern::default()
+                }),
+            ),
+            "$eslint" | "$eslint-stylish" => (
+                "eslint",
+                ProblemPatterns::Multiple(vec![
+                    ProblemPattern {
+                        regexp: r"^([^\s].*)$".to_owned(),
+                        file: Some(1),
+                        ..ProblemPattern::default()
+                    },
+                    ProblemPattern {
+                        regexp: r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.*?)(?:\s\s+(.*))?$"
+                            .to_owned(),
+                        line: Some(1),
+                        column: Some(2),
+                        severity: Some(3),
+                        message: Some(4),
+                        code: Some(5),
+                        repeat: true,
+                        ..ProblemPattern::default()
+                    },
+                ]),
+            ),
+            "$eslint-compact" => (
+                "eslint",
+                ProblemPatterns::Single(ProblemPattern {
+                    regexp: r"^(.+):\sline\s(\d+),\scol\s(\d+),\s(Error|Warning|Info)\s-\s(.+)\s\((.+)\)$".to_owned(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    severity: Some(4),
+                    message: Some(5),
+                    code: Some(6),
+                    ..ProblemPattern::default()
+                }),
+            ),
+            _ => return None,
+        };
+        Some(ProblemMatcherDefinition {
+            source: Some(source.to_owned()),
+            severity: ProblemSeverity::Error,
+            pattern,
+            background: None,
+        })
+    }
+
+    /// Source of the problems produced, if the matcher defines one.
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Finds all problems in the output given.
+    /// For a background matcher, only the output of the last finished run is matched.
+    pub fn match_output(&self, output: &str) -> Vec<Problem> {
+        let output = match &self.background {
+            Some((begins, ends)) => last_finished_run(output, begins, ends),
+            None => output,
+        };
+        let last_pattern_ix = self.patterns.len() - 1;
+        let mut problems = Vec::new();
+        let mut captured = CapturedProblem::default();
+        let mut pattern_ix = 0;
+        for line in output.lines() {
+            if pattern_ix > 0 {
+                let (regex, pattern) = &self.patterns[pattern_ix];
+                if let Some(captures) = regex.captures(line) {
+                    if pattern_ix == last_pattern_ix {
+                        let mut problem = captured.clone();
+                        problem.fill(pattern, &captures);
+                        problems.extend(problem.into_problem(self));
+                        if !pattern.repeat {
+                            captured = CapturedProblem::default();
+                            pattern_ix = 0;
+                        }
+                    } else {
+                        captured.fill(pattern, &captures);
+                        pattern_ix += 1;
+                    }
+                    continue;
+                }
+                captured = CapturedProblem::default();
+                pattern_ix = 0;
+            }
+
+            let (regex, pattern) = &self.patterns[0];
+            if let Some(captures) = regex.captures(line) {
+                captured.fill(pattern, &captures);
+                if last_pattern_ix == 0 {
+                    problems.extend(std::mem::take(&mut captured).into_problem(self));
+                } else {
+                    pattern_ix = 1;
+                }
+            }
+        }
+        problems
+    }
+}
+
+/// Returns the output between the last pair of lines matching `begins` and `ends`,
+/// or the whole output if no run began, or nothing if the first run hasn't finished yet.
+fn last_finished_run<'a>(output: &'a str, begins: &Regex, ends: &Regex) -> &'a str {
+    let mut last_finished = None;
+    let mut running_since = None;
+    let mut offset = 0;
+    for line in output.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        if begins.is_match(line) {
+            running_since = Some(offset);
+        } else if let Some(run_start) = running_since {
+            if ends.is_match(line) {
+                last_finished = Some(&output[run_start..line_start]);
+                running_since = None;
+            }
+        }
+    }
+    match (last_finished, running_since) {
+        (Some(run), _) => run,
+        (None, Some(_)) => "",
+        (None, None) => output,
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct CapturedProblem {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<ProblemSeverity>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl CapturedProblem {
+    fn fill(&mut self, pattern: &ProblemPattern, captures: &Captures<'_>) {
+        let group = |ix: Option<usize>| {
+            ix.and_then(|ix| captures.get(ix))
+                .map(|group| group.as_str())
+                .filter(|text| !text.is_empty())
+        };
+        let number = |ix: Option<usize>| group(ix).and_then(|text| text.trim().parse().ok());
+
+        if let Some(file) = group(pattern.file) {
+            self.file = Some(file.trim().to_owned());
+        }
+        if let Some(location) = group(pattern.location) {
+            let mut parts = location
+                .split(',')
+                .map(|part| part.trim().parse::<u32>().ok());
+            self.line = parts.next().flatten().or(self.line);
+            self.column = parts.next().flatten().or(self.column);
+            self.end_line = parts.next().flatten().or(self.end_line);
+            self.end_column = parts.next().flatten().or(self.end_column);
+        }
+        self.line = number(pattern.line).or(self.line);
+        self.column = number(pattern.column).or(self.column);
+        self.end_line = number(pattern.end_line).or(self.end_line);
+        self.end_column = number(pattern.end_column).or(self.end_column);
+        if let Some(severity) = group(pattern.severity).and_then(ProblemSeverity::parse) {
+            self.severity = Some(severity);
+        }
+        if let Some(code) = group(pattern.code) {
+            self.code = Some(code.to_owned());
+        }
+        if let Some(message) = group(pattern.message) {
+            self.message = Some(message.trim().to_owned());
+        }
+    }
+
+    fn into_problem(self, matcher: &ProblemMatcher) -> Option<Problem> {
+        Some(Problem {
+            source: matcher.source.clone(),
+            path: self.file?,
+            line: self.line.unwrap_or(1).max(1),
+            column: self.column,
+            end_line: self.end_line,
+            end_column: self.end_column,
+            severity: self.severity.unwrap_or(matcher.severity),
+            code: self.code,
+            message: self.message?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(preset: &str) -> ProblemMatcher {
+        ProblemMatcher::new(&ProblemMatcher::preset(preset).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_rustc_preset() {
+        let output = r#"   Compiling foo v0.1.0 (/tmp/foo)
+error[E0308]: mismatched types
+ --> src/main.rs:2:18
+  |
+2 |     let x: u32 = "a";
+  |            ---   ^^^ expected `u32`, found `&str`
+warning: unused variable: `y`
+  --> src/lib.rs:10:9
+error: aborting due to 1 previous error
+"#;
+        assert_eq!(
+            matcher("$rustc").match_output(output),
+            vec![
+                Problem {
+                    source: Some("rustc".to_owned()),
+                    path: "src/main.rs".to_owned(),
+                    line: 2,
+                    column: Some(18),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Error,
+                    code: Some("E0308".to_owned()),
+                    message: "mismatched types".to_owned(),
+                },
+                Problem {
+                    source: Some("rustc".to_owned()),
+                    path: "src/lib.rs".to_owned(),
+                    line: 10,
+                    column: Some(9),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Warning,
+                    code: None,
+                    message: "unused variable: `y`".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_line_presets() {
+        let tsc_problems = matcher("$tsc").match_output(
+            "src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\nFound 1 error.",
+        );
+        assert_eq!(tsc_problems.len(), 1);
+        assert_eq!(tsc_problems[0].path, "src/index.ts");
+        assert_eq!((tsc_problems[0].line, tsc_problems[0].column), (3, Some(7)));
+        assert_eq!(tsc_problems[0].code.as_deref(), Some("2322"));
+
+        let gcc_problems = matcher("$gcc").match_output(
+            "main.c: In function 'main':\nmain.c:4:5: warning: implicit declaration of function 'foo'\nmain.c:7:1: error: expected ';' before '}' token",
+        );
+        assert_eq!(
+            gcc_problems
+                .iter()
+                .map(|problem| (problem.line, problem.severity, problem.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    4,
+                    ProblemSeverity::Warning,
+                    "implicit declaration of function 'foo'"
+                ),
+                (7, ProblemSeverity::Error, "expected ';' before '}' token"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_looping_pattern() {
+        let output = "/project/src/a.js
+  1:10  error    'x' is defined but never used  no-unused-vars
+  2:1   warning  Unexpected console statement   no-console
+
+/project/src/b.js
+  5:3  error  Missing semicolon  semi
+
+✖ 3 problems (2 errors, 1 warning)
+";
+        let problems = matcher("$eslint").match_output(output);
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.as_str(),
+                    problem.line,
+                    problem.column,
+                    problem.code.as_deref()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("/project/src/a.js", 1, Some(10), Some("no-unused-vars")),
+                ("/project/src/a.js", 2, Some(1), Some("no-console")),
+                ("/project/src/b.js", 5, Some(3), Some("semi")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_background_matcher() {
+        let matcher = matcher("$tsc-watch");
+        let first_run = "[10:00:00 AM] Starting compilation in watch mode...
+src/a.ts(1,1): error TS1005: ';' expected.
+";
+        let first_run_end = "[10:00:01 AM] Found 1 error. Watching for file changes.
+";
+        let second_run = "[10:00:05 AM] File change detected. Starting incremental compilation...
+src/b.ts(2,2): error TS2304: Cannot find name 'x'.
+";
+        let paths = |output: &str| {
+            matcher
+                .match_output(output)
+                .into_iter()
+                .map(|problem| problem.path)
+                .collect::<Vec<_>>()
+        };
+
+        // Nothing is reported until the first run finishes.
+        assert!(paths(first_run).is_empty());
+        let output = format!("{first_run}{first_run_end}");
+        assert_eq!(paths(&output), ["src/a.ts"]);
+
+        // The problems of the last finished run are kept while the next one runs.
+        let output = format!("{first_run}{first_run_end}{second_run}");
+        assert_eq!(paths(&output), ["src/a.ts"]);
+        let output = format!("{output}[10:00:06 AM] Found 1 error. Watching for file changes.\n");
+        assert_eq!(paths(&output), ["src/b.ts"]);
+
+        // Output without runs is matched as a whole.
+        assert_eq!(
+            paths("src/c.ts(3,3): error TS2322: Type 'string' is not assignable to type 'number'."),
+            ["src/c.ts"]
+        );
+    }
+
+    #[test]
+    fn test_custom_matcher_deserialization() {
+        let config: ProblemMatcherConfig = serde_json_lenient::from_str(
+            r#"["$gcc", {
+                "severity": "warning",
+                "pattern": {
+                    "regexp": "^(.*)@(\\d+,\\d+): (.*)$",
+                    "file": 1,
+                    "location": 2,
+                    "message": 3
+                }
+            }]"#,
+        )
+        .unwrap();
+        let matchers = config.matchers().unwrap();
+        assert_eq!(matchers.len(), 2);
+        assert_eq!(matchers[0].source(), Some("gcc"));
+        assert_eq!(
+            matchers[1].match_output("lib/foo.txt@12,3: something is off"),
+            vec![Problem {
+                source: None,
+                path: "lib/foo.txt".to_owned(),
+                line: 12,
+                column: Some(3),
+                end_line: None,
+                end_column: None,
+                severity: ProblemSeverity::Warning,
+                code: None,
+                message: "something is off".to_owned(),
+            }]
+        );
+
+        let unknown: ProblemMatcherConfig = serde_json_lenient::from_str(r#""$unknown""#).unwrap();
+        assert!(unknown.matchers().is_err());
+    }
+}
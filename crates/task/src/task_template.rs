@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{borrow::Cow, path::PathBuf};
 
 use anyhow::{bail, Context};
 use collections::{HashMap, HashSet};
@@ -8,7 +8,8 @@ use sha2::{Digest, Sha256};
 use util::{truncate_and_remove_front, ResultExt};
 
 use crate::{
-    ResolvedTask, SpawnInTerminal, TaskContext, TaskId, VariableName, ZED_VARIABLE_NAME_PREFIX,
+    ProblemMatcherConfig, ResolvedTask, SpawnInTerminal, TaskContext, TaskId, VariableName,
+    ZED_VARIABLE_NAME_PREFIX,
 };
 
 /// A template definition of a Zed task to run.
@@ -58,6 +59,56 @@ pub struct TaskTemplate {
     /// * `never` — avoid changing current terminal pane focus, but still add/reuse the task's tab there
     #[serde(default)]
     pub reveal: RevealStrategy,
+    /// Problem matcher(s) to turn the task output into project diagnostics with, as the task runs.
+    /// Either a builtin matcher name (`$rustc`, `$tsc`, `$tsc-watch`, `$gcc`, `$eslint`), a custom matcher definition, or a list of those.
+    /// Diagnostics of the previous run are removed when the task is rerun.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub problem_matcher: Option<ProblemMatcherConfig>,
+    /// Labels of the tasks to run before this one; if any of them fails, this task is not run.
+    /// A task with dependencies may omit its `command`, to only run the dependencies.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// How to run the tasks from `depends_on`:
+    /// * `parallel` — run all dependencies at once (default)
+    /// * `sequence` — run dependencies one after another, in the order listed, stopping on the first failure
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// Values to ask the user for before the task is spawned, available in the task as `${ZED_INPUT_<id>}` variables.
+    /// When the task is listed, its inputs are substituted with their default values.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<TaskInput>,
+}
+
+/// A value to ask the user for before the task is spawned.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TaskInput {
+    /// Id of the input, the value is available in the task as the `${ZED_INPUT_<id>}` variable.
+    pub id: String,
+    /// A text to show to the user when asking for the value.
+    #[serde(default)]
+    pub description: String,
+    /// Values to pick the input value from; if empty, any text can be entered instead.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+    /// A value to suggest to the user first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+impl TaskInput {
+    /// A task variable, that gets the input value.
+    pub fn variable_name(&self) -> VariableName {
+        VariableName::Input(Cow::Owned(self.id.clone()))
+    }
+
+    /// A value to use when the user was not asked for the input: the default one, or the first option, if any.
+    pub fn default_value(&self) -> String {
+        self.default
+            .clone()
+            .or_else(|| self.options.first().cloned())
+            .unwrap_or_default()
+    }
 }
 
 /// What to do with the terminal pane and tab, after the command was started.
@@ -71,6 +122,17 @@ pub enum RevealStrategy {
     Never,
 }
 
+/// How to run the dependencies of a task.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Run all dependencies at once.
+    #[default]
+    Parallel,
+    /// Run dependencies one after another, stopping on the first failure.
+    Sequence,
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -88,23 +150,40 @@ impl TaskTemplates {
 }
 
 impl TaskTemplate {
+    /// Inputs of the task, that have no values in the [`TaskContext`] given yet.
+    pub fn missing_inputs(&self, cx: &TaskContext) -> Vec<&TaskInput> {
+        self.inputs
+            .iter()
+            .filter(|input| cx.task_variables.get(&input.variable_name()).is_none())
+            .collect()
+    }
+
     /// Replaces all `VariableName` task variables in the task template string fields.
     /// If any replacement fails or the new string substitutions still have [`ZED_VARIABLE_NAME_PREFIX`],
     /// `None` is returned.
+    /// Inputs of the template, that have no values in the context, are substituted with their [`TaskInput::default_value`].
     ///
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty()
+            || (self.command.trim().is_empty() && self.depends_on.is_empty())
+        {
             return None;
         }
 
         let mut variable_names = HashMap::default();
         let mut substituted_variables = HashSet::default();
+        let default_inputs = self
+            .missing_inputs(cx)
+            .into_iter()
+            .map(|input| (input.variable_name(), input.default_value()))
+            .collect::<Vec<_>>();
         let task_variables = cx
             .task_variables
             .0
             .iter()
+            .chain(default_inputs.iter().map(|(key, value)| (key, value)))
             .map(|(key, value)| {
                 let key_string = key.to_string();
                 if !variable_names.contains_key(&key_string) {
@@ -184,6 +263,7 @@ impl TaskTemplate {
             resolved_label: full_label.clone(),
             resolved: Some(SpawnInTerminal {
                 id,
+                id_base: id_base.to_owned(),
                 cwd,
                 full_label,
                 label: human_readable_label,
@@ -201,6 +281,9 @@ impl TaskTemplate {
                 use_new_terminal: self.use_new_terminal,
                 allow_concurrent_runs: self.allow_concurrent_runs,
                 reveal: self.reveal,
+                problem_matcher: self.problem_matcher.clone(),
+                depends_on: Vec::new(),
+                depends_order: self.depends_order,
             }),
         })
     }
@@ -349,6 +432,24 @@ mod tests {
                 "should not resolve task with blank label and/or command: {task_with_blank_property:?}"
             );
         }
+
+        let task_with_dependencies_only = TaskTemplate {
+            label: "test_label".to_string(),
+            depends_on: vec!["build".to_string(), "lint".to_string()],
+            depends_order: DependsOrder::Sequence,
+            ..TaskTemplate::default()
+        };
+        let resolved = task_with_dependencies_only
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .expect("should resolve a command-less task with dependencies")
+            .resolved
+            .unwrap();
+        assert!(resolved.command.is_empty());
+        assert_eq!(resolved.depends_order, DependsOrder::Sequence);
+        assert!(
+            resolved.depends_on.is_empty(),
+            "Dependencies are resolved by the task inventory users, not the template"
+        );
     }
 
     #[test]
@@ -611,6 +712,63 @@ mod tests {
             .is_none());
     }
 
+    #[test]
+    fn test_input_variables_resolution() {
+        let task = TaskTemplate {
+            label: format!(
+                "Deploy to {}",
+                VariableName::Input("target".into()).template_value()
+            ),
+            command: "deploy".into(),
+            args: vec![VariableName::Input("version".into()).template_value()],
+            inputs: vec![
+                TaskInput {
+                    id: "target".into(),
+                    description: "Deployment target".into(),
+                    options: vec!["staging".into(), "production".into()],
+                    default: None,
+                },
+                TaskInput {
+                    id: "version".into(),
+                    description: "Version to deploy".into(),
+                    options: Vec::new(),
+                    default: Some("latest".into()),
+                },
+            ],
+            ..Default::default()
+        };
+
+        let empty_context = TaskContext::default();
+        assert_eq!(task.missing_inputs(&empty_context).len(), 2);
+        let resolved = task
+            .resolve_task(TEST_ID_BASE, &empty_context)
+            .unwrap()
+            .resolved
+            .unwrap();
+        assert_eq!(resolved.label, "Deploy to staging");
+        assert_eq!(resolved.command_label, "deploy latest");
+
+        let context_with_inputs = TaskContext {
+            cwd: None,
+            task_variables: TaskVariables::from_iter([
+                (VariableName::Input("target".into()), "production".into()),
+                (VariableName::Input("version".into()), "1.2.3".into()),
+            ]),
+        };
+        assert!(task.missing_inputs(&context_with_inputs).is_empty());
+        let resolved = task
+            .resolve_task(TEST_ID_BASE, &context_with_inputs)
+            .unwrap()
+            .resolved
+            .unwrap();
+        assert_eq!(resolved.label, "Deploy to production");
+        assert_eq!(resolved.command_label, "deploy 1.2.3");
+        assert_eq!(
+            resolved.env.get("ZED_INPUT_version").map(String::as_str),
+            Some("1.2.3")
+        );
+    }
+
     #[test]
     fn test_symbol_dependent_tasks() {
         let task_with_all_properties = TaskTemplate {
@@ -1,9 +1,13 @@
-use anyhow::bail;
+use anyhow::{bail, Context};
 use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    DependsOrder, ProblemMatcherBackground, ProblemMatcherConfig, ProblemMatcherDefinition,
+    ProblemMatcherEntry, ProblemPattern, ProblemPatterns, ProblemSeverity, TaskInput, TaskTemplate,
+    TaskTemplates, VariableName,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -41,6 +45,254 @@ enum Command {
     },
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatcher {
+    Single(VsCodeProblemMatcherEntry),
+    Multiple(Vec<VsCodeProblemMatcherEntry>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatcherEntry {
+    Named(String),
+    Definition(VsCodeProblemMatcherDefinition),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemMatcherDefinition {
+    base: Option<String>,
+    owner: Option<String>,
+    source: Option<String>,
+    severity: Option<ProblemSeverity>,
+    pattern: Option<VsCodeProblemPatterns>,
+    background: Option<VsCodeProblemMatcherBackground>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemMatcherBackground {
+    begins_pattern: VsCodeBackgroundPattern,
+    ends_pattern: VsCodeBackgroundPattern,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeBackgroundPattern {
+    Regexp(String),
+    Object { regexp: String },
+}
+
+impl VsCodeBackgroundPattern {
+    fn into_regexp(self) -> String {
+        match self {
+            Self::Regexp(regexp) | Self::Object { regexp } => regexp,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemPatterns {
+    Named(String),
+    Single(VsCodeProblemPattern),
+    Multiple(Vec<VsCodeProblemPattern>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    file: Option<usize>,
+    location: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<usize>,
+    code: Option<usize>,
+    message: Option<usize>,
+    #[serde(default, rename = "loop")]
+    repeat: bool,
+}
+
+impl From<VsCodeProblemPattern> for ProblemPattern {
+    fn from(pattern: VsCodeProblemPattern) -> Self {
+        Self {
+            regexp: pattern.regexp,
+            file: pattern.file,
+            location: pattern.location,
+            line: pattern.line,
+            column: pattern.column,
+            end_line: pattern.end_line,
+            end_column: pattern.end_column,
+            severity: pattern.severity,
+            code: pattern.code,
+            message: pattern.message,
+            repeat: pattern.repeat,
+        }
+    }
+}
+
+impl VsCodeProblemMatcher {
+    /// Converts the matchers Zed knows about, dropping the rest.
+    /// `fileLocation` is not converted: Zed resolves relative paths against the task's working directory.
+    fn to_zed_format(self) -> Option<ProblemMatcherConfig> {
+        let entries = match self {
+            Self::Single(entry) => vec![entry],
+            Self::Multiple(entries) => entries,
+        };
+        let mut entries = entries
+            .into_iter()
+            .filter_map(VsCodeProblemMatcherEntry::to_zed_format)
+            .collect::<Vec<_>>();
+        match entries.len() {
+            0 => None,
+            1 => entries.pop().map(ProblemMatcherConfig::Single),
+            _ => Some(ProblemMatcherConfig::Multiple(entries)),
+        }
+    }
+}
+
+impl VsCodeProblemMatcherEntry {
+    fn to_zed_format(self) -> Option<ProblemMatcherEntry> {
+        let definition = match self {
+            Self::Named(name) => return zed_preset_name(&name).map(ProblemMatcherEntry::Preset),
+            Self::Definition(definition) => definition,
+        };
+        let pattern = match definition.pattern {
+            Some(VsCodeProblemPatterns::Single(pattern)) => ProblemPatterns::Single(pattern.into()),
+            Some(VsCodeProblemPatterns::Multiple(patterns)) => {
+                ProblemPatterns::Multiple(patterns.into_iter().map(Into::into).collect())
+            }
+            Some(VsCodeProblemPatterns::Named(name)) => {
+                return zed_preset_name(&name).map(ProblemMatcherEntry::Preset)
+            }
+            None => {
+                return definition
+                    .base
+                    .as_deref()
+                    .and_then(zed_preset_name)
+                    .map(ProblemMatcherEntry::Preset)
+            }
+        };
+        Some(ProblemMatcherEntry::Custom(ProblemMatcherDefinition {
+            source: definition.source.or(definition.owner),
+            severity: definition.severity.unwrap_or_default(),
+            pattern,
+            background: definition
+                .background
+                .map(|background| ProblemMatcherBackground {
+                    begins_pattern: background.begins_pattern.into_regexp(),
+                    ends_pattern: background.ends_pattern.into_regexp(),
+                }),
+        }))
+    }
+}
+
+fn zed_preset_name(vscode_name: &str) -> Option<String> {
+    let name = match vscode_name {
+        "$rustc" | "$rustc-watch" => "$rustc",
+        "$tsc" => "$tsc",
+        "$tsc-watch" => "$tsc-watch",
+        "$gcc" => "$gcc",
+        "$eslint-stylish" => "$eslint",
+        "$eslint-compact" => "$eslint-compact",
+        _ => return None,
+    };
+    Some(name.to_owned())
+}
+
+/// Either a single task label or multiple ones; VS Code also allows `{ "type": ..., "task": ... }` task references,
+/// which have no Zed equivalent and are ignored.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeDependsOn {
+    Single(String),
+    Multiple(Vec<serde_json_lenient::Value>),
+}
+
+impl VsCodeDependsOn {
+    fn into_labels(self) -> Vec<String> {
+        match self {
+            Self::Single(label) => vec![label],
+            Self::Multiple(labels) => labels
+                .into_iter()
+                .filter_map(|label| match label {
+                    serde_json_lenient::Value::String(label) => Some(label),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum VsCodeInput {
+    PromptString {
+        id: String,
+        #[serde(default)]
+        description: String,
+        default: Option<String>,
+    },
+    PickString {
+        id: String,
+        #[serde(default)]
+        description: String,
+        options: Vec<VsCodePickStringOption>,
+        default: Option<String>,
+    },
+    Command {
+        id: String,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodePickStringOption {
+    Value(String),
+    Labeled { value: String },
+}
+
+impl VsCodeInput {
+    fn to_zed_format(self) -> Option<TaskInput> {
+        match self {
+            Self::PromptString {
+                id,
+                description,
+                default,
+            } => Some(TaskInput {
+                id,
+                description,
+                options: Vec::new(),
+                default,
+            }),
+            Self::PickString {
+                id,
+                description,
+                options,
+                default,
+            } => Some(TaskInput {
+                id,
+                description,
+                options: options
+                    .into_iter()
+                    .map(|option| match option {
+                        VsCodePickStringOption::Value(value)
+                        | VsCodePickStringOption::Labeled { value } => value,
+                    })
+                    .collect(),
+                default,
+            }),
+            // Inputs, produced by commands of VSCode extensions, cannot be run in Zed:
+            // tasks that use them are rejected when converted.
+            Self::Command { .. } => None,
+        }
+    }
+}
+
 type VsCodeEnvVariable = String;
 type ZedEnvVariable = String;
 
@@ -56,6 +308,9 @@ impl EnvVariableReplacer {
     fn replace(&self, input: &str) -> String {
         shellexpand::env_with_context_no_errors(&input, |var: &str| {
             // Colons denote a default value in case the variable is not set. We want to preserve that default, as otherwise shellexpand will substitute it for us.
+            if let Some(input_id) = var.strip_prefix("input:") {
+                return Some(VariableName::Input(input_id.to_owned().into()).template_value());
+            }
             let colon_position = var.find(':').unwrap_or(var.len());
             let (variable_name, default) = var.split_at(colon_position);
             let append_previous_default = |ret: &mut String| {
@@ -84,36 +339,79 @@ impl EnvVariableReplacer {
 }
 
 impl VsCodeTaskDefinition {
-    fn to_zed_format(self, replacer: &EnvVariableReplacer) -> anyhow::Result<TaskTemplate> {
-        if self.other_attributes.contains_key("dependsOn") {
-            bail!("Encountered unsupported `dependsOn` key during deserialization");
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+    fn to_zed_format(
+        self,
+        replacer: &EnvVariableReplacer,
+        inputs: &[TaskInput],
+        command_input_ids: &[String],
+    ) -> anyhow::Result<TaskTemplate> {
+        let depends_on = match self.other_attributes.get("dependsOn") {
+            Some(depends_on) => {
+                serde_json_lenient::from_value::<VsCodeDependsOn>(depends_on.clone())
+                    .context("Failed to deserialize `dependsOn`")?
+                    .into_labels()
+            }
+            None => Vec::new(),
         };
-
-        let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+        let depends_order = match self.other_attributes.get("dependsOrder") {
+            Some(depends_order) => {
+                serde_json_lenient::from_value::<DependsOrder>(depends_order.clone())
+                    .context("Failed to deserialize `dependsOrder`")?
+            }
+            None => DependsOrder::default(),
+        };
+        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
+        // as such tasks only run their dependencies.
+        let (command, args) = match self.command {
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args }) => (command, args),
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            None if !depends_on.is_empty() => (String::new(), Vec::new()),
+            None => bail!("Missing `type` field in task"),
         };
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
         let command = replacer.replace(&command);
         let args = args.into_iter().map(|arg| replacer.replace(&arg)).collect();
+        let problem_matcher = self
+            .other_attributes
+            .get("problemMatcher")
+            .and_then(|problem_matcher| {
+                serde_json_lenient::from_value::<VsCodeProblemMatcher>(problem_matcher.clone())
+                    .log_err()
+            })
+            .and_then(VsCodeProblemMatcher::to_zed_format);
         let mut ret = TaskTemplate {
             label: self.label,
             command,
             args,
+            problem_matcher,
+            depends_on,
+            depends_order,
             ..Default::default()
         };
         if let Some(options) = self.options {
             ret.cwd = options.cwd.map(|cwd| replacer.replace(&cwd));
             ret.env = options.env;
         }
+        let uses_variable = |variable: &str| {
+            ret.command.contains(variable)
+                || ret.args.iter().any(|arg| arg.contains(variable))
+                || ret.cwd.as_ref().map_or(false, |cwd| cwd.contains(variable))
+        };
+        if let Some(input_id) = command_input_ids.iter().find(|input_id| {
+            uses_variable(&VariableName::Input(input_id.clone().into()).template_value())
+        }) {
+            bail!(
+                "Task {:?} uses input {input_id:?}, which is produced by a VS Code extension command and cannot be run in Zed",
+                ret.label
+            );
+        }
+        let task_inputs = inputs
+            .iter()
+            .filter(|input| uses_variable(&input.variable_name().template_value()))
+            .cloned()
+            .collect();
+        ret.inputs = task_inputs;
         Ok(ret)
     }
 }
@@ -122,6 +420,8 @@ impl VsCodeTaskDefinition {
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct VsCodeTaskFile {
     tasks: Vec<VsCodeTaskDefinition>,
+    #[serde(default)]
+    inputs: Vec<VsCodeInput>,
 }
 
 impl TryFrom<VsCodeTaskFile> for TaskTemplates {
@@ -133,17 +433,55 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
                 "workspaceFolder".to_owned(),
                 VariableName::WorktreeRoot.to_string(),
             ),
+            (
+                "fileWorkspaceFolder".to_owned(),
+                VariableName::WorktreeRoot.to_string(),
+            ),
             ("file".to_owned(), VariableName::File.to_string()),
+            (
+                "relativeFile".to_owned(),
+                VariableName::RelativeFile.to_string(),
+            ),
+            (
+                "fileBasename".to_owned(),
+                VariableName::Filename.to_string(),
+            ),
+            (
+                "fileBasenameNoExtension".to_owned(),
+                VariableName::Stem.to_string(),
+            ),
+            ("fileDirname".to_owned(), VariableName::Dirname.to_string()),
+            (
+                "fileExtname".to_owned(),
+                VariableName::DottedExtension.to_string(),
+            ),
             ("lineNumber".to_owned(), VariableName::Row.to_string()),
             (
                 "selectedText".to_owned(),
                 VariableName::SelectedText.to_string(),
             ),
         ]));
+        let command_input_ids = value
+            .inputs
+            .iter()
+            .filter_map(|input| match input {
+                VsCodeInput::Command { id } => Some(id.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let inputs = value
+            .inputs
+            .into_iter()
+            .filter_map(VsCodeInput::to_zed_format)
+            .collect::<Vec<_>>();
         let templates = value
             .tasks
             .into_iter()
-            .filter_map(|vscode_definition| vscode_definition.to_zed_format(&replacer).log_err())
+            .filter_map(|vscode_definition| {
+                vscode_definition
+                    .to_zed_format(&replacer, &inputs, &command_input_ids)
+                    .log_err()
+            })
             .collect();
         Ok(Self(templates))
     }
@@ -155,10 +493,18 @@ mod tests {
 
     use crate::{
         vscode_format::{Command, VsCodeTaskDefinition},
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        DependsOrder, ProblemMatcherBackground, ProblemMatcherConfig, ProblemMatcherDefinition,
+        ProblemMatcherEntry, ProblemPattern, ProblemPatterns, ProblemSeverity, TaskContext,
+        TaskInput, TaskTemplate, TaskTemplates, TaskVariables, VariableName, VsCodeTaskFile,
     };
 
-    use super::EnvVariableReplacer;
+    use super::{EnvVariableReplacer, VsCodeProblemMatcher};
+
+    fn preset(name: &str) -> Option<ProblemMatcherConfig> {
+        Some(ProblemMatcherConfig::Single(ProblemMatcherEntry::Preset(
+            name.to_owned(),
+        )))
+    }
 
     fn compare_without_other_attributes(lhs: VsCodeTaskDefinition, rhs: VsCodeTaskDefinition) {
         assert_eq!(
@@ -257,6 +603,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matcher: preset("$tsc-watch"),
                 ..Default::default()
             },
             TaskTemplate {
@@ -268,18 +615,21 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matcher: preset("$tsc"),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matcher: preset("$tsc"),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matcher: preset("$tsc"),
                 ..Default::default()
             },
         ];
@@ -360,32 +710,287 @@ mod tests {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matcher: preset("$tsc-watch"),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matcher: preset("$tsc"),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matcher: preset("$rustc"),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matcher: preset("$rustc"),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matcher: preset("$tsc"),
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                problem_matcher: preset("$rustc"),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                problem_matcher: preset("$rustc"),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
                 ..Default::default()
             },
         ];
         let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
         assert_eq!(tasks.0, expected);
     }
+
+    #[test]
+    fn can_convert_task_dependencies() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "build and run",
+                        "type": "shell",
+                        "command": "./run.sh",
+                        "dependsOn": "build",
+                        "dependsOrder": "sequence"
+                    },
+                    {
+                        "label": "check",
+                        "dependsOn": ["lint", { "type": "npm", "script": "test" }, "test"]
+                    },
+                    {
+                        "label": "no command",
+                        "dependsOn": []
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "build and run".to_string(),
+                    command: "./run.sh".to_string(),
+                    depends_on: vec!["build".to_string()],
+                    depends_order: DependsOrder::Sequence,
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "check".to_string(),
+                    depends_on: vec!["lint".to_string(), "test".to_string()],
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn can_convert_variables_and_inputs() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "compile file",
+                        "type": "shell",
+                        "command": "cc",
+                        "args": ["${relativeFile}", "-o", "${fileDirname}/${fileBasenameNoExtension}${fileExtname}.out"],
+                        "options": { "cwd": "${input:buildDir}" }
+                    },
+                    {
+                        "label": "deploy",
+                        "type": "shell",
+                        "command": "./deploy.sh ${input:target}"
+                    },
+                    {
+                        "label": "deploy picked target",
+                        "type": "shell",
+                        "command": "./deploy.sh",
+                        "args": ["${input:fromExtension}"]
+                    }
+                ],
+                "inputs": [
+                    {
+                        "id": "target",
+                        "type": "pickString",
+                        "description": "Where to deploy?",
+                        "options": ["staging", { "label": "Production", "value": "production" }],
+                        "default": "staging"
+                    },
+                    {
+                        "id": "buildDir",
+                        "type": "promptString",
+                        "description": "Build directory",
+                        "default": "build"
+                    },
+                    {
+                        "id": "fromExtension",
+                        "type": "command",
+                        "command": "extension.pickTarget"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        // Tasks using inputs produced by extension commands can't be run, and are dropped.
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "compile file".to_string(),
+                    command: "cc".to_string(),
+                    args: vec![
+                        "${ZED_RELATIVE_FILE}".to_string(),
+                        "-o".to_string(),
+                        "${ZED_DIRNAME}/${ZED_STEM}${ZED_DOTTED_EXTENSION}.out".to_string(),
+                    ],
+                    cwd: Some("${ZED_INPUT_buildDir}".to_string()),
+                    inputs: vec![TaskInput {
+                        id: "buildDir".to_string(),
+                        description: "Build directory".to_string(),
+                        options: Vec::new(),
+                        default: Some("build".to_string()),
+                    }],
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "deploy".to_string(),
+                    command: "./deploy.sh ${ZED_INPUT_target}".to_string(),
+                    inputs: vec![TaskInput {
+                        id: "target".to_string(),
+                        description: "Where to deploy?".to_string(),
+                        options: vec!["staging".to_string(), "production".to_string()],
+                        default: Some("staging".to_string()),
+                    }],
+                    ..Default::default()
+                },
+            ]
+        );
+
+        // VS Code file extensions include their dot, so a file without one gets no stray dot.
+        for (filename, stem, dotted_extension, output) in [
+            ("main.c", "main", ".c", "/src/main.c.out"),
+            ("Makefile", "Makefile", "", "/src/Makefile.out"),
+        ] {
+            let cx = TaskContext {
+                cwd: None,
+                task_variables: TaskVariables::from_iter([
+                    (VariableName::RelativeFile, format!("src/{filename}")),
+                    (VariableName::Dirname, "/src".to_string()),
+                    (VariableName::Stem, stem.to_string()),
+                    (VariableName::DottedExtension, dotted_extension.to_string()),
+                ]),
+            };
+            let resolved = tasks.0[0].resolve_task("vscode", &cx).unwrap();
+            assert_eq!(
+                resolved.resolved.unwrap().args,
+                vec![
+                    format!("src/{filename}"),
+                    "-o".to_string(),
+                    output.to_string()
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn can_convert_problem_matchers() {
+        let convert = |json: &str| {
+            serde_json_lenient::from_str::<VsCodeProblemMatcher>(json)
+                .unwrap()
+                .to_zed_format()
+        };
+        assert_eq!(convert(r#""$gcc""#), preset("$gcc"));
+        assert_eq!(convert(r#"["$tsc-watch"]"#), preset("$tsc-watch"));
+        assert_eq!(convert(r#"["$msCompile", "$unknown"]"#), None);
+        assert_eq!(
+            convert(r#"["$eslint-stylish", {"base": "$rustc", "fileLocation": "absolute"}]"#),
+            Some(ProblemMatcherConfig::Multiple(vec![
+                ProblemMatcherEntry::Preset("$eslint".to_owned()),
+                ProblemMatcherEntry::Preset("$rustc".to_owned()),
+            ]))
+        );
+        assert_eq!(
+            convert(
+                r#"{
+                    "owner": "cpp",
+                    "severity": "warning",
+                    "pattern": [
+                        {"regexp": "^(.*):(\\d+):(\\d+)$", "file": 1, "line": 2, "column": 3},
+                        {"regexp": "^\\s+(.*)$", "message": 1, "loop": true}
+                    ]
+                }"#
+            ),
+            Some(ProblemMatcherConfig::Single(ProblemMatcherEntry::Custom(
+                ProblemMatcherDefinition {
+                    source: Some("cpp".to_owned()),
+                    severity: ProblemSeverity::Warning,
+                    pattern: ProblemPatterns::Multiple(vec![
+                        ProblemPattern {
+                            regexp: r"^(.*):(\d+):(\d+)$".to_owned(),
+                            file: Some(1),
+                            line: Some(2),
+                            column: Some(3),
+                            ..ProblemPattern::default()
+                        },
+                        ProblemPattern {
+                            regexp: r"^\s+(.*)$".to_owned(),
+                            message: Some(1),
+                            repeat: true,
+                            ..ProblemPattern::default()
+                        },
+                    ]),
+                    background: None,
+                }
+            )))
+        );
+        assert_eq!(
+            convert(
+                r#"{
+                    "pattern": {"regexp": "^(.*):(\\d+): (.*)$", "file": 1, "line": 2, "message": 3},
+                    "background": {
+                        "activeBegin": true,
+                        "beginsPattern": "^Rebuilding",
+                        "endsPattern": {"regexp": "^Done"}
+                    }
+                }"#
+            ),
+            Some(ProblemMatcherConfig::Single(ProblemMatcherEntry::Custom(
+                ProblemMatcherDefinition {
+                    source: None,
+                    severity: ProblemSeverity::Error,
+                    pattern: ProblemPatterns::Single(ProblemPattern {
+                        regexp: r"^(.*):(\d+): (.*)$".to_owned(),
+                        file: Some(1),
+                        line: Some(2),
+                        message: Some(3),
+                        ..ProblemPattern::default()
+                    }),
+                    background: Some(ProblemMatcherBackground {
+                        begins_pattern: "^Rebuilding".to_owned(),
+                        ends_pattern: "^Done".to_owned(),
+                    }),
+                }
+            )))
+        );
+    }
 }
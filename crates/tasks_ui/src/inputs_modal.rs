@@ -0,0 +1,288 @@
+use std::sync::Arc;
+
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    rems, AnyElement, DismissEvent, EventEmitter, FocusableView, InteractiveElement, ParentElement,
+    Render, SharedString, Styled, Subscription, View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use project::TaskSourceKind;
+use task::{TaskContext, TaskInput, TaskTemplate};
+use ui::{
+    h_flex, v_flex, Color, HighlightedLabel, Label, LabelCommon, LabelSize, ListItem,
+    ListItemSpacing, Selectable, WindowContext,
+};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+use crate::schedule_task;
+
+/// A modal, that asks for the values of the task inputs one after another, and schedules the task after the last one is entered.
+pub(crate) struct TaskInputsModalDelegate {
+    workspace: WeakView<Workspace>,
+    task_source_kind: TaskSourceKind,
+    task_template: TaskTemplate,
+    task_context: TaskContext,
+    omit_history: bool,
+    inputs: Vec<TaskInput>,
+    current_input_index: usize,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl TaskInputsModalDelegate {
+    fn current_input(&self) -> &TaskInput {
+        &self.inputs[self.current_input_index]
+    }
+
+    /// A query to show when the user starts entering the input value.
+    fn initial_query(&self) -> String {
+        let input = self.current_input();
+        if input.options.is_empty() {
+            input.default_value()
+        } else {
+            String::new()
+        }
+    }
+}
+
+pub(crate) struct TaskInputsModal {
+    picker: View<Picker<TaskInputsModalDelegate>>,
+    _subscription: Subscription,
+}
+
+impl TaskInputsModal {
+    pub(crate) fn new(
+        workspace: WeakView<Workspace>,
+        task_source_kind: TaskSourceKind,
+        task_template: TaskTemplate,
+        task_context: TaskContext,
+        inputs: Vec<TaskInput>,
+        omit_history: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        debug_assert!(!inputs.is_empty(), "Should not ask for no task inputs");
+        let delegate = TaskInputsModalDelegate {
+            workspace,
+            task_source_kind,
+            task_template,
+            task_context,
+            omit_history,
+            inputs,
+            current_input_index: 0,
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let initial_query = delegate.initial_query();
+        let picker = cx.new_view(|cx| {
+            let picker = Picker::uniform_list(delegate, cx);
+            picker.set_query(initial_query, cx);
+            picker
+        });
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl Render for TaskInputsModal {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl gpui::prelude::IntoElement {
+        v_flex()
+            .key_context("TaskInputsModal")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl EventEmitter<DismissEvent> for TaskInputsModal {}
+
+impl FocusableView for TaskInputsModal {
+    fn focus_handle(&self, cx: &gpui::AppContext) -> gpui::FocusHandle {
+        self.picker.read(cx).focus_handle(cx)
+    }
+}
+
+impl ModalView for TaskInputsModal {}
+
+impl PickerDelegate for TaskInputsModalDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _cx: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _: &mut WindowContext) -> Arc<str> {
+        Arc::from("Enter the task input value...")
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let input = self.current_input();
+        // Inputs without options accept any text, the query itself is the only match.
+        if input.options.is_empty() {
+            self.matches = vec![StringMatch {
+                candidate_id: 0,
+                score: 0.0,
+                positions: Vec::new(),
+                string: query,
+            }];
+            self.selected_index = 0;
+            return gpui::Task::ready(());
+        }
+
+        let candidates = input
+            .options
+            .iter()
+            .enumerate()
+            .map(|(id, option)| StringMatchCandidate {
+                id,
+                char_bag: option.chars().collect(),
+                string: option.clone(),
+            })
+            .collect::<Vec<_>>();
+        let default_index = input
+            .default
+            .as_ref()
+            .and_then(|default| input.options.iter().position(|option| option == default));
+        cx.spawn(move |picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        score: 0.0,
+                        positions: Vec::new(),
+                        string: candidate.string,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    1000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.selected_index = if query.is_empty() {
+                        default_index.unwrap_or(0)
+                    } else {
+                        0
+                    };
+                    delegate.matches = matches;
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(value) = self
+            .matches
+            .get(self.selected_index)
+            .map(|selected_match| selected_match.string.clone())
+        else {
+            return;
+        };
+        let variable_name = self.current_input().variable_name();
+        self.task_context
+            .task_variables
+            .insert(variable_name, value);
+
+        if self.current_input_index + 1 < self.inputs.len() {
+            self.current_input_index += 1;
+            let initial_query = self.initial_query();
+            cx.defer(move |picker, cx| {
+                picker.set_query(initial_query, cx);
+                picker.refresh(cx);
+            });
+            return;
+        }
+
+        let task_source_kind = self.task_source_kind.clone();
+        let task_template = &self.task_template;
+        let task_context = &self.task_context;
+        let omit_history = self.omit_history;
+        self.workspace
+            .update(cx, |workspace, cx| {
+                schedule_task(
+                    workspace,
+                    task_source_kind,
+                    task_template,
+                    task_context,
+                    omit_history,
+                    cx,
+                );
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_header(&self, _: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        let input = self.current_input();
+        let description = if input.description.is_empty() {
+            input.id.clone()
+        } else {
+            input.description.clone()
+        };
+        let progress = (self.inputs.len() > 1)
+            .then(|| format!("{}/{}", self.current_input_index + 1, self.inputs.len()));
+        Some(
+            h_flex()
+                .px_3()
+                .pt_2()
+                .justify_between()
+                .child(Label::new(description).size(LabelSize::Small))
+                .children(progress.map(|progress| {
+                    Label::new(progress)
+                        .color(Color::Muted)
+                        .size(LabelSize::Small)
+                }))
+                .into_any(),
+        )
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let item = ListItem::new(SharedString::from(format!("task-input-{ix}")))
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .selected(selected);
+        Some(if hit.string.is_empty() {
+            item.child(Label::new("Empty value").color(Color::Muted))
+        } else {
+            item.child(HighlightedLabel::new(
+                hit.string.clone(),
+                hit.positions.clone(),
+            ))
+        })
+    }
+}
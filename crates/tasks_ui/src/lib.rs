@@ -7,13 +7,15 @@ use ::settings::Settings;
 use anyhow::Context;
 use editor::Editor;
 use gpui::{AppContext, ViewContext, WindowContext};
+use inputs_modal::TaskInputsModal;
 use language::{BasicContextProvider, ContextProvider, Language};
 use modal::TasksModal;
-use project::{Location, TaskSourceKind, WorktreeId};
-use task::{ResolvedTask, TaskContext, TaskTemplate, TaskVariables};
+use project::{Location, ProjectPath, TaskSourceKind, WorktreeId};
+use task::{ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate, TaskVariables, VariableName};
 use util::ResultExt;
 use workspace::Workspace;
 
+mod inputs_modal;
 mod modal;
 mod settings;
 
@@ -184,10 +186,13 @@ fn task_context(workspace: &Workspace, cx: &mut WindowContext<'_>) -> TaskContex
             .buffer_snapshot
             .anchor_after(selection_range.end)
             .text_anchor;
-        let worktree_abs_path = buffer
-            .read(cx)
-            .file()
-            .map(|file| WorktreeId::from_usize(file.worktree_id()))
+        let project_path = buffer.read(cx).file().map(|file| ProjectPath {
+            worktree_id: WorktreeId::from_usize(file.worktree_id()),
+            path: file.path().clone(),
+        });
+        let worktree_abs_path = project_path
+            .as_ref()
+            .map(|project_path| project_path.worktree_id)
             .and_then(|worktree_id| {
                 workspace
                     .project()
@@ -199,13 +204,16 @@ fn task_context(workspace: &Workspace, cx: &mut WindowContext<'_>) -> TaskContex
             buffer,
             range: start..end,
         };
-        let task_variables = combine_task_variables(
+        let mut task_variables = combine_task_variables(
             worktree_abs_path.as_deref(),
             location,
             language_context_provider.as_ref(),
             cx,
         )
         .log_err()?;
+        if let Some(project_path) = project_path {
+            task_variables.extend(git_task_variables(workspace, &project_path, cx));
+        }
         Some(TaskContext {
             cwd,
             task_variables,
@@ -215,6 +223,31 @@ fn task_context(workspace: &Workspace, cx: &mut WindowContext<'_>) -> TaskContex
     task_context_impl(workspace, cx).unwrap_or_default()
 }
 
+/// Returns the branch and the HEAD SHA of the repository containing the given path,
+/// as last read by the worktree's background scanner.
+fn git_task_variables(
+    workspace: &Workspace,
+    project_path: &ProjectPath,
+    cx: &AppContext,
+) -> TaskVariables {
+    let mut task_variables = TaskVariables::default();
+    let Some(repository) = workspace
+        .project()
+        .read(cx)
+        .worktree_for_id(project_path.worktree_id, cx)
+        .and_then(|worktree| worktree.read(cx).repository_for_path(&project_path.path))
+    else {
+        return task_variables;
+    };
+    if let Some(branch) = repository.branch() {
+        task_variables.insert(VariableName::GitBranch, branch.to_string());
+    }
+    if let Some(head_sha) = repository.head_sha() {
+        task_variables.insert(VariableName::GitHeadSha, head_sha.to_string());
+    }
+    task_variables
+}
+
 fn combine_task_variables(
     worktree_abs_path: Option<&Path>,
     location: Location,
@@ -239,26 +272,115 @@ fn combine_task_variables(
 }
 
 fn schedule_task(
-    workspace: &Workspace,
+    workspace: &mut Workspace,
     task_source_kind: TaskSourceKind,
     task_to_resolve: &TaskTemplate,
     task_cx: &TaskContext,
     omit_history: bool,
     cx: &mut ViewContext<'_, Workspace>,
 ) {
-    if let Some(spawn_in_terminal) =
+    let missing_inputs = task_to_resolve.missing_inputs(task_cx);
+    if !missing_inputs.is_empty() {
+        let inputs = missing_inputs.into_iter().cloned().collect();
+        let workspace_handle = workspace.weak_handle();
+        let task_to_resolve = task_to_resolve.clone();
+        let task_cx = task_cx.clone();
+        workspace.toggle_modal(cx, |cx| {
+            TaskInputsModal::new(
+                workspace_handle,
+                task_source_kind,
+                task_to_resolve,
+                task_cx,
+                inputs,
+                omit_history,
+                cx,
+            )
+        });
+        return;
+    }
+
+    if let Some(mut resolved_task) =
         task_to_resolve.resolve_task(&task_source_kind.to_id_base(), task_cx)
     {
-        schedule_resolved_task(
-            workspace,
-            task_source_kind,
-            spawn_in_terminal,
-            omit_history,
-            cx,
-        );
+        if let Err(e) = resolve_task_dependencies(workspace, &mut resolved_task, task_cx, cx) {
+            workspace.show_error(&e, cx);
+            return;
+        }
+        schedule_resolved_task(workspace, task_source_kind, resolved_task, omit_history, cx);
     }
 }
 
+/// Resolves `depends_on` labels of the task into the tasks to spawn before it, recursively,
+/// looking the labels up among the tasks available for the active item and resolving them with the same context.
+/// Does nothing if the dependencies were resolved already, e.g. for the tasks from the history.
+fn resolve_task_dependencies(
+    workspace: &Workspace,
+    resolved_task: &mut ResolvedTask,
+    task_cx: &TaskContext,
+    cx: &mut WindowContext,
+) -> anyhow::Result<()> {
+    let template = resolved_task.original_task().clone();
+    let Some(spawn_in_terminal) = resolved_task.resolved.as_mut() else {
+        return Ok(());
+    };
+    if template.depends_on.is_empty() || !spawn_in_terminal.depends_on.is_empty() {
+        return Ok(());
+    }
+
+    let (worktree, language) = active_item_selection_properties(workspace, cx);
+    let available_tasks = workspace.project().update(cx, |project, cx| {
+        project.task_inventory().update(cx, |inventory, cx| {
+            inventory.list_tasks(language, worktree, cx)
+        })
+    });
+    let mut dependency_chain = vec![template.label.clone()];
+    spawn_in_terminal.depends_on =
+        resolve_dependencies(&template, &available_tasks, task_cx, &mut dependency_chain)?;
+    Ok(())
+}
+
+fn resolve_dependencies(
+    template: &TaskTemplate,
+    available_tasks: &[(TaskSourceKind, TaskTemplate)],
+    task_cx: &TaskContext,
+    dependency_chain: &mut Vec<String>,
+) -> anyhow::Result<Vec<SpawnInTerminal>> {
+    template
+        .depends_on
+        .iter()
+        .map(|label| {
+            anyhow::ensure!(
+                !dependency_chain.contains(label),
+                "Task dependency cycle: {} -> {label}",
+                dependency_chain.join(" -> ")
+            );
+            let (source_kind, dependency) = available_tasks
+                .iter()
+                .find(|(_, task)| &task.label == label)
+                .with_context(|| {
+                    format!(
+                        "Task `{}` depends on unknown task `{label}`",
+                        template.label
+                    )
+                })?;
+            let mut spawn_in_terminal = dependency
+                .resolve_task(&source_kind.to_id_base(), task_cx)
+                .and_then(|resolved_task| resolved_task.resolved)
+                .with_context(|| {
+                    format!(
+                        "Failed to resolve task `{label}`, a dependency of `{}`",
+                        template.label
+                    )
+                })?;
+            dependency_chain.push(label.clone());
+            spawn_in_terminal.depends_on =
+                resolve_dependencies(dependency, available_tasks, task_cx, dependency_chain)?;
+            dependency_chain.pop();
+            Ok(spawn_in_terminal)
+        })
+        .collect()
+}
+
 fn schedule_resolved_task(
     workspace: &Workspace,
     task_source_kind: TaskSourceKind,
@@ -316,18 +438,18 @@ fn task_cwd(workspace: &Workspace, cx: &mut WindowContext) -> anyhow::Result<Opt
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
+    use std::{path::Path, sync::Arc};
 
     use editor::Editor;
-    use gpui::{Entity, TestAppContext};
+    use gpui::{Entity, TestAppContext, VisualTestContext};
     use language::{BasicContextProvider, Language, LanguageConfig};
-    use project::{FakeFs, Project};
+    use project::{FakeFs, Project, TaskSourceKind};
     use serde_json::json;
-    use task::{TaskContext, TaskVariables, VariableName};
+    use task::{SpawnInTerminal, TaskContext, TaskTemplate, TaskVariables, VariableName};
     use ui::VisualContext;
     use workspace::{AppState, Workspace};
 
-    use crate::task_context;
+    use crate::{resolve_dependencies, task_context};
 
     #[gpui::test]
     async fn test_default_language_context(cx: &mut TestAppContext) {
@@ -361,7 +483,10 @@ mod tests {
 
         let rust_language = Arc::new(
             Language::new(
-                LanguageConfig::default(),
+                LanguageConfig {
+                    name: "Rust".into(),
+                    ..LanguageConfig::default()
+                },
                 Some(tree_sitter_rust::language()),
             )
             .with_outline_query(
@@ -375,7 +500,10 @@ mod tests {
 
         let typescript_language = Arc::new(
             Language::new(
-                LanguageConfig::default(),
+                LanguageConfig {
+                    name: "TypeScript".into(),
+                    ..LanguageConfig::default()
+                },
                 Some(tree_sitter_typescript::language_typescript()),
             )
             .with_outline_query(
@@ -427,6 +555,13 @@ mod tests {
                     cwd: Some("/dir".into()),
                     task_variables: TaskVariables::from_iter([
                         (VariableName::File, "/dir/rust/b.rs".into()),
+                        (VariableName::RelativeFile, "rust/b.rs".into()),
+                        (VariableName::Filename, "b.rs".into()),
+                        (VariableName::Stem, "b".into()),
+                        (VariableName::Extension, "rs".into()),
+                        (VariableName::DottedExtension, ".rs".into()),
+                        (VariableName::Dirname, "/dir/rust".into()),
+                        (VariableName::Language, "Rust".into()),
                         (VariableName::WorktreeRoot, "/dir".into()),
                         (VariableName::Row, "1".into()),
                         (VariableName::Column, "1".into()),
@@ -443,6 +578,13 @@ mod tests {
                     cwd: Some("/dir".into()),
                     task_variables: TaskVariables::from_iter([
                         (VariableName::File, "/dir/rust/b.rs".into()),
+                        (VariableName::RelativeFile, "rust/b.rs".into()),
+                        (VariableName::Filename, "b.rs".into()),
+                        (VariableName::Stem, "b".into()),
+                        (VariableName::Extension, "rs".into()),
+                        (VariableName::DottedExtension, ".rs".into()),
+                        (VariableName::Dirname, "/dir/rust".into()),
+                        (VariableName::Language, "Rust".into()),
                         (VariableName::WorktreeRoot, "/dir".into()),
                         (VariableName::Row, "1".into()),
                         (VariableName::Column, "15".into()),
@@ -460,6 +602,13 @@ mod tests {
                     cwd: Some("/dir".into()),
                     task_variables: TaskVariables::from_iter([
                         (VariableName::File, "/dir/a.ts".into()),
+                        (VariableName::RelativeFile, "a.ts".into()),
+                        (VariableName::Filename, "a.ts".into()),
+                        (VariableName::Stem, "a".into()),
+                        (VariableName::Extension, "ts".into()),
+                        (VariableName::DottedExtension, ".ts".into()),
+                        (VariableName::Dirname, "/dir".into()),
+                        (VariableName::Language, "TypeScript".into()),
                         (VariableName::WorktreeRoot, "/dir".into()),
                         (VariableName::Row, "1".into()),
                         (VariableName::Column, "1".into()),
@@ -470,6 +619,137 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_git_task_variables(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                ".git": {},
+                "src": {
+                    "main.rs": "fn main() {}",
+                },
+            }),
+        )
+        .await;
+        fs.set_branch_name(Path::new("/dir/.git"), Some("main"));
+        fs.set_head_sha_for_repo(Path::new("/dir/.git"), Some("1111111"));
+
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees().next().unwrap().read(cx).id()
+        });
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_buffer((worktree_id, "src/main.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let editor = cx.new_view(|cx| Editor::for_buffer(buffer, Some(project), cx));
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_center(Box::new(editor), cx)
+        });
+        cx.run_until_parked();
+
+        let git_variables = |cx: &mut VisualTestContext| {
+            workspace.update(cx, |workspace, cx| {
+                let task_variables = task_context(workspace, cx).task_variables;
+                (
+                    task_variables
+                        .get(&VariableName::GitBranch)
+                        .map(str::to_owned),
+                    task_variables
+                        .get(&VariableName::GitHeadSha)
+                        .map(str::to_owned),
+                )
+            })
+        };
+        assert_eq!(
+            git_variables(cx),
+            (Some("main".to_owned()), Some("1111111".to_owned()))
+        );
+
+        // The variables follow the repository, once the worktree rescans it.
+        fs.set_branch_name(Path::new("/dir/.git"), Some("feature"));
+        fs.set_head_sha_for_repo(Path::new("/dir/.git"), Some("2222222"));
+        cx.run_until_parked();
+        assert_eq!(
+            git_variables(cx),
+            (Some("feature".to_owned()), Some("2222222".to_owned()))
+        );
+
+        fs.set_head_sha_for_repo(Path::new("/dir/.git"), None::<String>);
+        cx.run_until_parked();
+        assert_eq!(git_variables(cx), (Some("feature".to_owned()), None));
+    }
+
+    #[test]
+    fn test_resolve_dependencies() {
+        let task = |label: &str, depends_on: &[&str]| TaskTemplate {
+            label: label.to_owned(),
+            command: format!("echo {label}"),
+            depends_on: depends_on.iter().map(|label| label.to_string()).collect(),
+            ..TaskTemplate::default()
+        };
+        let resolve = |available_tasks: &[TaskTemplate], label: &str| {
+            let available_tasks = available_tasks
+                .iter()
+                .map(|task| (TaskSourceKind::UserInput, task.clone()))
+                .collect::<Vec<_>>();
+            let (_, template) = available_tasks
+                .iter()
+                .find(|(_, task)| task.label == label)
+                .unwrap();
+            resolve_dependencies(
+                template,
+                &available_tasks,
+                &TaskContext::default(),
+                &mut vec![label.to_owned()],
+            )
+        };
+        fn labels(tasks: &[SpawnInTerminal]) -> Vec<String> {
+            tasks
+                .iter()
+                .map(|task| {
+                    if task.depends_on.is_empty() {
+                        task.label.clone()
+                    } else {
+                        format!("{}({})", task.label, labels(&task.depends_on).join(", "))
+                    }
+                })
+                .collect()
+        }
+
+        // A task that several others depend on is resolved for each of them.
+        let diamond = [
+            task("a", &["b", "c"]),
+            task("b", &["d"]),
+            task("c", &["d"]),
+            task("d", &[]),
+        ];
+        assert_eq!(labels(&resolve(&diamond, "a").unwrap()), ["b(d)", "c(d)"]);
+
+        let cycle = [task("a", &["b"]), task("b", &["c"]), task("c", &["a"])];
+        assert_eq!(
+            resolve(&cycle, "a").unwrap_err().to_string(),
+            "Task dependency cycle: a -> b -> c -> a"
+        );
+
+        let self_dependency = [task("a", &["a"])];
+        assert_eq!(
+            resolve(&self_dependency, "a").unwrap_err().to_string(),
+            "Task dependency cycle: a -> a"
+        );
+
+        let unknown = [task("a", &["b"]), task("b", &["missing"])];
+        assert_eq!(
+            resolve(&unknown, "a").unwrap_err().to_string(),
+            "Task `b` depends on unknown task `missing`"
+        );
+    }
+
     pub(crate) fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
         cx.update(|cx| {
             let state = AppState::test(cx);
@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
-use crate::{active_item_selection_properties, schedule_resolved_task};
+use crate::{
+    active_item_selection_properties, resolve_task_dependencies, schedule_resolved_task,
+    schedule_task,
+};
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
     impl_actions, rems, AppContext, DismissEvent, EventEmitter, FocusableView, InteractiveElement,
@@ -8,12 +11,12 @@ use gpui::{
     VisualContext, WeakView,
 };
 use picker::{highlighted_match_with_paths::HighlightedText, Picker, PickerDelegate};
-use project::{Inventory, TaskSourceKind};
+use project::{Inventory, TaskGraphProgress, TaskGraphStepStatus, TaskSourceKind};
 use task::{ResolvedTask, TaskContext, TaskTemplate};
 use ui::{
-    div, v_flex, ButtonCommon, ButtonSize, Clickable, Color, FluentBuilder as _, Icon, IconButton,
-    IconButtonShape, IconName, IconSize, ListItem, ListItemSpacing, RenderOnce, Selectable,
-    Tooltip, WindowContext,
+    div, h_flex, v_flex, ButtonCommon, ButtonSize, Clickable, Color, FluentBuilder as _, Icon,
+    IconButton, IconButtonShape, IconName, IconSize, Label, LabelCommon, LabelSize, ListItem,
+    ListItemSpacing, RenderOnce, Selectable, Tooltip, WindowContext,
 };
 use util::ResultExt;
 use workspace::{ModalView, Workspace};
@@ -128,7 +131,7 @@ impl TasksModalDelegate {
 
 pub(crate) struct TasksModal {
     picker: View<Picker<TasksModalDelegate>>,
-    _subscription: Subscription,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl TasksModal {
@@ -140,16 +143,22 @@ impl TasksModal {
     ) -> Self {
         let picker = cx.new_view(|cx| {
             Picker::uniform_list(
-                TasksModalDelegate::new(inventory, task_context, workspace),
+                TasksModalDelegate::new(inventory.clone(), task_context, workspace),
                 cx,
             )
         });
-        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
-            cx.emit(DismissEvent);
-        });
+        let _subscriptions = vec![
+            cx.subscribe(&picker, |_, _, _, cx| {
+                cx.emit(DismissEvent);
+            }),
+            // Keep the progress of the running tasks up to date.
+            cx.observe(&inventory, |modal, _, cx| {
+                modal.picker.update(cx, |_, cx| cx.notify());
+            }),
+        ];
         Self {
             picker,
-            _subscription,
+            _subscriptions,
         }
     }
 }
@@ -291,12 +300,30 @@ impl PickerDelegate for TasksModalDelegate {
                     .as_ref()
                     .map(|candidates| candidates[ix].clone())
             });
-        let Some((task_source_kind, task)) = task else {
+        let Some((task_source_kind, mut task)) = task else {
             return;
         };
 
+        let task_context = &self.task_context;
         self.workspace
             .update(cx, |workspace, cx| {
+                // Tasks with inputs are resolved again, after the user enters the input values.
+                if !task.original_task().missing_inputs(task_context).is_empty() {
+                    let template = task.original_task().clone();
+                    schedule_task(
+                        workspace,
+                        task_source_kind,
+                        &template,
+                        task_context,
+                        omit_history_entry,
+                        cx,
+                    );
+                    return;
+                }
+                if let Err(e) = resolve_task_dependencies(workspace, &mut task, task_context, cx) {
+                    workspace.show_error(&e, cx);
+                    return;
+                }
                 schedule_resolved_task(workspace, task_source_kind, task, omit_history_entry, cx);
             })
             .ok();
@@ -340,6 +367,12 @@ impl PickerDelegate for TasksModalDelegate {
             Some(Tooltip::text(tooltip_label_text, cx))
         };
 
+        let task_graph_progress = self
+            .inventory
+            .read(cx)
+            .task_graph_progress(&resolved_task.id)
+            .map(task_graph_progress_label);
+
         let highlighted_location = HighlightedText {
             text: hit.string.clone(),
             highlight_positions: hit.positions.clone(),
@@ -400,7 +433,18 @@ impl PickerDelegate for TasksModalDelegate {
                     }
                 })
                 .selected(selected)
-                .child(highlighted_location.render(cx)),
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(highlighted_location.render(cx))
+                        .when_some(task_graph_progress, |row, (progress_text, color)| {
+                            row.child(
+                                Label::new(progress_text)
+                                    .size(LabelSize::Small)
+                                    .color(color),
+                            )
+                        }),
+                ),
         )
     }
 
@@ -432,6 +476,32 @@ impl PickerDelegate for TasksModalDelegate {
     }
 }
 
+/// A short summary of the latest run of a task with dependencies, to display next to the task.
+fn task_graph_progress_label(progress: &TaskGraphProgress) -> (String, Color) {
+    let total_steps = progress.steps.len();
+    let succeeded_steps = progress.succeeded_steps();
+    if let Some(failed_step) = progress.failed_step() {
+        (format!("`{}` failed", failed_step.label), Color::Error)
+    } else if progress.is_running() {
+        let running_step = progress
+            .steps
+            .iter()
+            .find(|step| step.status == TaskGraphStepStatus::Running);
+        let text = match running_step {
+            Some(step) => format!("{succeeded_steps}/{total_steps}, running `{}`", step.label),
+            None => format!("{succeeded_steps}/{total_steps}"),
+        };
+        (text, Color::Muted)
+    } else {
+        let color = if succeeded_steps == total_steps {
+            Color::Success
+        } else {
+            Color::Warning
+        };
+        (format!("{succeeded_steps}/{total_steps} done"), color)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{path::PathBuf, sync::Arc};
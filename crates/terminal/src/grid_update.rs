@@ -0,0 +1,479 @@
+//! Sharing a terminal's grid with collaborators.
+//!
+//! The host of a shared project sends the changes to a terminal's grid as
+//! [`TerminalGridUpdate`]s, and its guests apply them to a remote terminal that
+//! has no process of its own.
+
+use crate::{Terminal, TerminalBackend, TerminalBuilder, TerminalSize, ZedListener};
+use alacritty_terminal::{
+    grid::Dimensions,
+    index::{Column, Line, Point as AlacPoint},
+    sync::FairMutex,
+    term::{cell::Cell, Config, RenderableCursor, TermMode},
+    vte::ansi::{Color, CursorShape},
+    Term,
+};
+use collections::HashMap;
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use gpui::ModelContext;
+use std::{cmp::Reverse, sync::Arc};
+
+/// Changes to a terminal's grid since the previous update.
+#[derive(Clone, Debug)]
+pub struct TerminalGridUpdate {
+    /// Whether this update replaces the whole grid, including the scrollback.
+    pub is_full: bool,
+    pub screen_lines: usize,
+    pub columns: usize,
+    /// The number of lines that scrolled off the top of the screen into the
+    /// scrollback since the previous update.
+    pub scrolled_lines: usize,
+    /// The changed lines, after scrolling.
+    pub lines: Vec<TerminalLineUpdate>,
+    pub cursor: RenderableCursor,
+    pub mode: TermMode,
+    pub title: String,
+}
+
+/// The contents of one line of a terminal's grid.
+#[derive(Clone, Debug)]
+pub struct TerminalLineUpdate {
+    /// The line's index from the top of the screen, negative for lines in the scrollback.
+    pub line: i32,
+    pub cells: Vec<Cell>,
+}
+
+/// The grid of a terminal as of the last update taken from it.
+pub(crate) struct GridSyncState {
+    screen_lines: usize,
+    columns: usize,
+    history_size: usize,
+    alt_screen: bool,
+    lines: Vec<Vec<Cell>>,
+    cursor: (AlacPoint, CursorShape),
+    mode: TermMode,
+    title: String,
+}
+
+pub(crate) struct RemoteTerminalState {
+    input_tx: UnboundedSender<Vec<u8>>,
+    pub(crate) can_write: bool,
+    pub(crate) title: String,
+    pub(crate) cursor: RenderableCursor,
+    pub(crate) mode: TermMode,
+    /// Whether a full update has been applied, before which incremental ones are ignored.
+    has_full_grid: bool,
+}
+
+impl RemoteTerminalState {
+    pub(crate) fn write(&self, input: Vec<u8>) {
+        if self.can_write {
+            self.input_tx.unbounded_send(input).ok();
+        }
+    }
+}
+
+/// The size of a grid in lines and columns, for resizing a remote terminal to match the host's.
+struct GridSize {
+    screen_lines: usize,
+    columns: usize,
+}
+
+impl Dimensions for GridSize {
+    fn total_lines(&self) -> usize {
+        self.screen_lines
+    }
+
+    fn screen_lines(&self) -> usize {
+        self.screen_lines
+    }
+
+    fn columns(&self) -> usize {
+        self.columns
+    }
+}
+
+impl TerminalBuilder {
+    /// Creates a terminal that mirrors one shared by the host of a remote project.
+    /// Input to the terminal is sent on `input_tx`, once the host allows it.
+    pub fn new_remote(
+        title: String,
+        max_scroll_history_lines: Option<usize>,
+        input_tx: UnboundedSender<Vec<u8>>,
+    ) -> TerminalBuilder {
+        let scrolling_history = max_scroll_history_lines
+            .unwrap_or(crate::DEFAULT_SCROLL_HISTORY_LINES)
+            .min(crate::MAX_SCROLL_HISTORY_LINES);
+        let config = Config {
+            scrolling_history,
+            ..Config::default()
+        };
+        let (events_tx, events_rx) = unbounded();
+        let term = Term::new(config, &TerminalSize::default(), ZedListener(events_tx));
+        let (completion_tx, _) = smol::channel::bounded(1);
+
+        let terminal = Terminal::new(
+            None,
+            TerminalBackend::Remote(RemoteTerminalState {
+                input_tx,
+                can_write: false,
+                title,
+                cursor: RenderableCursor {
+                    shape: CursorShape::Hidden,
+                    point: AlacPoint::new(Line(0), Column(0)),
+                },
+                mode: TermMode::default(),
+                has_full_grid: false,
+            }),
+            completion_tx,
+            Arc::new(FairMutex::new(term)),
+            scrolling_history,
+        );
+
+        TerminalBuilder {
+            terminal,
+            events_rx,
+        }
+    }
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl TerminalBuilder {
+    /// Creates a terminal without a process, to stand in for a local terminal in tests.
+    /// Input to the terminal is sent on `input_tx`.
+    pub fn new_fake(input_tx: UnboundedSender<Vec<u8>>) -> TerminalBuilder {
+        let mut builder = Self::new_remote("Terminal".into(), None, input_tx);
+        if let TerminalBackend::Remote(state) = &mut builder.terminal.backend {
+            state.can_write = true;
+        }
+        builder
+    }
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl Terminal {
+    /// Appends lines to the grid, as if the terminal's process had written them.
+    pub fn append_fake_output(&mut self, lines: &[&str], cx: &mut ModelContext<Self>) {
+        unsafe { crate::append_text_to_term(&mut self.term.lock(), lines) };
+        cx.emit(crate::Event::Wakeup);
+    }
+}
+
+impl Terminal {
+    /// Whether this terminal mirrors one shared by the host of a remote project.
+    pub fn is_remote(&self) -> bool {
+        matches!(self.backend, TerminalBackend::Remote(_))
+    }
+
+    /// Whether input to this terminal reaches its process. For a remote
+    /// terminal, this depends on whether the host allows it.
+    pub fn can_write(&self) -> bool {
+        match &self.backend {
+            TerminalBackend::Local { .. } => true,
+            TerminalBackend::Remote(remote) => remote.can_write,
+        }
+    }
+
+    pub fn set_remote_can_write(&mut self, can_write: bool, cx: &mut ModelContext<Self>) {
+        if let TerminalBackend::Remote(remote) = &mut self.backend {
+            if remote.can_write != can_write {
+                remote.can_write = can_write;
+                cx.notify();
+            }
+        }
+    }
+
+    /// Makes the next update taken from this terminal a full one.
+    pub fn reset_grid_update(&mut self) {
+        self.grid_sync = None;
+    }
+
+    /// Returns the changes to the grid since the last call, or the whole grid
+    /// if this is the first call since [`Terminal::reset_grid_update`]. Returns
+    /// `None` if nothing has changed, unless `force` is true.
+    pub fn take_grid_update(&mut self, force: bool) -> Option<TerminalGridUpdate> {
+        let title = self.title(false);
+        let term = self.term.clone();
+        let term = term.lock_unfair();
+        let grid = term.grid();
+        let screen_lines = grid.screen_lines();
+        let columns = grid.columns();
+        let history_size = grid.history_size();
+        let mode = *term.mode();
+        let alt_screen = mode.contains(TermMode::ALT_SCREEN);
+        let cursor = term.renderable_content().cursor;
+        let row = |line: i32| &grid[Line(line)][..Column(columns)];
+        let current_lines = (0..screen_lines as i32)
+            .map(|line| row(line).to_vec())
+            .collect::<Vec<_>>();
+
+        let previous = self.grid_sync.take().filter(|previous| {
+            previous.screen_lines == screen_lines
+                && previous.columns == columns
+                && previous.alt_screen == alt_screen
+                && previous.history_size <= history_size
+        });
+        let scrolled_lines = previous.as_ref().and_then(|previous| {
+            let added_history = history_size - previous.history_size;
+            if history_size < self.scrolling_history {
+                Some(added_history)
+            } else {
+                // Once the scrollback is full, its size no longer shows how far
+                // the grid scrolled, so look for the previous lines in the grid.
+                find_scrolled_lines(&previous.lines, row, added_history, history_size)
+            }
+        });
+
+        let (is_full, scrolled_lines, lines) = match (&previous, scrolled_lines) {
+            (Some(previous), Some(scrolled_lines)) => {
+                let first_line = -(scrolled_lines.min(history_size) as i32);
+                let lines = (first_line..screen_lines as i32)
+                    .filter(|line| {
+                        let previous_line = usize::try_from(line + scrolled_lines as i32)
+                            .ok()
+                            .and_then(|ix| previous.lines.get(ix));
+                        previous_line.map(Vec::as_slice) != Some(row(*line))
+                    })
+                    .map(|line| TerminalLineUpdate {
+                        line,
+                        cells: row(line).to_vec(),
+                    })
+                    .collect::<Vec<_>>();
+                (false, scrolled_lines, lines)
+            }
+            _ => {
+                let lines = (-(history_size as i32)..screen_lines as i32)
+                    .map(|line| TerminalLineUpdate {
+                        line,
+                        cells: row(line).to_vec(),
+                    })
+                    .collect();
+                (true, 0, lines)
+            }
+        };
+
+        let has_changes = is_full
+            || previous.as_ref().map_or(true, |previous| {
+                scrolled_lines > 0
+                    || !lines.is_empty()
+                    || previous.cursor != (cursor.point, cursor.shape)
+                    || previous.mode != mode
+                    || previous.title != title
+            });
+
+        self.grid_sync = Some(GridSyncState {
+            screen_lines,
+            columns,
+            history_size,
+            alt_screen,
+            lines: current_lines,
+            cursor: (cursor.point, cursor.shape),
+            mode,
+            title: title.clone(),
+        });
+
+        (has_changes || force).then_some(TerminalGridUpdate {
+            is_full,
+            screen_lines,
+            columns,
+            scrolled_lines,
+            lines,
+            cursor,
+            mode,
+            title,
+        })
+    }
+
+    /// Applies an update taken from the host's terminal to this remote terminal.
+    pub fn apply_grid_update(&mut self, update: TerminalGridUpdate, cx: &mut ModelContext<Self>) {
+        let TerminalBackend::Remote(remote) = &mut self.backend else {
+            return;
+        };
+        if !update.is_full && !remote.has_full_grid {
+            return;
+        }
+        if !write_grid_update(
+            &mut self.term.lock_unfair(),
+            &update,
+            self.scrolling_history,
+        ) {
+            return;
+        }
+
+        remote.has_full_grid = true;
+        let title_changed = remote.title != update.title;
+        remote.title = update.title;
+        remote.cursor = update.cursor;
+        remote.mode = update.mode;
+        if title_changed {
+            cx.emit(crate::Event::TitleChanged);
+        }
+        cx.emit(crate::Event::Wakeup);
+    }
+}
+
+/// Writes the lines of an update to a grid, returning false if the update
+/// doesn't apply to a grid of this size.
+fn write_grid_update(
+    term: &mut Term<ZedListener>,
+    update: &TerminalGridUpdate,
+    scrolling_history: usize,
+) -> bool {
+    if update.is_full {
+        term.resize(GridSize {
+            screen_lines: update.screen_lines,
+            columns: update.columns,
+        });
+        term.grid_mut().clear_history();
+        let history_lines = update
+            .lines
+            .first()
+            .map_or(0, |line| line.line.min(0).unsigned_abs() as usize);
+        scroll_up(term, history_lines, scrolling_history);
+    } else if term.grid().screen_lines() != update.screen_lines
+        || term.grid().columns() != update.columns
+    {
+        return false;
+    } else {
+        scroll_up(term, update.scrolled_lines, scrolling_history);
+    }
+
+    let grid = term.grid_mut();
+    let history_size = grid.history_size() as i32;
+    let columns = grid.columns();
+    for line in &update.lines {
+        if line.line < -history_size || line.line >= update.screen_lines as i32 {
+            continue;
+        }
+        let row = &mut grid[Line(line.line)];
+        for (column, cell) in line.cells.iter().take(columns).enumerate() {
+            row[Column(column)] = cell.clone();
+        }
+    }
+    let cursor_line = update
+        .cursor
+        .point
+        .line
+        .0
+        .min(update.screen_lines as i32 - 1)
+        .max(0);
+    let cursor_column = update.cursor.point.column.0.min(columns.saturating_sub(1));
+    grid.cursor.point = AlacPoint::new(Line(cursor_line), Column(cursor_column));
+    true
+}
+
+/// Returns how many lines the grid scrolled by, at least `min_scrolled_lines`,
+/// judging by where the lines of the previous screen are now. Returns `None`
+/// if none of them can be found.
+fn find_scrolled_lines<'a>(
+    previous_lines: &[Vec<Cell>],
+    row: impl Fn(i32) -> &'a [Cell],
+    min_scrolled_lines: usize,
+    history_size: usize,
+) -> Option<usize> {
+    let screen_lines = previous_lines.len() as i32;
+    let is_blank = |cells: &[Cell]| cells.iter().all(|cell| cell.c == ' ');
+    // Look near the screen first, since the grid rarely scrolls by more than a screen between updates.
+    let windows = [
+        (2 * screen_lines).min(history_size as i32),
+        history_size as i32,
+    ];
+    for history_lines in windows {
+        let mut matches_by_scrolled_lines = HashMap::<usize, usize>::default();
+        for line in -history_lines..screen_lines {
+            let cells = row(line);
+            if is_blank(cells) {
+                continue;
+            }
+            for (ix, previous_line) in previous_lines.iter().enumerate() {
+                let scrolled_lines = ix as i32 - line;
+                if scrolled_lines >= min_scrolled_lines as i32 && previous_line.as_slice() == cells
+                {
+                    *matches_by_scrolled_lines
+                        .entry(scrolled_lines as usize)
+                        .or_default() += 1;
+                }
+            }
+        }
+        if let Some((scrolled_lines, _)) = matches_by_scrolled_lines
+            .into_iter()
+            .max_by_key(|(scrolled_lines, matches)| (*matches, Reverse(*scrolled_lines)))
+        {
+            return Some(scrolled_lines);
+        }
+    }
+    None
+}
+
+/// Moves the given number of lines from the top of the screen into the scrollback.
+fn scroll_up(term: &mut Term<ZedListener>, lines: usize, scrolling_history: usize) {
+    let screen_lines = term.grid().screen_lines();
+    let lines = lines.min(screen_lines + scrolling_history);
+    if lines > 0 {
+        term.grid_mut()
+            .scroll_up::<Color>(&(Line(0)..Line(screen_lines as i32)), lines);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_grid_update;
+    use crate::{append_text_to_term, Terminal, TerminalBuilder};
+    use alacritty_terminal::grid::Dimensions;
+    use futures::channel::mpsc::unbounded;
+
+    fn remote_terminal(max_scroll_history_lines: usize) -> Terminal {
+        let (input_tx, _) = unbounded();
+        TerminalBuilder::new_remote("Terminal".into(), Some(max_scroll_history_lines), input_tx)
+            .terminal
+    }
+
+    fn append_lines(terminal: &Terminal, first_line: usize, count: usize) {
+        let lines = (first_line..first_line + count)
+            .map(|ix| format!("line {ix}"))
+            .collect::<Vec<_>>();
+        let lines = lines.iter().map(String::as_str).collect::<Vec<_>>();
+        unsafe { append_text_to_term(&mut terminal.term.lock(), &lines) };
+    }
+
+    fn sync(host: &mut Terminal, guest: &Terminal) -> usize {
+        let update = host.take_grid_update(true).unwrap();
+        assert!(write_grid_update(
+            &mut guest.term.lock(),
+            &update,
+            guest.scrolling_history
+        ));
+        assert_eq!(guest.text_contents(), host.text_contents());
+        update.lines.len()
+    }
+
+    #[test]
+    fn test_grid_updates() {
+        let mut host = remote_terminal(20);
+        let guest = remote_terminal(20);
+        let screen_lines = host.term.lock().screen_lines();
+
+        append_lines(&host, 0, 3);
+        sync(&mut host, &guest);
+
+        // Unchanged lines aren't sent again.
+        assert_eq!(sync(&mut host, &guest), 0);
+        append_lines(&host, 3, 1);
+        assert!(sync(&mut host, &guest) <= 2);
+
+        // Lines scrolled into the scrollback are kept by the guest.
+        let mut next_line = 4;
+        for count in [screen_lines, 2, 15, 1, screen_lines + 30] {
+            append_lines(&host, next_line, count);
+            next_line += count;
+            sync(&mut host, &guest);
+        }
+
+        // Once the scrollback is full, only the lines that scrolled into view are sent.
+        append_lines(&host, next_line, 2);
+        assert!(sync(&mut host, &guest) <= 3);
+
+        host.term.lock().grid_mut().clear_history();
+        sync(&mut host, &guest);
+    }
+}
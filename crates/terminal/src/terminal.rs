@@ -2,6 +2,7 @@ pub mod mappings;
 
 pub use alacritty_terminal;
 
+mod grid_update;
 mod pty_info;
 pub mod terminal_settings;
 
@@ -18,10 +19,13 @@ use alacritty_terminal::{
         Config, RenderableCursor, TermMode,
     },
     tty::{self, setup_env},
+    vi_mode::ViMotion,
     vte::ansi::{ClearMode, Handler, NamedPrivateMode, PrivateMode, Rgb},
     Term,
 };
 use anyhow::{bail, Result};
+use grid_update::{GridSyncState, RemoteTerminalState};
+pub use grid_update::{TerminalGridUpdate, TerminalLineUpdate};
 
 use futures::{
     channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
@@ -64,7 +68,14 @@ use crate::mappings::{colors::to_alac_rgb, keys::to_esc_str};
 
 actions!(
     terminal,
-    [Clear, Copy, Paste, ShowCharacterPalette, SearchTest,]
+    [
+        Clear,
+        Copy,
+        Paste,
+        ShowCharacterPalette,
+        SearchTest,
+        ToggleViMode,
+    ]
 );
 
 ///Scrolling is unbearably sluggish by default. Alacritty supports a configurable
@@ -126,6 +137,14 @@ enum InternalEvent {
     // Adjusted mouse position, should open
     FindHyperlink(Point<Pixels>, bool),
     Copy,
+    ToggleViMode,
+    ViMotion(ViMotion),
+    // Moves the vi mode cursor along with the viewport
+    ViScroll(AlacScroll),
+    // Starts a selection at the vi mode cursor, or clears it if one of the same type is active
+    ViToggleSelection(SelectionType),
+    // Clears the selection if there is one, and leaves the vi mode otherwise
+    ViEscape,
 }
 
 ///A translation struct for Alacritty to communicate with us from their event loop
@@ -404,30 +423,16 @@ impl TerminalBuilder {
         let pty_tx = event_loop.channel();
         let _io_thread = event_loop.spawn(); // DANGER
 
-        let url_regex = RegexSearch::new(r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`]+"#).unwrap();
-        let word_regex = RegexSearch::new(r#"[\$\+\w.\[\]:/@\-~]+"#).unwrap();
-
-        let terminal = Terminal {
+        let terminal = Terminal::new(
             task,
-            pty_tx: Notifier(pty_tx),
+            TerminalBackend::Local {
+                pty_tx: Notifier(pty_tx),
+                pty_info,
+            },
             completion_tx,
             term,
-            events: VecDeque::with_capacity(10), //Should never get this high.
-            last_content: Default::default(),
-            last_mouse: None,
-            matches: Vec::new(),
-            selection_head: None,
-            pty_info,
-            breadcrumb_text: String::new(),
-            scroll_px: px(0.),
-            last_mouse_position: None,
-            next_link_id: 0,
-            selection_phase: SelectionPhase::Ended,
-            secondary_pressed: false,
-            hovered_word: false,
-            url_regex,
-            word_regex,
-        };
+            scrolling_history,
+        );
 
         Ok(TerminalBuilder {
             terminal,
@@ -560,7 +565,7 @@ pub enum SelectionPhase {
 }
 
 pub struct Terminal {
-    pty_tx: Notifier,
+    backend: TerminalBackend,
     completion_tx: Sender<()>,
     term: Arc<FairMutex<Term<ZedListener>>>,
     events: VecDeque<InternalEvent>,
@@ -572,7 +577,6 @@ pub struct Terminal {
     pub last_content: TerminalContent,
     pub selection_head: Option<AlacPoint>,
     pub breadcrumb_text: String,
-    pub pty_info: PtyProcessInfo,
     scroll_px: Pixels,
     next_link_id: usize,
     selection_phase: SelectionPhase,
@@ -581,6 +585,22 @@ pub struct Terminal {
     url_regex: RegexSearch,
     word_regex: RegexSearch,
     task: Option<TaskState>,
+    vi_mode_enabled: bool,
+    scrolling_history: usize,
+    /// The grid as last shared with collaborators, see [`Terminal::take_grid_update`].
+    grid_sync: Option<GridSyncState>,
+}
+
+/// Where the terminal's output comes from, and where its input goes.
+enum TerminalBackend {
+    /// A process running in a local PTY.
+    Local {
+        pty_tx: Notifier,
+        pty_info: PtyProcessInfo,
+    },
+    /// A terminal shared by the host of a remote project, whose grid is
+    /// updated by [`Terminal::apply_grid_update`].
+    Remote(RemoteTerminalState),
 }
 
 pub struct TaskState {
@@ -619,6 +639,41 @@ impl TaskStatus {
 }
 
 impl Terminal {
+    fn new(
+        task: Option<TaskState>,
+        backend: TerminalBackend,
+        completion_tx: Sender<()>,
+        term: Arc<FairMutex<Term<ZedListener>>>,
+        scrolling_history: usize,
+    ) -> Self {
+        let url_regex = RegexSearch::new(r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`]+"#).unwrap();
+        let word_regex = RegexSearch::new(r#"[\$\+\w.\[\]:/@\-~]+"#).unwrap();
+
+        Terminal {
+            task,
+            backend,
+            completion_tx,
+            term,
+            events: VecDeque::with_capacity(10), //Should never get this high.
+            last_content: Default::default(),
+            last_mouse: None,
+            matches: Vec::new(),
+            selection_head: None,
+            breadcrumb_text: String::new(),
+            scroll_px: px(0.),
+            last_mouse_position: None,
+            next_link_id: 0,
+            selection_phase: SelectionPhase::Ended,
+            secondary_pressed: false,
+            hovered_word: false,
+            url_regex,
+            word_regex,
+            vi_mode_enabled: false,
+            scrolling_history,
+            grid_sync: None,
+        }
+    }
+
     fn process_event(&mut self, event: &AlacTermEvent, cx: &mut ModelContext<Self>) {
         match event {
             AlacTermEvent::Title(title) => {
@@ -654,8 +709,10 @@ impl Terminal {
             AlacTermEvent::Wakeup => {
                 cx.emit(Event::Wakeup);
 
-                if self.pty_info.has_changed() {
-                    cx.emit(Event::TitleChanged);
+                if let TerminalBackend::Local { pty_info, .. } = &mut self.backend {
+                    if pty_info.has_changed() {
+                        cx.emit(Event::TitleChanged);
+                    }
                 }
             }
             AlacTermEvent::ColorRequest(idx, fun_ptr) => {
@@ -673,7 +730,17 @@ impl Terminal {
     }
 
     pub fn get_cwd(&self) -> Option<PathBuf> {
-        self.pty_info.current.as_ref().map(|info| info.cwd.clone())
+        self.local_pty_info()?
+            .current
+            .as_ref()
+            .map(|info| info.cwd.clone())
+    }
+
+    fn local_pty_info(&self) -> Option<&PtyProcessInfo> {
+        match &self.backend {
+            TerminalBackend::Local { pty_info, .. } => Some(pty_info),
+            TerminalBackend::Remote(_) => None,
+        }
     }
 
     ///Takes events from Alacritty and translates them to behavior on this view
@@ -696,9 +763,11 @@ impl Terminal {
 
                 self.last_content.size = new_size;
 
-                self.pty_tx.0.send(Msg::Resize(new_size.into())).ok();
-
-                term.resize(new_size);
+                // The grid of a remote terminal keeps the size of the host's terminal.
+                if let TerminalBackend::Local { pty_tx, .. } = &self.backend {
+                    pty_tx.0.send(Msg::Resize(new_size.into())).ok();
+                    term.resize(new_size);
+                }
             }
             InternalEvent::Clear => {
                 // Clear back buffer
@@ -776,9 +845,89 @@ impl Terminal {
                 }
             }
             InternalEvent::ScrollToAlacPoint(point) => {
-                term.scroll_to_point(*point);
+                if term.mode().contains(TermMode::VI) {
+                    term.vi_goto_point(*point);
+                } else {
+                    term.scroll_to_point(*point);
+                }
                 self.refresh_hovered_word();
             }
+            InternalEvent::ToggleViMode => {
+                // Escape may have left the vi mode since this was queued, so follow the latest
+                // request rather than toggling blindly.
+                if term.mode().contains(TermMode::VI) != self.vi_mode_enabled {
+                    term.toggle_vi_mode();
+                    if !self.vi_mode_enabled {
+                        self.clear_vi_mode_state(term, cx);
+                    }
+                }
+                cx.emit(Event::Wakeup);
+            }
+            InternalEvent::ViEscape => {
+                if term.selection.is_some() {
+                    term.selection = None;
+                    self.selection_head = None;
+                    cx.emit(Event::SelectionsChanged);
+                } else if term.mode().contains(TermMode::VI) {
+                    term.toggle_vi_mode();
+                    self.vi_mode_enabled = false;
+                    self.clear_vi_mode_state(term, cx);
+                    cx.emit(Event::Wakeup);
+                }
+            }
+            InternalEvent::ViMotion(motion) => {
+                term.vi_motion(*motion);
+                self.update_vi_selection_head(term, cx);
+            }
+            InternalEvent::ViScroll(scroll) => {
+                let cursor = term.vi_mode_cursor;
+                let point = match *scroll {
+                    AlacScroll::Top => AlacPoint::new(term.topmost_line(), Column(0)),
+                    AlacScroll::Bottom => AlacPoint::new(term.bottommost_line(), Column(0)),
+                    AlacScroll::Delta(lines) => {
+                        term.scroll_display(AlacScroll::Delta(lines));
+                        cursor.scroll(term, lines).point
+                    }
+                    AlacScroll::PageUp => {
+                        let lines = term.screen_lines() as i32;
+                        term.scroll_display(AlacScroll::PageUp);
+                        cursor.scroll(term, lines).point
+                    }
+                    AlacScroll::PageDown => {
+                        let lines = term.screen_lines() as i32;
+                        term.scroll_display(AlacScroll::PageDown);
+                        cursor.scroll(term, -lines).point
+                    }
+                };
+                term.vi_goto_point(point);
+                self.update_vi_selection_head(term, cx);
+            }
+            InternalEvent::ViToggleSelection(ty) => {
+                let point = term.vi_mode_cursor.point;
+                let active_type = term
+                    .selection
+                    .as_ref()
+                    .filter(|selection| !selection.is_empty())
+                    .map(|selection| selection.ty);
+                match active_type {
+                    Some(active_type) if active_type == *ty => {
+                        term.selection = None;
+                        self.selection_head = None;
+                    }
+                    Some(_) => {
+                        if let Some(selection) = term.selection.as_mut() {
+                            selection.ty = *ty;
+                        }
+                    }
+                    None => {
+                        let mut selection = Selection::new(*ty, point, AlacDirection::Left);
+                        selection.include_all();
+                        term.selection = Some(selection);
+                        self.selection_head = Some(point);
+                    }
+                }
+                cx.emit(Event::SelectionsChanged);
+            }
             InternalEvent::FindHyperlink(position, open) => {
                 let prev_hovered_word = self.last_content.last_hovered_word.take();
 
@@ -888,6 +1037,31 @@ impl Terminal {
         }
     }
 
+    /// Clears the selection and scrolls back to the bottom after leaving the vi mode.
+    fn clear_vi_mode_state(&mut self, term: &mut Term<ZedListener>, cx: &mut ModelContext<Self>) {
+        term.selection = None;
+        term.scroll_display(AlacScroll::Bottom);
+        self.selection_head = None;
+        cx.emit(Event::SelectionsChanged);
+    }
+
+    fn update_vi_selection_head(
+        &mut self,
+        term: &mut Term<ZedListener>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if term.selection.is_some() {
+            self.selection_head = Some(term.vi_mode_cursor.point);
+
+            #[cfg(target_os = "linux")]
+            if let Some(selection_text) = term.selection_to_string() {
+                cx.write_to_primary(ClipboardItem::new(selection_text));
+            }
+
+            cx.emit(Event::SelectionsChanged);
+        }
+    }
+
     fn update_selected_word(
         &mut self,
         prev_word: Option<HoveredWord>,
@@ -969,6 +1143,14 @@ impl Terminal {
         self.set_selection(Some((make_selection(&(start..=end)), end)));
     }
 
+    /// Returns the whole text of the terminal, including its scrollback history.
+    pub fn text_contents(&self) -> String {
+        let term = self.term.lock();
+        let start = AlacPoint::new(term.topmost_line(), Column(0));
+        let end = AlacPoint::new(term.bottommost_line(), term.last_column());
+        term.bounds_to_string(start, end)
+    }
+
     fn set_selection(&mut self, selection: Option<(Selection, AlacPoint)>) {
         self.events
             .push_back(InternalEvent::SetSelection(selection));
@@ -991,30 +1173,136 @@ impl Terminal {
 
     ///Write the Input payload to the tty.
     fn write_to_pty(&self, input: String) {
-        self.pty_tx.notify(input.into_bytes());
+        self.write_bytes_to_pty(input.into_bytes());
     }
 
     fn write_bytes_to_pty(&self, input: Vec<u8>) {
-        self.pty_tx.notify(input);
+        match &self.backend {
+            TerminalBackend::Local { pty_tx, .. } => pty_tx.notify(input),
+            TerminalBackend::Remote(remote) => remote.write(input),
+        }
     }
 
+    /// Writes input to the terminal's process. In the vi mode, the view and selection are left
+    /// alone and the terminal stays in the vi mode, so input typed by the user should be preceded
+    /// by [`Terminal::exit_vi_mode`].
     pub fn input(&mut self, input: String) {
-        self.events
-            .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
-        self.events.push_back(InternalEvent::SetSelection(None));
-
+        self.prepare_for_input();
         self.write_to_pty(input);
     }
 
     pub fn input_bytes(&mut self, input: Vec<u8>) {
-        self.events
-            .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
-        self.events.push_back(InternalEvent::SetSelection(None));
-
+        self.prepare_for_input();
         self.write_bytes_to_pty(input);
     }
 
+    fn prepare_for_input(&mut self) {
+        if !self.vi_mode_enabled {
+            self.events
+                .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
+            self.events.push_back(InternalEvent::SetSelection(None));
+        }
+    }
+
+    pub fn vi_mode_enabled(&self) -> bool {
+        self.vi_mode_enabled
+    }
+
+    /// Enters or leaves the vi mode, used to navigate and select the scrollback with the keyboard.
+    /// Leaving it clears the selection and scrolls back to the bottom of the terminal.
+    pub fn toggle_vi_mode(&mut self) {
+        self.vi_mode_enabled = !self.vi_mode_enabled;
+        self.events.push_back(InternalEvent::ToggleViMode);
+    }
+
+    pub fn exit_vi_mode(&mut self) {
+        if self.vi_mode_enabled {
+            self.toggle_vi_mode();
+        }
+    }
+
+    /// Handles a keystroke while in the vi mode. Keystrokes are never sent to the PTY in this mode.
+    fn vi_keystroke(&mut self, keystroke: &Keystroke) {
+        let modifiers = &keystroke.modifiers;
+        let key = match &keystroke.ime_key {
+            Some(ime_key) if !modifiers.control => ime_key.clone(),
+            _ if modifiers.shift && keystroke.key.len() == 1 => keystroke.key.to_uppercase(),
+            _ => keystroke.key.clone(),
+        };
+
+        // Control chords are never motions, so that they can scroll.
+        let motion = match key.as_str() {
+            _ if modifiers.control => None,
+            "h" | "left" => Some(ViMotion::Left),
+            "j" | "down" => Some(ViMotion::Down),
+            "k" | "up" => Some(ViMotion::Up),
+            "l" | "right" => Some(ViMotion::Right),
+            "w" => Some(ViMotion::SemanticRight),
+            "W" => Some(ViMotion::WordRight),
+            "b" => Some(ViMotion::SemanticLeft),
+            "B" => Some(ViMotion::WordLeft),
+            "e" => Some(ViMotion::SemanticRightEnd),
+            "E" => Some(ViMotion::WordRightEnd),
+            "0" | "home" => Some(ViMotion::First),
+            "$" | "end" => Some(ViMotion::Last),
+            "^" => Some(ViMotion::FirstOccupied),
+            "H" => Some(ViMotion::High),
+            "M" => Some(ViMotion::Middle),
+            "L" => Some(ViMotion::Low),
+            "%" => Some(ViMotion::Bracket),
+            _ => None,
+        };
+        if let Some(motion) = motion {
+            self.events.push_back(InternalEvent::ViMotion(motion));
+            return;
+        }
+
+        let half_page = (self.last_content.size.num_lines() / 2) as i32;
+        let scroll = match key.as_str() {
+            "g" => Some(AlacScroll::Top),
+            "G" => Some(AlacScroll::Bottom),
+            "u" if modifiers.control => Some(AlacScroll::Delta(half_page)),
+            "d" if modifiers.control => Some(AlacScroll::Delta(-half_page)),
+            "b" if modifiers.control => Some(AlacScroll::PageUp),
+            "f" if modifiers.control => Some(AlacScroll::PageDown),
+            "y" if modifiers.control => Some(AlacScroll::Delta(1)),
+            "e" if modifiers.control => Some(AlacScroll::Delta(-1)),
+            "pageup" => Some(AlacScroll::PageUp),
+            "pagedown" => Some(AlacScroll::PageDown),
+            _ => None,
+        };
+        if let Some(scroll) = scroll {
+            self.events.push_back(InternalEvent::ViScroll(scroll));
+            return;
+        }
+
+        match key.as_str() {
+            "v" if modifiers.control => self
+                .events
+                .push_back(InternalEvent::ViToggleSelection(SelectionType::Block)),
+            "v" => self
+                .events
+                .push_back(InternalEvent::ViToggleSelection(SelectionType::Simple)),
+            "V" => self
+                .events
+                .push_back(InternalEvent::ViToggleSelection(SelectionType::Lines)),
+            "y" => {
+                self.events.push_back(InternalEvent::Copy);
+                self.events.push_back(InternalEvent::SetSelection(None));
+            }
+            // Whether there's a selection is only known once the preceding keystrokes are processed.
+            "escape" => self.events.push_back(InternalEvent::ViEscape),
+            "i" => self.toggle_vi_mode(),
+            _ => {}
+        }
+    }
+
     pub fn try_keystroke(&mut self, keystroke: &Keystroke, alt_is_meta: bool) -> bool {
+        if self.vi_mode_enabled {
+            self.vi_keystroke(keystroke);
+            return true;
+        }
+
         let esc = to_esc_str(keystroke, &self.last_content.mode, alt_is_meta);
         if let Some(esc) = esc {
             self.input(esc);
@@ -1053,6 +1341,13 @@ impl Terminal {
         }
 
         self.last_content = Self::make_content(&terminal, &self.last_content);
+        // The vi mode of a remote terminal is local, the rest of its state is the host's.
+        if let TerminalBackend::Remote(remote) = &self.backend {
+            if !self.vi_mode_enabled {
+                self.last_content.cursor = remote.cursor;
+            }
+            self.last_content.mode = remote.mode | (self.last_content.mode & TermMode::VI);
+        }
     }
 
     fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
@@ -1129,7 +1424,7 @@ impl Terminal {
 
             if self.mouse_changed(point, side) {
                 if let Some(bytes) = mouse_moved_report(point, e, self.last_content.mode) {
-                    self.pty_tx.notify(bytes);
+                    self.write_bytes_to_pty(bytes);
                 }
             }
         } else if self.secondary_pressed {
@@ -1208,7 +1503,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, true, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_bytes_to_pty(bytes);
             }
         } else if e.button == MouseButton::Left {
             let position = e.position - origin;
@@ -1260,7 +1555,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, false, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_bytes_to_pty(bytes);
             }
         } else {
             if e.button == MouseButton::Left && setting.copy_on_select {
@@ -1298,7 +1593,7 @@ impl Terminal {
                 if let Some(scrolls) = scroll_report(point, scroll_lines, e, self.last_content.mode)
                 {
                     for scroll in scrolls {
-                        self.pty_tx.notify(scroll);
+                        self.write_bytes_to_pty(scroll);
                     }
                 };
             } else if self
@@ -1307,7 +1602,7 @@ impl Terminal {
                 .contains(TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL)
                 && !e.shift
             {
-                self.pty_tx.notify(alt_scroll(scroll_lines))
+                self.write_bytes_to_pty(alt_scroll(scroll_lines))
             } else {
                 if scroll_lines != 0 {
                     let scroll = AlacScroll::Delta(scroll_lines);
@@ -1364,6 +1659,13 @@ impl Terminal {
 
     pub fn title(&self, truncate: bool) -> String {
         const MAX_CHARS: usize = 25;
+        if let TerminalBackend::Remote(remote) = &self.backend {
+            return if truncate {
+                truncate_and_trailoff(&remote.title, MAX_CHARS)
+            } else {
+                remote.title.clone()
+            };
+        }
         match &self.task {
             Some(task_state) => {
                 if truncate {
@@ -1373,9 +1675,8 @@ impl Terminal {
                 }
             }
             None => self
-                .pty_info
-                .current
-                .as_ref()
+                .local_pty_info()
+                .and_then(|pty_info| pty_info.current.as_ref())
                 .map(|fpi| {
                     let process_file = fpi
                         .cwd
@@ -1521,7 +1822,9 @@ unsafe fn append_text_to_term(term: &mut Term<ZedListener>, text_lines: &[&str])
 
 impl Drop for Terminal {
     fn drop(&mut self) {
-        self.pty_tx.0.send(Msg::Shutdown).ok();
+        if let TerminalBackend::Local { pty_tx, .. } = &self.backend {
+            pty_tx.0.send(Msg::Shutdown).ok();
+        }
     }
 }
 
@@ -1664,13 +1967,100 @@ mod tests {
         index::{Column, Line, Point as AlacPoint},
         term::cell::Cell,
     };
-    use gpui::{point, size, Pixels};
+    use futures::{channel::mpsc::unbounded, StreamExt as _};
+    use gpui::{point, size, Context as _, Keystroke, Pixels, TestAppContext};
     use rand::{distributions::Alphanumeric, rngs::ThreadRng, thread_rng, Rng};
 
     use crate::{
-        content_index_for_mouse, rgb_for_index, IndexedCell, TerminalContent, TerminalSize,
+        content_index_for_mouse, rgb_for_index, IndexedCell, TermMode, TerminalBuilder,
+        TerminalContent, TerminalSize,
     };
 
+    #[gpui::test]
+    async fn test_vi_mode(cx: &mut TestAppContext) {
+        let (input_tx, mut input_rx) = unbounded();
+        let terminal = cx.new_model(|_| TerminalBuilder::new_fake(input_tx).terminal);
+        let keystrokes = |terminal: &mut crate::Terminal, keystrokes: &str| {
+            for keystroke in keystrokes.split(' ') {
+                assert!(terminal.try_keystroke(&Keystroke::parse(keystroke).unwrap(), false));
+            }
+        };
+
+        terminal.update(cx, |terminal, cx| {
+            terminal.append_fake_output(&["hello world", "second line"], cx);
+            terminal.toggle_vi_mode();
+            terminal.sync(cx);
+            assert!(terminal.last_content.mode.contains(TermMode::VI));
+
+            // Motions move the vi mode cursor.
+            keystrokes(terminal, "g j w");
+            terminal.sync(cx);
+            assert_eq!(
+                terminal.term.lock().vi_mode_cursor.point,
+                AlacPoint::new(Line(1), Column(6))
+            );
+
+            // Motions extend the selection, and yanking copies and clears it.
+            keystrokes(terminal, "v e");
+            terminal.sync(cx);
+            assert_eq!(
+                terminal.term.lock().selection_to_string().as_deref(),
+                Some("world")
+            );
+            keystrokes(terminal, "y");
+            terminal.sync(cx);
+            assert!(terminal.last_content.selection.is_none());
+
+            // Control chords scroll rather than move by words. Without scrollback, ctrl-e
+            // moves the vi mode cursor down a line as it scrolls, and ctrl-y back up.
+            let line = terminal.term.lock().vi_mode_cursor.point.line;
+            keystrokes(terminal, "ctrl-e");
+            terminal.sync(cx);
+            assert_eq!(
+                terminal.term.lock().vi_mode_cursor.point,
+                AlacPoint::new(Line(line.0 + 1), Column(0))
+            );
+            keystrokes(terminal, "ctrl-y");
+            terminal.sync(cx);
+            assert_eq!(
+                terminal.term.lock().vi_mode_cursor.point,
+                AlacPoint::new(line, Column(0))
+            );
+        });
+        assert_eq!(
+            cx.read_from_clipboard().map(|item| item.text().clone()),
+            Some("world".to_string())
+        );
+
+        terminal.update(cx, |terminal, cx| {
+            // Escape clears a selection made since the terminal's content was last synced,
+            // rather than leaving the vi mode.
+            keystrokes(terminal, "v escape");
+            terminal.sync(cx);
+            assert!(terminal.last_content.selection.is_none());
+            assert!(terminal.vi_mode_enabled());
+            assert!(terminal.last_content.mode.contains(TermMode::VI));
+
+            // Input reaches the process without leaving the vi mode.
+            terminal.input("ls\n".into());
+            terminal.sync(cx);
+            assert!(terminal.vi_mode_enabled());
+        });
+        assert_eq!(input_rx.next().await, Some(b"ls\n".to_vec()));
+
+        terminal.update(cx, |terminal, cx| {
+            // Escape leaves the vi mode once there's no selection.
+            keystrokes(terminal, "escape");
+            terminal.sync(cx);
+            assert!(!terminal.vi_mode_enabled());
+            assert!(!terminal.last_content.mode.contains(TermMode::VI));
+
+            // Outside the vi mode, keystrokes are input.
+            keystrokes(terminal, "enter");
+        });
+        assert_eq!(input_rx.next().await, Some(b"\r".to_vec()));
+    }
+
     #[test]
     fn test_rgb_for_index() {
         // Test every possible value in the color cube.
@@ -919,6 +919,7 @@ impl InputHandler for TerminalInputHandler {
         cx: &mut WindowContext,
     ) {
         self.terminal.update(cx, |terminal, _| {
+            terminal.exit_vi_mode();
             terminal.input(text.into());
         });
 
@@ -1,21 +1,28 @@
-use std::{ops::ControlFlow, path::PathBuf, sync::Arc};
+use std::{cell::RefCell, ops::ControlFlow, path::PathBuf, rc::Rc, sync::Arc};
 
 use crate::TerminalView;
 use collections::{HashMap, HashSet};
 use db::kvp::KEY_VALUE_STORE;
-use futures::future::join_all;
+use futures::{
+    channel::oneshot,
+    future::{join_all, Shared},
+    FutureExt,
+};
 use gpui::{
     actions, Action, AppContext, AsyncWindowContext, DismissEvent, Entity, EventEmitter,
-    ExternalPaths, FocusHandle, FocusableView, IntoElement, ParentElement, Pixels, Render, Styled,
-    Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+    ExternalPaths, FocusHandle, FocusableView, IntoElement, Model, ParentElement, Pixels, Render,
+    Styled, Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
 };
 use itertools::Itertools;
-use project::{Fs, ProjectEntryId};
+use project::{Fs, Inventory, ProjectEntryId, TaskGraphStepStatus};
 use search::{buffer_search::DivRegistrar, BufferSearchBar};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
-use task::{RevealStrategy, SpawnInTerminal, TaskId};
-use terminal::terminal_settings::{Shell, TerminalDockPosition, TerminalSettings};
+use task::{DependsOrder, RevealStrategy, SpawnInTerminal, TaskId};
+use terminal::{
+    terminal_settings::{Shell, TerminalDockPosition, TerminalSettings},
+    TaskStatus, Terminal,
+};
 use ui::{
     h_flex, ButtonCommon, Clickable, ContextMenu, FluentBuilder, IconButton, IconSize, Selectable,
     Tooltip,
@@ -29,12 +36,16 @@ use workspace::{
     DraggedTab, NewTerminal, Pane, ToggleZoom, Workspace,
 };
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 
 const TERMINAL_PANEL_KEY: &str = "TerminalPanel";
 
 actions!(terminal_panel, [ToggleFocus]);
 
+/// The runs of the tasks in a task graph, by label, so that a task that several tasks of the graph
+/// depend on is only run once.
+type TaskGraphRuns = Rc<RefCell<HashMap<String, Shared<Task<bool>>>>>;
+
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(
         |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
@@ -319,16 +330,220 @@ impl TerminalPanel {
         };
 
         terminal_panel.update(cx, |panel, cx| {
-            panel.add_terminal(
-                Some(action.working_directory.clone()),
-                None,
-                RevealStrategy::Always,
-                cx,
-            )
+            panel
+                .add_terminal(
+                    Some(action.working_directory.clone()),
+                    None,
+                    RevealStrategy::Always,
+                    cx,
+                )
+                .detach_and_log_err(cx)
         });
     }
 
     fn spawn_task(&mut self, spawn_in_terminal: &SpawnInTerminal, cx: &mut ViewContext<Self>) {
+        if spawn_in_terminal.depends_on.is_empty() {
+            self.spawn_single_task(spawn_in_terminal, cx).detach();
+        } else {
+            self.spawn_task_with_dependencies(spawn_in_terminal.clone(), cx);
+        }
+    }
+
+    /// Spawns the task after all of its dependencies, aborting the spawn if any of them fails.
+    /// Progress of the whole task graph is reported into the task inventory.
+    fn spawn_task_with_dependencies(
+        &mut self,
+        spawn_in_terminal: SpawnInTerminal,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(inventory) = self.task_inventory(cx) else {
+            return;
+        };
+        let graph_id = spawn_in_terminal.id.clone();
+        let task_label = spawn_in_terminal.label.clone();
+        inventory.update(cx, |inventory, cx| {
+            inventory.task_graph_started(&spawn_in_terminal, cx)
+        });
+        let task_graph_run = self.spawn_task_graph(
+            graph_id.clone(),
+            inventory.clone(),
+            spawn_in_terminal,
+            TaskGraphRuns::default(),
+            cx,
+        );
+        cx.spawn(|terminal_panel, mut cx| async move {
+            let succeeded = task_graph_run.await;
+            let progress = inventory
+                .update(&mut cx, |inventory, cx| {
+                    inventory.task_graph_finished(&graph_id, cx)
+                })
+                .ok()
+                .flatten();
+            if succeeded {
+                return;
+            }
+            let failed_step = progress
+                .as_ref()
+                .and_then(|progress| progress.failed_step())
+                .map(|step| step.label.clone());
+            let Some(failed_step) = failed_step else {
+                return;
+            };
+            terminal_panel
+                .update(&mut cx, |terminal_panel, cx| {
+                    terminal_panel
+                        .workspace
+                        .update(cx, |workspace, cx| {
+                            workspace.show_error(
+                                &anyhow::anyhow!(
+                                    "Task `{task_label}` was aborted: its dependency `{failed_step}` failed"
+                                ),
+                                cx,
+                            )
+                        })
+                        .ok();
+                })
+                .ok();
+        })
+        .detach();
+    }
+
+    /// Runs the dependencies of the task given (in parallel or in sequence), then the task itself, if all of them succeeded.
+    /// Resolves to whether the task and all of its dependencies finished successfully.
+    /// If the task already runs as a part of the graph, resolves along with that run instead of starting another one.
+    fn spawn_task_graph(
+        &mut self,
+        graph_id: TaskId,
+        inventory: Model<Inventory>,
+        spawn_in_terminal: SpawnInTerminal,
+        runs: TaskGraphRuns,
+        cx: &mut ViewContext<Self>,
+    ) -> Shared<Task<bool>> {
+        if let Some(run) = runs.borrow().get(&spawn_in_terminal.full_label) {
+            return run.clone();
+        }
+        let label = spawn_in_terminal.full_label.clone();
+        let dependency_runs = runs.clone();
+        let run = cx.spawn(|terminal_panel, mut cx| async move {
+            let runs = dependency_runs;
+            let dependencies_succeeded = match spawn_in_terminal.depends_order {
+                DependsOrder::Sequence => {
+                    let mut succeeded = true;
+                    for dependency in &spawn_in_terminal.depends_on {
+                        let Ok(dependency_run) =
+                            terminal_panel.update(&mut cx, |terminal_panel, cx| {
+                                terminal_panel.spawn_task_graph(
+                                    graph_id.clone(),
+                                    inventory.clone(),
+                                    dependency.clone(),
+                                    runs.clone(),
+                                    cx,
+                                )
+                            })
+                        else {
+                            return false;
+                        };
+                        if !dependency_run.await {
+                            succeeded = false;
+                            break;
+                        }
+                    }
+                    succeeded
+                }
+                DependsOrder::Parallel => {
+                    let Ok(dependency_runs) =
+                        terminal_panel.update(&mut cx, |terminal_panel, cx| {
+                            spawn_in_terminal
+                                .depends_on
+                                .iter()
+                                .map(|dependency| {
+                                    terminal_panel.spawn_task_graph(
+                                        graph_id.clone(),
+                                        inventory.clone(),
+                                        dependency.clone(),
+                                        runs.clone(),
+                                        cx,
+                                    )
+                                })
+                                .collect::<Vec<_>>()
+                        })
+                    else {
+                        return false;
+                    };
+                    join_all(dependency_runs)
+                        .await
+                        .into_iter()
+                        .all(|succeeded| succeeded)
+                }
+            };
+            let update_status = |status, cx: &mut AsyncWindowContext| {
+                inventory
+                    .update(cx, |inventory, cx| {
+                        inventory.task_graph_step_updated(
+                            &graph_id,
+                            &spawn_in_terminal.id,
+                            status,
+                            cx,
+                        )
+                    })
+                    .ok();
+            };
+            if !dependencies_succeeded {
+                update_status(TaskGraphStepStatus::Skipped, &mut cx);
+                return false;
+            }
+            // Tasks without a command only group their dependencies.
+            if spawn_in_terminal.command.trim().is_empty() {
+                update_status(TaskGraphStepStatus::Succeeded, &mut cx);
+                return true;
+            }
+
+            update_status(TaskGraphStepStatus::Running, &mut cx);
+            let spawned_terminal = match terminal_panel.update(&mut cx, |terminal_panel, cx| {
+                terminal_panel.spawn_single_task(&spawn_in_terminal, cx)
+            }) {
+                Ok(spawned_terminal) => spawned_terminal.await,
+                Err(_) => None,
+            };
+            let succeeded = match spawned_terminal {
+                Some(terminal) => {
+                    let task_finished = terminal
+                        .update(&mut cx, |terminal, cx| terminal.wait_for_completed_task(cx))
+                        .ok();
+                    if let Some(task_finished) = task_finished {
+                        task_finished.await;
+                    }
+                    terminal
+                        .update(&mut cx, |terminal, _| {
+                            terminal.task().map(|task| task.status)
+                                == Some(TaskStatus::Completed { success: true })
+                        })
+                        .unwrap_or(false)
+                }
+                None => false,
+            };
+            update_status(
+                if succeeded {
+                    TaskGraphStepStatus::Succeeded
+                } else {
+                    TaskGraphStepStatus::Failed
+                },
+                &mut cx,
+            );
+            succeeded
+        });
+        let run = run.shared();
+        runs.borrow_mut().insert(label, run.clone());
+        run
+    }
+
+    /// Spawns the task given in a terminal, ignoring its dependencies.
+    /// Resolves to the terminal the task is run in, once it is spawned.
+    fn spawn_single_task(
+        &mut self,
+        spawn_in_terminal: &SpawnInTerminal,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Option<Model<Terminal>>> {
         let mut spawn_task = spawn_in_terminal.clone();
         // Set up shell args unconditionally, as tasks are always spawned inside of a shell.
         let Some((shell, mut user_args)) = (match TerminalSettings::get_global(cx).shell.clone() {
@@ -336,9 +551,10 @@ impl TerminalPanel {
             Shell::Program(shell) => Some((shell, Vec::new())),
             Shell::WithArguments { program, args } => Some((program, args)),
         }) else {
-            return;
+            return Task::ready(None);
         };
 
+        spawn_task.depends_on.clear();
         spawn_task.command_label = format!("{shell} -i -c `{}`", spawn_task.command_label);
         let task_command = std::mem::replace(&mut spawn_task.command, shell);
         let task_args = std::mem::take(&mut spawn_task.args);
@@ -359,14 +575,14 @@ impl TerminalPanel {
         let use_new_terminal = spawn_in_terminal.use_new_terminal;
 
         if allow_concurrent_runs && use_new_terminal {
-            self.spawn_in_new_terminal(spawn_task, working_directory, cx);
-            return;
+            let new_terminal = self.spawn_in_new_terminal(spawn_task, working_directory, cx);
+            return cx.spawn(|_, _| async move { new_terminal.await.log_err() });
         }
 
         let terminals_for_task = self.terminals_for_task(&spawn_in_terminal.full_label, cx);
         if terminals_for_task.is_empty() {
-            self.spawn_in_new_terminal(spawn_task, working_directory, cx);
-            return;
+            let new_terminal = self.spawn_in_new_terminal(spawn_task, working_directory, cx);
+            return cx.spawn(|_, _| async move { new_terminal.await.log_err() });
         }
         let (existing_item_index, existing_terminal) = terminals_for_task
             .last()
@@ -377,37 +593,41 @@ impl TerminalPanel {
                 !use_new_terminal,
                 "Should have handled 'allow_concurrent_runs && use_new_terminal' case above"
             );
-            self.replace_terminal(
+            Task::ready(self.replace_terminal(
                 working_directory,
                 spawn_task,
                 existing_item_index,
                 existing_terminal,
                 cx,
-            );
+            ))
         } else {
+            let (new_terminal_tx, new_terminal_rx) = oneshot::channel();
             self.deferred_tasks.insert(
                 spawn_in_terminal.id.clone(),
                 cx.spawn(|terminal_panel, mut cx| async move {
                     wait_for_terminals_tasks(terminals_for_task, &mut cx).await;
-                    terminal_panel
-                        .update(&mut cx, |terminal_panel, cx| {
-                            if use_new_terminal {
-                                terminal_panel.spawn_in_new_terminal(
-                                    spawn_task,
-                                    working_directory,
-                                    cx,
-                                );
-                            } else {
-                                terminal_panel.replace_terminal(
-                                    working_directory,
-                                    spawn_task,
-                                    existing_item_index,
-                                    existing_terminal,
-                                    cx,
-                                );
-                            }
-                        })
-                        .ok();
+                    let Ok(new_terminal) = terminal_panel.update(&mut cx, |terminal_panel, cx| {
+                        if use_new_terminal {
+                            terminal_panel.spawn_in_new_terminal(spawn_task, working_directory, cx)
+                        } else {
+                            Task::ready(
+                                terminal_panel
+                                    .replace_terminal(
+                                        working_directory,
+                                        spawn_task,
+                                        existing_item_index,
+                                        existing_terminal,
+                                        cx,
+                                    )
+                                    .context("replacing the task terminal"),
+                            )
+                        }
+                    }) else {
+                        return;
+                    };
+                    if let Some(new_terminal) = new_terminal.await.log_err() {
+                        new_terminal_tx.send(new_terminal).ok();
+                    }
                 }),
             );
 
@@ -424,6 +644,7 @@ impl TerminalPanel {
                 }
                 RevealStrategy::Never => {}
             }
+            cx.spawn(|_, _| async move { new_terminal_rx.await.ok() })
         }
     }
 
@@ -432,9 +653,9 @@ impl TerminalPanel {
         spawn_task: SpawnInTerminal,
         working_directory: Option<PathBuf>,
         cx: &mut ViewContext<Self>,
-    ) {
+    ) -> Task<Result<Model<Terminal>>> {
         let reveal = spawn_task.reveal;
-        self.add_terminal(working_directory, Some(spawn_task), reveal, cx);
+        self.add_terminal(working_directory, Some(spawn_task), reveal, cx)
     }
 
     /// Create a new Terminal in the current working directory or the user's home directory
@@ -449,9 +670,21 @@ impl TerminalPanel {
 
         terminal_panel.update(cx, |this, cx| {
             this.add_terminal(None, None, RevealStrategy::Always, cx)
+                .detach_and_log_err(cx)
         });
     }
 
+    fn task_inventory(&self, cx: &AppContext) -> Option<Model<Inventory>> {
+        let workspace = self.workspace.upgrade()?;
+        let inventory = workspace
+            .read(cx)
+            .project()
+            .read(cx)
+            .task_inventory()
+            .clone();
+        Some(inventory)
+    }
+
     fn terminals_for_task(
         &self,
         label: &str,
@@ -485,19 +718,19 @@ impl TerminalPanel {
         spawn_task: Option<SpawnInTerminal>,
         reveal_strategy: RevealStrategy,
         cx: &mut ViewContext<Self>,
-    ) {
+    ) -> Task<Result<Model<Terminal>>> {
         let workspace = self.workspace.clone();
         self.pending_terminals_to_add += 1;
 
         cx.spawn(|terminal_panel, mut cx| async move {
             let pane = terminal_panel.update(&mut cx, |this, _| this.pane.clone())?;
-            workspace.update(&mut cx, |workspace, cx| {
+            let result = workspace.update(&mut cx, |workspace, cx| {
                 if workspace.project().read(cx).is_remote() {
                     workspace.show_error(
                         &anyhow::anyhow!("Cannot open terminals on remote projects (yet!)"),
                         cx,
                     );
-                    return;
+                    anyhow::bail!("Cannot open terminals on remote projects");
                 };
 
                 let working_directory = if let Some(working_directory) = working_directory {
@@ -509,14 +742,13 @@ impl TerminalPanel {
                 };
 
                 let window = cx.window_handle();
-                if let Some(terminal) = workspace.project().update(cx, |project, cx| {
-                    project
-                        .create_terminal(working_directory, spawn_task, window, cx)
-                        .log_err()
-                }) {
-                    let terminal = Box::new(cx.new_view(|cx| {
+                let terminal = workspace.project().update(cx, |project, cx| {
+                    project.create_terminal(working_directory, spawn_task, window, cx)
+                });
+                if let Ok(terminal) = &terminal {
+                    let terminal_view = Box::new(cx.new_view(|cx| {
                         TerminalView::new(
-                            terminal,
+                            terminal.clone(),
                             workspace.weak_handle(),
                             workspace.database_id(),
                             cx,
@@ -524,20 +756,20 @@ impl TerminalPanel {
                     }));
                     pane.update(cx, |pane, cx| {
                         let focus = pane.has_focus(cx);
-                        pane.add_item(terminal, true, focus, None, cx);
+                        pane.add_item(terminal_view, true, focus, None, cx);
                     });
                 }
                 if reveal_strategy == RevealStrategy::Always {
                     workspace.focus_panel::<Self>(cx);
                 }
+                terminal
             })?;
             terminal_panel.update(&mut cx, |this, cx| {
                 this.pending_terminals_to_add = this.pending_terminals_to_add.saturating_sub(1);
                 this.serialize(cx)
             })?;
-            anyhow::Ok(())
+            result
         })
-        .detach_and_log_err(cx);
     }
 
     fn serialize(&mut self, cx: &mut ViewContext<Self>) {
@@ -591,7 +823,7 @@ impl TerminalPanel {
         terminal_item_index: usize,
         terminal_to_replace: View<TerminalView>,
         cx: &mut ViewContext<'_, Self>,
-    ) -> Option<()> {
+    ) -> Option<Model<Terminal>> {
         let project = self
             .workspace
             .update(cx, |workspace, _| workspace.project().clone())
@@ -604,7 +836,7 @@ impl TerminalPanel {
                 .log_err()
         })?;
         terminal_to_replace.update(cx, |terminal_to_replace, cx| {
-            terminal_to_replace.set_terminal(new_terminal, cx);
+            terminal_to_replace.set_terminal(new_terminal.clone(), cx);
         });
 
         match reveal {
@@ -621,7 +853,7 @@ impl TerminalPanel {
             RevealStrategy::Never => {}
         }
 
-        Some(())
+        Some(new_terminal)
     }
 
     pub fn pane(&self) -> &View<Pane> {
@@ -659,6 +891,7 @@ fn add_paths_to_terminal(pane: &mut Pane, paths: &[PathBuf], cx: &mut ViewContex
         new_text.push(' ');
         terminal_view.update(cx, |terminal_view, cx| {
             terminal_view.terminal().update(cx, |terminal, _| {
+                terminal.exit_vi_mode();
                 terminal.paste(&new_text);
             });
         });
@@ -744,7 +977,8 @@ impl Panel for TerminalPanel {
 
     fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
         if active && self.has_no_terminals(cx) {
-            self.add_terminal(None, None, RevealStrategy::Never, cx);
+            self.add_terminal(None, None, RevealStrategy::Never, cx)
+                .detach_and_log_err(cx);
         }
     }
 
@@ -2,6 +2,7 @@ mod persistence;
 pub mod terminal_element;
 pub mod terminal_panel;
 
+use client::proto::{self, PeerId};
 use collections::HashSet;
 use editor::{scroll::Autoscroll, Editor};
 use futures::{stream::FuturesUnordered, StreamExt};
@@ -21,16 +22,17 @@ use terminal::{
     },
     terminal_settings::{TerminalBlink, TerminalSettings, WorkingDirectory},
     Clear, Copy, Event, MaybeNavigationTarget, Paste, ShowCharacterPalette, TaskStatus, Terminal,
+    ToggleViMode,
 };
 use terminal_element::TerminalElement;
 use ui::{h_flex, prelude::*, ContextMenu, Icon, IconName, Label};
 use util::{paths::PathLikeWithPosition, ResultExt};
 use workspace::{
-    item::{BreadcrumbText, Item, ItemEvent, TabContentParams},
+    item::{BreadcrumbText, FollowEvent, FollowableItem, Item, ItemEvent, TabContentParams},
     notifications::NotifyResultExt,
-    register_deserializable_item,
+    register_deserializable_item, register_followable_item,
     searchable::{SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
-    CloseActiveItem, NewCenterTerminal, OpenVisible, Pane, ToolbarItemLocation, Workspace,
+    CloseActiveItem, NewCenterTerminal, OpenVisible, Pane, ToolbarItemLocation, ViewId, Workspace,
     WorkspaceId,
 };
 
@@ -70,6 +72,7 @@ pub fn init(cx: &mut AppContext) {
     terminal::init(cx);
 
     register_deserializable_item::<TerminalView>(cx);
+    register_followable_item::<TerminalView>(cx);
 
     cx.observe_new_views(|workspace: &mut Workspace, _| {
         workspace.register_action(TerminalView::deploy);
@@ -92,6 +95,7 @@ pub struct TerminalView {
     can_navigate_to_selected_word: bool,
     workspace_id: WorkspaceId,
     show_title: bool,
+    remote_id: Option<ViewId>,
     _subscriptions: Vec<Subscription>,
     _terminal_subscriptions: Vec<Subscription>,
 }
@@ -168,6 +172,7 @@ impl TerminalView {
             can_navigate_to_selected_word: false,
             workspace_id,
             show_title: TerminalSettings::get_global(cx).toolbar.title,
+            remote_id: None,
             _subscriptions: vec![
                 focus_in,
                 focus_out,
@@ -195,9 +200,45 @@ impl TerminalView {
         position: gpui::Point<Pixels>,
         cx: &mut ViewContext<Self>,
     ) {
+        let sharing = self.terminal_sharing(cx);
+        let writers = self.shared_terminal_writers(cx);
+        let terminal = self.terminal.clone();
         let context_menu = ContextMenu::build(cx, |menu, _| {
-            menu.action("Clear", Box::new(Clear))
-                .action("Close", Box::new(CloseActiveItem { save_intent: None }))
+            let mut menu = menu
+                .action("Clear", Box::new(Clear))
+                .action("Close", Box::new(CloseActiveItem { save_intent: None }));
+            if let Some((project, is_shared)) = sharing {
+                let terminal = terminal.clone();
+                menu = menu.separator().toggleable_entry(
+                    "Share With Collaborators",
+                    is_shared,
+                    None,
+                    move |cx| {
+                        project.update(cx, |project, cx| {
+                            if is_shared {
+                                project.unshare_terminal(&terminal, cx);
+                            } else {
+                                project.share_terminal(&terminal, cx).log_err();
+                            }
+                        })
+                    },
+                );
+            }
+            if let Some((project, collaborators)) = writers {
+                if !collaborators.is_empty() {
+                    menu = menu.separator().header("Collaborators Can Type");
+                }
+                for (user_id, login, can_write) in collaborators {
+                    let project = project.clone();
+                    let terminal = terminal.clone();
+                    menu = menu.toggleable_entry(login, can_write, None, move |cx| {
+                        project.update(cx, |project, cx| {
+                            project.set_shared_terminal_writer(&terminal, user_id, !can_write, cx)
+                        })
+                    });
+                }
+            }
+            menu
         });
 
         cx.focus_view(&context_menu);
@@ -215,6 +256,47 @@ impl TerminalView {
         self.context_menu = Some((context_menu, position, subscription));
     }
 
+    /// Returns the project, and whether this terminal is shared with its collaborators,
+    /// if this terminal belongs to the host of a shared project.
+    fn terminal_sharing(&self, cx: &AppContext) -> Option<(Model<Project>, bool)> {
+        let workspace = self.workspace.upgrade()?;
+        let project = workspace.read(cx).project().clone();
+        if self.terminal.read(cx).is_remote() || !project.read(cx).is_shared() {
+            return None;
+        }
+        let is_shared = project.read(cx).is_terminal_shared(&self.terminal);
+        Some((project, is_shared))
+    }
+
+    /// Returns the project's collaborators, and whether each of them is allowed
+    /// to write to this terminal, if the terminal is shared by this project.
+    fn shared_terminal_writers(
+        &self,
+        cx: &AppContext,
+    ) -> Option<(Model<Project>, Vec<(u64, SharedString, bool)>)> {
+        let workspace = self.workspace.upgrade()?;
+        let workspace = workspace.read(cx);
+        let project = workspace.project().clone();
+        let writer_ids = project.read(cx).shared_terminal_writers(&self.terminal)?;
+        let user_store = workspace.user_store().read(cx);
+        let mut collaborators = project
+            .read(cx)
+            .collaborators()
+            .values()
+            .filter_map(|collaborator| {
+                let user = user_store.get_cached_user(collaborator.user_id)?;
+                Some((
+                    user.id,
+                    SharedString::from(user.github_login.clone()),
+                    writer_ids.contains(&user.id),
+                ))
+            })
+            .collect::<Vec<_>>();
+        collaborators.sort_by(|a, b| a.1.cmp(&b.1));
+        collaborators.dedup_by_key(|(user_id, _, _)| *user_id);
+        Some((project, collaborators))
+    }
+
     fn settings_changed(&mut self, cx: &mut ViewContext<Self>) {
         let settings = TerminalSettings::get_global(cx);
         self.show_title = settings.toolbar.title;
@@ -250,6 +332,11 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn toggle_vi_mode(&mut self, _: &ToggleViMode, cx: &mut ViewContext<Self>) {
+        self.terminal.update(cx, |term, _| term.toggle_vi_mode());
+        cx.notify();
+    }
+
     pub fn should_show_cursor(&self, focused: bool, cx: &mut gpui::ViewContext<Self>) -> bool {
         //Don't blink the cursor when not focused, blinking is disabled, or paused
         if !focused
@@ -326,14 +413,17 @@ impl TerminalView {
     ///Attempt to paste the clipboard into the terminal
     fn paste(&mut self, _: &Paste, cx: &mut ViewContext<Self>) {
         if let Some(item) = cx.read_from_clipboard() {
-            self.terminal
-                .update(cx, |terminal, _cx| terminal.paste(item.text()));
+            self.terminal.update(cx, |terminal, _cx| {
+                terminal.exit_vi_mode();
+                terminal.paste(item.text());
+            });
         }
     }
 
     fn send_text(&mut self, text: &SendText, cx: &mut ViewContext<Self>) {
         self.clear_bell(cx);
         self.terminal.update(cx, |term, _| {
+            term.exit_vi_mode();
             term.input(text.0.to_string());
         });
     }
@@ -361,6 +451,9 @@ impl TerminalView {
             },
         );
 
+        if self.terminal.read(cx).vi_mode_enabled() {
+            dispatch_context.add("vi_mode");
+        }
         if mode.contains(TermMode::APP_CURSOR) {
             dispatch_context.add("DECCKM");
         }
@@ -743,6 +836,7 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::clear))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
+            .on_action(cx.listener(TerminalView::toggle_vi_mode))
             .on_key_down(cx.listener(Self::key_down))
             .on_mouse_down(
                 MouseButton::Right,
@@ -921,6 +1015,87 @@ impl Item for TerminalView {
     }
 }
 
+impl FollowableItem for TerminalView {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant> {
+        let workspace = self.workspace.upgrade()?;
+        let terminal_id = workspace
+            .read(cx)
+            .project()
+            .read(cx)
+            .shared_terminal_id(&self.terminal)?;
+        Some(proto::view::Variant::Terminal(proto::view::Terminal {
+            terminal_id,
+        }))
+    }
+
+    fn from_state_proto(
+        pane: View<Pane>,
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<anyhow::Result<View<Self>>>> {
+        let Some(proto::view::Variant::Terminal(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::Terminal(state)) = state.take() else {
+            unreachable!()
+        };
+
+        let terminal = workspace
+            .read(cx)
+            .project()
+            .read(cx)
+            .shared_terminal(state.terminal_id);
+        let workspace_id = workspace.read(cx).database_id();
+        let workspace = workspace.downgrade();
+        Some(cx.spawn(|mut cx| async move {
+            let terminal = terminal
+                .with_context(|| format!("no shared terminal with id {}", state.terminal_id))?;
+            pane.update(&mut cx, |_, cx| {
+                cx.new_view(|cx| {
+                    let mut view = TerminalView::new(terminal, workspace, workspace_id, cx);
+                    view.remote_id = Some(remote_id);
+                    view
+                })
+            })
+        }))
+    }
+
+    fn to_follow_event(_: &Self::Event) -> Option<FollowEvent> {
+        None
+    }
+
+    // The terminal's contents are synchronized by the project, so its views have no updates of their own.
+    fn add_event_to_update_proto(
+        &self,
+        _: &Self::Event,
+        _: &mut Option<proto::update_view::Variant>,
+        _: &WindowContext,
+    ) -> bool {
+        false
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        _: &Model<Project>,
+        _: proto::update_view::Variant,
+        _: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        Task::ready(Ok(()))
+    }
+
+    fn is_project_item(&self, _: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, _: Option<PeerId>, _: &mut ViewContext<Self>) {}
+}
+
 impl SearchableItem for TerminalView {
     type Match = RangeInclusive<Point>;
 
@@ -987,6 +1162,8 @@ impl SearchableItem for TerminalView {
                 .unwrap()),
             ),
             SearchQuery::Regex { .. } => regex_search_for_query(&query),
+            // Terminal output has no syntax tree to match against.
+            SearchQuery::Structural { .. } => None,
         };
 
         if let Some(s) = searcher {
@@ -0,0 +1,380 @@
+use anyhow::{anyhow, Result};
+use std::{borrow::Cow, fmt};
+
+const BOM_SUFFIX: &str = " with BOM";
+
+/// A character encoding of a file on disk.
+///
+/// Buffers always hold UTF-8 text: file contents are decoded when the file is loaded,
+/// and encoded back into the same encoding when the buffer is saved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Encoding {
+    encoding: &'static encoding_rs::Encoding,
+    has_bom: bool,
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Self::UTF8
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.encoding.name())?;
+        if self.has_bom {
+            write!(f, "{BOM_SUFFIX}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Encoding {
+    pub const UTF8: Self = Self {
+        encoding: encoding_rs::UTF_8,
+        has_bom: false,
+    };
+
+    /// Encodings that files can be reopened and saved with.
+    pub fn all() -> Vec<Self> {
+        let with_bom = |encoding| Self {
+            encoding,
+            has_bom: true,
+        };
+        let mut all = vec![
+            Self::UTF8,
+            with_bom(encoding_rs::UTF_8),
+            with_bom(encoding_rs::UTF_16LE),
+            with_bom(encoding_rs::UTF_16BE),
+        ];
+        all.extend(
+            [
+                encoding_rs::UTF_16LE,
+                encoding_rs::UTF_16BE,
+                encoding_rs::WINDOWS_1252,
+                encoding_rs::ISO_8859_2,
+                encoding_rs::ISO_8859_15,
+                encoding_rs::WINDOWS_1250,
+                encoding_rs::WINDOWS_1251,
+                encoding_rs::KOI8_R,
+                encoding_rs::WINDOWS_1253,
+                encoding_rs::WINDOWS_1254,
+                encoding_rs::WINDOWS_1255,
+                encoding_rs::WINDOWS_1256,
+                encoding_rs::WINDOWS_1257,
+                encoding_rs::WINDOWS_1258,
+                encoding_rs::WINDOWS_874,
+                encoding_rs::SHIFT_JIS,
+                encoding_rs::EUC_JP,
+                encoding_rs::ISO_2022_JP,
+                encoding_rs::GBK,
+                encoding_rs::GB18030,
+                encoding_rs::BIG5,
+                encoding_rs::EUC_KR,
+            ]
+            .into_iter()
+            .map(Self::without_bom),
+        );
+        all
+    }
+
+    fn without_bom(encoding: &'static encoding_rs::Encoding) -> Self {
+        Self {
+            encoding,
+            has_bom: false,
+        }
+    }
+
+    /// Looks up the encoding by its name, as displayed by [`Encoding`], or by any of its WHATWG labels.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let (label, has_bom) = match name.strip_suffix(BOM_SUFFIX) {
+            Some(label) => (label, true),
+            None => (name, false),
+        };
+        let encoding = encoding_rs::Encoding::for_label(label.trim().as_bytes())?;
+        Some(Self { encoding, has_bom })
+    }
+
+    /// Whether the encoded text starts with a byte order mark.
+    pub fn has_bom(&self) -> bool {
+        self.has_bom
+    }
+
+    /// Whether the encoded text is the same as the UTF-8 text of the buffer.
+    pub fn is_utf8(&self) -> bool {
+        *self == Self::UTF8
+    }
+
+    /// Guesses the encoding of the bytes given: by the byte order mark, if any, or by heuristics otherwise.
+    /// Falls back to Windows-1252 (a superset of Latin-1), as every byte sequence is valid in it.
+    ///
+    /// The bytes may be only the start of a file, so a UTF-8 sequence cut off at the end is allowed.
+    pub fn detect(bytes: &[u8]) -> Self {
+        if let Some((encoding, _)) = encoding_rs::Encoding::for_bom(bytes) {
+            return Self {
+                encoding,
+                has_bom: true,
+            };
+        }
+        // Zero bytes are valid UTF-8, so UTF-16 has to be checked first.
+        if let Some(encoding) = detect_utf16_without_bom(bytes) {
+            return Self::without_bom(encoding);
+        }
+        match std::str::from_utf8(bytes) {
+            Ok(_) => return Self::UTF8,
+            Err(error) if error.error_len().is_none() => return Self::UTF8,
+            Err(_) => {}
+        }
+        if is_likely_shift_jis(bytes) {
+            return Self::without_bom(encoding_rs::SHIFT_JIS);
+        }
+        Self::without_bom(encoding_rs::WINDOWS_1252)
+    }
+
+    /// Whether the bytes are binary data rather than text, guessed the way git does: by a zero byte
+    /// near the start. Zero bytes are common in UTF-16 text, so it is never considered binary.
+    pub fn is_binary(bytes: &[u8]) -> bool {
+        if encoding_rs::Encoding::for_bom(bytes).is_some()
+            || detect_utf16_without_bom(bytes).is_some()
+        {
+            return false;
+        }
+        bytes[..bytes.len().min(8000)].contains(&0)
+    }
+
+    /// Decodes the bytes into text, replacing malformed sequences with the replacement character.
+    pub fn decode<'a>(&self, bytes: &'a [u8]) -> Cow<'a, str> {
+        let bytes = match encoding_rs::Encoding::for_bom(bytes) {
+            Some((encoding, bom_length)) if encoding == self.encoding => &bytes[bom_length..],
+            _ => bytes,
+        };
+        self.encoding.decode_without_bom_handling(bytes).0
+    }
+
+    /// Creates a decoder for text that is read in chunks, such as a file too large to be read at once.
+    pub fn chunk_decoder(&self) -> ChunkDecoder {
+        ChunkDecoder {
+            decoder: self.encoding.new_decoder_with_bom_removal(),
+        }
+    }
+
+    /// Encodes the text, failing if it contains characters that cannot be represented in this encoding.
+    pub fn encode<'a>(&self, text: &'a str) -> Result<Cow<'a, [u8]>> {
+        let encoded: Cow<'a, [u8]> = if self.encoding == encoding_rs::UTF_16LE {
+            Cow::Owned(text.encode_utf16().flat_map(u16::to_le_bytes).collect())
+        } else if self.encoding == encoding_rs::UTF_16BE {
+            Cow::Owned(text.encode_utf16().flat_map(u16::to_be_bytes).collect())
+        } else {
+            let (encoded, _, has_unmappable_characters) = self.encoding.encode(text);
+            if has_unmappable_characters {
+                return Err(anyhow!(
+                    "text contains characters that cannot be encoded in {}",
+                    self.encoding.name()
+                ));
+            }
+            encoded
+        };
+
+        if self.has_bom {
+            let mut with_bom = self.bom().to_vec();
+            with_bom.extend_from_slice(&encoded);
+            Ok(Cow::Owned(with_bom))
+        } else {
+            Ok(encoded)
+        }
+    }
+
+    fn bom(&self) -> &'static [u8] {
+        if self.encoding == encoding_rs::UTF_16LE {
+            &[0xFF, 0xFE]
+        } else if self.encoding == encoding_rs::UTF_16BE {
+            &[0xFE, 0xFF]
+        } else if self.encoding == encoding_rs::UTF_8 {
+            &[0xEF, 0xBB, 0xBF]
+        } else {
+            &[]
+        }
+    }
+}
+
+/// The error of loading a file as text when its contents are binary data, which no encoding decodes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncodingError;
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the file contains binary data that cannot be decoded as text"
+        )
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+/// Decodes text split into chunks of bytes, keeping the characters that span the chunks' boundaries intact.
+pub struct ChunkDecoder {
+    decoder: encoding_rs::Decoder,
+}
+
+impl ChunkDecoder {
+    /// Decodes the next chunk of bytes, appending the text to `output`.
+    /// The `last` chunk flushes the characters left incomplete by the previous ones.
+    pub fn decode(&mut self, bytes: &[u8], last: bool, output: &mut String) {
+        let max_length = self
+            .decoder
+            .max_utf8_buffer_length(bytes.len())
+            .unwrap_or(bytes.len() * 3 + 4);
+        output.reserve(max_length);
+        self.decoder.decode_to_string(bytes, output, last);
+    }
+}
+
+/// Text in UTF-16 without a byte order mark is guessed by the amount of zero bytes:
+/// ASCII characters have one of their two bytes zeroed, which is rare for any other encoding.
+fn detect_utf16_without_bom(bytes: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    let sample = &bytes[..bytes.len().min(4096)];
+    if sample.len() < 2 || bytes.len() % 2 != 0 {
+        return None;
+    }
+    let pairs = sample.len() / 2;
+    let (mut even_zeros, mut odd_zeros) = (0, 0);
+    for pair in sample.chunks_exact(2) {
+        if pair[0] == 0 {
+            even_zeros += 1;
+        }
+        if pair[1] == 0 {
+            odd_zeros += 1;
+        }
+    }
+    let is_mostly_zeros = |zeros: usize| zeros * 10 >= pairs * 4;
+    let is_rarely_zero = |zeros: usize| zeros * 10 <= pairs;
+    if is_mostly_zeros(odd_zeros) && is_rarely_zero(even_zeros) {
+        Some(encoding_rs::UTF_16LE)
+    } else if is_mostly_zeros(even_zeros) && is_rarely_zero(odd_zeros) {
+        Some(encoding_rs::UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Many Latin-1 texts are valid Shift_JIS too: accented capitals decode as half-width katakana,
+/// and pairs of accented letters as kanji. So Shift_JIS is only guessed for text with at least
+/// two Japanese characters in a row, which single accented letters between ASCII ones never form,
+/// and without the user-defined characters that no Japanese text uses.
+fn is_likely_shift_jis(bytes: &[u8]) -> bool {
+    let (text, has_errors) = encoding_rs::SHIFT_JIS.decode_without_bom_handling(bytes);
+    if has_errors {
+        return false;
+    }
+    // CJK punctuation and kana, ideographs, and full-width forms.
+    let is_japanese = |c: char| {
+        ('\u{3000}'..='\u{30ff}').contains(&c)
+            || ('\u{4e00}'..='\u{9fff}').contains(&c)
+            || ('\u{ff01}'..='\u{ff60}').contains(&c)
+    };
+    let mut has_japanese_run = false;
+    let mut previous_is_japanese = false;
+    for c in text.chars() {
+        if ('\u{e000}'..='\u{f8ff}').contains(&c) {
+            return false;
+        }
+        has_japanese_run |= previous_is_japanese && is_japanese(c);
+        previous_is_japanese = is_japanese(c);
+    }
+    has_japanese_run
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_binary() {
+        assert!(!Encoding::is_binary(b"plain ascii"));
+        assert!(!Encoding::is_binary(b"caf\xe9"));
+        assert!(!Encoding::is_binary(b"\xFF\xFEh\0i\0"));
+        assert!(!Encoding::is_binary(b"\0h\0e\0l\0l\0o"));
+        assert!(Encoding::is_binary(
+            b"\x7fELF\x02\x01\x01\0\0\0\0\0\0\0\0\0"
+        ));
+        assert!(Encoding::is_binary(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"));
+    }
+
+    #[test]
+    fn test_detect_encoding() {
+        assert_eq!(Encoding::detect(b"plain ascii"), Encoding::UTF8);
+        assert_eq!(Encoding::detect("héllo".as_bytes()), Encoding::UTF8);
+        // The start of a file, cut off in the middle of a character.
+        assert_eq!(Encoding::detect(&"hé".as_bytes()[..2]), Encoding::UTF8);
+        assert_eq!(
+            Encoding::detect(b"\xEF\xBB\xBFhello"),
+            Encoding::from_name("UTF-8 with BOM").unwrap()
+        );
+        assert_eq!(
+            Encoding::detect(b"\xFF\xFEh\0i\0"),
+            Encoding::from_name("UTF-16LE with BOM").unwrap()
+        );
+        assert_eq!(
+            Encoding::detect(b"\0h\0e\0l\0l\0o"),
+            Encoding::from_name("UTF-16BE").unwrap()
+        );
+        // "日本語" in Shift_JIS.
+        assert_eq!(
+            Encoding::detect(b"\x93\xfa\x96\x7b\x8c\xea"),
+            Encoding::from_name("Shift_JIS").unwrap()
+        );
+        // "café au lait" in Latin-1.
+        assert_eq!(
+            Encoding::detect(b"caf\xe9 au lait"),
+            Encoding::from_name("windows-1252").unwrap()
+        );
+        // Latin-1 texts that are also valid Shift_JIS: "CAFÉ", "Ärger", "Größe" and "ação".
+        for latin1 in [&b"CAF\xc9"[..], b"\xc4rger", b"Gr\xf6\xdfe", b"a\xe7\xe3o"] {
+            assert_eq!(
+                Encoding::detect(latin1),
+                Encoding::from_name("windows-1252").unwrap(),
+                "{latin1:?}"
+            );
+        }
+        let (japanese, _, _) = encoding_rs::SHIFT_JIS.encode("日本語のテキスト");
+        assert_eq!(
+            Encoding::detect(&japanese),
+            Encoding::from_name("Shift_JIS").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encoding_round_trip() {
+        for (encoding, text) in [
+            ("UTF-8", "héllo wörld"),
+            ("UTF-8 with BOM", "héllo wörld"),
+            ("UTF-16LE with BOM", "héllo wörld"),
+            ("UTF-16BE", "héllo wörld"),
+            ("windows-1252", "héllo wörld"),
+            ("Shift_JIS", "日本語のテキスト"),
+        ] {
+            let encoding = Encoding::from_name(encoding).unwrap();
+            let encoded = encoding.encode(text).unwrap();
+            assert_eq!(encoding.decode(&encoded), text, "{encoding}");
+            assert_eq!(Encoding::from_name(&encoding.to_string()), Some(encoding));
+        }
+
+        let text = "日本語のテキスト, in chunks";
+        for encoding in ["UTF-8 with BOM", "UTF-16BE with BOM", "Shift_JIS"] {
+            let encoding = Encoding::from_name(encoding).unwrap();
+            let encoded = encoding.encode(text).unwrap();
+            let mut decoder = encoding.chunk_decoder();
+            let mut decoded = String::new();
+            for chunk in encoded.chunks(3) {
+                decoder.decode(chunk, false, &mut decoded);
+            }
+            decoder.decode(&[], true, &mut decoded);
+            assert_eq!(decoded, text, "{encoding}");
+        }
+
+        let latin1 = Encoding::from_name("latin1").unwrap();
+        assert_eq!(latin1.to_string(), "windows-1252");
+        assert!(latin1.encode("日本語").is_err());
+    }
+}
@@ -75,6 +75,8 @@ impl ThemeColors {
             editor_invisible: neutral().light().step_10(),
             editor_wrap_guide: neutral().light_alpha().step_7(),
             editor_active_wrap_guide: neutral().light_alpha().step_8(),
+            editor_indent_guide: neutral().light_alpha().step_5(),
+            editor_indent_guide_active: neutral().light_alpha().step_9(),
             editor_document_highlight_read_background: neutral().light_alpha().step_3(),
             editor_document_highlight_write_background: neutral().light_alpha().step_4(),
             terminal_background: neutral().light().step_1(),
@@ -170,6 +172,8 @@ impl ThemeColors {
             editor_invisible: neutral().dark_alpha().step_4(),
             editor_wrap_guide: neutral().dark_alpha().step_4(),
             editor_active_wrap_guide: neutral().dark_alpha().step_4(),
+            editor_indent_guide: neutral().dark_alpha().step_4(),
+            editor_indent_guide_active: neutral().dark_alpha().step_8(),
             editor_document_highlight_read_background: neutral().dark_alpha().step_4(),
             editor_document_highlight_write_background: neutral().dark_alpha().step_4(),
             terminal_background: neutral().dark().step_1(),
@@ -79,6 +79,7 @@ pub enum IconName {
     BellRing,
     BellDot,
     Bolt,
+    Bookmark,
     CaseSensitive,
     Check,
     ChevronDown,
@@ -87,6 +88,7 @@ pub enum IconName {
     ChevronUp,
     ExpandVertical,
     Close,
+    Code,
     Collab,
     Command,
     Control,
@@ -183,6 +185,7 @@ impl IconName {
             IconName::BellRing => "icons/bell_ring.svg",
             IconName::BellDot => "icons/bell_dot.svg",
             IconName::Bolt => "icons/bolt.svg",
+            IconName::Bookmark => "icons/bookmark.svg",
             IconName::CaseSensitive => "icons/case_insensitive.svg",
             IconName::Check => "icons/check.svg",
             IconName::ChevronDown => "icons/chevron_down.svg",
@@ -191,6 +194,7 @@ impl IconName {
             IconName::ChevronUp => "icons/chevron_up.svg",
             IconName::ExpandVertical => "icons/expand_vertical.svg",
             IconName::Close => "icons/x.svg",
+            IconName::Code => "icons/file_icons/code.svg",
             IconName::Collab => "icons/user_group_16.svg",
             IconName::Command => "icons/command.svg",
             IconName::Control => "icons/control.svg",
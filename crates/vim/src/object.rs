@@ -13,7 +13,7 @@ use editor::{
 use itertools::Itertools;
 
 use gpui::{actions, impl_actions, ViewContext, WindowContext};
-use language::{char_kind, BufferSnapshot, CharKind, Point, Selection};
+use language::{char_kind, BufferSnapshot, CharKind, Point, Selection, TextObject};
 use serde::Deserialize;
 use workspace::Workspace;
 
@@ -32,6 +32,9 @@ pub enum Object {
     AngleBrackets,
     Argument,
     Tag,
+    Method,
+    Class,
+    Comment,
 }
 
 #[derive(Clone, Deserialize, PartialEq)]
@@ -57,7 +60,10 @@ actions!(
         CurlyBrackets,
         AngleBrackets,
         Argument,
-        Tag
+        Tag,
+        Method,
+        Class,
+        Comment
     ]
 );
 
@@ -95,6 +101,10 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     });
     workspace
         .register_action(|_: &mut Workspace, _: &Argument, cx: _| object(Object::Argument, cx));
+    workspace.register_action(|_: &mut Workspace, _: &Method, cx: _| object(Object::Method, cx));
+    workspace.register_action(|_: &mut Workspace, _: &Class, cx: _| object(Object::Class, cx));
+    workspace
+        .register_action(|_: &mut Workspace, _: &Comment, cx: _| object(Object::Comment, cx));
 }
 
 fn object(object: Object, cx: &mut WindowContext) {
@@ -122,7 +132,10 @@ impl Object {
             | Object::AngleBrackets
             | Object::CurlyBrackets
             | Object::SquareBrackets
-            | Object::Argument => true,
+            | Object::Argument
+            | Object::Method
+            | Object::Class
+            | Object::Comment => true,
         }
     }
 
@@ -137,7 +150,10 @@ impl Object {
             | Object::SquareBrackets
             | Object::Tag
             | Object::CurlyBrackets
-            | Object::AngleBrackets => true,
+            | Object::AngleBrackets
+            | Object::Method
+            | Object::Class
+            | Object::Comment => true,
         }
     }
 
@@ -160,7 +176,10 @@ impl Object {
             | Object::AngleBrackets
             | Object::VerticalBars
             | Object::Tag
-            | Object::Argument => Mode::Visual,
+            | Object::Argument
+            | Object::Method
+            | Object::Class
+            | Object::Comment => Mode::Visual,
             Object::Paragraph => Mode::VisualLine,
         }
     }
@@ -208,6 +227,33 @@ impl Object {
                 surrounding_markers(map, relative_to, around, self.is_multiline(), '<', '>')
             }
             Object::Argument => argument(map, relative_to, around),
+            Object::Method => text_object(
+                map,
+                selection,
+                if around {
+                    TextObject::AroundFunction
+                } else {
+                    TextObject::InsideFunction
+                },
+            ),
+            Object::Class => text_object(
+                map,
+                selection,
+                if around {
+                    TextObject::AroundClass
+                } else {
+                    TextObject::InsideClass
+                },
+            ),
+            Object::Comment => text_object(
+                map,
+                selection,
+                if around {
+                    TextObject::AroundComment
+                } else {
+                    TextObject::InsideComment
+                },
+            ),
         }
     }
 
@@ -410,6 +456,66 @@ fn around_next_word(
     Some(start..end)
 }
 
+/// Returns the smallest syntax-aware text object of the given kind that surrounds the selection,
+/// as defined by the `textobjects.scm` query of the language at the cursor.
+///
+/// If the selection already covers such an object, the next enclosing one is returned, so that
+/// repeating the object in visual mode expands outwards.
+fn text_object(
+    map: &DisplaySnapshot,
+    selection: Selection<DisplayPoint>,
+    target: TextObject,
+) -> Option<Range<DisplayPoint>> {
+    let snapshot = &map.buffer_snapshot;
+    let start = selection.start.to_offset(map, Bias::Left);
+    let end = selection.end.to_offset(map, Bias::Right);
+
+    // The text objects are defined on the syntax tree, so we operate at the buffer level and map back to the display level
+    let excerpt = snapshot.excerpt_containing(start..end)?;
+    let buffer = excerpt.buffer();
+    let selected = excerpt.map_offset_to_buffer(start)..excerpt.map_offset_to_buffer(end);
+
+    let is_candidate = |range: &Range<usize>| {
+        range.start <= selected.start
+            && selected.end <= range.end
+            // In visual mode the head is moved one character to the left, so an object whose end is
+            // one character past the selection is already selected.
+            && (range.start < selected.start || range.end > selected.end + 1)
+    };
+
+    let find = |target: TextObject, range: Range<usize>| {
+        buffer
+            .text_object_ranges(range)
+            .filter_map(|(range, text_object)| (text_object == target).then_some(range))
+            .filter(|range| is_candidate(range))
+            .min_by_key(|range| range.len())
+    };
+
+    let mut result = find(target, selected.clone());
+
+    // Objects like the inside of a function may not intersect the cursor when it is placed on
+    // the function's signature, so fall back to the inside of the enclosing object.
+    if result.is_none() {
+        if let Some(around) = target.around() {
+            let around_range = find(around, selected.clone())?;
+            result = buffer
+                .text_object_ranges(around_range.clone())
+                .filter_map(|(range, text_object)| (text_object == target).then_some(range))
+                .filter(|range| around_range.start <= range.start && range.end <= around_range.end)
+                .min_by_key(|range| range.start)
+                .or(Some(around_range));
+        }
+    }
+
+    let result = result?;
+    if excerpt.contains_buffer_range(result.clone()) {
+        let result = excerpt.map_range_from_buffer(result);
+        Some(result.start.to_display_point(map)..result.end.to_display_point(map))
+    } else {
+        None
+    }
+}
+
 fn argument(
     map: &DisplaySnapshot,
     relative_to: DisplayPoint,
@@ -1613,6 +1719,121 @@ mod test {
         cx.assert_state("let a = [«test::call(first_arg)ˇ»]", Mode::Visual);
     }
 
+    #[gpui::test]
+    async fn test_syntax_text_objects(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        // Inside function
+        cx.set_state(
+            indoc! {"
+                fn boop() {
+                    let a = 1;
+                    ˇa + 1
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["c", "i", "f"]);
+        cx.assert_state(
+            indoc! {"
+                fn boop() {
+                    ˇ
+                }"},
+            Mode::Insert,
+        );
+
+        // Inside function, cursor on the signature
+        cx.set_state(
+            indoc! {"
+                fn ˇboop() {
+                    1
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["c", "i", "f"]);
+        cx.assert_state(
+            indoc! {"
+                fn boop() {
+                    ˇ
+                }"},
+            Mode::Insert,
+        );
+
+        // Around function
+        cx.set_state(
+            indoc! {"
+                impl Foo {
+                    fn ˇboop() {
+                        1
+                    }
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["v", "a", "f"]);
+        cx.assert_state(
+            indoc! {"
+                impl Foo {
+                    «fn boop() {
+                        1
+                    }ˇ»
+                }"},
+            Mode::Visual,
+        );
+
+        // Around class
+        cx.set_state(
+            indoc! {"
+                impl Foo {
+                    fn ˇboop() {
+                        1
+                    }
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["v", "a", "c"]);
+        cx.assert_state(
+            indoc! {"
+                «impl Foo {
+                    fn boop() {
+                        1
+                    }
+                }ˇ»"},
+            Mode::Visual,
+        );
+
+        // Comments
+        cx.set_state(
+            indoc! {"
+                // one
+                // ˇtwo
+                fn boop() {}"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["v", "i", "g", "c"]);
+        cx.assert_state(
+            indoc! {"
+                // one
+                «// twoˇ»
+                fn boop() {}"},
+            Mode::Visual,
+        );
+
+        cx.set_state(
+            indoc! {"
+                // one
+                // ˇtwo
+                fn boop() {}"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["v", "a", "g", "c"]);
+        cx.assert_state(
+            indoc! {"
+                «// one
+                // twoˇ»
+                fn boop() {}"},
+            Mode::Visual,
+        );
+    }
+
     #[gpui::test]
     async fn test_delete_surrounding_character_objects(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -1,4 +1,5 @@
 use crate::{Toast, Workspace};
+use client::{ErrorCode, ErrorExt};
 use collections::HashMap;
 use gpui::{
     svg, AnyView, AppContext, AsyncWindowContext, ClipboardItem, DismissEvent, Entity, EntityId,
@@ -7,7 +8,7 @@ use gpui::{
 };
 use language::DiagnosticSeverity;
 
-use std::{any::TypeId, ops::DerefMut};
+use std::{any::TypeId, ops::DerefMut, time::Duration};
 use ui::{prelude::*, Tooltip};
 use util::ResultExt;
 
@@ -511,6 +512,17 @@ where
     }
 }
 
+/// The message to show when the server has rate limited a request.
+pub fn rate_limit_message(retry_after: Option<Duration>) -> String {
+    match retry_after {
+        Some(retry_after) => format!(
+            "You're making requests too quickly. Please try again in {} seconds.",
+            retry_after.as_millis().div_ceil(1000).max(1)
+        ),
+        None => "You're making requests too quickly. Please try again later.".into(),
+    }
+}
+
 pub trait DetachAndPromptErr {
     fn detach_and_prompt_err(
         self,
@@ -535,8 +547,13 @@ where
             if let Err(err) = self.await {
                 log::error!("{err:?}");
                 if let Ok(prompt) = cx.update(|cx| {
-                    let detail = f(&err, cx)
-                        .unwrap_or_else(|| format!("{err:?}. Please try again.", err = err));
+                    let detail = f(&err, cx).unwrap_or_else(|| {
+                        if err.error_code() == ErrorCode::RateLimitExceeded {
+                            rate_limit_message(err.retry_after())
+                        } else {
+                            format!("{err:?}. Please try again.", err = err)
+                        }
+                    });
                     cx.prompt(PromptLevel::Critical, &msg, Some(&detail), &["Ok"])
                 }) {
                     prompt.await.ok();
@@ -38,7 +38,7 @@ use item::{
     ProjectItem,
 };
 use itertools::Itertools;
-use language::{LanguageRegistry, Rope};
+use language::{EncodingError, LanguageRegistry, Rope};
 use lazy_static::lazy_static;
 pub use modal_layer::*;
 use node_runtime::NodeRuntime;
@@ -69,6 +69,7 @@ use std::{
     collections::hash_map::DefaultHasher,
     env,
     hash::{Hash, Hasher},
+    mem,
     path::{Path, PathBuf},
     rc::Rc,
     sync::{atomic::AtomicUsize, Arc, Weak},
@@ -317,25 +318,46 @@ type WorkspaceItemBuilder = Box<dyn FnOnce(&mut ViewContext<Pane>) -> Box<dyn It
 
 impl Global for ProjectItemOpeners {}
 
+/// Openers for the files that the [ProjectItemOpeners] fail to open because they
+/// cannot be decoded as text.
+#[derive(Clone, Default, Deref, DerefMut)]
+struct ProjectItemFallbackOpeners(Vec<ProjectItemOpener>);
+
+impl Global for ProjectItemFallbackOpeners {}
+
 /// Registers a [ProjectItem] for the app. When opening a file, all the registered
 /// items will get a chance to open the file, starting from the project item that
 /// was added last.
 pub fn register_project_item<I: ProjectItem>(cx: &mut AppContext) {
     let builders = cx.default_global::<ProjectItemOpeners>();
-    builders.push(|project, project_path, cx| {
-        let project_item = <I::Item as project::Item>::try_open(&project, project_path, cx)?;
-        let project = project.clone();
-        Some(cx.spawn(|cx| async move {
-            let project_item = project_item.await?;
-            let project_entry_id: Option<ProjectEntryId> =
-                project_item.read_with(&cx, |item, cx| project::Item::entry_id(item, cx))?;
-            let build_workspace_item = Box::new(|cx: &mut ViewContext<Pane>| {
-                Box::new(cx.new_view(|cx| I::for_project_item(project, project_item, cx)))
-                    as Box<dyn ItemHandle>
-            }) as Box<_>;
-            Ok((project_entry_id, build_workspace_item))
-        }))
-    });
+    builders.push(project_item_opener::<I>);
+}
+
+/// Registers a [ProjectItem] that opens the files the other project items fail to
+/// open with an [EncodingError], such as binary files. The fallback items get a chance
+/// to open such a file in the same order as the regular ones.
+pub fn register_project_item_fallback<I: ProjectItem>(cx: &mut AppContext) {
+    let builders = cx.default_global::<ProjectItemFallbackOpeners>();
+    builders.push(project_item_opener::<I>);
+}
+
+fn project_item_opener<I: ProjectItem>(
+    project: &Model<Project>,
+    project_path: &ProjectPath,
+    cx: &mut WindowContext,
+) -> Option<Task<Result<(Option<ProjectEntryId>, WorkspaceItemBuilder)>>> {
+    let project_item = <I::Item as project::Item>::try_open(project, project_path, cx)?;
+    let project = project.clone();
+    Some(cx.spawn(|cx| async move {
+        let project_item = project_item.await?;
+        let project_entry_id: Option<ProjectEntryId> =
+            project_item.read_with(&cx, |item, cx| project::Item::entry_id(item, cx))?;
+        let build_workspace_item = Box::new(|cx: &mut ViewContext<Pane>| {
+            Box::new(cx.new_view(|cx| I::for_project_item(project, project_item, cx)))
+                as Box<dyn ItemHandle>
+        }) as Box<_>;
+        Ok((project_entry_id, build_workspace_item))
+    }))
 }
 
 type FollowableItemBuilder = fn(
@@ -536,6 +558,7 @@ pub enum Event {
         language: &'static str,
     },
     ZoomChanged,
+    LeaderPanelUpdated(proto::UpdatePanel),
 }
 
 pub enum OpenVisible {
@@ -576,6 +599,8 @@ pub struct Workspace {
     project: Model<Project>,
     follower_states: HashMap<View<Pane>, FollowerState>,
     last_leaders_by_pane: HashMap<WeakView<Pane>, PeerId>,
+    shared_panels: HashMap<mem::Discriminant<proto::update_panel::Variant>, proto::UpdatePanel>,
+    presenter: Option<PresenterState>,
     window_edited: bool,
     active_call: Option<(Model<ActiveCall>, Vec<Subscription>)>,
     leader_updates_tx: mpsc::UnboundedSender<(PeerId, proto::UpdateFollowers)>,
@@ -608,6 +633,13 @@ struct FollowerState {
     items_by_leader_view_id: HashMap<ViewId, Box<dyn FollowableItemHandle>>,
 }
 
+/// The participant presenting to the room. We follow them automatically
+/// until we opt out by unfollowing.
+struct PresenterState {
+    peer_id: PeerId,
+    following: bool,
+}
+
 impl Workspace {
     const DEFAULT_PADDING: f32 = 0.2;
     const MAX_PADDING: f32 = 0.4;
@@ -865,6 +897,8 @@ impl Workspace {
             project: project.clone(),
             follower_states: Default::default(),
             last_leaders_by_pane: Default::default(),
+            shared_panels: Default::default(),
+            presenter: None,
             dispatching_keystrokes: Default::default(),
             window_edited: false,
             active_call,
@@ -2258,7 +2292,26 @@ impl Workspace {
         else {
             return Task::ready(Err(anyhow!("cannot open file {:?}", path.path)));
         };
-        open_project_item
+        let fallback_builders = cx.default_global::<ProjectItemFallbackOpeners>().clone();
+        if fallback_builders.is_empty() {
+            return open_project_item;
+        }
+
+        cx.spawn(|mut cx| async move {
+            match open_project_item.await {
+                Err(error) if error.is::<EncodingError>() => {
+                    for open_fallback_item in fallback_builders.iter().rev() {
+                        let fallback_item =
+                            cx.update(|cx| open_fallback_item(&project, &path, cx))?;
+                        if let Some(fallback_item) = fallback_item {
+                            return fallback_item.await;
+                        }
+                    }
+                    Err(error)
+                }
+                result => result,
+            }
+        })
     }
 
     pub fn open_project_item<T>(
@@ -2791,7 +2844,12 @@ impl Workspace {
                 &mut cx,
             )
             .await?;
-            this.update(&mut cx, |this, cx| this.leader_updated(leader_id, cx))?;
+            this.update(&mut cx, |this, cx| {
+                for panel in response.panels {
+                    cx.emit(Event::LeaderPanelUpdated(panel));
+                }
+                this.leader_updated(leader_id, cx)
+            })?;
             Ok(())
         }))
     }
@@ -2844,6 +2902,18 @@ impl Workspace {
             return;
         };
 
+        if room.presenter_id() == Some(leader_id)
+            && self
+                .presenter
+                .as_ref()
+                .map_or(true, |presenter| presenter.peer_id != leader_id)
+        {
+            self.presenter = Some(PresenterState {
+                peer_id: leader_id,
+                following: true,
+            });
+        }
+
         let project = self.project.read(cx);
 
         let other_project_id = match remote_participant.location {
@@ -2891,6 +2961,12 @@ impl Workspace {
             .values()
             .all(|state| state.leader_id != leader_id)
         {
+            if let Some(presenter) = self.presenter.as_mut() {
+                if presenter.peer_id == leader_id {
+                    presenter.following = false;
+                }
+            }
+
             let project_id = self.project.read(cx).remote_id();
             let room_id = self.active_call()?.read(cx).room()?.read(cx).id();
             self.app_state
@@ -3041,6 +3117,11 @@ impl Workspace {
 
         let active_view = self.active_view_for_follower(follower_project_id, cx);
         let active_view_id = active_view.as_ref().and_then(|view| view.id.clone());
+        let panels = if project_id.is_some() && project_id == follower_project_id {
+            self.shared_panels.values().cloned().collect()
+        } else {
+            Vec::new()
+        };
 
         cx.notify();
 
@@ -3082,6 +3163,7 @@ impl Workspace {
                     })
                 })
                 .collect(),
+            panels,
         }
     }
 
@@ -3166,6 +3248,13 @@ impl Workspace {
                 })?;
                 Self::add_views_from_leader(this.clone(), leader_id, panes, vec![view], cx).await?;
             }
+            proto::update_followers::Variant::UpdatePanel(update_panel) => {
+                this.update(cx, |this, cx| {
+                    if this.is_being_followed(leader_id) {
+                        cx.emit(Event::LeaderPanelUpdated(update_panel));
+                    }
+                })?;
+            }
         }
         this.update(cx, |this, cx| this.leader_updated(leader_id, cx))?;
         Ok(())
@@ -3331,6 +3420,28 @@ impl Workspace {
         }
     }
 
+    /// Shares the state of a panel with anyone following us in this project.
+    /// The latest state of each panel is also sent to new followers.
+    pub fn update_followers_panel(
+        &mut self,
+        variant: proto::update_panel::Variant,
+        cx: &mut WindowContext,
+    ) {
+        let kind = mem::discriminant(&variant);
+        let update = proto::UpdatePanel {
+            variant: Some(variant),
+        };
+        if self.shared_panels.get(&kind) == Some(&update) {
+            return;
+        }
+        self.shared_panels.insert(kind, update.clone());
+        self.update_followers(
+            true,
+            proto::update_followers::Variant::UpdatePanel(update),
+            cx,
+        );
+    }
+
     fn update_followers(
         &self,
         project_only: bool,
@@ -3478,10 +3589,59 @@ impl Workspace {
             | call::room::Event::RemoteVideoTracksChanged { participant_id } => {
                 self.leader_updated(*participant_id, cx);
             }
+            call::room::Event::PresenterChanged { presenter_id } => {
+                self.presenter_changed(*presenter_id, cx);
+            }
             _ => {}
         }
     }
 
+    fn presenter_changed(&mut self, presenter_id: Option<PeerId>, cx: &mut ViewContext<Self>) {
+        if self.presenter.as_ref().map(|presenter| presenter.peer_id) == presenter_id {
+            return;
+        }
+
+        if let Some(previous) = self.presenter.take() {
+            if previous.following {
+                let panes = self
+                    .follower_states
+                    .iter()
+                    .filter(|(_, state)| state.leader_id == previous.peer_id)
+                    .map(|(pane, _)| pane.clone())
+                    .collect::<Vec<_>>();
+                for pane in panes {
+                    self.unfollow(&pane, cx);
+                }
+            }
+        }
+
+        let Some(presenter_id) = presenter_id else {
+            return;
+        };
+        if self.client().peer_id() == Some(presenter_id) {
+            return;
+        }
+
+        // Every window receives this event. Follow from the window showing the
+        // presenter's project, and from the active window, which joins the
+        // presenter's project if it isn't open yet.
+        let Some(room) = self.active_call().and_then(|call| call.read(cx).room()) else {
+            return;
+        };
+        let presenter_in_this_project = room
+            .read(cx)
+            .remote_participant_for_peer_id(presenter_id)
+            .map_or(false, |participant| match participant.location {
+                call::ParticipantLocation::SharedProject { project_id } => {
+                    self.project.read(cx).remote_id() == Some(project_id)
+                }
+                _ => false,
+            });
+        if presenter_in_this_project || cx.is_window_active() {
+            self.follow(presenter_id, cx);
+        }
+    }
+
     pub fn database_id(&self) -> WorkspaceId {
         self.database_id
     }
@@ -4629,6 +4789,7 @@ pub fn join_channel(
                                 "This channel is private, and you do not have access. Please ask someone to add you and try again.".into()
                             }
                             ErrorCode::Disconnected => "Please check your internet connection and try again.".into(),
+                            ErrorCode::RateLimitExceeded => notifications::rate_limit_message(err.retry_after()).into(),
                             _ => format!("{}\n\nPlease try again.", err).into(),
                         };
                         cx.prompt(
@@ -4813,8 +4974,13 @@ pub fn create_and_open_local_file(
         let fs = workspace.update(&mut cx, |workspace, _| workspace.app_state().fs.clone())?;
         if !fs.is_file(path).await {
             fs.create_file(path, Default::default()).await?;
-            fs.save(path, &default_content(), Default::default())
-                .await?;
+            fs.save(
+                path,
+                &default_content(),
+                Default::default(),
+                Default::default(),
+            )
+            .await?;
         }
 
         let mut items = workspace
@@ -6335,6 +6501,136 @@ mod tests {
             }
         }
 
+        const TEST_TEXT_KIND: &str = "TestTextItemView";
+        // View
+        struct TestTextItemView {
+            focus_handle: FocusHandle,
+        }
+        // Model
+        struct TestTextItem {}
+
+        impl project::Item for TestTextItem {
+            fn try_open(
+                _project: &Model<Project>,
+                path: &ProjectPath,
+                cx: &mut AppContext,
+            ) -> Option<Task<gpui::Result<Model<Self>>>> {
+                if path.path.extension().unwrap() == "bin" {
+                    Some(Task::ready(Err(EncodingError.into())))
+                } else {
+                    Some(cx.spawn(|mut cx| async move { cx.new_model(|_| TestTextItem {}) }))
+                }
+            }
+
+            fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
+                None
+            }
+
+            fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
+                None
+            }
+        }
+
+        impl Item for TestTextItemView {
+            type Event = ();
+
+            fn serialized_item_kind() -> Option<&'static str> {
+                Some(TEST_TEXT_KIND)
+            }
+        }
+        impl EventEmitter<()> for TestTextItemView {}
+        impl FocusableView for TestTextItemView {
+            fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+                self.focus_handle.clone()
+            }
+        }
+
+        impl Render for TestTextItemView {
+            fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+                Empty
+            }
+        }
+
+        impl ProjectItem for TestTextItemView {
+            type Item = TestTextItem;
+
+            fn for_project_item(
+                _project: Model<Project>,
+                _item: Model<Self::Item>,
+                cx: &mut ViewContext<Self>,
+            ) -> Self
+            where
+                Self: Sized,
+            {
+                Self {
+                    focus_handle: cx.focus_handle(),
+                }
+            }
+        }
+
+        const TEST_BINARY_KIND: &str = "TestBinaryItemView";
+        // View
+        struct TestBinaryItemView {
+            focus_handle: FocusHandle,
+        }
+        // Model
+        struct TestBinaryItem {}
+
+        impl project::Item for TestBinaryItem {
+            fn try_open(
+                _project: &Model<Project>,
+                _path: &ProjectPath,
+                cx: &mut AppContext,
+            ) -> Option<Task<gpui::Result<Model<Self>>>> {
+                Some(cx.spawn(|mut cx| async move { cx.new_model(|_| TestBinaryItem {}) }))
+            }
+
+            fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
+                None
+            }
+
+            fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
+                None
+            }
+        }
+
+        impl Item for TestBinaryItemView {
+            type Event = ();
+
+            fn serialized_item_kind() -> Option<&'static str> {
+                Some(TEST_BINARY_KIND)
+            }
+        }
+        impl EventEmitter<()> for TestBinaryItemView {}
+        impl FocusableView for TestBinaryItemView {
+            fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+                self.focus_handle.clone()
+            }
+        }
+
+        impl Render for TestBinaryItemView {
+            fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+                Empty
+            }
+        }
+
+        impl ProjectItem for TestBinaryItemView {
+            type Item = TestBinaryItem;
+
+            fn for_project_item(
+                _project: Model<Project>,
+                _item: Model<Self::Item>,
+                cx: &mut ViewContext<Self>,
+            ) -> Self
+            where
+                Self: Sized,
+            {
+                Self {
+                    focus_handle: cx.focus_handle(),
+                }
+            }
+        }
+
         #[gpui::test]
         async fn test_register_project_item(cx: &mut TestAppContext) {
             init_test(cx);
@@ -6441,6 +6737,52 @@ mod tests {
                 .await;
             assert!(handle.is_err());
         }
+
+        #[gpui::test]
+        async fn test_register_project_item_fallback(cx: &mut TestAppContext) {
+            init_test(cx);
+
+            cx.update(|cx| {
+                register_project_item_fallback::<TestBinaryItemView>(cx);
+                register_project_item::<TestTextItemView>(cx);
+            });
+
+            let fs = FakeFs::new(cx.executor());
+            fs.insert_tree(
+                "/root1",
+                json!({
+                    "one.bin": "BINARYDATAHERE",
+                    "two.txt": "editing text, sure why not?"
+                }),
+            )
+            .await;
+
+            let project = Project::test(fs, ["root1".as_ref()], cx).await;
+            let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+            let worktree_id = project.update(cx, |project, cx| {
+                project.worktrees().next().unwrap().read(cx).id()
+            });
+
+            // The file that cannot be decoded as text is opened by the fallback item.
+            let handle = workspace
+                .update(cx, |workspace, cx| {
+                    let project_path = (worktree_id, "one.bin");
+                    workspace.open_path(project_path, None, true, cx)
+                })
+                .await
+                .unwrap();
+            assert_eq!(handle.serialized_item_kind().unwrap(), TEST_BINARY_KIND);
+
+            let handle = workspace
+                .update(cx, |workspace, cx| {
+                    let project_path = (worktree_id, "two.txt");
+                    workspace.open_path(project_path, None, true, cx)
+                })
+                .await
+                .unwrap();
+            assert_eq!(handle.serialized_item_kind().unwrap(), TEST_TEXT_KIND);
+        }
     }
 
     pub fn init_test(cx: &mut TestAppContext) {
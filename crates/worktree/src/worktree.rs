@@ -31,8 +31,12 @@ use gpui::{
 use ignore::IgnoreStack;
 use itertools::Itertools;
 use language::{
-    proto::{deserialize_version, serialize_line_ending, serialize_version},
-    Buffer, Capability, DiagnosticEntry, File as _, LineEnding, PointUtf16, Rope, Unclipped,
+    proto::{
+        deserialize_encoding, deserialize_version, serialize_encoding, serialize_line_ending,
+        serialize_version,
+    },
+    Buffer, Capability, DiagnosticEntry, Encoding, EncodingError, File as _, LineEnding,
+    PointUtf16, Rope, Unclipped,
 };
 use lsp::{DiagnosticSeverity, LanguageServerId};
 use parking_lot::Mutex;
@@ -51,7 +55,7 @@ use std::{
     ffi::OsStr,
     fmt,
     future::Future,
-    mem,
+    io, mem,
     ops::{AddAssign, Deref, DerefMut, Sub},
     path::{Path, PathBuf},
     pin::Pin,
@@ -160,6 +164,8 @@ pub struct Snapshot {
 pub struct RepositoryEntry {
     pub(crate) work_directory: WorkDirectoryEntry,
     pub(crate) branch: Option<Arc<str>>,
+    /// Read by the background scanner along with the branch. Not replicated to guests.
+    pub(crate) head_sha: Option<Arc<str>>,
 }
 
 impl RepositoryEntry {
@@ -167,6 +173,11 @@ impl RepositoryEntry {
         self.branch.clone()
     }
 
+    /// Returns the SHA of the repository's HEAD, as of the last scan of the repository.
+    pub fn head_sha(&self) -> Option<Arc<str>> {
+        self.head_sha.clone()
+    }
+
     pub fn work_directory_id(&self) -> ProjectEntryId {
         *self.work_directory
     }
@@ -725,6 +736,64 @@ fn start_background_scan_tasks(
     vec![background_scanner, scan_state_updater]
 }
 
+/// A file loaded from disk, to be opened in a buffer.
+struct LoadedFile {
+    file: File,
+    /// The decoded text of the file, with its line endings normalized.
+    text: Rope,
+    line_ending: LineEnding,
+    encoding: Encoding,
+    /// Whether the file exceeds the `large_file_threshold` setting.
+    is_large: bool,
+    diff_base: Option<String>,
+}
+
+/// Reads and decodes a file chunk by chunk, so that a large file is never held
+/// in memory in full, besides the rope that is built out of it.
+fn read_large_file(mut reader: Box<dyn io::Read>) -> Result<(Rope, LineEnding, Encoding)> {
+    const CHUNK_SIZE: usize = 1024 * 1024;
+
+    let mut bytes = vec![0; CHUNK_SIZE];
+    let mut encoding = Encoding::default();
+    let mut decoder = None;
+    let mut line_ending = None;
+    let mut text = String::new();
+    let mut rope = Rope::new();
+    loop {
+        let len = reader.read(&mut bytes)?;
+        let is_last = len == 0;
+        if decoder.is_none() && Encoding::is_binary(&bytes[..len]) {
+            return Err(EncodingError.into());
+        }
+        let decoder = decoder.get_or_insert_with(|| {
+            encoding = Encoding::detect(&bytes[..len]);
+            encoding.chunk_decoder()
+        });
+        decoder.decode(&bytes[..len], is_last, &mut text);
+
+        // A carriage return may be followed by a line feed in the next chunk.
+        let ends_with_carriage_return = !is_last && text.ends_with('\r');
+        if ends_with_carriage_return {
+            text.pop();
+        }
+        if line_ending.is_none() && !text.is_empty() {
+            line_ending = Some(LineEnding::detect(&text));
+        }
+        LineEnding::normalize(&mut text);
+        rope.push(&text);
+        text.clear();
+        if ends_with_carriage_return {
+            text.push('\r');
+        }
+
+        if is_last {
+            break;
+        }
+    }
+
+    Ok((rope, line_ending.unwrap_or_default(), encoding))
+}
+
 fn path_matchers(values: Option<&[String]>, context: &'static str) -> Vec<PathMatcher> {
     values
         .unwrap_or(&[])
@@ -757,20 +826,20 @@ impl LocalWorktree {
         let reservation = cx.reserve_model();
         let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
         cx.spawn(move |this, mut cx| async move {
-            let (file, contents, diff_base) = this
+            let loaded = this
                 .update(&mut cx, |t, cx| t.as_local().unwrap().load(&path, cx))?
                 .await?;
-            let text_buffer = cx
-                .background_executor()
-                .spawn(async move { text::Buffer::new(0, buffer_id, contents) })
-                .await;
+            let text_buffer =
+                text::Buffer::new_normalized(0, buffer_id, loaded.line_ending, loaded.text);
             cx.insert_model(reservation, |_| {
                 Buffer::build(
                     text_buffer,
-                    diff_base,
-                    Some(Arc::new(file)),
+                    loaded.diff_base,
+                    Some(Arc::new(loaded.file)),
                     Capability::ReadWrite,
                 )
+                .with_encoding(loaded.encoding)
+                .with_large_file_mode(loaded.is_large)
             })
         })
     }
@@ -1061,22 +1130,58 @@ impl LocalWorktree {
         }
     }
 
-    fn load(
-        &self,
-        path: &Path,
-        cx: &mut ModelContext<Worktree>,
-    ) -> Task<Result<(File, String, Option<String>)>> {
+    fn load(&self, path: &Path, cx: &mut ModelContext<Worktree>) -> Task<Result<LoadedFile>> {
         let path = Arc::from(path);
         let abs_path = self.absolutize(&path);
         let fs = self.fs.clone();
         let entry = self.refresh_entry(path.clone(), None, cx);
+        let large_file_threshold = WorktreeSettings::get(
+            Some(SettingsLocation {
+                worktree_id: cx.handle().entity_id().as_u64() as usize,
+                path: &path,
+            }),
+            cx,
+        )
+        .large_file_threshold;
 
         cx.spawn(|this, mut cx| async move {
             let abs_path = abs_path?;
-            let text = fs.load(&abs_path).await?;
+            let is_large = match large_file_threshold {
+                Some(threshold) => fs
+                    .metadata(&abs_path)
+                    .await?
+                    .map_or(false, |metadata| metadata.len > threshold),
+                None => false,
+            };
+            let (text, line_ending, encoding) = if is_large {
+                let fs = fs.clone();
+                let abs_path = abs_path.clone();
+                cx.background_executor()
+                    .spawn(async move {
+                        let reader = fs.open_sync(&abs_path).await?;
+                        read_large_file(reader)
+                    })
+                    .await?
+            } else {
+                let bytes = fs.load_bytes(&abs_path).await?;
+                if Encoding::is_binary(&bytes) {
+                    return Err(EncodingError.into());
+                }
+                cx.background_executor()
+                    .spawn(async move {
+                        let encoding = Encoding::detect(&bytes);
+                        let mut text = encoding.decode(&bytes).into_owned();
+                        let line_ending = LineEnding::detect(&text);
+                        LineEnding::normalize(&mut text);
+                        (Rope::from(text.as_str()), line_ending, encoding)
+                    })
+                    .await
+            };
             let mut index_task = None;
             let snapshot = this.update(&mut cx, |this, _| this.as_local().unwrap().snapshot())?;
-            if let Some(repo) = snapshot.repository_for_path(&path) {
+            // Large files are not diffed, so their index text is not needed.
+            let repo = snapshot.repository_for_path(&path).filter(|_| !is_large);
+            if let Some(repo) = repo {
                 if let Some(repo_path) = repo.work_directory.relativize(&snapshot, &path).log_err()
                 {
                     if let Some(git_repo) = snapshot.git_repositories.get(&*repo.work_directory) {
@@ -1114,8 +1219,8 @@ impl LocalWorktree {
                 .upgrade()
                 .ok_or_else(|| anyhow!("worktree was dropped"))?;
             match entry.await? {
-                Some(entry) => Ok((
-                    File {
+                Some(entry) => Ok(LoadedFile {
+                    file: File {
                         entry_id: Some(entry.id),
                         worktree,
                         path: entry.path,
@@ -1125,8 +1230,11 @@ impl LocalWorktree {
                         is_private: entry.is_private,
                     },
                     text,
+                    line_ending,
+                    encoding,
+                    is_large,
                     diff_base,
-                )),
+                }),
                 None => {
                     let metadata = fs
                         .metadata(&abs_path)
@@ -1138,8 +1246,8 @@ impl LocalWorktree {
                             format!("Excluded file {abs_path:?} got removed during loading")
                         })?;
                     let is_private = snapshot.is_path_private(path.as_ref());
-                    Ok((
-                        File {
+                    Ok(LoadedFile {
+                        file: File {
                             entry_id: None,
                             worktree,
                             path,
@@ -1149,8 +1257,11 @@ impl LocalWorktree {
                             is_private,
                         },
                         text,
+                        line_ending,
+                        encoding,
+                        is_large,
                         diff_base,
-                    ))
+                    })
                 }
             }
         })
@@ -1175,7 +1286,8 @@ impl LocalWorktree {
 
         let text = buffer.as_rope().clone();
         let version = buffer.version();
-        let save = self.write_file(path.as_ref(), text, buffer.line_ending(), cx);
+        let encoding = buffer.encoding();
+        let save = self.write_file(path.as_ref(), text, buffer.line_ending(), encoding, cx);
         let fs = Arc::clone(&self.fs);
         let abs_path = self.absolutize(&path);
         let is_private = self.snapshot.is_path_private(&path);
@@ -1236,6 +1348,7 @@ impl LocalWorktree {
                     buffer_id,
                     version: serialize_version(&version),
                     mtime: mtime.map(|time| time.into()),
+                    encoding: serialize_encoding(encoding),
                 })?;
             }
 
@@ -1274,8 +1387,13 @@ impl LocalWorktree {
             if is_dir {
                 fs.create_dir(&abs_path?).await
             } else {
-                fs.save(&abs_path?, &Default::default(), Default::default())
-                    .await
+                fs.save(
+                    &abs_path?,
+                    &Default::default(),
+                    Default::default(),
+                    Default::default(),
+                )
+                .await
             }
         });
 
@@ -1314,6 +1432,7 @@ impl LocalWorktree {
         path: impl Into<Arc<Path>>,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &mut ModelContext<Worktree>,
     ) -> Task<Result<Option<Entry>>> {
         let path: Arc<Path> = path.into();
@@ -1321,7 +1440,7 @@ impl LocalWorktree {
         let fs = self.fs.clone();
         let write = cx
             .background_executor()
-            .spawn(async move { fs.save(&abs_path?, &text, line_ending).await });
+            .spawn(async move { fs.save(&abs_path?, &text, line_ending, encoding).await });
 
         cx.spawn(|this, mut cx| async move {
             write.await?;
@@ -1660,8 +1779,10 @@ impl RemoteWorktree {
                 .await?;
             let version = deserialize_version(&response.version);
             let mtime = response.mtime.map(|mtime| mtime.into());
+            let encoding = deserialize_encoding(&response.encoding);
 
             buffer_handle.update(&mut cx, |buffer, cx| {
+                buffer.set_encoding(encoding, cx);
                 buffer.did_save(version.clone(), mtime, cx);
             })?;
 
@@ -1903,6 +2024,7 @@ impl Snapshot {
                         RepositoryEntry {
                             work_directory: work_directory_entry,
                             branch: repository.branch.map(Into::into),
+                            head_sha: None,
                         },
                     )
                 }
@@ -2698,6 +2820,7 @@ impl BackgroundScannerState {
                     log::info!("reload git repository {dot_git_dir:?}");
                     let repository = repository.repo_ptr.lock();
                     let branch = repository.branch_name();
+                    let head_sha = repository.head_sha();
                     repository.reload_index();
 
                     self.snapshot
@@ -2706,7 +2829,10 @@ impl BackgroundScannerState {
                     self.snapshot
                         .snapshot
                         .repository_entries
-                        .update(&work_dir, |entry| entry.branch = branch.map(Into::into));
+                        .update(&work_dir, |entry| {
+                            entry.branch = branch.map(Into::into);
+                            entry.head_sha = head_sha.map(Into::into);
+                        });
 
                     self.update_git_statuses(&work_dir, &*repository);
                 }
@@ -2794,6 +2920,7 @@ impl BackgroundScannerState {
             RepositoryEntry {
                 work_directory: work_dir_id.into(),
                 branch: repo_lock.branch_name().map(Into::into),
+                head_sha: repo_lock.head_sha().map(Into::into),
             },
         );
 
@@ -3043,12 +3170,14 @@ impl language::LocalFile for File {
         }
     }
 
-    fn load(&self, cx: &AppContext) -> Task<Result<String>> {
+    fn load(&self, encoding: Encoding, cx: &AppContext) -> Task<Result<String>> {
         let worktree = self.worktree.read(cx).as_local().unwrap();
         let abs_path = worktree.absolutize(&self.path);
         let fs = worktree.fs.clone();
-        cx.background_executor()
-            .spawn(async move { fs.load(&abs_path?).await })
+        cx.background_executor().spawn(async move {
+            let bytes = fs.load_bytes(&abs_path?).await?;
+            Ok(encoding.decode(&bytes).into_owned())
+        })
     }
 
     fn buffer_reloaded(
@@ -3056,6 +3185,7 @@ impl language::LocalFile for File {
         buffer_id: BufferId,
         version: &clock::Global,
         line_ending: LineEnding,
+        encoding: Encoding,
         mtime: Option<SystemTime>,
         cx: &mut AppContext,
     ) {
@@ -3069,6 +3199,7 @@ impl language::LocalFile for File {
                     version: serialize_version(version),
                     mtime: mtime.map(|time| time.into()),
                     line_ending: serialize_line_ending(line_ending) as i32,
+                    encoding: serialize_encoding(encoding),
                 })
                 .log_err();
         }
@@ -23,6 +23,11 @@ pub struct WorktreeSettings {
     /// Treat the files matching these globs as `.env` files.
     /// Default: [ "**/.env*" ]
     pub private_files: Option<Vec<String>>,
+
+    /// Open the files larger than this size, in bytes, in large file mode:
+    /// without syntax highlighting, language servers, inlay hints and git diff.
+    /// Default: 20000000
+    pub large_file_threshold: Option<u64>,
 }
 
 impl Settings for WorktreeSettings {
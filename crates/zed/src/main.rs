@@ -269,6 +269,7 @@ fn init_ui(args: Args) {
         language::init(cx);
         editor::init(cx);
         image_viewer::init(cx);
+        hex_editor::init(cx);
         diagnostics::init(cx);
         copilot::init(
             copilot_language_server_id,
@@ -358,6 +359,7 @@ fn init_ui(args: Args) {
         file_finder::init(cx);
         tab_switcher::init(cx);
         outline::init(cx);
+        bookmarks::init(cx);
         project_symbols::init(cx);
         project_panel::init(Assets, cx);
         tasks_ui::init(cx);
@@ -368,6 +370,7 @@ fn init_ui(args: Args) {
 
         journal::init(app_state.clone(), cx);
         language_selector::init(cx);
+        encoding_selector::init(cx);
         theme_selector::init(cx);
         language_tools::init(cx);
         call::init(app_state.client.clone(), app_state.user_store.clone(), cx);
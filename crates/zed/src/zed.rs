@@ -133,6 +133,8 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             activity_indicator::ActivityIndicator::new(workspace, app_state.languages.clone(), cx);
         let active_buffer_language =
             cx.new_view(|_| language_selector::ActiveBufferLanguage::new(workspace));
+        let active_buffer_encoding =
+            cx.new_view(|_| encoding_selector::ActiveBufferEncoding::new(workspace));
         let vim_mode_indicator = cx.new_view(|cx| vim::ModeIndicator::new(cx));
         let cursor_position =
             cx.new_view(|_| go_to_line::cursor_position::CursorPosition::new(workspace));
@@ -140,6 +142,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             status_bar.add_left_item(diagnostic_summary, cx);
             status_bar.add_left_item(activity_indicator, cx);
             status_bar.add_right_item(copilot, cx);
+            status_bar.add_right_item(active_buffer_encoding, cx);
             status_bar.add_right_item(active_buffer_language, cx);
             status_bar.add_right_item(vim_mode_indicator, cx);
             status_bar.add_right_item(cursor_position, cx);
@@ -195,6 +198,8 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 workspace_handle.clone(),
                 cx.clone(),
             );
+            let review_panel =
+                collab_ui::review_panel::ReviewPanel::load(workspace_handle.clone(), cx.clone());
 
             let (
                 project_panel,
@@ -203,6 +208,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 channels_panel,
                 chat_panel,
                 notification_panel,
+                review_panel,
             ) = futures::try_join!(
                 project_panel,
                 terminal_panel,
@@ -210,6 +216,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 channels_panel,
                 chat_panel,
                 notification_panel,
+                review_panel,
             )?;
 
             workspace_handle.update(&mut cx, |workspace, cx| {
@@ -221,6 +228,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 workspace.add_panel(channels_panel, cx);
                 workspace.add_panel(chat_panel, cx);
                 workspace.add_panel(notification_panel, cx);
+                workspace.add_panel(review_panel, cx);
                 cx.focus_self();
             })
         })
@@ -476,6 +484,8 @@ fn initialize_pane(workspace: &mut Workspace, pane: &View<Pane>, cx: &mut ViewCo
             let syntax_tree_item =
                 cx.new_view(|_| language_tools::SyntaxTreeToolbarItemView::new());
             toolbar.add_item(syntax_tree_item, cx);
+            let large_file_banner = cx.new_view(|_| editor::LargeFileBanner::new());
+            toolbar.add_item(large_file_banner, cx);
         })
     });
 }
@@ -2804,6 +2814,7 @@ mod tests {
                 "#
                 .into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -2823,6 +2834,7 @@ mod tests {
                 "#
                 .into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -2875,6 +2887,7 @@ mod tests {
                 "#
                 .into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -2900,6 +2913,7 @@ mod tests {
                 "#
                 .into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -2944,6 +2958,7 @@ mod tests {
                 "#
                 .into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -2962,6 +2977,7 @@ mod tests {
                 "#
                 .into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -3009,6 +3025,7 @@ mod tests {
                 "#
                 .into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();
@@ -3034,6 +3051,7 @@ mod tests {
                 "#
                 .into(),
                 Default::default(),
+                Default::default(),
             )
             .await
             .unwrap();